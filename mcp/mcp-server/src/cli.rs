@@ -1,25 +1,35 @@
+mod diff;
+mod eval;
+mod llm_policy;
+mod safe_write;
+mod serve;
+
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use sha2::{Sha256, Digest};
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
 /// CLI for testing MCP server with LLM integration
 #[derive(Parser, Debug)]
 #[command(name = "mcp-cli")]
 #[command(about = "Domain Model pipeline: transcript → normalize → validate → emit", long_about = None)]
+#[command(subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Enable dry-run mode for LLM (simulates LLM response)
-    #[arg(long)]
+    #[arg(long, global = true)]
     dry_run_llm: bool,
 
     /// Path to input transcript file (.json JSONL format)
-    #[arg(long, value_name = "FILE")]
-    input: PathBuf,
+    #[arg(long, value_name = "FILE", required = true)]
+    input: Option<PathBuf>,
     
     /// Path to output markdown file
     #[arg(long, value_name = "FILE")]
@@ -34,14 +44,55 @@ struct Args {
     validate_only: bool,
     
     /// Enable detailed tracing (logs prompts as hashes, never raw PII)
-    #[arg(long)]
+    #[arg(long, global = true)]
     trace: bool,
     
     /// Number of retry attempts for invalid JSON responses (default: 2)
-    #[arg(long, default_value = "2")]
+    #[arg(long, default_value = "2", global = true)]
     retry: u32,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Score the pipeline on a labeled corpus (precision/recall per element type)
+    Eval {
+        /// Corpus directory: <case>.jsonl transcripts with <case>.expected.json models
+        #[arg(long, value_name = "DIR")]
+        corpus: PathBuf,
+
+        /// Write the JSON report to this file
+        #[arg(long, value_name = "FILE")]
+        report: Option<PathBuf>,
+
+        /// Previous report to compare against; regressions make the command fail
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+
+        /// F1 drop tolerated before a case counts as a regression
+        #[arg(long, default_value = "0.05")]
+        tolerance: f64,
+    },
+
+    /// Watch project directories and regenerate their documentation when transcripts change
+    Serve {
+        /// Project directory holding `*.jsonl` transcripts (repeatable); docs go to `<DIR>/docs/`
+        #[arg(long = "project", value_name = "DIR", required = true)]
+        projects: Vec<PathBuf>,
+
+        /// Also regenerate every project nightly, at this local time
+        #[arg(long, value_name = "HH:MM", num_args = 0..=1, default_missing_value = "02:00")]
+        schedule: Option<String>,
+
+        /// Seconds between two checks of the transcripts
+        #[arg(long, default_value = "30")]
+        poll_secs: u64,
+
+        /// Check the projects once and exit (e.g. from cron)
+        #[arg(long)]
+        once: bool,
+    },
+}
+
 /// Hash sensitive data for logging (privacy-preserving)
 fn hash_sensitive(data: &str) -> String {
     let mut hasher = Sha256::new();
@@ -190,6 +241,7 @@ Repaired JSON:"#,
         "ollama" => {
             let base_url = env::var("OLLAMA_BASE_URL")
                 .unwrap_or_else(|_| "http://localhost:11434".to_string());
+            llm_policy::enforce(provider, &base_url)?;
             let model = env::var("OLLAMA_MODEL")
                 .unwrap_or_else(|_| "llama2".to_string());
             
@@ -229,6 +281,7 @@ Repaired JSON:"#,
                 .context("LLM_API_KEY not set")?;
             let endpoint = env::var("LLM_ENDPOINT")
                 .context("LLM_ENDPOINT not set")?;
+            llm_policy::enforce(provider, &endpoint)?;
             
             if enable_trace {
                 info!(target: "domain::llm", provider = provider, "Calling external LLM for repair");
@@ -371,6 +424,7 @@ RÈGLES STRICTES:
         "ollama" => {
             let base_url = env::var("OLLAMA_BASE_URL")
                 .unwrap_or_else(|_| "http://localhost:11434".to_string());
+            llm_policy::enforce(&provider, &base_url)?;
             let model = env::var("OLLAMA_MODEL")
                 .unwrap_or_else(|_| "llama2".to_string());
             
@@ -476,6 +530,7 @@ RÈGLES STRICTES:
                 .context("LLM_API_KEY not set for external provider")?;
             let endpoint = env::var("LLM_ENDPOINT")
                 .context("LLM_ENDPOINT not set")?;
+            llm_policy::enforce(&provider, &endpoint)?;
             
             if enable_trace {
                 let api_key_hash = hash_sensitive(&api_key);
@@ -670,6 +725,222 @@ fn validate_domain_model(model: &Value) -> Result<(Vec<String>, Vec<String>)> {
     Ok((errors, warnings))
 }
 
+/// Read a JSONL transcript and join its lines; returns (transcript, line count)
+fn read_transcript(path: &Path) -> Result<(String, usize)> {
+    let content = fs::read_to_string(path)
+        .context(format!("Failed to read input file: {:?}", path))?;
+    
+    let mut transcript_parts = Vec::new();
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let transcript_line: TranscriptLine = serde_json::from_str(line)
+            .context(format!("Failed to parse JSONL line: {}", line))?;
+        transcript_parts.push(transcript_line.text);
+    }
+    
+    Ok((transcript_parts.join("\n"), transcript_parts.len()))
+}
+
+/// Run every labeled case of the corpus through generation and score it
+async fn run_eval(
+    args: &Args,
+    corpus: &Path,
+    report_path: Option<&Path>,
+    baseline: Option<&Path>,
+    tolerance: f64,
+) -> Result<()> {
+    let cases = eval::discover_cases(corpus)?;
+    if cases.is_empty() {
+        return Err(anyhow::anyhow!("No labeled case found in {:?}", corpus));
+    }
+    
+    println!("\n============================================================");
+    println!("  Normalization Evaluation ({} cases)", cases.len());
+    println!("  Mode: {}", if args.dry_run_llm { "DRY-RUN" } else { "LIVE LLM" });
+    println!("============================================================\n");
+    
+    let mut reports = Vec::new();
+    for case in &cases {
+        let expected: Value = serde_json::from_str(&fs::read_to_string(&case.expected)?)
+            .context(format!("Invalid expected model: {:?}", case.expected))?;
+        
+        let generated = match read_transcript(&case.transcript) {
+            Ok((transcript, _)) => call_llm_api(&transcript, args.dry_run_llm, args.trace, args.retry)
+                .await
+                .and_then(|model| Ok(serde_json::to_value(model)?)),
+            Err(e) => Err(e),
+        };
+        
+        // A failed generation scores as an empty model
+        let (scores, error) = match generated {
+            Ok(model) => (eval::score_model(&expected, &model), None),
+            Err(e) => (eval::score_model(&expected, &json!({})), Some(e.to_string())),
+        };
+        
+        println!(
+            "  {:<24} entities F1 {:.2}  relations F1 {:.2}  invariants F1 {:.2}{}",
+            case.name,
+            scores.entities.f1,
+            scores.relations.f1,
+            scores.invariants.f1,
+            if error.is_some() { "  ❌" } else { "" }
+        );
+        reports.push(eval::CaseReport { name: case.name.clone(), error, scores });
+    }
+    
+    let mut report = eval::EvalReport::new(reports);
+    println!(
+        "\n  {:<24} entities F1 {:.2}  relations F1 {:.2}  invariants F1 {:.2}",
+        "OVERALL",
+        report.overall.entities.f1,
+        report.overall.relations.f1,
+        report.overall.invariants.f1
+    );
+    
+    if let Some(baseline_path) = baseline {
+        let baseline: eval::EvalReport = serde_json::from_str(&fs::read_to_string(baseline_path)?)
+            .context(format!("Invalid baseline report: {:?}", baseline_path))?;
+        report.compare(&baseline, tolerance);
+    }
+    
+    if let Some(path) = report_path {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        safe_write::write(path, serde_json::to_string_pretty(&report)?)?;
+        println!("\n  ✔ Report written to: {}", path.display());
+    }
+    
+    if !report.regressions.is_empty() {
+        eprintln!("\n❌ {} regression(s) against baseline:", report.regressions.len());
+        for regression in &report.regressions {
+            eprintln!(
+                "   - {} / {}: F1 {:.2} → {:.2}",
+                regression.case, regression.category, regression.baseline_f1, regression.f1
+            );
+        }
+        return Err(anyhow::anyhow!("{} regression(s) detected", report.regressions.len()));
+    }
+    
+    println!();
+    Ok(())
+}
+
+/// Markdown documentation of a model (simplified - the server's emit_markdown is richer)
+fn render_markdown(model: &DomainModel) -> Result<String> {
+    Ok(format!("# Domain Model\n\n## Entities\n\n{}\n", 
+        serde_json::to_string_pretty(&model.entities)?))
+}
+
+/// Mermaid ER diagram of a model (simplified)
+fn render_mermaid(model: &DomainModel) -> String {
+    let mut mermaid_parts = vec!["erDiagram".to_string()];
+    for entity in &model.entities {
+        if let Some(name) = entity.get("name").and_then(|n| n.as_str()) {
+            mermaid_parts.push(format!("    {} {{", name));
+            mermaid_parts.push("    }".to_string());
+        }
+    }
+    mermaid_parts.join("\n")
+}
+
+/// Regenerate a project's model and docs; a model change is added to its changelog
+async fn regenerate_project(args: &Args, project: &Path, reason: &str) -> Result<()> {
+    let mut transcript_parts = Vec::new();
+    for path in serve::transcripts(project)? {
+        transcript_parts.push(read_transcript(&path)?.0);
+    }
+    if transcript_parts.is_empty() {
+        return Err(anyhow::anyhow!("No transcript (*.jsonl) in {:?}", project));
+    }
+    
+    let domain_model = call_llm_api(&transcript_parts.join("\n"), args.dry_run_llm, args.trace, args.retry).await?;
+    let model_json = serde_json::to_value(&domain_model)?;
+    let (errors, _) = validate_domain_model(&model_json)?;
+    if !errors.is_empty() {
+        return Err(anyhow::anyhow!("Validation failed with {} errors: {}", errors.len(), errors.join("; ")));
+    }
+    
+    let docs = project.join(serve::DOCS_DIR);
+    fs::create_dir_all(&docs)?;
+    let model_path = docs.join(serve::MODEL_FILE);
+    let previous: Option<Value> = fs::read_to_string(&model_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let model_diff = diff::diff_models(previous.as_ref().unwrap_or(&json!({})), &model_json);
+    
+    safe_write::write(&model_path, serde_json::to_string_pretty(&model_json)?)?;
+    safe_write::write(docs.join("model.md"), render_markdown(&domain_model)?)?;
+    safe_write::write(docs.join("model.mmd"), render_mermaid(&domain_model))?;
+    
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    if let Some(entry) = serve::changelog_entry(&timestamp, reason, previous.as_ref(), &model_json, &model_diff) {
+        let changelog_path = docs.join(serve::CHANGELOG_FILE);
+        let changelog = fs::read_to_string(&changelog_path).unwrap_or_default();
+        safe_write::write(&changelog_path, serve::prepend_entry(&changelog, &entry))?;
+    }
+    Ok(())
+}
+
+/// Watch the projects: regenerate on transcript changes and, with a schedule, every night
+async fn run_serve(
+    args: &Args,
+    projects: &[PathBuf],
+    schedule: Option<&str>,
+    poll_secs: u64,
+    once: bool,
+) -> Result<()> {
+    let nightly_at = schedule.map(serve::parse_time).transpose()?;
+    let mut next_nightly = nightly_at.map(|at| serve::next_run(chrono::Local::now().naive_local(), at));
+    
+    println!("\n============================================================");
+    println!("  Domain Model Service ({} projects)", projects.len());
+    println!("  Mode: {}", if args.dry_run_llm { "DRY-RUN" } else { "LIVE LLM" });
+    if let Some(next) = next_nightly {
+        println!("  Nightly regeneration, next run: {}", next.format("%Y-%m-%d %H:%M"));
+    }
+    println!("============================================================\n");
+    
+    loop {
+        let nightly_due = next_nightly.is_some_and(|next| chrono::Local::now().naive_local() >= next);
+        
+        for project in projects {
+            let fingerprint = match serve::fingerprint(project) {
+                Ok(fingerprint) => fingerprint,
+                Err(e) => {
+                    warn!(target: "domain::serve", project = %project.display(), "Cannot read project: {}", e);
+                    continue;
+                }
+            };
+            let reason = if serve::last_fingerprint(project).as_deref() != Some(fingerprint.as_str()) {
+                "transcripts changed"
+            } else if nightly_due {
+                "nightly"
+            } else {
+                continue;
+            };
+            
+            println!("  ⚙️  {}: regenerating ({})", project.display(), reason);
+            match regenerate_project(args, project, reason).await {
+                Ok(()) => println!("      ✔ Docs written to: {}", project.join(serve::DOCS_DIR).display()),
+                Err(e) => eprintln!("      ❌ {}", e),
+            }
+            // Recorded even on failure: the next attempt waits for a change or the next night
+            serve::record_fingerprint(project, &fingerprint)?;
+        }
+        
+        if nightly_due {
+            next_nightly = nightly_at.map(|at| serve::next_run(chrono::Local::now().naive_local(), at));
+        }
+        if once {
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(poll_secs)).await;
+    }
+}
+
 /// Run the complete pipeline
 async fn run_pipeline(args: &Args) -> Result<()> {
     use std::time::Instant;
@@ -692,22 +963,10 @@ async fn run_pipeline(args: &Args) -> Result<()> {
     println!("[1/5] 📝 Reading transcript...");
     let start = Instant::now();
     
-    let content = fs::read_to_string(&args.input)
-        .context(format!("Failed to read input file: {:?}", args.input))?;
-    
-    let mut transcript_parts = Vec::new();
-    for line in content.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        let transcript_line: TranscriptLine = serde_json::from_str(line)
-            .context(format!("Failed to parse JSONL line: {}", line))?;
-        transcript_parts.push(transcript_line.text);
-    }
-    
-    let full_transcript = transcript_parts.join("\n");
+    let input = args.input.as_ref().context("--input is required")?;
+    let (full_transcript, line_count) = read_transcript(input)?;
     steps[0].succeed(start.elapsed().as_millis() as u64);
-    println!("      ✔ Loaded {} lines", transcript_parts.len());
+    println!("      ✔ Loaded {} lines", line_count);
     
     // Step 2: Normalize terms (generate domain model)
     steps[1].start();
@@ -790,14 +1049,12 @@ async fn run_pipeline(args: &Args) -> Result<()> {
         println!("\n[4/5] 📝 Generating markdown...");
         let start = Instant::now();
         
-        // Generate markdown content (simplified - in real implementation would call emit_markdown function)
-        let markdown = format!("# Domain Model\n\n## Entities\n\n{}\n", 
-            serde_json::to_string_pretty(&domain_model.entities)?);
+        let markdown = render_markdown(&domain_model)?;
         
         if let Some(parent) = md_path.parent() {
             fs::create_dir_all(parent)?;
         }
-        fs::write(md_path, markdown)?;
+        safe_write::write(md_path, markdown)?;
         
         steps[3].succeed(start.elapsed().as_millis() as u64);
         println!("      ✔ Written to: {}", md_path.display());
@@ -812,22 +1069,12 @@ async fn run_pipeline(args: &Args) -> Result<()> {
         println!("\n[5/5] 🔷 Generating Mermaid diagram...");
         let start = Instant::now();
         
-        // Generate mermaid content (simplified)
-        let mut mermaid_parts = vec!["erDiagram".to_string()];
-        for entity in &domain_model.entities {
-            if let Ok(v) = serde_json::to_value(entity) {
-                if let Some(name) = v.get("name").and_then(|n| n.as_str()) {
-                    mermaid_parts.push(format!("    {} {{", name));
-                    mermaid_parts.push("    }".to_string());
-                }
-            }
-        }
-        let mermaid = mermaid_parts.join("\n");
+        let mermaid = render_mermaid(&domain_model);
         
         if let Some(parent) = mmd_path.parent() {
             fs::create_dir_all(parent)?;
         }
-        fs::write(mmd_path, mermaid)?;
+        safe_write::write(mmd_path, mermaid)?;
         
         steps[4].succeed(start.elapsed().as_millis() as u64);
         println!("      ✔ Written to: {}", mmd_path.display());
@@ -868,7 +1115,17 @@ async fn main() -> Result<()> {
         tracing_subscriber::fmt::init();
     }
     
-    if let Err(e) = run_pipeline(&args).await {
+    let result = match &args.command {
+        Some(Command::Eval { corpus, report, baseline, tolerance }) => {
+            run_eval(&args, corpus, report.as_deref(), baseline.as_deref(), *tolerance).await
+        }
+        Some(Command::Serve { projects, schedule, poll_secs, once }) => {
+            run_serve(&args, projects, schedule.as_deref(), *poll_secs, *once).await
+        }
+        None => run_pipeline(&args).await,
+    };
+    
+    if let Err(e) = result {
         eprintln!("\n❌ Error: {}", e);
         std::process::exit(1);
     }
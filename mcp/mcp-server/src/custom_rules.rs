@@ -0,0 +1,233 @@
+//! User-defined validation rules.
+//!
+//! Organizations encode their house modeling standards as Rhai scripts
+//! (`*.rhai`) in a rules directory. Each script sees the model as the `model`
+//! variable (plain maps and arrays, same shape as the JSON) and returns an
+//! array of findings: a string (an error) or a map
+//! `#{ severity: "error" | "warning", message: "...", target: "Order.total" }`.
+//!
+//! Scripts run in a sandboxed engine: no file, network or process access, and
+//! bounded operations, call depth and data sizes, so a broken rule cannot hang
+//! or take down the server. A failing script is reported, never fatal.
+
+use anyhow::{Context, Result};
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// Environment variable naming the default rules directory
+pub const RULES_DIR_ENV: &str = "DOMAIN_RULES_DIR";
+
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_COLLECTION_SIZE: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    /// Rule file name, without extension
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+pub struct CustomRule {
+    pub name: String,
+    ast: AST,
+}
+
+/// Findings of all rules, plus the rules that could not be loaded or run
+#[derive(Debug, Default)]
+pub struct RulesReport {
+    pub findings: Vec<Finding>,
+    pub failures: Vec<String>,
+}
+
+pub fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_max_array_size(MAX_COLLECTION_SIZE);
+    engine.set_max_map_size(MAX_COLLECTION_SIZE);
+    // No module resolution: a rule cannot import files
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine
+}
+
+/// Compile every `*.rhai` file of `dir`, sorted by name. Scripts that do not
+/// compile are listed in the second vector.
+pub fn load_rules(engine: &Engine, dir: &Path) -> Result<(Vec<CustomRule>, Vec<String>)> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read rules directory {}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("rhai"))
+        .collect();
+    paths.sort();
+
+    let mut rules = Vec::new();
+    let mut failures = Vec::new();
+    for path in paths {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let compiled = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| engine.compile(source).map_err(|e| e.to_string()));
+        match compiled {
+            Ok(ast) => rules.push(CustomRule { name, ast }),
+            Err(e) => failures.push(format!("Rule '{}' does not compile: {}", name, e)),
+        }
+    }
+    Ok((rules, failures))
+}
+
+fn to_finding(rule: &str, value: Dynamic) -> Result<Finding, String> {
+    if value.is_string() {
+        return Ok(Finding { rule: rule.to_string(), severity: Severity::Error, message: value.to_string(), target: None });
+    }
+    let map = value.try_cast::<Map>().ok_or("a finding must be a string or a map")?;
+    let field = |key: &str| map.get(key).filter(|v| !v.is_unit()).map(|v| v.to_string());
+    let severity = match field("severity").as_deref() {
+        None | Some("error") => Severity::Error,
+        Some("warning") => Severity::Warning,
+        Some(other) => return Err(format!("unknown severity '{}'", other)),
+    };
+    Ok(Finding {
+        rule: rule.to_string(),
+        severity,
+        message: field("message").ok_or("a finding needs a message")?,
+        target: field("target"),
+    })
+}
+
+/// Run every rule against `model`
+pub fn run_rules(engine: &Engine, rules: &[CustomRule], model: &Value) -> RulesReport {
+    let mut report = RulesReport::default();
+    let model = match rhai::serde::to_dynamic(model) {
+        Ok(model) => model,
+        Err(e) => {
+            report.failures.push(format!("Model could not be passed to rules: {}", e));
+            return report;
+        }
+    };
+
+    for rule in rules {
+        let mut scope = Scope::new();
+        scope.push_constant_dynamic("model", model.clone());
+        let result = engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &rule.ast)
+            .map_err(|e| e.to_string())
+            .and_then(|value| {
+                if value.is_unit() {
+                    return Ok(Vec::new());
+                }
+                value
+                    .into_array()
+                    .map_err(|_| "the script must return an array of findings".to_string())?
+                    .into_iter()
+                    .map(|item| to_finding(&rule.name, item))
+                    .collect::<Result<Vec<_>, _>>()
+            });
+        match result {
+            Ok(findings) => report.findings.extend(findings),
+            Err(e) => report.failures.push(format!("Rule '{}' failed: {}", rule.name, e)),
+        }
+    }
+    report
+}
+
+/// Load the rules of `dir` and run them against `model`
+pub fn check_model(dir: &Path, model: &Value) -> Result<RulesReport> {
+    let engine = sandboxed_engine();
+    let (rules, failures) = load_rules(&engine, dir)?;
+    let mut report = run_rules(&engine, &rules, model);
+    report.failures.splice(0..0, failures);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules_dir(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("custom_rules_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, source) in files {
+            std::fs::write(dir.join(file), source).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_rules_return_findings() {
+        let dir = rules_dir(
+            "findings",
+            &[
+                (
+                    "10_money_is_decimal.rhai",
+                    r#"
+                    let findings = [];
+                    for entity in model.entities {
+                        for attr in entity.attributes {
+                            if attr.name.contains("amount") && attr.type != "decimal" {
+                                findings.push(#{ message: "Amounts must be decimal", target: `${entity.id}.${attr.name}` });
+                            }
+                        }
+                    }
+                    findings
+                    "#,
+                ),
+                ("20_descriptions.rhai", r#"if model.entities.len() > 1 { [#{ severity: "warning", message: "Describe your entities" }] } else { [] }"#),
+                ("30_plain.rhai", r#"["Plain string finding"]"#),
+                ("notes.txt", "ignored"),
+            ],
+        );
+        let model = json!({
+            "entities": [
+                {"id": "Order", "attributes": [{"name": "amount", "type": "float"}]},
+                {"id": "Customer", "attributes": []}
+            ],
+            "relations": [],
+            "invariants": []
+        });
+
+        let report = check_model(&dir, &model).unwrap();
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+        assert_eq!(report.findings.len(), 3);
+        assert_eq!(report.findings[0].rule, "10_money_is_decimal");
+        assert_eq!(report.findings[0].target.as_deref(), Some("Order.amount"));
+        assert_eq!(report.findings[1].severity, Severity::Warning);
+        assert_eq!(report.findings[2].severity, Severity::Error);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_broken_and_runaway_rules_are_contained() {
+        let dir = rules_dir(
+            "sandbox",
+            &[
+                ("loop.rhai", "loop { }"),
+                ("syntax.rhai", "let = ;"),
+                ("import.rhai", r#"import "other" as o; []"#),
+                ("not_array.rhai", "42"),
+                ("ok.rhai", "[]"),
+            ],
+        );
+        let report = check_model(&dir, &json!({"entities": [], "relations": [], "invariants": []})).unwrap();
+        assert!(report.findings.is_empty());
+        assert_eq!(report.failures.len(), 4, "{:?}", report.failures);
+        assert!(report.failures.iter().any(|f| f.contains("syntax")));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,139 @@
+//! Structural diff between two domain models.
+//!
+//! Works on raw JSON so that fields unknown to the typed model (validation,
+//! defaultValue, uniqueConstraints…) also show up as modifications.
+//! Elements are matched by id; entity attributes are matched by name.
+
+use serde_json::{json, Map, Value};
+
+fn items<'a>(model: &'a Value, key: &str) -> &'a [Value] {
+    model.get(key).and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[])
+}
+
+fn key_of<'a>(item: &'a Value, field: &str) -> &'a str {
+    item.get(field).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Top-level fields whose value differs, sorted, ignoring `skip`
+fn changed_fields(before: &Value, after: &Value, skip: &[&str]) -> Vec<String> {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+    let mut fields: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|k| !skip.contains(&k.as_str()))
+        .filter(|k| before.get(*k) != after.get(*k))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+/// Diff two lists of elements matched on `key`; `detail` adds extra
+/// information to a modified element (e.g. attribute-level changes)
+fn diff_list(
+    before: &[Value],
+    after: &[Value],
+    key: &str,
+    detail: impl Fn(&Value, &Value, &mut Map<String, Value>) -> bool,
+) -> Value {
+    let find = |list: &'_ [Value], id: &str| list.iter().find(|v| key_of(v, key) == id).cloned();
+    let added: Vec<&str> = after
+        .iter()
+        .map(|v| key_of(v, key))
+        .filter(|id| find(before, id).is_none())
+        .collect();
+    let removed: Vec<&str> = before
+        .iter()
+        .map(|v| key_of(v, key))
+        .filter(|id| find(after, id).is_none())
+        .collect();
+
+    let mut modified = Vec::new();
+    for old in before {
+        let id = key_of(old, key);
+        let Some(new) = find(after, id) else { continue };
+        let mut entry = Map::new();
+        let has_detail = detail(old, &new, &mut entry);
+        let fields = changed_fields(old, &new, &[key, "attributes"]);
+        if fields.is_empty() && !has_detail {
+            continue;
+        }
+        entry.insert(key.to_string(), json!(id));
+        entry.insert("fields".to_string(), json!(fields));
+        modified.push(Value::Object(entry));
+    }
+
+    json!({ "added": added, "removed": removed, "modified": modified })
+}
+
+fn is_empty_diff(diff: &Value) -> bool {
+    ["added", "removed", "modified"]
+        .iter()
+        .all(|k| diff[*k].as_array().is_none_or(|a| a.is_empty()))
+}
+
+/// Compare `before` and `after`, returning added/removed/modified elements
+pub fn diff_models(before: &Value, after: &Value) -> Value {
+    let entities = diff_list(items(before, "entities"), items(after, "entities"), "id", |old, new, entry| {
+        let attributes = diff_list(items(old, "attributes"), items(new, "attributes"), "name", |_, _, _| false);
+        if is_empty_diff(&attributes) {
+            return false;
+        }
+        entry.insert("attributes".to_string(), attributes);
+        true
+    });
+    let relations = diff_list(items(before, "relations"), items(after, "relations"), "id", |_, _, _| false);
+    let invariants = diff_list(items(before, "invariants"), items(after, "invariants"), "id", |_, _, _| false);
+
+    let identical = is_empty_diff(&entities) && is_empty_diff(&relations) && is_empty_diff(&invariants);
+    json!({
+        "identical": identical,
+        "entities": entities,
+        "relations": relations,
+        "invariants": invariants
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_added_removed_and_modified() {
+        let before = json!({
+            "entities": [
+                {"id": "Book", "name": "Book", "attributes": [{"name": "title", "type": "string"}, {"name": "isbn", "type": "string"}]},
+                {"id": "Shelf", "name": "Shelf", "attributes": [{"name": "code", "type": "string"}]}
+            ],
+            "relations": [{"id": "shelf-holds-book", "name": "holds"}],
+            "invariants": []
+        });
+        let after = json!({
+            "entities": [
+                {"id": "Book", "name": "Book", "attributes": [{"name": "title", "type": "text"}, {"name": "isbn", "type": "string", "unique": true}]},
+                {"id": "Author", "name": "Author", "attributes": [{"name": "name", "type": "string"}]}
+            ],
+            "relations": [{"id": "shelf-holds-book", "name": "contains"}],
+            "invariants": []
+        });
+        let diff = diff_models(&before, &after);
+
+        assert_eq!(diff["identical"], false);
+        assert_eq!(diff["entities"]["added"], json!(["Author"]));
+        assert_eq!(diff["entities"]["removed"], json!(["Shelf"]));
+        let book = &diff["entities"]["modified"][0];
+        assert_eq!(book["id"], "Book");
+        assert_eq!(book["attributes"]["modified"][0], json!({"name": "title", "fields": ["type"]}));
+        assert_eq!(book["attributes"]["modified"][1], json!({"name": "isbn", "fields": ["unique"]}));
+        assert_eq!(diff["relations"]["modified"][0]["fields"], json!(["name"]));
+    }
+
+    #[test]
+    fn identical_models() {
+        let model = json!({"entities": [{"id": "A", "name": "A", "attributes": []}], "relations": [], "invariants": []});
+        assert_eq!(diff_models(&model, &model)["identical"], true);
+    }
+}
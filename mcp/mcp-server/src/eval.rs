@@ -0,0 +1,350 @@
+//! Evaluation harness for normalization quality.
+//!
+//! A corpus is a directory of labeled cases: `<case>.jsonl` (transcript, same
+//! format as `--input`) next to `<case>.expected.json` (expected DomainModel).
+//! Each generated model is scored against its expected model with
+//! precision/recall/F1 for entities, relations and invariants, and the
+//! report can be compared with a previous one to catch regressions.
+//!
+//! Matching is deliberately lenient so that cosmetic differences do not
+//! count as errors:
+//! - entities match on their id or name (case, accents and plural folded),
+//! - relations match on the unordered pair of entities they connect,
+//! - invariants match when their names share at least half of their words.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Minimal word overlap (Jaccard) for two invariant names to match
+const INVARIANT_NAME_OVERLAP: f64 = 0.5;
+
+/// A labeled case of the corpus
+#[derive(Debug, Clone)]
+pub struct EvalCase {
+    pub name: String,
+    pub transcript: PathBuf,
+    pub expected: PathBuf,
+}
+
+/// Precision/recall for one category of model elements
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct Score {
+    pub expected: usize,
+    pub generated: usize,
+    pub matched: usize,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+impl Score {
+    fn new(expected: usize, generated: usize, matched: usize) -> Self {
+        // Nothing expected and nothing generated is a perfect score
+        let ratio = |num: usize, den: usize| if den == 0 { 1.0 } else { num as f64 / den as f64 };
+        let precision = ratio(matched, generated);
+        let recall = ratio(matched, expected);
+        let f1 = if precision + recall == 0.0 {
+            0.0
+        } else {
+            2.0 * precision * recall / (precision + recall)
+        };
+        Self { expected, generated, matched, precision, recall, f1 }
+    }
+
+    /// Micro-average: sum the counts, then recompute the ratios
+    fn merge(scores: &[Score]) -> Self {
+        let sum = |f: fn(&Score) -> usize| scores.iter().map(f).sum::<usize>();
+        Self::new(sum(|s| s.expected), sum(|s| s.generated), sum(|s| s.matched))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelScore {
+    pub entities: Score,
+    pub relations: Score,
+    pub invariants: Score,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseReport {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub scores: ModelScore,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Regression {
+    pub case: String,
+    pub category: String,
+    pub baseline_f1: f64,
+    pub f1: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalReport {
+    pub cases: Vec<CaseReport>,
+    pub overall: ModelScore,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regressions: Vec<Regression>,
+}
+
+/// Find the labeled cases of a corpus directory, sorted by name.
+///
+/// Transcripts without an `.expected.json` sibling are skipped with a warning.
+pub fn discover_cases(dir: &Path) -> Result<Vec<EvalCase>> {
+    let mut cases = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read corpus directory: {:?}", dir))? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+            continue;
+        };
+        let expected = dir.join(format!("{}.expected.json", name));
+        if !expected.exists() {
+            tracing::warn!(target: "domain::eval", case = name, "No expected model, case skipped");
+            continue;
+        }
+        cases.push(EvalCase { name, transcript: path, expected });
+    }
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(cases)
+}
+
+/// Lowercase, strip accents, separators and a trailing plural mark
+fn fold(identifier: &str) -> String {
+    let folded: String = identifier
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| match c {
+            'à' | 'â' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'î' | 'ï' => 'i',
+            'ô' | 'ö' => 'o',
+            'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            c => c,
+        })
+        .collect();
+    match folded.strip_suffix('s') {
+        Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+        _ => folded,
+    }
+}
+
+fn items<'a>(model: &'a Value, key: &str) -> &'a [Value] {
+    model.get(key).and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[])
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Greedy one-to-one matching; returns the number of matched pairs
+fn count_matches<T>(expected: &[T], generated: &[T], same: impl Fn(&T, &T) -> bool) -> usize {
+    let mut used = vec![false; generated.len()];
+    let mut matched = 0;
+    for e in expected {
+        if let Some(i) = (0..generated.len()).find(|&i| !used[i] && same(e, &generated[i])) {
+            used[i] = true;
+            matched += 1;
+        }
+    }
+    matched
+}
+
+fn name_words(name: &str) -> HashSet<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 2)
+        .map(fold)
+        .collect()
+}
+
+/// Score a generated model against the expected one
+pub fn score_model(expected: &Value, generated: &Value) -> ModelScore {
+    let entity_keys = |model: &Value| -> Vec<(String, String)> {
+        items(model, "entities")
+            .iter()
+            .map(|e| (fold(str_field(e, "id")), fold(str_field(e, "name"))))
+            .collect()
+    };
+    let expected_entities = entity_keys(expected);
+    let generated_entities = entity_keys(generated);
+    let entities = Score::new(
+        expected_entities.len(),
+        generated_entities.len(),
+        count_matches(&expected_entities, &generated_entities, |a, b| {
+            a.0 == b.0 || (!a.1.is_empty() && a.1 == b.1)
+        }),
+    );
+
+    // Relations are compared on the entities they connect, in either direction,
+    // using the folded entity ids so that a renamed entity does not hide a good relation.
+    let relation_keys = |model: &Value| -> Vec<(String, String)> {
+        items(model, "relations")
+            .iter()
+            .map(|r| {
+                let from = fold(r.pointer("/from/entityId").and_then(|v| v.as_str()).unwrap_or(""));
+                let to = fold(r.pointer("/to/entityId").and_then(|v| v.as_str()).unwrap_or(""));
+                if from <= to { (from, to) } else { (to, from) }
+            })
+            .collect()
+    };
+    let expected_relations = relation_keys(expected);
+    let generated_relations = relation_keys(generated);
+    let relations = Score::new(
+        expected_relations.len(),
+        generated_relations.len(),
+        count_matches(&expected_relations, &generated_relations, |a, b| a == b),
+    );
+
+    let invariant_keys = |model: &Value| -> Vec<(String, HashSet<String>)> {
+        items(model, "invariants")
+            .iter()
+            .map(|i| (fold(str_field(i, "id")), name_words(str_field(i, "name"))))
+            .collect()
+    };
+    let expected_invariants = invariant_keys(expected);
+    let generated_invariants = invariant_keys(generated);
+    let invariants = Score::new(
+        expected_invariants.len(),
+        generated_invariants.len(),
+        count_matches(&expected_invariants, &generated_invariants, |a, b| {
+            if a.0 == b.0 {
+                return true;
+            }
+            let union = a.1.union(&b.1).count();
+            union > 0 && a.1.intersection(&b.1).count() as f64 / union as f64 >= INVARIANT_NAME_OVERLAP
+        }),
+    );
+
+    ModelScore { entities, relations, invariants }
+}
+
+impl EvalReport {
+    pub fn new(cases: Vec<CaseReport>) -> Self {
+        let overall = ModelScore {
+            entities: Score::merge(&cases.iter().map(|c| c.scores.entities).collect::<Vec<_>>()),
+            relations: Score::merge(&cases.iter().map(|c| c.scores.relations).collect::<Vec<_>>()),
+            invariants: Score::merge(&cases.iter().map(|c| c.scores.invariants).collect::<Vec<_>>()),
+        };
+        Self { cases, overall, regressions: Vec::new() }
+    }
+
+    /// Record every case (and the overall score) whose F1 dropped by more
+    /// than `tolerance` compared to `baseline`
+    pub fn compare(&mut self, baseline: &EvalReport, tolerance: f64) {
+        let by_name: BTreeMap<&str, &ModelScore> =
+            baseline.cases.iter().map(|c| (c.name.as_str(), &c.scores)).collect();
+        let mut regressions = Vec::new();
+        let mut check = |case: &str, before: &ModelScore, after: &ModelScore| {
+            for (category, b, a) in [
+                ("entities", before.entities, after.entities),
+                ("relations", before.relations, after.relations),
+                ("invariants", before.invariants, after.invariants),
+            ] {
+                if b.f1 - a.f1 > tolerance {
+                    regressions.push(Regression {
+                        case: case.to_string(),
+                        category: category.to_string(),
+                        baseline_f1: b.f1,
+                        f1: a.f1,
+                    });
+                }
+            }
+        };
+        for case in &self.cases {
+            if let Some(before) = by_name.get(case.name.as_str()) {
+                check(&case.name, before, &case.scores);
+            }
+        }
+        check("overall", &baseline.overall, &self.overall);
+        self.regressions = regressions;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn expected() -> Value {
+        json!({
+            "entities": [
+                {"id": "Livre", "name": "Livre"},
+                {"id": "Auteur", "name": "Auteur"}
+            ],
+            "relations": [
+                {"id": "ecrit", "from": {"entityId": "Auteur"}, "to": {"entityId": "Livre"}}
+            ],
+            "invariants": [
+                {"id": "isbn_unique", "name": "ISBN unique dans le système"}
+            ]
+        })
+    }
+
+    #[test]
+    fn perfect_match_is_lenient_on_form() {
+        let generated = json!({
+            "entities": [
+                {"id": "livres", "name": "Livres"},
+                {"id": "Author", "name": "Auteur"}
+            ],
+            "relations": [
+                {"id": "livre_auteurs", "from": {"entityId": "Livre"}, "to": {"entityId": "Auteur"}}
+            ],
+            "invariants": [
+                {"id": "unicite_isbn", "name": "Unicité de l'ISBN dans le système"}
+            ]
+        });
+        let score = score_model(&expected(), &generated);
+        assert_eq!(score.entities.f1, 1.0);
+        assert_eq!(score.relations.f1, 1.0);
+        assert_eq!(score.invariants.matched, 1);
+    }
+
+    #[test]
+    fn partial_match_scores_precision_and_recall() {
+        let generated = json!({
+            "entities": [
+                {"id": "Livre", "name": "Livre"},
+                {"id": "Exemplaire", "name": "Exemplaire"},
+                {"id": "Emprunt", "name": "Emprunt"}
+            ],
+            "relations": [],
+            "invariants": []
+        });
+        let score = score_model(&expected(), &generated);
+        assert_eq!(score.entities.matched, 1);
+        assert!((score.entities.precision - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(score.entities.recall, 0.5);
+        assert_eq!(score.relations.recall, 0.0);
+        assert_eq!(score.relations.precision, 1.0);
+    }
+
+    #[test]
+    fn compare_flags_regressions() {
+        let case = |f1_source: &Value| CaseReport {
+            name: "library".to_string(),
+            error: None,
+            scores: score_model(&expected(), f1_source),
+        };
+        let baseline = EvalReport::new(vec![case(&expected())]);
+        let mut report = EvalReport::new(vec![case(&json!({"entities": [{"id": "Livre", "name": "Livre"}]}))]);
+        report.compare(&baseline, 0.05);
+
+        assert!(report.regressions.iter().any(|r| r.case == "library" && r.category == "relations"));
+        assert!(report.regressions.iter().any(|r| r.case == "overall" && r.category == "entities"));
+
+        let mut unchanged = EvalReport::new(vec![case(&expected())]);
+        unchanged.compare(&baseline, 0.05);
+        assert!(unchanged.regressions.is_empty());
+    }
+}
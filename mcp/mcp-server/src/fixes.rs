@@ -0,0 +1,241 @@
+//! Fix suggestions for validation errors.
+//!
+//! Works on raw JSON, as an invalid model may not deserialize. Each suggestion
+//! is an independent RFC 6902 patch the user can apply selectively; patches
+//! that depend on array positions start with `test` operations, so one applied
+//! after another fix moved things around fails cleanly instead of editing the
+//! wrong element.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Fix {
+    /// Problem the patch solves, in the words of the validation report
+    pub error: String,
+    pub description: String,
+    pub patch: Vec<Value>,
+    /// Quoted names an error must mention to be solved by this fix
+    #[serde(skip)]
+    mentions: Vec<String>,
+    /// Phrase identifying the kind of error, matched case-insensitively
+    #[serde(skip)]
+    kind: &'static str,
+}
+
+fn items<'a>(model: &'a Value, key: &str) -> &'a [Value] {
+    model.get(key).and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[])
+}
+
+fn str_of<'a>(item: &'a Value, field: &str) -> &'a str {
+    item.get(field).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Closest entity id to a mistyped one: same id in another case, or a few edits away
+fn closest_entity<'a>(ids: &[&'a str], wanted: &str) -> Option<&'a str> {
+    let wanted_lower = wanted.to_lowercase();
+    ids.iter()
+        .map(|id| (distance(&id.to_lowercase(), &wanted_lower), *id))
+        .filter(|(d, id)| *d <= (id.chars().count() / 3).max(1))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, id)| id)
+}
+
+/// Attribute to use as primary key: `id`, then `<entity>Id` / `<entity>_id`, then a uuid
+fn key_candidate<'a>(entity_id: &str, attributes: &'a [Value]) -> Option<&'a str> {
+    let own_keys = [format!("{}id", entity_id.to_lowercase()), format!("{}_id", entity_id.to_lowercase())];
+    let find = |matches: &dyn Fn(&Value) -> bool| attributes.iter().find(|a| matches(a)).map(|a| str_of(a, "name"));
+    find(&|a| str_of(a, "name").eq_ignore_ascii_case("id"))
+        .or_else(|| find(&|a| own_keys.contains(&str_of(a, "name").to_lowercase())))
+        .or_else(|| find(&|a| str_of(a, "type") == "uuid"))
+}
+
+fn entity_fixes(index: usize, entity: &Value, fixes: &mut Vec<Fix>) {
+    let id = str_of(entity, "id");
+    let attributes = entity.get("attributes").and_then(|a| a.as_array()).map(|a| a.as_slice()).unwrap_or(&[]);
+    let base = format!("/entities/{}", index);
+    let guard = json!({"op": "test", "path": format!("{}/id", base), "value": id});
+
+    // Duplicate attributes: drop the later occurrences
+    let mut seen = Vec::new();
+    for (attr_index, attr) in attributes.iter().enumerate() {
+        let name = str_of(attr, "name");
+        if seen.contains(&name) {
+            let path = format!("{}/attributes/{}", base, attr_index);
+            fixes.push(Fix {
+                error: format!("Entity '{}': Duplicate attribute name '{}'", id, name),
+                description: format!("Remove the second '{}' attribute of '{}'", name, id),
+                patch: vec![guard.clone(), json!({"op": "test", "path": format!("{}/name", path), "value": name}), json!({"op": "remove", "path": path})],
+                mentions: vec![id.to_string(), name.to_string()],
+                kind: "duplicate",
+            });
+        } else {
+            seen.push(name);
+        }
+    }
+
+    // Primary key naming missing attributes: keep the existing ones
+    let primary_key: Option<Vec<&str>> = entity
+        .get("primaryKey")
+        .and_then(|pk| pk.as_array())
+        .map(|pk| pk.iter().filter_map(|k| k.as_str()).collect());
+    if let Some(keys) = &primary_key {
+        let missing: Vec<&str> = keys.iter().filter(|k| !seen.contains(k)).copied().collect();
+        if !missing.is_empty() {
+            let kept: Vec<&str> = keys.iter().filter(|k| seen.contains(k)).copied().collect();
+            let operation = if kept.is_empty() {
+                json!({"op": "remove", "path": format!("{}/primaryKey", base)})
+            } else {
+                json!({"op": "replace", "path": format!("{}/primaryKey", base), "value": kept})
+            };
+            fixes.push(Fix {
+                error: format!("Entity '{}': Primary key references non-existent attribute '{}'", id, missing.join("', '")),
+                description: format!("Drop {} from the primary key of '{}'", missing.join(", "), id),
+                patch: vec![guard.clone(), operation],
+                mentions: std::iter::once(id.to_string()).chain(missing.iter().map(|k| k.to_string())).collect(),
+                kind: "primary key",
+            });
+        }
+    }
+
+    // No primary key nor unique attribute
+    let has_key = primary_key.as_ref().is_some_and(|keys| keys.iter().any(|k| seen.contains(k)));
+    let has_unique = attributes.iter().any(|a| a.get("unique").and_then(|u| u.as_bool()).unwrap_or(false));
+    if !has_key && !has_unique {
+        let key_path = format!("{}/primaryKey", base);
+        let key_op = if primary_key.is_some() { "replace" } else { "add" };
+        let candidate = key_candidate(id, attributes);
+        let (description, mut patch) = match candidate {
+            Some(name) => (format!("Use '{}' as primary key of '{}'", name, id), vec![guard.clone()]),
+            None => (
+                format!("Add an 'id' attribute (uuid) as primary key of '{}'", id),
+                vec![
+                    guard.clone(),
+                    json!({"op": "add", "path": format!("{}/attributes/0", base), "value": {"name": "id", "type": "uuid", "required": true, "unique": true}}),
+                ],
+            ),
+        };
+        patch.push(json!({"op": key_op, "path": key_path, "value": [candidate.unwrap_or("id")]}));
+        fixes.push(Fix {
+            error: format!("Entity '{}' must have either a primaryKey or at least one unique attribute", id),
+            description,
+            patch,
+            mentions: vec![id.to_string()],
+            kind: "unique attribute",
+        });
+    }
+}
+
+/// Concrete patches for the problems of `model`. With `errors` (from validate_model or
+/// a pipeline run), only the fixes of the problems they mention are kept, and errors
+/// with no fix are returned in `unfixed`.
+pub fn suggest_fixes(model: &Value, errors: Option<&[String]>) -> Value {
+    let mut fixes = Vec::new();
+    for (index, entity) in items(model, "entities").iter().enumerate() {
+        entity_fixes(index, entity, &mut fixes);
+    }
+
+    // Relations to a mistyped entity id
+    let ids: Vec<&str> = items(model, "entities").iter().map(|e| str_of(e, "id")).collect();
+    for (index, relation) in items(model, "relations").iter().enumerate() {
+        let relation_id = str_of(relation, "id");
+        for end in ["from", "to"] {
+            let wanted = relation.get(end).map(|e| str_of(e, "entityId")).unwrap_or("");
+            if wanted.is_empty() || ids.contains(&wanted) {
+                continue;
+            }
+            let Some(existing) = closest_entity(&ids, wanted) else {
+                continue;
+            };
+            let path = format!("/relations/{}/{}/entityId", index, end);
+            fixes.push(Fix {
+                error: format!("Relation '{}': References non-existent entity '{}'", relation_id, wanted),
+                description: format!("Point the '{}' end of '{}' to '{}' instead of '{}'", end, relation_id, existing, wanted),
+                patch: vec![json!({"op": "test", "path": path, "value": wanted}), json!({"op": "replace", "path": path, "value": existing})],
+                mentions: vec![relation_id.to_string(), wanted.to_string()],
+                kind: "non-existent entity",
+            });
+        }
+    }
+
+    let mut unfixed = Vec::new();
+    if let Some(errors) = errors {
+        let solves = |fix: &Fix, error: &str| {
+            error.to_lowercase().contains(fix.kind) && fix.mentions.iter().all(|name| error.contains(&format!("'{}'", name)))
+        };
+        unfixed = errors.iter().filter(|error| !fixes.iter().any(|fix| solves(fix, error))).cloned().collect();
+        fixes.retain(|fix| errors.iter().any(|error| solves(fix, error)));
+    }
+
+    let fixes: Vec<Value> = fixes
+        .into_iter()
+        .enumerate()
+        .map(|(i, fix)| {
+            let mut value = json!(fix);
+            value["id"] = json!(format!("fix-{}", i + 1));
+            value
+        })
+        .collect();
+    json!({ "fixes": fixes, "unfixed": unfixed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_patches_for_validation_errors() {
+        let model = json!({
+            "entities": [
+                {"id": "Customer", "name": "Customer", "attributes": [
+                    {"name": "customerId", "type": "uuid"}, {"name": "email", "type": "email"}, {"name": "email", "type": "string"}
+                ]},
+                {"id": "Order", "name": "Order", "primaryKey": ["number"], "attributes": [{"name": "total", "type": "number"}]}
+            ],
+            "relations": [
+                {"id": "places", "name": "places", "from": {"entityId": "customer"}, "to": {"entityId": "Order"}, "cardinality": {"from": "1", "to": "0..n"}},
+                {"id": "ships", "name": "ships", "from": {"entityId": "Order"}, "to": {"entityId": "Warehouse"}, "cardinality": {"from": "1", "to": "1"}}
+            ],
+            "invariants": []
+        });
+
+        let all = suggest_fixes(&model, None);
+        let descriptions: Vec<&str> = all["fixes"].as_array().unwrap().iter().map(|f| f["description"].as_str().unwrap()).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "Remove the second 'email' attribute of 'Customer'",
+                "Use 'customerId' as primary key of 'Customer'",
+                "Drop number from the primary key of 'Order'",
+                "Add an 'id' attribute (uuid) as primary key of 'Order'",
+                "Point the 'from' end of 'places' to 'Customer' instead of 'customer'",
+            ]
+        );
+        assert_eq!(all["fixes"][0]["patch"][2], json!({"op": "remove", "path": "/entities/0/attributes/2"}));
+        assert_eq!(all["fixes"][3]["patch"][2], json!({"op": "replace", "path": "/entities/1/primaryKey", "value": ["id"]}));
+
+        // Only the reported errors; "Warehouse" has no close match
+        let errors = vec![
+            "Entity 'Customer' (index 0) has duplicate attribute 'email' at index 2".to_string(),
+            "Relation 'ships' (index 1) references non-existent entity 'Warehouse' in 'to'".to_string(),
+        ];
+        let selected = suggest_fixes(&model, Some(&errors));
+        assert_eq!(selected["fixes"].as_array().unwrap().len(), 1);
+        assert_eq!(selected["fixes"][0]["id"], "fix-1");
+        assert_eq!(selected["unfixed"], json!([errors[1]]));
+    }
+}
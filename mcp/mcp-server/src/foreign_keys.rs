@@ -0,0 +1,268 @@
+//! Attribute-level relation endpoints.
+//!
+//! A relation end may name the attribute that carries it (`from.attribute`,
+//! `to.attribute`): the key on one side, the foreign key on the other. This
+//! checks that those attributes exist, that one of the two is a key the other
+//! can reference (primary key or unique attribute), and that both map to the
+//! same column type. Works on raw JSON so the raw-model validator can share it.
+//!
+//! `required` and `cascadeDelete` apply to the child side, the one carrying
+//! the foreign key: a NOT NULL foreign key, and children deleted with their
+//! parent. Cascades that run into another aggregate root are reported, as
+//! deleting one aggregate would silently delete another.
+
+use serde_json::Value;
+
+use crate::custom_rules::{Finding, Severity};
+
+/// Column type family of an attribute type: a foreign key and the key it
+/// references must share it to be declared as a SQL constraint.
+pub(crate) fn column_type(attr_type: &str) -> &'static str {
+    match attr_type {
+        "uuid" => "uuid",
+        "integer" => "integer",
+        "number" => "numeric",
+        "boolean" => "boolean",
+        "date" => "date",
+        "datetime" => "timestamp",
+        "json" => "json",
+        // string, text, email, url and custom types are stored as text
+        _ => "text",
+    }
+}
+
+fn str_of<'a>(item: &'a Value, field: &str) -> Option<&'a str> {
+    item.get(field).and_then(|v| v.as_str())
+}
+
+fn is_key(entity: &Value, attribute: &Value) -> bool {
+    let name = str_of(attribute, "name").unwrap_or("");
+    let in_primary_key = entity
+        .get("primaryKey")
+        .and_then(|pk| pk.as_array())
+        .is_some_and(|pk| pk.iter().any(|k| k.as_str() == Some(name)));
+    in_primary_key || attribute.get("unique").and_then(|u| u.as_bool()).unwrap_or(false)
+}
+
+fn is_one(cardinality: &str) -> bool {
+    matches!(cardinality, "1" | "0..1")
+}
+
+/// Ends (`"from"` / `"to"`) of the parent and of the child carrying the foreign key,
+/// or None for a many-to-many relation
+pub(crate) fn parent_child(relation: &Value) -> Option<(&'static str, &'static str)> {
+    let cardinality = |end: &str| relation.get("cardinality").and_then(|c| str_of(c, end)).unwrap_or("");
+    if is_one(cardinality("from")) {
+        Some(("from", "to"))
+    } else if is_one(cardinality("to")) {
+        Some(("to", "from"))
+    } else {
+        None
+    }
+}
+
+/// Whether the foreign key is NOT NULL: `required`, or exactly one parent per child
+pub(crate) fn not_null(relation: &Value) -> bool {
+    match relation.get("required").and_then(|r| r.as_bool()) {
+        Some(required) => required,
+        None => parent_child(relation)
+            .is_some_and(|(parent, _)| relation.get("cardinality").and_then(|c| str_of(c, parent)) == Some("1")),
+    }
+}
+
+pub(crate) fn cascades(relation: &Value) -> bool {
+    relation.get("cascadeDelete").and_then(|c| c.as_bool()).unwrap_or(false)
+}
+
+/// (parent, child, relation id) of every cascading relation
+fn cascade_edges(relations: &[Value]) -> Vec<(&str, &str, &str)> {
+    relations
+        .iter()
+        .filter(|r| cascades(r))
+        .filter_map(|r| {
+            let (parent, child) = parent_child(r)?;
+            let entity = |end: &str| r.get(end).and_then(|e| str_of(e, "entityId"));
+            Some((entity(parent)?, entity(child)?, str_of(r, "id").unwrap_or("<unknown>")))
+        })
+        .collect()
+}
+
+fn finding(rule: &str, severity: Severity, message: String, target: String) -> Finding {
+    Finding { rule: rule.to_string(), severity, message, target: Some(target) }
+}
+
+pub fn check(model: &Value) -> Vec<Finding> {
+    let empty = Vec::new();
+    let entities = model.get("entities").and_then(|e| e.as_array()).unwrap_or(&empty);
+    let relations = model.get("relations").and_then(|r| r.as_array()).unwrap_or(&empty);
+    let mut findings = Vec::new();
+
+    for relation in relations {
+        let relation_id = str_of(relation, "id").unwrap_or("<unknown>");
+        // (entity, attribute) of each end naming an existing attribute
+        let mut ends = Vec::new();
+        for end in ["from", "to"] {
+            let Some(name) = relation.get(end).and_then(|e| str_of(e, "attribute")) else {
+                continue;
+            };
+            let entity_id = relation.get(end).and_then(|e| str_of(e, "entityId")).unwrap_or("");
+            // Unknown entities are reported by the entity reference rule
+            let Some(entity) = entities.iter().find(|e| str_of(e, "id") == Some(entity_id)) else {
+                continue;
+            };
+            let attributes = entity.get("attributes").and_then(|a| a.as_array()).unwrap_or(&empty);
+            match attributes.iter().find(|a| str_of(a, "name") == Some(name)) {
+                Some(attribute) => ends.push((entity, attribute)),
+                None => findings.push(finding(
+                    "relation-attribute",
+                    Severity::Error,
+                    format!(
+                        "Relation '{}': '{}' attribute '{}' does not exist on entity '{}'",
+                        relation_id, end, name, entity_id
+                    ),
+                    format!("{}.{}", entity_id, name),
+                )),
+            }
+        }
+
+        let [(from_entity, from_attr), (to_entity, to_attr)] = ends[..] else {
+            continue;
+        };
+        let qualified = |entity: &Value, attribute: &Value| {
+            format!("{}.{}", str_of(entity, "id").unwrap_or(""), str_of(attribute, "name").unwrap_or(""))
+        };
+        let (from_name, to_name) = (qualified(from_entity, from_attr), qualified(to_entity, to_attr));
+        let target = format!("{}, {}", from_name, to_name);
+        let (from_type, to_type) = (str_of(from_attr, "type").unwrap_or(""), str_of(to_attr, "type").unwrap_or(""));
+        if column_type(from_type) != column_type(to_type) {
+            findings.push(finding(
+                "foreign-key-type",
+                Severity::Error,
+                format!(
+                    "Relation '{}': {} ({}) and {} ({}) have incompatible types",
+                    relation_id, from_name, from_type, to_name, to_type
+                ),
+                target,
+            ));
+        } else if !is_key(from_entity, from_attr) && !is_key(to_entity, to_attr) {
+            findings.push(finding(
+                "foreign-key-target",
+                Severity::Warning,
+                format!(
+                    "Relation '{}': neither {} nor {} is a primary key or unique attribute, no foreign key can reference it",
+                    relation_id, from_name, to_name
+                ),
+                target,
+            ));
+        }
+    }
+
+    // Cascades into another aggregate, with the chain of deletes leading to it
+    let roots: Vec<&str> = entities
+        .iter()
+        .filter(|e| e.get("aggregateRoot").and_then(|r| r.as_bool()).unwrap_or(false))
+        .filter_map(|e| str_of(e, "id"))
+        .collect();
+    let edges = cascade_edges(relations);
+    for (parent, child, relation_id) in &edges {
+        if parent == child || !roots.contains(child) {
+            continue;
+        }
+        let mut chain = vec![*child, *parent];
+        while let Some((upstream, _, _)) = edges.iter().find(|(_, c, _)| c == chain.last().unwrap()) {
+            if chain.contains(upstream) {
+                break;
+            }
+            chain.push(upstream);
+        }
+        chain.reverse();
+        findings.push(finding(
+            "cascade-chain",
+            Severity::Warning,
+            format!(
+                "Relation '{}': deleting a {} cascades through {} into aggregate root '{}'",
+                relation_id,
+                chain[0],
+                chain.join(" → "),
+                child
+            ),
+            chain.join(" → "),
+        ));
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn checks_endpoint_attributes_and_key_types() {
+        let model = json!({
+            "entities": [
+                {"id": "Customer", "primaryKey": ["id"], "attributes": [
+                    {"name": "id", "type": "uuid"}, {"name": "email", "type": "email"}, {"name": "code", "type": "string"}
+                ]},
+                {"id": "Order", "attributes": [
+                    {"name": "id", "type": "uuid", "unique": true},
+                    {"name": "customerId", "type": "uuid"}, {"name": "customerRef", "type": "integer"}, {"name": "customerEmail", "type": "text"}
+                ]}
+            ],
+            "relations": [
+                {"id": "places", "from": {"entityId": "Customer", "attribute": "id"}, "to": {"entityId": "Order", "attribute": "customerId"}},
+                {"id": "legacy", "from": {"entityId": "Customer", "attribute": "id"}, "to": {"entityId": "Order", "attribute": "customerRef"}},
+                {"id": "contact", "from": {"entityId": "Customer", "attribute": "email"}, "to": {"entityId": "Order", "attribute": "customerEmail"}},
+                {"id": "broken", "from": {"entityId": "Customer", "attribute": "uuid"}, "to": {"entityId": "Order"}}
+            ]
+        });
+
+        let findings = check(&model);
+        let summary: Vec<(&str, Severity, &str)> = findings
+            .iter()
+            .map(|f| (f.rule.as_str(), f.severity, f.target.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("foreign-key-type", Severity::Error, "Customer.id, Order.customerRef"),
+                ("foreign-key-target", Severity::Warning, "Customer.email, Order.customerEmail"),
+                ("relation-attribute", Severity::Error, "Customer.uuid"),
+            ]
+        );
+        assert_eq!(
+            findings[0].message,
+            "Relation 'legacy': Customer.id (uuid) and Order.customerRef (integer) have incompatible types"
+        );
+    }
+
+    #[test]
+    fn warns_on_cascades_into_aggregates() {
+        let model = json!({
+            "entities": [
+                {"id": "Customer", "aggregateRoot": true, "attributes": []},
+                {"id": "Order", "attributes": []},
+                {"id": "Invoice", "aggregateRoot": true, "attributes": []},
+                {"id": "InvoiceLine", "attributes": []}
+            ],
+            "relations": [
+                {"id": "places", "from": {"entityId": "Customer"}, "to": {"entityId": "Order"},
+                 "cardinality": {"from": "1", "to": "0..n"}, "cascadeDelete": true},
+                {"id": "billed-by", "from": {"entityId": "Invoice"}, "to": {"entityId": "Order"},
+                 "cardinality": {"from": "0..n", "to": "1"}, "cascadeDelete": true},
+                {"id": "lines", "from": {"entityId": "Invoice"}, "to": {"entityId": "InvoiceLine"},
+                 "cardinality": {"from": "1", "to": "1..n"}, "cascadeDelete": true}
+            ]
+        });
+
+        let findings = check(&model);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "cascade-chain");
+        assert_eq!(
+            findings[0].message,
+            "Relation 'billed-by': deleting a Customer cascades through Customer → Order → Invoice into aggregate root 'Invoice'"
+        );
+        assert!(not_null(&model["relations"][1]), "Each invoice bills exactly one order");
+    }
+}
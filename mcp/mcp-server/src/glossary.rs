@@ -0,0 +1,330 @@
+//! Ubiquitous-language checks.
+//!
+//! Model identifiers are cross-checked against the project glossary and the
+//! interview "Langage Ubiquiste" table. Terms are grouped into synonym
+//! classes (built-in FR/EN pairs plus synonyms declared in the glossary);
+//! using two members of the same class in one model, or a synonym instead of
+//! the glossary term, is reported as a lint warning with a canonical term.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::DomainModel;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryTerm {
+    pub term: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+}
+
+/// Common business synonyms, French and English. The first entry of each
+/// group is only a fallback canonical term; the glossary always wins.
+pub(crate) const SYNONYM_GROUPS: &[&[&str]] = &[
+    &["customer", "client", "buyer", "acheteur"],
+    &["order", "commande", "purchase"],
+    &["product", "produit"],
+    &["invoice", "facture", "bill"],
+    &["user", "utilisateur"],
+    &["address", "adresse"],
+    &["payment", "paiement", "reglement"],
+    &["delivery", "livraison", "shipment", "expedition"],
+    &["item", "article", "line", "ligne"],
+    &["cart", "panier", "basket"],
+    &["account", "compte"],
+    &["supplier", "fournisseur", "vendor"],
+    &["subscription", "abonnement"],
+    &["booking", "reservation"],
+    &["employee", "employe", "staff"],
+    &["book", "livre", "ouvrage"],
+    &["author", "auteur"],
+    &["price", "prix", "tarif"],
+    &["quantity", "quantite", "qty"],
+];
+
+/// Lowercase, strip accents and a trailing plural mark.
+pub(crate) fn fold(word: &str) -> String {
+    let folded: String = word
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'à' | 'â' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'î' | 'ï' => 'i',
+            'ô' | 'ö' => 'o',
+            'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            c => c,
+        })
+        .collect();
+    match folded.strip_suffix('s') {
+        Some(stem) if stem.len() > 2 && !stem.ends_with('s') && !stem.ends_with('u') => stem.to_string(),
+        _ => folded,
+    }
+}
+
+/// Split an identifier (camelCase, PascalCase, snake_case, kebab, spaces) into words.
+pub(crate) fn words(identifier: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in identifier.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                out.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            out.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+    out
+}
+
+/// Parse the "Langage Ubiquiste" markdown table (`| Terme | Définition | Exemple |`).
+///
+/// Synonyms mentioned in the definition as "syn. X", "synonyme : X",
+/// "aka X" or "aussi appelé X" are attached to the term.
+pub fn parse_ubiquitous_table(markdown: &str) -> Vec<GlossaryTerm> {
+    let mut terms = Vec::new();
+    for line in markdown.lines() {
+        let line = line.trim();
+        if !line.starts_with('|') {
+            continue;
+        }
+        let cells: Vec<&str> = line.trim_matches('|').split('|').map(|c| c.trim()).collect();
+        let Some(first) = cells.first() else { continue };
+        let term = first.trim_matches(|c| c == '*' || c == '`' || c == '[' || c == ']').trim();
+        let header = fold(term);
+        if term.is_empty() || term.chars().all(|c| c == '-' || c == ':') || header == "terme" || header == "term" {
+            continue;
+        }
+        let definition = cells.get(1).map(|d| d.to_string()).filter(|d| !d.is_empty());
+        let synonyms = definition.as_deref().map(extract_synonyms).unwrap_or_default();
+        terms.push(GlossaryTerm {
+            term: term.to_string(),
+            definition,
+            synonyms,
+        });
+    }
+    terms
+}
+
+fn extract_synonyms(definition: &str) -> Vec<String> {
+    let lower = definition.to_lowercase();
+    let markers = ["synonymes", "synonyme", "syn.", "aka", "aussi appelé", "aussi appele", "also called"];
+    let mut synonyms = Vec::new();
+    for marker in markers {
+        let Some(start) = lower.find(marker) else { continue };
+        let rest = &lower[start + marker.len()..];
+        let rest = rest.trim_start_matches(|c: char| c == ':' || c.is_whitespace());
+        let end = rest.find([')', '.', ';']).unwrap_or(rest.len());
+        synonyms.extend(
+            rest[..end]
+                .split([',', '/'])
+                .flat_map(|s| s.split(" ou "))
+                .flat_map(|s| s.split(" or "))
+                .map(|s| s.trim().trim_matches(|c| c == '"' || c == '«' || c == '»' || c == '*').trim().to_string())
+                .filter(|s| !s.is_empty()),
+        );
+        break;
+    }
+    synonyms
+}
+
+struct Usage {
+    surface: String,
+    location: String,
+}
+
+/// Check model names against the glossary and report inconsistent synonyms.
+pub fn check_ubiquitous_language(model: &DomainModel, glossary: &[GlossaryTerm]) -> Result<Value> {
+    // Synonym classes: folded word -> class index, with a canonical term per class.
+    let mut class_of: HashMap<String, usize> = HashMap::new();
+    let mut canonical: Vec<Option<String>> = Vec::new();
+    let mut fallback: Vec<String> = Vec::new();
+
+    for group in SYNONYM_GROUPS {
+        let index = canonical.len();
+        canonical.push(None);
+        fallback.push(group[0].to_string());
+        for word in *group {
+            class_of.insert(fold(word), index);
+        }
+    }
+    for entry in glossary {
+        let members: Vec<String> = std::iter::once(&entry.term)
+            .chain(entry.synonyms.iter())
+            .map(|t| fold(t))
+            .collect();
+        let index = members
+            .iter()
+            .find_map(|m| class_of.get(m).copied())
+            .unwrap_or_else(|| {
+                canonical.push(None);
+                fallback.push(entry.term.clone());
+                canonical.len() - 1
+            });
+        for member in members {
+            class_of.insert(member, index);
+        }
+        canonical[index].get_or_insert_with(|| entry.term.clone());
+    }
+
+    // Collect usages per class, per folded surface form.
+    let mut usages: BTreeMap<usize, BTreeMap<String, Vec<Usage>>> = BTreeMap::new();
+    let mut record = |identifier: &str, location: String| {
+        for word in words(identifier) {
+            let folded = fold(&word);
+            if let Some(class) = class_of.get(&folded) {
+                usages
+                    .entry(*class)
+                    .or_default()
+                    .entry(folded)
+                    .or_default()
+                    .push(Usage { surface: word, location: location.clone() });
+            }
+        }
+    };
+    for entity in &model.entities {
+        record(&entity.id, format!("entity '{}'", entity.id));
+        if entity.name != entity.id {
+            record(&entity.name, format!("entity name '{}'", entity.name));
+        }
+        for attr in &entity.attributes {
+            record(&attr.name, format!("attribute '{}.{}'", entity.id, attr.name));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let mut suggestions = Vec::new();
+    for (class, forms) in &usages {
+        let glossary_term = canonical[*class].clone();
+        let canonical_term = glossary_term.clone().unwrap_or_else(|| {
+            // Most used form in the model when the glossary says nothing.
+            forms
+                .values()
+                .max_by_key(|u| u.len())
+                .and_then(|u| u.first())
+                .map(|u| u.surface.clone())
+                .unwrap_or_else(|| fallback[*class].clone())
+        });
+        let canonical_folded = fold(&canonical_term);
+
+        let offending: Vec<&Usage> = forms
+            .iter()
+            .filter(|(form, _)| **form != canonical_folded)
+            .flat_map(|(_, u)| u.iter())
+            .collect();
+        let inconsistent = forms.len() > 1;
+        let off_glossary = glossary_term.is_some() && !offending.is_empty();
+        if !inconsistent && !off_glossary {
+            continue;
+        }
+
+        for usage in &offending {
+            let reason = if glossary_term.is_some() {
+                "glossary term is"
+            } else {
+                "model also uses"
+            };
+            warnings.push(format!(
+                "Ubiquitous language: {} uses '{}' but the {} '{}'",
+                usage.location, usage.surface, reason, canonical_term
+            ));
+        }
+        suggestions.push(json!({
+            "canonical": canonical_term,
+            "synonyms": forms.values().filter_map(|u| u.first()).map(|u| u.surface.clone()).filter(|s| fold(s) != canonical_folded).collect::<Vec<_>>(),
+            "locations": offending.iter().map(|u| u.location.clone()).collect::<Vec<_>>(),
+        }));
+    }
+
+    // Entities the glossary does not define at all.
+    if !glossary.is_empty() {
+        for entity in &model.entities {
+            let defined = words(&entity.id).iter().any(|w| {
+                let folded = fold(w);
+                class_of
+                    .get(&folded)
+                    .is_some_and(|c| canonical[*c].is_some())
+            });
+            if !defined {
+                warnings.push(format!(
+                    "Ubiquitous language: entity '{}' is not defined in the glossary",
+                    entity.id
+                ));
+            }
+        }
+    }
+
+    Ok(json!({
+        "ok": warnings.is_empty(),
+        "warnings": warnings,
+        "suggestions": suggestions
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> DomainModel {
+        serde_json::from_value(json!({
+            "entities": [
+                {"id": "Client", "name": "Client", "attributes": [{"name": "email", "type": "email"}]},
+                {"id": "Order", "name": "Order", "attributes": [{"name": "customerId", "type": "uuid"}, {"name": "total", "type": "number"}]}
+            ],
+            "relations": [],
+            "invariants": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_ubiquitous_table_with_synonyms() {
+        let table = "| Terme | Définition métier | Exemple |\n\
+                     | ----- | ----------------- | ------- |\n\
+                     | **Client** | Personne qui passe commande (syn. acheteur, customer) | Alice |\n\
+                     | Commande | Demande d'achat validée | CMD-42 |\n";
+        let terms = parse_ubiquitous_table(table);
+        assert_eq!(terms.len(), 2);
+        assert_eq!(terms[0].term, "Client");
+        assert_eq!(terms[0].synonyms, vec!["acheteur", "customer"]);
+        assert!(terms[1].synonyms.is_empty());
+    }
+
+    #[test]
+    fn flags_synonyms_against_glossary() {
+        let glossary = vec![GlossaryTerm {
+            term: "Client".to_string(),
+            definition: None,
+            synonyms: vec![],
+        }];
+        let result = check_ubiquitous_language(&model(), &glossary).unwrap();
+        let warnings: Vec<&str> = result["warnings"].as_array().unwrap().iter().map(|w| w.as_str().unwrap()).collect();
+
+        assert!(warnings.iter().any(|w| w.contains("'Order.customerId' uses 'customer'") && w.contains("'Client'")));
+        assert_eq!(result["suggestions"][0]["canonical"], "Client");
+        // Order is a known business term but the glossary does not define it.
+        assert!(warnings.iter().any(|w| w.contains("entity 'Order' is not defined")));
+    }
+
+    #[test]
+    fn flags_inconsistency_without_glossary() {
+        let result = check_ubiquitous_language(&model(), &[]).unwrap();
+        assert_eq!(result["ok"], false);
+        assert_eq!(result["warnings"].as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,128 @@
+//! Hallucination guard.
+//!
+//! Every entity and attribute name of a generated model must be grounded in
+//! the interview: each of its words has to appear in the transcript or the
+//! glossary, up to case, accents, plural, a shared stem (`livraison` /
+//! `livraisons`, `deliver` / `delivery`) or a known FR/EN synonym. Names that
+//! are not are reported as `unsupported` for review instead of being accepted.
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::glossary::{self, GlossaryTerm};
+use crate::DomainModel;
+
+/// Words that carry no domain meaning in identifiers (keys, timestamps, connectors).
+const NEUTRAL_WORDS: &[&str] = &[
+    "id", "uuid", "key", "at", "is", "has", "created", "updated", "deleted", "de", "du", "des", "la", "le", "of", "the",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Unsupported {
+    /// "entity" or "attribute"
+    pub kind: &'static str,
+    /// Entity id, or "Entity.attribute"
+    pub target: String,
+    /// Words of the name found neither in the transcript nor in the glossary
+    pub unmatched: Vec<String>,
+}
+
+struct Vocabulary {
+    words: HashSet<String>,
+}
+
+impl Vocabulary {
+    fn new(transcript: &str, glossary: &[GlossaryTerm]) -> Self {
+        let mut words: HashSet<String> = glossary::words(transcript).iter().map(|w| glossary::fold(w)).collect();
+        for entry in glossary {
+            for term in std::iter::once(&entry.term).chain(entry.synonyms.iter()) {
+                words.extend(glossary::words(term).iter().map(|w| glossary::fold(w)));
+            }
+        }
+        // A French transcript grounds the English names of the same concept, and vice versa
+        for group in glossary::SYNONYM_GROUPS {
+            if group.iter().any(|w| words.contains(&glossary::fold(w))) {
+                words.extend(group.iter().map(|w| glossary::fold(w)));
+            }
+        }
+        Self { words }
+    }
+
+    fn grounds(&self, word: &str) -> bool {
+        if word.chars().count() <= 2 || word.chars().all(|c| c.is_ascii_digit()) || NEUTRAL_WORDS.contains(&word) {
+            return true;
+        }
+        self.words.contains(word) || self.words.iter().any(|known| same_stem(word, known))
+    }
+
+    fn unmatched(&self, name: &str) -> Vec<String> {
+        glossary::words(name)
+            .into_iter()
+            .filter(|w| !self.grounds(&glossary::fold(w)))
+            .collect()
+    }
+}
+
+/// Lemmatization by shared prefix: at least 4 characters, and at most 3
+/// characters left over on the shorter word ("commande" / "commander").
+fn same_stem(a: &str, b: &str) -> bool {
+    let shorter = a.chars().count().min(b.chars().count());
+    if shorter < 4 {
+        return false;
+    }
+    let common = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count();
+    common >= 4.max(shorter.saturating_sub(3))
+}
+
+/// Entities and attributes whose names are not grounded in the transcript or glossary
+pub fn unsupported(model: &DomainModel, transcript: &str, glossary: &[GlossaryTerm]) -> Vec<Unsupported> {
+    let vocabulary = Vocabulary::new(transcript, glossary);
+    let mut flagged = Vec::new();
+    for entity in &model.entities {
+        // Either the identifier or the display name may carry the spoken term
+        let by_id = vocabulary.unmatched(&entity.id);
+        if !by_id.is_empty() && !vocabulary.unmatched(&entity.name).is_empty() {
+            flagged.push(Unsupported { kind: "entity", target: entity.id.clone(), unmatched: by_id });
+        }
+        for attr in &entity.attributes {
+            let unmatched = vocabulary.unmatched(&attr.name);
+            if !unmatched.is_empty() {
+                flagged.push(Unsupported { kind: "attribute", target: format!("{}.{}", entity.id, attr.name), unmatched });
+            }
+        }
+    }
+    flagged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flags_names_absent_from_transcript() {
+        let model: DomainModel = serde_json::from_value(json!({
+            "entities": [
+                {"id": "Order", "name": "Commande", "attributes": [
+                    {"name": "id", "type": "uuid"},
+                    {"name": "deliveryDate", "type": "date"},
+                    {"name": "customerId", "type": "uuid"},
+                    {"name": "loyaltyPoints", "type": "integer"}
+                ]},
+                {"id": "Warehouse", "name": "Warehouse", "attributes": [{"name": "createdAt", "type": "datetime"}]},
+                {"id": "Sku", "name": "Sku", "attributes": [{"name": "code", "type": "string"}]}
+            ],
+            "relations": [],
+            "invariants": []
+        }))
+        .unwrap();
+        let transcript = "Le client passe des commandes. Chaque commande a une date de livraison.";
+        let glossary = vec![GlossaryTerm { term: "SKU".to_string(), definition: None, synonyms: vec!["code article".to_string()] }];
+
+        let flagged = unsupported(&model, transcript, &glossary);
+        let targets: Vec<&str> = flagged.iter().map(|u| u.target.as_str()).collect();
+        assert_eq!(targets, vec!["Order.loyaltyPoints", "Warehouse"]);
+        assert_eq!(flagged[0].unmatched, vec!["loyalty", "Points"]);
+        assert!(same_stem("command", "commander") && !same_stem("date", "data"));
+    }
+}
@@ -0,0 +1,524 @@
+//! Layout hints for diagram emission.
+//!
+//! A model can carry an optional `layout` block that the emitters honor:
+//! groups (rendered as namespaces / commented sections), entity ranks
+//! (emission order inside a group), hidden relations and a direction hint.
+//! Emitters can also render a subset of the model through [`DiagramFilter`].
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use crate::{DomainModel, Entity};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LayoutHints {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<LayoutGroup>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ranks: BTreeMap<String, u32>,
+    #[serde(default, rename = "hiddenRelations", skip_serializing_if = "Vec::is_empty")]
+    pub hidden_relations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutGroup {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub entities: Vec<String>,
+}
+
+impl LayoutHints {
+    /// Mermaid direction keyword, if a valid one was provided.
+    pub fn mermaid_direction(&self) -> Option<&'static str> {
+        match self.direction.as_deref().map(|d| d.to_uppercase()) {
+            Some(d) if d == "TB" || d == "TD" => Some("TB"),
+            Some(d) if d == "BT" => Some("BT"),
+            Some(d) if d == "LR" => Some("LR"),
+            Some(d) if d == "RL" => Some("RL"),
+            _ => None,
+        }
+    }
+
+    pub fn is_hidden(&self, relation_id: &str) -> bool {
+        self.hidden_relations.iter().any(|id| id == relation_id)
+    }
+}
+
+/// Entities bucketed by layout group, in emission order.
+///
+/// Grouped entities come first (in group declaration order), ungrouped ones
+/// last with `None` as group. Inside each bucket entities are sorted by rank
+/// (unranked entities keep their model order, after the ranked ones).
+pub fn grouped_entities(model: &DomainModel) -> Vec<(Option<&LayoutGroup>, Vec<&Entity>)> {
+    let hints = match &model.layout {
+        Some(hints) => hints,
+        None => return vec![(None, model.entities.iter().collect())],
+    };
+
+    let by_id: HashMap<&str, &Entity> = model.entities.iter().map(|e| (e.id.as_str(), e)).collect();
+    let mut placed: HashSet<&str> = HashSet::new();
+    let mut buckets = Vec::new();
+
+    for group in &hints.groups {
+        let mut members: Vec<&Entity> = group
+            .entities
+            .iter()
+            .filter_map(|id| by_id.get(id.as_str()).copied())
+            .filter(|e| placed.insert(e.id.as_str()))
+            .collect();
+        sort_by_rank(&mut members, &hints.ranks);
+        if !members.is_empty() {
+            buckets.push((Some(group), members));
+        }
+    }
+
+    let mut rest: Vec<&Entity> = model
+        .entities
+        .iter()
+        .filter(|e| !placed.contains(e.id.as_str()))
+        .collect();
+    sort_by_rank(&mut rest, &hints.ranks);
+    if !rest.is_empty() {
+        buckets.push((None, rest));
+    }
+
+    buckets
+}
+
+fn sort_by_rank(entities: &mut [&Entity], ranks: &BTreeMap<String, u32>) {
+    entities.sort_by_key(|e| ranks.get(&e.id).copied().unwrap_or(u32::MAX));
+}
+
+/// Turn a group id into an identifier Mermaid accepts as namespace name.
+pub fn mermaid_identifier(id: &str) -> String {
+    let cleaned: String = id
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if cleaned.chars().next().is_none_or(|c| c.is_ascii_digit()) {
+        format!("g_{}", cleaned)
+    } else {
+        cleaned
+    }
+}
+
+/// Subset of the model to render: a focus entity with its N-hop
+/// neighbourhood, a single bounded context (layout group id or label),
+/// and/or aggregate roots only. Criteria combine.
+#[derive(Debug, Clone, Default)]
+pub struct DiagramFilter {
+    pub focus: Option<String>,
+    pub hops: usize,
+    pub context: Option<String>,
+    pub aggregate_roots_only: bool,
+}
+
+impl DiagramFilter {
+    pub fn from_params(params: &Value) -> Self {
+        Self {
+            focus: params.get("focus").and_then(|v| v.as_str()).map(String::from),
+            hops: params.get("hops").and_then(|v| v.as_u64()).unwrap_or(1) as usize,
+            context: params.get("context").and_then(|v| v.as_str()).map(String::from),
+            aggregate_roots_only: params
+                .get("aggregate_roots_only")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.focus.is_none() && self.context.is_none() && !self.aggregate_roots_only
+    }
+
+    /// Build the filtered model. Relations are kept only when both ends are
+    /// kept, and layout hints are pruned accordingly.
+    pub fn apply(&self, model: &DomainModel) -> Result<DomainModel> {
+        let mut keep: HashSet<&str> = model.entities.iter().map(|e| e.id.as_str()).collect();
+
+        if let Some(context) = &self.context {
+            let group = model
+                .layout
+                .as_ref()
+                .and_then(|l| {
+                    l.groups
+                        .iter()
+                        .find(|g| &g.id == context || g.label.as_ref() == Some(context))
+                })
+                .ok_or_else(|| anyhow::anyhow!("Unknown bounded context: {}", context))?;
+            let members: HashSet<&str> = group.entities.iter().map(|id| id.as_str()).collect();
+            keep.retain(|id| members.contains(id));
+        }
+
+        if self.aggregate_roots_only {
+            let roots = aggregate_roots(model);
+            keep.retain(|id| roots.contains(id));
+        }
+
+        if let Some(focus) = &self.focus {
+            if !model.entities.iter().any(|e| &e.id == focus) {
+                return Err(anyhow::anyhow!("Unknown focus entity: {}", focus));
+            }
+            let mut reached = HashSet::from([focus.as_str()]);
+            let mut frontier = vec![focus.as_str()];
+            for _ in 0..self.hops {
+                let mut next = Vec::new();
+                for relation in &model.relations {
+                    let (from, to) = (relation.from.entity_id.as_str(), relation.to.entity_id.as_str());
+                    if !keep.contains(from) || !keep.contains(to) {
+                        continue;
+                    }
+                    for (a, b) in [(from, to), (to, from)] {
+                        if frontier.contains(&a) && reached.insert(b) {
+                            next.push(b);
+                        }
+                    }
+                }
+                frontier = next;
+            }
+            keep.retain(|id| reached.contains(id));
+        }
+
+        let mut filtered = model.clone();
+        filtered.entities.retain(|e| keep.contains(e.id.as_str()));
+        filtered
+            .relations
+            .retain(|r| keep.contains(r.from.entity_id.as_str()) && keep.contains(r.to.entity_id.as_str()));
+        if let Some(layout) = filtered.layout.as_mut() {
+            for group in &mut layout.groups {
+                group.entities.retain(|id| keep.contains(id.as_str()));
+            }
+            layout.groups.retain(|g| !g.entities.is_empty());
+            layout.ranks.retain(|id, _| keep.contains(id.as_str()));
+        }
+        Ok(filtered)
+    }
+}
+
+/// Entity ids flagged `aggregateRoot`. When nothing is flagged, roots are
+/// inferred: an entity owned by exactly one parent (target of a relation
+/// whose source cardinality is "1") is considered part of that aggregate.
+fn aggregate_roots(model: &DomainModel) -> HashSet<&str> {
+    let flagged: HashSet<&str> = model
+        .entities
+        .iter()
+        .filter(|e| e.aggregate_root == Some(true))
+        .map(|e| e.id.as_str())
+        .collect();
+    if !flagged.is_empty() {
+        return flagged;
+    }
+
+    let owned: HashSet<&str> = model
+        .relations
+        .iter()
+        .filter(|r| r.cardinality.from == "1" && r.from.entity_id != r.to.entity_id)
+        .map(|r| r.to.entity_id.as_str())
+        .collect();
+    model
+        .entities
+        .iter()
+        .map(|e| e.id.as_str())
+        .filter(|id| !owned.contains(id))
+        .collect()
+}
+
+/// Propose layout hints for a model.
+///
+/// Heuristic: the most connected entities become group seeds and claim
+/// their unassigned neighbours; leftovers join the group of their first
+/// grouped neighbour. Ranks are the BFS distance from the seed, and extra
+/// relations between two already linked groups are suggested as hidden.
+pub fn suggest_layout(model: &DomainModel, max_group_size: usize) -> Result<Value> {
+    let max_group_size = max_group_size.max(2);
+    let ids: Vec<&str> = model.entities.iter().map(|e| e.id.as_str()).collect();
+    let known: HashSet<&str> = ids.iter().copied().collect();
+
+    let mut neighbours: HashMap<&str, Vec<&str>> = ids.iter().map(|id| (*id, Vec::new())).collect();
+    for relation in &model.relations {
+        let (from, to) = (relation.from.entity_id.as_str(), relation.to.entity_id.as_str());
+        if from == to || !known.contains(from) || !known.contains(to) {
+            continue;
+        }
+        neighbours.entry(from).or_default().push(to);
+        neighbours.entry(to).or_default().push(from);
+    }
+
+    // Seeds: degree >= 2, most connected first (model order breaks ties).
+    let mut seeds: Vec<&str> = ids
+        .iter()
+        .copied()
+        .filter(|id| neighbours[id].len() >= 2)
+        .collect();
+    seeds.sort_by_key(|id| std::cmp::Reverse(neighbours[id].len()));
+
+    let mut group_of: HashMap<&str, usize> = HashMap::new();
+    let mut groups: Vec<Vec<&str>> = Vec::new();
+    let mut rationale = Vec::new();
+
+    for seed in seeds {
+        if group_of.contains_key(seed) {
+            continue;
+        }
+        let index = groups.len();
+        let mut members = vec![seed];
+        group_of.insert(seed, index);
+        for neighbour in &neighbours[seed] {
+            if members.len() >= max_group_size {
+                break;
+            }
+            if !group_of.contains_key(neighbour) {
+                group_of.insert(neighbour, index);
+                members.push(neighbour);
+            }
+        }
+        rationale.push(format!(
+            "'{}' is connected to {} entities and anchors group {}",
+            seed,
+            neighbours[seed].len(),
+            index + 1
+        ));
+        groups.push(members);
+    }
+
+    // Attach leftovers to a neighbour's group when there is room, otherwise
+    // keep them together in a trailing group.
+    let mut leftovers = Vec::new();
+    for id in &ids {
+        if group_of.contains_key(id) {
+            continue;
+        }
+        let target = neighbours[id]
+            .iter()
+            .filter_map(|n| group_of.get(n).copied())
+            .find(|g| groups[*g].len() < max_group_size);
+        match target {
+            Some(g) => {
+                group_of.insert(id, g);
+                groups[g].push(id);
+            }
+            None => leftovers.push(*id),
+        }
+    }
+    if !leftovers.is_empty() {
+        rationale.push(format!("{} loosely connected entities grouped together", leftovers.len()));
+        groups.push(leftovers);
+    }
+
+    // Ranks: BFS depth from the first member of each group, within the group.
+    let mut ranks = BTreeMap::new();
+    for members in &groups {
+        let in_group: HashSet<&str> = members.iter().copied().collect();
+        let mut queue = VecDeque::from([(members[0], 0u32)]);
+        let mut seen = HashSet::from([members[0]]);
+        while let Some((id, depth)) = queue.pop_front() {
+            ranks.insert(id.to_string(), depth);
+            for n in &neighbours[id] {
+                if in_group.contains(n) && seen.insert(n) {
+                    queue.push_back((n, depth + 1));
+                }
+            }
+        }
+        for id in members {
+            ranks.entry(id.to_string()).or_insert(0);
+        }
+    }
+
+    // Keep one relation per pair of groups, suggest hiding the others.
+    let mut linked_pairs = HashSet::new();
+    let mut hidden_relations = Vec::new();
+    for relation in &model.relations {
+        let (Some(a), Some(b)) = (
+            group_of.get(relation.from.entity_id.as_str()),
+            group_of.get(relation.to.entity_id.as_str()),
+        ) else {
+            continue;
+        };
+        if a == b {
+            continue;
+        }
+        let pair = (*a.min(b), *a.max(b));
+        if !linked_pairs.insert(pair) {
+            hidden_relations.push(relation.id.clone());
+        }
+    }
+    if !hidden_relations.is_empty() {
+        rationale.push(format!(
+            "{} redundant cross-group relations can be hidden",
+            hidden_relations.len()
+        ));
+    }
+
+    let hints = LayoutHints {
+        groups: groups
+            .iter()
+            .enumerate()
+            .map(|(i, members)| LayoutGroup {
+                id: format!("group_{}", i + 1),
+                label: Some(
+                    model
+                        .entities
+                        .iter()
+                        .find(|e| e.id == members[0])
+                        .map(|e| e.name.clone())
+                        .unwrap_or_else(|| members[0].to_string()),
+                ),
+                entities: members.iter().map(|id| id.to_string()).collect(),
+            })
+            .collect(),
+        direction: Some(if model.entities.len() > 6 { "LR" } else { "TB" }.to_string()),
+        ranks,
+        hidden_relations,
+    };
+
+    Ok(json!({
+        "layout": hints,
+        "rationale": rationale
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Attribute, Cardinality, Relation, RelationEnd};
+
+    fn entity(id: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            attributes: vec![Attribute {
+                name: "id".to_string(),
+                attr_type: "uuid".to_string(),
+                description: None,
+                required: Some(true),
+                unique: Some(true),
+                default_value: None,
+                validation: None,
+            }],
+            primary_key: Some(vec!["id".to_string()]),
+            aggregate_root: None,
+            unique_constraints: None,
+        }
+    }
+
+    fn relation(id: &str, from: &str, to: &str) -> Relation {
+        Relation {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            required: None,
+            cascade_delete: None,
+            from: RelationEnd { entity_id: from.to_string(), attribute: None, label: None },
+            to: RelationEnd { entity_id: to.to_string(), attribute: None, label: None },
+            cardinality: Cardinality { from: "1".to_string(), to: "0..n".to_string() },
+        }
+    }
+
+    fn sample_model() -> DomainModel {
+        DomainModel {
+            entities: ["Order", "OrderLine", "Customer", "Product", "Invoice"]
+                .iter()
+                .map(|id| entity(id))
+                .collect(),
+            relations: vec![
+                relation("places", "Customer", "Order"),
+                relation("contains", "Order", "OrderLine"),
+                relation("refers_to", "OrderLine", "Product"),
+                relation("billed_by", "Order", "Invoice"),
+                relation("billed_to", "Customer", "Invoice"),
+            ],
+            invariants: vec![],
+            layout: None,
+        }
+    }
+
+    #[test]
+    fn grouped_entities_follow_groups_and_ranks() {
+        let mut model = sample_model();
+        model.layout = Some(LayoutHints {
+            groups: vec![LayoutGroup {
+                id: "sales".to_string(),
+                label: Some("Sales".to_string()),
+                entities: vec!["OrderLine".to_string(), "Order".to_string()],
+            }],
+            direction: Some("lr".to_string()),
+            ranks: BTreeMap::from([("Order".to_string(), 0), ("OrderLine".to_string(), 1)]),
+            hidden_relations: vec![],
+        });
+
+        let buckets = grouped_entities(&model);
+        assert_eq!(buckets.len(), 2);
+        let first: Vec<&str> = buckets[0].1.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(first, vec!["Order", "OrderLine"]);
+        assert!(buckets[1].0.is_none());
+        assert_eq!(buckets[1].1.len(), 3);
+        assert_eq!(model.layout.as_ref().unwrap().mermaid_direction(), Some("LR"));
+    }
+
+    #[test]
+    fn filter_focus_and_context() {
+        let mut model = sample_model();
+        model.layout = Some(LayoutHints {
+            groups: vec![LayoutGroup {
+                id: "billing".to_string(),
+                label: Some("Billing".to_string()),
+                entities: vec!["Invoice".to_string(), "Customer".to_string()],
+            }],
+            ..Default::default()
+        });
+
+        let focus = DiagramFilter { focus: Some("OrderLine".to_string()), hops: 1, ..Default::default() };
+        let subset = focus.apply(&model).unwrap();
+        let mut ids: Vec<&str> = subset.entities.iter().map(|e| e.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["Order", "OrderLine", "Product"]);
+        assert_eq!(subset.relations.len(), 2);
+        assert!(subset.layout.unwrap().groups.is_empty());
+
+        let context = DiagramFilter { context: Some("Billing".to_string()), ..Default::default() };
+        let subset = context.apply(&model).unwrap();
+        assert_eq!(subset.entities.len(), 2);
+        assert_eq!(subset.relations.len(), 1);
+
+        let unknown = DiagramFilter { focus: Some("Nope".to_string()), ..Default::default() };
+        assert!(unknown.apply(&model).is_err());
+    }
+
+    #[test]
+    fn filter_aggregate_roots() {
+        let mut model = sample_model();
+        // Many order lines refer to one product: Product is not owned.
+        model.relations[2].cardinality = Cardinality { from: "0..n".to_string(), to: "1".to_string() };
+        let roots = DiagramFilter { aggregate_roots_only: true, ..Default::default() };
+        let subset = roots.apply(&model).unwrap();
+        // Order, OrderLine, Invoice are owned through "1" relations.
+        let ids: Vec<&str> = subset.entities.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["Customer", "Product"]);
+
+        let mut flagged = sample_model();
+        flagged.entities[0].aggregate_root = Some(true);
+        let subset = roots.apply(&flagged).unwrap();
+        assert_eq!(subset.entities.len(), 1);
+        assert_eq!(subset.entities[0].id, "Order");
+    }
+
+    #[test]
+    fn suggest_layout_covers_every_entity_once() {
+        let model = sample_model();
+        let result = suggest_layout(&model, 3).unwrap();
+        let hints: LayoutHints = serde_json::from_value(result["layout"].clone()).unwrap();
+
+        let mut all: Vec<String> = hints.groups.iter().flat_map(|g| g.entities.clone()).collect();
+        all.sort();
+        assert_eq!(all, vec!["Customer", "Invoice", "Order", "OrderLine", "Product"]);
+        assert!(hints.groups.iter().all(|g| g.entities.len() <= 3));
+        // Order is the most connected entity and anchors the first group.
+        assert_eq!(hints.groups[0].entities[0], "Order");
+        assert_eq!(hints.ranks.get("Order"), Some(&0));
+    }
+}
@@ -0,0 +1,107 @@
+//! Workspace LLM policy, the same file the desktop app enforces.
+//!
+//! Read from `LLM_POLICY_FILE`, or `llm-policy.json` in the working directory, and
+//! checked before the server or the CLI sends anything to an LLM.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Looked up in the working directory, next to `.env`, when LLM_POLICY_FILE is unset
+const DEFAULT_POLICY_FILE: &str = "llm-policy.json";
+
+/// Restrictions on the LLM providers of a workspace; `{}` allows everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LlmPolicy {
+    /// LLM_PROVIDER values that may be used; empty = all
+    pub allowed_providers: Vec<String>,
+    /// Regex the endpoint of an external provider must match
+    pub allowed_endpoints: Option<String>,
+    /// Only an Ollama server on this machine
+    pub offline_only: bool,
+}
+
+fn is_loopback(url: &str) -> bool {
+    let host = url.split("://").nth(1).unwrap_or(url).split(['/', '?']).next().unwrap_or("");
+    let host = host.rsplit_once('@').map(|(_, host)| host).unwrap_or(host);
+    let host = if host.starts_with('[') {
+        host.split(']').next().unwrap_or("").trim_start_matches('[')
+    } else {
+        host.split(':').next().unwrap_or("")
+    };
+    host.eq_ignore_ascii_case("localhost") || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+impl LlmPolicy {
+    /// Check a provider (LLM_PROVIDER value) and the URL it would be called at: the
+    /// Ollama base URL, or the endpoint of an external provider
+    pub fn check(&self, provider: &str, endpoint: &str) -> Result<()> {
+        let provider = provider.to_lowercase();
+        if !self.allowed_providers.is_empty() && !self.allowed_providers.iter().any(|allowed| allowed.eq_ignore_ascii_case(&provider)) {
+            anyhow::bail!("provider '{}' is not allowed (allowed: {})", provider, self.allowed_providers.join(", "));
+        }
+        if provider == "ollama" {
+            if self.offline_only && !is_loopback(endpoint) {
+                anyhow::bail!("offline only: Ollama must run on this machine, not at {}", endpoint);
+            }
+            return Ok(());
+        }
+        if self.offline_only {
+            anyhow::bail!("offline only: external provider '{}' is not allowed", provider);
+        }
+        if let Some(pattern) = &self.allowed_endpoints {
+            let regex = Regex::new(pattern).with_context(|| format!("invalid allowed_endpoints regex '{}'", pattern))?;
+            if !regex.is_match(endpoint) {
+                anyhow::bail!("endpoint {} does not match allowed_endpoints '{}'", endpoint, pattern);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// LLM_POLICY_FILE, else `llm-policy.json` in the working directory if present
+fn policy_path() -> Option<PathBuf> {
+    match std::env::var("LLM_POLICY_FILE") {
+        Ok(path) if !path.trim().is_empty() => Some(PathBuf::from(path)),
+        _ => Some(PathBuf::from(DEFAULT_POLICY_FILE)).filter(|path| path.exists()),
+    }
+}
+
+fn load_from(path: &Path) -> Result<LlmPolicy> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read LLM policy {:?}", path))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid LLM policy {:?}", path))
+}
+
+/// Check a provider against the workspace policy; a policy file that cannot be read is
+/// an error rather than no restriction
+pub fn enforce(provider: &str, endpoint: &str) -> Result<()> {
+    let Some(path) = policy_path() else { return Ok(()) };
+    load_from(&path)?
+        .check(provider, endpoint)
+        .with_context(|| format!("LLM provider refused by policy {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_checks() {
+        let policy: LlmPolicy = serde_json::from_str(r#"{"allowed_providers": ["ollama", "openai"], "allowed_endpoints": "^https://llm\\.example\\.com/"}"#).unwrap();
+        assert!(policy.check("ollama", "http://gpu-box:11434").is_ok());
+        assert!(policy.check("OpenAI", "https://llm.example.com/v1/chat/completions").is_ok());
+        let refused = policy.check("openai", "https://api.openai.com/v1/chat/completions").unwrap_err();
+        assert!(refused.to_string().contains("does not match allowed_endpoints"));
+        assert!(policy.check("anthropic", "https://llm.example.com/v1").unwrap_err().to_string().contains("not allowed"));
+
+        let offline = LlmPolicy { offline_only: true, ..Default::default() };
+        assert!(offline.check("ollama", "http://localhost:11434").is_ok());
+        assert!(offline.check("ollama", "http://[::1]:11434").is_ok());
+        assert!(offline.check("ollama", "http://gpu-box:11434").is_err());
+        assert!(offline.check("openai", "https://llm.example.com/v1").is_err());
+        assert!(LlmPolicy::default().check("external", "https://anything").is_ok());
+    }
+}
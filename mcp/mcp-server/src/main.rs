@@ -4,12 +4,30 @@ use serde_json::{json, Value};
 use std::collections::HashMap;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
 
+mod custom_rules;
+mod diff;
+mod fixes;
+mod foreign_keys;
+mod glossary;
+mod grounding;
+mod layout;
+mod llm_policy;
+mod markdown_import;
+mod normalize;
+mod relation_checks;
+mod sql;
+mod traceability;
+
+use layout::LayoutHints;
+
 // Domain Model Types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct DomainModel {
     entities: Vec<Entity>,
     relations: Vec<Relation>,
     invariants: Vec<Invariant>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layout: Option<LayoutHints>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +40,19 @@ struct Entity {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "primaryKey")]
     primary_key: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "aggregateRoot")]
+    aggregate_root: Option<bool>,
+    /// Attribute sets unique together, beyond the primary key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "uniqueConstraints")]
+    unique_constraints: Option<Vec<UniqueConstraint>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UniqueConstraint {
+    name: String,
+    attributes: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +66,32 @@ struct Attribute {
     required: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     unique: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "defaultValue")]
+    default_value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validation: Option<AttributeValidation>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AttributeValidation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_length: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_length: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "enum")]
+    enum_values: Option<Vec<Value>>,
+    /// Free-form rule, documented but not enforced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    custom: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,12 +103,22 @@ struct Relation {
     from: RelationEnd,
     to: RelationEnd,
     cardinality: Cardinality,
+    /// The child side cannot exist without its parent (NOT NULL foreign key)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required: Option<bool>,
+    /// Deleting the parent deletes its children (ON DELETE CASCADE)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "cascadeDelete")]
+    cascade_delete: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct RelationEnd {
     #[serde(rename = "entityId")]
     entity_id: String,
+    /// Key or foreign key attribute of the entity carrying the relation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attribute: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     label: Option<String>,
 }
@@ -152,13 +219,6 @@ fn normalize_terms(_input_lang: &str, transcript: &str) -> Result<Value> {
 }
 
 async fn normalize_terms_with_llm(input_lang: &str, transcript: &str) -> Result<Value> {
-    use std::env;
-    
-    // Load .env if available
-    let _ = dotenvy::dotenv();
-    
-    let provider = env::var("LLM_PROVIDER").unwrap_or_else(|_| "ollama".to_string());
-    
     // System prompt in the specified language
     let system_prompt = match input_lang {
         "en" => r#"
@@ -195,6 +255,23 @@ RÈGLES STRICTES:
 "#,
     };
     
+    let llm_response_json = call_llm_json(system_prompt, transcript).await?;
+    
+    // Validate against JSON Schema
+    validate_domain_model(&llm_response_json)?;
+    
+    Ok(llm_response_json)
+}
+
+/// Send a system/user prompt pair to the configured LLM provider in JSON mode
+async fn call_llm_json(system_prompt: &str, user_prompt: &str) -> Result<Value> {
+    use std::env;
+
+    // Load .env if available
+    let _ = dotenvy::dotenv();
+    
+    let provider = env::var("LLM_PROVIDER").unwrap_or_else(|_| "ollama".to_string());
+
     let client = reqwest::Client::new();
     let llm_response_json: Value;
     
@@ -202,13 +279,14 @@ RÈGLES STRICTES:
         "ollama" => {
             let base_url = env::var("OLLAMA_BASE_URL")
                 .unwrap_or_else(|_| "http://localhost:11434".to_string());
+            llm_policy::enforce(&provider, &base_url)?;
             let model = env::var("OLLAMA_MODEL")
                 .unwrap_or_else(|_| "llama2".to_string());
             
             let url = format!("{}/api/generate", base_url);
             let request_body = json!({
                 "model": model,
-                "prompt": format!("{}\n\nUser: {}", system_prompt, transcript),
+                "prompt": format!("{}\n\nUser: {}", system_prompt, user_prompt),
                 "stream": false,
                 "format": "json"
             });
@@ -238,11 +316,12 @@ RÈGLES STRICTES:
                 .context("LLM_API_KEY not set for external provider")?;
             let endpoint = env::var("LLM_ENDPOINT")
                 .context("LLM_ENDPOINT not set")?;
+            llm_policy::enforce(&provider, &endpoint)?;
             
             let request_body = json!({
                 "messages": [
                     {"role": "system", "content": system_prompt},
-                    {"role": "user", "content": transcript}
+                    {"role": "user", "content": user_prompt}
                 ],
                 "temperature": 0.7,
                 "response_format": {"type": "json_object"}
@@ -276,10 +355,7 @@ RÈGLES STRICTES:
                 .context("Failed to parse LLM output as JSON")?;
         }
     }
-    
-    // Validate against JSON Schema
-    validate_domain_model(&llm_response_json)?;
-    
+
     Ok(llm_response_json)
 }
 
@@ -385,6 +461,35 @@ fn validate_custom_rules(model: &Value) -> Result<()> {
                 }
             }
         }
+        
+        // Rule 5: Les contraintes d'unicité portent sur des attributs existants
+        let empty_constraints = vec![];
+        let constraints = entity.get("uniqueConstraints")
+            .and_then(|c| c.as_array())
+            .unwrap_or(&empty_constraints);
+        for constraint in constraints {
+            let constraint_name = constraint.get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("<unnamed>");
+            let names: Vec<&str> = constraint.get("attributes")
+                .and_then(|a| a.as_array())
+                .map(|a| a.iter().filter_map(|n| n.as_str()).collect())
+                .unwrap_or_default();
+            if names.is_empty() {
+                errors.push(format!(
+                    "Entity '{}' unique constraint '{}' has no attributes",
+                    entity_id, constraint_name
+                ));
+            }
+            for name in names {
+                if !attr_names.contains(name) {
+                    errors.push(format!(
+                        "Entity '{}' unique constraint '{}' references non-existent attribute '{}'",
+                        entity_id, constraint_name, name
+                    ));
+                }
+            }
+        }
     }
     
     // Rule 3: Relations pointent vers des entités existantes
@@ -425,6 +530,13 @@ fn validate_custom_rules(model: &Value) -> Result<()> {
         }
     }
     
+    // Rule 4: Les attributs d'extrémité de relation existent et ont des types compatibles
+    for finding in foreign_keys::check(model) {
+        if finding.severity == custom_rules::Severity::Error {
+            errors.push(finding.message);
+        }
+    }
+    
     // If there are validation errors, bail with all of them
     if !errors.is_empty() {
         anyhow::bail!(
@@ -672,6 +784,35 @@ Un système de bibliothèque simple:
         validate_domain_model(&valid_with_relations)?;
         println!("   ✅ Valid model with relations accepted");
         
+        // Test 6: Unique constraint on a non-existent attribute
+        println!("\n📋 Test 6: Unique constraint referencing a missing attribute");
+        let invalid_constraint_model = json!({
+            "entities": [
+                {
+                    "id": "Booking",
+                    "name": "Booking",
+                    "attributes": [
+                        {"name": "id", "type": "uuid", "required": true},
+                        {"name": "roomId", "type": "uuid", "required": true}
+                    ],
+                    "primaryKey": ["id"],
+                    "uniqueConstraints": [{"name": "uq_room_slot", "attributes": ["roomId", "slot"]}]
+                }
+            ],
+            "relations": [],
+            "invariants": []
+        });
+        
+        match validate_domain_model(&invalid_constraint_model) {
+            Err(e) => {
+                let error_msg = e.to_string();
+                println!("   ✅ Correctly rejected: {}", error_msg);
+                assert!(error_msg.contains("unique constraint 'uq_room_slot' references non-existent attribute 'slot'"),
+                    "Should name the constraint and the missing attribute");
+            }
+            Ok(_) => panic!("Should fail validation with an unknown unique constraint attribute"),
+        }
+        
         println!("\n✅ All custom validation tests passed!");
         
         Ok(())
@@ -688,6 +829,8 @@ Un système de bibliothèque simple:
                     description: None,
                     attributes: vec![],
                     primary_key: None,
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
                 Entity {
                     id: "Profile".to_string(),
@@ -695,18 +838,24 @@ Un système de bibliothèque simple:
                     description: None,
                     attributes: vec![],
                     primary_key: None,
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
             ],
             relations: vec![Relation {
                 id: "user_profile".to_string(),
                 name: "has_profile".to_string(),
                 description: None,
+                required: None,
+                cascade_delete: None,
                 from: RelationEnd {
                     entity_id: "User".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 to: RelationEnd {
                     entity_id: "Profile".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 cardinality: Cardinality {
@@ -715,6 +864,7 @@ Un système de bibliothèque simple:
                 },
             }],
             invariants: vec![],
+            layout: None,
         };
 
         let result = emit_mermaid(&model_1_1, Some("er")).unwrap();
@@ -731,6 +881,8 @@ Un système de bibliothèque simple:
                     description: None,
                     attributes: vec![],
                     primary_key: None,
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
                 Entity {
                     id: "Article".to_string(),
@@ -738,18 +890,24 @@ Un système de bibliothèque simple:
                     description: None,
                     attributes: vec![],
                     primary_key: None,
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
             ],
             relations: vec![Relation {
                 id: "author_articles".to_string(),
                 name: "writes".to_string(),
                 description: None,
+                required: None,
+                cascade_delete: None,
                 from: RelationEnd {
                     entity_id: "Author".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 to: RelationEnd {
                     entity_id: "Article".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 cardinality: Cardinality {
@@ -758,6 +916,7 @@ Un système de bibliothèque simple:
                 },
             }],
             invariants: vec![],
+            layout: None,
         };
 
         let result = emit_mermaid(&model_1_n, Some("er")).unwrap();
@@ -774,6 +933,8 @@ Un système de bibliothèque simple:
                     description: None,
                     attributes: vec![],
                     primary_key: None,
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
                 Entity {
                     id: "Customer".to_string(),
@@ -781,18 +942,24 @@ Un système de bibliothèque simple:
                     description: None,
                     attributes: vec![],
                     primary_key: None,
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
             ],
             relations: vec![Relation {
                 id: "order_customer".to_string(),
                 name: "belongs_to".to_string(),
                 description: None,
+                required: None,
+                cascade_delete: None,
                 from: RelationEnd {
                     entity_id: "Order".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 to: RelationEnd {
                     entity_id: "Customer".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 cardinality: Cardinality {
@@ -801,6 +968,7 @@ Un système de bibliothèque simple:
                 },
             }],
             invariants: vec![],
+            layout: None,
         };
 
         let result = emit_mermaid(&model_n_1, Some("er")).unwrap();
@@ -817,6 +985,8 @@ Un système de bibliothèque simple:
                     description: None,
                     attributes: vec![],
                     primary_key: None,
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
                 Entity {
                     id: "Course".to_string(),
@@ -824,18 +994,24 @@ Un système de bibliothèque simple:
                     description: None,
                     attributes: vec![],
                     primary_key: None,
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
             ],
             relations: vec![Relation {
                 id: "student_courses".to_string(),
                 name: "enrolls_in".to_string(),
                 description: None,
+                required: None,
+                cascade_delete: None,
                 from: RelationEnd {
                     entity_id: "Student".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 to: RelationEnd {
                     entity_id: "Course".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 cardinality: Cardinality {
@@ -844,6 +1020,7 @@ Un système de bibliothèque simple:
                 },
             }],
             invariants: vec![],
+            layout: None,
         };
 
         let result = emit_mermaid(&model_n_n, Some("er")).unwrap();
@@ -851,6 +1028,39 @@ Un système de bibliothèque simple:
         println!("N-N relation:\n{}", mermaid);
         assert!(mermaid.contains("}o--o{"), "Should contain }}o--o{{ for N-N relation");
     }
+
+    #[test]
+    fn emit_mermaid_honors_layout() {
+        let model: DomainModel = serde_json::from_value(json!({
+            "entities": [
+                {"id": "Order", "name": "Order", "attributes": [{"name": "id", "type": "uuid", "required": true}]},
+                {"id": "Customer", "name": "Customer", "attributes": [{"name": "id", "type": "uuid", "required": true}]}
+            ],
+            "relations": [
+                {"id": "places", "name": "places", "from": {"entityId": "Customer"}, "to": {"entityId": "Order"}, "cardinality": {"from": "1", "to": "0..n"}},
+                {"id": "audit", "name": "audit", "from": {"entityId": "Order"}, "to": {"entityId": "Customer"}, "cardinality": {"from": "0..n", "to": "1"}}
+            ],
+            "invariants": [],
+            "layout": {
+                "groups": [{"id": "sales-context", "label": "Sales", "entities": ["Order"]}],
+                "direction": "LR",
+                "hiddenRelations": ["audit"]
+            }
+        })).unwrap();
+
+        let class = emit_mermaid(&model, Some("class")).unwrap();
+        let class = class["mermaid"].as_str().unwrap();
+        assert!(class.contains("direction LR"));
+        assert!(class.contains("namespace sales_context {"));
+        assert!(class.contains("Customer --> Order : places"));
+        assert!(!class.contains(": audit"), "Hidden relation should not be emitted");
+
+        let er = emit_mermaid(&model, Some("er")).unwrap();
+        let er = er["mermaid"].as_str().unwrap();
+        assert!(er.contains("%% Sales"));
+        assert!(er.find("Order {").unwrap() < er.find("Customer {").unwrap());
+        assert!(!er.contains("\"audit\""));
+    }
     
     #[test]
     fn emit_markdown_sections() {
@@ -868,6 +1078,8 @@ Un système de bibliothèque simple:
                             description: Some("Identifiant unique".to_string()),
                             required: Some(true),
                             unique: Some(true),
+                            default_value: None,
+                            validation: None,
                         },
                         Attribute {
                             name: "email".to_string(),
@@ -875,6 +1087,8 @@ Un système de bibliothèque simple:
                             description: Some("Adresse email".to_string()),
                             required: Some(true),
                             unique: Some(true),
+                            default_value: None,
+                            validation: None,
                         },
                         Attribute {
                             name: "name".to_string(),
@@ -882,9 +1096,16 @@ Un système de bibliothèque simple:
                             description: None,
                             required: Some(true),
                             unique: Some(false),
+                            default_value: None,
+                            validation: None,
                         },
                     ],
                     primary_key: Some(vec!["id".to_string()]),
+                    aggregate_root: None,
+                    unique_constraints: Some(vec![UniqueConstraint {
+                        name: "uq_user_identity".to_string(),
+                        attributes: vec!["email".to_string(), "name".to_string()],
+                    }]),
                 },
                 Entity {
                     id: "Order".to_string(),
@@ -897,6 +1118,8 @@ Un système de bibliothèque simple:
                             description: None,
                             required: Some(true),
                             unique: Some(true),
+                            default_value: None,
+                            validation: None,
                         },
                         Attribute {
                             name: "total".to_string(),
@@ -904,21 +1127,29 @@ Un système de bibliothèque simple:
                             description: None,
                             required: Some(true),
                             unique: Some(false),
+                            default_value: None,
+                            validation: None,
                         },
                     ],
                     primary_key: Some(vec!["id".to_string()]),
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
             ],
             relations: vec![Relation {
                 id: "user_orders".to_string(),
                 name: "places".to_string(),
                 description: Some("Un utilisateur peut passer plusieurs commandes".to_string()),
+                required: Some(true),
+                cascade_delete: Some(true),
                 from: RelationEnd {
                     entity_id: "User".to_string(),
+                    attribute: None,
                     label: Some("customer".to_string()),
                 },
                 to: RelationEnd {
                     entity_id: "Order".to_string(),
+                    attribute: None,
                     label: Some("orders".to_string()),
                 },
                 cardinality: Cardinality {
@@ -944,6 +1175,7 @@ Un système de bibliothèque simple:
                     severity: Some("error".to_string()),
                 },
             ],
+            layout: None,
         };
 
         let result = emit_markdown(&model, Some("business")).unwrap();
@@ -970,12 +1202,17 @@ Un système de bibliothèque simple:
         
         // Verify detailed attribute tables
         assert!(markdown.contains("### User"), "Should have User detail section");
-        assert!(markdown.contains("| Attribut | Type | Requis | Unique | Description |"), "Should have attribute table");
+        assert!(markdown.contains("| Attribut | Type | Requis | Unique | Défaut | Contraintes | Description |"), "Should have attribute table");
         assert!(markdown.contains("| email | `email` |"), "Should list email attribute");
+        assert!(markdown.contains("**Unicité combinée :**\n\n- `uq_user_identity` : email, name\n"), "Should list unique constraints");
         
         // Verify relations table
-        assert!(markdown.contains("| Relation | De | Vers | Cardinalité | Description |"), "Should have relations table");
+        assert!(markdown.contains("| Relation | De | Vers | Cardinalité | Contraintes | Description |"), "Should have relations table");
         assert!(markdown.contains("| **places** | User | Order | 1..0..n |"), "Should list relation");
+        assert!(
+            markdown.contains("Obligatoire : un Order n'existe pas sans son User ; Suppression en cascade : supprimer un User supprime ses Order"),
+            "Should spell out required and cascade delete"
+        );
         
         // Verify business rules as numbered list
         assert!(markdown.contains("1. **Email Uniqueness**"), "Should have numbered business rule");
@@ -1049,6 +1286,8 @@ mod orchestration {
                                     description: None,
                                     required: Some(required),
                                     unique: Some(unique),
+                                    default_value: None,
+                                    validation: None,
                                 });
                             }
                         }
@@ -1072,6 +1311,8 @@ mod orchestration {
                         description: None,
                         attributes,
                         primary_key,
+                        aggregate_root: None,
+                        unique_constraints: None,
                     });
                 }
             }
@@ -1110,12 +1351,16 @@ mod orchestration {
                                 id: format!("{}_{}", from_entity.to_lowercase(), to_entity.to_lowercase()),
                                 name,
                                 description: None,
+                                required: None,
+                                cascade_delete: None,
                                 from: RelationEnd {
                                     entity_id: from_entity,
+                                    attribute: None,
                                     label: None,
                                 },
                                 to: RelationEnd {
                                     entity_id: to_entity,
+                                    attribute: None,
                                     label: None,
                                 },
                                 cardinality: Cardinality {
@@ -1181,6 +1426,7 @@ mod orchestration {
             entities,
             relations,
             invariants,
+            layout: None,
         })
     }
     
@@ -1297,6 +1543,8 @@ mod orchestration {
                             description: Some("Identifiant unique".to_string()),
                             required: Some(true),
                             unique: Some(true),
+                            default_value: None,
+                            validation: None,
                         },
                         Attribute {
                             name: "email".to_string(),
@@ -1304,9 +1552,13 @@ mod orchestration {
                             description: Some("Adresse email".to_string()),
                             required: Some(true),
                             unique: Some(false),
+                            default_value: None,
+                            validation: None,
                         },
                     ],
                     primary_key: Some(vec!["id".to_string()]),
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
                 Entity {
                     id: "Order".to_string(),
@@ -1319,21 +1571,29 @@ mod orchestration {
                             description: None,
                             required: Some(true),
                             unique: Some(true),
+                            default_value: None,
+                            validation: None,
                         },
                     ],
                     primary_key: Some(vec!["id".to_string()]),
+                    aggregate_root: None,
+                    unique_constraints: None,
                 },
             ],
             relations: vec![Relation {
                 id: "user_orders".to_string(),
                 name: "places".to_string(),
                 description: Some("Relation utilisateur-commandes".to_string()),
+                required: None,
+                cascade_delete: None,
                 from: RelationEnd {
                     entity_id: "User".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 to: RelationEnd {
                     entity_id: "Order".to_string(),
+                    attribute: None,
                     label: None,
                 },
                 cardinality: Cardinality {
@@ -1349,6 +1609,7 @@ mod orchestration {
                 expression: "User.email UNIQUE".to_string(),
                 severity: Some("error".to_string()),
             }],
+            layout: None,
         };
         
         println!("📊 Original model:");
@@ -1410,6 +1671,69 @@ mod orchestration {
     }
 }
 
+/// Validation constraints of an attribute, worded as in the markdown tables
+/// (parsed back by `markdown_import`)
+fn attribute_constraints(attr: &Attribute) -> Vec<String> {
+    let Some(validation) = &attr.validation else {
+        return Vec::new();
+    };
+    let mut constraints = Vec::new();
+    if let Some(min) = validation.min {
+        constraints.push(format!("min {}", min));
+    }
+    if let Some(max) = validation.max {
+        constraints.push(format!("max {}", max));
+    }
+    if let Some(min_length) = validation.min_length {
+        constraints.push(format!("longueur min {}", min_length));
+    }
+    if let Some(max_length) = validation.max_length {
+        constraints.push(format!("longueur max {}", max_length));
+    }
+    if let Some(pattern) = &validation.pattern {
+        constraints.push(format!("format `{}`", pattern));
+    }
+    if let Some(values) = &validation.enum_values {
+        constraints.push(format!("valeurs `{}`", Value::Array(values.clone())));
+    }
+    if let Some(custom) = &validation.custom {
+        constraints.push(format!("règle : {}", custom));
+    }
+    constraints
+}
+
+/// Default value and constraints of an attribute as a Mermaid comment (no double quotes allowed)
+fn mermaid_annotation(attr: &Attribute) -> String {
+    let mut parts: Vec<String> = attr.default_value.iter().map(|v| format!("default {}", v)).collect();
+    parts.extend(attribute_constraints(attr).iter().map(|c| c.replace('`', "")));
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" \"{}\"", parts.join(", ").replace('"', "'"))
+    }
+}
+
+/// Explicit wording of `required` and `cascadeDelete`, from the child's point of view
+fn relation_constraints(relation: &Relation) -> String {
+    let value = serde_json::to_value(relation).unwrap_or_default();
+    let Some((parent_end, _)) = foreign_keys::parent_child(&value) else {
+        return String::new();
+    };
+    let (parent, child) = if parent_end == "from" {
+        (&relation.from.entity_id, &relation.to.entity_id)
+    } else {
+        (&relation.to.entity_id, &relation.from.entity_id)
+    };
+    let mut constraints = Vec::new();
+    if relation.required == Some(true) {
+        constraints.push(format!("Obligatoire : un {} n'existe pas sans son {}", child, parent));
+    }
+    if relation.cascade_delete == Some(true) {
+        constraints.push(format!("Suppression en cascade : supprimer un {} supprime ses {}", parent, child));
+    }
+    constraints.join(" ; ")
+}
+
 fn emit_markdown(model: &DomainModel, audience: Option<&str>) -> Result<Value> {
     let mut markdown = String::new();
     use chrono::Utc;
@@ -1458,20 +1782,30 @@ fn emit_markdown(model: &DomainModel, audience: Option<&str>) -> Result<Value> {
             if !entity.attributes.is_empty() {
                 markdown.push_str(&format!("### {}\n\n", entity.name));
                 
-                markdown.push_str("| Attribut | Type | Requis | Unique | Description |\n");
-                markdown.push_str("|----------|------|--------|--------|-------------|\n");
+                markdown.push_str("| Attribut | Type | Requis | Unique | Défaut | Contraintes | Description |\n");
+                markdown.push_str("|----------|------|--------|--------|--------|-------------|-------------|\n");
                 
                 for attr in &entity.attributes {
                     markdown.push_str(&format!(
-                        "| {} | `{}` | {} | {} | {} |\n",
+                        "| {} | `{}` | {} | {} | {} | {} | {} |\n",
                         attr.name,
                         attr.attr_type,
                         if attr.required.unwrap_or(false) { "✓" } else { "" },
                         if attr.unique.unwrap_or(false) { "✓" } else { "" },
+                        attr.default_value.as_ref().map(|v| format!("`{}`", v)).unwrap_or_default(),
+                        attribute_constraints(attr).join(" ; ").replace('|', "\\|"),
                         attr.description.as_deref().unwrap_or("")
                     ));
                 }
                 markdown.push_str("\n");
+                
+                if let Some(constraints) = entity.unique_constraints.as_ref().filter(|c| !c.is_empty()) {
+                    markdown.push_str("**Unicité combinée :**\n\n");
+                    for constraint in constraints {
+                        markdown.push_str(&format!("- `{}` : {}\n", constraint.name, constraint.attributes.join(", ")));
+                    }
+                    markdown.push('\n');
+                }
             }
         }
     }
@@ -1482,18 +1816,19 @@ fn emit_markdown(model: &DomainModel, audience: Option<&str>) -> Result<Value> {
     if model.relations.is_empty() {
         markdown.push_str("*Aucune relation définie.*\n\n");
     } else {
-        markdown.push_str("| Relation | De | Vers | Cardinalité | Description |\n");
-        markdown.push_str("|----------|----|----|-------------|-------------|\n");
+        markdown.push_str("| Relation | De | Vers | Cardinalité | Contraintes | Description |\n");
+        markdown.push_str("|----------|----|----|-------------|-------------|-------------|\n");
         
         for relation in &model.relations {
             let desc = relation.description.as_deref().unwrap_or("");
             markdown.push_str(&format!(
-                "| **{}** | {} | {} | {}..{} | {} |\n",
+                "| **{}** | {} | {} | {}..{} | {} | {} |\n",
                 relation.name,
                 relation.from.entity_id,
                 relation.to.entity_id,
                 relation.cardinality.from,
                 relation.cardinality.to,
+                relation_constraints(relation),
                 desc
             ));
         }
@@ -1577,19 +1912,32 @@ fn emit_mermaid(model: &DomainModel, style: Option<&str>) -> Result<Value> {
     };
     
     mermaid.push_str(&format!("{}\n", diagram_type));
+    if let Some(direction) = model.layout.as_ref().and_then(|l| l.mermaid_direction()) {
+        mermaid.push_str(&format!("    direction {}\n", direction));
+    }
     
     if style == Some("class") {
-        // Generate class diagram
-        for entity in &model.entities {
-            mermaid.push_str(&format!("    class {} {{\n", entity.id));
-            for attr in &entity.attributes {
-                let visibility = if attr.required.unwrap_or(false) { "+" } else { "-" };
-                mermaid.push_str(&format!("        {}{}: {}\n", visibility, attr.name, attr.attr_type));
+        // Generate class diagram, one namespace per layout group
+        for (group, entities) in layout::grouped_entities(model) {
+            let indent = if group.is_some() { "        " } else { "    " };
+            if let Some(group) = group {
+                mermaid.push_str(&format!("    namespace {} {{\n", layout::mermaid_identifier(&group.id)));
+            }
+            for entity in entities {
+                mermaid.push_str(&format!("{}class {} {{\n", indent, entity.id));
+                for attr in &entity.attributes {
+                    let visibility = if attr.required.unwrap_or(false) { "+" } else { "-" };
+                    let default = attr.default_value.as_ref().map(|v| format!(" = {}", v)).unwrap_or_default();
+                    mermaid.push_str(&format!("{}    {}{}: {}{}\n", indent, visibility, attr.name, attr.attr_type, default));
+                }
+                mermaid.push_str(&format!("{}}}\n", indent));
+            }
+            if group.is_some() {
+                mermaid.push_str("    }\n");
             }
-            mermaid.push_str("    }\n");
         }
         
-        for relation in &model.relations {
+        for relation in visible_relations(model) {
             let arrow = match (relation.cardinality.from.as_str(), relation.cardinality.to.as_str()) {
                 ("1", "1") => "--",
                 ("1", _) => "-->",
@@ -1616,26 +1964,39 @@ fn emit_mermaid(model: &DomainModel, style: Option<&str>) -> Result<Value> {
     }
 }
 
+/// Relations that are not hidden by the model's layout hints.
+fn visible_relations(model: &DomainModel) -> impl Iterator<Item = &Relation> {
+    model.relations.iter().filter(move |r| {
+        !model.layout.as_ref().is_some_and(|l| l.is_hidden(&r.id))
+    })
+}
+
 fn emit_er_diagram(model: &DomainModel, mermaid: &mut String) -> Result<()> {
     
-    for entity in &model.entities {
-        mermaid.push_str(&format!("    {} {{\n", entity.id));
-        for attr in &entity.attributes {
-            let type_str = match attr.attr_type.as_str() {
-                "string" => "string",
-                "number" | "integer" => "int",
-                "boolean" => "bool",
-                "date" | "datetime" => "date",
-                "uuid" => "uuid",
-                _ => "string",
-            };
-            let modifiers = if attr.required.unwrap_or(false) { " PK" } else { "" };
-            mermaid.push_str(&format!("        {} {}{}\n", type_str, attr.name, modifiers));
+    // erDiagram has no subgraphs: groups are emitted contiguously with a comment header
+    for (group, entities) in layout::grouped_entities(model) {
+        if let Some(group) = group {
+            mermaid.push_str(&format!("    %% {}\n", group.label.as_deref().unwrap_or(&group.id)));
+        }
+        for entity in entities {
+            mermaid.push_str(&format!("    {} {{\n", entity.id));
+            for attr in &entity.attributes {
+                let type_str = match attr.attr_type.as_str() {
+                    "string" => "string",
+                    "number" | "integer" => "int",
+                    "boolean" => "bool",
+                    "date" | "datetime" => "date",
+                    "uuid" => "uuid",
+                    _ => "string",
+                };
+                let modifiers = if attr.required.unwrap_or(false) { " PK" } else { "" };
+                mermaid.push_str(&format!("        {} {}{}{}\n", type_str, attr.name, modifiers, mermaid_annotation(attr)));
+            }
+            mermaid.push_str("    }\n");
         }
-        mermaid.push_str("    }\n");
     }
     
-    for relation in &model.relations {
+    for relation in visible_relations(model) {
         let from_card = match relation.cardinality.from.as_str() {
             "1" => "||",
             "0..1" => "|o",
@@ -1670,7 +2031,14 @@ fn emit_er_diagram(model: &DomainModel, mermaid: &mut String) -> Result<()> {
     Ok(())
 }
 
-fn validate_model(model: &DomainModel, schema_path: Option<&str>) -> Result<Value> {
+/// `sources` (transcript and glossary), when given, enables the hallucination guard:
+/// names they do not support are listed in `unsupported` without failing the validation.
+fn validate_model(
+    model: &DomainModel,
+    schema_path: Option<&str>,
+    rules_dir: Option<&str>,
+    sources: Option<(&str, &[glossary::GlossaryTerm])>,
+) -> Result<Value> {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
     
@@ -1750,6 +2118,28 @@ fn validate_model(model: &DomainModel, schema_path: Option<&str>) -> Result<Valu
         }
     }
     
+    // Built-in relation rules and house rules: error findings fail the validation,
+    // the rest is reported alongside
+    let mut findings = relation_checks::check(model);
+    findings.extend(foreign_keys::check(&serde_json::to_value(model)?));
+    let rules_dir = rules_dir
+        .map(str::to_string)
+        .or_else(|| std::env::var(custom_rules::RULES_DIR_ENV).ok().filter(|d| !d.is_empty()));
+    let mut rule_failures = Vec::new();
+    if let Some(dir) = rules_dir {
+        let report = custom_rules::check_model(std::path::Path::new(&dir), &serde_json::to_value(model)?)?;
+        findings.extend(report.findings);
+        rule_failures = report.failures;
+    }
+    for finding in &findings {
+        if finding.severity == custom_rules::Severity::Error {
+            errors.push(match &finding.target {
+                Some(target) => format!("Rule '{}' ({}): {}", finding.rule, target, finding.message),
+                None => format!("Rule '{}': {}", finding.rule, finding.message),
+            });
+        }
+    }
+    
     let is_valid = errors.is_empty();
     
     // If schema_path provided, validate against JSON schema
@@ -1762,16 +2152,26 @@ fn validate_model(model: &DomainModel, schema_path: Option<&str>) -> Result<Valu
         }
     }
     
-    if is_valid {
-        Ok(json!({
+    let mut result = if is_valid {
+        json!({
             "ok": true
-        }))
+        })
     } else {
-        Ok(json!({
+        json!({
             "ok": false,
             "errors": errors
-        }))
+        })
+    };
+    if !findings.is_empty() {
+        result["findings"] = json!(findings);
+    }
+    if !rule_failures.is_empty() {
+        result["rule_failures"] = json!(rule_failures);
+    }
+    if let Some((transcript, terms)) = sources {
+        result["unsupported"] = json!(grounding::unsupported(model, transcript, terms));
     }
+    Ok(result)
 }
 
 // Helper functions
@@ -1899,6 +2299,38 @@ fn handle_list_tools() -> Result<JsonRpcResponse> {
                         "type": "string",
                         "description": "Diagram style",
                         "enum": ["er", "class"]
+                    },
+                    "focus": {
+                        "type": "string",
+                        "description": "Render only this entity and its neighbourhood"
+                    },
+                    "hops": {
+                        "type": "integer",
+                        "description": "Neighbourhood depth around the focus entity",
+                        "default": 1
+                    },
+                    "context": {
+                        "type": "string",
+                        "description": "Render a single bounded context (layout group id or label)"
+                    },
+                    "aggregate_roots_only": {
+                        "type": "boolean",
+                        "description": "Render only aggregate roots",
+                        "default": false
+                    }
+                },
+                "required": ["model"]
+            }),
+        },
+        ToolDefinition {
+            name: "emit_sql".to_string(),
+            description: "Generate PostgreSQL DDL of the domain model: one table per entity, foreign keys from relations (NOT NULL when required, ON DELETE CASCADE when cascadeDelete), join tables for many-to-many".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "model": {
+                        "type": "object",
+                        "description": "The domain model to translate"
                     }
                 },
                 "required": ["model"]
@@ -1917,6 +2349,225 @@ fn handle_list_tools() -> Result<JsonRpcResponse> {
                     "schema_path": {
                         "type": "string",
                         "description": "Optional path to JSON schema file for validation"
+                    },
+                    "rules_dir": {
+                        "type": "string",
+                        "description": "Directory of custom Rhai validation rules (*.rhai); defaults to $DOMAIN_RULES_DIR"
+                    },
+                    "transcript": {
+                        "type": "string",
+                        "description": "Interview transcript; entity and attribute names it does not support (nor the glossary) are listed in 'unsupported'"
+                    },
+                    "glossary": {
+                        "type": "array",
+                        "description": "Project glossary terms, also accepted as support for names",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "term": { "type": "string" },
+                                "definition": { "type": "string" },
+                                "synonyms": { "type": "array", "items": { "type": "string" } }
+                            },
+                            "required": ["term"]
+                        }
+                    }
+                },
+                "required": ["model"]
+            }),
+        },
+        ToolDefinition {
+            name: "normalize_relations".to_string(),
+            description: "Normalize relation directions (parent → child) and verbs (active voice), flipping cardinalities consistently".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "model": {
+                        "type": "object",
+                        "description": "The domain model to normalize"
+                    },
+                    "conventions": {
+                        "type": "object",
+                        "description": "Conventions to enforce (all enabled by default)",
+                        "properties": {
+                            "parent_to_child": { "type": "boolean", "default": true },
+                            "active_verbs": { "type": "boolean", "default": true }
+                        }
+                    }
+                },
+                "required": ["model"]
+            }),
+        },
+        ToolDefinition {
+            name: "enforce_naming".to_string(),
+            description: "Enforce the naming policy (singular PascalCase entities, camelCase/snake_case attributes, kebab/snake relation ids) and report fixups".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "model": {
+                        "type": "object",
+                        "description": "The domain model to normalize"
+                    },
+                    "policy": {
+                        "type": "object",
+                        "description": "Naming policy (defaults: singular entities, camelCase attributes, kebab relation ids)",
+                        "properties": {
+                            "singular_entities": { "type": "boolean", "default": true },
+                            "attribute_case": { "type": "string", "enum": ["camel", "snake"], "default": "camel" },
+                            "relation_id_case": { "type": "string", "enum": ["kebab", "snake"], "default": "kebab" }
+                        }
+                    }
+                },
+                "required": ["model"]
+            }),
+        },
+        ToolDefinition {
+            name: "check_ubiquitous_language".to_string(),
+            description: "Lint entity and attribute names against the glossary and the 'Langage Ubiquiste' table, flagging inconsistent synonyms".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "model": {
+                        "type": "object",
+                        "description": "The domain model to check"
+                    },
+                    "glossary": {
+                        "type": "array",
+                        "description": "Project glossary terms",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "term": { "type": "string" },
+                                "definition": { "type": "string" },
+                                "synonyms": { "type": "array", "items": { "type": "string" } }
+                            },
+                            "required": ["term"]
+                        }
+                    },
+                    "ubiquitous_language": {
+                        "type": "string",
+                        "description": "Markdown of the interview 'Langage Ubiquiste' section (| Terme | Définition | Exemple |)"
+                    }
+                },
+                "required": ["model"]
+            }),
+        },
+        ToolDefinition {
+            name: "diff_models".to_string(),
+            description: "Structural diff between two domain models (added, removed and modified entities, attributes, relations and invariants)".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "before": {
+                        "type": "object",
+                        "description": "Reference domain model"
+                    },
+                    "after": {
+                        "type": "object",
+                        "description": "Domain model compared to the reference"
+                    }
+                },
+                "required": ["before", "after"]
+            }),
+        },
+        ToolDefinition {
+            name: "suggest_fixes".to_string(),
+            description: "Propose JSON Patch fixes (add a primary key, remove a duplicate attribute, correct a mistyped entityId) for validation errors; each fix can be applied on its own".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "model": {
+                        "type": "object",
+                        "description": "Domain model that failed validation"
+                    },
+                    "errors": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Validation errors to fix (from validate_model); all detected problems when omitted"
+                    }
+                },
+                "required": ["model"]
+            }),
+        },
+        ToolDefinition {
+            name: "import_markdown".to_string(),
+            description: "Parse markdown documentation (emit_markdown output or similar hand-written docs) back into a DomainModel; tables are parsed deterministically, prose sections go through the LLM".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "markdown": {
+                        "type": "string",
+                        "description": "Markdown document to import"
+                    },
+                    "use_llm": {
+                        "type": "boolean",
+                        "description": "Extract elements from unrecognized prose sections with the LLM",
+                        "default": true
+                    }
+                },
+                "required": ["markdown"]
+            }),
+        },
+        ToolDefinition {
+            name: "traceability_matrix".to_string(),
+            description: "Map each invariant to its interview sources, Gherkin scenarios and implementing code, as rows plus markdown and CSV tables".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "model": {
+                        "type": "object",
+                        "description": "The domain model whose invariants are traced"
+                    },
+                    "sources": {
+                        "type": "array",
+                        "description": "Interview answers the model was built from",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "source": { "type": "string", "description": "Label shown in the matrix" },
+                                "question": { "type": "string" },
+                                "answer": { "type": "string" }
+                            },
+                            "required": ["answer"]
+                        }
+                    },
+                    "features": {
+                        "type": "array",
+                        "description": "Gherkin feature files; scenarios tagged @<invariant id> are linked explicitly",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "path": { "type": "string" },
+                                "content": { "type": "string" }
+                            },
+                            "required": ["path", "content"]
+                        }
+                    },
+                    "features_dir": {
+                        "type": "string",
+                        "description": "Directory searched for .feature files"
+                    },
+                    "code_dir": {
+                        "type": "string",
+                        "description": "Codebase audited for lines naming each invariant id"
+                    }
+                },
+                "required": ["model"]
+            }),
+        },
+        ToolDefinition {
+            name: "suggest_layout".to_string(),
+            description: "Propose layout hints (groups, ranks, hidden relations, direction) for diagram emission".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "model": {
+                        "type": "object",
+                        "description": "The domain model to lay out"
+                    },
+                    "max_group_size": {
+                        "type": "integer",
+                        "description": "Maximum number of entities per suggested group",
+                        "default": 6
                     }
                 },
                 "required": ["model"]
@@ -1973,14 +2624,128 @@ async fn handle_tool_call(name: &str, params: &Value) -> Result<JsonRpcResponse>
                 .ok_or_else(|| anyhow::anyhow!("Missing 'model' parameter"))?;
             let model: DomainModel = serde_json::from_value(model_value.clone())?;
             let style = params.get("style").and_then(|v| v.as_str());
-            emit_mermaid(&model, style)?
+            let filter = layout::DiagramFilter::from_params(params);
+            if filter.is_empty() {
+                emit_mermaid(&model, style)?
+            } else {
+                emit_mermaid(&filter.apply(&model)?, style)?
+            }
+        }
+        "emit_sql" => {
+            let model = params.get("model")
+                .ok_or_else(|| anyhow::anyhow!("Missing 'model' parameter"))?;
+            json!({ "sql": sql::emit_sql(model) })
         }
         "validate_model" => {
             let model_value = params.get("model")
                 .ok_or_else(|| anyhow::anyhow!("Missing 'model' parameter"))?;
             let model: DomainModel = serde_json::from_value(model_value.clone())?;
             let schema_path = params.get("schema_path").and_then(|v| v.as_str());
-            validate_model(&model, schema_path)?
+            let rules_dir = params.get("rules_dir").and_then(|v| v.as_str());
+            let terms: Vec<glossary::GlossaryTerm> = match params.get("glossary") {
+                Some(v) => serde_json::from_value(v.clone())?,
+                None => Vec::new(),
+            };
+            let transcript = params.get("transcript").and_then(|v| v.as_str());
+            let sources = transcript.map(|t| (t, terms.as_slice()));
+            validate_model(&model, schema_path, rules_dir, sources)?
+        }
+        "diff_models" => {
+            let before = params.get("before")
+                .ok_or_else(|| anyhow::anyhow!("Missing 'before' parameter"))?;
+            let after = params.get("after")
+                .ok_or_else(|| anyhow::anyhow!("Missing 'after' parameter"))?;
+            diff::diff_models(before, after)
+        }
+        "suggest_fixes" => {
+            let model = params.get("model")
+                .ok_or_else(|| anyhow::anyhow!("Missing 'model' parameter"))?;
+            let errors: Option<Vec<String>> = match params.get("errors") {
+                Some(v) => Some(serde_json::from_value(v.clone())?),
+                None => None,
+            };
+            fixes::suggest_fixes(model, errors.as_deref())
+        }
+        "import_markdown" => {
+            let markdown = params.get("markdown")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing 'markdown' parameter"))?;
+            let use_llm = params.get("use_llm").and_then(|v| v.as_bool()).unwrap_or(true);
+
+            let markdown_import::MarkdownImport { mut model, prose, mut warnings } =
+                markdown_import::parse_markdown(markdown);
+            let mut llm_added = Vec::new();
+            if use_llm && !prose.is_empty() {
+                let prompt = markdown_import::prose_prompt(&model, &prose);
+                // The deterministic result stands on its own if the LLM is unavailable
+                match call_llm_json(markdown_import::PROSE_SYSTEM_PROMPT, &prompt).await {
+                    Ok(extracted) => llm_added = markdown_import::merge_extracted(&mut model, &extracted),
+                    Err(e) => warnings.push(format!("LLM pass skipped: {}", e)),
+                }
+            }
+            json!({
+                "model": model,
+                "warnings": warnings,
+                "prose_sections": prose.iter().map(|(heading, _)| heading).collect::<Vec<_>>(),
+                "llm_added": llm_added
+            })
+        }
+        "traceability_matrix" => {
+            let model_value = params.get("model")
+                .ok_or_else(|| anyhow::anyhow!("Missing 'model' parameter"))?;
+            let sources: Vec<traceability::InterviewSource> = match params.get("sources") {
+                Some(v) => serde_json::from_value(v.clone())?,
+                None => Vec::new(),
+            };
+            let mut features: Vec<traceability::FeatureFile> = match params.get("features") {
+                Some(v) => serde_json::from_value(v.clone())?,
+                None => Vec::new(),
+            };
+            if let Some(dir) = params.get("features_dir").and_then(|v| v.as_str()) {
+                features.extend(traceability::read_features(std::path::Path::new(dir))?);
+            }
+            let code_dir = params.get("code_dir").and_then(|v| v.as_str()).map(std::path::Path::new);
+            traceability::traceability_matrix_tool(model_value, &sources, &features, code_dir)?
+        }
+        "normalize_relations" => {
+            let model_value = params.get("model")
+                .ok_or_else(|| anyhow::anyhow!("Missing 'model' parameter"))?;
+            let conventions: normalize::RelationConventions = match params.get("conventions") {
+                Some(v) => serde_json::from_value(v.clone())?,
+                None => Default::default(),
+            };
+            normalize::normalize_relations_tool(model_value, &conventions)?
+        }
+        "enforce_naming" => {
+            let model_value = params.get("model")
+                .ok_or_else(|| anyhow::anyhow!("Missing 'model' parameter"))?;
+            let policy: normalize::NamingPolicy = match params.get("policy") {
+                Some(v) => serde_json::from_value(v.clone())?,
+                None => Default::default(),
+            };
+            normalize::enforce_naming_tool(model_value, &policy)?
+        }
+        "check_ubiquitous_language" => {
+            let model_value = params.get("model")
+                .ok_or_else(|| anyhow::anyhow!("Missing 'model' parameter"))?;
+            let model: DomainModel = serde_json::from_value(model_value.clone())?;
+            let mut terms: Vec<glossary::GlossaryTerm> = match params.get("glossary") {
+                Some(v) => serde_json::from_value(v.clone())?,
+                None => Vec::new(),
+            };
+            if let Some(table) = params.get("ubiquitous_language").and_then(|v| v.as_str()) {
+                terms.extend(glossary::parse_ubiquitous_table(table));
+            }
+            glossary::check_ubiquitous_language(&model, &terms)?
+        }
+        "suggest_layout" => {
+            let model_value = params.get("model")
+                .ok_or_else(|| anyhow::anyhow!("Missing 'model' parameter"))?;
+            let model: DomainModel = serde_json::from_value(model_value.clone())?;
+            let max_group_size = params.get("max_group_size")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(6) as usize;
+            layout::suggest_layout(&model, max_group_size)?
         }
         _ => return Err(anyhow::anyhow!("Unknown tool: {}", name)),
     };
@@ -0,0 +1,757 @@
+//! Import of markdown documentation back into a domain model.
+//!
+//! Tables and lists laid out like `emit_markdown` output (or loosely similar
+//! hand-written docs) are parsed deterministically: the entity summary table,
+//! one `### <Entity>` attribute table or list per entity, the relations table
+//! and the numbered business rules. Sections that are not recognized are kept
+//! as prose for an LLM pass, whose additions never override parsed elements.
+
+use serde_json::{json, Map, Value};
+
+/// System prompt for extracting model elements from prose sections
+pub const PROSE_SYSTEM_PROMPT: &str = r#"
+Tu complètes un Domain Model à partir de sections de documentation en prose. Tu reçois le modèle déjà extrait des tableaux et le texte des autres sections.
+
+Réponds UNIQUEMENT avec un JSON contenant les éléments NOUVEAUX décrits par la prose:
+{
+  "entities": [{"id": "string", "name": "string", "attributes": [{"name": "string", "type": "string|number|integer|boolean|date|datetime|email|url|uuid|json|text"}]}],
+  "relations": [{"id": "string", "name": "string", "from": {"entityId": "string"}, "to": {"entityId": "string"}, "cardinality": {"from": "0..1|1|0..n|1..n|*", "to": "0..1|1|0..n|1..n|*"}}],
+  "invariants": [{"id": "string", "name": "string", "type": "uniqueness|referential_integrity|domain_constraint|cardinality|business_rule|temporal|aggregation", "expression": "string"}]
+}
+
+RÈGLES:
+1. Ne répète pas les éléments du modèle existant
+2. N'invente rien qui ne soit pas dans le texte
+3. Les relations référencent des entités existantes ou ajoutées
+"#;
+
+const ATTRIBUTE_TYPES: &[&str] =
+    &["string", "number", "integer", "boolean", "date", "datetime", "email", "url", "uuid", "json", "text"];
+
+const INVARIANT_TYPES: &[&str] = &[
+    "uniqueness", "referential_integrity", "domain_constraint", "cardinality", "business_rule", "temporal", "aggregation",
+];
+
+const SEVERITIES: &[&str] = &["error", "warning", "info"];
+
+/// Outcome of the deterministic pass
+#[derive(Debug, Clone)]
+pub struct MarkdownImport {
+    pub model: Value,
+    /// (heading, text) of the sections left for the LLM
+    pub prose: Vec<(String, String)>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SectionKind {
+    Entities,
+    Relations,
+    Rules,
+    /// Generated metadata (context header, glossary)
+    Ignored,
+    Prose,
+}
+
+/// Lowercase and strip accents
+fn fold(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'à' | 'â' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'î' | 'ï' => 'i',
+            'ô' | 'ö' => 'o',
+            'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            c => c,
+        })
+        .collect()
+}
+
+fn section_kind(heading: &str) -> SectionKind {
+    let heading = fold(heading);
+    if heading.contains("entit") {
+        SectionKind::Entities
+    } else if heading.contains("relation") {
+        SectionKind::Relations
+    } else if heading.contains("regle") || heading.contains("invariant") || heading.contains("business rule") {
+        SectionKind::Rules
+    } else if heading.contains("glossa") || heading.contains("contexte") || heading.contains("context") {
+        SectionKind::Ignored
+    } else {
+        SectionKind::Prose
+    }
+}
+
+/// Remove markdown emphasis and code marks from a cell or list item
+fn clean(text: &str) -> String {
+    text.replace("**", "").replace('`', "").trim().trim_matches('*').trim().to_string()
+}
+
+/// Words of a label, accents stripped
+fn label_words(label: &str) -> Vec<String> {
+    fold(label)
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+/// Schema-compliant identifier: PascalCase for entities, camelCase otherwise
+fn identifier(label: &str, pascal: bool) -> String {
+    let words = label_words(label);
+    // Already an identifier (e.g. "orderId"): keep its casing
+    let single = label.trim();
+    let mut id = if words.len() == 1 && single.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        single.to_string()
+    } else {
+        words.iter().enumerate().map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) }).collect()
+    };
+    if !id.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        id.insert(0, if pascal { 'E' } else { 'x' });
+    }
+    if pascal {
+        capitalize(&id)
+    } else {
+        let mut chars = id.chars();
+        chars.next().map(|c| c.to_lowercase().chain(chars).collect()).unwrap_or_default()
+    }
+}
+
+fn attribute_type(raw: &str, warnings: &mut Vec<String>, context: &str) -> String {
+    let folded = fold(&clean(raw));
+    let base = folded.split(|c: char| c == '(' || c.is_whitespace()).next().unwrap_or("");
+    let mapped = match base {
+        t if ATTRIBUTE_TYPES.contains(&t) => t,
+        "int" | "integer" | "entier" | "long" | "bigint" => "integer",
+        "float" | "double" | "decimal" | "money" | "montant" | "nombre" | "numeric" => "number",
+        "bool" | "booleen" => "boolean",
+        "timestamp" | "instant" => "datetime",
+        "id" | "guid" => "uuid",
+        "texte" => "text",
+        "str" | "chaine" | "varchar" | "char" => "string",
+        "courriel" => "email",
+        "" => "string",
+        other => {
+            warnings.push(format!("{}: unknown type '{}', imported as string", context, other));
+            "string"
+        }
+    };
+    mapped.to_string()
+}
+
+fn normalize_cardinality(raw: &str) -> Option<&'static str> {
+    match raw.trim().to_lowercase().as_str() {
+        "1" => Some("1"),
+        "0..1" => Some("0..1"),
+        "0..n" | "0..*" => Some("0..n"),
+        "1..n" | "1..*" => Some("1..n"),
+        "*" | "n" | "many" => Some("*"),
+        _ => None,
+    }
+}
+
+/// "1..0..n" (emit_markdown), "1 → 0..n", "1:n" or "1-n" into (from, to)
+fn parse_cardinality(cell: &str) -> Option<(String, String)> {
+    // Not `clean`: a trailing '*' is a cardinality here, not emphasis
+    let cell = cell.replace("**", "").replace('`', "");
+    let cell = cell.trim();
+    for separator in ["→", "->", ":", " - ", "/", "-"] {
+        if let Some((from, to)) = cell.split_once(separator) {
+            if let (Some(from), Some(to)) = (normalize_cardinality(from), normalize_cardinality(to)) {
+                return Some((from.to_string(), to.to_string()));
+            }
+        }
+    }
+    let tokens: Vec<&str> = cell.split("..").collect();
+    (1..tokens.len()).find_map(|k| {
+        let from = normalize_cardinality(&tokens[..k].join(".."))?;
+        let to = normalize_cardinality(&tokens[k..].join(".."))?;
+        Some((from.to_string(), to.to_string()))
+    })
+}
+
+struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Column whose folded header is one of `names`, or else starts with one of them
+    fn column(&self, names: &[&str]) -> Option<usize> {
+        let headers: Vec<String> = self.headers.iter().map(|h| fold(&clean(h))).collect();
+        headers
+            .iter()
+            .position(|h| names.contains(&h.as_str()))
+            .or_else(|| headers.iter().position(|h| names.iter().any(|n| h.starts_with(n))))
+    }
+
+    fn cell<'a>(&self, row: &'a [String], column: Option<usize>) -> &'a str {
+        column.and_then(|c| row.get(c)).map(|s| s.as_str()).unwrap_or("")
+    }
+}
+
+fn split_row(line: &str) -> Vec<String> {
+    let inner = line.trim().trim_start_matches('|').trim_end_matches('|');
+    // Escaped pipes stay inside their cell
+    inner
+        .replace("\\|", "\u{0}")
+        .split('|')
+        .map(|c| c.replace('\u{0}', "|").trim().to_string())
+        .collect()
+}
+
+fn parse_table(lines: &[&str]) -> Option<Table> {
+    let rows: Vec<&str> = lines.iter().copied().filter(|l| l.trim_start().starts_with('|')).collect();
+    let (header, rest) = rows.split_first()?;
+    let is_separator = |l: &str| l.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '));
+    Some(Table {
+        headers: split_row(header),
+        rows: rest.iter().filter(|l| !is_separator(l)).map(|l| split_row(l)).collect(),
+    })
+}
+
+fn yes(cell: &str) -> bool {
+    let cell = fold(&clean(cell));
+    matches!(cell.as_str(), "✓" | "✔" | "x" | "oui" | "yes" | "true" | "o" | "y")
+}
+
+/// Lines of a section split on `### ` sub-headings
+fn subsections<'a>(lines: &[&'a str]) -> Vec<(Option<String>, Vec<&'a str>)> {
+    let mut out: Vec<(Option<String>, Vec<&str>)> = vec![(None, Vec::new())];
+    for line in lines {
+        if let Some(title) = line.trim().strip_prefix("### ") {
+            out.push((Some(clean(title)), Vec::new()));
+        } else {
+            out.last_mut().unwrap().1.push(line);
+        }
+    }
+    out
+}
+
+/// "- **email** : string (requis)" or "- total (number)" list items as attributes
+fn parse_attribute_list(lines: &[&str], entity: &str, warnings: &mut Vec<String>) -> Vec<Value> {
+    lines
+        .iter()
+        .filter_map(|l| l.trim().strip_prefix("- ").or_else(|| l.trim().strip_prefix("* ")))
+        .filter_map(|item| {
+            let item = clean(item);
+            let (name, rest) = match item.split_once(':') {
+                Some((name, rest)) => (name.trim().to_string(), rest.trim().to_string()),
+                None => match item.split_once('(') {
+                    Some((name, rest)) => (name.trim().to_string(), rest.trim_end_matches(')').to_string()),
+                    None => (item.clone(), String::new()),
+                },
+            };
+            if name.is_empty() {
+                return None;
+            }
+            let folded = fold(&rest);
+            let mut attribute = json!({
+                "name": identifier(&name, false),
+                "type": attribute_type(&rest, warnings, &format!("{}.{}", entity, name)),
+            });
+            if folded.contains("requis") || folded.contains("required") || folded.contains("obligatoire") {
+                attribute["required"] = json!(true);
+            }
+            if folded.contains("unique") {
+                attribute["unique"] = json!(true);
+            }
+            Some(attribute)
+        })
+        .collect()
+}
+
+/// JSON literal of a cell, or the text itself
+fn literal(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| json!(text))
+}
+
+/// Constraints cell as written by `emit_markdown` ("min 0 ; longueur max 80 ; format `^[A-Z]+$`")
+fn parse_validation(cell: &str) -> Map<String, Value> {
+    let mut validation = Map::new();
+    for constraint in cell.split(';').map(str::trim).filter(|c| !c.is_empty()) {
+        let folded = fold(constraint);
+        // (key, number of label words before the value)
+        let (key, words) = if folded.starts_with("longueur min") || folded.starts_with("min length") {
+            ("minLength", 2)
+        } else if folded.starts_with("longueur max") || folded.starts_with("max length") {
+            ("maxLength", 2)
+        } else if folded.starts_with("min ") {
+            ("min", 1)
+        } else if folded.starts_with("max ") {
+            ("max", 1)
+        } else if folded.starts_with("format ") || folded.starts_with("pattern ") {
+            ("pattern", 1)
+        } else if folded.starts_with("valeurs ") || folded.starts_with("enum ") {
+            ("enum", 1)
+        } else if folded.starts_with("regle") || folded.starts_with("rule") {
+            ("custom", 0)
+        } else {
+            continue;
+        };
+        let text = match key {
+            "custom" => constraint.split_once(':').map_or("", |(_, rest)| rest),
+            _ => constraint.splitn(words + 1, ' ').nth(words).unwrap_or(""),
+        };
+        let text = text.trim().trim_matches('`');
+        if text.is_empty() {
+            continue;
+        }
+        let value = match key {
+            "pattern" | "custom" => json!(text),
+            _ => literal(text),
+        };
+        validation.insert(key.to_string(), value);
+    }
+    validation
+}
+
+fn parse_attribute_table(table: &Table, entity: &str, warnings: &mut Vec<String>) -> Vec<Value> {
+    let name_col = table.column(&["attribut", "attribute", "nom", "name", "champ", "field"]).or(Some(0));
+    let type_col = table.column(&["type"]);
+    let required_col = table.column(&["requis", "required", "obligatoire"]);
+    let unique_col = table.column(&["unique"]);
+    let default_col = table.column(&["defaut", "default"]);
+    let constraints_col = table.column(&["contrainte", "constraint", "validation"]);
+    let description_col = table.column(&["description"]);
+
+    table
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let name = clean(table.cell(row, name_col));
+            if name.is_empty() {
+                return None;
+            }
+            let context = format!("{}.{}", entity, name);
+            let mut attribute = json!({
+                "name": identifier(&name, false),
+                "type": attribute_type(table.cell(row, type_col), warnings, &context),
+            });
+            if yes(table.cell(row, required_col)) {
+                attribute["required"] = json!(true);
+            }
+            if yes(table.cell(row, unique_col)) {
+                attribute["unique"] = json!(true);
+            }
+            let default = clean(table.cell(row, default_col));
+            if !default.is_empty() {
+                attribute["defaultValue"] = literal(&default);
+            }
+            let validation = parse_validation(table.cell(row, constraints_col));
+            if !validation.is_empty() {
+                attribute["validation"] = Value::Object(validation);
+            }
+            let description = clean(table.cell(row, description_col));
+            if !description.is_empty() {
+                attribute["description"] = json!(description);
+            }
+            Some(attribute)
+        })
+        .collect()
+}
+
+/// Lines of an entity sub-section before its "Unicité combinée" list, and the
+/// constraints of that list ("- `uq_name` : attr1, attr2")
+fn split_unique_constraints<'a, 'b>(lines: &'b [&'a str]) -> (&'b [&'a str], Vec<Value>) {
+    let Some(start) = lines.iter().position(|l| {
+        let folded = fold(l);
+        folded.contains("unicite combinee") || folded.contains("unique constraints")
+    }) else {
+        return (lines, Vec::new());
+    };
+    let constraints = lines[start + 1..]
+        .iter()
+        .map(|l| l.trim())
+        .skip_while(|l| l.is_empty())
+        .take_while(|l| l.starts_with("- ") || l.starts_with("* "))
+        .filter_map(|l| {
+            let (name, attributes) = l[2..].split_once(':')?;
+            let attributes: Vec<String> = attributes
+                .split(',')
+                .map(clean)
+                .filter(|a| !a.is_empty())
+                .map(|a| identifier(&a, false))
+                .collect();
+            Some(json!({"name": clean(name), "attributes": attributes}))
+        })
+        .collect();
+    (&lines[..start], constraints)
+}
+
+fn parse_entities(lines: &[&str], entities: &mut Vec<Value>, warnings: &mut Vec<String>) {
+    for (title, lines) in subsections(lines) {
+        match title {
+            // Summary table: name and description
+            None => {
+                let Some(table) = parse_table(&lines) else { continue };
+                let name_col = table.column(&["entit", "nom", "name"]).or(Some(0));
+                let description_col = table.column(&["description"]);
+                for row in &table.rows {
+                    let name = clean(table.cell(row, name_col));
+                    if name.is_empty() {
+                        continue;
+                    }
+                    let mut entity = json!({"id": identifier(&name, true), "name": name, "attributes": []});
+                    let description = clean(table.cell(row, description_col));
+                    if !description.is_empty() {
+                        entity["description"] = json!(description);
+                    }
+                    entities.push(entity);
+                }
+            }
+            // One sub-section per entity with its attributes
+            Some(title) => {
+                let (lines, constraints) = split_unique_constraints(&lines);
+                let attributes = match parse_table(lines) {
+                    Some(table) => parse_attribute_table(&table, &title, warnings),
+                    None => parse_attribute_list(lines, &title, warnings),
+                };
+                let id = identifier(&title, true);
+                let position = entities.iter().position(|e| {
+                    e["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(&title)) || e["id"] == json!(id)
+                });
+                let i = match position {
+                    Some(i) => {
+                        entities[i]["attributes"] = json!(attributes);
+                        i
+                    }
+                    None => {
+                        entities.push(json!({"id": id, "name": title, "attributes": attributes}));
+                        entities.len() - 1
+                    }
+                };
+                if !constraints.is_empty() {
+                    entities[i]["uniqueConstraints"] = json!(constraints);
+                }
+            }
+        }
+    }
+}
+
+/// Entity id for a relation end written as an id or a name
+fn resolve_entity(entities: &[Value], reference: &str) -> Option<String> {
+    let reference = clean(reference);
+    entities
+        .iter()
+        .find(|e| e["id"].as_str() == Some(reference.as_str()))
+        .or_else(|| {
+            entities
+                .iter()
+                .find(|e| e["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(&reference)))
+        })
+        .or_else(|| {
+            let id = identifier(&reference, true);
+            entities.iter().find(|e| e["id"].as_str() == Some(id.as_str()))
+        })
+        .and_then(|e| e["id"].as_str())
+        .map(str::to_string)
+}
+
+fn parse_relations(lines: &[&str], entities: &[Value], relations: &mut Vec<Value>, warnings: &mut Vec<String>) {
+    let Some(table) = parse_table(lines) else { return };
+    let name_col = table.column(&["relation", "nom", "name"]).or(Some(0));
+    let from_col = table.column(&["de", "from", "source"]);
+    let to_col = table.column(&["vers", "to", "cible", "target"]);
+    let cardinality_col = table.column(&["cardinal"]);
+    let description_col = table.column(&["description"]);
+    let constraints_col = table.column(&["contrainte", "constraint"]);
+
+    for row in &table.rows {
+        let name = clean(table.cell(row, name_col));
+        let (from_ref, to_ref) = (clean(table.cell(row, from_col)), clean(table.cell(row, to_col)));
+        if name.is_empty() || from_ref.is_empty() || to_ref.is_empty() {
+            continue;
+        }
+        let mut end = |reference: &str| {
+            resolve_entity(entities, reference).unwrap_or_else(|| {
+                warnings.push(format!("Relation '{}': unknown entity '{}'", name, reference));
+                identifier(reference, true)
+            })
+        };
+        let (from, to) = (end(&from_ref), end(&to_ref));
+        let (card_from, card_to) = parse_cardinality(table.cell(row, cardinality_col)).unwrap_or_else(|| {
+            warnings.push(format!("Relation '{}': unreadable cardinality, defaulted to 1..0..n", name));
+            ("1".to_string(), "0..n".to_string())
+        });
+
+        let id = format!("{}-{}-{}", from, label_words(&name).join("-"), to).to_lowercase();
+        let mut relation = json!({
+            "id": id,
+            "name": name,
+            "from": {"entityId": from},
+            "to": {"entityId": to},
+            "cardinality": {"from": card_from, "to": card_to}
+        });
+        let description = clean(table.cell(row, description_col));
+        if !description.is_empty() {
+            relation["description"] = json!(description);
+        }
+        let constraints = fold(table.cell(row, constraints_col));
+        if constraints.contains("obligatoire") || constraints.contains("required") {
+            relation["required"] = json!(true);
+        }
+        if constraints.contains("cascade") {
+            relation["cascadeDelete"] = json!(true);
+        }
+        relations.push(relation);
+    }
+}
+
+/// "N. **Name**" (or "- Name") items with "- Type:", "- Expression:", "- Sévérité:" details
+fn parse_rules(lines: &[&str], invariants: &mut Vec<Value>, warnings: &mut Vec<String>) {
+    let mut current: Option<Map<String, Value>> = None;
+    let finish = |rule: Option<Map<String, Value>>, invariants: &mut Vec<Value>| {
+        if let Some(mut rule) = rule {
+            if !rule.contains_key("expression") {
+                rule.insert("expression".to_string(), rule["name"].clone());
+            }
+            invariants.push(Value::Object(rule));
+        }
+    };
+
+    for line in lines {
+        let indented = line.starts_with("  ") || line.starts_with('\t');
+        let trimmed = line.trim();
+        let numbered = trimmed
+            .split_once(". ")
+            .filter(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            .map(|(_, rest)| rest);
+        let bullet = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* "));
+
+        let item = match (numbered, bullet) {
+            (Some(item), _) => Some(item),
+            (None, Some(item)) if !indented => Some(item),
+            _ => None,
+        };
+        if let Some(item) = item {
+            finish(current.take(), invariants);
+            let name = clean(item);
+            let mut rule = Map::new();
+            rule.insert("id".to_string(), json!(identifier(&name, false)));
+            rule.insert("name".to_string(), json!(name));
+            rule.insert("type".to_string(), json!("business_rule"));
+            current = Some(rule);
+            continue;
+        }
+
+        let (Some(detail), Some(rule)) = (bullet, current.as_mut()) else { continue };
+        let detail = detail.trim();
+        match detail.split_once(':') {
+            Some((key, value)) if fold(key.trim()) == "type" => {
+                let value = clean(value);
+                if INVARIANT_TYPES.contains(&value.as_str()) {
+                    rule.insert("type".to_string(), json!(value));
+                } else {
+                    warnings.push(format!("Rule '{}': unknown type '{}'", rule["name"].as_str().unwrap_or(""), value));
+                }
+            }
+            Some((key, value)) if fold(key.trim()) == "expression" => {
+                rule.insert("expression".to_string(), json!(clean(value)));
+            }
+            Some((key, value)) if ["severite", "severity"].contains(&fold(key.trim()).as_str()) => {
+                let value = fold(&clean(value));
+                if SEVERITIES.contains(&value.as_str()) {
+                    rule.insert("severity".to_string(), json!(value));
+                }
+            }
+            _ => {
+                rule.insert("description".to_string(), json!(clean(detail)));
+            }
+        }
+    }
+    finish(current.take(), invariants);
+}
+
+/// Deterministic pass over the document
+pub fn parse_markdown(markdown: &str) -> MarkdownImport {
+    let mut sections: Vec<(String, Vec<&str>)> = Vec::new();
+    for line in markdown.lines() {
+        if let Some(heading) = line.strip_prefix("## ").or_else(|| line.strip_prefix("# ")) {
+            sections.push((clean(heading), Vec::new()));
+        } else if let Some((_, lines)) = sections.last_mut() {
+            lines.push(line);
+        } else {
+            sections.push((String::new(), vec![line]));
+        }
+    }
+
+    let (mut entities, mut relations, mut invariants) = (Vec::new(), Vec::new(), Vec::new());
+    let mut warnings = Vec::new();
+    let mut prose = Vec::new();
+
+    for kind in [SectionKind::Entities, SectionKind::Relations, SectionKind::Rules, SectionKind::Prose] {
+        for (heading, lines) in sections.iter().filter(|(h, _)| section_kind(h) == kind) {
+            match kind {
+                SectionKind::Entities => parse_entities(lines, &mut entities, &mut warnings),
+                SectionKind::Relations => parse_relations(lines, &entities, &mut relations, &mut warnings),
+                SectionKind::Rules => parse_rules(lines, &mut invariants, &mut warnings),
+                _ => {
+                    let text = lines.join("\n").trim().to_string();
+                    if !text.is_empty() {
+                        prose.push((heading.clone(), text));
+                    }
+                }
+            }
+        }
+    }
+
+    MarkdownImport {
+        model: json!({"entities": entities, "relations": relations, "invariants": invariants}),
+        prose,
+        warnings,
+    }
+}
+
+/// User prompt for the LLM pass over the prose sections
+pub fn prose_prompt(model: &Value, prose: &[(String, String)]) -> String {
+    let mut prompt = format!(
+        "Modèle déjà extrait:\n{}\n\n",
+        serde_json::to_string_pretty(model).unwrap_or_default()
+    );
+    for (heading, text) in prose {
+        prompt.push_str(&format!("## {}\n{}\n\n", heading, text));
+    }
+    prompt
+}
+
+/// Add the LLM's elements whose id is not already in the model; returns the added ids
+pub fn merge_extracted(model: &mut Value, extracted: &Value) -> Vec<String> {
+    let mut added = Vec::new();
+    for key in ["entities", "relations", "invariants"] {
+        let Some(new_items) = extracted.get(key).and_then(|v| v.as_array()) else { continue };
+        let Some(items) = model.get_mut(key).and_then(|v| v.as_array_mut()) else { continue };
+        for item in new_items {
+            let Some(id) = item.get("id").and_then(|v| v.as_str()) else { continue };
+            if items.iter().all(|existing| existing["id"].as_str() != Some(id)) {
+                added.push(id.to_string());
+                items.push(item.clone());
+            }
+        }
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMITTED: &str = r#"# Contexte
+
+**Generated:** 2026-01-01 10:00:00 UTC
+**Entities:** 2
+
+## Entités
+
+| Entité | Description | Attributs |
+|--------|-------------|-----------|
+| **Customer** | Client du magasin | 2 (id, email) |
+| **Order** | Commande | 1 (total) |
+
+### Customer
+
+| Attribut | Type | Requis | Unique | Défaut | Contraintes | Description |
+|----------|------|--------|--------|--------|-------------|-------------|
+| id | `uuid` | ✓ | ✓ |  |  |  |
+| email | `email` | ✓ | ✓ |  |  | Adresse de contact |
+
+**Unicité combinée :**
+
+- `uq_customer_contact` : id, email
+
+### Order
+
+| Attribut | Type | Requis | Unique | Défaut | Contraintes | Description |
+|----------|------|--------|--------|--------|-------------|-------------|
+| total | `number` | ✓ |  | `0` | min 0 ; format `^\d+(\.\d{2})?$` ; valeurs `[0,10]` |  |
+
+## Relations
+
+| Relation | De | Vers | Cardinalité | Description |
+|----------|----|----|-------------|-------------|
+| **places** | Customer | Order | 1..0..n |  |
+
+## Règles métier
+
+1. **Total positif**
+   - *Le total ne peut pas être négatif*
+   - Type: `domain_constraint`
+   - Expression: `Order.total >= 0`
+   - Sévérité: error
+
+## Glossaire
+
+- **Customer**: Client du magasin
+"#;
+
+    #[test]
+    fn round_trips_emitted_markdown() {
+        let import = parse_markdown(EMITTED);
+        let model = &import.model;
+
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        assert!(import.prose.is_empty());
+        assert_eq!(model["entities"][0]["id"], "Customer");
+        assert_eq!(model["entities"][0]["description"], "Client du magasin");
+        assert_eq!(
+            model["entities"][0]["attributes"][1],
+            json!({"name": "email", "type": "email", "required": true, "unique": true, "description": "Adresse de contact"})
+        );
+        assert_eq!(
+            model["entities"][0]["uniqueConstraints"],
+            json!([{"name": "uq_customer_contact", "attributes": ["id", "email"]}])
+        );
+        assert_eq!(
+            model["entities"][1]["attributes"][0],
+            json!({"name": "total", "type": "number", "required": true, "defaultValue": 0,
+                   "validation": {"min": 0, "pattern": "^\\d+(\\.\\d{2})?$", "enum": [0, 10]}})
+        );
+        assert_eq!(
+            model["relations"][0],
+            json!({"id": "customer-places-order", "name": "places", "from": {"entityId": "Customer"},
+                   "to": {"entityId": "Order"}, "cardinality": {"from": "1", "to": "0..n"}})
+        );
+        assert_eq!(
+            model["invariants"][0],
+            json!({"id": "totalPositif", "name": "Total positif", "type": "domain_constraint",
+                   "description": "Le total ne peut pas être négatif", "expression": "Order.total >= 0", "severity": "error"})
+        );
+    }
+
+    #[test]
+    fn parses_hand_written_docs() {
+        let markdown = "## Entités métier\n\n### Ligne de commande\n\n- **quantité** : entier (requis)\n- prix unitaire (decimal)\n\n## Règles\n\n- Une commande a au moins une ligne\n\n## Flux de paiement\n\nLe paiement est capturé à l'expédition.\n";
+        let import = parse_markdown(markdown);
+
+        assert_eq!(import.model["entities"][0]["id"], "LigneDeCommande");
+        assert_eq!(
+            import.model["entities"][0]["attributes"],
+            json!([{"name": "quantite", "type": "integer", "required": true}, {"name": "prixUnitaire", "type": "number"}])
+        );
+        assert_eq!(import.model["invariants"][0]["expression"], "Une commande a au moins une ligne");
+        assert_eq!(import.prose, vec![("Flux de paiement".to_string(), "Le paiement est capturé à l'expédition.".to_string())]);
+    }
+
+    #[test]
+    fn cardinality_formats() {
+        assert_eq!(parse_cardinality("0..1..1..n"), Some(("0..1".to_string(), "1..n".to_string())));
+        assert_eq!(parse_cardinality("1 → 0..*"), Some(("1".to_string(), "0..n".to_string())));
+        assert_eq!(parse_cardinality("1:n"), Some(("1".to_string(), "*".to_string())));
+        assert_eq!(parse_cardinality("beaucoup"), None);
+    }
+
+    #[test]
+    fn llm_additions_never_override() {
+        let mut model = parse_markdown(EMITTED).model;
+        let extracted = json!({"entities": [{"id": "Customer", "name": "X", "attributes": []}, {"id": "Payment", "name": "Payment", "attributes": []}]});
+        assert_eq!(merge_extracted(&mut model, &extracted), vec!["Payment"]);
+        assert_eq!(model["entities"][0]["name"], "Customer");
+    }
+}
@@ -0,0 +1,734 @@
+//! Normalization passes applied to LLM-generated models before validation
+//! and emission.
+//!
+//! LLMs describe the same relation in either direction ("Order belongs_to
+//! Customer" vs "Customer has Orders"). The relation pass rewrites them so
+//! that they read parent → child with an active verb, flipping ends and
+//! cardinalities together. Every rewrite is reported so callers can surface
+//! it as a fixup.
+//!
+//! The naming pass enforces a configurable policy (singular PascalCase
+//! entity ids, camelCase or snake_case attributes, kebab or snake relation
+//! ids) and propagates renames to every reference in the model.
+
+use anyhow::Result;
+use inflector::Inflector;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+
+use crate::{DomainModel, Relation};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationConventions {
+    /// Relations point from the "one" side (parent) to the "many" side (child).
+    #[serde(default = "default_true")]
+    pub parent_to_child: bool,
+    /// Passive verbs ("belongs_to", "écrit par") are replaced by their active form.
+    #[serde(default = "default_true")]
+    pub active_verbs: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for RelationConventions {
+    fn default() -> Self {
+        Self {
+            parent_to_child: true,
+            active_verbs: true,
+        }
+    }
+}
+
+/// Passive (child → parent) verbs and their active (parent → child) form.
+const PASSIVE_VERBS: &[(&str, &str)] = &[
+    ("belongs_to", "has"),
+    ("is_part_of", "contains"),
+    ("part_of", "contains"),
+    ("owned_by", "owns"),
+    ("placed_by", "places"),
+    ("written_by", "writes"),
+    ("created_by", "creates"),
+    ("managed_by", "manages"),
+    ("held_by", "holds"),
+    ("contained_in", "contains"),
+    ("appartient_a", "possede"),
+    ("fait_partie_de", "contient"),
+    ("ecrit_par", "ecrit"),
+    ("cree_par", "cree"),
+    ("gere_par", "gere"),
+    ("passe_par", "passe"),
+    ("detenu_par", "detient"),
+];
+
+/// Possession verbs that only make sense from the parent side.
+const POSSESSION_VERBS: &[&str] = &["has", "have", "contains", "owns", "possede", "contient", "a"];
+
+/// Lowercase, strip accents and the "is_"/"est_" prefix, and join words with '_'.
+fn verb_key(name: &str) -> String {
+    let folded: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'à' | 'â' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'î' | 'ï' => 'i',
+            'ô' | 'ö' => 'o',
+            'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            c if c.is_alphanumeric() => c,
+            _ => '_',
+        })
+        .collect();
+    let key = folded
+        .split('_')
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    for prefix in ["is_", "est_"] {
+        if let Some(rest) = key.strip_prefix(prefix) {
+            if PASSIVE_VERBS.iter().any(|(p, _)| *p == rest) {
+                return rest.to_string();
+            }
+        }
+    }
+    key
+}
+
+fn is_many(cardinality: &str) -> bool {
+    matches!(cardinality, "0..n" | "1..n" | "*" | "n" | "N")
+}
+
+fn is_one(cardinality: &str) -> bool {
+    matches!(cardinality, "1" | "0..1")
+}
+
+fn flip(relation: &mut Relation) {
+    std::mem::swap(&mut relation.from, &mut relation.to);
+    std::mem::swap(&mut relation.cardinality.from, &mut relation.cardinality.to);
+}
+
+/// The relation read parent → child with its active verb key, however it was written
+/// ("Order belongs_to Customer" becomes "Customer has Order"). Used to compare relations.
+pub(crate) fn active_form(relation: &Relation) -> (Relation, String) {
+    let mut active = relation.clone();
+    let key = verb_key(&relation.name);
+    match PASSIVE_VERBS.iter().find(|(p, _)| *p == key) {
+        Some((_, verb)) => {
+            flip(&mut active);
+            (active, verb.to_string())
+        }
+        None => (active, key),
+    }
+}
+
+/// Whether the verb key is a possession verb ("has", "contains"...), which only reads parent → child
+pub(crate) fn is_possession(verb: &str) -> bool {
+    POSSESSION_VERBS.contains(&verb)
+}
+
+/// Normalize relation directions and verbs according to `conventions`.
+///
+/// A relation is flipped when its verb reads child → parent (known passive
+/// verb), or when a possession verb points from the many side. Relations
+/// with other verbs are left untouched since flipping them would change
+/// their meaning. Relation ids are kept stable.
+///
+/// Returns the normalized model, the change log and the indices of flipped
+/// relations.
+pub fn normalize_relations(
+    model: &DomainModel,
+    conventions: &RelationConventions,
+) -> (DomainModel, Vec<String>, Vec<usize>) {
+    let mut normalized = model.clone();
+    let mut changes = Vec::new();
+    let mut flipped = Vec::new();
+
+    for (index, relation) in normalized.relations.iter_mut().enumerate() {
+        let key = verb_key(&relation.name);
+        let passive = PASSIVE_VERBS.iter().find(|(p, _)| *p == key).map(|(_, a)| *a);
+        let before = format!(
+            "'{}' ({} → {})",
+            relation.name, relation.from.entity_id, relation.to.entity_id
+        );
+
+        let child_to_parent = passive.is_some()
+            || (POSSESSION_VERBS.contains(&key.as_str())
+                && is_many(&relation.cardinality.from)
+                && is_one(&relation.cardinality.to));
+
+        let should_flip = conventions.parent_to_child && child_to_parent;
+        if should_flip {
+            flip(relation);
+            flipped.push(index);
+        }
+
+        // Renaming a passive verb without flipping would invert its meaning.
+        let rename = if should_flip && conventions.active_verbs { passive } else { None };
+        if let Some(active) = rename {
+            relation.name = active.to_string();
+        }
+
+        if should_flip || rename.is_some() {
+            changes.push(format!(
+                "Relation '{}': {} normalized to '{}' ({} → {})",
+                relation.id,
+                before,
+                relation.name,
+                relation.from.entity_id,
+                relation.to.entity_id
+            ));
+        }
+    }
+
+    (normalized, changes, flipped)
+}
+
+/// Tool entry point: returns the normalized model and the list of changes.
+///
+/// `original` is the model as received; fields the typed model does not know
+/// about (invariant scopes...) are carried over.
+pub fn normalize_relations_tool(original: &Value, conventions: &RelationConventions) -> Result<Value> {
+    let model: DomainModel = serde_json::from_value(original.clone())?;
+    let (normalized, changes, flipped) = normalize_relations(&model, conventions);
+
+    // Swap the original ends too so that end-level extras (e.g. `attribute`)
+    // follow their entity.
+    let mut original = original.clone();
+    for index in flipped {
+        if let Some(relation) = original["relations"].get_mut(index).and_then(|r| r.as_object_mut()) {
+            let from = relation.remove("from");
+            let to = relation.remove("to");
+            if let Some(to) = to {
+                relation.insert("from".to_string(), to);
+            }
+            if let Some(from) = from {
+                relation.insert("to".to_string(), from);
+            }
+        }
+    }
+
+    Ok(json!({
+        "model": overlay(&original, &serde_json::to_value(&normalized)?),
+        "changes": changes
+    }))
+}
+
+/// Deep-merge `typed` over `original`: keys present in `typed` win, keys only
+/// present in `original` are kept, arrays of equal length merge item by item.
+/// Normalization passes never add or remove items, so indices line up.
+pub fn overlay(original: &Value, typed: &Value) -> Value {
+    match (original, typed) {
+        (Value::Object(orig), Value::Object(new)) => {
+            let mut merged = orig.clone();
+            for (key, value) in new {
+                let value = match orig.get(key) {
+                    Some(previous) => overlay(previous, value),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), value);
+            }
+            Value::Object(merged)
+        }
+        (Value::Array(orig), Value::Array(new)) if orig.len() == new.len() => Value::Array(
+            orig.iter().zip(new).map(|(o, n)| overlay(o, n)).collect(),
+        ),
+        _ => typed.clone(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeCase {
+    #[default]
+    Camel,
+    Snake,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationIdCase {
+    #[default]
+    Kebab,
+    Snake,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamingPolicy {
+    /// Entity ids are singular (PascalCase is always enforced).
+    #[serde(default = "default_true")]
+    pub singular_entities: bool,
+    #[serde(default)]
+    pub attribute_case: AttributeCase,
+    #[serde(default)]
+    pub relation_id_case: RelationIdCase,
+}
+
+impl Default for NamingPolicy {
+    fn default() -> Self {
+        Self {
+            singular_entities: true,
+            attribute_case: AttributeCase::default(),
+            relation_id_case: RelationIdCase::default(),
+        }
+    }
+}
+
+/// Old → new identifiers produced by [`enforce_naming`].
+#[derive(Debug, Default)]
+pub struct Renames {
+    pub entities: HashMap<String, String>,
+    /// Keyed by the entity's *original* id, then the original attribute name.
+    pub attributes: HashMap<String, HashMap<String, String>>,
+    pub relations: HashMap<String, String>,
+}
+
+impl Renames {
+    fn entity<'a>(&'a self, id: &'a str) -> &'a str {
+        self.entities.get(id).map(String::as_str).unwrap_or(id)
+    }
+
+    fn attribute<'a>(&'a self, entity_id: &str, name: &'a str) -> &'a str {
+        self.attributes
+            .get(entity_id)
+            .and_then(|m| m.get(name))
+            .map(String::as_str)
+            .unwrap_or(name)
+    }
+
+    fn relation<'a>(&'a self, id: &'a str) -> &'a str {
+        self.relations.get(id).map(String::as_str).unwrap_or(id)
+    }
+}
+
+/// Words that end in "s" but are already singular.
+fn looks_singular(word: &str) -> bool {
+    let lower = word.to_lowercase();
+    ["us", "ss", "is", "os"].iter().any(|suffix| lower.ends_with(suffix))
+}
+
+/// Singularize the last word of a PascalCase identifier.
+fn singularize_pascal(id: &str) -> String {
+    let split = id
+        .char_indices()
+        .rfind(|(_, c)| c.is_uppercase())
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let (head, last) = id.split_at(split);
+    if looks_singular(last) {
+        return id.to_string();
+    }
+    let singular = if last.is_ascii() {
+        last.to_singular()
+    } else {
+        // Inflection rules are English-only; for other words drop the plural mark.
+        last.strip_suffix('s').or_else(|| last.strip_suffix('x')).unwrap_or(last).to_string()
+    };
+    format!("{}{}", head, singular.to_pascal_case())
+}
+
+fn entity_id_for(id: &str, policy: &NamingPolicy) -> String {
+    let pascal = id.to_pascal_case();
+    if policy.singular_entities {
+        singularize_pascal(&pascal)
+    } else {
+        pascal
+    }
+}
+
+fn attribute_name_for(name: &str, policy: &NamingPolicy) -> String {
+    match policy.attribute_case {
+        AttributeCase::Camel => name.to_camel_case(),
+        AttributeCase::Snake => name.to_snake_case(),
+    }
+}
+
+fn relation_id_for(id: &str, policy: &NamingPolicy) -> String {
+    match policy.relation_id_case {
+        RelationIdCase::Kebab => id.to_kebab_case(),
+        RelationIdCase::Snake => id.to_snake_case(),
+    }
+}
+
+/// Rename `Entity` and `Entity.attribute` references inside an expression.
+fn rename_expression(expression: &str, renames: &Renames) -> String {
+    let mut out = String::with_capacity(expression.len());
+    let mut token = String::new();
+    // Original identifier right before a '.', i.e. the owner of the next token.
+    let mut owner: Option<String> = None;
+    let mut last_token: Option<String> = None;
+
+    let emit = |token: &mut String, owner: &Option<String>, out: &mut String| -> Option<String> {
+        if token.is_empty() {
+            return None;
+        }
+        let renamed = match owner {
+            Some(entity) => renames.attribute(entity, token),
+            None => renames.entity(token),
+        };
+        out.push_str(renamed);
+        Some(std::mem::take(token))
+    };
+
+    for c in expression.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            token.push(c);
+            continue;
+        }
+        if let Some(t) = emit(&mut token, &owner, &mut out) {
+            last_token = Some(t);
+        }
+        owner = if c == '.' { last_token.take() } else { None };
+        last_token = None;
+        out.push(c);
+    }
+    emit(&mut token, &owner, &mut out);
+    out
+}
+
+/// Enforce `policy` on identifiers and propagate renames to references
+/// (relation ends, primary keys, layout hints, invariant expressions).
+///
+/// Identifiers with non-ASCII characters are left as is and reported, as
+/// case conversion would mangle them. A rename that would collide with an
+/// existing identifier is skipped and reported.
+pub fn enforce_naming(model: &DomainModel, policy: &NamingPolicy) -> (DomainModel, Vec<String>, Renames) {
+    let mut fixups = Vec::new();
+    let mut renames = Renames::default();
+
+    let mut taken: HashSet<String> = model.entities.iter().map(|e| e.id.clone()).collect();
+    for entity in &model.entities {
+        if !entity.id.is_ascii() {
+            fixups.push(format!("Entity '{}' left as is: non-ASCII identifier", entity.id));
+            continue;
+        }
+        let target = entity_id_for(&entity.id, policy);
+        if target == entity.id {
+            continue;
+        }
+        if taken.contains(&target) {
+            fixups.push(format!("Entity '{}' not renamed to '{}': identifier already used", entity.id, target));
+            continue;
+        }
+        taken.remove(&entity.id);
+        taken.insert(target.clone());
+        fixups.push(format!("Entity '{}' renamed to '{}'", entity.id, target));
+        renames.entities.insert(entity.id.clone(), target);
+    }
+
+    for entity in &model.entities {
+        let mut names: HashSet<String> = entity.attributes.iter().map(|a| a.name.clone()).collect();
+        let mut map = HashMap::new();
+        for attr in &entity.attributes {
+            if !attr.name.is_ascii() {
+                fixups.push(format!("Attribute '{}.{}' left as is: non-ASCII identifier", entity.id, attr.name));
+                continue;
+            }
+            let target = attribute_name_for(&attr.name, policy);
+            if target == attr.name {
+                continue;
+            }
+            if names.contains(&target) {
+                fixups.push(format!("Attribute '{}.{}' not renamed to '{}': identifier already used", entity.id, attr.name, target));
+                continue;
+            }
+            names.remove(&attr.name);
+            names.insert(target.clone());
+            fixups.push(format!("Attribute '{}.{}' renamed to '{}'", entity.id, attr.name, target));
+            map.insert(attr.name.clone(), target);
+        }
+        if !map.is_empty() {
+            renames.attributes.insert(entity.id.clone(), map);
+        }
+    }
+
+    let mut taken: HashSet<String> = model.relations.iter().map(|r| r.id.clone()).collect();
+    for relation in &model.relations {
+        if !relation.id.is_ascii() {
+            fixups.push(format!("Relation id '{}' left as is: non-ASCII identifier", relation.id));
+            continue;
+        }
+        let target = relation_id_for(&relation.id, policy);
+        if target == relation.id {
+            continue;
+        }
+        if taken.contains(&target) {
+            fixups.push(format!("Relation id '{}' not renamed to '{}': identifier already used", relation.id, target));
+            continue;
+        }
+        taken.remove(&relation.id);
+        taken.insert(target.clone());
+        fixups.push(format!("Relation id '{}' renamed to '{}'", relation.id, target));
+        renames.relations.insert(relation.id.clone(), target);
+    }
+
+    let mut normalized = model.clone();
+    for entity in &mut normalized.entities {
+        let original_id = entity.id.clone();
+        for attr in &mut entity.attributes {
+            attr.name = renames.attribute(&original_id, &attr.name).to_string();
+        }
+        if let Some(pk) = entity.primary_key.as_mut() {
+            for name in pk.iter_mut() {
+                *name = renames.attribute(&original_id, name).to_string();
+            }
+        }
+        for constraint in entity.unique_constraints.iter_mut().flatten() {
+            for name in constraint.attributes.iter_mut() {
+                *name = renames.attribute(&original_id, name).to_string();
+            }
+        }
+        entity.id = renames.entity(&original_id).to_string();
+    }
+    for relation in &mut normalized.relations {
+        relation.id = renames.relation(&relation.id).to_string();
+        for end in [&mut relation.from, &mut relation.to] {
+            if let Some(attribute) = end.attribute.as_mut() {
+                *attribute = renames.attribute(&end.entity_id, attribute).to_string();
+            }
+            end.entity_id = renames.entity(&end.entity_id).to_string();
+        }
+    }
+    for invariant in &mut normalized.invariants {
+        invariant.expression = rename_expression(&invariant.expression, &renames);
+    }
+    if let Some(layout) = normalized.layout.as_mut() {
+        for group in &mut layout.groups {
+            for id in group.entities.iter_mut() {
+                *id = renames.entity(id).to_string();
+            }
+        }
+        layout.ranks = std::mem::take(&mut layout.ranks)
+            .into_iter()
+            .map(|(id, rank)| (renames.entity(&id).to_string(), rank))
+            .collect();
+        for id in layout.hidden_relations.iter_mut() {
+            *id = renames.relation(id).to_string();
+        }
+    }
+
+    (normalized, fixups, renames)
+}
+
+/// Tool entry point for [`enforce_naming`]; also renames references held in
+/// fields the typed model does not know about (invariant scopes).
+pub fn enforce_naming_tool(original: &Value, policy: &NamingPolicy) -> Result<Value> {
+    let model: DomainModel = serde_json::from_value(original.clone())?;
+    let (normalized, fixups, renames) = enforce_naming(&model, policy);
+
+    let mut original = original.clone();
+    if let Some(invariants) = original["invariants"].as_array_mut() {
+        for invariant in invariants {
+            if let Some(scope) = invariant.get_mut("scope") {
+                rename_strings(&mut scope["entities"], |id| renames.entity(id).to_string());
+                rename_strings(&mut scope["relations"], |id| renames.relation(id).to_string());
+            }
+        }
+    }
+
+    Ok(json!({
+        "model": overlay(&original, &serde_json::to_value(&normalized)?),
+        "fixups": fixups
+    }))
+}
+
+/// Apply `rename` to a string or to every string of an array.
+fn rename_strings(value: &mut Value, rename: impl Fn(&str) -> String) {
+    match value {
+        Value::String(s) => *s = rename(s),
+        Value::Array(items) => {
+            for item in items {
+                if let Value::String(s) = item {
+                    *s = rename(s);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cardinality, RelationEnd};
+
+    fn relation(name: &str, from: &str, to: &str, card_from: &str, card_to: &str) -> Relation {
+        Relation {
+            id: format!("{}_{}", from.to_lowercase(), to.to_lowercase()),
+            name: name.to_string(),
+            description: None,
+            required: None,
+            cascade_delete: None,
+            from: RelationEnd { entity_id: from.to_string(), attribute: None, label: Some(from.to_lowercase()) },
+            to: RelationEnd { entity_id: to.to_string(), attribute: None, label: None },
+            cardinality: Cardinality { from: card_from.to_string(), to: card_to.to_string() },
+        }
+    }
+
+    fn model(relations: Vec<Relation>) -> DomainModel {
+        DomainModel {
+            entities: vec![],
+            relations,
+            invariants: vec![],
+            layout: None,
+        }
+    }
+
+    #[test]
+    fn passive_verb_is_flipped_with_cardinalities() {
+        let input = model(vec![relation("belongs_to", "Order", "Customer", "0..n", "1")]);
+        let (output, changes, _) = normalize_relations(&input, &RelationConventions::default());
+
+        let r = &output.relations[0];
+        assert_eq!(r.name, "has");
+        assert_eq!(r.from.entity_id, "Customer");
+        assert_eq!(r.to.entity_id, "Order");
+        assert_eq!(r.cardinality.from, "1");
+        assert_eq!(r.cardinality.to, "0..n");
+        assert_eq!(r.to.label.as_deref(), Some("order"), "Labels travel with their end");
+        assert_eq!(r.id, "order_customer", "Ids stay stable");
+        assert_eq!(changes.len(), 1);
+    }
+
+    #[test]
+    fn french_and_possession_verbs() {
+        let input = model(vec![
+            relation("est écrit par", "Livre", "Auteur", "0..n", "1..n"),
+            relation("has", "Exemplaire", "Livre", "0..n", "1"),
+            relation("references", "Order", "Product", "0..n", "1"),
+        ]);
+        let (output, changes, _) = normalize_relations(&input, &RelationConventions::default());
+
+        assert_eq!(output.relations[0].name, "ecrit");
+        assert_eq!(output.relations[0].from.entity_id, "Auteur");
+        assert_eq!(output.relations[1].from.entity_id, "Livre");
+        // Arbitrary active verbs keep their direction.
+        assert_eq!(output.relations[2].from.entity_id, "Order");
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn tool_keeps_unknown_fields() {
+        let original = json!({
+            "entities": [],
+            "relations": [{
+                "id": "order_customer",
+                "name": "belongs_to",
+                "from": {"entityId": "Order", "attribute": "customerId"},
+                "to": {"entityId": "Customer"},
+                "cardinality": {"from": "0..n", "to": "1"},
+                "cascadeDelete": true
+            }],
+            "invariants": []
+        });
+        let result = normalize_relations_tool(&original, &RelationConventions::default()).unwrap();
+        let relation = &result["model"]["relations"][0];
+        assert_eq!(relation["name"], "has");
+        assert_eq!(relation["from"]["entityId"], "Customer");
+        assert_eq!(relation["cascadeDelete"], true);
+        // End-level extras follow their entity through the flip.
+        assert_eq!(relation["to"]["entityId"], "Order");
+        assert_eq!(relation["to"]["attribute"], "customerId");
+    }
+
+    #[test]
+    fn conventions_can_be_disabled() {
+        let input = model(vec![relation("belongs_to", "Order", "Customer", "0..n", "1")]);
+        let conventions = RelationConventions { parent_to_child: false, active_verbs: true };
+        let (output, changes, _) = normalize_relations(&input, &conventions);
+
+        // Without flipping, renaming the passive verb would invert its meaning.
+        assert_eq!(output.relations[0].name, "belongs_to");
+        assert_eq!(output.relations[0].from.entity_id, "Order");
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn naming_policy_renames_and_propagates() {
+        let original = json!({
+            "entities": [
+                {
+                    "id": "order_lines",
+                    "name": "Order lines",
+                    "attributes": [{"name": "unit_price", "type": "number"}, {"name": "sku", "type": "string"}],
+                    "primaryKey": ["sku"],
+                    "uniqueConstraints": [{"name": "uq_price", "attributes": ["unit_price"]}]
+                },
+                {"id": "Status", "name": "Status", "attributes": [{"name": "code", "type": "string"}]}
+            ],
+            "relations": [{
+                "id": "status_lines",
+                "name": "qualifies",
+                "from": {"entityId": "Status"},
+                "to": {"entityId": "order_lines", "attribute": "unit_price"},
+                "cardinality": {"from": "1", "to": "0..n"}
+            }],
+            "invariants": [{
+                "id": "positive_price",
+                "name": "Positive price",
+                "type": "domain_constraint",
+                "expression": "order_lines.unit_price > 0",
+                "scope": {"entities": ["order_lines"], "relations": ["status_lines"]}
+            }]
+        });
+
+        let result = enforce_naming_tool(&original, &NamingPolicy::default()).unwrap();
+        let model = &result["model"];
+        assert_eq!(model["entities"][0]["id"], "OrderLine");
+        assert_eq!(model["entities"][0]["attributes"][0]["name"], "unitPrice");
+        assert_eq!(model["entities"][0]["uniqueConstraints"][0]["attributes"][0], "unitPrice");
+        assert_eq!(model["entities"][1]["id"], "Status", "Already singular");
+        assert_eq!(model["relations"][0]["id"], "status-lines");
+        assert_eq!(model["relations"][0]["to"]["entityId"], "OrderLine");
+        assert_eq!(model["relations"][0]["to"]["attribute"], "unitPrice");
+        assert_eq!(model["invariants"][0]["expression"], "OrderLine.unitPrice > 0");
+        assert_eq!(model["invariants"][0]["scope"]["entities"][0], "OrderLine");
+        assert_eq!(model["invariants"][0]["scope"]["relations"][0], "status-lines");
+        assert_eq!(result["fixups"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn naming_policy_snake_case_and_collisions() {
+        let original = json!({
+            "entities": [
+                {"id": "Livres", "name": "Livres", "attributes": [{"name": "datePublication", "type": "date"}]},
+                {"id": "Livre", "name": "Livre", "attributes": [{"name": "titre", "type": "string"}]}
+            ],
+            "relations": [],
+            "invariants": []
+        });
+        let policy = NamingPolicy {
+            attribute_case: AttributeCase::Snake,
+            relation_id_case: RelationIdCase::Snake,
+            ..Default::default()
+        };
+        let result = enforce_naming_tool(&original, &policy).unwrap();
+        assert_eq!(result["model"]["entities"][0]["id"], "Livres", "Would collide with Livre");
+        assert_eq!(result["model"]["entities"][0]["attributes"][0]["name"], "date_publication");
+        assert!(result["fixups"][0].as_str().unwrap().contains("already used"));
+    }
+
+    #[test]
+    fn naming_policy_reports_attribute_and_relation_collisions() {
+        let original = json!({
+            "entities": [
+                {"id": "Order", "name": "Order", "attributes": [{"name": "unit_price", "type": "number"}, {"name": "unitPrice", "type": "number"}]},
+                {"id": "Customer", "name": "Customer", "attributes": [{"name": "id", "type": "uuid"}]}
+            ],
+            "relations": [
+                {"id": "places_order", "name": "places", "from": {"entityId": "Customer"}, "to": {"entityId": "Order"}, "cardinality": {"from": "1", "to": "0..n"}},
+                {"id": "places-order", "name": "places", "from": {"entityId": "Customer"}, "to": {"entityId": "Order"}, "cardinality": {"from": "1", "to": "0..n"}}
+            ],
+            "invariants": []
+        });
+        let result = enforce_naming_tool(&original, &NamingPolicy::default()).unwrap();
+        assert_eq!(result["model"]["entities"][0]["attributes"][0]["name"], "unit_price");
+        assert_eq!(result["model"]["relations"][0]["id"], "places_order");
+        let fixups: Vec<&str> = result["fixups"].as_array().unwrap().iter().filter_map(|f| f.as_str()).collect();
+        assert!(fixups.contains(&"Attribute 'Order.unit_price' not renamed to 'unitPrice': identifier already used"));
+        assert!(fixups.contains(&"Relation id 'places_order' not renamed to 'places-order': identifier already used"));
+    }
+}
@@ -0,0 +1,241 @@
+//! Built-in relation rules run by `validate_model`.
+//!
+//! Relations are compared in their active, parent → child form (see
+//! `normalize::active_form`), so "Order belongs_to Customer" and "Customer
+//! has Order" are recognized as the same link. Three rules report findings
+//! with the same shape as the custom Rhai rules:
+//!
+//! - `duplicate-relation`: the same link stated twice (warning);
+//! - `contradictory-relations`: the same link with different cardinalities,
+//!   or two entities each owning the other (error);
+//! - `required-cycle`: entities that each require another one of the cycle
+//!   (minimum cardinality 1), so none can be created first. Error when the
+//!   cycle is made of exactly-one references across several relations,
+//!   warning when it goes through a "1..n" collection or a single mandatory
+//!   one-to-one relation.
+
+use std::collections::{BTreeSet, HashSet};
+
+use crate::custom_rules::{Finding, Severity};
+use crate::normalize::{active_form, is_possession};
+use crate::DomainModel;
+
+struct Edge<'a> {
+    /// Entity that cannot exist without `to`
+    from: &'a str,
+    to: &'a str,
+    relation: &'a str,
+    /// Exactly one, rather than at least one
+    exact: bool,
+}
+
+fn finding(rule: &str, severity: Severity, message: String, target: String) -> Finding {
+    Finding { rule: rule.to_string(), severity, message, target: Some(target) }
+}
+
+fn required(cardinality: &str) -> bool {
+    matches!(cardinality, "1" | "1..n")
+}
+
+fn reachable<'a>(start: &'a str, edges: &[Edge<'a>]) -> HashSet<&'a str> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        for edge in edges.iter().filter(|e| e.from == node) {
+            if seen.insert(edge.to) {
+                stack.push(edge.to);
+            }
+        }
+    }
+    seen
+}
+
+/// Strongly connected sets of entities that contain a cycle (self-references included)
+fn cycles<'a>(edges: &[Edge<'a>]) -> Vec<BTreeSet<&'a str>> {
+    let mut found: Vec<BTreeSet<&str>> = Vec::new();
+    for edge in edges {
+        let forward = reachable(edge.from, edges);
+        if !forward.contains(edge.from) || found.iter().any(|c| c.contains(edge.from)) {
+            continue;
+        }
+        let component = forward
+            .into_iter()
+            .filter(|node| reachable(node, edges).contains(edge.from))
+            .collect();
+        found.push(component);
+    }
+    found
+}
+
+/// Ids of the relations with both ends in `component`, in model order
+fn relations_within<'a>(component: &BTreeSet<&str>, edges: &[Edge<'a>]) -> Vec<&'a str> {
+    let mut relations = Vec::new();
+    for edge in edges.iter().filter(|e| component.contains(e.from) && component.contains(e.to)) {
+        if !relations.contains(&edge.relation) {
+            relations.push(edge.relation);
+        }
+    }
+    relations
+}
+
+fn quoted(ids: &[&str]) -> String {
+    ids.iter().map(|id| format!("'{}'", id)).collect::<Vec<_>>().join(", ")
+}
+
+pub fn check(model: &DomainModel) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    // Pairs of relations stating the same link
+    let active: Vec<_> = model.relations.iter().map(active_form).collect();
+    for (i, (a, verb_a)) in active.iter().enumerate() {
+        for (b, verb_b) in &active[i + 1..] {
+            let same_ends = a.from.entity_id == b.from.entity_id && a.to.entity_id == b.to.entity_id;
+            let target = format!("{}, {}", a.id, b.id);
+            if same_ends && verb_a == verb_b {
+                let (card_a, card_b) = (&a.cardinality, &b.cardinality);
+                if card_a.from == card_b.from && card_a.to == card_b.to {
+                    findings.push(finding(
+                        "duplicate-relation",
+                        Severity::Warning,
+                        format!("Relations '{}' and '{}' both state {} {} {}", a.id, b.id, a.from.entity_id, verb_a, a.to.entity_id),
+                        target,
+                    ));
+                } else {
+                    findings.push(finding(
+                        "contradictory-relations",
+                        Severity::Error,
+                        format!(
+                            "Relations '{}' and '{}' both state {} {} {} with different cardinalities ({} → {} vs {} → {})",
+                            a.id, b.id, a.from.entity_id, verb_a, a.to.entity_id, card_a.from, card_a.to, card_b.from, card_b.to
+                        ),
+                        target,
+                    ));
+                }
+            } else if a.from.entity_id == b.to.entity_id
+                && a.to.entity_id == b.from.entity_id
+                && a.from.entity_id != a.to.entity_id
+                && is_possession(verb_a)
+                && is_possession(verb_b)
+            {
+                findings.push(finding(
+                    "contradictory-relations",
+                    Severity::Error,
+                    format!(
+                        "Relations '{}' and '{}' make {} and {} each own the other",
+                        a.id, b.id, a.from.entity_id, a.to.entity_id
+                    ),
+                    target,
+                ));
+            }
+        }
+    }
+
+    // Dependencies: an entity requires the other end when its side has a minimum of 1
+    let known: HashSet<&str> = model.entities.iter().map(|e| e.id.as_str()).collect();
+    let mut edges = Vec::new();
+    for relation in &model.relations {
+        let (from, to) = (relation.from.entity_id.as_str(), relation.to.entity_id.as_str());
+        if !known.contains(from) || !known.contains(to) {
+            continue;
+        }
+        if required(&relation.cardinality.from) {
+            edges.push(Edge { from: to, to: from, relation: &relation.id, exact: relation.cardinality.from == "1" });
+        }
+        if required(&relation.cardinality.to) {
+            edges.push(Edge { from, to, relation: &relation.id, exact: relation.cardinality.to == "1" });
+        }
+    }
+    let exact_edges: Vec<Edge> = edges
+        .iter()
+        .filter(|e| e.exact)
+        .map(|e| Edge { from: e.from, to: e.to, relation: e.relation, exact: true })
+        .collect();
+    // A cycle is unconstructible when exactly-one references alone close it over several relations
+    let blocking: Vec<BTreeSet<&str>> = cycles(&exact_edges)
+        .into_iter()
+        .filter(|c| c.len() == 1 || relations_within(c, &exact_edges).len() > 1)
+        .collect();
+
+    for component in cycles(&edges) {
+        let relations = relations_within(&component, &edges);
+        let entities: Vec<&str> = component.iter().copied().collect();
+        let severity = if blocking.iter().any(|b| b.is_subset(&component)) {
+            Severity::Error
+        } else if component.len() == 1 || relations.len() > 1 {
+            Severity::Warning
+        } else if edges.iter().filter(|e| e.relation == relations[0]).all(|e| e.exact) {
+            // Mandatory one-to-one: both rows must be created together
+            Severity::Warning
+        } else {
+            // "1" ↔ "1..n" within one relation (an order and its lines) is built in one go
+            continue;
+        };
+        let message = match entities.as_slice() {
+            [entity] => format!(
+                "Entity {} requires another {} through relation {}: the first one cannot be created",
+                entity,
+                entity,
+                quoted(&relations)
+            ),
+            _ => format!(
+                "Entities {} require each other through relations {}: none can be created first",
+                entities.join(", "),
+                quoted(&relations)
+            ),
+        };
+        findings.push(finding("required-cycle", severity, message, entities.join(", ")));
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_duplicates_contradictions_and_required_cycles() {
+        let entity = |id: &str| json!({"id": id, "name": id, "attributes": [{"name": "id", "type": "uuid"}], "primaryKey": ["id"]});
+        let relation = |id: &str, name: &str, from: &str, to: &str, card_from: &str, card_to: &str| {
+            json!({"id": id, "name": name, "from": {"entityId": from}, "to": {"entityId": to},
+                   "cardinality": {"from": card_from, "to": card_to}})
+        };
+        let entities: Vec<_> = ["Customer", "Order", "OrderLine", "Account", "Employee"].map(entity).into();
+        let model: DomainModel = serde_json::from_value(json!({
+            "entities": entities,
+            "relations": [
+                relation("places", "places", "Customer", "Order", "1", "0..n"),
+                relation("placed-by", "placed_by", "Order", "Customer", "0..n", "1"),
+                relation("orders", "places", "Customer", "Order", "0..1", "0..n"),
+                relation("lines", "contains", "Order", "OrderLine", "1", "1..n"),
+                relation("owns", "owns", "Customer", "Account", "0..n", "1"),
+                relation("has-account", "has", "Account", "Customer", "0..n", "1"),
+                relation("manages", "manages", "Employee", "Employee", "1", "0..n")
+            ],
+            "invariants": []
+        }))
+        .unwrap();
+
+        let findings = check(&model);
+        let summary: Vec<(&str, Severity, &str)> = findings
+            .iter()
+            .map(|f| (f.rule.as_str(), f.severity, f.target.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("duplicate-relation", Severity::Warning, "places, placed-by"),
+                ("contradictory-relations", Severity::Error, "places, orders"),
+                ("contradictory-relations", Severity::Error, "placed-by, orders"),
+                ("contradictory-relations", Severity::Error, "owns, has-account"),
+                ("required-cycle", Severity::Error, "Account, Customer"),
+                ("required-cycle", Severity::Error, "Employee"),
+            ]
+        );
+        assert_eq!(
+            findings[4].message,
+            "Entities Account, Customer require each other through relations 'owns', 'has-account': none can be created first"
+        );
+    }
+}
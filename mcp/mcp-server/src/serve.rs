@@ -0,0 +1,205 @@
+//! Headless regeneration service (`mcp-cli serve`).
+//!
+//! Each watched project directory holds its interview transcripts (`*.jsonl`).
+//! When they change, or every night with `--schedule`, the model is generated
+//! again and its documentation is rewritten under `<project>/docs/`. Each
+//! regeneration that changes the model appends an entry to
+//! `docs/CHANGELOG.md`, listing the diff with the previous run.
+
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Output directory, relative to the project directory
+pub const DOCS_DIR: &str = "docs";
+pub const MODEL_FILE: &str = "model.json";
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+/// Transcripts fingerprint of the last run, so a restart does not regenerate everything
+const FINGERPRINT_FILE: &str = ".transcripts.sha256";
+
+/// Transcripts of a project, sorted by file name
+pub fn transcripts(project: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(project)
+        .with_context(|| format!("Failed to read project directory {:?}", project))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Hash of the transcripts' names and contents; changes whenever one is added,
+/// removed or edited. Generated docs are not part of it.
+pub fn fingerprint(project: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for path in transcripts(project)? {
+        hasher.update(path.file_name().unwrap_or_default().to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?);
+        hasher.update([0]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+pub fn last_fingerprint(project: &Path) -> Option<String> {
+    fs::read_to_string(project.join(DOCS_DIR).join(FINGERPRINT_FILE))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+pub fn record_fingerprint(project: &Path, fingerprint: &str) -> Result<()> {
+    let docs = project.join(DOCS_DIR);
+    fs::create_dir_all(&docs).with_context(|| format!("Failed to create {:?}", docs))?;
+    crate::safe_write::write(docs.join(FINGERPRINT_FILE), fingerprint)
+}
+
+/// Parse a nightly run time ("02:00")
+pub fn parse_time(value: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").with_context(|| format!("Invalid time '{}', expected HH:MM", value))
+}
+
+/// First occurrence of `at` strictly after `now`
+pub fn next_run(now: NaiveDateTime, at: NaiveTime) -> NaiveDateTime {
+    let today = now.date().and_time(at);
+    if today > now {
+        today
+    } else {
+        today + Duration::days(1)
+    }
+}
+
+fn ids(list: &Value) -> Vec<String> {
+    list.as_array()
+        .map(|items| items.iter().filter_map(|v| v.as_str()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Human-readable lines for one category of a `diff_models` result
+fn describe(diff: &Value, label: &str, lines: &mut Vec<String>) {
+    for id in ids(&diff["added"]) {
+        lines.push(format!("Added {} `{}`", label, id));
+    }
+    for id in ids(&diff["removed"]) {
+        lines.push(format!("Removed {} `{}`", label, id));
+    }
+    for modified in diff["modified"].as_array().into_iter().flatten() {
+        let id = modified.get("id").and_then(|v| v.as_str()).unwrap_or("?");
+        let mut changes = ids(&modified["fields"]);
+        let attributes = &modified["attributes"];
+        changes.extend(ids(&attributes["added"]).into_iter().map(|a| format!("+{}", a)));
+        changes.extend(ids(&attributes["removed"]).into_iter().map(|a| format!("-{}", a)));
+        changes.extend(
+            attributes["modified"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|a| a.get("name").and_then(|v| v.as_str()))
+                .map(|a| format!("~{}", a)),
+        );
+        lines.push(format!("Modified {} `{}` ({})", label, id, changes.join(", ")));
+    }
+}
+
+/// Changelog entry for a regeneration; `None` when the model did not change.
+/// `previous` is `None` on the first run.
+pub fn changelog_entry(timestamp: &str, reason: &str, previous: Option<&Value>, current: &Value, diff: &Value) -> Option<String> {
+    let count = |key: &str| current.get(key).and_then(|v| v.as_array()).map_or(0, |v| v.len());
+    let lines = match previous {
+        None => vec![format!(
+            "Initial model: {} entities, {} relations, {} invariants",
+            count("entities"),
+            count("relations"),
+            count("invariants")
+        )],
+        Some(_) if diff["identical"] == true => return None,
+        Some(_) => {
+            let mut lines = Vec::new();
+            describe(&diff["entities"], "entity", &mut lines);
+            describe(&diff["relations"], "relation", &mut lines);
+            describe(&diff["invariants"], "invariant", &mut lines);
+            lines
+        }
+    };
+    let mut entry = format!("## {} ({})\n\n", timestamp, reason);
+    for line in lines {
+        entry.push_str(&format!("- {}\n", line));
+    }
+    entry.push('\n');
+    Some(entry)
+}
+
+/// Insert `entry` at the top of the changelog, below its title
+pub fn prepend_entry(changelog: &str, entry: &str) -> String {
+    const TITLE: &str = "# Model Changelog\n\n";
+    let body = changelog.strip_prefix(TITLE).unwrap_or(changelog);
+    format!("{}{}{}", TITLE, entry, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    #[test]
+    fn test_next_run_is_tonight_or_tomorrow() {
+        let at = parse_time("02:00").unwrap();
+        let day = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        assert_eq!(next_run(day.and_hms_opt(1, 0, 0).unwrap(), at), day.and_hms_opt(2, 0, 0).unwrap());
+        assert_eq!(
+            next_run(day.and_hms_opt(2, 0, 0).unwrap(), at),
+            NaiveDate::from_ymd_opt(2024, 3, 11).unwrap().and_hms_opt(2, 0, 0).unwrap()
+        );
+        assert!(parse_time("25:00").is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_follows_transcripts_only() {
+        let dir = std::env::temp_dir().join(format!("serve_fingerprint_{}", std::process::id()));
+        fs::create_dir_all(dir.join(DOCS_DIR)).unwrap();
+        fs::write(dir.join("interview.jsonl"), "{\"speaker\":\"a\",\"text\":\"Un livre\"}\n").unwrap();
+        let first = fingerprint(&dir).unwrap();
+
+        fs::write(dir.join(DOCS_DIR).join(MODEL_FILE), "{}").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        assert_eq!(fingerprint(&dir).unwrap(), first);
+
+        fs::write(dir.join("interview2.jsonl"), "{\"speaker\":\"a\",\"text\":\"Un auteur\"}\n").unwrap();
+        assert_ne!(fingerprint(&dir).unwrap(), first);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_changelog_entries() {
+        let before = json!({"entities": [{"id": "Book", "attributes": [{"name": "title", "type": "string"}]}], "relations": [], "invariants": []});
+        let after = json!({
+            "entities": [
+                {"id": "Book", "attributes": [{"name": "title", "type": "text"}, {"name": "isbn", "type": "string"}]},
+                {"id": "Author", "attributes": []}
+            ],
+            "relations": [],
+            "invariants": [{"id": "isbn-unique", "name": "ISBN is unique"}]
+        });
+
+        let initial = changelog_entry("2024-03-10 02:00", "nightly", None, &before, &json!({})).unwrap();
+        assert!(initial.contains("Initial model: 1 entities, 0 relations, 0 invariants"));
+
+        let diff = crate::diff::diff_models(&before, &after);
+        let entry = changelog_entry("2024-03-11 02:00", "transcripts changed", Some(&before), &after, &diff).unwrap();
+        assert!(entry.starts_with("## 2024-03-11 02:00 (transcripts changed)\n\n"));
+        assert!(entry.contains("- Added entity `Author`\n"));
+        assert!(entry.contains("- Modified entity `Book` (+isbn, ~title)\n"));
+        assert!(entry.contains("- Added invariant `isbn-unique`\n"));
+
+        let same = crate::diff::diff_models(&after, &after);
+        assert!(changelog_entry("2024-03-12 02:00", "nightly", Some(&after), &after, &same).is_none());
+
+        let changelog = prepend_entry(&prepend_entry("", &initial), &entry);
+        assert!(changelog.starts_with("# Model Changelog\n\n## 2024-03-11"));
+        assert!(changelog.ends_with(&initial));
+    }
+}
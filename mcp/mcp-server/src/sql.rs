@@ -0,0 +1,283 @@
+//! SQL DDL emitter (PostgreSQL dialect).
+//!
+//! One table per entity, with snake_case table and column names. Relations
+//! become foreign keys on the child table (see `foreign_keys::parent_child`):
+//! the end attribute when one is named, otherwise a `<parent>_id` column typed
+//! like the referenced key. `required` makes the column NOT NULL and
+//! `cascadeDelete` adds ON DELETE CASCADE. Many-to-many relations get a join
+//! table. Foreign keys are added after every table exists, so the emission
+//! order does not matter. Attribute `defaultValue` becomes a DEFAULT clause,
+//! `validation` a CHECK constraint (custom rules are free text and stay out of
+//! the DDL) and `uniqueConstraints` composite UNIQUE constraints.
+
+use inflector::Inflector;
+use serde_json::Value;
+
+use crate::foreign_keys::{cascades, column_type, not_null, parent_child};
+
+fn sql_type(attr_type: &str) -> &'static str {
+    match column_type(attr_type) {
+        "uuid" => "UUID",
+        "integer" => "INTEGER",
+        "numeric" => "NUMERIC",
+        "boolean" => "BOOLEAN",
+        "date" => "DATE",
+        "timestamp" => "TIMESTAMP",
+        "json" => "JSONB",
+        _ => "TEXT",
+    }
+}
+
+fn ident(name: &str) -> String {
+    format!("\"{}\"", name.to_snake_case())
+}
+
+fn str_of<'a>(item: &'a Value, field: &str) -> &'a str {
+    item.get(field).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn attributes(entity: &Value) -> &[Value] {
+    entity.get("attributes").and_then(|a| a.as_array()).map(|a| a.as_slice()).unwrap_or(&[])
+}
+
+/// Column a foreign key references: the named attribute, the single-column primary key,
+/// the first unique attribute, or `id`
+fn referenced_key<'a>(entity: &'a Value, named: Option<&'a str>) -> (&'a str, &'a str) {
+    let primary_key: Vec<&str> = entity
+        .get("primaryKey")
+        .and_then(|pk| pk.as_array())
+        .map(|pk| pk.iter().filter_map(|k| k.as_str()).collect())
+        .unwrap_or_default();
+    let name = named
+        .or_else(|| if primary_key.len() == 1 { Some(primary_key[0]) } else { None })
+        .or_else(|| {
+            attributes(entity)
+                .iter()
+                .find(|a| a.get("unique").and_then(|u| u.as_bool()).unwrap_or(false))
+                .map(|a| str_of(a, "name"))
+        })
+        .unwrap_or("id");
+    let attr_type = attributes(entity).iter().find(|a| str_of(a, "name") == name).map_or("uuid", |a| str_of(a, "type"));
+    (name, attr_type)
+}
+
+fn sql_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("'{}'", s.replace('\'', "''")),
+        other => format!("'{}'", other.to_string().replace('\'', "''")),
+    }
+}
+
+/// CHECK condition of an attribute's `validation`, if it has any checkable bound
+fn check_condition(column: &str, validation: &Value) -> Option<String> {
+    let mut conditions = Vec::new();
+    for (field, operator) in [("min", ">="), ("max", "<=")] {
+        if let Some(bound) = validation.get(field).filter(|b| b.is_number()) {
+            conditions.push(format!("{} {} {}", column, operator, bound));
+        }
+    }
+    for (field, operator) in [("minLength", ">="), ("maxLength", "<=")] {
+        if let Some(bound) = validation.get(field).filter(|b| b.is_number()) {
+            conditions.push(format!("char_length({}) {} {}", column, operator, bound));
+        }
+    }
+    if let Some(pattern) = validation.get("pattern").and_then(|p| p.as_str()) {
+        conditions.push(format!("{} ~ {}", column, sql_literal(&Value::from(pattern))));
+    }
+    if let Some(values) = validation.get("enum").and_then(|e| e.as_array()).filter(|e| !e.is_empty()) {
+        let values: Vec<String> = values.iter().map(sql_literal).collect();
+        conditions.push(format!("{} IN ({})", column, values.join(", ")));
+    }
+    if conditions.is_empty() {
+        None
+    } else {
+        Some(conditions.join(" AND "))
+    }
+}
+
+struct ForeignKey {
+    name: String,
+    table: String,
+    column: String,
+    references: String,
+    referenced_column: String,
+    cascade: bool,
+}
+
+pub fn emit_sql(model: &Value) -> String {
+    let empty = Vec::new();
+    let entities = model.get("entities").and_then(|e| e.as_array()).unwrap_or(&empty);
+    let relations = model.get("relations").and_then(|r| r.as_array()).unwrap_or(&empty);
+    let entity = |id: &str| entities.iter().find(|e| str_of(e, "id") == id);
+
+    // Foreign key columns that are not attributes (table, column, type), and NOT NULL ones
+    let mut added: Vec<(String, String, &str)> = Vec::new();
+    let mut not_null_columns: Vec<(String, String)> = Vec::new();
+    let mut foreign_keys = Vec::new();
+    let mut join_tables = Vec::new();
+
+    for relation in relations {
+        let end_entity = |end: &str| relation.get(end).map(|e| str_of(e, "entityId")).and_then(entity);
+        let end_attribute = |end: &str| relation.get(end).and_then(|e| e.get("attribute")).and_then(|a| a.as_str());
+        let (Some(from), Some(to)) = (end_entity("from"), end_entity("to")) else {
+            continue;
+        };
+        let relation_id = str_of(relation, "id");
+
+        match parent_child(relation) {
+            Some((parent_end, child_end)) => {
+                let (parent, child) = if parent_end == "from" { (from, to) } else { (to, from) };
+                let (key, key_type) = referenced_key(parent, end_attribute(parent_end));
+                let table = ident(str_of(child, "id"));
+                let column = match end_attribute(child_end) {
+                    Some(attribute) => ident(attribute),
+                    None => {
+                        let mut column = ident(&format!("{}_id", str_of(parent, "id")));
+                        // Second relation to the same parent
+                        if added.iter().any(|(t, c, _)| *t == table && *c == column) {
+                            column = ident(&format!("{}_{}_id", relation_id, str_of(parent, "id")));
+                        }
+                        added.push((table.clone(), column.clone(), sql_type(key_type)));
+                        column
+                    }
+                };
+                if not_null(relation) {
+                    not_null_columns.push((table.clone(), column.clone()));
+                }
+                foreign_keys.push(ForeignKey {
+                    name: ident(&format!("fk_{}_{}", str_of(child, "id"), relation_id)),
+                    table,
+                    column,
+                    references: ident(str_of(parent, "id")),
+                    referenced_column: ident(key),
+                    cascade: cascades(relation),
+                });
+            }
+            None => {
+                // Join rows only exist for their two ends
+                let table = ident(&format!("{}_{}", str_of(from, "id"), str_of(to, "id")));
+                let mut columns = Vec::new();
+                for end in [from, to] {
+                    let (key, key_type) = referenced_key(end, None);
+                    let column = ident(&format!("{}_id", str_of(end, "id")));
+                    foreign_keys.push(ForeignKey {
+                        name: ident(&format!("fk_{}_{}", relation_id, str_of(end, "id"))),
+                        table: table.clone(),
+                        column: column.clone(),
+                        references: ident(str_of(end, "id")),
+                        referenced_column: ident(key),
+                        cascade: true,
+                    });
+                    columns.push((column, sql_type(key_type)));
+                }
+                join_tables.push((table, columns));
+            }
+        }
+    }
+
+    let mut sql = String::from("-- Generated from the domain model\n\n");
+    for entity in entities {
+        let table = ident(str_of(entity, "id"));
+        let mut lines = Vec::new();
+        for attr in attributes(entity) {
+            let column = ident(str_of(attr, "name"));
+            let mut line = format!("    {} {}", column, sql_type(str_of(attr, "type")));
+            let required = attr.get("required").and_then(|r| r.as_bool()).unwrap_or(false);
+            if required || not_null_columns.contains(&(table.clone(), column.clone())) {
+                line.push_str(" NOT NULL");
+            }
+            if attr.get("unique").and_then(|u| u.as_bool()).unwrap_or(false) {
+                line.push_str(" UNIQUE");
+            }
+            if let Some(default) = attr.get("defaultValue") {
+                line.push_str(&format!(" DEFAULT {}", sql_literal(default)));
+            }
+            if let Some(condition) = attr.get("validation").and_then(|v| check_condition(&column, v)) {
+                line.push_str(&format!(" CHECK ({})", condition));
+            }
+            lines.push(line);
+        }
+        for (_, column, column_type) in added.iter().filter(|(t, ..)| *t == table) {
+            let required = not_null_columns.contains(&(table.clone(), column.clone()));
+            lines.push(format!("    {} {}{}", column, column_type, if required { " NOT NULL" } else { "" }));
+        }
+        if let Some(pk) = entity.get("primaryKey").and_then(|pk| pk.as_array()) {
+            let columns: Vec<String> = pk.iter().filter_map(|k| k.as_str()).map(ident).collect();
+            lines.push(format!("    PRIMARY KEY ({})", columns.join(", ")));
+        }
+        for constraint in entity.get("uniqueConstraints").and_then(|c| c.as_array()).into_iter().flatten() {
+            let columns: Vec<String> = constraint
+                .get("attributes")
+                .and_then(|a| a.as_array())
+                .map(|a| a.iter().filter_map(|n| n.as_str()).map(ident).collect())
+                .unwrap_or_default();
+            if !columns.is_empty() {
+                lines.push(format!("    CONSTRAINT {} UNIQUE ({})", ident(str_of(constraint, "name")), columns.join(", ")));
+            }
+        }
+        sql.push_str(&format!("CREATE TABLE {} (\n{}\n);\n\n", table, lines.join(",\n")));
+    }
+    for (table, columns) in &join_tables {
+        let mut lines: Vec<String> = columns.iter().map(|(column, t)| format!("    {} {} NOT NULL", column, t)).collect();
+        let names: Vec<&str> = columns.iter().map(|(column, _)| column.as_str()).collect();
+        lines.push(format!("    PRIMARY KEY ({})", names.join(", ")));
+        sql.push_str(&format!("CREATE TABLE {} (\n{}\n);\n\n", table, lines.join(",\n")));
+    }
+    for fk in &foreign_keys {
+        sql.push_str(&format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}){};\n",
+            fk.table,
+            fk.name,
+            fk.column,
+            fk.references,
+            fk.referenced_column,
+            if fk.cascade { " ON DELETE CASCADE" } else { "" }
+        ));
+    }
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn emits_tables_and_foreign_keys() {
+        let model = json!({
+            "entities": [
+                {"id": "Customer", "primaryKey": ["id"], "attributes": [
+                    {"name": "id", "type": "uuid", "required": true}, {"name": "email", "type": "email", "unique": true}
+                ]},
+                {"id": "Order", "primaryKey": ["id"], "attributes": [
+                    {"name": "id", "type": "uuid", "required": true}, {"name": "total", "type": "number"},
+                    {"name": "status", "type": "string", "defaultValue": "draft",
+                     "validation": {"enum": ["draft", "paid"], "maxLength": 10}}
+                ]},
+                {"id": "OrderLine", "primaryKey": ["lineNo"], "attributes": [
+                    {"name": "lineNo", "type": "integer"}, {"name": "orderId", "type": "uuid"}
+                ], "uniqueConstraints": [{"name": "uqOrderLine", "attributes": ["orderId", "lineNo"]}]},
+                {"id": "Tag", "primaryKey": ["code"], "attributes": [{"name": "code", "type": "string"}]}
+            ],
+            "relations": [
+                {"id": "places", "from": {"entityId": "Customer"}, "to": {"entityId": "Order"},
+                 "cardinality": {"from": "0..1", "to": "0..n"}, "required": true},
+                {"id": "lines", "from": {"entityId": "Order"}, "to": {"entityId": "OrderLine", "attribute": "orderId"},
+                 "cardinality": {"from": "1", "to": "1..n"}, "cascadeDelete": true},
+                {"id": "tagged", "from": {"entityId": "Order"}, "to": {"entityId": "Tag"},
+                 "cardinality": {"from": "0..n", "to": "0..n"}}
+            ]
+        });
+
+        let sql = emit_sql(&model);
+        assert!(sql.contains("CREATE TABLE \"order\" (\n    \"id\" UUID NOT NULL,\n    \"total\" NUMERIC,\n    \"status\" TEXT DEFAULT 'draft' CHECK (char_length(\"status\") <= 10 AND \"status\" IN ('draft', 'paid')),\n    \"customer_id\" UUID NOT NULL,\n    PRIMARY KEY (\"id\")\n);"), "{}", sql);
+        assert!(sql.contains("    \"order_id\" UUID NOT NULL,\n"), "{}", sql);
+        assert!(sql.contains("    PRIMARY KEY (\"line_no\"),\n    CONSTRAINT \"uq_order_line\" UNIQUE (\"order_id\", \"line_no\")\n);"), "{}", sql);
+        assert!(sql.contains("ALTER TABLE \"order\" ADD CONSTRAINT \"fk_order_places\" FOREIGN KEY (\"customer_id\") REFERENCES \"customer\" (\"id\");"));
+        assert!(sql.contains("ALTER TABLE \"order_line\" ADD CONSTRAINT \"fk_order_line_lines\" FOREIGN KEY (\"order_id\") REFERENCES \"order\" (\"id\") ON DELETE CASCADE;"));
+        assert!(sql.contains("CREATE TABLE \"order_tag\" (\n    \"order_id\" UUID NOT NULL,\n    \"tag_id\" TEXT NOT NULL,\n    PRIMARY KEY (\"order_id\", \"tag_id\")\n);"), "{}", sql);
+    }
+}
@@ -0,0 +1,468 @@
+//! Traceability matrix for governance reviews.
+//!
+//! Maps each invariant of a model to the interview answers it comes from,
+//! the Gherkin scenarios covering it and the code implementing it. Matching
+//! is lexical: an explicit reference to the invariant id (a `@<id>` tag on a
+//! scenario, the id in a symbol name) always wins, otherwise the words of the
+//! invariant name and its scope entities have to show up in the text.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Extensions scanned when auditing a code directory
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "svelte", "py", "java", "kt", "cs", "go", "rb", "php", "scala", "swift", "sql",
+];
+
+/// Directories never worth auditing
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "vendor", "__pycache__"];
+
+/// Files above this size are skipped (generated or minified code)
+const MAX_CODE_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Code references kept per invariant
+const MAX_CODE_REFERENCES: usize = 10;
+
+/// Words too common to link an invariant to a text
+const STOP_WORDS: &[&str] = &[
+    "avec", "dans", "doit", "etre", "pour", "sans", "sont", "chaque", "tout", "toute", "plus", "moins", "must",
+    "with", "each", "every", "have", "should", "than", "that", "from", "only",
+];
+
+/// One interview answer (or any other source text)
+#[derive(Debug, Clone, Deserialize)]
+pub struct InterviewSource {
+    /// Label shown in the matrix (e.g. "Agrégats — Q2")
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub question: Option<String>,
+    pub answer: String,
+}
+
+/// A Gherkin feature file given inline
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeatureFile {
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone)]
+struct Scenario {
+    reference: String,
+    tags: Vec<String>,
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceabilityRow {
+    pub invariant: String,
+    pub name: String,
+    pub sources: Vec<String>,
+    pub tests: Vec<String>,
+    pub code: Vec<String>,
+    /// "traced" (sources, tests and code), "partial" or "untraced"
+    pub status: String,
+}
+
+/// Lowercase and strip accents, keeping only letters and digits
+fn squash(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| match c {
+            'à' | 'â' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'î' | 'ï' => 'i',
+            'ô' | 'ö' => 'o',
+            'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            c => c,
+        })
+        .collect()
+}
+
+/// Folded words of a text (camelCase split, plural mark dropped)
+fn words(text: &str) -> HashSet<String> {
+    let mut out = HashSet::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    let mut flush = |current: &mut String| {
+        let word = squash(current);
+        current.clear();
+        let word = match word.strip_suffix('s') {
+            Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+            _ => word,
+        };
+        if word.chars().count() > 3 && !STOP_WORDS.contains(&word.as_str()) {
+            out.insert(word);
+        }
+    };
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            flush(&mut current);
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower {
+            flush(&mut current);
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    flush(&mut current);
+    out
+}
+
+/// What identifies an invariant in free text
+struct InvariantKeys {
+    id: String,
+    squashed_id: String,
+    name_words: HashSet<String>,
+    entity_words: HashSet<String>,
+}
+
+impl InvariantKeys {
+    fn new(invariant: &Value) -> Self {
+        let id = invariant.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let name = invariant.get("name").and_then(|v| v.as_str()).unwrap_or_default();
+        let mut name_words = words(name);
+        name_words.extend(words(&id));
+        let entity_words = invariant
+            .pointer("/scope/entities")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.as_str())
+            .flat_map(words)
+            .collect();
+        Self { squashed_id: squash(&id), id, name_words, entity_words }
+    }
+
+    /// Free text mentioning enough of the invariant's words
+    fn mentioned_in(&self, text: &str) -> bool {
+        if self.name_words.is_empty() {
+            return false;
+        }
+        let text_words = words(text);
+        let hits = self.name_words.intersection(&text_words).count();
+        let entity_hit = self.entity_words.intersection(&text_words).next().is_some();
+        let needed = self.name_words.len().min(2) - usize::from(entity_hit && self.name_words.len() > 1);
+        hits >= needed
+    }
+
+    /// Explicit reference to the id, ignoring case and separators
+    fn referenced_in(&self, text: &str) -> bool {
+        self.squashed_id.chars().count() > 3 && squash(text).contains(&self.squashed_id)
+    }
+}
+
+/// Split feature files into scenarios, tags of the feature included
+fn parse_features(features: &[FeatureFile]) -> Vec<Scenario> {
+    const SCENARIO_KEYWORDS: &[&str] = &[
+        "Scenario Outline:", "Scenario Template:", "Scenario:", "Example:", "Plan du scénario:", "Plan du Scénario:",
+        "Scénario:", "Exemple:",
+    ];
+    let mut scenarios = Vec::new();
+    for feature in features {
+        let mut feature_tags: Vec<String> = Vec::new();
+        let mut pending_tags: Vec<String> = Vec::new();
+        let mut current: Option<Scenario> = None;
+        for (index, line) in feature.content.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('@') {
+                pending_tags.extend(trimmed.split_whitespace().map(|t| t.trim_start_matches('@').to_string()));
+            } else if trimmed.starts_with("Feature:") || trimmed.starts_with("Fonctionnalité:") {
+                feature_tags = std::mem::take(&mut pending_tags);
+            } else if let Some(title) = SCENARIO_KEYWORDS.iter().find_map(|k| trimmed.strip_prefix(k)) {
+                scenarios.extend(current.take());
+                let mut tags = feature_tags.clone();
+                tags.append(&mut pending_tags);
+                current = Some(Scenario {
+                    reference: format!("{}:{} {}", feature.path, index + 1, title.trim()),
+                    tags,
+                    text: title.to_string(),
+                });
+            } else if let Some(scenario) = current.as_mut() {
+                if !trimmed.starts_with('#') {
+                    scenario.text.push('\n');
+                    scenario.text.push_str(trimmed);
+                }
+            }
+        }
+        scenarios.extend(current);
+    }
+    scenarios
+}
+
+/// Read every `.feature` file under `dir`
+pub fn read_features(dir: &Path) -> Result<Vec<FeatureFile>> {
+    let mut files = Vec::new();
+    walk(dir, &mut |path| {
+        if path.extension().is_some_and(|e| e == "feature") {
+            let content = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+            let relative = path.strip_prefix(dir).unwrap_or(path);
+            files.push(FeatureFile { path: relative.display().to_string(), content });
+        }
+        Ok(())
+    })?;
+    Ok(files)
+}
+
+fn walk(dir: &Path, visit: &mut dyn FnMut(&Path) -> Result<()>) -> Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory {:?}", dir))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    entries.sort();
+    for path in entries {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name) {
+                walk(&path, visit)?;
+            }
+        } else {
+            visit(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Audit a code directory: `path:line` of each line naming an invariant id
+fn audit_code(dir: &Path, keys: &[InvariantKeys]) -> Result<Vec<Vec<String>>> {
+    let mut hits = vec![Vec::new(); keys.len()];
+    walk(dir, &mut |path| {
+        let is_code = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| CODE_EXTENSIONS.contains(&e));
+        let small = fs::metadata(path).map(|m| m.len() <= MAX_CODE_FILE_BYTES).unwrap_or(false);
+        if !is_code || !small {
+            return Ok(());
+        }
+        // Binary or non UTF-8 files are not code we can audit
+        let Ok(content) = fs::read_to_string(path) else { return Ok(()) };
+        let relative = path.strip_prefix(dir).unwrap_or(path).display().to_string();
+        for (number, line) in content.lines().enumerate() {
+            for (key, found) in keys.iter().zip(hits.iter_mut()) {
+                if found.len() < MAX_CODE_REFERENCES && key.referenced_in(line) {
+                    found.push(format!("{}:{}", relative, number + 1));
+                }
+            }
+        }
+        Ok(())
+    })?;
+    Ok(hits)
+}
+
+/// Build one row per invariant of `model`
+pub fn build_matrix(
+    model: &Value,
+    sources: &[InterviewSource],
+    features: &[FeatureFile],
+    code_dir: Option<&Path>,
+) -> Result<Vec<TraceabilityRow>> {
+    let invariants = model.get("invariants").and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[]);
+    let keys: Vec<InvariantKeys> = invariants.iter().map(InvariantKeys::new).collect();
+    let scenarios = parse_features(features);
+    let code = match code_dir {
+        Some(dir) => audit_code(dir, &keys)?,
+        None => vec![Vec::new(); keys.len()],
+    };
+
+    let rows = invariants
+        .iter()
+        .zip(&keys)
+        .zip(code)
+        .map(|((invariant, key), code)| {
+            let sources: Vec<String> = sources
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| {
+                    let text = format!("{}\n{}", s.question.as_deref().unwrap_or_default(), s.answer);
+                    key.referenced_in(&s.answer) || key.mentioned_in(&text)
+                })
+                .map(|(i, s)| s.source.clone().unwrap_or_else(|| format!("Réponse {}", i + 1)))
+                .collect();
+            let tests: Vec<String> = scenarios
+                .iter()
+                .filter(|s| {
+                    let tagged = s.tags.iter().any(|t| {
+                        let tag = t.strip_prefix("invariant:").or_else(|| t.strip_prefix("inv:")).unwrap_or(t);
+                        squash(tag) == key.squashed_id
+                    });
+                    tagged || key.mentioned_in(&s.text)
+                })
+                .map(|s| s.reference.clone())
+                .collect();
+            let covered = [!sources.is_empty(), !tests.is_empty(), !code.is_empty()];
+            let status = if covered.iter().all(|c| *c) {
+                "traced"
+            } else if covered.iter().any(|c| *c) {
+                "partial"
+            } else {
+                "untraced"
+            };
+            TraceabilityRow {
+                invariant: key.id.clone(),
+                name: invariant.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                sources,
+                tests,
+                code,
+                status: status.to_string(),
+            }
+        })
+        .collect();
+    Ok(rows)
+}
+
+fn markdown_cell(items: &[String]) -> String {
+    if items.is_empty() {
+        "—".to_string()
+    } else {
+        items.iter().map(|i| i.replace('|', "\\|")).collect::<Vec<_>>().join("<br>")
+    }
+}
+
+pub fn to_markdown(rows: &[TraceabilityRow]) -> String {
+    let mut markdown = String::from("# Matrice de traçabilité\n\n");
+    if rows.is_empty() {
+        markdown.push_str("*Aucun invariant défini.*\n");
+        return markdown;
+    }
+    markdown.push_str("| Invariant | Nom | Sources (entretien) | Tests Gherkin | Code | Statut |\n");
+    markdown.push_str("|-----------|-----|---------------------|---------------|------|--------|\n");
+    for row in rows {
+        markdown.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} | {} |\n",
+            row.invariant,
+            row.name.replace('|', "\\|"),
+            markdown_cell(&row.sources),
+            markdown_cell(&row.tests),
+            markdown_cell(&row.code),
+            row.status
+        ));
+    }
+    markdown
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', ';']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// CSV with `; `-separated references in each cell
+pub fn to_csv(rows: &[TraceabilityRow]) -> String {
+    let mut csv = String::from("invariant,name,sources,tests,code,status\n");
+    for row in rows {
+        let fields = [
+            row.invariant.clone(),
+            row.name.clone(),
+            row.sources.join("; "),
+            row.tests.join("; "),
+            row.code.join("; "),
+            row.status.clone(),
+        ];
+        csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// `traceability_matrix` tool: rows, coverage summary, markdown and CSV
+pub fn traceability_matrix_tool(
+    model: &Value,
+    sources: &[InterviewSource],
+    features: &[FeatureFile],
+    code_dir: Option<&Path>,
+) -> Result<Value> {
+    let rows = build_matrix(model, sources, features, code_dir)?;
+    let count = |f: fn(&TraceabilityRow) -> bool| rows.iter().filter(|r| f(r)).count();
+    Ok(json!({
+        "rows": rows,
+        "summary": {
+            "invariants": rows.len(),
+            "with_sources": count(|r| !r.sources.is_empty()),
+            "with_tests": count(|r| !r.tests.is_empty()),
+            "with_code": count(|r| !r.code.is_empty()),
+            "traced": count(|r| r.status == "traced"),
+            "code_audited": code_dir.is_some()
+        },
+        "markdown": to_markdown(&rows),
+        "csv": to_csv(&rows)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> Value {
+        json!({
+            "entities": [],
+            "relations": [],
+            "invariants": [
+                {"id": "uniqueCustomerEmail", "name": "Email client unique", "type": "uniqueness",
+                 "expression": "unique(Customer.email)", "scope": {"entities": ["Customer"]}},
+                {"id": "positiveOrderTotal", "name": "Total de commande positif", "type": "domain_constraint",
+                 "expression": "Order.total >= 0"}
+            ]
+        })
+    }
+
+    fn sources() -> Vec<InterviewSource> {
+        vec![
+            InterviewSource {
+                source: Some("Invariants — Q1".to_string()),
+                question: Some("Quelles règles sur les clients ?".to_string()),
+                answer: "Un client ne peut pas avoir le même email qu'un autre client.".to_string(),
+            },
+            InterviewSource {
+                source: None,
+                question: None,
+                answer: "Le total d'une commande est toujours positif.".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn maps_sources_and_scenarios() {
+        let features = vec![FeatureFile {
+            path: "orders.feature".to_string(),
+            content: "Feature: Commandes\n\n  @uniqueCustomerEmail\n  Scenario: Inscription en double\n    Given un client existant\n\n  Scenario: Commande négative\n    When le total de la commande est -5\n    Then la commande est refusée car le total doit rester positif\n".to_string(),
+        }];
+        let rows = build_matrix(&model(), &sources(), &features, None).unwrap();
+
+        assert_eq!(rows[0].sources, vec!["Invariants — Q1"]);
+        assert_eq!(rows[0].tests, vec!["orders.feature:4 Inscription en double"]);
+        assert_eq!(rows[1].sources, vec!["Réponse 2"]);
+        assert_eq!(rows[1].tests, vec!["orders.feature:7 Commande négative"]);
+        assert_eq!(rows[1].status, "partial");
+    }
+
+    #[test]
+    fn audits_code_directory() {
+        let dir = std::env::temp_dir().join(format!("traceability_test_{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("node_modules")).unwrap();
+        fs::write(dir.join("src/customer.rs"), "fn check() {}\nfn ensure_unique_customer_email() {}\n").unwrap();
+        fs::write(dir.join("node_modules/x.js"), "uniqueCustomerEmail").unwrap();
+
+        let result = traceability_matrix_tool(&model(), &[], &[], Some(&dir)).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(result["rows"][0]["code"], json!(["src/customer.rs:2"]));
+        assert_eq!(result["rows"][1]["status"], "untraced");
+        assert_eq!(result["summary"]["with_code"], 1);
+        assert!(result["markdown"].as_str().unwrap().contains("| `uniqueCustomerEmail` | Email client unique |"));
+        assert!(result["csv"].as_str().unwrap().starts_with("invariant,name,sources,tests,code,status\n"));
+    }
+}
@@ -0,0 +1,167 @@
+package app.whispeer.audiocapture
+
+import android.Manifest
+import android.app.Activity
+import android.media.AudioFormat
+import android.media.AudioRecord
+import android.media.MediaRecorder
+import app.tauri.PermissionState
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.Permission
+import app.tauri.annotation.PermissionCallback
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.JSObject
+import app.tauri.plugin.Plugin
+import java.io.File
+import java.io.RandomAccessFile
+import java.util.concurrent.atomic.AtomicBoolean
+
+@InvokeArg
+class StartCaptureArgs {
+    lateinit var outputPath: String
+    var sampleRate: Int = 16000
+}
+
+/**
+ * Records the microphone as 16-bit mono PCM into a WAV file.
+ * The header is written with empty sizes and patched when the capture stops.
+ */
+@TauriPlugin(
+    permissions = [
+        Permission(strings = [Manifest.permission.RECORD_AUDIO], alias = "microphone")
+    ]
+)
+class AudioCapturePlugin(private val activity: Activity) : Plugin(activity) {
+    private var recorder: AudioRecord? = null
+    private var worker: Thread? = null
+    private var outputPath: String? = null
+    private var sampleRate: Int = 16000
+    @Volatile private var dataBytes: Long = 0
+    /** Read by the worker thread, cleared by stopCapture */
+    private val running = AtomicBoolean(false)
+
+    @Command
+    fun startCapture(invoke: Invoke) {
+        if (getPermissionState("microphone") != PermissionState.GRANTED) {
+            requestPermissionForAlias("microphone", invoke, "microphonePermissionCallback")
+            return
+        }
+        startRecorder(invoke)
+    }
+
+    @PermissionCallback
+    private fun microphonePermissionCallback(invoke: Invoke) {
+        if (getPermissionState("microphone") == PermissionState.GRANTED) {
+            startRecorder(invoke)
+        } else {
+            invoke.reject("Microphone permission denied")
+        }
+    }
+
+    private fun startRecorder(invoke: Invoke) {
+        if (recorder != null) {
+            invoke.reject("Capture already in progress")
+            return
+        }
+        val args = invoke.parseArgs(StartCaptureArgs::class.java)
+        val bufferSize = AudioRecord.getMinBufferSize(
+            args.sampleRate, AudioFormat.CHANNEL_IN_MONO, AudioFormat.ENCODING_PCM_16BIT
+        ).coerceAtLeast(args.sampleRate / 5)
+
+        val record = try {
+            AudioRecord(
+                MediaRecorder.AudioSource.VOICE_RECOGNITION,
+                args.sampleRate,
+                AudioFormat.CHANNEL_IN_MONO,
+                AudioFormat.ENCODING_PCM_16BIT,
+                bufferSize
+            )
+        } catch (e: SecurityException) {
+            invoke.reject("Microphone permission denied")
+            return
+        }
+        if (record.state != AudioRecord.STATE_INITIALIZED) {
+            record.release()
+            invoke.reject("Failed to initialize AudioRecord at ${args.sampleRate} Hz")
+            return
+        }
+
+        val file = File(args.outputPath)
+        file.parentFile?.mkdirs()
+        val output = RandomAccessFile(file, "rw")
+        output.setLength(0)
+        writeWavHeader(output, args.sampleRate, 0)
+
+        recorder = record
+        outputPath = args.outputPath
+        sampleRate = args.sampleRate
+        dataBytes = 0
+        running.set(true)
+        record.startRecording()
+
+        worker = Thread {
+            val buffer = ByteArray(bufferSize)
+            output.use {
+                while (running.get()) {
+                    val read = record.read(buffer, 0, buffer.size)
+                    // Negative values are AudioRecord errors (dead object, invalid operation)
+                    if (read < 0) break
+                    if (read > 0) {
+                        it.write(buffer, 0, read)
+                        dataBytes += read
+                    }
+                }
+                writeWavHeader(it, sampleRate, dataBytes)
+            }
+        }.also { it.start() }
+
+        invoke.resolve()
+    }
+
+    @Command
+    fun stopCapture(invoke: Invoke) {
+        val record = recorder
+        val path = outputPath
+        if (record == null || path == null) {
+            invoke.reject("No capture in progress")
+            return
+        }
+        recorder = null
+        running.set(false)
+        record.stop()
+        worker?.join()
+        record.release()
+        worker = null
+
+        val result = JSObject()
+        result.put("path", path)
+        result.put("durationMs", dataBytes * 1000 / (sampleRate * 2))
+        result.put("sampleRate", sampleRate)
+        invoke.resolve(result)
+    }
+
+    private fun writeWavHeader(file: RandomAccessFile, sampleRate: Int, dataSize: Long) {
+        fun le32(value: Long) = byteArrayOf(
+            value.toByte(), (value shr 8).toByte(), (value shr 16).toByte(), (value shr 24).toByte()
+        )
+        fun le16(value: Int) = byteArrayOf(value.toByte(), (value shr 8).toByte())
+
+        val position = file.filePointer
+        file.seek(0)
+        file.write("RIFF".toByteArray())
+        file.write(le32(36 + dataSize))
+        file.write("WAVEfmt ".toByteArray())
+        file.write(le32(16))
+        file.write(le16(1)) // PCM
+        file.write(le16(1)) // mono
+        file.write(le32(sampleRate.toLong()))
+        file.write(le32(sampleRate.toLong() * 2))
+        file.write(le16(2))
+        file.write(le16(16))
+        file.write("data".toByteArray())
+        file.write(le32(dataSize))
+        if (position > 44) file.seek(position)
+    }
+}
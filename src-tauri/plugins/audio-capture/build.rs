@@ -0,0 +1,9 @@
+// No frontend commands: the app drives the capture from Rust
+const COMMANDS: &[&str] = &[];
+
+fn main() {
+    tauri_plugin::Builder::new(COMMANDS)
+        .android_path("android")
+        .ios_path("ios")
+        .build();
+}
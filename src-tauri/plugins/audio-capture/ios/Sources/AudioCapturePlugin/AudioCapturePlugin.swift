@@ -0,0 +1,85 @@
+import AVFoundation
+import Tauri
+
+class StartCaptureArgs: Decodable {
+  let outputPath: String
+  var sampleRate: Double?
+}
+
+/// Records the microphone as 16-bit mono PCM WAV with AVAudioRecorder.
+/// The audio session is switched to .playAndRecord for the capture only.
+class AudioCapturePlugin: Plugin {
+  private var recorder: AVAudioRecorder?
+
+  @objc public func startCapture(_ invoke: Invoke) throws {
+    let args = try invoke.parseArgs(StartCaptureArgs.self)
+    if recorder != nil {
+      invoke.reject("Capture already in progress")
+      return
+    }
+
+    AVAudioSession.sharedInstance().requestRecordPermission { granted in
+      DispatchQueue.main.async {
+        guard granted else {
+          invoke.reject("Microphone permission denied")
+          return
+        }
+        do {
+          try self.startRecorder(args)
+          invoke.resolve()
+        } catch {
+          invoke.reject("Failed to start capture: \(error.localizedDescription)")
+        }
+      }
+    }
+  }
+
+  private func startRecorder(_ args: StartCaptureArgs) throws {
+    let session = AVAudioSession.sharedInstance()
+    try session.setCategory(.playAndRecord, mode: .measurement, options: [.defaultToSpeaker, .allowBluetooth])
+    try session.setActive(true)
+
+    let url = URL(fileURLWithPath: args.outputPath)
+    try FileManager.default.createDirectory(
+      at: url.deletingLastPathComponent(), withIntermediateDirectories: true)
+
+    let settings: [String: Any] = [
+      AVFormatIDKey: Int(kAudioFormatLinearPCM),
+      AVSampleRateKey: args.sampleRate ?? 16000,
+      AVNumberOfChannelsKey: 1,
+      AVLinearPCMBitDepthKey: 16,
+      AVLinearPCMIsFloatKey: false,
+      AVLinearPCMIsBigEndianKey: false,
+    ]
+    let recorder = try AVAudioRecorder(url: url, settings: settings)
+    guard recorder.record() else {
+      throw NSError(
+        domain: "AudioCapture", code: 1,
+        userInfo: [NSLocalizedDescriptionKey: "AVAudioRecorder refused to record"])
+    }
+    self.recorder = recorder
+  }
+
+  @objc public func stopCapture(_ invoke: Invoke) {
+    guard let recorder = recorder else {
+      invoke.reject("No capture in progress")
+      return
+    }
+    let durationMs = Int(recorder.currentTime * 1000)
+    let sampleRate = Int(recorder.format.sampleRate)
+    recorder.stop()
+    self.recorder = nil
+    try? AVAudioSession.sharedInstance().setActive(false, options: .notifyOthersOnDeactivation)
+
+    invoke.resolve([
+      "path": recorder.url.path,
+      "durationMs": durationMs,
+      "sampleRate": sampleRate,
+    ])
+  }
+}
+
+@_cdecl("init_plugin_audio_capture")
+func initPlugin() -> Plugin {
+  return AudioCapturePlugin()
+}
@@ -0,0 +1,24 @@
+use serde::de::DeserializeOwned;
+use tauri::{plugin::PluginApi, AppHandle, Runtime};
+
+use crate::models::*;
+
+pub fn init<R: Runtime, C: DeserializeOwned>(
+    app: &AppHandle<R>,
+    _api: PluginApi<R, C>,
+) -> crate::Result<AudioCapture<R>> {
+    Ok(AudioCapture(app.clone()))
+}
+
+/// Desktop builds capture through cpal (see `AudioSession`)
+pub struct AudioCapture<R: Runtime>(#[allow(dead_code)] AppHandle<R>);
+
+impl<R: Runtime> AudioCapture<R> {
+    pub fn start(&self, _request: StartCaptureRequest) -> crate::Result<()> {
+        Err(crate::Error::Unsupported)
+    }
+
+    pub fn stop(&self) -> crate::Result<CaptureResult> {
+        Err(crate::Error::Unsupported)
+    }
+}
@@ -0,0 +1,50 @@
+//! Microphone capture for the mobile builds.
+//!
+//! cpal can open the microphone on iOS and Android, but not configure the
+//! AVAudioSession category or ask for the RECORD_AUDIO permission. This plugin
+//! records natively (AVAudioRecorder / AudioRecord) into a 16-bit PCM WAV file
+//! in the app sandbox, which then goes through the usual transcription path.
+//! Desktop builds keep capturing through cpal and get `Error::Unsupported`.
+
+use tauri::{
+    plugin::{Builder, TauriPlugin},
+    Manager, Runtime,
+};
+
+#[cfg(desktop)]
+mod desktop;
+mod error;
+#[cfg(mobile)]
+mod mobile;
+mod models;
+
+#[cfg(desktop)]
+pub use desktop::AudioCapture;
+pub use error::{Error, Result};
+#[cfg(mobile)]
+pub use mobile::AudioCapture;
+pub use models::*;
+
+/// Access to the audio capture from any `Manager` (app, handle, window)
+pub trait AudioCaptureExt<R: Runtime> {
+    fn audio_capture(&self) -> &AudioCapture<R>;
+}
+
+impl<R: Runtime, T: Manager<R>> AudioCaptureExt<R> for T {
+    fn audio_capture(&self) -> &AudioCapture<R> {
+        self.state::<AudioCapture<R>>().inner()
+    }
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("audio-capture")
+        .setup(|app, api| {
+            #[cfg(mobile)]
+            let capture = mobile::init(app, api)?;
+            #[cfg(desktop)]
+            let capture = desktop::init(app, api)?;
+            app.manage(capture);
+            Ok(())
+        })
+        .build()
+}
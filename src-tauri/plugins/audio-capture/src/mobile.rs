@@ -0,0 +1,41 @@
+use serde::de::DeserializeOwned;
+use tauri::{
+    plugin::{PluginApi, PluginHandle},
+    AppHandle, Runtime,
+};
+
+use crate::models::*;
+
+#[cfg(target_os = "ios")]
+tauri::ios_plugin_binding!(init_plugin_audio_capture);
+
+pub fn init<R: Runtime, C: DeserializeOwned>(
+    _app: &AppHandle<R>,
+    api: PluginApi<R, C>,
+) -> crate::Result<AudioCapture<R>> {
+    #[cfg(target_os = "android")]
+    let handle = api.register_android_plugin("app.whispeer.audiocapture", "AudioCapturePlugin")?;
+    #[cfg(target_os = "ios")]
+    let handle = api.register_ios_plugin(init_plugin_audio_capture)?;
+    Ok(AudioCapture(handle))
+}
+
+/// Native recorder: AVAudioRecorder on iOS, AudioRecord on Android
+pub struct AudioCapture<R: Runtime>(PluginHandle<R>);
+
+impl<R: Runtime> AudioCapture<R> {
+    /// Ask for the microphone permission if needed and start recording
+    pub fn start(&self, request: StartCaptureRequest) -> crate::Result<()> {
+        self.0
+            .run_mobile_plugin::<serde::de::IgnoredAny>("startCapture", request)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    /// Stop recording and finalize the WAV file
+    pub fn stop(&self) -> crate::Result<CaptureResult> {
+        self.0
+            .run_mobile_plugin("stopCapture", ())
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartCaptureRequest {
+    /// WAV file to record into, inside the app sandbox
+    pub output_path: String,
+    /// Whisper works at 16 kHz: recording at that rate avoids resampling on the phone
+    pub sample_rate: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureResult {
+    pub path: String,
+    pub duration_ms: u64,
+    pub sample_rate: u32,
+}
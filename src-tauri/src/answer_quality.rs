@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// System prompt rating an interview answer against the rubric
+pub const SCORING_SYSTEM_PROMPT: &str = r#"
+Tu évalues la réponse à une question d'interview Domain-Driven Design, AVANT qu'elle serve à générer le canvas. Le but est d'inviter l'utilisateur à préciser une réponse trop pauvre.
+
+Barème (0 à 3 pour chaque critère):
+- completeness: 0 = hors sujet ou vide, 1 = effleure la question, 2 = répond à l'essentiel, 3 = couvre tous les aspects demandés
+- specificity: 0 = générique ("ça dépend", "les utilisateurs"), 1 = quelques termes du métier, 2 = exemples ou règles concrètes, 3 = chiffres, cas limites, exceptions
+
+Réponds UNIQUEMENT avec un JSON:
+{
+  "completeness": 0-3,
+  "specificity": 0-3,
+  "missing": ["aspect de la question non couvert", ...],
+  "follow_up": "question de relance courte pour compléter la réponse (null si la réponse suffit)"
+}
+
+RÈGLES:
+1. Juge la réponse par rapport à la question posée, pas au domaine en général
+2. "missing" cite des aspects précis de la question, au plus 3
+3. La relance est dans la langue de la réponse
+"#;
+
+/// Answers with fewer words are rated without an LLM call
+const MIN_WORDS: usize = 3;
+/// Highest score of each rubric criterion
+const MAX_CRITERION: u8 = 3;
+/// Combined score (out of 6) under which the user is nudged to elaborate
+const ELABORATE_BELOW: u8 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Good enough to process the section
+    Ok,
+    /// Processable, but a follow-up would give a better canvas
+    Elaborate,
+    /// Empty or a few words: not worth a generation
+    TooShort,
+}
+
+/// Rubric rating of one interview answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnswerScore {
+    /// 0 (off-topic) to 3 (every aspect of the question covered)
+    pub completeness: u8,
+    /// 0 (generic) to 3 (figures, edge cases, exceptions)
+    pub specificity: u8,
+    pub verdict: Verdict,
+    /// Aspects of the question the answer leaves out
+    pub missing: Vec<String>,
+    /// Question to ask the user to complete the answer
+    pub follow_up: Option<String>,
+}
+
+pub fn scoring_prompt(question: &str, answer: &str) -> String {
+    format!("Question: {}\n\nRéponse: {}", question.trim(), answer.trim())
+}
+
+/// Rating of an answer too short to be worth an LLM call, None otherwise
+pub fn quick_check(answer: &str) -> Option<AnswerScore> {
+    (answer.split_whitespace().count() < MIN_WORDS).then(|| AnswerScore {
+        completeness: 0,
+        specificity: 0,
+        verdict: Verdict::TooShort,
+        missing: Vec::new(),
+        follow_up: None,
+    })
+}
+
+/// Score from the LLM answer; criteria are clamped to the rubric and the verdict is
+/// derived here so the threshold does not depend on the model
+pub fn parse_score(response: &Value) -> Result<AnswerScore> {
+    let criterion = |name: &str| -> Result<u8> {
+        let value = response[name].as_u64().with_context(|| format!("LLM answer has no '{}' score", name))?;
+        Ok(value.min(MAX_CRITERION as u64) as u8)
+    };
+    let (completeness, specificity) = (criterion("completeness")?, criterion("specificity")?);
+    let missing: Vec<String> = serde_json::from_value(response["missing"].clone()).unwrap_or_default();
+    let follow_up = response["follow_up"].as_str().map(str::trim).filter(|f| !f.is_empty()).map(str::to_string);
+
+    let verdict = if completeness + specificity < ELABORATE_BELOW || completeness == 0 {
+        Verdict::Elaborate
+    } else {
+        Verdict::Ok
+    };
+    Ok(AnswerScore {
+        completeness,
+        specificity,
+        verdict,
+        missing,
+        // A follow-up is only worth showing when the user is asked to elaborate
+        follow_up: follow_up.filter(|_| verdict == Verdict::Elaborate),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_short_answers_and_verdicts() {
+        assert_eq!(quick_check("Oui.").unwrap().verdict, Verdict::TooShort);
+        assert!(quick_check("Les commandes sont validées par un superviseur").is_none());
+
+        let vague = parse_score(&json!({
+            "completeness": 2, "specificity": 1, "missing": ["qui valide"],
+            "follow_up": "Qui valide une commande, et sous quel délai ?"
+        }))
+        .unwrap();
+        assert_eq!(vague.verdict, Verdict::Elaborate);
+        assert_eq!(vague.follow_up.as_deref(), Some("Qui valide une commande, et sous quel délai ?"));
+
+        let detailed = parse_score(&json!({"completeness": 3, "specificity": 7, "follow_up": "Autre chose ?"})).unwrap();
+        assert_eq!((detailed.specificity, detailed.verdict, detailed.follow_up), (3, Verdict::Ok, None));
+        assert!(parse_score(&json!({"completeness": 2})).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::safe_write;
+
+/// Manifest entry: the content an artifact name currently points to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactEntry {
+    /// SHA-256 of the content, hex encoded
+    pub hash: String,
+    pub size: u64,
+    /// "model", "markdown", "mermaid"...
+    pub kind: String,
+    pub updated_at: String,
+}
+
+/// Artifacts of a project, by name ("model.json", "canvas.md"...)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    pub artifacts: BTreeMap<String, ArtifactEntry>,
+}
+
+/// Artifact whose stored content no longer matches its manifest entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    pub name: String,
+    pub message: String,
+}
+
+pub fn content_hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+fn objects_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("artifacts").join("objects")
+}
+
+fn object_path(objects_dir: &Path, hash: &str) -> PathBuf {
+    objects_dir.join(&hash[..2]).join(hash)
+}
+
+/// Write `content` under its hash, once
+fn write_object(objects_dir: &Path, content: &[u8]) -> Result<String> {
+    let hash = content_hash(content);
+    let path = object_path(objects_dir, &hash);
+    if !path.exists() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+        safe_write::write(&path, content)?;
+    }
+    Ok(hash)
+}
+
+/// Store content that belongs to no project (e.g. the transcript and prompt an
+/// artifact was generated from) and return its hash
+pub fn put_object(app_data_dir: &Path, content: &[u8]) -> Result<String> {
+    write_object(&objects_dir(app_data_dir), content)
+}
+
+/// Content stored under `hash`, None if absent or no longer matching its hash
+pub fn load_object(app_data_dir: &Path, hash: &str) -> Option<Vec<u8>> {
+    if hash.len() < 2 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    fs::read(object_path(&objects_dir(app_data_dir), hash))
+        .ok()
+        .filter(|content| content_hash(content) == hash)
+}
+
+/// Content-addressed storage of generated artifacts. Contents live once under
+/// `<app data>/artifacts/objects/<2 first hex chars>/<hash>`, shared by all projects;
+/// each project has a `<app data>/artifacts/<project>/manifest.json` mapping names to hashes.
+pub struct ArtifactStore {
+    objects_dir: PathBuf,
+    manifest_path: PathBuf,
+}
+
+impl ArtifactStore {
+    pub fn open(app_data_dir: &Path, project_name: &str) -> Self {
+        let sanitized_name = crate::sanitize_project_name(project_name);
+        Self {
+            objects_dir: objects_dir(app_data_dir),
+            manifest_path: app_data_dir.join("artifacts").join(sanitized_name).join("manifest.json"),
+        }
+    }
+
+    pub fn manifest(&self) -> Result<ArtifactManifest> {
+        if !self.manifest_path.exists() {
+            return Ok(ArtifactManifest::default());
+        }
+        let content = fs::read_to_string(&self.manifest_path)
+            .with_context(|| format!("Failed to read {:?}", self.manifest_path))?;
+        serde_json::from_str(&content).context("Invalid artifact manifest")
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        object_path(&self.objects_dir, hash)
+    }
+
+    /// Whether `content` differs from what is stored under `name` (true if absent)
+    pub fn has_changed(&self, name: &str, content: &[u8]) -> Result<bool> {
+        let hash = content_hash(content);
+        Ok(self.manifest()?.artifacts.get(name).is_none_or(|entry| entry.hash != hash))
+    }
+
+    /// Store `content` under `name`. Identical content is written only once, and an
+    /// unchanged artifact leaves the manifest untouched. Returns whether it changed.
+    pub fn put(&self, name: &str, kind: &str, content: &[u8]) -> Result<bool> {
+        let hash = content_hash(content);
+        let mut manifest = self.manifest()?;
+        if manifest.artifacts.get(name).is_some_and(|entry| entry.hash == hash) {
+            return Ok(false);
+        }
+
+        write_object(&self.objects_dir, content)?;
+        manifest.artifacts.insert(
+            name.to_string(),
+            ArtifactEntry {
+                hash,
+                size: content.len() as u64,
+                kind: kind.to_string(),
+                updated_at: chrono::Local::now().to_rfc3339(),
+            },
+        );
+        if let Some(dir) = self.manifest_path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+        safe_write::write(&self.manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+        Ok(true)
+    }
+
+    /// Content of `name`, checked against its hash
+    pub fn get(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let Some(entry) = self.manifest()?.artifacts.remove(name) else {
+            return Ok(None);
+        };
+        let content = fs::read(self.object_path(&entry.hash))
+            .with_context(|| format!("Missing content of artifact {}", name))?;
+        if content_hash(&content) != entry.hash {
+            anyhow::bail!("Artifact {} is corrupted (hash mismatch)", name);
+        }
+        Ok(Some(content))
+    }
+
+    /// Check every artifact of the manifest against its stored content
+    pub fn verify(&self) -> Result<Vec<IntegrityIssue>> {
+        let mut issues = Vec::new();
+        for (name, entry) in self.manifest()?.artifacts {
+            let message = match fs::read(self.object_path(&entry.hash)) {
+                Err(_) => "content missing".to_string(),
+                Ok(content) if content_hash(&content) != entry.hash => "hash mismatch".to_string(),
+                Ok(_) => continue,
+            };
+            issues.push(IntegrityIssue { name, message });
+        }
+        Ok(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_change_detection_and_integrity() {
+        let app_data = std::env::temp_dir().join(format!("artifact_store_test_{}", std::process::id()));
+        let store = ArtifactStore::open(&app_data, "Mon projet");
+        let other = ArtifactStore::open(&app_data, "Autre");
+
+        assert!(store.has_changed("canvas.md", b"# Canvas").unwrap());
+        assert!(store.put("canvas.md", "markdown", b"# Canvas").unwrap());
+        assert!(!store.put("canvas.md", "markdown", b"# Canvas").unwrap());
+        assert!(!store.has_changed("canvas.md", b"# Canvas").unwrap());
+        assert!(store.has_changed("canvas.md", b"# Canvas v2").unwrap());
+
+        // Same content in another project: one object on disk
+        assert!(other.put("notes.md", "markdown", b"# Canvas").unwrap());
+        let hash = content_hash(b"# Canvas");
+        let shard = app_data.join("artifacts").join("objects").join(&hash[..2]);
+        assert_eq!(fs::read_dir(&shard).unwrap().count(), 1);
+
+        assert_eq!(store.get("canvas.md").unwrap().unwrap(), b"# Canvas");
+        assert!(store.get("model.json").unwrap().is_none());
+        assert!(store.verify().unwrap().is_empty());
+
+        fs::write(shard.join(&hash), "tampered").unwrap();
+        assert!(store.get("canvas.md").is_err());
+        assert_eq!(store.verify().unwrap()[0].message, "hash mismatch");
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::path::Path;
+use std::process::Command;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::speech_to_text::{read_wav_mono, resample_audio};
+
+/// Fréquence attendue par Whisper
+const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// Lit un fichier audio quelconque (WAV, MP3, M4A/AAC, FLAC, OGG...) en samples f32
+/// mono à 16kHz, comme `read_wav_16k` pour un WAV
+pub fn read_audio_16k(path: &Path) -> Result<Vec<f32>> {
+    let (samples, sample_rate) = read_audio_mono(path)?;
+    if sample_rate != WHISPER_SAMPLE_RATE {
+        log::info!("Resampling from {} Hz to {} Hz", sample_rate, WHISPER_SAMPLE_RATE);
+        return Ok(resample_audio(&samples, sample_rate, WHISPER_SAMPLE_RATE));
+    }
+    Ok(samples)
+}
+
+/// Samples f32 mono d'un fichier audio et leur fréquence. Les WAV PCM passent par hound,
+/// les autres formats par symphonia ; ffmpeg, s'il est installé, prend le relais pour les
+/// codecs que symphonia ne connaît pas (Opus, WMA...).
+pub fn read_audio_mono(path: &Path) -> Result<(Vec<f32>, u32)> {
+    if !path.is_file() {
+        anyhow::bail!("Audio file not found: {:?}", path);
+    }
+    let is_wav = path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("wav"));
+    if is_wav {
+        match read_wav_mono(path) {
+            Ok(audio) => return Ok(audio),
+            // WAV compressé (ADPCM...) : symphonia sait le lire
+            Err(e) => log::info!("Not a PCM WAV, decoding with symphonia: {}", e),
+        }
+    }
+
+    let decoded = decode_symphonia(path);
+    let error = match decoded {
+        Ok((samples, _)) if samples.is_empty() => anyhow::anyhow!("no audio samples"),
+        Ok(audio) => return Ok(audio),
+        Err(e) => e,
+    };
+    log::warn!("Symphonia could not decode {:?} ({}), trying ffmpeg", path, error);
+    decode_ffmpeg(path).map_err(|ffmpeg_error| {
+        anyhow::anyhow!("Unsupported audio file {:?}: {} (ffmpeg: {})", path, error, ffmpeg_error)
+    })
+}
+
+/// Décode la première piste audio avec symphonia, canaux moyennés en mono
+fn decode_symphonia(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let file = File::open(path).with_context(|| format!("Failed to open audio file {:?}", path))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .context("Unknown audio format")?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .context("No audio track")?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .context("Unsupported audio codec")?;
+    log::info!("Decoding {:?}: {:?} at {:?} Hz", path, track.codec_params.codec, sample_rate);
+
+    let mut samples = Vec::new();
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // Fin du fichier
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e).context("Failed to read audio packet"),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Paquet corrompu : on le saute plutôt que de perdre tout l'enregistrement
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::warn!("Skipping undecodable packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e).context("Failed to decode audio"),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate = Some(spec.rate);
+        let channels = spec.channels.count().max(1);
+        let buffer = match buffer.as_mut() {
+            Some(buffer) if buffer.capacity() >= decoded.capacity() * channels => buffer,
+            _ => buffer.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+        };
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend(buffer.samples().chunks_exact(channels).map(|frame| frame.iter().sum::<f32>() / channels as f32));
+    }
+
+    let sample_rate = sample_rate.filter(|rate| *rate > 0).context("Unknown sample rate")?;
+    Ok((samples, sample_rate))
+}
+
+/// Conversion par ffmpeg, directement en f32 mono à 16kHz sur sa sortie standard
+fn decode_ffmpeg(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let output = Command::new("ffmpeg")
+        .arg("-i").arg(path)
+        .args(["-vn", "-f", "f32le", "-ar", &WHISPER_SAMPLE_RATE.to_string(), "-ac", "1", "pipe:1"])
+        .output()
+        .context("ffmpeg not found")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("ffmpeg failed: {}", stderr.lines().last().unwrap_or_default());
+    }
+    let samples: Vec<f32> = output
+        .stdout
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect();
+    if samples.is_empty() {
+        anyhow::bail!("ffmpeg found no audio");
+    }
+    Ok((samples, WHISPER_SAMPLE_RATE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_other_formats() {
+        let dir = std::env::temp_dir().join(format!("audio_decode_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Un WAV float stéréo lu par symphonia, comme un fichier d'un autre format
+        let stereo = dir.join("meeting.wav");
+        let spec = hound::WavSpec { channels: 2, sample_rate: 32000, bits_per_sample: 32, sample_format: hound::SampleFormat::Float };
+        let mut writer = hound::WavWriter::create(&stereo, spec).unwrap();
+        for _ in 0..3200 {
+            writer.write_sample(0.5f32).unwrap();
+            writer.write_sample(-0.1f32).unwrap();
+        }
+        writer.finalize().unwrap();
+        let (samples, sample_rate) = decode_symphonia(&stereo).unwrap();
+        assert_eq!((samples.len(), sample_rate), (3200, 32000));
+        assert!((samples[0] - 0.2).abs() < 1e-6);
+        assert_eq!(read_audio_16k(&stereo).unwrap().len(), 1600);
+
+        // Ni symphonia ni ffmpeg : l'erreur dit pourquoi
+        let text = dir.join("notes.mp3");
+        std::fs::write(&text, "pas de l'audio").unwrap();
+        let error = read_audio_16k(&text).unwrap_err().to_string();
+        assert!(error.starts_with("Unsupported audio file"), "{}", error);
+        assert!(read_audio_16k(&dir.join("missing.m4a")).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
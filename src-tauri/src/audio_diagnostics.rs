@@ -0,0 +1,386 @@
+use anyhow::{Context, Result};
+use cpal::traits::{DeviceTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::audio_session::{
+    build_input_stream, downmix_to_mono, open_input_device, probe_vad_engine, save_wav, to_i16_samples, StreamResampler,
+    VadEngine, TARGET_SAMPLE_RATE,
+};
+use crate::speech_to_text::read_wav_16k;
+
+/// Fréquence de la sinusoïde de test (La 440)
+pub const TONE_HZ: f32 = 440.0;
+const TONE_DURATION_MS: u32 = 1000;
+/// Écart toléré sur la fréquence détectée (2%) et sur la durée relue (50ms)
+const PITCH_TOLERANCE: f32 = 0.02;
+const DURATION_TOLERANCE_MS: u32 = 50;
+
+/// Résultat de l'aller-retour d'une sinusoïde dans le pipeline audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineReport {
+    pub host: String,
+    /// Périphérique dont la configuration a été négociée (None = simulé)
+    pub device: Option<String>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub tone_hz: f32,
+    pub detected_hz: f32,
+    pub expected_duration_ms: u32,
+    pub measured_duration_ms: u32,
+    /// Détection de voix des enregistrements (`energy` si webrtc-vad est inutilisable)
+    pub vad_engine: VadEngine,
+    pub passed: bool,
+    pub issues: Vec<String>,
+}
+
+/// Sinusoïde entrelacée sur `channels` canaux, comme la livre un callback cpal
+pub fn generate_tone(freq: f32, sample_rate: u32, channels: u16, duration_ms: u32, amplitude: f32) -> Vec<f32> {
+    let frames = (sample_rate as u64 * duration_ms as u64 / 1000) as usize;
+    (0..frames)
+        .flat_map(|i| {
+            let value = (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin() * amplitude;
+            std::iter::repeat_n(value, channels as usize)
+        })
+        .collect()
+}
+
+/// Fréquence estimée par comptage des passages par zéro montants
+pub fn estimate_frequency(samples: &[f32], sample_rate: u32) -> f32 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let crossings = samples.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+    crossings as f32 * sample_rate as f32 / samples.len() as f32
+}
+
+/// Fait passer une sinusoïde générée au format du périphérique par le même chemin qu'un
+/// enregistrement: downmix mono et conversion i16 du callback, écriture WAV de l'AudioSession, relecture
+/// et rééchantillonnage à 16kHz côté Whisper. Un décalage de hauteur ou de durée trahit
+/// un sample rate ou un nombre de canaux mal pris en compte.
+pub fn round_trip(output_dir: &Path, host: &str, device: Option<String>, sample_rate: u32, channels: u16) -> Result<PipelineReport> {
+    std::fs::create_dir_all(output_dir).context("Failed to create output directory")?;
+    let wav_path = output_dir.join(format!("pipeline_test_{}hz_{}ch.wav", sample_rate, channels));
+
+    let tone = generate_tone(TONE_HZ, sample_rate, channels, TONE_DURATION_MS, 0.5);
+    // Même chaîne que la capture: downmix mono puis rééchantillonnage en 16kHz avant écriture
+    let mono = downmix_to_mono(&tone, channels);
+    let samples = to_i16_samples(&StreamResampler::new(sample_rate).process(&mono), 1.0);
+    save_wav(&wav_path, &samples, TARGET_SAMPLE_RATE)?;
+    let read_back = read_wav_16k(&wav_path);
+    let _ = std::fs::remove_file(&wav_path);
+    let read_back = read_back?;
+
+    let detected_hz = estimate_frequency(&read_back, TARGET_SAMPLE_RATE);
+    let measured_duration_ms = (read_back.len() as u64 * 1000 / TARGET_SAMPLE_RATE as u64) as u32;
+
+    let mut issues = Vec::new();
+    if ((detected_hz - TONE_HZ) / TONE_HZ).abs() > PITCH_TOLERANCE {
+        issues.push(format!("Pitch shifted: {:.1} Hz read back for a {:.0} Hz tone", detected_hz, TONE_HZ));
+    }
+    if measured_duration_ms.abs_diff(TONE_DURATION_MS) > DURATION_TOLERANCE_MS {
+        issues.push(format!(
+            "Duration mismatch: {} ms read back for {} ms recorded",
+            measured_duration_ms, TONE_DURATION_MS
+        ));
+    }
+    let vad_engine = probe_vad_engine(&webrtc_vad::VadMode::Aggressive);
+    if vad_engine == VadEngine::Energy {
+        issues.push("webrtc-vad unavailable: utterances are split by the energy VAD fallback".to_string());
+    }
+
+    Ok(PipelineReport {
+        host: host.to_string(),
+        device,
+        sample_rate,
+        channels,
+        tone_hz: TONE_HZ,
+        detected_hz,
+        expected_duration_ms: TONE_DURATION_MS,
+        measured_duration_ms,
+        vad_engine,
+        passed: issues.is_empty(),
+        issues,
+    })
+}
+
+/// Fenêtres d'analyse de 20ms
+const FRAME_MS: u32 = 20;
+/// Niveau plancher des fenêtres silencieuses numériquement
+const MIN_DBFS: f32 = -100.0;
+/// Un sample à ±0.99 est considéré comme saturé
+pub(crate) const CLIP_LEVEL: f32 = 0.99;
+
+/// Bilan acoustique de la pièce et du micro avant une interview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentReport {
+    pub device: Option<String>,
+    pub sample_rate: u32,
+    pub duration_ms: u32,
+    /// Bruit de fond (10e percentile des niveaux par fenêtre)
+    pub noise_floor_dbfs: f32,
+    /// Niveau de la voix (95e percentile)
+    pub speech_level_dbfs: f32,
+    pub snr_db: f32,
+    pub peak_dbfs: f32,
+    /// Part des samples saturés
+    pub clipping_ratio: f32,
+    /// Temps de réverbération estimé (RT60), None sans fin de phrase mesurable
+    pub reverberation_ms: Option<u32>,
+    pub speech_detected: bool,
+    pub recommendations: Vec<String>,
+}
+
+pub(crate) fn to_dbfs(level: f32) -> f32 {
+    if level <= 0.0 {
+        MIN_DBFS
+    } else {
+        (20.0 * level.log10()).max(MIN_DBFS)
+    }
+}
+
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return MIN_DBFS;
+    }
+    sorted[((sorted.len() - 1) as f32 * p).round() as usize]
+}
+
+/// RT60 extrapolé de la décroissance après chaque fin de phrase: temps mis par le niveau
+/// pour perdre 20 dB depuis un pic de voix (T20), multiplié par 3. Médiane des mesures.
+fn estimate_reverberation_ms(levels: &[f32], noise_floor: f32, speech_level: f32) -> Option<u32> {
+    // Décroissance non mesurable si la voix ne dépasse pas le bruit d'au moins 25 dB
+    if speech_level - noise_floor < 25.0 {
+        return None;
+    }
+    let mut decays = Vec::new();
+    let mut i = 0;
+    while i + 1 < levels.len() {
+        // Fin de phrase: le niveau passe sous celui de la voix
+        let is_offset = levels[i] >= speech_level - 6.0 && levels[i + 1] < speech_level - 6.0;
+        if !is_offset {
+            i += 1;
+            continue;
+        }
+        let target = levels[i] - 20.0;
+        match levels[i + 1..].iter().position(|&l| l <= target) {
+            Some(frames) => {
+                // Décroissance monotone uniquement (une reprise de voix n'est pas un écho)
+                let tail = &levels[i..=i + 1 + frames];
+                if tail.windows(2).all(|w| w[1] <= w[0] + 1.0) {
+                    decays.push((frames + 1) as u32 * FRAME_MS * 3);
+                }
+                i += frames + 1;
+            }
+            None => break,
+        }
+    }
+    if decays.is_empty() {
+        return None;
+    }
+    decays.sort_unstable();
+    Some(decays[decays.len() / 2])
+}
+
+/// Analyse quelques secondes de signal mono: bruit de fond, SNR, saturation, réverbération,
+/// et conseils pour corriger l'installation avant l'interview
+pub fn analyze_environment(samples: &[f32], sample_rate: u32, device: Option<String>) -> EnvironmentReport {
+    let frame_len = (sample_rate * FRAME_MS / 1000).max(1) as usize;
+    let levels: Vec<f32> = samples
+        .chunks(frame_len)
+        .filter(|frame| frame.len() == frame_len)
+        .map(|frame| to_dbfs((frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt()))
+        .collect();
+    let mut sorted = levels.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let noise_floor_dbfs = percentile(&sorted, 0.10);
+    let speech_level_dbfs = percentile(&sorted, 0.95);
+    let snr_db = speech_level_dbfs - noise_floor_dbfs;
+    let peak_dbfs = to_dbfs(samples.iter().fold(0.0f32, |max, s| max.max(s.abs())));
+    let clipped = samples.iter().filter(|s| s.abs() >= CLIP_LEVEL).count();
+    let clipping_ratio = if samples.is_empty() { 0.0 } else { clipped as f32 / samples.len() as f32 };
+    let speech_detected = snr_db >= 10.0;
+    let reverberation_ms = estimate_reverberation_ms(&levels, noise_floor_dbfs, speech_level_dbfs);
+
+    let mut recommendations = Vec::new();
+    if clipping_ratio > 0.001 || peak_dbfs > -1.0 {
+        recommendations.push(format!(
+            "The input is clipping (peak {:.1} dBFS): lower the input gain or move the microphone away from the mouth",
+            peak_dbfs
+        ));
+    }
+    if noise_floor_dbfs > -50.0 {
+        recommendations.push(format!(
+            "Background noise is high ({:.0} dBFS): move away from fans or air conditioning, close the windows, or enable noise suppression",
+            noise_floor_dbfs
+        ));
+    }
+    if !speech_detected {
+        recommendations.push("No speech detected: speak normally during the analysis to measure the signal-to-noise ratio".to_string());
+    } else {
+        if snr_db < 20.0 {
+            recommendations.push(format!(
+                "Speech is only {:.0} dB above the background noise: move the microphone closer to the speakers",
+                snr_db
+            ));
+        }
+        if speech_level_dbfs < -35.0 && clipping_ratio <= 0.001 {
+            recommendations.push(format!(
+                "Speech level is low ({:.0} dBFS): raise the input gain or move closer to the microphone",
+                speech_level_dbfs
+            ));
+        }
+    }
+    if let Some(rt60) = reverberation_ms {
+        if rt60 > 600 {
+            recommendations.push(format!(
+                "The room is reverberant (~{} ms): prefer a smaller or furnished room, or a close-talk microphone",
+                rt60
+            ));
+        }
+    }
+
+    EnvironmentReport {
+        device,
+        sample_rate,
+        duration_ms: (samples.len() as u64 * 1000 / sample_rate.max(1) as u64) as u32,
+        noise_floor_dbfs,
+        speech_level_dbfs,
+        snr_db,
+        peak_dbfs,
+        clipping_ratio,
+        reverberation_ms,
+        speech_detected,
+        recommendations,
+    }
+}
+
+/// Enregistre `duration_ms` du périphérique (mono, moyenne des canaux) au format négocié
+pub fn capture(device_name: Option<&str>, duration_ms: u32) -> Result<(Vec<f32>, u32, Option<String>)> {
+    let (device, config) = open_input_device(device_name)?;
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels();
+    let wanted = sample_rate as usize * duration_ms as usize / 1000;
+
+    let samples = Arc::new(Mutex::new(Vec::with_capacity(wanted)));
+    let sink = Arc::clone(&samples);
+    let stream = build_input_stream(
+        &device,
+        &config,
+        &config.config(),
+        move |data| sink.lock().unwrap().extend(downmix_to_mono(data, channels)),
+        |_| {},
+    )?;
+    stream.play()?;
+
+    // Marge pour le démarrage du flux
+    let deadline = std::time::Instant::now() + std::time::Duration::from_millis(duration_ms as u64 + 2000);
+    while samples.lock().unwrap().len() < wanted && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    drop(stream);
+
+    let mut samples = std::mem::take(&mut *samples.lock().unwrap());
+    samples.truncate(wanted);
+    if samples.is_empty() {
+        anyhow::bail!("No audio received from the input device");
+    }
+    Ok((samples, sample_rate, device.name().ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_generation_and_estimation() {
+        let tone = generate_tone(TONE_HZ, 16000, 2, 500, 0.5);
+        assert_eq!(tone.len(), 16000);
+        assert_eq!(tone[2], tone[3]);
+        let mono: Vec<f32> = tone.iter().step_by(2).copied().collect();
+        assert!((estimate_frequency(&mono, 16000) - TONE_HZ).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_round_trip_common_device_rates() {
+        let dir = std::env::temp_dir().join(format!("pipeline_test_{}", std::process::id()));
+        for rate in [16000, 44100, 48000] {
+            let report = round_trip(&dir, "null", None, rate, 1).unwrap();
+            assert!(report.passed, "{} Hz: {:?}", rate, report.issues);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_round_trip_downmixes_interleaved_channels() {
+        let dir = std::env::temp_dir().join(format!("pipeline_stereo_{}", std::process::id()));
+        for channels in [2, 4] {
+            let report = round_trip(&dir, "null", None, 48000, channels).unwrap();
+            assert!(report.passed, "{} channels: {:?}", channels, report.issues);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Bruit blanc pseudo-aléatoire reproductible
+    fn noise(len: usize, amplitude: f32) -> Vec<f32> {
+        let mut state = 12345u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                ((state >> 16) as f32 / 32768.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    /// Deux secondes: voix simulée (sinusoïde) entre deux silences bruités
+    fn speech_in_noise(speech_amplitude: f32, noise_amplitude: f32) -> Vec<f32> {
+        let mut samples = noise(32000, noise_amplitude);
+        let tone = generate_tone(TONE_HZ, 16000, 1, 1000, speech_amplitude);
+        for (sample, voice) in samples[8000..24000].iter_mut().zip(tone) {
+            *sample += voice;
+        }
+        samples
+    }
+
+    #[test]
+    fn test_quiet_room_needs_no_change() {
+        let report = analyze_environment(&speech_in_noise(0.3, 0.001), 16000, None);
+        assert!(report.speech_detected);
+        assert!(report.snr_db > 40.0, "{:?}", report);
+        assert!(report.noise_floor_dbfs < -55.0);
+        assert_eq!(report.clipping_ratio, 0.0);
+        assert!(report.recommendations.is_empty(), "{:?}", report.recommendations);
+    }
+
+    #[test]
+    fn test_noisy_clipping_and_silent_inputs() {
+        let noisy = analyze_environment(&speech_in_noise(0.3, 0.05), 16000, None);
+        assert!(noisy.speech_detected);
+        assert!(noisy.recommendations.iter().any(|r| r.contains("Background noise")));
+        assert!(noisy.recommendations.iter().any(|r| r.contains("closer")));
+
+        let clipped: Vec<f32> = speech_in_noise(1.5, 0.001).iter().map(|s| s.clamp(-1.0, 1.0)).collect();
+        let clipping = analyze_environment(&clipped, 16000, None);
+        assert!(clipping.clipping_ratio > 0.01);
+        assert!(clipping.recommendations.iter().any(|r| r.contains("clipping")));
+
+        let silent = analyze_environment(&noise(32000, 0.001), 16000, None);
+        assert!(!silent.speech_detected);
+        assert!(silent.recommendations.iter().any(|r| r.contains("No speech")));
+    }
+
+    #[test]
+    fn test_reverberation_from_decay_tail() {
+        // Voix coupée net puis queue exponentielle: -20 dB en 200ms => RT60 ~600ms
+        let mut samples = speech_in_noise(0.3, 0.0001);
+        for (i, sample) in samples[24000..].iter_mut().enumerate() {
+            let t = i as f32 / 16000.0;
+            *sample += (2.0 * std::f32::consts::PI * TONE_HZ * t).sin() * 0.3 * 10f32.powf(-t / 0.2);
+        }
+        let report = analyze_environment(&samples, 16000, None);
+        let rt60 = report.reverberation_ms.expect("decay should be measured");
+        assert!((540..=720).contains(&rt60), "{} ms", rt60);
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 /// Configuration pour l'amélioration audio
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AudioEnhancementConfig {
     /// Force de réduction du bruit (0.0 = aucun, 1.0 = maximum)
     pub noise_reduction: f32,
@@ -42,7 +45,62 @@ impl AudioEnhancer {
 
     /// Traite un fichier WAV entier et le sauvegarde
     pub fn process_file(&self, input_path: &Path, output_path: &Path) -> Result<()> {
-        // Construire la chaîne de filtres ffmpeg
+        // Exécuter ffmpeg avec conversion à 16kHz pour Whisper
+        let output = Command::new("ffmpeg")
+            .arg("-i").arg(input_path)
+            .arg("-af").arg(self.filter_chain())
+            .arg("-ar").arg("16000") // Resample à 16kHz pour Whisper
+            .arg("-ac").arg("1") // Mono
+            .arg("-y") // Overwrite output file
+            .arg(output_path)
+            .output()
+            .context("Failed to run ffmpeg")?;
+        
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("ffmpeg failed: {}", stderr);
+        }
+        
+        Ok(())
+    }
+
+    /// Traite des samples 16 bits mono en mémoire, sans fichier temporaire: PCM brut
+    /// vers l'entrée standard de ffmpeg, samples f32 à 16kHz lus sur sa sortie
+    pub fn process_samples(&self, samples: &[i16], sample_rate: u32) -> Result<Vec<f32>> {
+        let mut child = Command::new("ffmpeg")
+            .args(["-f", "s16le", "-ar", &sample_rate.to_string(), "-ac", "1", "-i", "pipe:0"])
+            .arg("-af").arg(self.filter_chain())
+            .args(["-f", "f32le", "-ar", "16000", "-ac", "1", "pipe:1"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run ffmpeg")?;
+
+        // Écrire depuis un autre thread: ffmpeg remplit sa sortie avant d'avoir tout lu
+        let mut stdin = child.stdin.take().context("Failed to open ffmpeg stdin")?;
+        let input: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+        let output = child.wait_with_output().context("Failed to run ffmpeg")?;
+        let written = writer.join();
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("ffmpeg failed: {}", stderr);
+        }
+        if let Ok(Err(e)) = written {
+            anyhow::bail!("Failed to send samples to ffmpeg: {}", e);
+        }
+
+        Ok(output
+            .stdout
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect())
+    }
+
+    /// Chaîne de filtres ffmpeg de la configuration
+    fn filter_chain(&self) -> String {
         let mut filters = Vec::new();
         
         // Highpass filter (coupe les basses fréquences < 200Hz)
@@ -60,25 +118,7 @@ impl AudioEnhancer {
             filters.push("dynaudnorm=f=150:g=15".to_string());
         }
         
-        let filter_chain = filters.join(",");
-        
-        // Exécuter ffmpeg avec conversion à 16kHz pour Whisper
-        let output = Command::new("ffmpeg")
-            .arg("-i").arg(input_path)
-            .arg("-af").arg(&filter_chain)
-            .arg("-ar").arg("16000") // Resample à 16kHz pour Whisper
-            .arg("-ac").arg("1") // Mono
-            .arg("-y") // Overwrite output file
-            .arg(output_path)
-            .output()
-            .context("Failed to run ffmpeg")?;
-        
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("ffmpeg failed: {}", stderr);
-        }
-        
-        Ok(())
+        filters.join(",")
     }
 }
 
@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample};
+use log::warn;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::audio_session::TARGET_SAMPLE_RATE;
+
+/// Audio en attente de lecture au-delà duquel les plus anciens samples sont jetés:
+/// l'écoute reste à moins de 200ms du direct même si la sortie prend du retard
+const MAX_MONITOR_LATENCY_MS: u32 = 200;
+
+/// Samples mono 16kHz (après gain et AGC) en attente de lecture sur la sortie
+pub type MonitorBuffer = Arc<Mutex<VecDeque<f32>>>;
+
+/// Ajoute des samples traités au tampon d'écoute, en jetant les plus anciens s'il déborde
+pub fn push_samples(buffer: &MonitorBuffer, samples: &[i16]) {
+    let max = (TARGET_SAMPLE_RATE * MAX_MONITOR_LATENCY_MS / 1000) as usize;
+    let mut buffer = buffer.lock().unwrap();
+    buffer.extend(samples.iter().map(|&s| s as f32 / 32768.0));
+    let overflow = buffer.len().saturating_sub(max);
+    buffer.drain(..overflow);
+}
+
+/// Lecture du tampon 16kHz mono au sample rate et au nombre de canaux de la sortie
+/// (interpolation linéaire, même sample sur tous les canaux)
+pub struct MonitorReader {
+    /// Avance dans le tampon par frame de sortie (16000 / sample rate de sortie)
+    step: f64,
+    phase: f64,
+    current: f32,
+    next: f32,
+}
+
+impl MonitorReader {
+    pub fn new(output_rate: u32) -> Self {
+        Self { step: TARGET_SAMPLE_RATE as f64 / output_rate as f64, phase: 0.0, current: 0.0, next: 0.0 }
+    }
+
+    /// Remplit `out` (frames entrelacées); un tampon vide donne du silence
+    pub fn fill(&mut self, source: &mut VecDeque<f32>, out: &mut [f32], channels: usize) {
+        for frame in out.chunks_mut(channels.max(1)) {
+            while self.phase >= 1.0 {
+                self.phase -= 1.0;
+                self.current = self.next;
+                self.next = source.pop_front().unwrap_or(0.0);
+            }
+            let value = self.current + (self.next - self.current) * self.phase as f32;
+            frame.fill(value);
+            self.phase += self.step;
+        }
+    }
+}
+
+/// Ouvre la sortie par défaut et y joue le tampon d'écoute. Renvoie le flux (à garder
+/// vivant pendant l'écoute) et le nom du périphérique.
+pub fn open_monitor_output(buffer: MonitorBuffer) -> Result<(cpal::Stream, String)> {
+    let host = cpal::default_host();
+    let device = host.default_output_device().context("No output device available")?;
+    let device_name = device.name().unwrap_or_else(|_| "unknown".to_string());
+    let config = device.default_output_config().context("Failed to get default output config")?;
+    let stream_config: cpal::StreamConfig = config.clone().into();
+
+    fn build<T>(device: &cpal::Device, stream_config: &cpal::StreamConfig, buffer: MonitorBuffer) -> Result<cpal::Stream>
+    where
+        T: SizedSample + FromSample<f32>,
+    {
+        let channels = stream_config.channels as usize;
+        let mut reader = MonitorReader::new(stream_config.sample_rate.0);
+        let mut rendered = Vec::new();
+        let stream = device.build_output_stream(
+            stream_config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                rendered.resize(data.len(), 0.0);
+                reader.fill(&mut buffer.lock().unwrap(), &mut rendered, channels);
+                for (out, &value) in data.iter_mut().zip(&rendered) {
+                    *out = T::from_sample(value);
+                }
+            },
+            move |err| warn!("Monitor stream error: {}", err),
+            None,
+        )?;
+        Ok(stream)
+    }
+
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => build::<f32>(&device, &stream_config, buffer)?,
+        SampleFormat::I16 => build::<i16>(&device, &stream_config, buffer)?,
+        SampleFormat::I32 => build::<i32>(&device, &stream_config, buffer)?,
+        SampleFormat::U16 => build::<u16>(&device, &stream_config, buffer)?,
+        other => anyhow::bail!("Unsupported output sample format {:?}", other),
+    };
+    stream.play()?;
+    Ok((stream, device_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reader_upsamples_and_bounds_latency() {
+        let buffer: MonitorBuffer = Arc::new(Mutex::new(VecDeque::new()));
+        push_samples(&buffer, &[16384; 16]);
+        let mut out = vec![0.0; 48 * 2];
+        let mut reader = MonitorReader::new(48000);
+        reader.fill(&mut buffer.lock().unwrap(), &mut out, 2);
+        // 48 frames stéréo à 48kHz consomment 16 samples à 16kHz
+        assert!(buffer.lock().unwrap().len() <= 1);
+        assert_eq!(out[94], out[95]);
+        assert!((out[94] - 0.5).abs() < 1e-6);
+
+        // Sans audio: silence (après le dernier sample interpolé)
+        let mut out = vec![1.0; 24];
+        reader.fill(&mut buffer.lock().unwrap(), &mut out, 2);
+        assert_eq!(out[23], 0.0);
+
+        // Une sortie bloquée ne laisse pas le retard s'accumuler
+        push_samples(&buffer, &vec![0; 16000]);
+        assert_eq!(buffer.lock().unwrap().len(), (TARGET_SAMPLE_RATE * MAX_MONITOR_LATENCY_MS / 1000) as usize);
+    }
+}
@@ -1,13 +1,47 @@
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SampleRate, SizedSample, SupportedStreamConfig, SupportedStreamConfigRange};
 use log::{debug, info, warn};
-use std::fs::File;
-use std::io::Write;
+use serde::{Deserialize, Serialize};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use webrtc_vad::{Vad, VadMode};
 
+use crate::audio_diagnostics::{to_dbfs, CLIP_LEVEL};
+use crate::audio_enhancement::AudioEnhancementConfig;
+use crate::audio_monitor::{self, MonitorBuffer};
+use crate::echo_canceller::{EchoCanceller, DEFAULT_TAIL_MS};
+use crate::power::PowerProfile;
+use crate::safe_write::AtomicFile;
+
+/// Sample rate attendu par le VAD et par Whisper
+pub const TARGET_SAMPLE_RATE: u32 = 16000;
+/// Taille d'une frame VAD (480 samples = 30ms à 16kHz)
+const VAD_FRAME_SIZE: usize = 480;
+/// Profil basse consommation: une décision VAD pour 3 frames (90ms), appliquée au bloc entier
+const LOW_POWER_VAD_STRIDE: usize = 3;
+/// Profil basse consommation: buffer audio demandé au périphérique (moins de réveils du callback)
+const LOW_POWER_BUFFER_MS: u32 = 100;
+/// Profil basse consommation: durée de parole gardée en mémoire avant d'écrire les WAV d'un coup
+const LOW_POWER_WRITE_BATCH_MS: u32 = 120_000;
+/// Tampon d'écriture des WAV (1 Mo en basse consommation, soit ~30s de prise par écriture)
+const WAV_WRITE_BUFFER: usize = 64 * 1024;
+const LOW_POWER_WAV_WRITE_BUFFER: usize = 1024 * 1024;
+/// Période des mesures de niveau envoyées au vumètre
+const LEVEL_INTERVAL_MS: u32 = 100;
+/// Sans bloc audio pendant ce délai (hors pause), le flux est considéré comme mort
+const STREAM_STALL_TIMEOUT_MS: u64 = 2000;
+/// Délai entre deux tentatives de reconnexion au périphérique par défaut
+const RECONNECT_RETRY_MS: u64 = 1000;
+/// Parole continue: au-delà de `max_utterance_ms`, l'utterance est coupée au premier
+/// silence, ou de force après ce délai supplémentaire
+const MAX_UTTERANCE_GRACE_MS: u32 = 5000;
+/// Double capture: référence de l'annulation d'écho gardée au plus 1s (les deux flux
+/// n'avancent pas exactement au même rythme)
+const FAR_END_MAX_SAMPLES: usize = TARGET_SAMPLE_RATE as usize;
+
 /// Wrapper pour rendre Vad thread-safe
 /// SAFETY: Vad est toujours utilisé derrière un Mutex, donc l'accès concurrent est contrôlé
 struct SendVad(Vad);
@@ -20,12 +54,416 @@ impl SendVad {
     }
 }
 
+/// Moteur de détection de voix effectivement utilisé
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VadEngine {
+    WebRtc,
+    /// Secours quand webrtc-vad ne s'initialise pas ou refuse les frames
+    Energy,
+}
+
+/// Plancher initial du bruit de fond (RMS normalisé, ~-50 dBFS)
+const ENERGY_VAD_INITIAL_FLOOR: f32 = 0.003;
+/// En dessous de ce niveau (~-46 dBFS), jamais de voix, quel que soit le plancher
+const ENERGY_VAD_MIN_RMS: f32 = 0.005;
+/// Hystérésis: pour rester en parole, il suffit de dépasser le plancher de ce facteur
+const ENERGY_VAD_OFF_RATIO: f32 = 1.8;
+
+/// VAD de secours: seuil d'énergie au-dessus d'un plancher de bruit suivi en continu,
+/// avec hystérésis pour ne pas couper les fins de mots
+struct EnergyVad {
+    noise_floor: f32,
+    /// Facteur au-dessus du plancher pour entrer en parole (selon la sensibilité)
+    on_ratio: f32,
+    speaking: bool,
+}
+
+impl EnergyVad {
+    fn new(mode: &VadMode) -> Self {
+        let on_ratio = match mode {
+            VadMode::Quality => 2.0,
+            VadMode::LowBitrate => 2.5,
+            VadMode::Aggressive => 3.0,
+            VadMode::VeryAggressive => 4.0,
+        };
+        Self { noise_floor: ENERGY_VAD_INITIAL_FLOOR, on_ratio, speaking: false }
+    }
+
+    fn is_voice(&mut self, frame: &[i16]) -> bool {
+        let rms = (frame.iter().map(|&s| (s as f32 / 32768.0).powi(2)).sum::<f32>() / frame.len().max(1) as f32).sqrt();
+        let ratio = if self.speaking { ENERGY_VAD_OFF_RATIO } else { self.on_ratio };
+        self.speaking = rms > (self.noise_floor * ratio).max(ENERGY_VAD_MIN_RMS);
+        // Le plancher suit vite une baisse du bruit, lentement une hausse, et pas la voix
+        if rms < self.noise_floor {
+            self.noise_floor += (rms - self.noise_floor) * 0.1;
+        } else if !self.speaking {
+            self.noise_floor += (rms - self.noise_floor) * 0.01;
+        }
+        self.speaking
+    }
+}
+
+/// webrtc-vad, remplacé par le VAD d'énergie s'il ne peut pas servir: sans ce secours,
+/// chaque frame refusée compterait comme du silence et rien ne serait découpé
+enum VoiceDetector {
+    WebRtc(SendVad, EnergyVad),
+    Energy(EnergyVad),
+}
+
+impl VoiceDetector {
+    fn new(mode: &VadMode) -> Self {
+        let fallback = EnergyVad::new(mode);
+        let webrtc_mode = match mode {
+            VadMode::Quality => VadMode::Quality,
+            VadMode::LowBitrate => VadMode::LowBitrate,
+            VadMode::Aggressive => VadMode::Aggressive,
+            VadMode::VeryAggressive => VadMode::VeryAggressive,
+        };
+        // webrtc-vad panique si libfvad ne s'initialise pas
+        let vad = std::panic::catch_unwind(move || SendVad(Vad::new_with_rate_and_mode(webrtc_vad::SampleRate::Rate16kHz, webrtc_mode)))
+            .ok()
+            .and_then(|mut vad| vad.is_voice_segment(&[0; VAD_FRAME_SIZE]).map(|_| vad));
+        match vad {
+            Some(vad) => VoiceDetector::WebRtc(vad, fallback),
+            None => {
+                warn!("webrtc-vad unavailable, falling back to the energy VAD");
+                VoiceDetector::Energy(fallback)
+            }
+        }
+    }
+
+    fn engine(&self) -> VadEngine {
+        match self {
+            VoiceDetector::WebRtc(..) => VadEngine::WebRtc,
+            VoiceDetector::Energy(_) => VadEngine::Energy,
+        }
+    }
+
+    fn is_voice(&mut self, frame: &[i16]) -> bool {
+        if let VoiceDetector::WebRtc(vad, fallback) = self {
+            match vad.is_voice_segment(frame) {
+                Some(is_voice) => return is_voice,
+                None => {
+                    warn!("webrtc-vad rejected a {}-sample frame, switching to the energy VAD", frame.len());
+                    let fallback = std::mem::replace(fallback, EnergyVad::new(&VadMode::Aggressive));
+                    *self = VoiceDetector::Energy(fallback);
+                }
+            }
+        }
+        match self {
+            VoiceDetector::Energy(energy) => energy.is_voice(frame),
+            VoiceDetector::WebRtc(..) => unreachable!("replaced above"),
+        }
+    }
+}
+
+/// Moteur VAD qu'aurait une session avec ce mode (diagnostic)
+pub fn probe_vad_engine(mode: &VadMode) -> VadEngine {
+    VoiceDetector::new(mode).engine()
+}
+
+/// Sensibilité du VAD, de la plus permissive (Quality) à la plus stricte (VeryAggressive)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VadSensitivity {
+    Quality,
+    LowBitrate,
+    #[default]
+    Aggressive,
+    VeryAggressive,
+}
+
+impl From<VadSensitivity> for VadMode {
+    fn from(sensitivity: VadSensitivity) -> Self {
+        match sensitivity {
+            VadSensitivity::Quality => VadMode::Quality,
+            VadSensitivity::LowBitrate => VadMode::LowBitrate,
+            VadSensitivity::Aggressive => VadMode::Aggressive,
+            VadSensitivity::VeryAggressive => VadMode::VeryAggressive,
+        }
+    }
+}
+
+/// Réglages de détection de parole exposés à l'interface et persistés dans les paramètres
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VadSettings {
+    /// Durée minimale du silence pour considérer la fin d'une utterance (en ms)
+    pub silence_duration_ms: u32,
+    /// Durée minimale d'une utterance valide (en ms)
+    pub min_utterance_duration_ms: u32,
+    pub vad_mode: VadSensitivity,
+    /// Alerte quand aucune voix n'est détectée pendant cette durée d'enregistrement
+    /// (en s, 0 = désactivée)
+    pub silence_alert_secs: u32,
+    /// Durée au-delà de laquelle une utterance sans pause est coupée, pour que la
+    /// transcription live ne prenne pas de retard (en s, 0 = pas de limite)
+    pub max_utterance_secs: u32,
+}
+
+impl Default for VadSettings {
+    fn default() -> Self {
+        Self {
+            silence_duration_ms: 1000,
+            min_utterance_duration_ms: 300,
+            vad_mode: VadSensitivity::Aggressive,
+            silence_alert_secs: 120,
+            max_utterance_secs: 30,
+        }
+    }
+}
+
+impl VadSettings {
+    /// Refuse les valeurs qui rendraient le découpage inutilisable
+    pub fn validate(&self) -> Result<()> {
+        if !(100..=10_000).contains(&self.silence_duration_ms) {
+            anyhow::bail!("silence_duration_ms must be between 100 and 10000 (got {})", self.silence_duration_ms);
+        }
+        if self.min_utterance_duration_ms > 10_000 {
+            anyhow::bail!("min_utterance_duration_ms must be at most 10000 (got {})", self.min_utterance_duration_ms);
+        }
+        if self.silence_alert_secs > 3600 {
+            anyhow::bail!("silence_alert_secs must be at most 3600 (got {})", self.silence_alert_secs);
+        }
+        if self.max_utterance_secs > 600 {
+            anyhow::bail!("max_utterance_secs must be at most 600 (got {})", self.max_utterance_secs);
+        }
+        Ok(())
+    }
+}
+
+/// Réglages de niveau et d'amélioration propres à un micro, persistés par nom de
+/// périphérique pour passer du micro du portable à la pieuvre de conférence sans tout
+/// réajuster
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioProfile {
+    /// Gain multiplier (1.0 = pas de gain, 2.0 = double le volume)
+    pub gain: f32,
+    pub enable_agc: bool,
+    /// Niveau cible pour l'AGC (0.0 à 1.0)
+    pub agc_target_level: f32,
+    /// Amélioration ffmpeg appliquée avant la transcription
+    pub enhancement: AudioEnhancementConfig,
+}
+
+impl Default for AudioProfile {
+    fn default() -> Self {
+        Self {
+            gain: 2.0, // Double le volume par défaut (réduit de 3.0 pour éviter distorsion)
+            enable_agc: true, // AGC activé par défaut
+            agc_target_level: 0.3, // Normaliser à 30% du niveau max (réduit de 0.5 pour éviter clipping)
+            enhancement: AudioEnhancementConfig::default(),
+        }
+    }
+}
+
+impl AudioProfile {
+    /// Refuse les réglages qui satureraient ou couperaient le signal
+    pub fn validate(&self) -> Result<()> {
+        if !(0.1..=10.0).contains(&self.gain) {
+            anyhow::bail!("gain must be between 0.1 and 10 (got {})", self.gain);
+        }
+        if !(0.05..=1.0).contains(&self.agc_target_level) {
+            anyhow::bail!("agc_target_level must be between 0.05 and 1 (got {})", self.agc_target_level);
+        }
+        if !(0.0..=1.0).contains(&self.enhancement.noise_reduction) {
+            anyhow::bail!("noise_reduction must be between 0 and 1 (got {})", self.enhancement.noise_reduction);
+        }
+        Ok(())
+    }
+}
+
+/// Découpage des enregistrements de l'app
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingMode {
+    /// Un seul segment par prise, transcrit à l'arrêt
+    #[default]
+    PushToTalk,
+    /// Découpage automatique par le VAD, chaque utterance est transcrite dès qu'elle
+    /// se termine (réunions longues)
+    Vad,
+}
+
+impl std::str::FromStr for RecordingMode {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().replace('-', "_").as_str() {
+            "push_to_talk" | "ptt" => Ok(Self::PushToTalk),
+            "vad" => Ok(Self::Vad),
+            other => anyhow::bail!("Unknown recording mode '{}', expected 'push_to_talk' or 'vad'", other),
+        }
+    }
+}
+
+/// Variables reconnues dans le modèle de nom des utterances
+const NAMING_VARIABLES: [&str; 4] = ["index", "session", "timestamp", "speaker"];
+
+/// Nom des fichiers d'utterance et fiches JSON écrites à côté, pour que les dossiers de
+/// segments restent lisibles hors de l'app
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UtteranceNaming {
+    /// Nom sans extension: {index} (0001), {session}, {timestamp} (début de l'utterance,
+    /// 20250314_093000) et {speaker}. {index} est obligatoire pour que les noms restent uniques.
+    pub template: String,
+    /// Locuteur repris dans les noms et les fiches (ex: la personne interviewée)
+    pub speaker: Option<String>,
+    /// Écrit `<nom>.json` (durée, périphérique, gain, mode VAD, transcription) à côté du WAV
+    pub sidecars: bool,
+}
+
+impl Default for UtteranceNaming {
+    fn default() -> Self {
+        Self { template: "utterance_{index}".to_string(), speaker: None, sidecars: true }
+    }
+}
+
+impl UtteranceNaming {
+    /// Refuse les modèles qui donneraient des noms en collision ou hors du dossier de session
+    pub fn validate(&self) -> Result<()> {
+        if !self.template.contains("{index}") {
+            anyhow::bail!("Utterance name template must contain {{index}} (got '{}')", self.template);
+        }
+        if self.template.contains(['/', '\\']) {
+            anyhow::bail!("Utterance name template must not contain path separators");
+        }
+        let mut rest = self.template.as_str();
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..].find('}').context("Unclosed '{' in utterance name template")?;
+            let variable = &rest[start + 1..start + end];
+            if !NAMING_VARIABLES.contains(&variable) {
+                anyhow::bail!("Unknown variable {{{}}} in utterance name template, expected one of {:?}", variable, NAMING_VARIABLES);
+            }
+            rest = &rest[start + end + 1..];
+        }
+        Ok(())
+    }
+
+    /// Nom (sans extension) de l'utterance `index`; les valeurs substituées ne gardent que
+    /// lettres, chiffres, `-` et `_`
+    pub fn file_stem(&self, index: usize, session: &str, started_at: chrono::DateTime<chrono::Local>) -> String {
+        let clean = |value: &str| -> String {
+            value.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+        };
+        self.template
+            .replace("{index}", &format!("{:04}", index))
+            .replace("{session}", &clean(session))
+            .replace("{timestamp}", &started_at.format("%Y%m%d_%H%M%S").to_string())
+            .replace("{speaker}", &clean(self.speaker.as_deref().unwrap_or("unknown")))
+    }
+}
+
+/// Flux d'où vient une utterance. En double capture, le micro capte la personne dans la
+/// pièce et le loopback (sortie système) les participants distants d'une visio.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioSource {
+    #[default]
+    Mic,
+    System,
+}
+
+impl AudioSource {
+    /// Étiquette des lignes du transcript (voir `recording_session::transcript`)
+    pub fn label(self) -> &'static str {
+        match self {
+            AudioSource::Mic => "Micro",
+            AudioSource::System => "Système",
+        }
+    }
+
+    /// Source d'une étiquette de transcript (`Micro:`), None pour un autre mot
+    pub fn from_label(word: &str) -> Option<Self> {
+        let label = word.strip_suffix(':')?;
+        [AudioSource::Mic, AudioSource::System].into_iter().find(|source| source.label() == label)
+    }
+}
+
+/// Double capture choisie par l'utilisateur. Le loopback est un périphérique d'entrée qui
+/// rejoue la sortie système (BlackHole sur macOS, "Monitor of ..." sous PulseAudio,
+/// "Stereo Mix" sous Windows).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SystemCapture {
+    /// Périphérique loopback capté en plus du micro (None = micro seul)
+    pub device: Option<String>,
+    /// Retire du micro l'écho des haut-parleurs
+    pub echo_cancellation: bool,
+}
+
+impl Default for SystemCapture {
+    fn default() -> Self {
+        Self { device: None, echo_cancellation: true }
+    }
+}
+
+/// Fiche JSON d'une utterance (`utterance_0001.json` à côté de `utterance_0001.wav`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UtteranceSidecar {
+    pub id: usize,
+    pub file_name: String,
+    /// Piste brute (`archive_raw`)
+    pub raw_file_name: Option<String>,
+    pub session: String,
+    pub speaker: Option<String>,
+    /// Flux capté (micro ou loopback système)
+    #[serde(default)]
+    pub source: AudioSource,
+    /// Début de l'utterance (RFC 3339)
+    pub started_at: String,
+    pub duration_ms: u32,
+    /// Sample rate du WAV
+    pub sample_rate: u32,
+    /// Sample rate natif du périphérique, avant rééchantillonnage
+    #[serde(default)]
+    pub device_sample_rate: u32,
+    pub device: String,
+    pub gain: f32,
+    pub agc: bool,
+    pub vad_mode: String,
+    pub push_to_talk: bool,
+    /// Ajoutée une fois l'utterance transcrite
+    pub transcript: Option<String>,
+}
+
+/// Fiche JSON d'un WAV d'utterance
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    path.with_extension("json")
+}
+
+/// Inscrit la transcription dans la fiche de l'utterance (rien si elle n'en a pas)
+pub fn set_sidecar_transcript(path: &Path, transcript: &str) -> Result<()> {
+    let sidecar_file = sidecar_path(path);
+    if !sidecar_file.exists() {
+        return Ok(());
+    }
+    let content = std::fs::read_to_string(&sidecar_file).context("Failed to read utterance sidecar")?;
+    let mut sidecar: UtteranceSidecar = serde_json::from_str(&content).context("Invalid utterance sidecar")?;
+    sidecar.transcript = Some(transcript.to_string());
+    crate::safe_write::write(&sidecar_file, serde_json::to_vec_pretty(&sidecar)?)
+}
+
+fn vad_mode_name(mode: &VadMode) -> &'static str {
+    match mode {
+        VadMode::Quality => "Quality",
+        VadMode::LowBitrate => "LowBitrate",
+        VadMode::Aggressive => "Aggressive",
+        VadMode::VeryAggressive => "VeryAggressive",
+    }
+}
+
 /// Configuration pour la session audio
 pub struct AudioSessionConfig {
     /// Durée minimale du silence pour considérer la fin d'une utterance (en ms)
     pub silence_duration_ms: u32,
     /// Durée minimale d'une utterance valide (en ms)
     pub min_utterance_duration_ms: u32,
+    /// Durée au-delà de laquelle une utterance sans pause est coupée (en ms, 0 = pas de limite)
+    pub max_utterance_ms: u32,
     /// Répertoire où sauvegarder les fichiers WAV temporaires
     pub output_dir: PathBuf,
     /// Mode VAD (Quality, LowBitrate, Aggressive, VeryAggressive)
@@ -40,6 +478,46 @@ pub struct AudioSessionConfig {
     pub agc_target_level: f32,
     /// Mode push-to-talk: enregistre tout le flux entre start/stop sans découpage VAD
     pub push_to_talk: bool,
+    /// Micro virtuel: lit un fichier WAV à la place du périphérique
+    pub virtual_input: Option<VirtualInput>,
+    /// Durée sans voix détectée avant un `SilenceEvent::Alert` (en ms, 0 = désactivé)
+    pub silence_alert_ms: u32,
+    /// Profil d'énergie (voir `PowerProfile`)
+    pub power_profile: PowerProfile,
+    /// Archive la capture brute (avant gain et AGC) à côté de chaque utterance traitée,
+    /// pour pouvoir retranscrire plus tard depuis le signal d'origine (voir `raw_track_path`)
+    pub archive_raw: bool,
+    /// Nom des fichiers d'utterance et fiches JSON
+    pub naming: UtteranceNaming,
+    /// Garde les utterances en mémoire (`Utterance::samples`) sans écrire de WAV, de piste
+    /// brute ni de fiche: rien ne reste sur disque, mais la session n'est pas rejouable
+    pub keep_in_memory: bool,
+    /// Flux capté par cette session, repris dans les utterances et leurs fiches
+    pub source: AudioSource,
+    /// Double capture: périphérique loopback (sortie système) ouvert en parallèle du micro,
+    /// ses utterances sont étiquetées `AudioSource::System`
+    pub system_device: Option<String>,
+    /// Double capture: retire du micro l'écho de la sortie système (voir `EchoCanceller`)
+    pub echo_cancellation: bool,
+}
+
+/// Cadence de lecture du micro virtuel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pacing {
+    /// Comme un vrai micro: 1s de fichier prend 1s
+    RealTime,
+    /// Accéléré d'un facteur (2.0 = deux fois plus vite)
+    Accelerated(f32),
+    /// Aussi vite que possible (tests)
+    Unpaced,
+}
+
+/// Fichier WAV joué comme s'il était capté en direct, pour les démos et les tests de bout
+/// en bout (VAD, amélioration, transcription) sans matériel
+#[derive(Debug, Clone)]
+pub struct VirtualInput {
+    pub path: PathBuf,
+    pub pacing: Pacing,
 }
 
 impl Clone for AudioSessionConfig {
@@ -47,6 +525,7 @@ impl Clone for AudioSessionConfig {
         Self {
             silence_duration_ms: self.silence_duration_ms,
             min_utterance_duration_ms: self.min_utterance_duration_ms,
+            max_utterance_ms: self.max_utterance_ms,
             output_dir: self.output_dir.clone(),
             vad_mode: match self.vad_mode {
                 VadMode::Quality => VadMode::Quality,
@@ -59,21 +538,26 @@ impl Clone for AudioSessionConfig {
             enable_agc: self.enable_agc,
             agc_target_level: self.agc_target_level,
             push_to_talk: self.push_to_talk,
+            silence_alert_ms: self.silence_alert_ms,
+            virtual_input: self.virtual_input.clone(),
+            power_profile: self.power_profile,
+            archive_raw: self.archive_raw,
+            naming: self.naming.clone(),
+            keep_in_memory: self.keep_in_memory,
+            source: self.source,
+            system_device: self.system_device.clone(),
+            echo_cancellation: self.echo_cancellation,
         }
     }
 }
 
 impl std::fmt::Debug for AudioSessionConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let vad_mode_repr = match self.vad_mode {
-            VadMode::Quality => "Quality",
-            VadMode::LowBitrate => "LowBitrate",
-            VadMode::Aggressive => "Aggressive",
-            VadMode::VeryAggressive => "VeryAggressive",
-        };
+        let vad_mode_repr = vad_mode_name(&self.vad_mode);
         f.debug_struct("AudioSessionConfig")
             .field("silence_duration_ms", &self.silence_duration_ms)
             .field("min_utterance_duration_ms", &self.min_utterance_duration_ms)
+            .field("max_utterance_ms", &self.max_utterance_ms)
             .field("output_dir", &self.output_dir)
             .field("vad_mode", &vad_mode_repr)
             .field("device_name", &self.device_name)
@@ -81,23 +565,368 @@ impl std::fmt::Debug for AudioSessionConfig {
             .field("enable_agc", &self.enable_agc)
             .field("agc_target_level", &self.agc_target_level)
             .field("push_to_talk", &self.push_to_talk)
+            .field("silence_alert_ms", &self.silence_alert_ms)
+            .field("virtual_input", &self.virtual_input)
+            .field("power_profile", &self.power_profile)
+            .field("archive_raw", &self.archive_raw)
+            .field("naming", &self.naming)
+            .field("keep_in_memory", &self.keep_in_memory)
+            .field("source", &self.source)
+            .field("system_device", &self.system_device)
+            .field("echo_cancellation", &self.echo_cancellation)
             .finish()
     }
 }
 
+impl AudioSessionConfig {
+    /// Ajoute la capture du loopback choisie par l'utilisateur
+    pub fn with_system_capture(self, capture: &SystemCapture) -> Self {
+        Self { system_device: capture.device.clone(), echo_cancellation: capture.echo_cancellation, ..self }
+    }
+
+    /// Applique les réglages VAD choisis par l'utilisateur
+    pub fn with_vad(self, vad: VadSettings) -> Self {
+        Self {
+            silence_duration_ms: vad.silence_duration_ms,
+            min_utterance_duration_ms: vad.min_utterance_duration_ms,
+            max_utterance_ms: vad.max_utterance_secs * 1000,
+            vad_mode: vad.vad_mode.into(),
+            silence_alert_ms: vad.silence_alert_secs * 1000,
+            ..self
+        }
+    }
+
+    /// Applique le profil du périphérique (l'amélioration est appliquée à la transcription)
+    pub fn with_profile(self, profile: &AudioProfile) -> Self {
+        Self {
+            gain: profile.gain,
+            enable_agc: profile.enable_agc,
+            agc_target_level: profile.agc_target_level,
+            ..self
+        }
+    }
+}
+
 impl Default for AudioSessionConfig {
     fn default() -> Self {
+        let vad = VadSettings::default();
+        let profile = AudioProfile::default();
         Self {
-            silence_duration_ms: 1000,
-            min_utterance_duration_ms: 300,
+            silence_duration_ms: vad.silence_duration_ms,
+            min_utterance_duration_ms: vad.min_utterance_duration_ms,
+            max_utterance_ms: vad.max_utterance_secs * 1000,
             output_dir: std::env::temp_dir(),
-            vad_mode: VadMode::Aggressive,
+            vad_mode: vad.vad_mode.into(),
             device_name: None,
-            gain: 2.0, // Double le volume par défaut (réduit de 3.0 pour éviter distorsion)
-            enable_agc: true, // AGC activé par défaut
-            agc_target_level: 0.3, // Normaliser à 30% du niveau max (réduit de 0.5 pour éviter clipping)
+            gain: profile.gain,
+            enable_agc: profile.enable_agc,
+            agc_target_level: profile.agc_target_level,
             push_to_talk: true, // Par défaut: vrai push-to-talk pour l'app Tauri
+            silence_alert_ms: 0, // Activée par l'app via `with_vad`
+            virtual_input: None,
+            power_profile: PowerProfile::Balanced,
+            archive_raw: false,
+            naming: UtteranceNaming::default(),
+            keep_in_memory: false,
+            source: AudioSource::Mic,
+            system_device: None,
+            echo_cancellation: true,
+        }
+    }
+}
+
+/// Préférence entre formats de samples (None = non géré par `build_input_stream`).
+/// f32 évite toute conversion; i16 est le format natif de nombreuses cartes USB.
+fn format_rank(format: SampleFormat) -> Option<u8> {
+    match format {
+        SampleFormat::F32 => Some(4),
+        SampleFormat::I16 => Some(3),
+        SampleFormat::I24 | SampleFormat::I32 | SampleFormat::F64 => Some(2),
+        SampleFormat::I8 | SampleFormat::I64 | SampleFormat::U8 | SampleFormat::U16 | SampleFormat::U32 | SampleFormat::U64 => Some(1),
+        _ => None,
+    }
+}
+
+/// Choisit la configuration d'entrée parmi celles que le périphérique supporte.
+///
+/// Les hôtes diffèrent: WASAPI (mode partagé) n'expose souvent que le format de mixage
+/// (48kHz stéréo f32), Pulse/ALSA exposent des plages larges dont la config par défaut
+/// peut être en i16, certaines cartes n'offrent que de l'i16 ou de l'u16. Ordre de
+/// préférence: 16kHz si la plage le permet (pas de rééchantillonnage), mono, le sample
+/// rate par défaut, puis le format (f32, i16, autres entiers).
+/// Renvoie None si aucun format de samples n'est géré.
+pub fn negotiate_input_config(
+    default: &SupportedStreamConfig,
+    supported: &[SupportedStreamConfigRange],
+) -> Option<SupportedStreamConfig> {
+    let default_rate = default.sample_rate().0;
+    supported
+        .iter()
+        .copied()
+        .filter(|range| format_rank(range.sample_format()).is_some())
+        .map(|range| {
+            let (min, max) = (range.min_sample_rate().0, range.max_sample_rate().0);
+            let rate = if (min..=max).contains(&TARGET_SAMPLE_RATE) {
+                TARGET_SAMPLE_RATE
+            } else if (min..=max).contains(&default_rate) {
+                default_rate
+            } else {
+                // Le plus proche de 16kHz: moins de données à rééchantillonner
+                min.max(TARGET_SAMPLE_RATE).min(max)
+            };
+            range.with_sample_rate(SampleRate(rate))
+        })
+        .max_by_key(|config| {
+            let rate = config.sample_rate().0;
+            (
+                rate == TARGET_SAMPLE_RATE,
+                config.channels() == 1,
+                rate == default_rate,
+                format_rank(config.sample_format()),
+                std::cmp::Reverse(config.channels()),
+            )
+        })
+}
+
+/// Ouvre le périphérique d'entrée (par nom, sinon celui par défaut) et négocie sa
+/// configuration; seule une entrée f32 est acceptée
+pub fn open_input_device(device_name: Option<&str>) -> Result<(cpal::Device, SupportedStreamConfig)> {
+    let host = cpal::default_host();
+    let device = if let Some(device_name) = device_name {
+        info!("Looking for audio device: {}", device_name);
+        host.input_devices()
+            .context("Failed to enumerate input devices")?
+            .find(|d| d.name().map(|n| n == device_name).unwrap_or(false))
+            .ok_or_else(|| anyhow::anyhow!("Audio device '{}' not found", device_name))?
+    } else {
+        host.default_input_device()
+            .context("No input device available")?
+    };
+
+    let default_config = device
+        .default_input_config()
+        .context("Failed to get default input config")?;
+    let supported: Vec<SupportedStreamConfigRange> = device
+        .supported_input_configs()
+        .map(|configs| configs.collect())
+        .unwrap_or_default();
+    let config = negotiate_input_config(&default_config, &supported).unwrap_or(default_config);
+    if format_rank(config.sample_format()).is_none() {
+        anyhow::bail!("Audio device only offers unsupported {:?} samples", config.sample_format());
+    }
+    Ok((device, config))
+}
+
+/// Ouvre un flux d'entrée au format négocié et passe à `on_data` des samples f32
+/// entrelacés, quel que soit le format du périphérique (i16, u16, i24...). Les erreurs
+/// du flux (périphérique débranché...) sont journalisées puis passées à `on_error`.
+pub fn build_input_stream<F, E>(
+    device: &cpal::Device,
+    config: &SupportedStreamConfig,
+    stream_config: &cpal::StreamConfig,
+    on_data: F,
+    on_error: E,
+) -> Result<cpal::Stream>
+where
+    F: FnMut(&[f32]) + Send + 'static,
+    E: FnMut(cpal::StreamError) + Send + 'static,
+{
+    fn build<T, F, E>(device: &cpal::Device, stream_config: &cpal::StreamConfig, mut on_data: F, mut on_error: E) -> Result<cpal::Stream>
+    where
+        T: SizedSample,
+        f32: FromSample<T>,
+        F: FnMut(&[f32]) + Send + 'static,
+        E: FnMut(cpal::StreamError) + Send + 'static,
+    {
+        let mut converted = Vec::new();
+        let stream = device.build_input_stream(
+            stream_config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                converted.clear();
+                converted.extend(data.iter().map(|&sample| sample.to_sample::<f32>()));
+                on_data(&converted);
+            },
+            move |err| {
+                warn!("Stream error: {}", err);
+                on_error(err);
+            },
+            None,
+        )?;
+        Ok(stream)
+    }
+
+    let format = config.sample_format();
+    if format != SampleFormat::F32 {
+        info!("Converting {:?} input samples to f32", format);
+    }
+    match format {
+        SampleFormat::F32 => {
+            let (mut on_data, mut on_error) = (on_data, on_error);
+            let stream = device.build_input_stream(
+                stream_config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| on_data(data),
+                move |err| {
+                    warn!("Stream error: {}", err);
+                    on_error(err);
+                },
+                None,
+            )?;
+            Ok(stream)
+        }
+        SampleFormat::F64 => build::<f64, F, E>(device, stream_config, on_data, on_error),
+        SampleFormat::I8 => build::<i8, F, E>(device, stream_config, on_data, on_error),
+        SampleFormat::I16 => build::<i16, F, E>(device, stream_config, on_data, on_error),
+        SampleFormat::I24 => build::<cpal::I24, F, E>(device, stream_config, on_data, on_error),
+        SampleFormat::I32 => build::<i32, F, E>(device, stream_config, on_data, on_error),
+        SampleFormat::I64 => build::<i64, F, E>(device, stream_config, on_data, on_error),
+        SampleFormat::U8 => build::<u8, F, E>(device, stream_config, on_data, on_error),
+        SampleFormat::U16 => build::<u16, F, E>(device, stream_config, on_data, on_error),
+        SampleFormat::U32 => build::<u32, F, E>(device, stream_config, on_data, on_error),
+        SampleFormat::U64 => build::<u64, F, E>(device, stream_config, on_data, on_error),
+        other => anyhow::bail!("Unsupported input sample format {:?}", other),
+    }
+}
+
+/// Convertit les samples f32 du périphérique en i16 en appliquant le gain
+pub fn to_i16_samples(data: &[f32], gain: f32) -> Vec<i16> {
+    data.iter()
+        .map(|&sample| (sample * gain * 32767.0).clamp(-32768.0, 32767.0) as i16)
+        .collect()
+}
+
+/// Ramène un bloc entrelacé sur `channels` canaux en mono (moyenne des canaux de chaque frame)
+pub fn downmix_to_mono(data: &[f32], channels: u16) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    data.chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Rééchantillonneur linéaire en flux vers TARGET_SAMPLE_RATE: garde la position
+/// fractionnaire et le dernier sample d'un bloc à l'autre, pour ne pas créer de
+/// discontinuité aux frontières des callbacks
+pub(crate) struct StreamResampler {
+    /// Pas dans le signal d'entrée pour un sample de sortie
+    step: f64,
+    /// Position du prochain sample de sortie, 0 = dernier sample du bloc précédent
+    pos: f64,
+    prev: f32,
+}
+
+impl StreamResampler {
+    pub(crate) fn new(from_rate: u32) -> Self {
+        Self { step: from_rate as f64 / TARGET_SAMPLE_RATE as f64, pos: 1.0, prev: 0.0 }
+    }
+
+    pub(crate) fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        let len = input.len() as f64;
+        let mut output = Vec::with_capacity((len / self.step) as usize + 1);
+        while self.pos < len {
+            let i = self.pos as usize;
+            let frac = (self.pos - i as f64) as f32;
+            let a = if i == 0 { self.prev } else { input[i - 1] };
+            output.push(a * (1.0 - frac) + input[i] * frac);
+            self.pos += self.step;
+        }
+        self.pos -= len;
+        if let Some(&last) = input.last() {
+            self.prev = last;
+        }
+        output
+    }
+}
+
+/// Niveau d'entrée sur LEVEL_INTERVAL_MS, mesuré avant gain et AGC: c'est ce que le
+/// micro capte réellement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AudioLevel {
+    pub rms_dbfs: f32,
+    pub peak_dbfs: f32,
+    /// Au moins un sample saturé dans la période
+    pub clipping: bool,
+}
+
+/// Accumulateur des mesures de niveau entre deux envois
+#[derive(Default)]
+struct LevelMeter {
+    sum_squares: f64,
+    peak: f32,
+    count: usize,
+}
+
+pub type LevelListener = Arc<dyn Fn(AudioLevel) + Send + Sync>;
+
+/// Perte ou reprise du périphérique d'entrée pendant la capture
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeviceEvent {
+    /// Le flux s'est arrêté (casque Bluetooth déconnecté, interface USB débranchée...)
+    Lost { device: String, reason: String },
+    /// La capture a repris sur le périphérique par défaut
+    Reconnected { device: String },
+}
+
+pub type DeviceListener = Arc<dyn Fn(DeviceEvent) + Send + Sync>;
+
+/// Silence prolongé pendant l'enregistrement (micro coupé, mauvais périphérique...)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SilenceEvent {
+    /// Aucune voix depuis `silent_ms` (envoyé une fois par silence)
+    Alert { silent_ms: u64 },
+    /// La voix est revenue après une alerte
+    Ended { silent_ms: u64 },
+}
+
+pub type SilenceListener = Arc<dyn Fn(SilenceEvent) + Send + Sync>;
+
+/// Durée d'audio sans voix, comptée en temps de flux (les pauses ne comptent pas)
+#[derive(Default)]
+struct SilenceWatch {
+    silent_ms: u64,
+    alerted: bool,
+}
+
+impl SilenceWatch {
+    /// Prend en compte une décision du VAD couvrant `frame_ms`
+    fn update(&mut self, is_voice: bool, frame_ms: u32, threshold_ms: u32) -> Option<SilenceEvent> {
+        if is_voice {
+            let event = self.alerted.then_some(SilenceEvent::Ended { silent_ms: self.silent_ms });
+            *self = Self::default();
+            return event;
+        }
+        self.silent_ms += frame_ms as u64;
+        if !self.alerted && self.silent_ms >= threshold_ms as u64 {
+            self.alerted = true;
+            return Some(SilenceEvent::Alert { silent_ms: self.silent_ms });
+        }
+        None
+    }
+}
+
+/// État du flux cpal: erreur remontée par cpal, ou date du dernier bloc reçu (certains
+/// hôtes n'appellent plus aucun callback quand le périphérique disparaît)
+struct StreamWatch {
+    last_block: std::time::Instant,
+    error: Option<String>,
+}
+
+impl StreamWatch {
+    fn new() -> Self {
+        Self { last_block: std::time::Instant::now(), error: None }
+    }
+
+    /// Raison de la perte du flux, None s'il est vivant. En pause, le flux peut être
+    /// suspendu: seul le silence hors pause compte.
+    fn lost(&self, now: std::time::Instant, paused: bool) -> Option<String> {
+        if let Some(error) = &self.error {
+            return Some(error.clone());
         }
+        let silent_for = now.saturating_duration_since(self.last_block);
+        if !paused && silent_for > std::time::Duration::from_millis(STREAM_STALL_TIMEOUT_MS) {
+            return Some(format!("No audio received for {} ms", silent_for.as_millis()));
+        }
+        None
     }
 }
 
@@ -108,39 +937,105 @@ pub struct Utterance {
     pub file_path: PathBuf,
     pub duration_ms: u32,
     pub sample_count: usize,
+    /// Sample rate du WAV écrit (TARGET_SAMPLE_RATE, le flux du périphérique étant rééchantillonné)
+    pub sample_rate: u32,
+    /// Samples gardés en mémoire (`keep_in_memory`), `file_path` n'étant alors pas écrit
+    pub samples: Option<Arc<Vec<i16>>>,
+    /// Flux capté (micro ou loopback système)
+    pub source: AudioSource,
+}
+
+/// Durée de `sample_count` échantillons mono, calculée en u64 (en u32, `len * 1000`
+/// déborde au-delà de ~4 min à 16kHz)
+pub fn duration_ms(sample_count: u64, sample_rate: u32) -> u32 {
+    (sample_count * 1000 / sample_rate.max(1) as u64) as u32
+}
+
+/// Piste brute d'une utterance, écrite à côté du WAV traité quand `archive_raw` est activé:
+/// `utterance_0001.wav` -> `utterance_0001.raw.wav`. Mêmes instants, même sample rate
+/// (16kHz mono), mais sans gain ni AGC.
+pub fn raw_track_path(path: &Path) -> PathBuf {
+    path.with_extension("raw.wav")
+}
+
+/// Utterance terminée, pas encore écrite sur disque
+struct PendingWrite {
+    utterance: Utterance,
+    samples: Vec<i16>,
+    // Piste brute (archive_raw)
+    raw: Option<Vec<i16>>,
+    sample_rate: u32,
+    sidecar: Option<UtteranceSidecar>,
+}
+
+/// Prise push-to-talk en cours d'écriture
+struct PttTake {
+    id: usize,
+    file_path: PathBuf,
+    started_at: chrono::DateTime<chrono::Local>,
+    writer: WavStreamWriter,
+    // Piste brute (archive_raw)
+    raw_writer: Option<WavStreamWriter>,
 }
 
 /// Gestionnaire de session audio avec détection d'utterances
 #[derive(Clone)]
 pub struct AudioSession {
     config: AudioSessionConfig,
-    vad: Arc<Mutex<SendVad>>,
+    vad: Arc<Mutex<VoiceDetector>>,
     utterances: Arc<Mutex<Vec<Utterance>>>,
     current_buffer: Arc<Mutex<Vec<i16>>>,
     silence_frames: Arc<Mutex<u32>>,
     utterance_counter: Arc<Mutex<usize>>,
     is_speaking: Arc<Mutex<bool>>,
     stop_flag: Arc<AtomicBool>,
+    // Pause: le flux reste ouvert mais les samples sont ignorés
+    pause_flag: Arc<AtomicBool>,
     // AGC state
     agc_current_gain: Arc<Mutex<f32>>,
     agc_peak_level: Arc<Mutex<f32>>,
-    // Sample rate du device
+    // Sample rate des samples traités (16kHz, sauf WAV virtuel)
     sample_rate: Arc<Mutex<u32>>,
+    // Nombre de canaux entrelacés livrés par le device (1 pour le micro virtuel)
+    channels: Arc<Mutex<u16>>,
+    // Nom du périphérique capté, repris dans les fiches d'utterance
+    device_label: Arc<Mutex<String>>,
+    // Sample rate natif du périphérique (ou du fichier joué), repris dans les fiches
+    device_sample_rate: Arc<Mutex<u32>>,
+    // Conversion du sample rate du device vers 16kHz (None si le device est déjà à 16kHz)
+    resampler: Arc<Mutex<Option<StreamResampler>>>,
+    // Samples en attente d'une frame VAD complète
+    vad_buffer: Arc<Mutex<Vec<i16>>>,
+    // Archive brute: mêmes samples que vad_buffer et current_buffer, avant gain et AGC
+    raw_vad_buffer: Arc<Mutex<Vec<i16>>>,
+    raw_buffer: Arc<Mutex<Vec<i16>>>,
+    // Utterances pas encore écrites sur disque (profil basse consommation)
+    pending_writes: Arc<Mutex<Vec<PendingWrite>>>,
+    // Prise push-to-talk écrite au fil de l'eau
+    ptt_take: Arc<Mutex<Option<PttTake>>>,
+    // Vumètre: destinataire des mesures et accumulateur en cours
+    level_listener: Arc<Mutex<Option<LevelListener>>>,
+    level_meter: Arc<Mutex<LevelMeter>>,
+    // Débranchement du périphérique: destinataire des événements et surveillance du flux
+    device_listener: Arc<Mutex<Option<DeviceListener>>>,
+    stream_watch: Arc<Mutex<StreamWatch>>,
+    // Alerte de silence prolongé
+    silence_listener: Arc<Mutex<Option<SilenceListener>>>,
+    silence_watch: Arc<Mutex<SilenceWatch>>,
+    // Écoute de contrôle: samples traités rejoués sur la sortie par défaut
+    monitor_enabled: Arc<AtomicBool>,
+    monitor_buffer: MonitorBuffer,
+    // Double capture: samples de la sortie système (16kHz mono, avant gain) en attente
+    // d'être retirés du micro, et filtre d'annulation d'écho du micro
+    far_end: Arc<Mutex<std::collections::VecDeque<f32>>>,
+    echo_canceller: Arc<Mutex<Option<EchoCanceller>>>,
 }
 
 impl AudioSession {
     /// Crée une nouvelle session audio
     pub fn new(config: AudioSessionConfig) -> Result<Self> {
         info!("Creating audio session with config: {:?}", config);
-        let vad = Vad::new_with_rate_and_mode(
-            webrtc_vad::SampleRate::Rate16kHz,
-            match config.vad_mode {
-                VadMode::Quality => VadMode::Quality,
-                VadMode::LowBitrate => VadMode::LowBitrate,
-                VadMode::Aggressive => VadMode::Aggressive,
-                VadMode::VeryAggressive => VadMode::VeryAggressive,
-            },
-        );
+        let vad = VoiceDetector::new(&config.vad_mode);
 
         // Créer le répertoire de sortie s'il n'existe pas
         std::fs::create_dir_all(&config.output_dir)
@@ -148,236 +1043,761 @@ impl AudioSession {
 
         Ok(Self {
             config,
-            vad: Arc::new(Mutex::new(SendVad(vad))),
+            vad: Arc::new(Mutex::new(vad)),
             utterances: Arc::new(Mutex::new(Vec::new())),
             current_buffer: Arc::new(Mutex::new(Vec::new())),
             silence_frames: Arc::new(Mutex::new(0)),
             utterance_counter: Arc::new(Mutex::new(0)),
             is_speaking: Arc::new(Mutex::new(false)),
             stop_flag: Arc::new(AtomicBool::new(false)),
+            pause_flag: Arc::new(AtomicBool::new(false)),
             agc_current_gain: Arc::new(Mutex::new(1.0)),
             agc_peak_level: Arc::new(Mutex::new(0.0)),
             sample_rate: Arc::new(Mutex::new(16000)), // Default, sera mis à jour au démarrage
+            vad_buffer: Arc::new(Mutex::new(Vec::new())),
+            raw_vad_buffer: Arc::new(Mutex::new(Vec::new())),
+            raw_buffer: Arc::new(Mutex::new(Vec::new())),
+            pending_writes: Arc::new(Mutex::new(Vec::new())),
+            channels: Arc::new(Mutex::new(1)),
+            device_label: Arc::new(Mutex::new(String::new())),
+            device_sample_rate: Arc::new(Mutex::new(TARGET_SAMPLE_RATE)),
+            resampler: Arc::new(Mutex::new(None)),
+            ptt_take: Arc::new(Mutex::new(None)),
+            level_listener: Arc::new(Mutex::new(None)),
+            level_meter: Arc::new(Mutex::new(LevelMeter::default())),
+            device_listener: Arc::new(Mutex::new(None)),
+            stream_watch: Arc::new(Mutex::new(StreamWatch::new())),
+            silence_listener: Arc::new(Mutex::new(None)),
+            silence_watch: Arc::new(Mutex::new(SilenceWatch::default())),
+            monitor_enabled: Arc::new(AtomicBool::new(false)),
+            monitor_buffer: Arc::new(Mutex::new(std::collections::VecDeque::new())),
+            far_end: Arc::new(Mutex::new(std::collections::VecDeque::new())),
+            echo_canceller: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Session du loopback en double capture: même dossier, même numérotation, mêmes
+    /// signaux d'arrêt et de pause, ses utterances rejoignent celles du micro. Elle alimente
+    /// la référence de l'annulation d'écho du micro.
+    fn system_session(&self, device_name: &str) -> Result<Self> {
+        let config = AudioSessionConfig {
+            device_name: Some(device_name.to_string()),
+            // Le son de la visio arrive déjà à un niveau normalisé: pas de gain micro
+            gain: 1.0,
+            virtual_input: None,
+            source: AudioSource::System,
+            system_device: None,
+            echo_cancellation: false,
+            ..self.config.clone()
+        };
+        let system = Self::new(config)?;
+        Ok(Self {
+            utterances: Arc::clone(&self.utterances),
+            utterance_counter: Arc::clone(&self.utterance_counter),
+            stop_flag: Arc::clone(&self.stop_flag),
+            pause_flag: Arc::clone(&self.pause_flag),
+            far_end: Arc::clone(&self.far_end),
+            ..system
         })
     }
 
+    /// Démarre la capture du loopback dans son propre thread (double capture). Sans
+    /// loopback disponible, l'enregistrement continue avec le micro seul.
+    fn start_system_capture(&self, device_name: &str) -> Option<std::thread::JoinHandle<()>> {
+        let system = match self.system_session(device_name) {
+            Ok(system) => system,
+            Err(e) => {
+                warn!("System audio capture unavailable: {}", e);
+                return None;
+            }
+        };
+        if self.config.echo_cancellation {
+            *self.echo_canceller.lock().unwrap() = Some(EchoCanceller::new(TARGET_SAMPLE_RATE, DEFAULT_TAIL_MS));
+        }
+        info!("Capturing system audio from '{}'", device_name);
+        Some(std::thread::spawn(move || {
+            if let Err(e) = system.start_recording() {
+                warn!("System audio capture failed, recording the microphone only: {}", e);
+            }
+        }))
+    }
+
     /// Démarre la capture audio et la détection d'utterances
     pub fn start_recording(&self) -> Result<()> {
-        let host = cpal::default_host();
-        
-        // Select device based on config
-        let device = if let Some(ref device_name) = self.config.device_name {
-            info!("Looking for audio device: {}", device_name);
-            host.input_devices()
-                .context("Failed to enumerate input devices")?
-                .find(|d| d.name().map(|n| n == *device_name).unwrap_or(false))
-                .ok_or_else(|| anyhow::anyhow!("Audio device '{}' not found", device_name))?
-        } else {
-            host.default_input_device()
-                .context("No input device available")?
-        };
+        if let Some(virtual_input) = self.config.virtual_input.clone() {
+            return self.run_virtual_input(&virtual_input);
+        }
 
-        let config = device
-            .default_input_config()
-            .context("Failed to get default input config")?;
+        let (mut stream, mut device_label) = self.open_stream(self.config.device_name.as_deref())?;
+        let system_capture = self.config.system_device.as_deref().and_then(|device| self.start_system_capture(device));
 
-        let actual_sample_rate = config.sample_rate().0;
-        info!("Audio input device: {}", device.name()?);
-        info!("Sample rate: {} Hz", actual_sample_rate);
-        info!("Channels: {}", config.channels());
-        
-        // Stocker le sample rate réel
-        *self.sample_rate.lock().unwrap() = actual_sample_rate;
-
-        // Clone des Arc pour le stream
-        let sample_rate_arc = Arc::clone(&self.sample_rate);
-        let vad = Arc::clone(&self.vad);
-        let current_buffer = Arc::clone(&self.current_buffer);
-        let silence_frames = Arc::clone(&self.silence_frames);
-        let utterance_counter = Arc::clone(&self.utterance_counter);
-        let is_speaking = Arc::clone(&self.is_speaking);
-        let utterances = Arc::clone(&self.utterances);
-        let session_config = self.config.clone();
-        let agc_current_gain = Arc::clone(&self.agc_current_gain);
-        let agc_peak_level = Arc::clone(&self.agc_peak_level);
-
-        // Buffer pour le VAD (480 samples = 30ms à 16kHz)
-        let vad_frame_size = 480;
-        let vad_buffer = Arc::new(Mutex::new(Vec::new()));
-        let ptt_mode = session_config.push_to_talk;
+        info!("Recording started. Waiting for stop signal...");
+        info!("Utterances will be saved to: {:?}", self.config.output_dir);
 
-        let stream = device.build_input_stream(
-            &config.into(),
-            move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                // Appliquer le gain et normalisation AGC
-                let mut samples: Vec<i16> = data
-                    .iter()
-                    .map(|&sample| sample * session_config.gain)
-                    .collect::<Vec<f32>>()
-                    .iter()
-                    .map(|&sample| (sample * 32767.0).clamp(-32768.0, 32767.0) as i16)
-                    .collect();
-                
-                // AGC: ajuster le gain automatiquement
-                if session_config.enable_agc {
-                    let max_sample = samples.iter()
-                        .map(|&s| s.abs())
-                        .max()
-                        .unwrap_or(0) as f32;
-                    
-                    let current_level = max_sample / 32768.0;
-                    
-                    // Mettre à jour le pic avec lissage
-                    let mut peak = agc_peak_level.lock().unwrap();
-                    *peak = (*peak * 0.95).max(current_level);
-                    
-                    // Calculer le gain nécessaire
-                    if *peak > 0.01 { // Éviter division par zéro
-                        let target_gain = session_config.agc_target_level / *peak;
-                        let mut current_gain = agc_current_gain.lock().unwrap();
-                        
-                        // Lissage du gain (atténuation rapide, amplification lente)
-                        if target_gain < *current_gain {
-                            *current_gain = (*current_gain * 0.8 + target_gain * 0.2).clamp(0.1, 10.0);
-                        } else {
-                            *current_gain = (*current_gain * 0.99 + target_gain * 0.01).clamp(0.1, 10.0);
+        // Garder le stream actif jusqu'au signal d'arrêt; en pause, le stream est suspendu
+        // quand le backend le permet (sinon process_block ignore simplement les samples)
+        let mut stream_paused = false;
+        let mut monitor: Option<cpal::Stream> = None;
+        while !self.stop_flag.load(Ordering::Relaxed) {
+            let monitoring = self.monitor_enabled.load(Ordering::Relaxed);
+            if monitoring != monitor.is_some() {
+                self.monitor_buffer.lock().unwrap().clear();
+                monitor = if monitoring {
+                    match audio_monitor::open_monitor_output(Arc::clone(&self.monitor_buffer)) {
+                        Ok((output, output_name)) => {
+                            info!("Monitoring capture on '{}'", output_name);
+                            Some(output)
                         }
-                        
-                        // Log occasionnel des niveaux audio
-                        static mut LOG_COUNTER: u32 = 0;
-                        unsafe {
-                            LOG_COUNTER += 1;
-                            if LOG_COUNTER % 100 == 0 { // Log toutes les 100 frames (~3 secondes)
-                                debug!("AGC: peak={:.3}, current_gain={:.3}, target_gain={:.3}", 
-                                      *peak, *current_gain, target_gain);
-                            }
+                        Err(e) => {
+                            warn!("Failed to open monitor output: {}", e);
+                            self.monitor_enabled.store(false, Ordering::Relaxed);
+                            None
                         }
-                        
-                        // Appliquer le gain AGC
-                        samples = samples.iter()
-                            .map(|&s| ((s as f32) * *current_gain).clamp(-32768.0, 32767.0) as i16)
-                            .collect();
                     }
+                } else {
+                    info!("Monitoring stopped");
+                    None
+                };
+            }
+
+            let paused = self.is_paused();
+            if paused != stream_paused {
+                let result = if paused {
+                    stream.pause().map_err(|e| e.to_string())
+                } else {
+                    stream.play().map_err(|e| e.to_string())
+                };
+                if let Err(e) = result {
+                    debug!("Stream pause/play not supported: {}", e);
                 }
+                // Pas de faux débranchement juste après la reprise
+                self.stream_watch.lock().unwrap().last_block = std::time::Instant::now();
+                stream_paused = paused;
+            }
 
-                if ptt_mode {
-                    // En mode push-to-talk: on stocke directement tout le flux
-                    let mut buffer = current_buffer.lock().unwrap();
-                    buffer.extend_from_slice(&samples);
-                    return;
+            // Périphérique perdu: les samples déjà reçus restent dans les tampons
+            // (utterance en cours, prise push-to-talk), la capture reprend dessus
+            let lost = self.stream_watch.lock().unwrap().lost(std::time::Instant::now(), paused);
+            if let Some(reason) = lost {
+                warn!("Audio device '{}' lost: {}", device_label, reason);
+                self.notify_device(DeviceEvent::Lost { device: device_label.clone(), reason });
+                drop(stream);
+                match self.reconnect() {
+                    Some((new_stream, new_label)) => {
+                        info!("Recording resumed on '{}'", new_label);
+                        self.notify_device(DeviceEvent::Reconnected { device: new_label.clone() });
+                        (stream, device_label) = (new_stream, new_label);
+                        stream_paused = false;
+                    }
+                    None => {
+                        info!("Stop signal received while reconnecting, ending recording");
+                        self.finish();
+                        join_system_capture(system_capture);
+                        return Ok(());
+                    }
                 }
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
 
-                let mut vad_buf = vad_buffer.lock().unwrap();
-                vad_buf.extend_from_slice(&samples);
+        info!("Stop signal received, ending recording");
+        drop(monitor);
+        drop(stream);
 
-                // Traiter les frames du VAD
-                while vad_buf.len() >= vad_frame_size {
-                    let frame: Vec<i16> = vad_buf.drain(..vad_frame_size).collect();
-                    
-                    // Détection de voix
-                    let is_voice = vad.lock().unwrap().is_voice_segment(&frame).unwrap_or(false);
+        self.finish();
+        join_system_capture(system_capture);
+        Ok(())
+    }
 
-                    let mut buffer = current_buffer.lock().unwrap();
-                    let mut silence = silence_frames.lock().unwrap();
-                    let mut speaking = is_speaking.lock().unwrap();
+    /// Tente d'ouvrir le périphérique par défaut (le même loopback pour la sortie système)
+    /// jusqu'à y arriver ou jusqu'au signal d'arrêt
+    fn reconnect(&self) -> Option<(cpal::Stream, String)> {
+        let device_name = match self.config.source {
+            AudioSource::Mic => None,
+            AudioSource::System => self.config.device_name.as_deref(),
+        };
+        while !self.stop_flag.load(Ordering::Relaxed) {
+            match self.open_stream(device_name) {
+                Ok(opened) => return Some(opened),
+                Err(e) => debug!("Reconnection failed, retrying: {}", e),
+            }
+            std::thread::sleep(std::time::Duration::from_millis(RECONNECT_RETRY_MS));
+        }
+        None
+    }
 
-                    if is_voice {
-                        // Voix détectée
-                        if !*speaking {
-                            debug!("Voice activity started");
-                        }
-                        *silence = 0;
-                        *speaking = true;
-                        buffer.extend_from_slice(&frame);
-                    } else if *speaking {
-                        // Silence pendant qu'on parle
-                        *silence += 30; // 30ms par frame
-                        buffer.extend_from_slice(&frame);
-
-                        // Vérifier si le silence est assez long pour terminer l'utterance
-                        if *silence >= session_config.silence_duration_ms {
-                            let sample_rate = *sample_rate_arc.lock().unwrap();
-                            let duration_ms = (buffer.len() as u32 * 1000) / sample_rate;
-                            
-                            // Sauvegarder l'utterance si elle est assez longue
-                            if duration_ms >= session_config.min_utterance_duration_ms {
-                                let mut counter = utterance_counter.lock().unwrap();
-                                *counter += 1;
-                                let utterance_id = *counter;
-
-                                let file_path = session_config.output_dir.join(
-                                    format!("utterance_{:04}.wav", utterance_id)
-                                );
-
-                                // Sauvegarder en WAV
-                                if let Err(e) = save_wav(&file_path, &buffer, sample_rate) {
-                                    warn!("Failed to save utterance: {}", e);
-                                } else {
-                                    info!("Saved utterance {} to {:?} ({}ms)", 
-                                             utterance_id, file_path, duration_ms);
-                                    
-                                    let utterance = Utterance {
-                                        id: utterance_id,
-                                        file_path,
-                                        duration_ms,
-                                        sample_count: buffer.len(),
-                                    };
-                                    
-                                    utterances.lock().unwrap().push(utterance);
-                                }
-                            }
-
-                            // Réinitialiser pour la prochaine utterance
-                            buffer.clear();
-                            *silence = 0;
-                            *speaking = false;
-                        }
-                    }
-                }
+    fn notify_device(&self, event: DeviceEvent) {
+        if let Some(listener) = self.device_listener.lock().unwrap().clone() {
+            listener(event);
+        }
+    }
+
+    /// Ouvre le périphérique (par nom, sinon celui par défaut), adapte le downmix et le
+    /// rééchantillonnage à son format et démarre le flux. Renvoie le flux et le nom du périphérique.
+    fn open_stream(&self, device_name: Option<&str>) -> Result<(cpal::Stream, String)> {
+        let (device, config) = open_input_device(device_name)?;
+        let host = cpal::default_host();
+        let device_label = device.name().unwrap_or_else(|_| "unknown".to_string());
+        *self.device_label.lock().unwrap() = device_label.clone();
+
+        let actual_sample_rate = config.sample_rate().0;
+        info!("Audio host: {}", host.id().name());
+        info!("Audio input device: {}", device_label);
+        info!("Sample rate: {} Hz", actual_sample_rate);
+        info!("Channels: {}", config.channels());
+        
+        // Interfaces USB souvent en stéréo: les frames entrelacées sont ramenées en mono
+        *self.channels.lock().unwrap() = config.channels();
+        if config.channels() > 1 {
+            info!("Downmixing {} channels to mono", config.channels());
+        }
+
+        // Le VAD et les WAV sont à 16kHz: rééchantillonner si le device tourne à 44.1/48kHz
+        *self.sample_rate.lock().unwrap() = TARGET_SAMPLE_RATE;
+        *self.device_sample_rate.lock().unwrap() = actual_sample_rate;
+        *self.resampler.lock().unwrap() = if actual_sample_rate != TARGET_SAMPLE_RATE {
+            info!("Resampling {} Hz to {} Hz", actual_sample_rate, TARGET_SAMPLE_RATE);
+            Some(StreamResampler::new(actual_sample_rate))
+        } else {
+            None
+        };
+
+        let mut stream_config: cpal::StreamConfig = config.clone().into();
+        if self.config.power_profile == PowerProfile::LowPower {
+            // Buffers plus longs: le callback se réveille ~10 fois par seconde au lieu de ~100
+            if let cpal::SupportedBufferSize::Range { min, max } = config.buffer_size() {
+                let frames = (actual_sample_rate * LOW_POWER_BUFFER_MS / 1000).clamp(*min, *max);
+                stream_config.buffer_size = cpal::BufferSize::Fixed(frames);
+                info!("Low power: {} frames per buffer", frames);
+            }
+        }
+
+        *self.stream_watch.lock().unwrap() = StreamWatch::new();
+        let session = self.clone();
+        let watch = Arc::clone(&self.stream_watch);
+        let stream = build_input_stream(
+            &device,
+            &config,
+            &stream_config,
+            move |data| {
+                session.stream_watch.lock().unwrap().last_block = std::time::Instant::now();
+                session.process_block(data);
             },
             move |err| {
-                eprintln!("Stream error: {}", err);
+                if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                    watch.lock().unwrap().error = Some(err.to_string());
+                }
             },
-            None,
         )?;
 
         stream.play()?;
+        Ok((stream, device_label))
+    }
 
-        info!("Recording started. Waiting for stop signal...");
-        info!("Utterances will be saved to: {:?}", self.config.output_dir);
+    /// Joue un fichier WAV comme une entrée micro, par blocs de 10ms, jusqu'à la fin du
+    /// fichier ou au signal d'arrêt. Un silence final laisse le VAD clore la dernière utterance.
+    fn run_virtual_input(&self, input: &VirtualInput) -> Result<()> {
+        let samples = crate::speech_to_text::read_wav_16k(&input.path)
+            .with_context(|| format!("Failed to read virtual input {:?}", input.path))?;
+        *self.sample_rate.lock().unwrap() = TARGET_SAMPLE_RATE;
+        *self.device_sample_rate.lock().unwrap() =
+            hound::WavReader::open(&input.path).map(|r| r.spec().sample_rate).unwrap_or(TARGET_SAMPLE_RATE);
+        *self.channels.lock().unwrap() = 1;
+        *self.device_label.lock().unwrap() = format!("virtual:{}", input.path.display());
+        info!(
+            "Virtual microphone: playing {:?} ({} ms, {:?})",
+            input.path,
+            samples.len() as u64 * 1000 / TARGET_SAMPLE_RATE as u64,
+            input.pacing
+        );
 
-        // Garder le stream actif jusqu'au signal d'arrêt
-        while !self.stop_flag.load(Ordering::Relaxed) {
-            std::thread::sleep(std::time::Duration::from_millis(100));
+        let block_size = (TARGET_SAMPLE_RATE / 100) as usize;
+        let block_duration = match input.pacing {
+            Pacing::RealTime => Some(std::time::Duration::from_millis(10)),
+            Pacing::Accelerated(factor) if factor > 0.0 => Some(std::time::Duration::from_secs_f32(0.01 / factor)),
+            _ => None,
+        };
+        let trailing_silence = if self.config.push_to_talk {
+            Vec::new()
+        } else {
+            let block_ms = 30 * self.vad_stride() as u32;
+            vec![0.0; (TARGET_SAMPLE_RATE * (self.config.silence_duration_ms + block_ms) / 1000) as usize]
+        };
+        let started = std::time::Instant::now();
+        let mut paused_for = std::time::Duration::ZERO;
+
+        for (i, block) in samples.chunks(block_size).chain(trailing_silence.chunks(block_size)).enumerate() {
+            // En pause, la lecture du fichier est suspendue (rien n'est perdu)
+            if self.is_paused() {
+                let pause_started = std::time::Instant::now();
+                while self.is_paused() && !self.stop_flag.load(Ordering::Relaxed) {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                paused_for += pause_started.elapsed();
+            }
+            if self.stop_flag.load(Ordering::Relaxed) {
+                info!("Stop signal received, ending virtual input");
+                break;
+            }
+            self.process_block(block);
+            // Cadence calée sur l'horloge de départ pour ne pas accumuler de dérive
+            if let Some(block_duration) = block_duration {
+                let due = block_duration * (i as u32 + 1) + paused_for;
+                if let Some(wait) = due.checked_sub(started.elapsed()) {
+                    std::thread::sleep(wait);
+                }
+            }
         }
 
-        info!("Stop signal received, ending recording");
-        drop(stream);
+        self.finish();
+        Ok(())
+    }
+
+    /// Nombre de frames VAD couvertes par une décision
+    fn vad_stride(&self) -> usize {
+        match self.config.power_profile {
+            PowerProfile::Balanced => 1,
+            PowerProfile::LowPower => LOW_POWER_VAD_STRIDE,
+        }
+    }
+
+    /// Traite un bloc de samples f32 entrelacés: downmix mono, rééchantillonnage, gain, AGC,
+    /// puis accumulation (push-to-talk) ou découpage en utterances par le VAD
+    fn process_block(&self, data: &[f32]) {
+        if self.is_paused() {
+            return;
+        }
+        let channels = *self.channels.lock().unwrap();
+        let mono = (channels > 1).then(|| downmix_to_mono(data, channels));
+        let data = mono.as_deref().unwrap_or(data);
+        let resampled = self.resampler.lock().unwrap().as_mut().map(|r| r.process(data));
+        let data = resampled.as_deref().unwrap_or(data);
+        let cancelled = self.cancel_echo(data);
+        let data = cancelled.as_deref().unwrap_or(data);
+        self.measure_level(data);
+        let raw = self.config.archive_raw.then(|| to_i16_samples(data, 1.0));
+
+        // Appliquer le gain et normalisation AGC
+        let mut samples = to_i16_samples(data, self.config.gain);
+        
+        // AGC: ajuster le gain automatiquement
+        if self.config.enable_agc {
+            let max_sample = samples.iter()
+                .map(|&s| s.abs())
+                .max()
+                .unwrap_or(0) as f32;
+            
+            let current_level = max_sample / 32768.0;
+            
+            // Mettre à jour le pic avec lissage
+            let mut peak = self.agc_peak_level.lock().unwrap();
+            *peak = (*peak * 0.95).max(current_level);
+            
+            // Calculer le gain nécessaire
+            if *peak > 0.01 { // Éviter division par zéro
+                let target_gain = self.config.agc_target_level / *peak;
+                let mut current_gain = self.agc_current_gain.lock().unwrap();
+                
+                // Lissage du gain (atténuation rapide, amplification lente)
+                if target_gain < *current_gain {
+                    *current_gain = (*current_gain * 0.8 + target_gain * 0.2).clamp(0.1, 10.0);
+                } else {
+                    *current_gain = (*current_gain * 0.99 + target_gain * 0.01).clamp(0.1, 10.0);
+                }
+                
+                // Log occasionnel des niveaux audio
+                static mut LOG_COUNTER: u32 = 0;
+                unsafe {
+                    LOG_COUNTER += 1;
+                    if LOG_COUNTER % 100 == 0 { // Log toutes les 100 frames (~3 secondes)
+                        debug!("AGC: peak={:.3}, current_gain={:.3}, target_gain={:.3}", 
+                              *peak, *current_gain, target_gain);
+                    }
+                }
+                
+                // Appliquer le gain AGC
+                samples = samples.iter()
+                    .map(|&s| ((s as f32) * *current_gain).clamp(-32768.0, 32767.0) as i16)
+                    .collect();
+            }
+        }
+
+        if self.monitor_enabled.load(Ordering::Relaxed) {
+            audio_monitor::push_samples(&self.monitor_buffer, &samples);
+        }
+
+        if self.config.push_to_talk {
+            // Pas de découpage en push-to-talk, mais le VAD surveille quand même le silence
+            if self.config.silence_alert_ms > 0 {
+                let mut vad_buf = self.vad_buffer.lock().unwrap();
+                vad_buf.extend_from_slice(&samples);
+                let stride = self.vad_stride();
+                while vad_buf.len() >= VAD_FRAME_SIZE * stride {
+                    let frame: Vec<i16> = vad_buf.drain(..VAD_FRAME_SIZE * stride).collect();
+                    let is_voice = self.vad.lock().unwrap().is_voice(&frame[..VAD_FRAME_SIZE]);
+                    self.track_silence(is_voice, 30 * stride as u32);
+                }
+            }
+
+            // En mode push-to-talk: tout le flux est écrit sur disque au fil de l'eau (une
+            // prise d'une heure ne reste pas en mémoire); en mémoire seulement si le
+            // fichier n'a pas pu être créé
+            let mut take = self.ptt_take.lock().unwrap();
+            if take.is_none() && self.current_buffer.lock().unwrap().is_empty() {
+                *take = self.open_ptt_take();
+            }
+            match take.as_mut() {
+                Some(take) => {
+                    if let Err(e) = take.writer.write(&samples) {
+                        warn!("Failed to write push-to-talk take: {}", e);
+                    }
+                    if let (Some(writer), Some(raw)) = (take.raw_writer.as_mut(), &raw) {
+                        if let Err(e) = writer.write(raw) {
+                            warn!("Failed to write raw push-to-talk track: {}", e);
+                        }
+                    }
+                }
+                None => {
+                    self.current_buffer.lock().unwrap().extend_from_slice(&samples);
+                    if let Some(raw) = &raw {
+                        self.raw_buffer.lock().unwrap().extend_from_slice(raw);
+                    }
+                }
+            }
+            return;
+        }
+
+        let mut vad_buf = self.vad_buffer.lock().unwrap();
+        let mut raw_vad_buf = self.raw_vad_buffer.lock().unwrap();
+        vad_buf.extend_from_slice(&samples);
+        if let Some(raw) = &raw {
+            raw_vad_buf.extend_from_slice(raw);
+        }
+
+        // Traiter les frames du VAD (en basse consommation, la première frame de chaque
+        // bloc de 90ms décide pour tout le bloc)
+        let stride = self.vad_stride();
+        while vad_buf.len() >= VAD_FRAME_SIZE * stride {
+            let frame: Vec<i16> = vad_buf.drain(..VAD_FRAME_SIZE * stride).collect();
+            // Vide sans archive brute
+            let raw_len = raw_vad_buf.len().min(frame.len());
+            let raw_frame: Vec<i16> = raw_vad_buf.drain(..raw_len).collect();
+            
+            // Détection de voix
+            let is_voice = self.vad.lock().unwrap().is_voice(&frame[..VAD_FRAME_SIZE]);
+            self.track_silence(is_voice, 30 * stride as u32);
 
-        // En mode push-to-talk: à l'arrêt, sauvegarder l'unique segment
-        if session_config.push_to_talk {
             let mut buffer = self.current_buffer.lock().unwrap();
-            if !buffer.is_empty() {
-                let mut counter = self.utterance_counter.lock().unwrap();
-                *counter += 1;
-                let utterance_id = *counter;
+            let mut raw_buffer = self.raw_buffer.lock().unwrap();
+            let mut silence = self.silence_frames.lock().unwrap();
+            let mut speaking = self.is_speaking.lock().unwrap();
+
+            if is_voice {
+                // Voix détectée
+                if !*speaking {
+                    debug!("Voice activity started");
+                }
+                *silence = 0;
+                *speaking = true;
+                buffer.extend_from_slice(&frame);
+                raw_buffer.extend_from_slice(&raw_frame);
+            } else if *speaking {
+                // Silence pendant qu'on parle
+                *silence += 30 * stride as u32; // 30ms par frame
+                buffer.extend_from_slice(&frame);
+                raw_buffer.extend_from_slice(&raw_frame);
+
+                // Vérifier si le silence est assez long pour terminer l'utterance
+                if *silence >= self.config.silence_duration_ms {
+                    let sample_rate = *self.sample_rate.lock().unwrap();
+                    let duration_ms = duration_ms(buffer.len() as u64, sample_rate);
+                    
+                    // Sauvegarder l'utterance si elle est assez longue
+                    if duration_ms >= self.config.min_utterance_duration_ms {
+                        let raw = self.config.archive_raw.then(|| std::mem::take(&mut *raw_buffer));
+                        self.store_utterance(std::mem::take(&mut *buffer), raw, sample_rate);
+                    }
+
+                    // Réinitialiser pour la prochaine utterance
+                    buffer.clear();
+                    raw_buffer.clear();
+                    *silence = 0;
+                    *speaking = false;
+                }
+            }
+
+            // Parole continue: coupée au premier silence après max_utterance_ms, pour que
+            // la transcription live avance pendant un long monologue
+            if *speaking && self.config.max_utterance_ms > 0 {
                 let sample_rate = *self.sample_rate.lock().unwrap();
-                let duration_ms = (buffer.len() as u32 * 1000) / sample_rate;
-                let file_path = self.config.output_dir.join(
-                    format!("utterance_{:04}.wav", utterance_id)
-                );
-                if let Err(e) = save_wav(&file_path, &buffer, sample_rate) {
-                    warn!("Failed to save push-to-talk utterance: {}", e);
-                } else {
-                    info!("Saved PTT utterance {} to {:?} ({}ms, {}Hz)", utterance_id, file_path, duration_ms, sample_rate);
-                    let utterance = Utterance { id: utterance_id, file_path, duration_ms, sample_count: buffer.len() };
-                    self.utterances.lock().unwrap().push(utterance);
+                let duration_ms = duration_ms(buffer.len() as u64, sample_rate);
+                if duration_ms >= self.config.max_utterance_ms && (!is_voice || duration_ms >= self.config.max_utterance_ms + MAX_UTTERANCE_GRACE_MS) {
+                    debug!("Utterance cut after {}ms of continuous speech", duration_ms);
+                    let raw = self.config.archive_raw.then(|| std::mem::take(&mut *raw_buffer));
+                    self.store_utterance(std::mem::take(&mut *buffer), raw, sample_rate);
+                    raw_buffer.clear();
+                    *silence = 0;
+                    *speaking = is_voice;
                 }
-                buffer.clear();
             }
         }
+    }
+
+    /// Double capture: la sortie système est gardée comme référence; le micro en retire
+    /// l'écho (None quand il n'y a rien à retirer)
+    fn cancel_echo(&self, data: &[f32]) -> Option<Vec<f32>> {
+        if self.config.source == AudioSource::System {
+            let mut far_end = self.far_end.lock().unwrap();
+            far_end.extend(data);
+            let overflow = far_end.len().saturating_sub(FAR_END_MAX_SAMPLES);
+            far_end.drain(..overflow);
+            return None;
+        }
+        let mut canceller = self.echo_canceller.lock().unwrap();
+        let canceller = canceller.as_mut()?;
+        let far: Vec<f32> = {
+            let mut far_end = self.far_end.lock().unwrap();
+            let len = far_end.len().min(data.len());
+            far_end.drain(..len).collect()
+        };
+        Some(canceller.process(data, &far))
+    }
 
-        Ok(())
+    /// Reçoit un `AudioLevel` toutes les LEVEL_INTERVAL_MS pendant la capture
+    pub fn set_level_listener<F>(&self, listener: F)
+    where
+        F: Fn(AudioLevel) + Send + Sync + 'static,
+    {
+        *self.level_listener.lock().unwrap() = Some(Arc::new(listener));
+    }
+
+    /// Active ou coupe l'écoute de contrôle: ce qui est capté, après gain et AGC, est rejoué
+    /// sur la sortie par défaut (pris en compte pendant l'enregistrement, au plus 100ms après)
+    pub fn set_monitor(&self, enabled: bool) {
+        self.monitor_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Reçoit un `SilenceEvent` quand aucune voix n'est détectée pendant `silence_alert_ms`,
+    /// puis quand la voix revient
+    pub fn set_silence_listener<F>(&self, listener: F)
+    where
+        F: Fn(SilenceEvent) + Send + Sync + 'static,
+    {
+        *self.silence_listener.lock().unwrap() = Some(Arc::new(listener));
+    }
+
+    fn track_silence(&self, is_voice: bool, frame_ms: u32) {
+        if self.config.silence_alert_ms == 0 {
+            return;
+        }
+        let event = self.silence_watch.lock().unwrap().update(is_voice, frame_ms, self.config.silence_alert_ms);
+        if let Some(event) = event {
+            info!("Silence watch: {:?}", event);
+            if let Some(listener) = self.silence_listener.lock().unwrap().clone() {
+                listener(event);
+            }
+        }
+    }
+
+    /// Reçoit un `DeviceEvent` quand le périphérique est perdu puis quand la capture reprend
+    pub fn set_device_listener<F>(&self, listener: F)
+    where
+        F: Fn(DeviceEvent) + Send + Sync + 'static,
+    {
+        *self.device_listener.lock().unwrap() = Some(Arc::new(listener));
+    }
+
+    /// Accumule les samples mono 16kHz et publie un niveau par période complète
+    fn measure_level(&self, data: &[f32]) {
+        let Some(listener) = self.level_listener.lock().unwrap().clone() else {
+            return;
+        };
+        let period = (TARGET_SAMPLE_RATE * LEVEL_INTERVAL_MS / 1000) as usize;
+        let mut levels = Vec::new();
+        {
+            let mut meter = self.level_meter.lock().unwrap();
+            for &sample in data {
+                meter.sum_squares += (sample as f64) * (sample as f64);
+                meter.peak = meter.peak.max(sample.abs());
+                meter.count += 1;
+                if meter.count >= period {
+                    let rms = (meter.sum_squares / meter.count as f64).sqrt() as f32;
+                    levels.push(AudioLevel {
+                        rms_dbfs: to_dbfs(rms),
+                        peak_dbfs: to_dbfs(meter.peak),
+                        clipping: meter.peak >= CLIP_LEVEL,
+                    });
+                    *meter = LevelMeter::default();
+                }
+            }
+        }
+        // Hors du verrou: le destinataire peut être lent (émission d'event)
+        for level in levels {
+            listener(level);
+        }
+    }
+
+    fn next_utterance_id(&self) -> usize {
+        let mut counter = self.utterance_counter.lock().unwrap();
+        *counter += 1;
+        *counter
+    }
+
+    fn utterance_path(&self, utterance_id: usize, started_at: chrono::DateTime<chrono::Local>) -> PathBuf {
+        let session = self.config.output_dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let stem = self.config.naming.file_stem(utterance_id, &session, started_at);
+        self.config.output_dir.join(format!("{}.wav", stem))
+    }
+
+    /// Fiche JSON de l'utterance, si activée
+    fn sidecar(&self, utterance: &Utterance, sample_rate: u32, started_at: chrono::DateTime<chrono::Local>) -> Option<UtteranceSidecar> {
+        if !self.config.naming.sidecars {
+            return None;
+        }
+        let file_name = |path: &Path| path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        Some(UtteranceSidecar {
+            id: utterance.id,
+            file_name: file_name(&utterance.file_path),
+            raw_file_name: self.config.archive_raw.then(|| file_name(&raw_track_path(&utterance.file_path))),
+            session: file_name(&self.config.output_dir),
+            speaker: self.config.naming.speaker.clone(),
+            source: self.config.source,
+            started_at: started_at.to_rfc3339(),
+            duration_ms: utterance.duration_ms,
+            sample_rate,
+            device_sample_rate: *self.device_sample_rate.lock().unwrap(),
+            device: self.device_label.lock().unwrap().clone(),
+            gain: self.config.gain,
+            agc: self.config.enable_agc,
+            vad_mode: vad_mode_name(&self.config.vad_mode).to_string(),
+            push_to_talk: self.config.push_to_talk,
+            transcript: None,
+        })
+    }
+
+    fn write_sidecar(&self, utterance: &Utterance, sidecar: &UtteranceSidecar) {
+        let result = serde_json::to_vec_pretty(sidecar)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| crate::safe_write::write(sidecar_path(&utterance.file_path), json));
+        if let Err(e) = result {
+            warn!("Failed to write sidecar of utterance {}: {}", utterance.id, e);
+        }
+    }
+
+    /// Ouvre le WAV de la prise push-to-talk (aucun en `keep_in_memory`: la prise reste
+    /// dans `current_buffer`)
+    fn open_ptt_take(&self) -> Option<PttTake> {
+        if self.config.keep_in_memory {
+            return None;
+        }
+        let id = self.next_utterance_id();
+        let started_at = chrono::Local::now();
+        let file_path = self.utterance_path(id, started_at);
+        let buffer_size = match self.config.power_profile {
+            PowerProfile::Balanced => WAV_WRITE_BUFFER,
+            PowerProfile::LowPower => LOW_POWER_WAV_WRITE_BUFFER,
+        };
+        let sample_rate = *self.sample_rate.lock().unwrap();
+        let raw_writer = if self.config.archive_raw {
+            WavStreamWriter::create(&raw_track_path(&file_path), sample_rate, buffer_size)
+                .map_err(|e| warn!("Failed to open raw push-to-talk track: {}", e))
+                .ok()
+        } else {
+            None
+        };
+        match WavStreamWriter::create(&file_path, sample_rate, buffer_size) {
+            Ok(writer) => Some(PttTake { id, file_path, started_at, writer, raw_writer }),
+            Err(e) => {
+                warn!("Failed to open push-to-talk take, keeping it in memory: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Numérote une utterance terminée et l'écrit en WAV. En basse consommation, elle
+    /// reste en mémoire jusqu'à ce que le lot atteigne LOW_POWER_WRITE_BATCH_MS.
+    fn store_utterance(&self, samples: Vec<i16>, raw: Option<Vec<i16>>, sample_rate: u32) {
+        let utterance_id = self.next_utterance_id();
+        let duration_ms = duration_ms(samples.len() as u64, sample_rate);
+        let started_at = chrono::Local::now() - chrono::Duration::milliseconds(duration_ms as i64);
+        let utterance = Utterance {
+            id: utterance_id,
+            file_path: self.utterance_path(utterance_id, started_at),
+            duration_ms,
+            sample_count: samples.len(),
+            sample_rate,
+            samples: None,
+            source: self.config.source,
+        };
+        let sidecar = self.sidecar(&utterance, sample_rate, started_at);
+
+        let mut pending = self.pending_writes.lock().unwrap();
+        pending.push(PendingWrite { utterance, samples, raw, sample_rate, sidecar });
+        let pending_ms: u32 = pending.iter().map(|write| write.utterance.duration_ms).sum();
+        if self.config.power_profile == PowerProfile::Balanced || pending_ms >= LOW_POWER_WRITE_BATCH_MS {
+            self.write_pending(&mut pending);
+        }
+    }
+
+    /// Écrit les utterances en attente et les rend visibles dans `get_utterances`
+    fn write_pending(&self, pending: &mut Vec<PendingWrite>) {
+        if pending.len() > 1 {
+            info!("Writing batch of {} utterances", pending.len());
+        }
+        for PendingWrite { mut utterance, samples, raw, sample_rate, sidecar } in pending.drain(..) {
+            if self.config.keep_in_memory {
+                info!("Kept utterance {} in memory ({}ms, {}Hz)", utterance.id, utterance.duration_ms, sample_rate);
+                utterance.samples = Some(Arc::new(samples));
+                self.utterances.lock().unwrap().push(utterance);
+                continue;
+            }
+            if let Err(e) = save_wav(&utterance.file_path, &samples, sample_rate) {
+                warn!("Failed to save utterance {}: {}", utterance.id, e);
+                continue;
+            }
+            if let Some(raw) = raw {
+                if let Err(e) = save_wav(&raw_track_path(&utterance.file_path), &raw, sample_rate) {
+                    warn!("Failed to save raw track of utterance {}: {}", utterance.id, e);
+                }
+            }
+            if let Some(sidecar) = &sidecar {
+                self.write_sidecar(&utterance, sidecar);
+            }
+            info!("Saved utterance {} to {:?} ({}ms, {}Hz)", utterance.id, utterance.file_path, utterance.duration_ms, sample_rate);
+            self.utterances.lock().unwrap().push(utterance);
+        }
+    }
+
+    /// Fin de capture: en push-to-talk, ferme (ou sauvegarde) l'unique segment, puis écrit
+    /// le dernier lot en attente
+    fn finish(&self) {
+        if let Some(take) = self.ptt_take.lock().unwrap().take() {
+            let sample_rate = *self.sample_rate.lock().unwrap();
+            if let Some(Err(e)) = take.raw_writer.map(WavStreamWriter::finalize) {
+                warn!("Failed to finalize raw push-to-talk track: {}", e);
+            }
+            match take.writer.finalize() {
+                Ok(sample_count) => {
+                    let duration_ms = duration_ms(sample_count, sample_rate);
+                    info!("Saved PTT utterance {} to {:?} ({}ms, {}Hz)", take.id, take.file_path, duration_ms, sample_rate);
+                    let utterance = Utterance { id: take.id, file_path: take.file_path, duration_ms, sample_count: sample_count as usize, sample_rate, samples: None, source: self.config.source };
+                    if let Some(sidecar) = self.sidecar(&utterance, sample_rate, take.started_at) {
+                        self.write_sidecar(&utterance, &sidecar);
+                    }
+                    self.utterances.lock().unwrap().push(utterance);
+                }
+                Err(e) => warn!("Failed to finalize push-to-talk take: {}", e),
+            }
+        }
+        if self.config.push_to_talk {
+            let buffer = std::mem::take(&mut *self.current_buffer.lock().unwrap());
+            let raw = std::mem::take(&mut *self.raw_buffer.lock().unwrap());
+            if !buffer.is_empty() {
+                let sample_rate = *self.sample_rate.lock().unwrap();
+                self.store_utterance(buffer, self.config.archive_raw.then_some(raw), sample_rate);
+            }
+        }
+        self.write_pending(&mut self.pending_writes.lock().unwrap());
     }
 
     /// Arrête l'enregistrement en cours
@@ -386,48 +1806,151 @@ impl AudioSession {
         self.stop_flag.store(true, Ordering::Relaxed);
     }
 
+    /// Met l'enregistrement en pause sans terminer la session. Hors push-to-talk,
+    /// l'utterance en cours est close pour ne pas être recollée à la reprise; en
+    /// push-to-talk, la prise continue simplement après la pause.
+    pub fn pause(&self) {
+        info!("Pausing recording...");
+        self.pause_flag.store(true, Ordering::Relaxed);
+        if !self.config.push_to_talk {
+            self.close_open_utterance();
+        }
+    }
+
+    pub fn resume(&self) {
+        info!("Resuming recording...");
+        self.pause_flag.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause_flag.load(Ordering::Relaxed)
+    }
+
+    /// Sauvegarde l'utterance en cours (si assez longue) et réinitialise l'état du VAD
+    fn close_open_utterance(&self) {
+        let mut vad_buf = self.vad_buffer.lock().unwrap();
+        let mut raw_vad_buf = self.raw_vad_buffer.lock().unwrap();
+        let mut buffer = self.current_buffer.lock().unwrap();
+        let mut raw_buffer = self.raw_buffer.lock().unwrap();
+        if *self.is_speaking.lock().unwrap() {
+            let sample_rate = *self.sample_rate.lock().unwrap();
+            let duration_ms = duration_ms(buffer.len() as u64, sample_rate);
+            if duration_ms >= self.config.min_utterance_duration_ms {
+                let raw = self.config.archive_raw.then(|| std::mem::take(&mut *raw_buffer));
+                self.store_utterance(std::mem::take(&mut *buffer), raw, sample_rate);
+            }
+        }
+        buffer.clear();
+        raw_buffer.clear();
+        vad_buf.clear();
+        raw_vad_buf.clear();
+        *self.silence_frames.lock().unwrap() = 0;
+        *self.is_speaking.lock().unwrap() = false;
+    }
+
+    /// Moteur VAD en service (le secours par énergie peut prendre le relais en cours de route)
+    pub fn vad_engine(&self) -> VadEngine {
+        self.vad.lock().unwrap().engine()
+    }
+
     /// Récupère toutes les utterances enregistrées
     pub fn get_utterances(&self) -> Vec<Utterance> {
         self.utterances.lock().unwrap().clone()
     }
 }
 
-/// Sauvegarde des samples audio au format WAV
-fn save_wav(path: &Path, samples: &[i16], sample_rate: u32) -> Result<()> {
-    let mut file = File::create(path).context("Failed to create WAV file")?;
+/// Attend la fin de la capture du loopback (arrêtée par le même signal que le micro)
+fn join_system_capture(capture: Option<std::thread::JoinHandle<()>>) {
+    if let Some(Err(_)) = capture.map(std::thread::JoinHandle::join) {
+        warn!("System audio capture thread panicked");
+    }
+}
+
+/// En-tête réservé en début de fichier: RIFF, chunk JUNK de 28 octets (devient `ds64`
+/// en RF64), fmt et data
+const WAV_HEADER_LEN: u64 = 80;
+/// Au-delà, les tailles ne tiennent plus sur 32 bits: le fichier passe en RF64
+const RIFF_MAX_SIZE: u64 = u32::MAX as u64;
 
-    let num_samples = samples.len() as u32;
-    let num_channels: u16 = 1;
-    let bits_per_sample: u16 = 16;
-    let byte_rate = sample_rate * num_channels as u32 * bits_per_sample as u32 / 8;
-    let block_align = num_channels * bits_per_sample / 8;
-    let data_size = num_samples * num_channels as u32 * bits_per_sample as u32 / 8;
+/// En-tête WAV PCM 16 bits mono pour `data_size` octets de samples
+fn wav_header(sample_rate: u32, data_size: u64) -> Vec<u8> {
+    let riff_size = WAV_HEADER_LEN - 8 + data_size;
+    let rf64 = riff_size > RIFF_MAX_SIZE;
+    let mut header = Vec::with_capacity(WAV_HEADER_LEN as usize);
 
-    // Header RIFF
-    file.write_all(b"RIFF")?;
-    file.write_all(&(36 + data_size).to_le_bytes())?;
-    file.write_all(b"WAVE")?;
+    header.extend_from_slice(if rf64 { b"RF64" } else { b"RIFF" });
+    header.extend_from_slice(&(if rf64 { u32::MAX } else { riff_size as u32 }).to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+
+    // ds64 (RF64) ou JUNK de même taille, pour pouvoir basculer sans décaler les données
+    header.extend_from_slice(if rf64 { b"ds64" } else { b"JUNK" });
+    header.extend_from_slice(&28u32.to_le_bytes());
+    if rf64 {
+        header.extend_from_slice(&riff_size.to_le_bytes());
+        header.extend_from_slice(&data_size.to_le_bytes());
+        header.extend_from_slice(&(data_size / 2).to_le_bytes()); // Nombre de frames
+        header.extend_from_slice(&0u32.to_le_bytes()); // Pas de table de chunks
+    } else {
+        header.extend_from_slice(&[0; 28]);
+    }
 
     // Chunk fmt
-    file.write_all(b"fmt ")?;
-    file.write_all(&16u32.to_le_bytes())?; // Taille du chunk fmt
-    file.write_all(&1u16.to_le_bytes())?; // Format PCM
-    file.write_all(&num_channels.to_le_bytes())?;
-    file.write_all(&sample_rate.to_le_bytes())?;
-    file.write_all(&byte_rate.to_le_bytes())?;
-    file.write_all(&block_align.to_le_bytes())?;
-    file.write_all(&bits_per_sample.to_le_bytes())?;
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&16u32.to_le_bytes()); // Taille du chunk fmt
+    header.extend_from_slice(&1u16.to_le_bytes()); // Format PCM
+    header.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // Byte rate
+    header.extend_from_slice(&2u16.to_le_bytes()); // Block align
+    header.extend_from_slice(&16u16.to_le_bytes()); // Bits par sample
 
     // Chunk data
-    file.write_all(b"data")?;
-    file.write_all(&data_size.to_le_bytes())?;
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&(if rf64 { u32::MAX } else { data_size as u32 }).to_le_bytes());
+    header
+}
+
+/// Écriture d'un WAV au fil de l'eau: les samples partent sur disque par blocs et les
+/// tailles de l'en-tête sont corrigées à la fermeture (RF64 au-delà de 4 Go). Le fichier
+/// n'apparaît sous son nom qu'une fois complet (voir `safe_write`).
+pub(crate) struct WavStreamWriter {
+    file: BufWriter<AtomicFile>,
+    sample_rate: u32,
+    sample_count: u64,
+}
 
-    // Données audio
-    for &sample in samples {
-        file.write_all(&sample.to_le_bytes())?;
+impl WavStreamWriter {
+    pub(crate) fn create(path: &Path, sample_rate: u32, buffer_size: usize) -> Result<Self> {
+        let file = AtomicFile::create(path).context("Failed to create WAV file")?;
+        let mut file = BufWriter::with_capacity(buffer_size, file);
+        file.write_all(&wav_header(sample_rate, 0))?;
+        Ok(Self { file, sample_rate, sample_count: 0 })
     }
 
-    file.flush()?;
+    pub(crate) fn write(&mut self, samples: &[i16]) -> Result<()> {
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        self.file.write_all(&data).context("Failed to write WAV samples")?;
+        self.sample_count += samples.len() as u64;
+        Ok(())
+    }
+
+    /// Écrit les tailles définitives dans l'en-tête et renvoie le nombre de samples
+    pub(crate) fn finalize(mut self) -> Result<u64> {
+        self.file.flush()?;
+        let header = wav_header(self.sample_rate, self.sample_count * 2);
+        let mut file = self.file.into_inner().map_err(|e| e.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&header)?;
+        file.commit()?;
+        Ok(self.sample_count)
+    }
+}
+
+/// Sauvegarde des samples audio au format WAV
+pub(crate) fn save_wav(path: &Path, samples: &[i16], sample_rate: u32) -> Result<()> {
+    let mut writer = WavStreamWriter::create(path, sample_rate, WAV_WRITE_BUFFER)?;
+    writer.write(samples)?;
+    writer.finalize()?;
     Ok(())
 }
 
@@ -442,6 +1965,539 @@ mod tests {
         assert_eq!(config.min_utterance_duration_ms, 300);
     }
 
+    fn range(channels: u16, min: u32, max: u32, format: SampleFormat) -> SupportedStreamConfigRange {
+        SupportedStreamConfigRange::new(
+            channels,
+            SampleRate(min),
+            SampleRate(max),
+            cpal::SupportedBufferSize::Unknown,
+            format,
+        )
+    }
+
+    fn default_config(channels: u16, rate: u32, format: SampleFormat) -> SupportedStreamConfig {
+        SupportedStreamConfig::new(channels, SampleRate(rate), cpal::SupportedBufferSize::Unknown, format)
+    }
+
+    #[test]
+    fn test_negotiate_wasapi_shared_mode() {
+        // WASAPI partagé: uniquement le format de mixage, 48kHz stéréo f32
+        let config = negotiate_input_config(
+            &default_config(2, 48000, SampleFormat::F32),
+            &[range(2, 48000, 48000, SampleFormat::F32)],
+        )
+        .unwrap();
+        assert_eq!(config.sample_rate().0, 48000);
+        assert_eq!(config.channels(), 2);
+    }
+
+    #[test]
+    fn test_negotiate_pulse_prefers_f32_mono_16k() {
+        // Pulse/ALSA: défaut en i16, plages larges en i16 et f32
+        let config = negotiate_input_config(
+            &default_config(2, 44100, SampleFormat::I16),
+            &[
+                range(2, 8000, 192000, SampleFormat::I16),
+                range(1, 8000, 192000, SampleFormat::I16),
+                range(2, 8000, 192000, SampleFormat::F32),
+                range(1, 8000, 192000, SampleFormat::F32),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.sample_format(), SampleFormat::F32);
+        assert_eq!(config.sample_rate().0, TARGET_SAMPLE_RATE);
+        assert_eq!(config.channels(), 1);
+    }
+
+    #[test]
+    fn test_negotiate_closest_rate_and_no_f32() {
+        // Périphérique 44.1k/48k uniquement: garder le rate par défaut
+        let config = negotiate_input_config(
+            &default_config(1, 48000, SampleFormat::F32),
+            &[range(1, 44100, 48000, SampleFormat::F32)],
+        )
+        .unwrap();
+        assert_eq!(config.sample_rate().0, 48000);
+
+        // Cartes USB en i16/u16 uniquement: format accepté et converti dans le callback
+        let only_i16 = negotiate_input_config(
+            &default_config(1, 48000, SampleFormat::I16),
+            &[range(1, 48000, 48000, SampleFormat::I16)],
+        )
+        .unwrap();
+        assert_eq!(only_i16.sample_format(), SampleFormat::I16);
+        let u16_or_i16 = negotiate_input_config(
+            &default_config(1, 48000, SampleFormat::U16),
+            &[range(1, 48000, 48000, SampleFormat::U16), range(1, 48000, 48000, SampleFormat::I16)],
+        )
+        .unwrap();
+        assert_eq!(u16_or_i16.sample_format(), SampleFormat::I16);
+    }
+
+    #[test]
+    fn test_integer_formats_convert_to_f32() {
+        use cpal::Sample;
+        assert_eq!(i16::MIN.to_sample::<f32>(), -1.0);
+        assert_eq!(0i16.to_sample::<f32>(), 0.0);
+        assert_eq!(32768u16.to_sample::<f32>(), 0.0);
+        assert_eq!(0u16.to_sample::<f32>(), -1.0);
+    }
+
+    #[test]
+    fn test_to_i16_samples_clamps() {
+        assert_eq!(to_i16_samples(&[0.0, 0.5, 1.0, -2.0], 1.0), vec![0, 16383, 32767, -32768]);
+        assert_eq!(to_i16_samples(&[0.25], 2.0), vec![16383]);
+    }
+
+    /// Négociation sur le vrai périphérique par défaut (CI: sink nul PulseAudio)
+    #[test]
+    #[ignore]
+    fn test_default_device_negotiation() {
+        let host = cpal::default_host();
+        let Some(device) = host.default_input_device() else {
+            eprintln!("No input device on host {}, skipping", host.id().name());
+            return;
+        };
+        let default = device.default_input_config().unwrap();
+        let supported: Vec<_> = device.supported_input_configs().unwrap().collect();
+        let config = negotiate_input_config(&default, &supported).expect("no f32 input config");
+        assert!(config.sample_rate().0 >= 8000);
+    }
+
+    fn virtual_session(name: &str, samples: &[i16], push_to_talk: bool, pacing: Pacing) -> AudioSession {
+        let dir = std::env::temp_dir().join(format!("virtual_mic_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.wav");
+        save_wav(&path, samples, TARGET_SAMPLE_RATE).unwrap();
+        AudioSession::new(AudioSessionConfig {
+            output_dir: dir,
+            gain: 1.0,
+            enable_agc: false,
+            push_to_talk,
+            virtual_input: Some(VirtualInput { path, pacing }),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn tone(duration_ms: u32) -> Vec<i16> {
+        (0..TARGET_SAMPLE_RATE * duration_ms / 1000)
+            .map(|i| ((i as f32 * 440.0 * 2.0 * std::f32::consts::PI / 16000.0).sin() * 16000.0) as i16)
+            .collect()
+    }
+
+    #[test]
+    fn test_virtual_input_push_to_talk() {
+        let session = virtual_session("ptt", &tone(500), true, Pacing::Unpaced);
+        session.start_recording().unwrap();
+        let utterances = session.get_utterances();
+        assert_eq!(utterances.len(), 1);
+        assert_eq!(utterances[0].sample_count, 8000);
+        assert_eq!(utterances[0].duration_ms, 500);
+        std::fs::remove_dir_all(session.config.output_dir.clone()).ok();
+    }
+
+    #[test]
+    fn test_templated_names_and_sidecars() {
+        let mut session = virtual_session("naming", &tone(500), true, Pacing::Unpaced);
+        session.config.naming = UtteranceNaming {
+            template: "{session}_{speaker}_{index}".to_string(),
+            speaker: Some("Jeanne D'Arc".to_string()),
+            sidecars: true,
+        };
+        session.start_recording().unwrap();
+
+        let utterance = &session.get_utterances()[0];
+        let dir = session.config.output_dir.clone();
+        let expected = format!("{}_Jeanne_D_Arc_0001.wav", dir.file_name().unwrap().to_string_lossy());
+        assert_eq!(utterance.file_path, dir.join(expected));
+
+        set_sidecar_transcript(&utterance.file_path, "Bonjour").unwrap();
+        let sidecar: UtteranceSidecar = serde_json::from_str(&std::fs::read_to_string(sidecar_path(&utterance.file_path)).unwrap()).unwrap();
+        assert_eq!(sidecar.duration_ms, 500);
+        assert!(sidecar.push_to_talk);
+        assert!(sidecar.device.starts_with("virtual:"));
+        assert_eq!(sidecar.transcript.as_deref(), Some("Bonjour"));
+        std::fs::remove_dir_all(dir).ok();
+
+        assert!(UtteranceNaming { template: "{session}".to_string(), ..Default::default() }.validate().is_err());
+        assert!(UtteranceNaming { template: "{index}_{date}".to_string(), ..Default::default() }.validate().is_err());
+        assert!(UtteranceNaming { template: "{timestamp}_{index}".to_string(), ..Default::default() }.validate().is_ok());
+    }
+
+    #[test]
+    fn test_raw_track_is_archived_before_gain() {
+        let dir = std::env::temp_dir().join(format!("virtual_mic_raw_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.wav");
+        save_wav(&path, &tone(300), TARGET_SAMPLE_RATE).unwrap();
+        let session = AudioSession::new(AudioSessionConfig {
+            output_dir: dir.clone(),
+            gain: 2.0,
+            enable_agc: false,
+            archive_raw: true,
+            virtual_input: Some(VirtualInput { path, pacing: Pacing::Unpaced }),
+            ..Default::default()
+        })
+        .unwrap();
+        session.start_recording().unwrap();
+
+        let utterances = session.get_utterances();
+        assert_eq!(utterances.len(), 1);
+        let read = |path: &Path| -> Vec<i16> { hound::WavReader::open(path).unwrap().samples().map(|s| s.unwrap()).collect() };
+        let processed = read(&utterances[0].file_path);
+        let raw = read(&raw_track_path(&utterances[0].file_path));
+        assert_eq!(raw.len(), processed.len());
+        // Le gain x2 n'est appliqué qu'à la piste traitée
+        assert!(raw.iter().zip(&processed).all(|(&r, &p)| (p as i32 - 2 * r as i32).abs() <= 2));
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_virtual_input_silence_gives_no_utterance() {
+        let session = virtual_session("silence", &vec![0; 16000], false, Pacing::Unpaced);
+        session.start_recording().unwrap();
+        assert!(session.get_utterances().is_empty());
+        std::fs::remove_dir_all(session.config.output_dir.clone()).ok();
+    }
+
+    #[test]
+    fn test_silence_alert_in_push_to_talk() {
+        let mut session = virtual_session("dead_air", &vec![0; 16000 * 2], true, Pacing::Unpaced);
+        session.config.silence_alert_ms = 1000;
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        session.set_silence_listener(move |event| sink.lock().unwrap().push(event));
+        session.start_recording().unwrap();
+        assert_eq!(*events.lock().unwrap(), vec![SilenceEvent::Alert { silent_ms: 1020 }]);
+
+        let mut watch = SilenceWatch { silent_ms: 1020, alerted: true };
+        assert_eq!(watch.update(true, 30, 1000), Some(SilenceEvent::Ended { silent_ms: 1020 }));
+        assert_eq!(watch.update(false, 30, 1000), None);
+        std::fs::remove_dir_all(session.config.output_dir.clone()).ok();
+    }
+
+    #[test]
+    fn test_virtual_input_pacing() {
+        let session = virtual_session("realtime", &tone(300), true, Pacing::RealTime);
+        let started = std::time::Instant::now();
+        session.start_recording().unwrap();
+        assert!(started.elapsed() >= std::time::Duration::from_millis(290));
+        std::fs::remove_dir_all(session.config.output_dir.clone()).ok();
+
+        let session = virtual_session("accelerated", &tone(300), true, Pacing::Accelerated(10.0));
+        let started = std::time::Instant::now();
+        session.start_recording().unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_millis(200));
+        assert_eq!(session.get_utterances().len(), 1);
+        std::fs::remove_dir_all(session.config.output_dir.clone()).ok();
+    }
+
+    #[test]
+    fn test_stream_resampler_is_continuous_across_blocks() {
+        let input: Vec<f32> = (0..48000).map(|i| (i as f32 * 440.0 * 2.0 * std::f32::consts::PI / 48000.0).sin()).collect();
+        let whole = StreamResampler::new(48000).process(&input);
+        assert_eq!(whole.len(), 16000);
+        // Même résultat par blocs de tailles irrégulières (callbacks cpal)
+        let mut resampler = StreamResampler::new(48000);
+        let chunked: Vec<f32> = input.chunks(441).flat_map(|block| resampler.process(block)).collect();
+        assert_eq!(chunked.len(), whole.len());
+        assert!(chunked.iter().zip(&whole).all(|(a, b)| (a - b).abs() < 1e-6));
+        // 44.1kHz -> 16kHz garde la durée
+        assert_eq!(StreamResampler::new(44100).process(&vec![0.0; 44100]).len(), 16000);
+    }
+
+    #[test]
+    fn test_device_rate_is_resampled_before_wav() {
+        let dir = std::env::temp_dir().join(format!("resampled_{}", std::process::id()));
+        let session = AudioSession::new(AudioSessionConfig { output_dir: dir.clone(), enable_agc: false, ..Default::default() }).unwrap();
+        *session.resampler.lock().unwrap() = Some(StreamResampler::new(48000));
+        let block: Vec<f32> = vec![0.25; 480];
+        for _ in 0..100 {
+            session.process_block(&block);
+        }
+        session.finish();
+        // 1s capturée à 48kHz donne 1s à 16kHz, pas 3s ralenties
+        let utterances = session.get_utterances();
+        assert_eq!(utterances[0].sample_count, 16000);
+        assert_eq!(utterances[0].duration_ms, 1000);
+        assert_eq!(utterances[0].sample_rate, 16000);
+        assert_eq!(hound::WavReader::open(&utterances[0].file_path).unwrap().spec().sample_rate, 16000);
+        // 10 min à 16kHz: le calcul ne déborde pas
+        assert_eq!(duration_ms(16000 * 600, 16000), 600_000);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_keep_in_memory_writes_nothing() {
+        let dir = std::env::temp_dir().join(format!("in_memory_{}", std::process::id()));
+        let session = AudioSession::new(AudioSessionConfig {
+            output_dir: dir.clone(),
+            enable_agc: false,
+            archive_raw: true,
+            keep_in_memory: true,
+            ..Default::default()
+        })
+        .unwrap();
+        session.process_block(&vec![0.25; 16000]);
+        session.finish();
+        // La prise push-to-talk reste en mémoire, sans WAV ni fiche
+        let utterances = session.get_utterances();
+        assert_eq!(utterances[0].samples.as_ref().map(|samples| samples.len()), Some(16000));
+        assert!(!utterances[0].file_path.exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_dual_capture_tags_sources_and_cancels_echo() {
+        let dir = std::env::temp_dir().join(format!("dual_capture_{}", std::process::id()));
+        let mic = AudioSession::new(AudioSessionConfig {
+            output_dir: dir.clone(),
+            gain: 1.0,
+            enable_agc: false,
+            system_device: Some("loopback".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let system = mic.system_session("loopback").unwrap();
+        *mic.echo_canceller.lock().unwrap() = Some(EchoCanceller::new(TARGET_SAMPLE_RATE, DEFAULT_TAIL_MS));
+
+        // 3s de visio, renvoyée au micro par les haut-parleurs avec 2,5ms de retard
+        let mut state = 12345u32;
+        let far: Vec<f32> = (0..48000)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                (state >> 16) as f32 / 65536.0 - 0.5
+            })
+            .collect();
+        let echo: Vec<f32> = (0..far.len()).map(|i| 0.25 * far.get(i.wrapping_sub(40)).unwrap_or(&0.0)).collect();
+        for (far_block, echo_block) in far.chunks(480).zip(echo.chunks(480)) {
+            system.process_block(far_block);
+            mic.process_block(echo_block);
+        }
+        system.finish();
+        mic.finish();
+
+        // Une prise par flux, numérotées à la suite dans le même dossier
+        let utterances = mic.get_utterances();
+        let sources: Vec<(usize, AudioSource)> = utterances.iter().map(|u| (u.id, u.source)).collect();
+        assert_eq!(sources, vec![(1, AudioSource::System), (2, AudioSource::Mic)]);
+        let sidecar: UtteranceSidecar =
+            serde_json::from_str(&std::fs::read_to_string(sidecar_path(&utterances[0].file_path)).unwrap()).unwrap();
+        assert_eq!(sidecar.source, AudioSource::System);
+
+        // La fin de la prise micro ne contient presque plus l'écho de la visio
+        let energy = |samples: &[i16]| samples.iter().map(|&s| (s as f64).powi(2)).sum::<f64>();
+        let recorded: Vec<i16> = hound::WavReader::open(&utterances[1].file_path).unwrap().samples().map(|s| s.unwrap()).collect();
+        let echoed = to_i16_samples(&echo, 1.0);
+        assert!(energy(&recorded[40000..]) < energy(&echoed[40000..]) / 100.0);
+        assert_eq!(AudioSource::from_label("Système:"), Some(AudioSource::System));
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_stereo_device_is_downmixed() {
+        let dir = std::env::temp_dir().join(format!("downmix_{}", std::process::id()));
+        let session = AudioSession::new(AudioSessionConfig { output_dir: dir.clone(), enable_agc: false, ..Default::default() }).unwrap();
+        *session.channels.lock().unwrap() = 2;
+        *session.resampler.lock().unwrap() = Some(StreamResampler::new(48000));
+        // Canal gauche à 0.5, droit à 0: moyenne à 0.25
+        let block: Vec<f32> = [0.5, 0.0].repeat(480);
+        for _ in 0..100 {
+            session.process_block(&block);
+        }
+        session.finish();
+        // 1s de stéréo à 48kHz donne 1s de mono, pas 2s à vitesse moitié
+        let utterances = session.get_utterances();
+        assert_eq!(utterances[0].duration_ms, 1000);
+        let samples: Vec<i16> = hound::WavReader::open(&utterances[0].file_path).unwrap().samples().map(|s| s.unwrap()).collect();
+        assert!(samples[100..].iter().all(|&s| (s - 16383).abs() <= 1));
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_low_power_batches_writes() {
+        let dir = std::env::temp_dir().join(format!("low_power_{}", std::process::id()));
+        let session = AudioSession::new(AudioSessionConfig {
+            output_dir: dir.clone(),
+            power_profile: PowerProfile::LowPower,
+            ..Default::default()
+        })
+        .unwrap();
+        session.store_utterance(tone(500), None, TARGET_SAMPLE_RATE);
+        session.store_utterance(tone(700), None, TARGET_SAMPLE_RATE);
+        // Rien n'est écrit avant la fin du lot
+        assert!(session.get_utterances().is_empty());
+        assert!(!dir.join("utterance_0001.wav").exists());
+
+        session.finish();
+        let utterances = session.get_utterances();
+        assert_eq!(utterances.iter().map(|u| u.duration_ms).collect::<Vec<_>>(), vec![500, 700]);
+        assert!(utterances.iter().all(|u| u.file_path.exists()));
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_level_events_every_period() {
+        let dir = std::env::temp_dir().join(format!("levels_{}", std::process::id()));
+        let session = AudioSession::new(AudioSessionConfig { output_dir: dir.clone(), ..Default::default() }).unwrap();
+        let levels = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&levels);
+        session.set_level_listener(move |level| sink.lock().unwrap().push(level));
+
+        // 250ms à 0.5 puis 100ms de silence, par blocs de 10ms
+        (0..25).for_each(|_| session.process_block(&[0.5; 160]));
+        (0..10).for_each(|_| session.process_block(&[0.0; 160]));
+        (0..10).for_each(|_| session.process_block(&[1.0; 160]));
+
+        let levels = levels.lock().unwrap();
+        assert_eq!(levels.len(), 4);
+        // Mesuré avant le gain de la session (x2)
+        assert!((levels[0].rms_dbfs + 6.02).abs() < 0.01);
+        assert!((levels[0].peak_dbfs + 6.02).abs() < 0.01);
+        assert!(!levels[0].clipping);
+        // Période à cheval: moitié signal, moitié silence
+        assert!((levels[2].rms_dbfs + 9.03).abs() < 0.01);
+        assert!(levels[2].peak_dbfs > -6.1);
+        assert!(levels[3].clipping);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_stream_watch_detects_lost_device() {
+        let start = std::time::Instant::now();
+        let mut watch = StreamWatch { last_block: start, error: None };
+        let later = start + std::time::Duration::from_millis(STREAM_STALL_TIMEOUT_MS + 500);
+        assert!(watch.lost(start + std::time::Duration::from_millis(500), false).is_none());
+        // Flux suspendu pendant la pause: pas une perte
+        assert!(watch.lost(later, true).is_none());
+        assert!(watch.lost(later, false).unwrap().starts_with("No audio received"));
+
+        watch.last_block = later;
+        watch.error = Some("The requested device is no longer available".to_string());
+        assert_eq!(watch.lost(later, true).as_deref(), Some("The requested device is no longer available"));
+    }
+
+    #[test]
+    fn test_long_speech_is_cut_for_live_transcription() {
+        let dir = std::env::temp_dir().join(format!("max_utterance_{}", std::process::id()));
+        let session = AudioSession::new(AudioSessionConfig {
+            output_dir: dir.clone(),
+            enable_agc: false,
+            push_to_talk: false,
+            max_utterance_ms: 1000,
+            ..Default::default()
+        })
+        .unwrap();
+        let voice = vec![0.25; VAD_FRAME_SIZE];
+        let silence = vec![0.0; VAD_FRAME_SIZE];
+
+        // 1,2 s de parole puis une courte pause (sous silence_duration_ms): coupée à la pause
+        (0..40).for_each(|_| session.process_block(&voice));
+        assert!(session.get_utterances().is_empty());
+        (0..5).for_each(|_| session.process_block(&silence));
+        assert_eq!(session.get_utterances().len(), 1);
+        assert_eq!(session.get_utterances()[0].duration_ms, 1230);
+
+        (0..20).for_each(|_| session.process_block(&voice));
+        (0..40).for_each(|_| session.process_block(&silence));
+        session.finish();
+        assert_eq!(session.get_utterances().len(), 2);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_pause_closes_utterance_and_ignores_audio() {
+        let dir = std::env::temp_dir().join(format!("pause_{}", std::process::id()));
+        let session = AudioSession::new(AudioSessionConfig {
+            output_dir: dir.clone(),
+            enable_agc: false,
+            push_to_talk: false,
+            ..Default::default()
+        })
+        .unwrap();
+        let voice = vec![0.25; VAD_FRAME_SIZE];
+        let silence = vec![0.0; VAD_FRAME_SIZE];
+
+        (0..20).for_each(|_| session.process_block(&voice));
+        session.pause();
+        assert!(session.is_paused());
+        // L'utterance en cours est close à la pause
+        assert_eq!(session.get_utterances().len(), 1);
+        (0..40).for_each(|_| session.process_block(&voice));
+
+        session.resume();
+        (0..20).for_each(|_| session.process_block(&voice));
+        (0..40).for_each(|_| session.process_block(&silence));
+        session.finish();
+
+        let utterances = session.get_utterances();
+        assert_eq!(utterances.len(), 2);
+        assert_eq!(utterances[0].duration_ms, 600);
+        // Parole d'après la reprise et silence de fin, sans l'audio reçu pendant la pause
+        assert!(utterances[1].duration_ms < 1700);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_low_power_vad_segmentation() {
+        let mut samples = tone(600);
+        samples.extend(vec![0; 24000]);
+        samples.extend(tone(600));
+        let mut session = virtual_session("low_power_vad", &samples, false, Pacing::Unpaced);
+        session.config.power_profile = PowerProfile::LowPower;
+        session.start_recording().unwrap();
+        let utterances = session.get_utterances();
+        assert_eq!(utterances.len(), 2);
+        assert!(utterances.iter().all(|u| u.duration_ms >= 600));
+        std::fs::remove_dir_all(session.config.output_dir.clone()).ok();
+    }
+
+    #[test]
+    fn test_energy_vad_hysteresis() {
+        let mut vad = EnergyVad::new(&VadMode::Aggressive);
+        let frame = |amplitude: i16| vec![amplitude; VAD_FRAME_SIZE];
+        // Bruit de fond: le plancher s'y cale sans déclencher
+        for _ in 0..50 {
+            assert!(!vad.is_voice(&frame(100)));
+        }
+        assert!(vad.is_voice(&frame(3000)));
+        // Fin de mot plus faible: reste en parole grâce à l'hystérésis...
+        assert!(vad.is_voice(&frame(250)));
+        // ...mais n'y serait pas entré
+        let mut fresh = EnergyVad::new(&VadMode::Aggressive);
+        fresh.noise_floor = vad.noise_floor;
+        assert!(!fresh.is_voice(&frame(250)));
+        assert!(!vad.is_voice(&frame(100)));
+    }
+
+    #[test]
+    fn test_streamed_wav_header_is_fixed_on_close() {
+        let path = std::env::temp_dir().join(format!("streamed_{}.wav", std::process::id()));
+        let mut writer = WavStreamWriter::create(&path, 16000, 1024).unwrap();
+        for _ in 0..10 {
+            writer.write(&tone(100)).unwrap();
+        }
+        assert_eq!(writer.finalize().unwrap(), 16000);
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().sample_rate, 16000);
+        assert_eq!(reader.len(), 16000);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), WAV_HEADER_LEN + 32000);
+        std::fs::remove_file(path).ok();
+
+        // Au-delà de 4 Go: en-tête RF64, tailles 64 bits dans ds64
+        let data_size = 5_000_000_000u64;
+        let header = wav_header(16000, data_size);
+        assert_eq!(header.len() as u64, WAV_HEADER_LEN);
+        assert_eq!(&header[0..4], b"RF64");
+        assert_eq!(&header[12..16], b"ds64");
+        assert_eq!(u64::from_le_bytes(header[20..28].try_into().unwrap()), WAV_HEADER_LEN - 8 + data_size);
+        assert_eq!(u64::from_le_bytes(header[28..36].try_into().unwrap()), data_size);
+        assert_eq!(&header[72..76], b"data");
+        assert_eq!(u32::from_le_bytes(header[76..80].try_into().unwrap()), u32::MAX);
+    }
+
     #[test]
     fn test_wav_creation() {
         // Créer quelques samples de test
@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::safe_write;
+use crate::speech_to_text::Cancelled;
+
+/// Files picked up in a folder (formats of `audio_decode::read_audio_16k`; the last ones
+/// need ffmpeg)
+pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "m4a", "aac", "mp4", "flac", "ogg", "oga", "opus", "webm", "wma"];
+/// Transcripts of every file of the folder, in file order
+pub const AGGREGATE_FILE: &str = "transcriptions.md";
+/// Output folder when none is given, inside the audio folder
+pub const DEFAULT_OUTPUT_DIR: &str = "transcriptions";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchFileStatus {
+    Started,
+    Done,
+    Failed,
+}
+
+/// Progress of a folder transcription, sent before and after each file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgress {
+    /// 1-based position of the file in the batch
+    pub index: usize,
+    pub total: usize,
+    pub file_name: String,
+    pub status: BatchFileStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchFileResult {
+    pub file_name: String,
+    /// Markdown transcript of the file, None when it failed
+    pub markdown_path: Option<PathBuf>,
+    pub text: String,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub files: Vec<BatchFileResult>,
+    pub aggregate_path: PathBuf,
+    /// Stopped by a cancellation: the remaining files were not transcribed
+    pub cancelled: bool,
+}
+
+/// Audio files directly in `dir` (sub-folders are not visited), sorted by name
+pub fn audio_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir).with_context(|| format!("Failed to read folder {:?}", dir))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// `<stem>.md`, or `<stem>_<ext>.md` when another audio file has the same stem
+fn markdown_name(path: &Path, used: &mut Vec<String>) -> String {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("audio");
+    let mut name = format!("{}.md", stem);
+    if used.contains(&name) {
+        let ext = path.extension().and_then(|s| s.to_str()).unwrap_or_default();
+        name = format!("{}_{}.md", stem, ext);
+    }
+    used.push(name.clone());
+    name
+}
+
+pub fn file_markdown(file_name: &str, text: &str) -> String {
+    format!("# Transcription — {}\n\n{}\n", file_name, text.trim())
+}
+
+/// One section per file, failures included so that a gap in the transcript is visible
+pub fn aggregate_markdown(files: &[BatchFileResult]) -> String {
+    let mut out = String::from("# Transcriptions\n\n");
+    for file in files {
+        out.push_str(&format!("## {}\n\n", file.file_name));
+        match &file.error {
+            Some(error) => out.push_str(&format!("*Transcription failed: {}*\n\n", error)),
+            None => out.push_str(&format!("{}\n\n", file.text.trim())),
+        }
+    }
+    out
+}
+
+/// Transcribe every audio file of `dir` with `transcribe`, writing one markdown per file
+/// and the aggregated transcript into `output_dir`. A failed file is reported and skipped;
+/// a cancelled transcription stops the batch.
+pub fn transcribe_folder<T, P>(dir: &Path, output_dir: &Path, mut transcribe: T, mut on_progress: P) -> Result<BatchResult>
+where
+    T: FnMut(&Path) -> Result<String>,
+    P: FnMut(BatchProgress),
+{
+    let audio = audio_files(dir)?;
+    if audio.is_empty() {
+        anyhow::bail!("No audio file in {:?} (expected {})", dir, AUDIO_EXTENSIONS.join(", "));
+    }
+    std::fs::create_dir_all(output_dir).with_context(|| format!("Failed to create folder {:?}", output_dir))?;
+    log::info!("[Batch] Transcribing {} files from {:?}", audio.len(), dir);
+
+    let total = audio.len();
+    let mut used_names = Vec::new();
+    let mut files = Vec::new();
+    let mut cancelled = false;
+    for (index, path) in audio.iter().enumerate() {
+        let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+        let progress = |status, error| BatchProgress { index: index + 1, total, file_name: file_name.clone(), status, error };
+        on_progress(progress(BatchFileStatus::Started, None));
+
+        let started = Instant::now();
+        let transcription = transcribe(path).and_then(|text| {
+            let markdown_path = output_dir.join(markdown_name(path, &mut used_names));
+            safe_write::write(&markdown_path, file_markdown(&file_name, &text))?;
+            Ok((text, markdown_path))
+        });
+        let duration_ms = started.elapsed().as_millis() as u64;
+        match transcription {
+            Ok((text, markdown_path)) => {
+                log::info!("[Batch] {}/{} {} transcribed in {}ms", index + 1, total, file_name, duration_ms);
+                on_progress(progress(BatchFileStatus::Done, None));
+                files.push(BatchFileResult { file_name, markdown_path: Some(markdown_path), text, error: None, duration_ms });
+            }
+            Err(e) => {
+                cancelled = e.is::<Cancelled>();
+                log::warn!("[Batch] {}/{} {} failed: {}", index + 1, total, file_name, e);
+                on_progress(progress(BatchFileStatus::Failed, Some(e.to_string())));
+                files.push(BatchFileResult { file_name, markdown_path: None, text: String::new(), error: Some(e.to_string()), duration_ms });
+                if cancelled {
+                    break;
+                }
+            }
+        }
+    }
+
+    let aggregate_path = output_dir.join(AGGREGATE_FILE);
+    safe_write::write(&aggregate_path, aggregate_markdown(&files))?;
+    Ok(BatchResult { files, aggregate_path, cancelled })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcribe_folder() {
+        let dir = std::env::temp_dir().join(format!("batch_transcription_{}", std::process::id()));
+        let output = dir.join(DEFAULT_OUTPUT_DIR);
+        std::fs::create_dir_all(&output).unwrap();
+        for name in ["b_atelier.m4a", "a_atelier.mp3", "a_atelier.wav", "notes.txt", "c_corrompu.flac"] {
+            std::fs::write(dir.join(name), name).unwrap();
+        }
+
+        let mut events = Vec::new();
+        let result = transcribe_folder(
+            &dir,
+            &output,
+            |path| match path.extension().and_then(|e| e.to_str()) {
+                Some("flac") => anyhow::bail!("Unsupported audio file"),
+                _ => Ok(format!("Texte de {}", path.file_name().unwrap().to_str().unwrap())),
+            },
+            |progress| events.push((progress.index, progress.status)),
+        )
+        .unwrap();
+
+        let names: Vec<&str> = result.files.iter().map(|f| f.file_name.as_str()).collect();
+        assert_eq!(names, vec!["a_atelier.mp3", "a_atelier.wav", "b_atelier.m4a", "c_corrompu.flac"]);
+        assert_eq!(result.files[1].markdown_path, Some(output.join("a_atelier_wav.md")));
+        assert_eq!(std::fs::read_to_string(output.join("a_atelier.md")).unwrap(), "# Transcription — a_atelier.mp3\n\nTexte de a_atelier.mp3\n");
+        assert_eq!(events.len(), 8);
+        assert_eq!(events[7], (4, BatchFileStatus::Failed));
+
+        let aggregate = std::fs::read_to_string(&result.aggregate_path).unwrap();
+        assert!(aggregate.contains("## b_atelier.m4a\n\nTexte de b_atelier.m4a\n\n## c_corrompu.flac\n\n*Transcription failed"));
+
+        // A cancellation stops the batch
+        let result = transcribe_folder(&dir, &output, |_| Err(Cancelled.into()), |_| {}).unwrap();
+        assert!(result.cancelled);
+        assert_eq!(result.files.len(), 1);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::entity_candidates::name_key;
+use crate::interview::SectionCanvasResult;
+use crate::prompt_budget::{PromptBudget, PromptSection, Trim};
+
+/// System prompt looking for contradictions between the sections of a full canvas
+pub const CONSISTENCY_SYSTEM_PROMPT: &str = r###"
+Tu relis un Canvas Domain Model complet, rédigé section par section, et tu cherches les incohérences ENTRE sections. Chaque section est précédée de son identifiant: "## [id] Titre".
+
+Exemples d'incohérences:
+- un acteur cité dans les use cases ou les événements mais absent de la liste des acteurs
+- un agrégat ou une entité nommé différemment d'une section à l'autre (Commande / Order / BonDeCommande)
+- une règle métier ou un invariant contredit par une autre section
+- un événement dont le déclencheur ne correspond à aucun use case ni agrégat
+
+Réponds UNIQUEMENT avec un JSON:
+{
+  "findings": [
+    {
+      "kind": "missing_actor" | "naming_mismatch" | "contradiction",
+      "section_ids": [identifiants des sections concernées],
+      "message": "incohérence en une phrase, en citant les termes exacts"
+    }
+  ]
+}
+
+RÈGLES:
+1. Ne signale que des incohérences entre au moins deux sections, pas le style ni les oublis d'une section seule
+2. Ne répète pas les incohérences déjà détectées listées à la fin
+3. Aucune incohérence: {"findings": []}
+"###;
+
+const ACTORS_SECTION: &str = "Acteurs & Use Cases";
+const AGGREGATES_SECTION: &str = "Agrégats & Entités/Value Objects";
+const EVENTS_SECTION: &str = "Domain Events & Règles";
+/// Names this close to an aggregate (edit distance) are taken for a misspelling of it
+const MAX_NAME_DISTANCE: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// An actor used by a section but missing from the actor list
+    MissingActor,
+    /// The same concept named differently in two sections
+    NamingMismatch,
+    Contradiction,
+}
+
+/// Whether a finding comes from the built-in rules or the LLM review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingOrigin {
+    Rule,
+    Llm,
+}
+
+/// Inconsistency between sections of the canvas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsistencyFinding {
+    pub kind: FindingKind,
+    /// Sections the user should revisit
+    pub section_ids: Vec<u32>,
+    pub message: String,
+    pub origin: FindingOrigin,
+}
+
+fn section<'a>(sections: &'a [SectionCanvasResult], title: &str) -> Option<&'a SectionCanvasResult> {
+    sections.iter().find(|s| s.section_title == title)
+}
+
+/// "un client" -> "client": articles are not part of an actor's name
+fn strip_article(name: &str) -> &str {
+    let name = name.trim();
+    ["un ", "une ", "le ", "la ", "les ", "l'", "a ", "an ", "the "]
+        .iter()
+        .find_map(|article| {
+            name.get(..article.len())
+                .filter(|start| start.eq_ignore_ascii_case(article))
+                .map(|_| name[article.len()..].trim_start())
+        })
+        .unwrap_or(name)
+}
+
+/// Markdown emphasis and placeholders out of a list item or table cell
+fn clean(text: &str) -> &str {
+    text.trim().trim_matches(|c: char| c == '*' || c == '`' || c == '_' || c.is_whitespace())
+}
+
+/// Actors of "* **Acteurs :** Client, Gestionnaire", or of the sub-items below it
+fn listed_actors(content: &str) -> Vec<String> {
+    let mut actors = Vec::new();
+    let mut in_list = false;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if let Some(rest) = trimmed.split_once("Acteurs").filter(|(before, _)| clean(before).is_empty()).map(|(_, rest)| rest) {
+            in_list = true;
+            let rest = clean(rest.trim_start_matches(|c: char| c == '*' || c == ':' || c.is_whitespace()));
+            actors.extend(rest.split([',', ';']).map(clean).filter(|a| !a.is_empty()).map(str::to_string));
+        } else if in_list && line.starts_with([' ', '\t']) && trimmed.starts_with(['-', '*']) {
+            actors.push(clean(&trimmed[1..]).to_string());
+        } else if in_list && !trimmed.is_empty() {
+            in_list = false;
+        }
+    }
+    actors
+        .into_iter()
+        .map(|actor| actor.split(" (").next().unwrap_or_default().to_string())
+        .filter(|actor| !actor.is_empty() && !actor.starts_with('['))
+        .collect()
+}
+
+/// Actors of the user stories: "En tant que gestionnaire, je veux ..."
+fn use_case_actors(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let start = line.find("En tant que ").or_else(|| line.find("en tant que "))? + "en tant que ".len();
+            let rest = &line[start..];
+            let end = rest.find([',', ';']).or_else(|| rest.find(" je ")).unwrap_or(rest.len());
+            Some(clean(&rest[..end]).to_string())
+        })
+        .filter(|actor| !actor.is_empty() && !actor.starts_with('['))
+        .collect()
+}
+
+/// First cells of the markdown table rows following `heading` (the first table when
+/// None), header and separator rows excluded
+fn table_names(content: &str, heading: Option<&str>) -> Vec<String> {
+    let mut lines = content.lines().skip_while(|line| heading.is_some_and(|heading| !line.contains(heading)));
+    lines
+        .by_ref()
+        .skip_while(|line| !line.trim_start().starts_with('|'))
+        .take_while(|line| line.trim_start().starts_with('|'))
+        .skip(2)
+        .filter_map(|row| row.trim().trim_start_matches('|').split('|').next().map(clean))
+        .filter(|name| !name.is_empty() && !name.starts_with('['))
+        .map(str::to_string)
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (diagonal + (ca != *cb) as usize).min(row[j] + 1).min(above + 1);
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Leading word of an event name: "CommandeValidée" -> "Commande"
+fn event_subject(event: &str) -> &str {
+    let end = event.char_indices().skip(1).find(|(_, c)| c.is_uppercase() || c.is_whitespace()).map(|(i, _)| i);
+    &event[..end.unwrap_or(event.len())]
+}
+
+/// Rule pass: use-case actors missing from the actor list, and events named after an
+/// aggregate spelled differently in the aggregate table
+pub fn rule_findings(sections: &[SectionCanvasResult]) -> Vec<ConsistencyFinding> {
+    let mut findings = Vec::new();
+
+    if let Some(actors_section) = section(sections, ACTORS_SECTION) {
+        let listed: Vec<String> = listed_actors(&actors_section.canvas_content).iter().map(|a| name_key(strip_article(a))).collect();
+        let mut reported = Vec::new();
+        for actor in use_case_actors(&actors_section.canvas_content) {
+            let key = name_key(strip_article(&actor));
+            if listed.is_empty() || listed.contains(&key) || reported.contains(&key) {
+                continue;
+            }
+            findings.push(ConsistencyFinding {
+                kind: FindingKind::MissingActor,
+                section_ids: vec![actors_section.section_id],
+                message: format!("Actor '{}' of the use cases is not in the actor list", strip_article(&actor)),
+                origin: FindingOrigin::Rule,
+            });
+            reported.push(key);
+        }
+    }
+
+    if let (Some(aggregates_section), Some(events_section)) = (section(sections, AGGREGATES_SECTION), section(sections, EVENTS_SECTION)) {
+        let aggregates = table_names(&aggregates_section.canvas_content, Some("Agrégats"));
+        let keys: Vec<String> = aggregates.iter().map(|a| name_key(a)).collect();
+        for event in table_names(&events_section.canvas_content, None) {
+            let subject = name_key(event_subject(&event));
+            if subject.chars().count() < 4 || keys.contains(&subject) {
+                continue;
+            }
+            let closest = keys.iter().zip(&aggregates).find(|(key, _)| edit_distance(key, &subject) <= MAX_NAME_DISTANCE);
+            if let Some((_, aggregate)) = closest {
+                findings.push(ConsistencyFinding {
+                    kind: FindingKind::NamingMismatch,
+                    section_ids: vec![aggregates_section.section_id, events_section.section_id],
+                    message: format!("Event '{}' names aggregate '{}' as '{}'", event, aggregate, event_subject(&event)),
+                    origin: FindingOrigin::Rule,
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// User prompt: every section with its id (the longest ones cut from their end when
+/// over the token budget), then the findings of the rule pass
+pub fn review_prompt(sections: &[SectionCanvasResult], known: &[ConsistencyFinding], budget: &PromptBudget) -> (String, Vec<String>) {
+    let mut prompt_sections = vec![PromptSection::new("system prompt", CONSISTENCY_SYSTEM_PROMPT.to_string(), 2, Trim::Never)];
+    prompt_sections.extend(sections.iter().map(|s| {
+        PromptSection::new(
+            &format!("section:{}", s.section_id),
+            format!("## [{}] {}\n{}\n\n", s.section_id, s.section_title, s.canvas_content.trim()),
+            1,
+            Trim::Lines,
+        )
+    }));
+    let budgeted = budget.fit(prompt_sections);
+
+    let mut prompt: String = budgeted.sections.iter().filter(|s| s.name.starts_with("section:")).map(|s| s.text.as_str()).collect();
+    if !known.is_empty() {
+        prompt.push_str("Incohérences déjà détectées:\n");
+        for finding in known {
+            prompt.push_str(&format!("- {:?} {}\n", finding.section_ids, finding.message));
+        }
+    }
+    (prompt, budgeted.warnings)
+}
+
+/// Findings of the LLM review; those without a known section are dropped
+pub fn parse_findings(response: &Value, sections: &[SectionCanvasResult]) -> Vec<ConsistencyFinding> {
+    let findings = response["findings"].as_array().map(Vec::as_slice).unwrap_or_default();
+    findings
+        .iter()
+        .filter_map(|finding| {
+            let message = finding["message"].as_str().map(str::trim).filter(|m| !m.is_empty())?;
+            let section_ids: Vec<u32> = finding["section_ids"]
+                .as_array()?
+                .iter()
+                .filter_map(Value::as_u64)
+                .map(|id| id as u32)
+                .filter(|id| sections.iter().any(|s| s.section_id == *id))
+                .collect();
+            if section_ids.is_empty() {
+                return None;
+            }
+            let kind = serde_json::from_value(finding["kind"].clone()).unwrap_or(FindingKind::Contradiction);
+            Some(ConsistencyFinding { kind, section_ids, message: message.to_string(), origin: FindingOrigin::Llm })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn section(section_id: u32, title: &str, content: &str) -> SectionCanvasResult {
+        SectionCanvasResult { section_id, section_title: title.to_string(), canvas_content: content.to_string(), generation: None }
+    }
+
+    #[test]
+    fn test_rule_findings() {
+        let sections = vec![
+            section(
+                2,
+                ACTORS_SECTION,
+                "* **Acteurs :** Client, Gestionnaire de stock\n* **Top 5 use cases (orientés résultat) :**\n  1. En tant que client, je veux commander afin de recevoir mes produits\n  2. En tant que superviseur, je veux valider les commandes afin de limiter la fraude\n  3. En tant que gestionnaire de stock, je veux réapprovisionner",
+            ),
+            section(
+                4,
+                AGGREGATES_SECTION,
+                "### Agrégats\n\n| Agrégat | Racine | Principales entités internes | Invariants | Politiques |\n| --- | --- | --- | --- | --- |\n| **Commande** | Commande | LigneCommande | total >= 0 | - |\n| Stock | Stock | - | - | - |",
+            ),
+            section(
+                5,
+                EVENTS_SECTION,
+                "| Événement | Quand | Payload minimal | Consommateurs | Outbox ? |\n| --- | --- | --- | --- | --- |\n| CommandeValidée | validation | id | Stock | Oui |\n| CommandValidée | validation | id | Stock | Oui |\n| StockÉpuisé | rupture | sku | Achats | Non |",
+            ),
+        ];
+
+        let findings = rule_findings(&sections);
+        assert_eq!(findings.len(), 2, "{:?}", findings);
+        assert_eq!((findings[0].kind, findings[0].section_ids.clone()), (FindingKind::MissingActor, vec![2]));
+        assert!(findings[0].message.contains("'superviseur'"));
+        assert_eq!((findings[1].kind, findings[1].section_ids.clone()), (FindingKind::NamingMismatch, vec![4, 5]));
+        assert!(findings[1].message.contains("'CommandValidée'"));
+
+        // LLM findings must point at sections of the canvas
+        let response = json!({"findings": [
+            {"kind": "contradiction", "section_ids": [4, 9], "message": "Le total peut être négatif selon la section 5"},
+            {"kind": "unknown", "section_ids": [5], "message": "Consommateur Achats inconnu"},
+            {"kind": "naming_mismatch", "section_ids": [9], "message": "Hors canvas"}
+        ]});
+        let parsed = parse_findings(&response, &sections);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!((parsed[0].section_ids.clone(), parsed[0].origin), (vec![4], FindingOrigin::Llm));
+        assert_eq!(parsed[1].kind, FindingKind::Contradiction);
+    }
+}
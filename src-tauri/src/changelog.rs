@@ -0,0 +1,197 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// System prompt for changelog summarization: the LLM receives the structural
+/// changes between two model versions and writes them up for readers
+pub const CHANGELOG_SYSTEM_PROMPT: &str = r#"
+Tu rédiges le changelog d'un Domain Model entre deux versions. Tu reçois la liste des changements structurels et, pour chaque élément modifié, son état avant et après.
+
+Réponds en markdown, sans titre:
+- un paragraphe de 2 à 3 phrases résumant l'évolution du modèle
+- puis une liste à puces, un changement par ligne, formulé pour un lecteur métier (ex: "Ajout de l'entité Commande", "Invariant montant_positif durci: le montant doit maintenant être strictement positif")
+
+RÈGLES:
+1. Ne mentionne que les changements fournis, n'en invente aucun
+2. Qualifie les modifications d'invariants et de cardinalités (durci, assoupli, élargi...) quand l'avant/après le permet
+3. Réponds en français
+"#;
+
+/// Changelog between two versions of a project's model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelChangelog {
+    pub from: u32,
+    pub to: u32,
+    /// One line per structural change, derived from the diff
+    pub changes: Vec<String>,
+    /// LLM write-up of the changes, absent if the LLM was unavailable
+    pub summary: Option<String>,
+    /// Section appended to the exported documentation
+    pub markdown: String,
+}
+
+fn ids(list: &Value) -> Vec<String> {
+    list.as_array()
+        .map(|items| items.iter().filter_map(|v| v.as_str()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn find<'a>(model: &'a Value, category: &str, id: &str) -> Option<&'a Value> {
+    model[category].as_array()?.iter().find(|item| item["id"] == id)
+}
+
+/// (category in the model, label in French) of each diff category
+const CATEGORIES: [(&str, &str); 3] = [("entities", "l'entité"), ("relations", "la relation"), ("invariants", "l'invariant")];
+
+/// One French line per structural change of a `diff_models` result
+pub fn describe_diff(diff: &Value) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (category, label) in CATEGORIES {
+        let category_diff = &diff[category];
+        for id in ids(&category_diff["added"]) {
+            lines.push(format!("Ajout de {} `{}`", label, id));
+        }
+        for id in ids(&category_diff["removed"]) {
+            lines.push(format!("Suppression de {} `{}`", label, id));
+        }
+        for modified in category_diff["modified"].as_array().into_iter().flatten() {
+            let id = modified["id"].as_str().unwrap_or("?");
+            let attributes = &modified["attributes"];
+            let mut changes = ids(&modified["fields"]);
+            changes.extend(ids(&attributes["added"]).into_iter().map(|a| format!("+{}", a)));
+            changes.extend(ids(&attributes["removed"]).into_iter().map(|a| format!("-{}", a)));
+            changes.extend(
+                attributes["modified"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|a| a["name"].as_str())
+                    .map(|a| format!("~{}", a)),
+            );
+            lines.push(format!("Modification de {} `{}` ({})", label, id, changes.join(", ")));
+        }
+    }
+    lines
+}
+
+/// User prompt for the summary: the change lines, plus the before/after of modified elements
+pub fn changelog_prompt(diff: &Value, before: &Value, after: &Value, changes: &[String]) -> Result<String> {
+    let mut modified = Vec::new();
+    for (category, _) in CATEGORIES {
+        for item in diff[category]["modified"].as_array().into_iter().flatten() {
+            let id = item["id"].as_str().unwrap_or_default();
+            modified.push(json!({
+                "category": category,
+                "id": id,
+                "before": find(before, category, id),
+                "after": find(after, category, id),
+            }));
+        }
+    }
+    Ok(format!(
+        "Changements:\n{}\n\nÉléments modifiés (avant/après):\n{}",
+        changes.iter().map(|c| format!("- {}", c)).collect::<Vec<_>>().join("\n"),
+        serde_json::to_string_pretty(&modified).context("Failed to serialize modified elements")?
+    ))
+}
+
+/// Markdown section for the exported documentation
+pub fn to_markdown(from: u32, to: u32, changes: &[String], summary: Option<&str>) -> String {
+    let mut markdown = format!("## Changelog v{} → v{}\n\n", from, to);
+    if changes.is_empty() {
+        markdown.push_str("Aucun changement.\n");
+        return markdown;
+    }
+    match summary {
+        Some(summary) => {
+            markdown.push_str(summary.trim());
+            markdown.push('\n');
+        }
+        None => {
+            for change in changes {
+                markdown.push_str(&format!("- {}\n", change));
+            }
+        }
+    }
+    markdown
+}
+
+/// Canvas with the changelog `section` of v`from` → v`to`: an earlier section for the same
+/// versions is replaced, otherwise the section is appended
+pub fn insert_section(canvas: &str, from: u32, to: u32, section: &str) -> String {
+    let heading = format!("## Changelog v{} → v{}\n", from, to);
+    let start = if canvas.starts_with(&heading) { Some(0) } else { canvas.find(&format!("\n{}", heading)).map(|i| i + 1) };
+    match start {
+        Some(start) => {
+            let body = start + heading.len();
+            let end = canvas[body..].find("\n## ").map_or(canvas.len(), |i| body + i + 1);
+            match &canvas[end..] {
+                "" => format!("{}{}", &canvas[..start], section),
+                next => format!("{}{}\n\n{}", &canvas[..start], section.trim_end(), next),
+            }
+        }
+        None if canvas.trim().is_empty() => section.to_string(),
+        None => format!("{}\n\n{}", canvas.trim_end(), section),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_diff_and_prompt() {
+        let before = json!({
+            "entities": [{"id": "Book", "attributes": [{"name": "title", "type": "string"}]}],
+            "relations": [],
+            "invariants": [{"id": "positive_price", "expression": "price >= 0"}]
+        });
+        let after = json!({
+            "entities": [
+                {"id": "Book", "attributes": [{"name": "title", "type": "string"}, {"name": "isbn", "type": "string"}]},
+                {"id": "Order", "attributes": []}
+            ],
+            "relations": [],
+            "invariants": [{"id": "positive_price", "expression": "price > 0"}]
+        });
+        let diff = json!({
+            "identical": false,
+            "entities": {"added": ["Order"], "removed": [], "modified": [{"id": "Book", "fields": [], "attributes": {"added": ["isbn"], "removed": [], "modified": []}}]},
+            "relations": {"added": [], "removed": [], "modified": []},
+            "invariants": {"added": [], "removed": [], "modified": [{"id": "positive_price", "fields": ["expression"]}]}
+        });
+
+        let changes = describe_diff(&diff);
+        assert_eq!(
+            changes,
+            vec![
+                "Ajout de l'entité `Order`",
+                "Modification de l'entité `Book` (+isbn)",
+                "Modification de l'invariant `positive_price` (expression)",
+            ]
+        );
+
+        let prompt = changelog_prompt(&diff, &before, &after, &changes).unwrap();
+        assert!(prompt.contains("price >= 0") && prompt.contains("price > 0"));
+
+        let fallback = to_markdown(1, 3, &changes, None);
+        assert!(fallback.starts_with("## Changelog v1 → v3\n\n- Ajout de l'entité `Order`\n"));
+        assert_eq!(to_markdown(2, 3, &[], None), "## Changelog v2 → v3\n\nAucun changement.\n");
+    }
+
+    #[test]
+    fn test_insert_section_replaces_same_versions() {
+        let first = to_markdown(1, 2, &["Ajout de l'entité `Order`".to_string()], None);
+        let canvas = insert_section("## Contexte & Vision\n\nVente en ligne.\n", 1, 2, &first);
+        assert_eq!(canvas, format!("## Contexte & Vision\n\nVente en ligne.\n\n{}", first));
+
+        let other = to_markdown(2, 3, &[], None);
+        let canvas = insert_section(&canvas, 2, 3, &other);
+        let again = to_markdown(1, 2, &["Ajout de l'entité `Invoice`".to_string()], None);
+        let canvas = insert_section(&canvas, 1, 2, &again);
+        assert_eq!(canvas.matches("## Changelog v1 → v2").count(), 1);
+        assert!(!canvas.contains("`Order`") && canvas.contains("`Invoice`"));
+        assert!(canvas.ends_with("- Ajout de l'entité `Invoice`\n\n## Changelog v2 → v3\n\nAucun changement.\n"));
+        assert_eq!(insert_section("", 1, 2, &first), first);
+    }
+}
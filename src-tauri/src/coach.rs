@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// System prompt of the facilitation coach: the LLM compares what was said
+/// recently with the goals of the current section and points out what is missing
+pub const COACH_SYSTEM_PROMPT: &str = r#"
+Tu assistes l'animateur d'un entretien de modélisation métier (Domain-Driven Design). Tu reçois les objectifs de la section en cours, les dernières minutes de transcription et les suggestions déjà faites.
+
+Propose au plus 2 relances courtes que l'animateur pourrait faire maintenant, pour couvrir un objectif de la section que la transcription n'aborde pas encore (ex: "Vous n'avez pas encore parlé des invariants de Paiement", "Demandez qui peut annuler une commande").
+
+RÈGLES:
+1. Une relance par ligne, sans numérotation ni commentaire
+2. Ne répète pas une suggestion déjà faite
+3. Si la transcription couvre déjà les objectifs, réponds uniquement: RIEN
+4. Réponds en français
+"#;
+
+/// Most suggestions kept from one analysis
+const MAX_SUGGESTIONS: usize = 2;
+/// Transcript the coach looks at
+const RECENT_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// Coach preferences, persisted in the settings. Off by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoachSettings {
+    pub enabled: bool,
+    /// Minimum time between two analyses
+    pub interval_secs: u64,
+}
+
+impl Default for CoachSettings {
+    fn default() -> Self {
+        Self { enabled: false, interval_secs: 90 }
+    }
+}
+
+/// Interview section being facilitated, with what it should cover
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoachSection {
+    pub section_id: u32,
+    pub section_title: String,
+    /// Questions or topics of the section
+    pub goals: Vec<String>,
+}
+
+/// Payload of a "coach-suggestion" event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoachSuggestion {
+    pub section_id: u32,
+    pub message: String,
+}
+
+/// Analysis to run: the section and the transcript of the last minutes
+#[derive(Debug, Clone)]
+pub struct CoachRequest {
+    pub section: CoachSection,
+    pub recent: String,
+    pub previous: Vec<String>,
+}
+
+/// Transcript window and throttling of the coach
+#[derive(Debug, Default)]
+pub struct Coach {
+    settings: CoachSettings,
+    section: Option<CoachSection>,
+    transcript: VecDeque<(Instant, String)>,
+    /// New text since the last analysis
+    unseen: bool,
+    in_flight: bool,
+    last_run: Option<Instant>,
+    /// Suggestions already emitted for the current section
+    emitted: Vec<String>,
+}
+
+impl Coach {
+    pub fn set_settings(&mut self, settings: CoachSettings) {
+        self.settings = settings;
+    }
+
+    /// Switch to another section (None when leaving the interview): its suggestions start over
+    pub fn set_section(&mut self, section: Option<CoachSection>) {
+        if self.section.as_ref().map(|s| s.section_id) != section.as_ref().map(|s| s.section_id) {
+            self.emitted.clear();
+        }
+        self.section = section;
+    }
+
+    pub fn push_transcript(&mut self, text: &str, now: Instant) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.transcript.push_back((now, text.to_string()));
+        while self.transcript.front().is_some_and(|(at, _)| now.duration_since(*at) > RECENT_WINDOW) {
+            self.transcript.pop_front();
+        }
+        self.unseen = true;
+    }
+
+    /// Analysis to run now, if the coach is enabled, has a section, got new text
+    /// and the last analysis is older than the interval
+    pub fn take_request(&mut self, now: Instant) -> Option<CoachRequest> {
+        if !self.settings.enabled || !self.unseen || self.in_flight {
+            return None;
+        }
+        let interval = Duration::from_secs(self.settings.interval_secs);
+        if self.last_run.is_some_and(|last| now.duration_since(last) < interval) {
+            return None;
+        }
+        let section = self.section.clone()?;
+        self.unseen = false;
+        self.in_flight = true;
+        self.last_run = Some(now);
+        Some(CoachRequest {
+            section,
+            recent: self.transcript.iter().map(|(_, text)| text.as_str()).collect::<Vec<_>>().join("\n"),
+            previous: self.emitted.clone(),
+        })
+    }
+
+    /// End of an analysis: the suggestions not emitted yet for the section
+    pub fn finish(&mut self, section_id: u32, suggestions: Vec<String>) -> Vec<CoachSuggestion> {
+        self.in_flight = false;
+        if self.section.as_ref().map(|s| s.section_id) != Some(section_id) {
+            return Vec::new();
+        }
+        let fresh: Vec<String> = suggestions.into_iter().filter(|s| !self.emitted.contains(s)).collect();
+        self.emitted.extend(fresh.iter().cloned());
+        fresh.into_iter().map(|message| CoachSuggestion { section_id, message }).collect()
+    }
+}
+
+pub fn coach_prompt(request: &CoachRequest) -> String {
+    let list = |items: &[String]| items.iter().map(|item| format!("- {}", item)).collect::<Vec<_>>().join("\n");
+    format!(
+        "Section: {}\n\nObjectifs:\n{}\n\nSuggestions déjà faites:\n{}\n\nTranscription récente:\n{}",
+        request.section.section_title,
+        list(&request.section.goals),
+        if request.previous.is_empty() { "(aucune)".to_string() } else { list(&request.previous) },
+        request.recent
+    )
+}
+
+/// Suggestions of the LLM answer, one per line, without bullets or numbering
+pub fn parse_suggestions(answer: &str) -> Vec<String> {
+    // "1." or "2)" only: a line may start with a number ("2 clients...")
+    let strip_numbering = |line: &str| {
+        let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+        match rest.strip_prefix(['.', ')']) {
+            Some(rest) if rest.len() + 1 < line.len() => rest.to_string(),
+            _ => line.to_string(),
+        }
+    };
+    answer
+        .lines()
+        .map(|line| strip_numbering(line.trim().trim_start_matches(['-', '*', '•']).trim()).trim().to_string())
+        .filter(|line| !line.is_empty() && !line.eq_ignore_ascii_case("RIEN"))
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(id: u32) -> CoachSection {
+        CoachSection { section_id: id, section_title: "Invariants".to_string(), goals: vec!["Règles de Paiement".to_string()] }
+    }
+
+    #[test]
+    fn test_throttling_and_dedup() {
+        let start = Instant::now();
+        let mut coach = Coach::default();
+        coach.set_section(Some(section(5)));
+        coach.push_transcript("On encaisse le paiement à la commande", start);
+        assert!(coach.take_request(start).is_none(), "disabled by default");
+
+        coach.set_settings(CoachSettings { enabled: true, interval_secs: 60 });
+        let request = coach.take_request(start).unwrap();
+        assert_eq!(request.recent, "On encaisse le paiement à la commande");
+        assert!(coach.take_request(start).is_none(), "analysis in flight");
+
+        let emitted = coach.finish(5, parse_suggestions("- Vous n'avez pas parlé des invariants de Paiement\nRIEN"));
+        assert_eq!(emitted.len(), 1);
+
+        coach.push_transcript("Le montant est toujours positif", start + Duration::from_secs(30));
+        assert!(coach.take_request(start + Duration::from_secs(30)).is_none(), "throttled");
+        let later = start + Duration::from_secs(61);
+        assert!(coach.take_request(later).unwrap().previous.len() == 1);
+        assert!(coach.finish(5, vec!["Vous n'avez pas parlé des invariants de Paiement".to_string()]).is_empty());
+
+        // Old transcript leaves the window
+        coach.push_transcript("Autre sujet", start + Duration::from_secs(400));
+        assert_eq!(coach.transcript.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_suggestions() {
+        assert_eq!(parse_suggestions("1. Demandez qui annule\n\n2) Et les remboursements ?\n3. Trop"), vec!["Demandez qui annule", "Et les remboursements ?"]);
+        assert!(parse_suggestions("RIEN").is_empty());
+        assert_eq!(parse_suggestions("2 clients peuvent-ils partager un panier ?"), vec!["2 clients peuvent-ils partager un panier ?"]);
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 16kHz mono 16-bit WAV: what one minute of recording takes on disk
+pub const BYTES_PER_RECORDING_MINUTE: u64 = 16_000 * 2 * 60;
+/// Below this much recording time left, a recording is refused
+const MIN_RECORDING_MINUTES: u64 = 10;
+/// Below this much recording time left, the recording starts with a warning
+const WARN_RECORDING_MINUTES: u64 = 60;
+/// Kept free on top of any estimate (session files, transcripts, the OS itself)
+const SAFETY_MARGIN_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Payload of the "disk-space-warning" and "disk-space-error" events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskSpaceStatus {
+    pub path: String,
+    pub available_bytes: u64,
+    pub required_bytes: u64,
+    /// Recording time the free space allows (recordings only)
+    pub recording_minutes_left: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Preflight {
+    Ok,
+    /// Enough for now, but not for a long session
+    Low(DiskSpaceStatus),
+    /// Refuse to start
+    Insufficient(DiskSpaceStatus),
+}
+
+impl DiskSpaceStatus {
+    pub fn message(&self) -> String {
+        format!(
+            "Not enough disk space in {}: {} MB free, {} MB needed",
+            self.path,
+            self.available_bytes / (1024 * 1024),
+            self.required_bytes / (1024 * 1024)
+        )
+    }
+}
+
+/// "Available" column of `df -Pk`, in bytes
+fn parse_df(output: &str) -> Option<u64> {
+    let kb: u64 = output.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Free space of the file system holding `path` (or its closest existing parent).
+/// None when it cannot be measured (Windows, no `df`): the preflight then lets it go.
+pub fn available_bytes(path: &Path) -> Option<u64> {
+    if !cfg!(unix) {
+        return None;
+    }
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let output = std::process::Command::new("df").arg("-Pk").arg(existing).output().ok()?;
+    output.status.success().then(|| parse_df(&String::from_utf8_lossy(&output.stdout))).flatten()
+}
+
+fn recording_check(path: &Path, available: u64, bytes_per_minute: u64) -> Preflight {
+    let bytes_per_minute = bytes_per_minute.max(1);
+    let status = |minutes: u64| DiskSpaceStatus {
+        path: path.display().to_string(),
+        available_bytes: available,
+        required_bytes: minutes * bytes_per_minute + SAFETY_MARGIN_BYTES,
+        recording_minutes_left: Some(available.saturating_sub(SAFETY_MARGIN_BYTES) / bytes_per_minute),
+    };
+    let minimum = status(MIN_RECORDING_MINUTES);
+    if available < minimum.required_bytes {
+        return Preflight::Insufficient(minimum);
+    }
+    let comfortable = status(WARN_RECORDING_MINUTES);
+    if available < comfortable.required_bytes {
+        return Preflight::Low(comfortable);
+    }
+    Preflight::Ok
+}
+
+/// Before a recording writing `bytes_per_minute` into `dir`: refused under
+/// MIN_RECORDING_MINUTES of room, warned under WARN_RECORDING_MINUTES
+pub fn recording_preflight(dir: &Path, bytes_per_minute: u64) -> Preflight {
+    match available_bytes(dir) {
+        Some(available) => recording_check(dir, available, bytes_per_minute),
+        None => Preflight::Ok,
+    }
+}
+
+fn download_check(path: &Path, available: u64, size_bytes: u64) -> Preflight {
+    let required_bytes = size_bytes + SAFETY_MARGIN_BYTES;
+    if available < required_bytes {
+        return Preflight::Insufficient(DiskSpaceStatus {
+            path: path.display().to_string(),
+            available_bytes: available,
+            required_bytes,
+            recording_minutes_left: None,
+        });
+    }
+    Preflight::Ok
+}
+
+/// Before downloading `size_bytes` into `dir`
+pub fn download_preflight(dir: &Path, size_bytes: u64) -> Preflight {
+    match available_bytes(dir) {
+        Some(available) => download_check(dir, available, size_bytes),
+        None => Preflight::Ok,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n/dev/sda1        102400000  5000000  97400000      5% /\n";
+        assert_eq!(parse_df(output), Some(97_400_000 * 1024));
+        assert_eq!(parse_df("Filesystem\n"), None);
+    }
+
+    #[test]
+    fn test_preflight_thresholds() {
+        let dir = Path::new("/recordings");
+        let minutes = |n: u64| n * BYTES_PER_RECORDING_MINUTE + SAFETY_MARGIN_BYTES;
+        assert_eq!(recording_check(dir, minutes(120), BYTES_PER_RECORDING_MINUTE), Preflight::Ok);
+        match recording_check(dir, minutes(30), BYTES_PER_RECORDING_MINUTE) {
+            Preflight::Low(status) => assert_eq!(status.recording_minutes_left, Some(30)),
+            other => panic!("expected a warning, got {:?}", other),
+        }
+        assert!(matches!(recording_check(dir, minutes(5), BYTES_PER_RECORDING_MINUTE), Preflight::Insufficient(_)));
+        // Raw archive doubles the footprint
+        assert!(matches!(recording_check(dir, minutes(15), 2 * BYTES_PER_RECORDING_MINUTE), Preflight::Insufficient(_)));
+
+        assert_eq!(download_check(dir, 600 * 1024 * 1024, 487_601_967), Preflight::Ok);
+        assert!(matches!(download_check(dir, 500 * 1024 * 1024, 487_601_967), Preflight::Insufficient(_)));
+    }
+}
@@ -0,0 +1,127 @@
+/// Durée d'écho couverte par défaut: les réverbérations d'une salle de réunion au-delà
+/// de 128ms sont assez faibles pour ne plus gêner Whisper
+pub const DEFAULT_TAIL_MS: u32 = 128;
+
+/// Pas d'adaptation du NLMS (0 < mu < 2): assez lent pour rester stable en double parole
+const STEP_SIZE: f32 = 0.5;
+/// Évite la division par zéro quand la référence est silencieuse
+const ENERGY_FLOOR: f32 = 1e-6;
+/// Détecteur de Geigel: le micro dépasse la moitié du pic de la référence, c'est donc
+/// la personne dans la pièce qui parle (l'écho est atténué d'au moins 6dB)
+const DOUBLE_TALK_RATIO: f32 = 0.5;
+/// Adaptation gelée encore 30ms après la dernière détection de double parole
+const DOUBLE_TALK_HANGOVER_MS: u32 = 30;
+
+/// Annulation d'écho acoustique (AEC) pour le mode réunion: la voix de l'interlocuteur
+/// distant, jouée par les haut-parleurs (la référence), est retirée du micro avant le VAD
+/// et la transcription, pour ne pas être captée puis transcrite une seconde fois.
+///
+/// Filtre adaptatif NLMS qui apprend la réponse haut-parleurs → pièce → micro, avec un
+/// détecteur de double parole qui fige l'apprentissage quand les deux côtés parlent.
+/// Micro et référence sont mono, au même sample rate et alignés bloc par bloc.
+pub struct EchoCanceller {
+    /// Réponse estimée de l'écho, un coefficient par sample de retard
+    weights: Vec<f32>,
+    /// Derniers samples de la référence, écrits deux fois pour lire une fenêtre contiguë
+    history: Vec<f32>,
+    position: usize,
+    hangover: u32,
+    hangover_samples: u32,
+}
+
+impl EchoCanceller {
+    pub fn new(sample_rate: u32, tail_ms: u32) -> Self {
+        let taps = (sample_rate * tail_ms / 1000).max(1) as usize;
+        Self {
+            weights: vec![0.0; taps],
+            history: vec![0.0; taps * 2],
+            position: 0,
+            hangover: 0,
+            hangover_samples: sample_rate * DOUBLE_TALK_HANGOVER_MS / 1000,
+        }
+    }
+
+    /// Retire l'écho de `far` (référence jouée) contenu dans `near` (micro). Si la
+    /// référence est plus courte que le bloc micro, la suite est traitée comme du silence.
+    pub fn process(&mut self, near: &[f32], far: &[f32]) -> Vec<f32> {
+        near.iter()
+            .enumerate()
+            .map(|(i, &sample)| self.process_sample(sample, far.get(i).copied().unwrap_or(0.0)))
+            .collect()
+    }
+
+    fn process_sample(&mut self, near: f32, far: f32) -> f32 {
+        let taps = self.weights.len();
+        self.position = (self.position + taps - 1) % taps;
+        self.history[self.position] = far;
+        self.history[self.position + taps] = far;
+        // window[0] est le sample le plus récent de la référence
+        let window = &self.history[self.position..self.position + taps];
+
+        let (mut estimate, mut energy, mut peak) = (0.0f32, 0.0f32, 0.0f32);
+        for (weight, &x) in self.weights.iter().zip(window) {
+            estimate += weight * x;
+            energy += x * x;
+            peak = peak.max(x.abs());
+        }
+        let error = near - estimate;
+
+        if near.abs() > DOUBLE_TALK_RATIO * peak {
+            self.hangover = self.hangover_samples;
+        } else if self.hangover > 0 {
+            self.hangover -= 1;
+        } else {
+            let gain = STEP_SIZE * error / (energy + ENERGY_FLOOR);
+            for (weight, &x) in self.weights.iter_mut().zip(window) {
+                *weight += gain * x;
+            }
+        }
+
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bruit pseudo-aléatoire reproductible dans [-0.5, 0.5]
+    fn noise(len: usize) -> Vec<f32> {
+        let mut state = 12345u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                (state >> 16) as f32 / 65536.0 - 0.5
+            })
+            .collect()
+    }
+
+    fn energy(samples: &[f32]) -> f32 {
+        samples.iter().map(|s| s * s).sum()
+    }
+
+    #[test]
+    fn test_echo_is_removed_and_local_voice_kept() {
+        // 3s de voix distante, renvoyée par la pièce avec deux réflexions (2,5ms et 6ms)
+        let far = noise(48000);
+        let echo: Vec<f32> = (0..far.len())
+            .map(|i| 0.25 * far.get(i.wrapping_sub(40)).unwrap_or(&0.0) + 0.1 * far.get(i.wrapping_sub(96)).unwrap_or(&0.0))
+            .collect();
+
+        let mut canceller = EchoCanceller::new(16000, DEFAULT_TAIL_MS);
+        let mut output = Vec::new();
+        for (near, far) in echo.chunks(480).zip(far.chunks(480)) {
+            output.extend(canceller.process(near, far));
+        }
+        // Après convergence, l'écho est atténué de plus de 20dB
+        let tail = 40000..48000;
+        assert!(energy(&output[tail.clone()]) < energy(&echo[tail]) / 100.0);
+
+        // Une fois la voix distante et son écho éteints, la voix locale passe telle quelle
+        canceller.process(&[0.0; 2048], &[]);
+        let local: Vec<f32> = (0..1600).map(|i| 0.3 * (i as f32 * 0.05).sin()).collect();
+        let kept = canceller.process(&local, &[]);
+        let residual: Vec<f32> = kept.iter().zip(&local).map(|(k, l)| k - l).collect();
+        assert!(energy(&residual) < energy(&local) / 100.0);
+    }
+}
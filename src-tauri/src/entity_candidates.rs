@@ -0,0 +1,347 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+
+use crate::llm_router::LlmProvider;
+use crate::provider_policy;
+
+/// Most frequent candidates kept for the prompt
+const MAX_ENTITIES: usize = 30;
+const MAX_ATTRIBUTES: usize = 50;
+
+const DETERMINERS: &[&str] = &["le", "la", "les", "l", "un", "une", "des", "du", "chaque", "ce", "cette", "the", "a", "an", "each", "every", "this"];
+const OWNER_DETERMINERS: &[&str] = &["du", "de", "des", "la", "l", "the", "a", "an", "each"];
+/// "a un", "possède", "has"... between an entity and one of its attributes
+const HAS_VERBS: &[&str] = &["a", "ont", "possède", "possèdent", "contient", "contiennent", "has", "have", "contains", "holds"];
+const STOPWORDS: &[&str] = &[
+    "le", "la", "les", "un", "une", "des", "du", "de", "et", "ou", "est", "sont", "a", "ont", "il", "elle", "ils", "elles", "on", "nous", "vous",
+    "qui", "que", "quoi", "dont", "pour", "par", "avec", "sans", "dans", "sur", "pas", "plus", "très", "aussi", "mais", "donc", "alors", "fois",
+    "chose", "choses", "truc", "gens", "moment", "exemple", "cas", "même", "tout", "tous", "toute", "toutes", "autre", "autres", "peu", "bien",
+    "the", "and", "or", "is", "are", "has", "have", "it", "they", "we", "you", "who", "which", "that", "for", "with", "without", "in", "on",
+    "not", "more", "very", "also", "but", "so", "then", "time", "thing", "things", "way", "people", "example", "case", "same", "all", "other",
+    "some", "one", "lot", "bit", "kind", "sort",
+];
+
+/// Name proposed by the deterministic pass, with its number of mentions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candidate {
+    pub name: String,
+    pub mentions: usize,
+    /// Entity an attribute was said to belong to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity: Option<String>,
+}
+
+/// Entity and attribute names found in a transcript before the LLM runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Candidates {
+    pub entities: Vec<Candidate>,
+    pub attributes: Vec<Candidate>,
+}
+
+/// Candidates compared with the generated model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CandidateReport {
+    pub candidates: Candidates,
+    /// Candidate entities found in the model
+    pub confirmed: Vec<String>,
+    /// Candidate entities mentioned several times that the model left out
+    pub missed: Vec<String>,
+    /// Model entities and attributes ("Entity.attribute") matching no candidate: possible
+    /// hallucinations, to check against the transcript
+    pub unexpected: Vec<String>,
+}
+
+/// Lowercase words of a sentence, elisions split ("l'adresse" -> "l", "adresse")
+fn words(sentence: &str) -> Vec<(String, bool)> {
+    sentence
+        .split(|c: char| !(c.is_alphanumeric() || c == '-'))
+        .filter(|word| !word.is_empty())
+        .map(|word| (word.to_lowercase(), word.chars().next().is_some_and(char::is_uppercase)))
+        .collect()
+}
+
+fn is_noun(word: &str) -> bool {
+    word.chars().count() >= 3 && word.chars().all(|c| c.is_alphabetic() || c == '-') && !STOPWORDS.contains(&word) && !DETERMINERS.contains(&word)
+}
+
+/// Crude singular: "commandes" -> "commande", "orders" -> "order"
+fn singular(word: &str) -> String {
+    match word.strip_suffix('s') {
+        Some(stem) if stem.chars().count() >= 3 && !stem.ends_with('s') => stem.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+/// Comparison key of a name in any case style: "date de livraison", "dateLivraison",
+/// "delivery_date" and "DeliveryDate" reduce to joined singular lowercase words
+pub fn name_key(name: &str) -> String {
+    let mut spaced = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lower {
+            spaced.push(' ');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        spaced.push(c);
+    }
+    words(&spaced.replace('_', " "))
+        .into_iter()
+        .map(|(word, _)| word)
+        .filter(|word| !matches!(word.as_str(), "de" | "du" | "des" | "d" | "of"))
+        .map(|word| singular(&word))
+        .collect()
+}
+
+/// Noun phrase starting at `start`: a noun and, in French, its "de <noun>" complement
+/// ("date de livraison"), in English up to two nouns ("delivery date")
+fn noun_phrase(words: &[(String, bool)], start: usize) -> Option<(String, usize)> {
+    let head = &words.get(start)?.0;
+    if !is_noun(head) {
+        return None;
+    }
+    match (words.get(start + 1), words.get(start + 2)) {
+        (Some((de, _)), Some((complement, _))) if (de == "de" || de == "d") && is_noun(complement) => {
+            Some((format!("{} de {}", head, complement), start + 3))
+        }
+        (Some((next, _)), _) if is_noun(next) && next.is_ascii() && head.is_ascii() && !HAS_VERBS.contains(&next.as_str()) => {
+            Some((format!("{} {}", head, next), start + 2))
+        }
+        _ => Some((head.clone(), start + 1)),
+    }
+}
+
+#[derive(Default)]
+struct Counts {
+    entities: HashMap<String, usize>,
+    owners: HashMap<String, usize>,
+    attributes: HashMap<(String, Option<String>), usize>,
+}
+
+impl Counts {
+    fn attribute(&mut self, attribute: String, owner: Option<String>) {
+        if let Some(owner) = &owner {
+            *self.owners.entry(owner.clone()).or_default() += 1;
+        }
+        *self.attributes.entry((attribute, owner)).or_default() += 1;
+    }
+}
+
+fn scan_sentence(sentence: &str, counts: &mut Counts) {
+    let words = words(sentence);
+    for index in 0..words.len() {
+        let (word, capitalized) = &words[index];
+        let after_determiner = index > 0 && DETERMINERS.contains(&words[index - 1].0.as_str());
+        // A noun after a determiner, or a capitalized term inside the sentence
+        if (after_determiner || (*capitalized && index > 0)) && is_noun(word) {
+            *counts.entities.entry(singular(word)).or_default() += 1;
+        }
+
+        // "<owner> a une <attribute>", "each order has a delivery date"
+        if HAS_VERBS.contains(&word.as_str()) && index > 0 && is_noun(&words[index - 1].0) {
+            let owner = singular(&words[index - 1].0);
+            let mut start = index + 1;
+            if words.get(start).is_some_and(|(next, _)| DETERMINERS.contains(&next.as_str())) {
+                start += 1;
+            }
+            if let Some((attribute, _)) = noun_phrase(&words, start) {
+                counts.attribute(attribute, Some(owner));
+            }
+        }
+
+        // "la date de livraison de la commande", "the delivery date of the order"
+        if after_determiner && matches!(words[index - 1].0.as_str(), "le" | "la" | "l" | "the") {
+            if let Some((attribute, end)) = noun_phrase(&words, index) {
+                let mut owner_start = end;
+                if words.get(owner_start).is_some_and(|(next, _)| matches!(next.as_str(), "de" | "d" | "du" | "des" | "of")) {
+                    owner_start += 1;
+                    if words.get(owner_start).is_some_and(|(next, _)| OWNER_DETERMINERS.contains(&next.as_str())) {
+                        owner_start += 1;
+                    }
+                    if let Some((owner, _)) = words.get(owner_start).filter(|(owner, _)| is_noun(owner)) {
+                        if !attribute.contains(" de ") {
+                            counts.attribute(attribute, Some(singular(owner)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn ranked(counts: HashMap<String, usize>, limit: usize) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = counts.into_iter().map(|(name, mentions)| Candidate { name, mentions, entity: None }).collect();
+    candidates.sort_by(|a, b| b.mentions.cmp(&a.mentions).then_with(|| a.name.cmp(&b.name)));
+    candidates.truncate(limit);
+    candidates
+}
+
+/// Rule-based extraction (French and English): nouns after determiners and capitalized
+/// terms mentioned at least twice, or owning an attribute, are candidate entities;
+/// "X a un Y", "X has a Y", "le Y de X", "the Y of X" give candidate attributes.
+pub fn extract(transcript: &str) -> Candidates {
+    let mut counts = Counts::default();
+    for sentence in transcript.split(['.', '!', '?', ';', '\n']) {
+        scan_sentence(sentence, &mut counts);
+    }
+
+    let attribute_keys: Vec<String> = counts.attributes.keys().map(|(attribute, _)| name_key(attribute)).collect();
+    let mut entities = counts.entities;
+    for (owner, count) in counts.owners {
+        *entities.entry(owner).or_default() += count;
+    }
+    // Attribute names are not entities unless they own something themselves
+    entities.retain(|name, mentions| *mentions >= 2 && !attribute_keys.contains(&name_key(name)));
+
+    let mut attributes: Vec<Candidate> = counts
+        .attributes
+        .into_iter()
+        .map(|((name, entity), mentions)| Candidate { name, mentions, entity })
+        .collect();
+    attributes.sort_by(|a, b| b.mentions.cmp(&a.mentions).then_with(|| a.name.cmp(&b.name)));
+    attributes.truncate(MAX_ATTRIBUTES);
+
+    Candidates { entities: ranked(entities, MAX_ENTITIES), attributes }
+}
+
+impl Candidates {
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty() && self.attributes.is_empty()
+    }
+
+    /// Add entity names proposed by the local model, when the rules did not find them
+    pub fn merge_entities(&mut self, names: Vec<String>) {
+        for name in names {
+            let key = name_key(&name);
+            if !key.is_empty() && !self.entities.iter().any(|candidate| name_key(&candidate.name) == key) {
+                self.entities.push(Candidate { name, mentions: 0, entity: None });
+            }
+        }
+    }
+
+    /// Candidates as a prompt section
+    pub fn prompt_note(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let entities = self.entities.iter().map(|candidate| candidate.name.as_str()).collect::<Vec<_>>().join(", ");
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|candidate| match &candidate.entity {
+                Some(entity) => format!("{} ({})", candidate.name, entity),
+                None => candidate.name.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!(
+            "\nCANDIDATS (extraits automatiquement de la transcription, à confirmer ou écarter):\nEntités: {}\nAttributs: {}\nN'ajoute pas d'élément dont la transcription ne parle pas.\n",
+            entities, attributes
+        ))
+    }
+}
+
+/// Compare the candidates with the entities and attributes of the generated model
+pub fn compare(candidates: &Candidates, model: &Value) -> CandidateReport {
+    let entity_keys: Vec<String> = candidates.entities.iter().map(|candidate| name_key(&candidate.name)).collect();
+    let attribute_keys: Vec<String> = candidates.attributes.iter().map(|candidate| name_key(&candidate.name)).collect();
+    let mut model_entity_keys = Vec::new();
+    let mut unexpected = Vec::new();
+
+    for entity in model["entities"].as_array().into_iter().flatten() {
+        let id = entity["id"].as_str().unwrap_or_default();
+        let keys: Vec<String> = [entity["id"].as_str(), entity["name"].as_str()].into_iter().flatten().map(name_key).collect();
+        if !keys.iter().any(|key| entity_keys.contains(key) || attribute_keys.contains(key)) {
+            unexpected.push(id.to_string());
+        }
+        model_entity_keys.extend(keys);
+        for attribute in entity["attributes"].as_array().into_iter().flatten() {
+            let name = attribute["name"].as_str().unwrap_or_default();
+            let key = name_key(name);
+            // Identifiers are added by the model, not said in the interview
+            if key == "id" || name.ends_with("Id") || name.ends_with("_id") {
+                continue;
+            }
+            if !attribute_keys.contains(&key) && !entity_keys.contains(&key) {
+                unexpected.push(format!("{}.{}", id, name));
+            }
+        }
+    }
+
+    let (confirmed, missed): (Vec<&Candidate>, Vec<&Candidate>) =
+        candidates.entities.iter().partition(|candidate| model_entity_keys.contains(&name_key(&candidate.name)));
+    CandidateReport {
+        candidates: candidates.clone(),
+        confirmed: confirmed.into_iter().map(|candidate| candidate.name.clone()).collect(),
+        missed: missed.into_iter().filter(|candidate| candidate.mentions >= 2).map(|candidate| candidate.name.clone()).collect(),
+        unexpected,
+    }
+}
+
+/// Entity names proposed by a small local model (NER_MODEL, served by the Ollama of
+/// OLLAMA_BASE_URL). None when NER_MODEL is unset.
+pub async fn model_candidates(transcript: &str) -> Result<Option<Vec<String>>> {
+    let Ok(model) = env::var("NER_MODEL") else {
+        return Ok(None);
+    };
+    let base_url = env::var("OLLAMA_BASE_URL").unwrap_or_else(|_| "http://localhost:11434".to_string());
+    provider_policy::enforce("ollama", &LlmProvider::Ollama { base_url: base_url.clone() })?;
+
+    let prompt = format!(
+        "Liste les concepts métier (noms communs désignant des objets du domaine) de cette transcription. \
+         Réponds uniquement en JSON: {{\"entities\": [\"nom\", ...]}}\n\nTranscription:\n{}",
+        transcript
+    );
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/generate", base_url))
+        .json(&json!({"model": model, "prompt": prompt, "stream": false, "format": "json", "options": {"temperature": 0}}))
+        .send()
+        .await
+        .context("Failed to send request to Ollama")?;
+    if !response.status().is_success() {
+        anyhow::bail!("Ollama API error: {}", response.status());
+    }
+    let body: Value = response.json().await.context("Failed to parse Ollama response")?;
+    let answer: Value = serde_json::from_str(body["response"].as_str().unwrap_or_default()).context("NER model did not answer JSON")?;
+    Ok(Some(
+        answer["entities"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|name| name.as_str())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_and_compare() {
+        let transcript = "Le client passe une commande. Chaque commande a une date de livraison. \
+                          La commande contient des lignes, et le client a une adresse. \
+                          L'adresse du client sert à la facture.";
+        let candidates = extract(transcript);
+        let entities: Vec<&str> = candidates.entities.iter().map(|candidate| candidate.name.as_str()).collect();
+        assert_eq!(entities[..2], ["client", "commande"]);
+        assert!(!entities.contains(&"adresse"));
+        assert!(candidates.attributes.contains(&Candidate { name: "date de livraison".to_string(), mentions: 1, entity: Some("commande".to_string()) }));
+        assert!(candidates.attributes.iter().any(|candidate| candidate.name == "adresse" && candidate.mentions == 2));
+        assert!(candidates.prompt_note().unwrap().contains("date de livraison (commande)"));
+
+        let model = json!({"entities": [
+            {"id": "Commande", "name": "Commande", "attributes": [{"name": "id"}, {"name": "dateLivraison"}, {"name": "montantTotal"}]},
+            {"id": "Produit", "name": "Produit", "attributes": [{"name": "nom"}]}
+        ]});
+        let report = compare(&candidates, &model);
+        assert_eq!(report.confirmed, vec!["commande"]);
+        assert_eq!(report.missed, vec!["client"]);
+        assert_eq!(report.unexpected, vec!["Commande.montantTotal", "Produit", "Produit.nom"]);
+        assert_eq!(name_key("delivery_date"), name_key("DeliveryDates"));
+    }
+}
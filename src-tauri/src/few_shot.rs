@@ -0,0 +1,208 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::safe_write;
+
+/// Number of dimensions of the hashed bag-of-words embedding
+const EMBEDDING_DIMS: usize = 512;
+
+/// Examples below this cosine similarity are never injected
+const MIN_SIMILARITY: f32 = 0.05;
+
+const BUILTIN_EXAMPLES: &str = include_str!("../templates/few_shots.json");
+
+const STORE_FILE: &str = "few_shot_examples.json";
+
+/// A transcript → DomainModel pair injected into the normalization prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FewShotExample {
+    pub id: String,
+    pub title: String,
+    pub transcript: String,
+    pub model: Value,
+    /// Curated examples shipped with the app cannot be removed
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+/// Curated examples plus user examples stored in the app data directory
+pub struct FewShotStore {
+    path: PathBuf,
+    user_examples: Vec<FewShotExample>,
+}
+
+impl FewShotStore {
+    /// Load the store from `dir` (missing file means no user examples yet)
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(STORE_FILE);
+        let user_examples = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {:?}", path))?;
+            serde_json::from_str(&content).context("Failed to parse few-shot examples")?
+        } else {
+            Vec::new()
+        };
+        Ok(Self { path, user_examples })
+    }
+
+    /// Curated and user examples
+    pub fn all(&self) -> Vec<FewShotExample> {
+        let mut examples = builtin_examples();
+        examples.extend(self.user_examples.iter().cloned());
+        examples
+    }
+
+    pub fn add(&mut self, title: &str, transcript: &str, model: Value) -> Result<FewShotExample> {
+        let example = FewShotExample {
+            id: format!("user-{}", chrono::Utc::now().timestamp_millis()),
+            title: title.to_string(),
+            transcript: transcript.to_string(),
+            model,
+            builtin: false,
+        };
+        self.user_examples.push(example.clone());
+        self.save()?;
+        Ok(example)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<()> {
+        let before = self.user_examples.len();
+        self.user_examples.retain(|e| e.id != id);
+        if self.user_examples.len() == before {
+            anyhow::bail!("No user example with id '{}'", id);
+        }
+        self.save()
+    }
+
+    /// The `k` examples most similar to `transcript`
+    pub fn select(&self, transcript: &str, k: usize) -> Vec<FewShotExample> {
+        select_examples(&self.all(), transcript, k)
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&self.user_examples)?;
+        safe_write::write(&self.path, content)
+    }
+}
+
+fn builtin_examples() -> Vec<FewShotExample> {
+    let mut examples: Vec<FewShotExample> =
+        serde_json::from_str(BUILTIN_EXAMPLES).expect("built-in few-shot examples are valid JSON");
+    for example in &mut examples {
+        example.builtin = true;
+    }
+    examples
+}
+
+/// Hashed bag-of-words embedding (lowercased, accents kept, words of 3+ chars), L2-normalized.
+///
+/// Cheap and local: good enough to tell a booking transcript from a billing one
+/// without calling an embedding model.
+pub fn embed(text: &str) -> Vec<f32> {
+    let mut vector = vec![0f32; EMBEDDING_DIMS];
+    for word in text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+    {
+        // FNV-1a, stable across runs unlike the std hasher
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in word.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        vector[(hash % EMBEDDING_DIMS as u64) as usize] += 1.0;
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Rank `examples` by similarity to `transcript` and keep the best `k`
+pub fn select_examples(examples: &[FewShotExample], transcript: &str, k: usize) -> Vec<FewShotExample> {
+    let query = embed(transcript);
+    let mut scored: Vec<(f32, &FewShotExample)> = examples
+        .iter()
+        .map(|e| (cosine(&query, &embed(&e.transcript)), e))
+        .filter(|(score, _)| *score >= MIN_SIMILARITY)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(k).map(|(_, e)| e.clone()).collect()
+}
+
+/// Heading of the few-shot section of the system prompt
+pub const EXAMPLES_HEADER: &str = "\nEXEMPLES (transcription → JSON attendu):\n";
+
+/// Render one example (1-based `number`) for the prompt
+pub fn format_example(number: usize, example: &FewShotExample) -> String {
+    format!(
+        "\nExemple {}:\nTranscription: {}\nJSON: {}\n",
+        number, example.transcript, example.model
+    )
+}
+
+/// Render examples as a prompt section to append to the system prompt
+pub fn format_examples(examples: &[FewShotExample]) -> String {
+    if examples.is_empty() {
+        return String::new();
+    }
+    let mut prompt = String::from(EXAMPLES_HEADER);
+    for (i, example) in examples.iter().enumerate() {
+        prompt.push_str(&format_example(i + 1, example));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_examples_load() {
+        let examples = builtin_examples();
+        assert!(examples.len() >= 2);
+        assert!(examples.iter().all(|e| e.builtin && e.model["entities"].is_array()));
+    }
+
+    #[test]
+    fn test_select_examples_by_similarity() {
+        let examples = builtin_examples();
+        let selected = select_examples(
+            &examples,
+            "Les adhérents empruntent des livres, chaque exemplaire a un code",
+            1,
+        );
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].id, "builtin-library");
+
+        assert!(select_examples(&examples, "zzz", 2).is_empty());
+    }
+
+    #[test]
+    fn test_store_add_and_remove() {
+        let dir = std::env::temp_dir().join(format!("few_shot_test_{}", std::process::id()));
+        let mut store = FewShotStore::load(&dir).unwrap();
+        let example = store
+            .add("Flotte", "Des véhicules affectés à des conducteurs", serde_json::json!({"entities": []}))
+            .unwrap();
+
+        let reloaded = FewShotStore::load(&dir).unwrap();
+        assert!(reloaded.all().iter().any(|e| e.id == example.id && !e.builtin));
+
+        store.remove(&example.id).unwrap();
+        assert!(store.remove("builtin-library").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::transcript_normalize;
+
+const GLOSSARY_SECTION: &str = "## Langage Ubiquiste";
+/// Language of the terms when neither the settings nor the definitions tell it
+const DEFAULT_LANGUAGE: &str = "fr";
+
+/// Glossary file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlossaryFormat {
+    Csv,
+    /// TermBase eXchange (ISO 30042, TBX-Basic dialect), read by translation memories
+    Tbx,
+}
+
+impl std::str::FromStr for GlossaryFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "tbx" => Ok(Self::Tbx),
+            other => anyhow::bail!("Unknown glossary format '{}', expected csv or tbx", other),
+        }
+    }
+}
+
+impl GlossaryFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Tbx => "tbx",
+        }
+    }
+}
+
+/// One term of the ubiquitous language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryEntry {
+    pub term: String,
+    #[serde(default)]
+    pub definition: Option<String>,
+    #[serde(default)]
+    pub example: Option<String>,
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+}
+
+fn cell(text: &str) -> Option<String> {
+    let text = text.trim().trim_matches(|c| c == '*' || c == '`').trim();
+    (!text.is_empty() && !text.starts_with('[') && text != "-").then(|| text.replace("\\|", "|"))
+}
+
+/// Terms of the "Langage Ubiquiste" table (`| Terme | Définition métier | Exemple |`), from
+/// the full canvas or from the section alone
+pub fn parse_table(markdown: &str) -> Vec<GlossaryEntry> {
+    let section = match markdown.find(GLOSSARY_SECTION) {
+        Some(start) => {
+            let rest = &markdown[start + GLOSSARY_SECTION.len()..];
+            &rest[..rest.find("\n## ").unwrap_or(rest.len())]
+        }
+        None => markdown,
+    };
+    section
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('|'))
+        .filter_map(|line| {
+            // Escaped pipes stay inside their cell
+            let cells: Vec<String> = line.trim_matches('|').replace("\\|", "\u{0}").split('|').map(|c| c.replace('\u{0}', "\\|")).collect();
+            let term = cell(cells.first()?)?;
+            let header = term.to_lowercase();
+            if term.chars().all(|c| c == '-' || c == ':') || header == "terme" || header == "term" {
+                return None;
+            }
+            Some(GlossaryEntry {
+                term,
+                definition: cells.get(1).and_then(|c| cell(c)),
+                example: cells.get(2).and_then(|c| cell(c)),
+                synonyms: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Canvas terms completed by the project glossary: same term (case-insensitive) merged,
+/// the canvas wording kept, glossary-only terms appended
+pub fn merge(mut entries: Vec<GlossaryEntry>, glossary: Vec<GlossaryEntry>) -> Vec<GlossaryEntry> {
+    for term in glossary {
+        match entries.iter_mut().find(|e| e.term.to_lowercase() == term.term.to_lowercase()) {
+            Some(entry) => {
+                entry.definition = entry.definition.take().or(term.definition);
+                entry.example = entry.example.take().or(term.example);
+                for synonym in term.synonyms {
+                    if !entry.synonyms.iter().any(|s| s.eq_ignore_ascii_case(&synonym)) {
+                        entry.synonyms.push(synonym);
+                    }
+                }
+            }
+            None => entries.push(term),
+        }
+    }
+    entries
+}
+
+/// Language of the terms: the transcription language of the settings, or the one of the
+/// definitions when the settings say "auto"
+pub fn glossary_language(setting: &str, entries: &[GlossaryEntry]) -> String {
+    if setting != "auto" {
+        return setting.to_string();
+    }
+    let text = entries.iter().filter_map(|e| e.definition.as_deref()).collect::<Vec<_>>().join("\n");
+    transcript_normalize::guess_language(&text).unwrap_or(DEFAULT_LANGUAGE).to_string()
+}
+
+pub fn to_csv(entries: &[GlossaryEntry]) -> String {
+    let field = |value: &str| {
+        if value.contains([',', '"', '\n']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    };
+    let mut csv = String::from("term,definition,example,synonyms\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            field(&entry.term),
+            field(entry.definition.as_deref().unwrap_or_default()),
+            field(entry.example.as_deref().unwrap_or_default()),
+            field(&entry.synonyms.join("; "))
+        ));
+    }
+    csv
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// TBX-Basic document: one concept per term, the term preferred and its synonyms admitted,
+/// all in `language` (ISO 639-1)
+pub fn to_tbx(entries: &[GlossaryEntry], title: &str, language: &str) -> String {
+    let language = xml_escape(language);
+    let mut tbx = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <tbx style=\"dca\" type=\"TBX-Basic\" xml:lang=\"{language}\" xmlns=\"urn:iso:std:iso:30042:ed-2\">\n\
+         \x20 <tbxHeader>\n\
+         \x20   <fileDesc>\n\
+         \x20     <titleStmt><title>{}</title></titleStmt>\n\
+         \x20     <sourceDesc><p>Langage ubiquiste du canvas Domain Model</p></sourceDesc>\n\
+         \x20   </fileDesc>\n\
+         \x20 </tbxHeader>\n\
+         \x20 <text>\n\
+         \x20   <body>\n",
+        xml_escape(title)
+    );
+    for (index, entry) in entries.iter().enumerate() {
+        tbx.push_str(&format!("      <conceptEntry id=\"c{}\">\n        <langSec xml:lang=\"{}\">\n", index + 1, language));
+        if let Some(definition) = &entry.definition {
+            tbx.push_str(&format!("          <descrip type=\"definition\">{}</descrip>\n", xml_escape(definition)));
+        }
+        tbx.push_str(&format!("          <termSec>\n            <term>{}</term>\n", xml_escape(&entry.term)));
+        tbx.push_str("            <termNote type=\"administrativeStatus\">preferredTerm-admn-sts</termNote>\n");
+        if let Some(example) = &entry.example {
+            tbx.push_str(&format!("            <descrip type=\"context\">{}</descrip>\n", xml_escape(example)));
+        }
+        tbx.push_str("          </termSec>\n");
+        for synonym in &entry.synonyms {
+            tbx.push_str(&format!(
+                "          <termSec>\n            <term>{}</term>\n            <termNote type=\"administrativeStatus\">admittedTerm-admn-sts</termNote>\n          </termSec>\n",
+                xml_escape(synonym)
+            ));
+        }
+        tbx.push_str("        </langSec>\n      </conceptEntry>\n");
+    }
+    tbx.push_str("    </body>\n  </text>\n</tbx>\n");
+    tbx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glossary_exports() {
+        let canvas = "## Acteurs & Use Cases\n\n* **Acteurs :** Client\n\n## Langage Ubiquiste\n\n\
+            | Terme | Définition métier | Exemple |\n| ----- | ----------------- | ------- |\n\
+            | **Commande** | Demande d'achat validée, avec ses lignes | Commande #42 de 3 articles |\n\
+            | Remise | Réduction \\| ristourne accordée au client, \"fidélité\" | -10 % |\n\
+            | [terme] | [définition] | [exemple concret] |\n\n## Domain Events & Règles\n\n| Événement | Quand |\n";
+        let glossary = vec![
+            GlossaryEntry { term: "commande".to_string(), definition: None, example: None, synonyms: vec!["Order".to_string()] },
+            GlossaryEntry { term: "Client".to_string(), definition: Some("Personne qui commande".to_string()), example: None, synonyms: Vec::new() },
+        ];
+        let entries = merge(parse_table(canvas), glossary);
+        let terms: Vec<&str> = entries.iter().map(|e| e.term.as_str()).collect();
+        assert_eq!(terms, vec!["Commande", "Remise", "Client"]);
+        assert_eq!(entries[0].synonyms, vec!["Order"]);
+        assert_eq!(entries[1].example.as_deref(), Some("-10 %"));
+
+        let csv = to_csv(&entries);
+        assert!(csv.starts_with("term,definition,example,synonyms\nCommande,\"Demande d'achat validée, avec ses lignes\",Commande #42 de 3 articles,Order\n"));
+        assert!(csv.contains("Remise,\"Réduction | ristourne accordée au client, \"\"fidélité\"\"\",-10 %,\n"));
+
+        let tbx = to_tbx(&entries, "Glossaire — Boutique", "fr");
+        assert_eq!(tbx.matches("<conceptEntry ").count(), 3);
+        assert!(tbx.contains("<langSec xml:lang=\"fr\">\n          <descrip type=\"definition\">Demande d'achat validée, avec ses lignes</descrip>"));
+        assert!(tbx.contains("<term>Order</term>\n            <termNote type=\"administrativeStatus\">admittedTerm-admn-sts</termNote>"));
+        assert!(tbx.contains("&quot;fidélité&quot;"));
+        assert_eq!("TBX".parse::<GlossaryFormat>().unwrap().extension(), "tbx");
+    }
+}
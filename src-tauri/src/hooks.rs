@@ -0,0 +1,255 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::safe_write;
+
+/// Hook output kept in the run report (the rest is dropped)
+const MAX_OUTPUT_BYTES: usize = 4096;
+
+/// Where a hook runs in the pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookPoint {
+    /// A recording session was transcribed
+    PostTranscription,
+    /// A domain model was generated
+    PostModel,
+    /// Markdown and Mermaid were emitted
+    PostEmit,
+}
+
+impl HookPoint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookPoint::PostTranscription => "post_transcription",
+            HookPoint::PostModel => "post_model",
+            HookPoint::PostEmit => "post_emit",
+        }
+    }
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// User script run at a hook point. It receives the payload as JSON on stdin and
+/// the hook point in the `DOMAIN_MODEL_HOOK` environment variable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hook {
+    pub point: HookPoint,
+    /// Program to run, looked up in PATH (e.g. "python3", "/usr/local/bin/publish-wiki")
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// Outcome of one hook, reported with the pipeline output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookRun {
+    pub point: HookPoint,
+    pub command: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl HookRun {
+    /// One-line summary of a failed run, for pipeline warnings
+    pub fn failure_message(&self) -> Option<String> {
+        if self.success {
+            return None;
+        }
+        let reason = if self.timed_out {
+            "timed out".to_string()
+        } else if let Some(code) = self.exit_code {
+            format!("exited with code {}", code)
+        } else {
+            self.stderr.lines().next().unwrap_or("failed").to_string()
+        };
+        Some(format!("Hook {} `{}` {}", self.point.as_str(), self.command, reason))
+    }
+}
+
+fn read_limited<R: Read + Send + 'static>(reader: Option<R>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut reader) = reader {
+            let _ = reader.read_to_end(&mut output);
+        }
+        output.truncate(MAX_OUTPUT_BYTES);
+        String::from_utf8_lossy(&output).into_owned()
+    })
+}
+
+/// Run one hook with `payload` on stdin, killing it after its timeout
+pub fn run_hook(hook: &Hook, payload: &Value) -> HookRun {
+    let started = Instant::now();
+    let failed = |stderr: String| HookRun {
+        point: hook.point,
+        command: hook.command.clone(),
+        success: false,
+        exit_code: None,
+        timed_out: false,
+        duration_ms: started.elapsed().as_millis() as u64,
+        stdout: String::new(),
+        stderr,
+    };
+
+    let mut child = match Command::new(&hook.command)
+        .args(&hook.args)
+        .env("DOMAIN_MODEL_HOOK", hook.point.as_str())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return failed(format!("Failed to start hook: {}", e)),
+    };
+
+    // Written from a thread: a script that does not read stdin must not block us
+    let input = serde_json::to_vec(payload).unwrap_or_default();
+    if let Some(mut stdin) = child.stdin.take() {
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+    }
+    let stdout = read_limited(child.stdout.take());
+    let stderr = read_limited(child.stderr.take());
+
+    let timeout = Duration::from_secs(hook.timeout_secs);
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if started.elapsed() >= timeout => {
+                timed_out = true;
+                let _ = child.kill();
+                break child.wait().ok();
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+            Err(_) => break None,
+        }
+    };
+
+    // After a kill, grandchildren may still hold the pipes open: their output is abandoned
+    let (stdout, stderr) = if timed_out {
+        (String::new(), format!("Killed after {}s", hook.timeout_secs))
+    } else {
+        (stdout.join().unwrap_or_default(), stderr.join().unwrap_or_default())
+    };
+
+    HookRun {
+        point: hook.point,
+        command: hook.command.clone(),
+        success: !timed_out && status.is_some_and(|s| s.success()),
+        exit_code: if timed_out { None } else { status.and_then(|s| s.code()) },
+        timed_out,
+        duration_ms: started.elapsed().as_millis() as u64,
+        stdout,
+        stderr,
+    }
+}
+
+/// Run the enabled hooks of `point`, in order. A failing hook is reported, never fatal.
+pub fn run_hooks(hooks: &[Hook], point: HookPoint, payload: &Value) -> Vec<HookRun> {
+    let mut payload = payload.clone();
+    payload["hook"] = json!(point.as_str());
+    hooks
+        .iter()
+        .filter(|hook| hook.enabled && hook.point == point)
+        .map(|hook| {
+            log::info!("[Hooks] Running {} hook: {} {:?}", point.as_str(), hook.command, hook.args);
+            let run = run_hook(hook, &payload);
+            if let Some(message) = run.failure_message() {
+                log::warn!("[Hooks] {}: {}", message, run.stderr.trim());
+            }
+            run
+        })
+        .collect()
+}
+
+/// Where in-memory artifacts are written for hooks, under the app data
+pub fn staging_root(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("hook-staging")
+}
+
+/// Write in-memory artifacts to a fresh directory so hooks get file paths;
+/// returns the directory, to remove once the hooks ran, and `{name: path}`
+pub fn stage_artifacts(root: &Path, files: &[(&str, &str)]) -> Result<(PathBuf, Value)> {
+    let dir: PathBuf = root.join(chrono::Local::now().format("%Y%m%d_%H%M%S_%3f").to_string());
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let mut paths = serde_json::Map::new();
+    for (name, content) in files {
+        let path = dir.join(name);
+        safe_write::write(&path, content)?;
+        paths.insert(name.to_string(), json!(path.display().to_string()));
+    }
+    Ok((dir, Value::Object(paths)))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn shell(point: HookPoint, script: &str, timeout_secs: u64) -> Hook {
+        Hook {
+            point,
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            timeout_secs,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_hooks_receive_payload_and_report_failures() {
+        let hooks = vec![
+            shell(HookPoint::PostModel, "echo \"$DOMAIN_MODEL_HOOK\"; cat", 10),
+            shell(HookPoint::PostEmit, "echo never", 10),
+            shell(HookPoint::PostModel, "echo lint failed >&2; exit 3", 10),
+            Hook { enabled: false, ..shell(HookPoint::PostModel, "exit 1", 10) },
+        ];
+        let runs = run_hooks(&hooks, HookPoint::PostModel, &json!({"model": {"entities": []}}));
+        assert_eq!(runs.len(), 2);
+
+        assert!(runs[0].success);
+        let mut lines = runs[0].stdout.lines();
+        assert_eq!(lines.next(), Some("post_model"));
+        let received: Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(received["hook"], "post_model");
+        assert_eq!(received["model"]["entities"], json!([]));
+
+        assert!(!runs[1].success);
+        assert_eq!(runs[1].exit_code, Some(3));
+        assert_eq!(runs[1].stderr.trim(), "lint failed");
+        assert_eq!(runs[1].failure_message().unwrap(), "Hook post_model `sh` exited with code 3");
+    }
+
+    #[test]
+    fn test_hook_timeout_and_missing_program() {
+        let run = run_hook(&shell(HookPoint::PostEmit, "sleep 5", 0), &json!({}));
+        assert!(run.timed_out && !run.success);
+        assert!(run.duration_ms < 2000);
+
+        let missing = Hook { command: "definitely-not-a-hook-program".to_string(), ..shell(HookPoint::PostEmit, "", 1) };
+        let run = run_hook(&missing, &json!({}));
+        assert!(!run.success && run.stderr.starts_with("Failed to start hook"));
+    }
+}
@@ -1,7 +1,12 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::llm_router::LlmRouter;
+use crate::answer_quality::{self, AnswerScore};
+use crate::canvas_consistency::{self, ConsistencyFinding};
+use crate::llm_router::{GenerationRecord, LlmRouter};
+use crate::kpi_catalog::{self, KpiRecord};
+use crate::prompt_budget::{PromptBudget, PromptSection, Trim};
+use crate::risk_register::{self, RegisterEntry, RiskRegister};
 
 /// User's answer to an interview question
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,12 +31,77 @@ pub struct SectionCanvasResult {
     pub section_id: u32,
     pub section_title: String,
     pub canvas_content: String, // Markdown content for this section
+    /// Provider and sampling parameters used to generate the content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<GenerationRecord>,
+}
+
+/// Sections already generated in this interview, passed to the next `process_section`
+/// calls so the canvas stays consistent from one section to the next
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterviewContext {
+    /// In interview order; when over the token budget the oldest are cut first
+    pub previous_sections: Vec<SectionSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionSummary {
+    pub section_title: String,
+    /// Generated canvas content of the section
+    pub content: String,
+}
+
+impl From<&SectionCanvasResult> for SectionSummary {
+    fn from(result: &SectionCanvasResult) -> Self {
+        Self { section_title: result.section_title.clone(), content: result.canvas_content.clone() }
+    }
+}
+
+const CONTEXT_HEADER: &str = "Sections du canvas déjà rédigées. Reste cohérent avec elles: réutilise les mêmes termes et ne les contredis pas. Si une réponse les contredit vraiment, signale-le explicitement au lieu de lisser.\n\n";
+
+/// User prompt of a section: the previous sections that fit in the budget (the most
+/// recent first, each cut line by line from its end), then the answers
+fn section_prompt(
+    system_prompt: &str,
+    qa_text: &str,
+    context: Option<&InterviewContext>,
+    budget: &PromptBudget,
+) -> (String, Vec<String>) {
+    let previous = context.map(|c| c.previous_sections.as_slice()).unwrap_or_default();
+    let mut sections = vec![PromptSection::new("system prompt", system_prompt.to_string(), 2, Trim::Never)];
+    sections.extend(previous.iter().rev().map(|summary| {
+        PromptSection::new(
+            &format!("previous:{}", summary.section_title),
+            format!("### {}\n{}\n", summary.section_title, summary.content.trim()),
+            1,
+            Trim::Lines,
+        )
+    }));
+    sections.push(PromptSection::new("answers", qa_text.to_string(), 2, Trim::Never));
+
+    let budgeted = budget.fit(sections);
+    let kept: Vec<&PromptSection> = budgeted.sections.iter().filter(|s| s.name.starts_with("previous:")).collect();
+    let mut prompt = String::new();
+    if !kept.is_empty() {
+        prompt.push_str(CONTEXT_HEADER);
+        // Back to interview order
+        for section in kept.iter().rev() {
+            prompt.push_str(&section.text);
+            prompt.push('\n');
+        }
+        prompt.push_str("---\n\n");
+    }
+    prompt.push_str(qa_text);
+    (prompt, budgeted.warnings)
 }
 
 /// Complete canvas content
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FullCanvasResult {
     pub markdown: String,
+    /// Inconsistencies between sections, attached to their section ids
+    #[serde(default)]
+    pub findings: Vec<ConsistencyFinding>,
 }
 
 /// Interview processor that uses LLM to transform answers into canvas content
@@ -45,8 +115,13 @@ impl InterviewProcessor {
         Ok(Self { llm_router })
     }
 
-    /// Process answers for a specific section and generate canvas content
-    pub async fn process_section(&self, section: InterviewSection) -> Result<SectionCanvasResult> {
+    /// Process answers for a specific section and generate canvas content, consistent with
+    /// the sections of `context` already generated
+    pub async fn process_section(
+        &self,
+        section: InterviewSection,
+        context: Option<&InterviewContext>,
+    ) -> Result<SectionCanvasResult> {
         log::info!("[Interview] Starting to process section: {} (ID: {})", section.section_title, section.section_id);
         log::info!("[Interview] Section has {} answers", section.answers.len());
         
@@ -57,13 +132,18 @@ impl InterviewProcessor {
         for answer in &section.answers {
             qa_text.push_str(&format!("Q: {}\nR: {}\n\n", answer.question, answer.answer));
         }
+        let (user_prompt, warnings) = section_prompt(&system_prompt, &qa_text, context, &PromptBudget::from_env());
+        for warning in &warnings {
+            log::warn!("[Interview] {}", warning);
+        }
 
         log::info!("[Interview] Sending request to LLM for section: {}", section.section_title);
+        let generation = self.llm_router.generation_record();
         
         // Ask LLM to transform answers into canvas markdown format with timeout
         let canvas_content = tokio::time::timeout(
             std::time::Duration::from_secs(120), // 2 minutes timeout
-            self.llm_router.generate_text(&system_prompt, &qa_text)
+            self.llm_router.generate_text(&system_prompt, &user_prompt)
         )
         .await
         .map_err(|_| anyhow::anyhow!("LLM request timed out after 120 seconds"))?
@@ -76,24 +156,129 @@ impl InterviewProcessor {
             section_id: section.section_id,
             section_title: section.section_title,
             canvas_content,
+            generation: Some(generation),
         })
     }
 
-    /// Generate the complete canvas from all processed sections
-    pub async fn generate_full_canvas(&self, sections: Vec<SectionCanvasResult>) -> Result<FullCanvasResult> {
+    /// Rate the completeness and specificity of an answer before its section is processed
+    /// (answers of a word or two are rated without calling the LLM)
+    pub async fn score_answer(&self, question: &str, answer: &str) -> Result<AnswerScore> {
+        if let Some(score) = answer_quality::quick_check(answer) {
+            return Ok(score);
+        }
+
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(60),
+            self.llm_router.generate_json(
+                answer_quality::SCORING_SYSTEM_PROMPT,
+                &answer_quality::scoring_prompt(question, answer),
+            ),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("LLM request timed out after 60 seconds"))?
+        .context("Failed to score answer")?;
+
+        let score = answer_quality::parse_score(&response)?;
+        log::info!(
+            "[Interview] Answer scored {}/{} ({:?})",
+            score.completeness,
+            score.specificity,
+            score.verdict
+        );
+        Ok(score)
+    }
+
+    /// Extract the assumptions and risks mentioned in the answers (ids are assigned on merge)
+    pub async fn extract_risks(&self, sections: &[InterviewSection]) -> Result<Vec<RegisterEntry>> {
+        log::info!("[Interview] Extracting assumptions and risks from {} sections", sections.len());
+
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(120),
+            self.llm_router.generate_json(
+                risk_register::EXTRACTION_SYSTEM_PROMPT,
+                &risk_register::extraction_prompt(sections),
+            ),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("LLM request timed out after 120 seconds"))?
+        .context("Failed to extract risks from answers")?;
+
+        let entries: Vec<RegisterEntry> = serde_json::from_value(response["entries"].clone())
+            .context("LLM answer is not a valid list of register entries")?;
+        log::info!("[Interview] Extracted {} assumptions/risks", entries.len());
+        Ok(entries)
+    }
+
+    /// Turn the "Tests de domaine & KPI" canvas content into KPI records built on the model's data
+    pub async fn extract_kpis(&self, kpi_section: &str, model: &serde_json::Value) -> Result<Vec<KpiRecord>> {
+        log::info!("[Interview] Extracting KPI definitions ({} chars)", kpi_section.len());
+
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(120),
+            self.llm_router.generate_json(
+                kpi_catalog::EXTRACTION_SYSTEM_PROMPT,
+                &kpi_catalog::extraction_prompt(kpi_section, model),
+            ),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("LLM request timed out after 120 seconds"))?
+        .context("Failed to extract KPIs from canvas")?;
+
+        let kpis: Vec<KpiRecord> = serde_json::from_value(response["kpis"].clone())
+            .context("LLM answer is not a valid list of KPI records")?;
+        log::info!("[Interview] Extracted {} KPIs", kpis.len());
+        Ok(kpis)
+    }
+
+    /// Inconsistencies between the sections: the rule pass, then an LLM review for what
+    /// the rules cannot see (only the rule findings are returned when the LLM fails)
+    pub async fn check_consistency(&self, sections: &[SectionCanvasResult]) -> Vec<ConsistencyFinding> {
+        let mut findings = canvas_consistency::rule_findings(sections);
+        let (user_prompt, warnings) = canvas_consistency::review_prompt(sections, &findings, &PromptBudget::from_env());
+        for warning in &warnings {
+            log::warn!("[Interview] {}", warning);
+        }
+
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(120),
+            self.llm_router.generate_json(canvas_consistency::CONSISTENCY_SYSTEM_PROMPT, &user_prompt),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("LLM request timed out after 120 seconds"))
+        .and_then(|response| response.context("Failed to review canvas consistency"));
+        match response {
+            Ok(response) => findings.extend(canvas_consistency::parse_findings(&response, sections)),
+            Err(e) => log::warn!("[Interview] Consistency review skipped, rule findings only: {}", e),
+        }
+        log::info!("[Interview] Found {} inconsistencies between sections", findings.len());
+        findings
+    }
+
+    /// Generate the complete canvas from all processed sections, followed by the
+    /// project's risk register when there is one, and check the sections against each other
+    pub async fn generate_full_canvas(
+        &self,
+        sections: Vec<SectionCanvasResult>,
+        risk_register: Option<&RiskRegister>,
+    ) -> Result<FullCanvasResult> {
         // Build the full canvas markdown
         let mut markdown = String::from("# Canvas — Rich Domain Model (DDD)\n\n");
         markdown.push_str("> Objectif : cadrer un domaine avec un modèle riche (entités porteuses de logique, invariants explicites, langage ubiquiste). Remplis court et concret.\n\n");
         markdown.push_str("---\n\n");
 
         // Add each section's content
-        for section in sections {
+        for section in &sections {
             markdown.push_str(&format!("## {}\n\n", section.section_title));
             markdown.push_str(&section.canvas_content);
             markdown.push_str("\n\n");
         }
 
-        Ok(FullCanvasResult { markdown })
+        if let Some(register) = risk_register {
+            markdown.push_str(&register.to_markdown());
+        }
+
+        let findings = self.check_consistency(&sections).await;
+        Ok(FullCanvasResult { markdown, findings })
     }
 
     /// Get section-specific system prompt to guide LLM output
@@ -221,7 +406,7 @@ mod tests {
             ],
         };
 
-        let result = processor.process_section(section).await?;
+        let result = processor.process_section(section, None).await?;
         assert!(!result.canvas_content.is_empty());
         
         Ok(())
@@ -256,6 +441,34 @@ mod tests {
         assert_eq!(section.answers[1].question_index, 1);
     }
 
+    #[test]
+    fn test_previous_sections_in_budget() {
+        let summary = |title: &str, lines: usize| SectionSummary {
+            section_title: title.to_string(),
+            content: (0..lines).map(|i| format!("* point {} de {}", i, title)).collect::<Vec<_>>().join("\n"),
+        };
+        let context = InterviewContext {
+            previous_sections: vec![summary("Contexte & Vision", 200), summary("Acteurs & Use Cases", 3)],
+        };
+        let qa = "Section: Langage ubiquitaire\n\nQ: Quels termes ?\nR: Commande, panier\n\n";
+
+        let (prompt, warnings) = section_prompt("Tu es un expert", qa, Some(&context), &PromptBudget::default());
+        assert!(prompt.starts_with(CONTEXT_HEADER));
+        let vision = prompt.find("### Contexte & Vision").unwrap();
+        assert!(vision < prompt.find("### Acteurs & Use Cases").unwrap(), "Interview order");
+        assert!(prompt.ends_with(qa));
+        assert!(warnings.is_empty());
+
+        // Tight budget: the oldest section is cut first, the most recent one stays whole
+        let tight = PromptBudget { context_tokens: 400, reserved_output_tokens: 0 };
+        let (prompt, warnings) = section_prompt("Tu es un expert", qa, Some(&context), &tight);
+        assert!(prompt.contains("* point 2 de Acteurs & Use Cases"));
+        assert!(!prompt.contains("* point 199 de Contexte & Vision"));
+        assert_eq!(warnings.len(), 1);
+
+        assert_eq!(section_prompt("Tu es un expert", qa, None, &tight).0, qa);
+    }
+
     #[test]
     fn test_generate_full_canvas_structure() {
         // Test the structure of generated canvas without LLM
@@ -264,11 +477,13 @@ mod tests {
                 section_id: 1,
                 section_title: "Contexte & Vision".to_string(),
                 canvas_content: "* **Problème à résoudre :** Gestion des commandes e-commerce\n* **Valeur métier attendue :** Réduction des erreurs".to_string(),
+                generation: None,
             },
             SectionCanvasResult {
                 section_id: 2,
                 section_title: "Acteurs & Use Cases".to_string(),
                 canvas_content: "* **Acteurs :** Client, Gestionnaire de stock\n* **Top 5 use cases :**\n  1. Commander un produit".to_string(),
+                generation: None,
             },
         ];
 
@@ -305,15 +520,17 @@ mod tests {
                 section_id: 1,
                 section_title: "Contexte & Vision".to_string(),
                 canvas_content: "* **Problème à résoudre :** Gestion des commandes e-commerce".to_string(),
+                generation: None,
             },
             SectionCanvasResult {
                 section_id: 2,
                 section_title: "Acteurs & Use Cases".to_string(),
                 canvas_content: "* **Acteurs :** Client, Gestionnaire".to_string(),
+                generation: None,
             },
         ];
 
-        let result = processor.generate_full_canvas(sections).await?;
+        let result = processor.generate_full_canvas(sections, None).await?;
 
         // Verify the structure
         assert!(result.markdown.starts_with("# Canvas — Rich Domain Model (DDD)"));
@@ -331,6 +548,7 @@ mod tests {
             section_id: 1,
             section_title: "Test Section".to_string(),
             canvas_content: "Test content".to_string(),
+            generation: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -348,6 +566,7 @@ mod tests {
     fn test_full_canvas_result_serialization() {
         let result = FullCanvasResult {
             markdown: "# Test Markdown\n\nContent".to_string(),
+            findings: Vec::new(),
         };
 
         let json = serde_json::to_string(&result).unwrap();
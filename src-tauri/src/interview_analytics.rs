@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::safe_write;
+
+/// Longest time credited to a section between two pings: beyond, the facilitator
+/// most likely left the app open
+const MAX_SECTION_GAP_MS: i64 = 30 * 60 * 1000;
+
+/// Section change sent by the frontend. `section_id: None` marks the end of the
+/// interview (or the app losing focus): time stops being counted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionEvent {
+    pub section_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section_title: Option<String>,
+    /// Unix time in milliseconds
+    pub timestamp_ms: i64,
+}
+
+/// Time spent in one canvas section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionTime {
+    pub section_id: u32,
+    pub section_title: String,
+    pub total_ms: i64,
+    /// Times the section was entered
+    pub visits: u32,
+    /// Share of the total interview time (0.0 to 1.0)
+    pub share: f64,
+}
+
+/// Time per section, most time-consuming first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InterviewAnalytics {
+    /// Projects the figures are aggregated over
+    pub projects: Vec<String>,
+    pub total_ms: i64,
+    pub sections: Vec<SectionTime>,
+}
+
+/// Time per section of one event stream, keyed by section id
+fn accumulate(events: &[SectionEvent], totals: &mut BTreeMap<u32, SectionTime>) {
+    for (index, event) in events.iter().enumerate() {
+        let Some(section_id) = event.section_id else { continue };
+        let entry = totals.entry(section_id).or_insert_with(|| SectionTime {
+            section_id,
+            section_title: String::new(),
+            total_ms: 0,
+            visits: 0,
+            share: 0.0,
+        });
+        if let Some(title) = &event.section_title {
+            entry.section_title = title.clone();
+        }
+        entry.visits += 1;
+        // The last section of an unfinished stream is still open: nothing to credit yet
+        if let Some(next) = events.get(index + 1) {
+            entry.total_ms += (next.timestamp_ms - event.timestamp_ms).clamp(0, MAX_SECTION_GAP_MS);
+        }
+    }
+}
+
+/// Aggregate the event streams of several projects
+pub fn summarize(streams: &[(String, Vec<SectionEvent>)]) -> InterviewAnalytics {
+    let mut totals = BTreeMap::new();
+    for (_, events) in streams {
+        accumulate(events, &mut totals);
+    }
+    let total_ms: i64 = totals.values().map(|s| s.total_ms).sum();
+    let mut sections: Vec<SectionTime> = totals
+        .into_values()
+        .map(|mut section| {
+            section.share = if total_ms > 0 { section.total_ms as f64 / total_ms as f64 } else { 0.0 };
+            section
+        })
+        .collect();
+    sections.sort_by(|a, b| b.total_ms.cmp(&a.total_ms).then(a.section_id.cmp(&b.section_id)));
+    InterviewAnalytics {
+        projects: streams.iter().map(|(project, _)| project.clone()).collect(),
+        total_ms,
+        sections,
+    }
+}
+
+/// Section events of a project, stored as `<app data>/analytics/<project>.json`
+pub struct AnalyticsStore {
+    path: PathBuf,
+}
+
+impl AnalyticsStore {
+    pub fn open(app_data_dir: &Path, project_name: &str) -> Self {
+        let sanitized_name = crate::sanitize_project_name(project_name);
+        Self { path: analytics_dir(app_data_dir).join(format!("{}.json", sanitized_name)) }
+    }
+
+    pub fn load(&self) -> Result<Vec<SectionEvent>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path).with_context(|| format!("Failed to read {:?}", self.path))?;
+        serde_json::from_str(&content).context("Invalid interview analytics")
+    }
+
+    /// Append an event; a repeated ping for the current section is ignored
+    pub fn record(&self, event: SectionEvent) -> Result<()> {
+        let mut events = self.load()?;
+        if events.last().is_some_and(|last| last.section_id == event.section_id) {
+            return Ok(());
+        }
+        events.push(event);
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+        safe_write::write(&self.path, serde_json::to_string_pretty(&events)?)
+    }
+}
+
+fn analytics_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("analytics")
+}
+
+/// Event streams of every project with analytics, by (sanitized) project name
+pub fn load_all(app_data_dir: &Path) -> Result<Vec<(String, Vec<SectionEvent>)>> {
+    let dir = analytics_dir(app_data_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut streams = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("Failed to read {:?}", dir))?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let project = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        streams.push((project.clone(), AnalyticsStore::open(app_data_dir, &project).load()?));
+    }
+    streams.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(streams)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(section: Option<u32>, minute: i64) -> SectionEvent {
+        SectionEvent {
+            section_id: section,
+            section_title: section.map(|id| format!("Section {}", id)),
+            timestamp_ms: minute * 60_000,
+        }
+    }
+
+    #[test]
+    fn test_time_per_section_across_projects() {
+        let app_data = std::env::temp_dir().join(format!("interview_analytics_test_{}", std::process::id()));
+        let first = AnalyticsStore::open(&app_data, "Projet A");
+        for e in [event(Some(1), 0), event(Some(1), 2), event(Some(2), 10), event(Some(1), 15), event(None, 20)] {
+            first.record(e).unwrap();
+        }
+        // Left open overnight: capped at 30 minutes
+        let second = AnalyticsStore::open(&app_data, "Projet B");
+        for e in [event(Some(2), 0), event(Some(3), 5), event(None, 600)] {
+            second.record(e).unwrap();
+        }
+
+        assert_eq!(first.load().unwrap().len(), 4);
+        let analytics = summarize(&load_all(&app_data).unwrap());
+        assert_eq!(analytics.projects, vec!["Projet_A", "Projet_B"]);
+        assert_eq!(analytics.total_ms, (10 + 5 + 5 + 5 + 30) * 60_000);
+
+        let top = &analytics.sections[0];
+        assert_eq!((top.section_id, top.total_ms, top.visits), (3, 30 * 60_000, 1));
+        let first_section = analytics.sections.iter().find(|s| s.section_id == 1).unwrap();
+        assert_eq!((first_section.total_ms, first_section.visits), (15 * 60_000, 2));
+        assert_eq!(first_section.section_title, "Section 1");
+        assert!((analytics.sections.iter().map(|s| s.share).sum::<f64>() - 1.0).abs() < 1e-9);
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
@@ -0,0 +1,346 @@
+//! RFC 6902 JSON Patch, used for every programmatic model edit.
+//!
+//! `diff` produces id-aware patches for domain models: arrays whose items all
+//! carry an `id` (entities, relations, invariants) or a `name` (attributes)
+//! are matched on that key, so a change to one entity yields operations on
+//! that entity only instead of a cascade of index shifts.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// One RFC 6902 operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// Split a JSON Pointer (RFC 6901) into unescaped tokens
+fn tokens(pointer: &str) -> Result<Vec<String>> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        anyhow::bail!("Invalid JSON pointer '{}'", pointer);
+    }
+    Ok(pointer[1..]
+        .split('/')
+        .map(|t| t.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn array_index(token: &str, len: usize, allow_end: bool) -> Result<usize> {
+    if allow_end && token == "-" {
+        return Ok(len);
+    }
+    let index: usize = token
+        .parse()
+        .ok()
+        .filter(|_| token == "0" || !token.starts_with('0'))
+        .with_context(|| format!("Invalid array index '{}'", token))?;
+    let max = if allow_end { len } else { len.saturating_sub(1) };
+    if index > max || (!allow_end && len == 0) {
+        anyhow::bail!("Array index {} out of bounds", index);
+    }
+    Ok(index)
+}
+
+/// Parent container of `pointer` and the last token
+fn parent_mut<'a>(doc: &'a mut Value, pointer: &str) -> Result<(&'a mut Value, String)> {
+    let mut tokens = tokens(pointer)?;
+    let last = tokens.pop().context("The root has no parent")?;
+    let mut current = doc;
+    for token in tokens {
+        current = match current {
+            Value::Object(map) => map.get_mut(&token),
+            Value::Array(items) => {
+                let index = array_index(&token, items.len(), false)?;
+                items.get_mut(index)
+            }
+            _ => None,
+        }
+        .with_context(|| format!("Path '{}' does not exist", pointer))?;
+    }
+    Ok((current, last))
+}
+
+fn get<'a>(doc: &'a Value, pointer: &str) -> Result<&'a Value> {
+    doc.pointer(pointer).with_context(|| format!("Path '{}' does not exist", pointer))
+}
+
+fn add(doc: &mut Value, path: &str, value: Value) -> Result<()> {
+    if path.is_empty() {
+        *doc = value;
+        return Ok(());
+    }
+    let (parent, last) = parent_mut(doc, path)?;
+    match parent {
+        Value::Object(map) => {
+            map.insert(last, value);
+        }
+        Value::Array(items) => {
+            let index = array_index(&last, items.len(), true)?;
+            items.insert(index, value);
+        }
+        _ => anyhow::bail!("Cannot add '{}': parent is not a container", path),
+    }
+    Ok(())
+}
+
+fn remove(doc: &mut Value, path: &str) -> Result<Value> {
+    let (parent, last) = parent_mut(doc, path)?;
+    match parent {
+        Value::Object(map) => map.remove(&last).with_context(|| format!("Path '{}' does not exist", path)),
+        Value::Array(items) => {
+            let index = array_index(&last, items.len(), false)?;
+            Ok(items.remove(index))
+        }
+        _ => anyhow::bail!("Cannot remove '{}': parent is not a container", path),
+    }
+}
+
+fn apply_operation(doc: &mut Value, operation: &PatchOperation) -> Result<()> {
+    match operation {
+        PatchOperation::Add { path, value } => add(doc, path, value.clone()),
+        PatchOperation::Remove { path } => remove(doc, path).map(|_| ()),
+        PatchOperation::Replace { path, value } => {
+            let target = doc
+                .pointer_mut(path)
+                .with_context(|| format!("Path '{}' does not exist", path))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{}/", from)) {
+                anyhow::bail!("Cannot move '{}' into one of its children", from);
+            }
+            let value = remove(doc, from)?;
+            add(doc, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = get(doc, from)?.clone();
+            add(doc, path, value)
+        }
+        PatchOperation::Test { path, value } => {
+            if get(doc, path)? != value {
+                anyhow::bail!("Test failed at '{}'", path);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Apply a patch atomically: on error the document is left untouched
+pub fn apply(doc: &Value, patch: &[PatchOperation]) -> Result<Value> {
+    let mut patched = doc.clone();
+    for (i, operation) in patch.iter().enumerate() {
+        apply_operation(&mut patched, operation)
+            .with_context(|| format!("Patch operation {} failed", i))?;
+    }
+    Ok(patched)
+}
+
+/// Key shared by every item of the array, used to match items across versions
+fn item_key(items: &[Value]) -> Option<&'static str> {
+    ["id", "name"].into_iter().find(|key| {
+        !items.is_empty() && items.iter().all(|item| item.get(*key).is_some_and(|v| v.is_string()))
+    })
+}
+
+fn diff_into(path: &str, before: &Value, after: &Value, ops: &mut Vec<PatchOperation>) {
+    if before == after {
+        return;
+    }
+    match (before, after) {
+        (Value::Object(a), Value::Object(b)) => diff_objects(path, a, b, ops),
+        (Value::Array(a), Value::Array(b)) => diff_arrays(path, a, b, ops),
+        _ => ops.push(PatchOperation::Replace { path: path.to_string(), value: after.clone() }),
+    }
+}
+
+fn diff_objects(path: &str, a: &Map<String, Value>, b: &Map<String, Value>, ops: &mut Vec<PatchOperation>) {
+    for key in a.keys().filter(|k| !b.contains_key(*k)) {
+        ops.push(PatchOperation::Remove { path: format!("{}/{}", path, escape(key)) });
+    }
+    for (key, value) in b {
+        let child = format!("{}/{}", path, escape(key));
+        match a.get(key) {
+            Some(old) => diff_into(&child, old, value, ops),
+            None => ops.push(PatchOperation::Add { path: child, value: value.clone() }),
+        }
+    }
+}
+
+fn diff_arrays(path: &str, a: &[Value], b: &[Value], ops: &mut Vec<PatchOperation>) {
+    let key = item_key(a).filter(|k| item_key(b) == Some(*k));
+    let Some(key) = key else {
+        if a.len() == b.len() {
+            for (i, (old, new)) in a.iter().zip(b).enumerate() {
+                diff_into(&format!("{}/{}", path, i), old, new, ops);
+            }
+        } else {
+            ops.push(PatchOperation::Replace { path: path.to_string(), value: Value::Array(b.to_vec()) });
+        }
+        return;
+    };
+
+    let id = |v: &Value| v[key].as_str().unwrap_or_default().to_string();
+    // Index paths only hold on the document the patch was computed from: each operation
+    // on an existing item is preceded by a test of its id, so the patch fails on another
+    // version instead of editing the wrong item
+    let guard = |index: usize, item: &Value| PatchOperation::Test {
+        path: format!("{}/{}/{}", path, index, escape(key)),
+        value: Value::String(id(item)),
+    };
+    let new_ids: Vec<String> = b.iter().map(id).collect();
+    let mut current: Vec<&Value> = a.iter().collect();
+    let mut item_ops = Vec::new();
+
+    // Removals from the end so earlier indices stay valid
+    for i in (0..current.len()).rev() {
+        if !new_ids.contains(&id(current[i])) {
+            item_ops.push(guard(i, current[i]));
+            item_ops.push(PatchOperation::Remove { path: format!("{}/{}", path, i) });
+            current.remove(i);
+        }
+    }
+    // Kept items must be in the same relative order, otherwise replace the array
+    let kept: Vec<String> = current.iter().map(|v| id(v)).collect();
+    let reordered = new_ids.iter().filter(|n| kept.contains(n)).ne(kept.iter());
+    if reordered {
+        ops.push(PatchOperation::Replace { path: path.to_string(), value: Value::Array(b.to_vec()) });
+        return;
+    }
+    // After step k, current[..k] matches b[..k]
+    for (k, new) in b.iter().enumerate() {
+        match current.get(k) {
+            Some(old) if id(old) == id(new) => {
+                let mut child_ops = Vec::new();
+                diff_into(&format!("{}/{}", path, k), old, new, &mut child_ops);
+                if !child_ops.is_empty() {
+                    item_ops.push(guard(k, old));
+                    item_ops.extend(child_ops);
+                }
+            }
+            _ => {
+                let target = if k == current.len() { "-".to_string() } else { k.to_string() };
+                item_ops.push(PatchOperation::Add { path: format!("{}/{}", path, target), value: new.clone() });
+                current.insert(k, new);
+            }
+        }
+    }
+    ops.extend(item_ops);
+}
+
+/// Patch turning `before` into `after`, with `test` operations guarding the ids of the
+/// array items it edits or removes
+pub fn diff(before: &Value, after: &Value) -> Vec<PatchOperation> {
+    let mut ops = Vec::new();
+    diff_into("", before, after, &mut ops);
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn model() -> Value {
+        json!({
+            "entities": [
+                {"id": "Customer", "name": "Customer", "attributes": [{"name": "id", "type": "uuid"}, {"name": "email", "type": "string"}]},
+                {"id": "Order", "name": "Order", "attributes": [{"name": "id", "type": "uuid"}]},
+                {"id": "Legacy", "name": "Legacy", "attributes": [{"name": "id", "type": "uuid"}]}
+            ],
+            "relations": [],
+            "invariants": []
+        })
+    }
+
+    #[test]
+    fn test_apply_rfc6902_operations() {
+        let patch: Vec<PatchOperation> = serde_json::from_value(json!([
+            {"op": "test", "path": "/entities/0/id", "value": "Customer"},
+            {"op": "replace", "path": "/entities/0/attributes/1/type", "value": "email"},
+            {"op": "add", "path": "/entities/1/description", "value": "Commande"},
+            {"op": "remove", "path": "/entities/2"},
+            {"op": "copy", "from": "/entities/1/attributes/0", "path": "/entities/0/attributes/-"},
+            {"op": "move", "from": "/entities/0/attributes/2", "path": "/entities/1/attributes/0"}
+        ]))
+        .unwrap();
+        let patched = apply(&model(), &patch).unwrap();
+
+        assert_eq!(patched["entities"].as_array().unwrap().len(), 2);
+        assert_eq!(patched["entities"][0]["attributes"][1]["type"], "email");
+        assert_eq!(patched["entities"][1]["description"], "Commande");
+        assert_eq!(patched["entities"][1]["attributes"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_apply_is_atomic() {
+        let patch: Vec<PatchOperation> = serde_json::from_value(json!([
+            {"op": "remove", "path": "/entities/2"},
+            {"op": "test", "path": "/entities/0/id", "value": "Order"}
+        ]))
+        .unwrap();
+        assert!(apply(&model(), &patch).is_err());
+
+        let bad_path = vec![PatchOperation::Remove { path: "/entities/7".to_string() }];
+        assert!(apply(&model(), &bad_path).is_err());
+    }
+
+    #[test]
+    fn test_diff_matches_items_by_id() {
+        let before = model();
+        let mut after = model();
+        after["entities"].as_array_mut().unwrap().remove(0);
+        after["entities"][0]["attributes"][0]["type"] = json!("integer");
+        after["entities"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({"id": "Invoice", "name": "Invoice", "attributes": []}));
+
+        let patch = diff(&before, &after);
+        assert_eq!(
+            patch,
+            vec![
+                PatchOperation::Test { path: "/entities/0/id".to_string(), value: json!("Customer") },
+                PatchOperation::Remove { path: "/entities/0".to_string() },
+                PatchOperation::Test { path: "/entities/0/id".to_string(), value: json!("Order") },
+                PatchOperation::Test { path: "/entities/0/attributes/0/name".to_string(), value: json!("id") },
+                PatchOperation::Replace { path: "/entities/0/attributes/0/type".to_string(), value: json!("integer") },
+                PatchOperation::Add {
+                    path: "/entities/-".to_string(),
+                    value: json!({"id": "Invoice", "name": "Invoice", "attributes": []})
+                },
+            ]
+        );
+        assert_eq!(apply(&before, &patch).unwrap(), after);
+        assert!(diff(&after, &after).is_empty());
+
+        // Applied to a model whose entities are in another order, the patch fails instead
+        // of editing the entity now at index 0
+        let mut reordered = model();
+        reordered["entities"].as_array_mut().unwrap().swap(0, 1);
+        let error = format!("{:#}", apply(&reordered, &patch).unwrap_err());
+        assert!(error.contains("/entities/0/id"), "{}", error);
+    }
+
+    #[test]
+    fn test_pointer_escaping() {
+        let doc = json!({"a/b": {"c~d": 1}});
+        let patch = diff(&doc, &json!({"a/b": {"c~d": 2}}));
+        assert_eq!(patch, vec![PatchOperation::Replace { path: "/a~1b/c~0d".to_string(), value: json!(2) }]);
+        assert_eq!(apply(&doc, &patch).unwrap()["a/b"]["c~d"], 2);
+    }
+}
@@ -0,0 +1,328 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::safe_write;
+
+/// System prompt turning the "Tests de domaine & KPI" section into KPI records
+pub const EXTRACTION_SYSTEM_PROMPT: &str = r#"
+Tu transformes les KPI métier d'un Canvas Domain Model en définitions mesurables. Tu reçois la section "Tests de domaine & KPI" du canvas et la liste des entités du Domain Model avec leurs attributs.
+
+Réponds UNIQUEMENT avec un JSON:
+{
+  "kpis": [
+    {
+      "name": "nom court du KPI",
+      "description": "ce que le KPI mesure (optionnel)",
+      "formula": "formule en fonction des attributs, ex: count(Order where Order.status = 'cancelled') / count(Order)",
+      "entity": "id de l'entité source des données",
+      "attributes": ["attributs de cette entité utilisés par la formule"],
+      "target": "cible chiffrée, ex: < 2 % par mois (optionnel)"
+    }
+  ]
+}
+
+RÈGLES:
+1. N'utilise QUE des entités et attributs présents dans la liste fournie; dans la formule, écris-les Entite.attribut
+2. Un KPI sans donnée disponible dans le modèle garde "entity" vide et l'explique dans "description"
+3. Ne crée pas de KPI absent du canvas
+"#;
+
+/// A KPI with its measurable definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KpiRecord {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub formula: String,
+    /// Entity the data comes from
+    #[serde(default)]
+    pub entity: String,
+    /// Attributes of `entity` used by the formula
+    #[serde(default)]
+    pub attributes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+/// A KPI referencing data the model does not have
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KpiIssue {
+    pub kpi: String,
+    pub message: String,
+}
+
+/// KPI records of a project and their validation against the model
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KpiCatalog {
+    pub kpis: Vec<KpiRecord>,
+    #[serde(default)]
+    pub issues: Vec<KpiIssue>,
+}
+
+fn list<'a>(model: &'a Value, key: &str) -> &'a [Value] {
+    model.get(key).and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[])
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Entity by id, or by name as LLMs sometimes use it
+fn find_entity<'a>(model: &'a Value, reference: &str) -> Option<&'a Value> {
+    list(model, "entities")
+        .iter()
+        .find(|e| str_field(e, "id") == reference)
+        .or_else(|| {
+            list(model, "entities")
+                .iter()
+                .find(|e| str_field(e, "name").eq_ignore_ascii_case(reference))
+        })
+}
+
+fn has_attribute(entity: &Value, name: &str) -> bool {
+    list(entity, "attributes").iter().any(|a| str_field(a, "name") == name)
+}
+
+/// `Entity.attribute` references of a formula
+fn formula_references(formula: &str) -> Vec<(String, String)> {
+    let mut references = Vec::new();
+    let chars: Vec<char> = formula.chars().collect();
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_ident(chars[i]) || (i > 0 && (is_ident(chars[i - 1]) || chars[i - 1] == '.')) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_ident(chars[i]) {
+            i += 1;
+        }
+        if i + 1 < chars.len() && chars[i] == '.' && is_ident(chars[i + 1]) {
+            let entity: String = chars[start..i].iter().collect();
+            let attr_start = i + 1;
+            i = attr_start;
+            while i < chars.len() && is_ident(chars[i]) {
+                i += 1;
+            }
+            let attribute: String = chars[attr_start..i].iter().collect();
+            // Numbers like 0.5 are not references
+            if entity.starts_with(|c: char| c.is_alphabetic()) {
+                references.push((entity, attribute));
+            }
+        }
+    }
+    references
+}
+
+/// Check every entity and attribute a KPI refers to exists in the model
+pub fn validate(kpis: &[KpiRecord], model: &Value) -> Vec<KpiIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |kpi: &KpiRecord, message: String| {
+        issues.push(KpiIssue { kpi: kpi.name.clone(), message });
+    };
+
+    for kpi in kpis {
+        if kpi.entity.is_empty() {
+            issue(kpi, "No data source entity".to_string());
+        } else {
+            match find_entity(model, &kpi.entity) {
+                None => issue(kpi, format!("Unknown entity '{}'", kpi.entity)),
+                Some(entity) => {
+                    for attribute in kpi.attributes.iter().filter(|a| !has_attribute(entity, a)) {
+                        issue(kpi, format!("Unknown attribute '{}.{}'", kpi.entity, attribute));
+                    }
+                }
+            }
+        }
+
+        for (entity_ref, attribute) in formula_references(&kpi.formula) {
+            match find_entity(model, &entity_ref) {
+                None => issue(kpi, format!("Formula references unknown entity '{}'", entity_ref)),
+                Some(entity) if !has_attribute(entity, &attribute) => {
+                    issue(kpi, format!("Formula references unknown attribute '{}.{}'", entity_ref, attribute))
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    issues
+}
+
+/// User prompt: the canvas KPI section and the data available in the model
+pub fn extraction_prompt(kpi_section: &str, model: &Value) -> String {
+    let mut prompt = format!("Section \"Tests de domaine & KPI\":\n{}\n\nEntités du Domain Model:\n", kpi_section);
+    for entity in list(model, "entities") {
+        let attributes: Vec<String> = list(entity, "attributes")
+            .iter()
+            .map(|a| format!("{}: {}", str_field(a, "name"), str_field(a, "type")))
+            .collect();
+        prompt.push_str(&format!("- {} ({})\n", str_field(entity, "id"), attributes.join(", ")));
+    }
+    prompt
+}
+
+impl KpiCatalog {
+    /// Catalog of `kpis` validated against `model`
+    pub fn new(kpis: Vec<KpiRecord>, model: &Value) -> Self {
+        let issues = validate(&kpis, model);
+        Self { kpis, issues }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Catalogue des KPI\n\n");
+        if self.kpis.is_empty() {
+            markdown.push_str("*Aucun KPI défini.*\n");
+            return markdown;
+        }
+        markdown.push_str("| KPI | Formule | Source (entité / attributs) | Cible | Statut |\n");
+        markdown.push_str("| --- | ------- | --------------------------- | ----- | ------ |\n");
+        for kpi in &self.kpis {
+            let has_issues = self.issues.iter().any(|i| i.kpi == kpi.name);
+            markdown.push_str(&format!(
+                "| **{}** | `{}` | {} / {} | {} | {} |\n",
+                kpi.name.replace('|', "\\|"),
+                kpi.formula.replace('|', "\\|"),
+                if kpi.entity.is_empty() { "—" } else { &kpi.entity },
+                if kpi.attributes.is_empty() { "—".to_string() } else { kpi.attributes.join(", ") },
+                kpi.target.as_deref().unwrap_or("—").replace('|', "\\|"),
+                if has_issues { "⚠️ à revoir" } else { "✅" },
+            ));
+        }
+
+        if !self.issues.is_empty() {
+            markdown.push_str("\n## Références à corriger\n\n");
+            for issue in &self.issues {
+                markdown.push_str(&format!("* **{}** : {}\n", issue.kpi, issue.message));
+            }
+        }
+        markdown
+    }
+
+    pub fn to_csv(&self) -> String {
+        let field = |value: &str| {
+            if value.contains([',', '"', '\n']) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value.to_string()
+            }
+        };
+        let mut csv = String::from("name,formula,entity,attributes,target,valid\n");
+        for kpi in &self.kpis {
+            let valid = !self.issues.iter().any(|i| i.kpi == kpi.name);
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                field(&kpi.name),
+                field(&kpi.formula),
+                field(&kpi.entity),
+                field(&kpi.attributes.join(";")),
+                field(kpi.target.as_deref().unwrap_or("")),
+                valid
+            ));
+        }
+        csv
+    }
+}
+
+/// KPI catalog of a project, stored as `<app data>/kpis/<project>.json`
+pub struct KpiStore {
+    path: PathBuf,
+}
+
+impl KpiStore {
+    pub fn open(app_data_dir: &Path, project_name: &str) -> Self {
+        let sanitized_name = crate::sanitize_project_name(project_name);
+        Self { path: app_data_dir.join("kpis").join(format!("{}.json", sanitized_name)) }
+    }
+
+    /// Stored catalog, empty if the project has none yet
+    pub fn load(&self) -> Result<KpiCatalog> {
+        if !self.path.exists() {
+            return Ok(KpiCatalog::default());
+        }
+        let content = fs::read_to_string(&self.path).with_context(|| format!("Failed to read {:?}", self.path))?;
+        serde_json::from_str(&content).context("Invalid KPI catalog")
+    }
+
+    pub fn save(&self, catalog: &KpiCatalog) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+        safe_write::write(&self.path, serde_json::to_string_pretty(catalog)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn model() -> Value {
+        json!({
+            "entities": [
+                {"id": "Order", "name": "Commande", "attributes": [
+                    {"name": "status", "type": "string"}, {"name": "total", "type": "number"}
+                ]},
+                {"id": "Customer", "name": "Client", "attributes": [{"name": "email", "type": "email"}]}
+            ],
+            "relations": [],
+            "invariants": []
+        })
+    }
+
+    fn kpi(name: &str, formula: &str, entity: &str, attributes: &[&str]) -> KpiRecord {
+        KpiRecord {
+            name: name.to_string(),
+            description: None,
+            formula: formula.to_string(),
+            entity: entity.to_string(),
+            attributes: attributes.iter().map(|a| a.to_string()).collect(),
+            target: Some("< 2 %".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_formula_references() {
+        assert_eq!(
+            formula_references("sum(Order.total) / count(Customer.email) * 0.5"),
+            vec![
+                ("Order".to_string(), "total".to_string()),
+                ("Customer".to_string(), "email".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_against_model() {
+        let kpis = vec![
+            kpi("Taux d'annulation", "count(Order.status = 'cancelled') / count(Order)", "Order", &["status"]),
+            kpi("Panier moyen", "avg(Commande.total)", "Commande", &["total"]),
+            kpi("Délai de livraison", "avg(Order.deliveredAt - Order.createdAt)", "Shipment", &["deliveredAt"]),
+        ];
+        let catalog = KpiCatalog::new(kpis, &model());
+
+        assert!(catalog.issues.iter().all(|i| i.kpi == "Délai de livraison"));
+        let messages: Vec<&str> = catalog.issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown entity 'Shipment'",
+                "Formula references unknown attribute 'Order.deliveredAt'",
+                "Formula references unknown attribute 'Order.createdAt'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exports() {
+        let catalog = KpiCatalog::new(vec![kpi("Panier moyen", "avg(Order.total)", "Order", &["total"])], &model());
+        assert!(catalog.to_markdown().contains("| **Panier moyen** | `avg(Order.total)` | Order / total | < 2 % | ✅ |"));
+        assert_eq!(
+            catalog.to_csv(),
+            "name,formula,entity,attributes,target,valid\nPanier moyen,avg(Order.total),Order,total,< 2 %,true\n"
+        );
+    }
+}
@@ -1,11 +1,57 @@
 pub mod audio_session;
 pub mod audio_enhancement;
+pub mod audio_decode;
+pub mod batch_transcription;
+pub mod audio_diagnostics;
+pub mod audio_monitor;
+pub mod echo_canceller;
 pub mod llm_integration;
 pub mod llm_router;
+pub mod openai_schema;
 pub mod mcp_client;
 pub mod speech_to_text;
 pub mod recording_manager;
+pub mod recording_session;
+pub mod power;
 pub mod interview;
+pub mod few_shot;
+pub mod templates;
+pub mod prompt_budget;
+pub mod refine;
+pub mod json_patch;
+pub mod model_store;
+pub mod risk_register;
+pub mod kpi_catalog;
+pub mod transcript_intake;
+pub mod settings;
+pub mod transcript_diff;
+pub mod memory_diagnostics;
+pub mod safe_write;
+pub mod artifact_store;
+pub mod hooks;
+pub mod changelog;
+pub mod model_semver;
+pub mod interview_analytics;
+pub mod coach;
+pub mod model_manager;
+pub mod disk_space;
+pub mod storage_gc;
+pub mod transcription_scheduler;
+pub mod provenance;
+pub mod provider_policy;
+pub mod transcript_export;
+pub mod stt_provider;
+pub mod transcript_normalize;
+pub mod entity_candidates;
+pub mod waveform;
+pub mod answer_quality;
+pub mod canvas_consistency;
+pub mod slides;
+pub mod summary_email;
+pub mod tickets;
+pub mod glossary_export;
+pub mod narration;
+pub mod quiz;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -17,6 +63,74 @@ pub struct OrchestrateResult {
     pub markdown: String,
     pub mermaid: String,
     pub model: Value,
+    /// Automatic corrections applied to the generated model (normalization passes)
+    #[serde(default)]
+    pub fixups: Vec<String>,
+    /// Pipeline warnings (e.g. prompt context trimmed to fit the token budget)
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Provider and sampling parameters, to reproduce or regenerate the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<llm_router::GenerationRecord>,
+    /// User hooks run after model generation and emission (failures also listed in `warnings`)
+    #[serde(default)]
+    pub hooks: Vec<hooks::HookRun>,
+    /// Provenance stamped on `markdown` and `mermaid`, to pass along when publishing
+    /// `model.json` (see `publish_artifacts`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<provenance::Provenance>,
+    /// Candidate names pre-extracted from the transcript, compared with the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<entity_candidates::CandidateReport>,
+}
+
+/// One side of an A/B prompt comparison
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromptVariantResult {
+    /// Generated model (null when generation failed)
+    pub model: Value,
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub duration_ms: u64,
+    pub generation: llm_router::GenerationRecord,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromptComparison {
+    pub a: PromptVariantResult,
+    pub b: PromptVariantResult,
+    /// Structural diff from model A to model B (null when either generation failed)
+    pub diff: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefineEntityResult {
+    /// RFC 6902 patch to apply to the model (see `apply_model_patch`)
+    pub patch: Vec<json_patch::PatchOperation>,
+    pub changes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FixSuggestion {
+    pub id: String,
+    /// Validation error the fix solves
+    pub error: String,
+    pub description: String,
+    /// RFC 6902 patch to apply to the model (see `apply_model_patch`)
+    pub patch: Vec<json_patch::PatchOperation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FixSuggestions {
+    pub fixes: Vec<FixSuggestion>,
+    /// Errors no fix could be proposed for
+    pub unfixed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplyPatchResult {
+    pub model: Value,
+    pub version: model_store::ModelVersion,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,6 +139,21 @@ pub struct AudioDevice {
     pub is_default: bool,
 }
 
+/// Path of the MCP server binary (MCP_SERVER_PATH or the default release build)
+fn mcp_server_path() -> String {
+    std::env::var("MCP_SERVER_PATH")
+        .unwrap_or_else(|_| "../mcp/mcp-server/target/release/mcp-server".to_string())
+}
+
+/// Project name usable in a file name: letters, digits, `-` and `_` kept, anything else
+/// replaced by `_`
+pub(crate) fn sanitize_project_name(project_name: &str) -> String {
+    project_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -32,10 +161,31 @@ fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-async fn orchestrate(transcript: String) -> Result<OrchestrateResult, String> {
+async fn orchestrate(
+    transcript: String,
+    base_model: Option<Value>,
+    options: Option<llm_router::LlmRequestOptions>,
+    glossary: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<OrchestrateResult, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    run_orchestration(&app_data_dir, transcript, base_model, options, glossary).await
+}
+
+/// Transcript to documented model; few-shot examples, settings (language, hooks) and
+/// provenance inputs live in `app_data_dir`
+async fn run_orchestration(
+    app_data_dir: &std::path::Path,
+    transcript: String,
+    base_model: Option<Value>,
+    options: Option<llm_router::LlmRequestOptions>,
+    glossary: Option<String>,
+) -> Result<OrchestrateResult, String> {
     use crate::llm_integration::LlmIntegration;
     use crate::mcp_client::McpClient;
-    use std::env;
 
     log::info!("[Orchestrate] Starting orchestration for transcript: {}", &transcript[..transcript.len().min(100)]);
 
@@ -47,10 +197,53 @@ async fn orchestrate(transcript: String) -> Result<OrchestrateResult, String> {
             format!("Failed to initialize LLM: {}", e)
         })?;
     log::info!("[Orchestrate] LLM integration initialized successfully");
+
+    // Pick the few-shot examples closest to this transcript
+    let examples = match few_shot::FewShotStore::load(app_data_dir) {
+        Ok(store) => store.select(&transcript, 2),
+        Err(e) => {
+            log::warn!("[Orchestrate] Few-shot examples unavailable: {}", e);
+            Vec::new()
+        }
+    };
+    log::info!("[Orchestrate] Injecting {} few-shot example(s)", examples.len());
+    // Deterministic pre-extraction of candidate entities and attributes
+    let mut candidates = entity_candidates::extract(&transcript);
+    match entity_candidates::model_candidates(&transcript).await {
+        Ok(Some(names)) => candidates.merge_entities(names),
+        Ok(None) => {}
+        Err(e) => log::warn!("[Orchestrate] NER model unavailable: {}", e),
+    }
+    log::info!(
+        "[Orchestrate] {} candidate entit(ies), {} candidate attribute(s)",
+        candidates.entities.len(),
+        candidates.attributes.len()
+    );
+    // Spoken numbers and dates are normalized in the transcript's language
+    let language = settings::load(app_data_dir).ok().map(|settings| settings.transcription_language);
+    let llm_integration = llm_integration
+        .with_examples(examples)
+        .with_glossary(glossary)
+        .with_language(language)
+        .with_candidates(Some(candidates.clone()))
+        .with_options(options.unwrap_or_default());
+    let generation = llm_integration.generation_record();
+    log::info!("[Orchestrate] Generation parameters: {:?}", generation.options);
+    let provenance = llm_integration
+        .system_prompt_for(&transcript, base_model.as_ref())
+        .map(|prompt| {
+            store_provenance_inputs(app_data_dir, &[&prompt, &transcript]);
+            provenance::Provenance::new(&generation, &prompt, &transcript)
+        })
+        .map_err(|e| log::warn!("[Orchestrate] Provenance unavailable: {}", e))
+        .ok();
     
     log::info!("[Orchestrate] Generating domain model from transcript...");
-    let model = llm_integration
-        .process_request(&transcript)
+    if base_model.is_some() {
+        log::info!("[Orchestrate] Refining from a base model");
+    }
+    let (model, mut warnings) = llm_integration
+        .process_request_with_base(&transcript, base_model.as_ref())
         .await
         .map_err(|e| {
             log::error!("[Orchestrate] Failed to generate domain model: {}", e);
@@ -59,13 +252,55 @@ async fn orchestrate(transcript: String) -> Result<OrchestrateResult, String> {
     log::info!("[Orchestrate] Domain model generated successfully");
 
     // 2. Get MCP server path from environment
-    let mcp_server_path = env::var("MCP_SERVER_PATH")
-        .unwrap_or_else(|_| "../mcp/mcp-server/target/release/mcp-server".to_string());
+    let mcp_server_path = mcp_server_path();
     log::info!("[Orchestrate] Using MCP server at: {}", mcp_server_path);
     
     let mcp_client = McpClient::new(mcp_server_path);
+    let mut fixups = Vec::new();
+
+    // 3. Normalize relation directions and verbs before emission
+    log::info!("[Orchestrate] Normalizing relations...");
+    let model = match mcp_client.normalize_relations(model.clone()).await {
+        Ok((normalized, changes)) => {
+            log::info!("[Orchestrate] {} relation(s) normalized", changes.len());
+            fixups.extend(changes);
+            normalized
+        }
+        Err(e) => {
+            log::warn!("[Orchestrate] Relation normalization skipped: {}", e);
+            model
+        }
+    };
 
-    // 3. Generate Mermaid diagram from model
+    // 4. Enforce the naming policy
+    log::info!("[Orchestrate] Enforcing naming policy...");
+    let model = match mcp_client.enforce_naming(model.clone()).await {
+        Ok((renamed, naming_fixups)) => {
+            log::info!("[Orchestrate] {} naming fixup(s)", naming_fixups.len());
+            fixups.extend(naming_fixups);
+            renamed
+        }
+        Err(e) => {
+            log::warn!("[Orchestrate] Naming policy skipped: {}", e);
+            model
+        }
+    };
+
+    let extraction = entity_candidates::compare(&candidates, &model);
+    if !extraction.unexpected.is_empty() {
+        warnings.push(format!(
+            "Not among the names extracted from the transcript, check they were discussed: {}",
+            extraction.unexpected.join(", ")
+        ));
+    }
+
+    let mut hook_runs = Vec::new();
+    hook_runs.extend(
+        run_pipeline_hooks(app_data_dir, hooks::HookPoint::PostModel, serde_json::json!({"model": model}), vec![("model.json", model.to_string())])
+            .await,
+    );
+
+    // 5. Generate Mermaid diagram from model
     log::info!("[Orchestrate] Generating Mermaid diagram...");
     let mermaid = mcp_client
         .emit_mermaid(model.clone(), Some("er"))
@@ -76,7 +311,7 @@ async fn orchestrate(transcript: String) -> Result<OrchestrateResult, String> {
         })?;
     log::info!("[Orchestrate] Mermaid diagram generated successfully");
 
-    // 4. Generate Markdown documentation from model
+    // 6. Generate Markdown documentation from model
     log::info!("[Orchestrate] Generating Markdown documentation...");
     let markdown = mcp_client
         .emit_markdown(model.clone(), None)
@@ -87,12 +322,88 @@ async fn orchestrate(transcript: String) -> Result<OrchestrateResult, String> {
         })?;
     log::info!("[Orchestrate] Markdown documentation generated successfully");
 
+    let (markdown, mermaid, model_json) = match &provenance {
+        Some(provenance) => (
+            provenance::stamp("markdown", &markdown, provenance).unwrap_or(markdown),
+            provenance::stamp("mermaid", &mermaid, provenance).unwrap_or(mermaid),
+            provenance::stamp("model", &model.to_string(), provenance).unwrap_or_else(|_| model.to_string()),
+        ),
+        None => (markdown, mermaid, model.to_string()),
+    };
+    let emitted = vec![
+        ("domain.md", markdown.clone()),
+        ("domain.mmd", mermaid.clone()),
+        ("model.json", model_json),
+    ];
+    hook_runs.extend(run_pipeline_hooks(app_data_dir, hooks::HookPoint::PostEmit, serde_json::json!({"model": model}), emitted).await);
+    warnings.extend(hook_runs.iter().filter_map(|run| run.failure_message()));
+
     log::info!("[Orchestrate] Orchestration completed successfully");
     Ok(OrchestrateResult {
         markdown,
         mermaid,
         model,
+        fixups,
+        warnings,
+        generation: Some(generation),
+        hooks: hook_runs,
+        provenance,
+        extraction: Some(extraction),
+    })
+}
+
+/// Keep the inputs of a generation in the artifact objects, so that `verify_artifact`
+/// can tell whether they are still available unchanged
+fn store_provenance_inputs(app_data_dir: &std::path::Path, inputs: &[&str]) {
+    for input in inputs {
+        if let Err(e) = artifact_store::put_object(app_data_dir, input.as_bytes()) {
+            log::warn!("[Orchestrate] Failed to store provenance input: {}", e);
+        }
+    }
+}
+
+/// Run the hooks configured for `point` off the async runtime. `artifacts` are written
+/// to a staging directory only when a hook will run; their paths go in `payload.artifacts`.
+async fn run_pipeline_hooks(
+    app_data_dir: &std::path::Path,
+    point: hooks::HookPoint,
+    mut payload: Value,
+    artifacts: Vec<(&'static str, String)>,
+) -> Vec<hooks::HookRun> {
+    let configured = match settings::load(app_data_dir) {
+        Ok(app_settings) => app_settings.hooks,
+        Err(e) => {
+            log::warn!("[Hooks] Hooks unavailable: {}", e);
+            return Vec::new();
+        }
+    };
+    if !configured.iter().any(|hook| hook.enabled && hook.point == point) {
+        return Vec::new();
+    }
+    let staging_root = hooks::staging_root(app_data_dir);
+    tauri::async_runtime::spawn_blocking(move || {
+        let files: Vec<(&str, &str)> = artifacts.iter().map(|(name, content)| (*name, content.as_str())).collect();
+        let staged = match hooks::stage_artifacts(&staging_root, &files) {
+            Ok((dir, paths)) => {
+                payload["artifacts"] = paths;
+                Some(dir)
+            }
+            Err(e) => {
+                log::warn!("[Hooks] Failed to stage artifacts: {}", e);
+                None
+            }
+        };
+        let runs = hooks::run_hooks(&configured, point, &payload);
+        // Hooks are waited for (or killed on timeout): nothing reads the artifacts anymore
+        if let Some(dir) = staged {
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                log::warn!("[Hooks] Failed to remove staged artifacts {:?}: {}", dir, e);
+            }
+        }
+        runs
     })
+        .await
+        .unwrap_or_default()
 }
 
 #[tauri::command]
@@ -121,298 +432,2521 @@ async fn stop_recording(state: tauri::State<'_, Arc<Mutex<Option<recording_manag
         })
 }
 
+#[tauri::command]
+async fn cancel_transcription(state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>) -> Result<(), String> {
+    log::info!("[Command] cancel_transcription called");
+    let manager_guard = state.lock().unwrap();
+    let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+    manager.cancel_transcription();
+    Ok(())
+}
+
+/// Global shortcut: start recording when idle, stop while recording or paused
+/// (the manager emits "recording-state-changed" as for the buttons)
+#[cfg(desktop)]
+fn toggle_recording(app: &tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let state = app.state::<Arc<Mutex<Option<recording_manager::RecordingManager>>>>();
+    let manager_guard = state.lock().unwrap();
+    let Some(manager) = manager_guard.as_ref() else { return };
+    let result = match manager.get_state() {
+        recording_manager::RecordingState::Idle => manager.start_recording(),
+        recording_manager::RecordingState::Recording | recording_manager::RecordingState::Paused => manager.stop_recording(),
+        recording_manager::RecordingState::Processing => {
+            log::info!("[Hotkey] Ignored while transcribing");
+            return;
+        }
+    };
+    match result {
+        Ok(message) => log::info!("[Hotkey] {}", message),
+        Err(e) => {
+            log::error!("[Hotkey] Failed to toggle recording: {}", e);
+            let _ = app.emit("recording-error", format!("{}", e));
+        }
+    }
+}
+
+/// Replace the recording shortcut (None only unregisters it)
+#[cfg(desktop)]
+fn register_recording_hotkey(app: &tauri::AppHandle, hotkey: Option<&str>) -> anyhow::Result<()> {
+    use anyhow::Context;
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+    let shortcut = hotkey
+        .map(|hotkey| hotkey.parse::<Shortcut>().map_err(|e| anyhow::anyhow!("Invalid shortcut '{}': {}", hotkey, e)))
+        .transpose()?;
+    let global_shortcut = app.global_shortcut();
+    global_shortcut.unregister_all().context("Failed to unregister the previous shortcut")?;
+    if let Some(shortcut) = shortcut {
+        global_shortcut
+            .register(shortcut)
+            .with_context(|| format!("Failed to register '{}' (already used by another app?)", hotkey.unwrap_or_default()))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+fn register_recording_hotkey(_app: &tauri::AppHandle, hotkey: Option<&str>) -> anyhow::Result<()> {
+    if hotkey.is_some() {
+        anyhow::bail!("Global shortcuts are not available on mobile");
+    }
+    Ok(())
+}
+
+/// Set the global shortcut toggling the recording, e.g. "CommandOrControl+Shift+R"
+/// (empty or None disables it). Returns the updated settings.
+#[tauri::command]
+async fn set_recording_hotkey(
+    hotkey: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<settings::AppSettings, String> {
+    let hotkey = hotkey.map(|h| h.trim().to_string()).filter(|h| !h.is_empty());
+    register_recording_hotkey(&app, hotkey.as_deref()).map_err(|e| format!("{:#}", e))?;
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.recording_hotkey = hotkey;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+    log::info!("[Settings] Recording hotkey set to: {:?}", app_settings.recording_hotkey);
+    Ok(app_settings)
+}
+
+/// Hear what is being captured (after gain and AGC) on the default output, including
+/// during the current recording. Not persisted: monitoring is off at each launch.
+#[tauri::command]
+async fn set_audio_monitor(
+    enabled: bool,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<(), String> {
+    let manager_guard = state.lock().unwrap();
+    let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+    manager.set_monitor(enabled);
+    Ok(())
+}
+
+/// Pause the current recording without ending its session
+#[tauri::command]
+async fn pause_recording(state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>) -> Result<String, String> {
+    log::info!("[Command] pause_recording called");
+    let manager_guard = state.lock().unwrap();
+    let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+    manager.pause_recording()
+        .map_err(|e| {
+            log::error!("[Command] Failed to pause recording: {}", e);
+            format!("Failed to pause recording: {}", e)
+        })
+}
+
+#[tauri::command]
+async fn resume_recording(state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>) -> Result<String, String> {
+    log::info!("[Command] resume_recording called");
+    let manager_guard = state.lock().unwrap();
+    let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+    manager.resume_recording()
+        .map_err(|e| {
+            log::error!("[Command] Failed to resume recording: {}", e);
+            format!("Failed to resume recording: {}", e)
+        })
+}
+
 #[tauri::command]
 async fn transcribe_audio(
     audio_path: String,
+    language: Option<String>,
+    word_timestamps: Option<bool>,
+    translate: Option<bool>,
     app: tauri::AppHandle,
+    stt: tauri::State<'_, Arc<speech_to_text::SpeechToText>>,
 ) -> Result<speech_to_text::TranscriptionResult, String> {
-    use crate::speech_to_text::SpeechToText;
-    use std::env;
-    use std::path::PathBuf;
-    
-    let model_path = if let Ok(path) = env::var("WHISPER_MODEL_PATH") {
-        PathBuf::from(path)
-    } else {
-        // Try to get from bundled resources
-        let resource_path = app.path().resolve("ggml-small.bin", tauri::path::BaseDirectory::Resource)
-            .map_err(|e| format!("Failed to resolve resource path: {}", e))?;
-        
-        if resource_path.exists() {
-            resource_path
-        } else {
-            // Fallback to dev mode path
-            PathBuf::from("models/whisper/ggml-small.bin")
-        }
+    // Explicit language and translation, otherwise the ones chosen in the settings
+    // (already set on the shared transcriber, like the model and the backend)
+    let options = speech_to_text::TranscribeOptions {
+        language: language
+            .map(|language| speech_to_text::normalize_language(&language))
+            .transpose()
+            .map_err(|e| e.to_string())?,
+        word_timestamps: Some(word_timestamps.unwrap_or(false)),
+        translate,
     };
-    
-    let stt = SpeechToText::new(model_path);
-    let audio_path_buf = PathBuf::from(audio_path);
-    
-    stt.transcribe_file(&audio_path_buf)
-        .map_err(|e| format!("Transcription failed: {}", e))
+    let stt = Arc::clone(&stt);
+
+    // Stream segments to the UI while long files are transcribed
+    let partial_handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        stt.transcribe_file_with_options(std::path::Path::new(&audio_path), &options, move |segment| {
+            use tauri::Emitter;
+            let _ = partial_handle.emit("transcription-partial", &segment);
+        })
+    })
+    .await
+    .map_err(|e| format!("Transcription task failed: {}", e))?
+    .map_err(|e| format!("Transcription failed: {}", e))
 }
 
+/// Transcribe every audio file of a folder with the app's transcriber: one markdown per
+/// file and `transcriptions.md` in `output_dir` (`<dir>/transcriptions` by default).
+/// Emits "batch-transcription-progress" before and after each file.
 #[tauri::command]
-async fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
-    use cpal::traits::{DeviceTrait, HostTrait};
-    
-    let host = cpal::default_host();
-    let default_device = host.default_input_device();
-    let default_name = default_device.as_ref().and_then(|d| d.name().ok());
-    
-    let devices: Vec<AudioDevice> = host
-        .input_devices()
-        .map_err(|e| format!("Failed to enumerate audio devices: {}", e))?
-        .filter_map(|device| {
-            device.name().ok().map(|name| AudioDevice {
-                is_default: Some(name.clone()) == default_name,
-                name,
-            })
-        })
-        .collect();
-    
-    Ok(devices)
+async fn transcribe_folder(
+    dir: String,
+    output_dir: Option<String>,
+    language: Option<String>,
+    app: tauri::AppHandle,
+    stt: tauri::State<'_, Arc<speech_to_text::SpeechToText>>,
+) -> Result<batch_transcription::BatchResult, String> {
+    let options = speech_to_text::TranscribeOptions {
+        language: language
+            .map(|language| speech_to_text::normalize_language(&language))
+            .transpose()
+            .map_err(|e| e.to_string())?,
+        ..Default::default()
+    };
+    let dir = std::path::PathBuf::from(dir);
+    let output_dir = output_dir
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| dir.join(batch_transcription::DEFAULT_OUTPUT_DIR));
+    let stt = Arc::clone(&stt);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        batch_transcription::transcribe_folder(
+            &dir,
+            &output_dir,
+            |path| stt.transcribe_file_with_options(path, &options, |_| {}).map(|result| result.text),
+            |progress| {
+                use tauri::Emitter;
+                let _ = app.emit("batch-transcription-progress", &progress);
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("Batch transcription task failed: {}", e))?
+    .map_err(|e| format!("Batch transcription failed: {}", e))
+}
+
+fn load_app_settings(app: &tauri::AppHandle) -> Result<settings::AppSettings, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))
 }
 
 #[tauri::command]
-async fn set_audio_device(
-    device_name: String,
+async fn get_app_settings(app: tauri::AppHandle) -> Result<settings::AppSettings, String> {
+    load_app_settings(&app)
+}
+
+/// Persist the spoken language and apply it to the recording pipeline
+#[tauri::command]
+async fn set_transcription_language(
+    language: String,
+    app: tauri::AppHandle,
     state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
-) -> Result<String, String> {
-    let manager_guard = state.lock().unwrap();
-    let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
-    
-    manager.set_audio_device(device_name.clone())
-        .map_err(|e| format!("Failed to set audio device: {}", e))?;
-    
-    Ok(format!("Audio device set to: {}", device_name))
+) -> Result<settings::AppSettings, String> {
+    let language = speech_to_text::normalize_language(&language).map_err(|e| e.to_string())?;
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.transcription_language = language.clone();
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_language(&language);
+    }
+    log::info!("[Settings] Transcription language set to: {}", language);
+    Ok(app_settings)
 }
 
+/// Persist whether Whisper translates the transcriptions to English, for English-only
+/// LLM prompts. Returns the updated settings.
 #[tauri::command]
-async fn save_interview_state(
-    project_name: String,
-    state_json: String,
+async fn set_translate_to_english(
+    enabled: bool,
     app: tauri::AppHandle,
-) -> Result<String, String> {
-    use std::fs;
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.translate_to_english = enabled;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_translate(enabled);
+    }
+    log::info!("[Settings] Translation to English {}", if enabled { "enabled" } else { "disabled" });
+    Ok(app_settings)
+}
+
+/// Persist the remote transcription provider used when no Whisper model is installed
+/// or when Whisper is unsure (`provider: null` to disable). Returns the updated settings.
+#[tauri::command]
+async fn set_cloud_stt(
+    cloud_stt: stt_provider::CloudSttSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    if !(0.0..=1.0).contains(&cloud_stt.min_confidence) {
+        return Err(format!("Invalid minimum confidence {}, expected 0 to 1", cloud_stt.min_confidence));
+    }
+    // Refused before saving when STT_API_KEY is missing
+    let cloud = stt_provider::CloudStt::from_settings(&cloud_stt).map_err(|e| e.to_string())?;
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.cloud_stt = cloud_stt;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    log::info!("[Settings] Cloud transcription: {}", cloud.as_ref().map(|cloud| cloud.name()).unwrap_or("disabled"));
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_cloud_stt(cloud);
+    }
+    Ok(app_settings)
+}
+
+/// Persist the Jira or Linear project tickets are pushed to (`provider: null` for preview
+/// only). Returns the updated settings.
+#[tauri::command]
+async fn set_ticket_tracker(tracker: tickets::TicketTrackerSettings, app: tauri::AppHandle) -> Result<settings::AppSettings, String> {
+    // Refused before saving when the project or TRACKER_API_TOKEN is missing
+    if tracker.provider.is_some() {
+        tickets::TicketTracker::from_settings(&tracker).map_err(|e| e.to_string())?;
+    }
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.ticket_tracker = tracker;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    log::info!("[Settings] Ticket tracker: {:?}", app_settings.ticket_tracker.provider);
+    Ok(app_settings)
+}
+
+/// Persist the power profile and apply it to the next recordings ("balanced" or
+/// "low_power"). Returns the updated settings.
+#[tauri::command]
+async fn set_power_profile(
+    profile: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    let profile: power::PowerProfile = profile.parse().map_err(|e: anyhow::Error| e.to_string())?;
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.power_profile = profile;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_power_profile(profile);
+    }
+    log::info!("[Settings] Power profile set to: {:?}", profile);
+    Ok(app_settings)
+}
+
+/// Current silence detection settings (silence and minimal utterance durations, VAD mode)
+#[tauri::command]
+async fn get_vad_settings(app: tauri::AppHandle) -> Result<audio_session::VadSettings, String> {
+    Ok(load_app_settings(&app)?.vad)
+}
+
+/// Persist the silence detection settings and apply them to the next recordings.
+/// Returns the updated settings.
+#[tauri::command]
+async fn set_vad_settings(
+    vad: audio_session::VadSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    vad.validate().map_err(|e| e.to_string())?;
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.vad = vad;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_vad_settings(vad);
+    }
+    log::info!("[Settings] VAD settings set to: {:?}", vad);
+    Ok(app_settings)
+}
+
+/// Persist how the utterances of the next recordings are named (template with {index},
+/// {session}, {timestamp}, {speaker}) and whether a JSON sidecar is written next to each
+/// one. Returns the updated settings.
+#[tauri::command]
+async fn set_utterance_naming(
+    naming: audio_session::UtteranceNaming,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    naming.validate().map_err(|e| e.to_string())?;
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.utterance_naming = naming.clone();
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_utterance_naming(naming);
+    }
+    Ok(app_settings)
+}
+
+/// Persist the recording mode and apply it to the next recordings: "push_to_talk"
+/// (one take, transcribed on stop) or "vad" (automatic segmentation, each utterance
+/// transcribed as soon as it ends). Returns the updated settings.
+#[tauri::command]
+async fn set_recording_mode(
+    mode: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    let mode: audio_session::RecordingMode = mode.parse().map_err(|e: anyhow::Error| e.to_string())?;
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.recording_mode = mode;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_recording_mode(mode);
+    }
+    log::info!("[Settings] Recording mode set to: {:?}", mode);
+    Ok(app_settings)
+}
+
+/// Persist whether the next recordings keep their raw capture (before gain and AGC)
+/// next to each processed utterance. Returns the updated settings.
+#[tauri::command]
+async fn set_archive_raw_audio(
+    enabled: bool,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.archive_raw_audio = enabled;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_archive_raw(enabled);
+    }
+    log::info!("[Settings] Raw audio archiving set to: {}", enabled);
+    Ok(app_settings)
+}
+
+/// Persist the system audio capture of the next recordings: the loopback device recorded
+/// next to the microphone (None = microphone only) and whether its echo is removed from
+/// the microphone. Returns the updated settings.
+#[tauri::command]
+async fn set_system_capture(
+    capture: audio_session::SystemCapture,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.system_capture = capture.clone();
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_system_capture(capture.clone());
+    }
+    log::info!("[Settings] System audio capture set to: {:?}", capture);
+    Ok(app_settings)
+}
+
+/// Persist whether the next recordings keep their utterances in memory rather than
+/// writing WAV files. Returns the updated settings.
+#[tauri::command]
+async fn set_in_memory_audio(
+    enabled: bool,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.in_memory_audio = enabled;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_in_memory_audio(enabled);
+    }
+    log::info!("[Settings] In-memory audio set to: {}", enabled);
+    Ok(app_settings)
+}
+
+/// Persist the age and size limits on the audio of finished sessions and enforce them
+/// right away. Returns the updated settings.
+#[tauri::command]
+async fn set_audio_retention(
+    retention: storage_gc::AudioRetention,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.audio_retention = retention;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_audio_retention(retention);
+    }
+    log::info!("[Settings] Audio retention set to: {:?}", retention);
+    Ok(app_settings)
+}
+
+/// Remove the audio of every finished recording session, keeping their transcripts
+#[tauri::command]
+async fn purge_audio_cache(
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<storage_gc::GcReport, String> {
+    let manager_guard = state.lock().unwrap();
+    let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+    let report = manager.purge_audio_cache();
+    log::info!("[Storage] Purged {} audio files ({} bytes)", report.removed.len(), report.freed_bytes);
+    Ok(report)
+}
+
+/// Persist the facilitation coach preferences (off by default) and apply them
+/// right away. Returns the updated settings.
+#[tauri::command]
+async fn set_coach_settings(
+    coach_settings: coach::CoachSettings,
+    app: tauri::AppHandle,
+    coach_state: tauri::State<'_, Arc<Mutex<coach::Coach>>>,
+) -> Result<settings::AppSettings, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.coach = coach_settings;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    coach_state.lock().unwrap().set_settings(coach_settings);
+    log::info!("[Coach] Settings set to: {:?}", coach_settings);
+    Ok(app_settings)
+}
+
+/// Section the interview is on, with its goals (None when leaving the interview):
+/// the coach checks the recent transcript against it
+#[tauri::command]
+async fn set_coach_section(
+    section: Option<coach::CoachSection>,
+    coach_state: tauri::State<'_, Arc<Mutex<coach::Coach>>>,
+) -> Result<(), String> {
+    coach_state.lock().unwrap().set_section(section);
+    Ok(())
+}
+
+/// Feed a "transcription-result" to the coach and, when an analysis is due, ask the
+/// LLM for suggestions in the background; each one is emitted as "coach-suggestion"
+fn feed_coach(app: &tauri::AppHandle, coach_state: &Arc<Mutex<coach::Coach>>, payload: &str) {
+    let text = match serde_json::from_str::<speech_to_text::TranscriptionResult>(payload) {
+        Ok(result) => result.text,
+        Err(e) => {
+            log::warn!("[Coach] Unreadable transcription result: {}", e);
+            return;
+        }
+    };
+    let request = {
+        let mut coach = coach_state.lock().unwrap();
+        coach.push_transcript(&text, std::time::Instant::now());
+        coach.take_request(std::time::Instant::now())
+    };
+    let Some(request) = request else { return };
+
+    let app = app.clone();
+    let coach_state = Arc::clone(coach_state);
+    tauri::async_runtime::spawn(async move {
+        use crate::llm_integration::LlmIntegration;
+        use tauri::Emitter;
+
+        let section_id = request.section.section_id;
+        let suggestions = match LlmIntegration::new() {
+            Ok(llm_integration) => llm_integration.coach_suggestions(&request).await,
+            Err(e) => Err(e),
+        };
+        let suggestions = suggestions.unwrap_or_else(|e| {
+            log::warn!("[Coach] Analysis failed: {}", e);
+            Vec::new()
+        });
+        for suggestion in coach_state.lock().unwrap().finish(section_id, suggestions) {
+            log::info!("[Coach] Suggestion for section {}: {}", section_id, suggestion.message);
+            let _ = app.emit("coach-suggestion", &suggestion);
+        }
+    });
+}
+
+/// Replace the user hooks (post_transcription, post_model, post_emit). Returns the
+/// updated settings.
+#[tauri::command]
+async fn set_hooks(hooks: Vec<hooks::Hook>, app: tauri::AppHandle) -> Result<settings::AppSettings, String> {
+    if let Some(hook) = hooks.iter().find(|hook| hook.command.trim().is_empty()) {
+        return Err(format!("Hook {} has no command", hook.point.as_str()));
+    }
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.hooks = hooks;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+    log::info!("[Settings] {} hook(s) configured", app_settings.hooks.len());
+    Ok(app_settings)
+}
+
+/// Run a hook once with a sample payload, to check a script before saving it
+#[tauri::command]
+async fn test_hook(hook: hooks::Hook) -> Result<hooks::HookRun, String> {
+    let payload = serde_json::json!({"hook": hook.point.as_str(), "test": true, "artifacts": {}});
+    tauri::async_runtime::spawn_blocking(move || hooks::run_hook(&hook, &payload))
+        .await
+        .map_err(|e| format!("Hook test failed: {}", e))
+}
+
+#[tauri::command]
+async fn list_recording_sessions(app: tauri::AppHandle) -> Result<Vec<recording_session::SessionRecord>, String> {
+    recording_session::list_sessions(&recording_manager::recordings_dir(&app))
+        .map_err(|e| format!("Failed to list sessions: {}", e))
+}
+
+/// Write the timestamped transcript of a session as "srt", "vtt" or "markdown" next to
+/// it (`<session>/transcript.<ext>`), for review with subtitles. Returns the file path.
+#[tauri::command]
+async fn export_transcript(app: tauri::AppHandle, session_id: String, format: String) -> Result<String, String> {
+    let format: transcript_export::TranscriptFormat = format.parse().map_err(|e: anyhow::Error| e.to_string())?;
+    let recordings_dir = recording_manager::recordings_dir(&app);
+    let record = recording_session::load_session(&recordings_dir, &session_id)
+        .map_err(|e| format!("Failed to load session: {}", e))?;
+    let session_dir = recording_session::session_dir(&recordings_dir, &session_id).map_err(|e| e.to_string())?;
+
+    let offsets = transcript_export::sidecar_offsets(&session_dir, &record.utterances);
+    let cues = transcript_export::session_cues(&record.utterances, &offsets);
+    let file_path = session_dir.join(format!("transcript.{}", format.extension()));
+    safe_write::write(&file_path, transcript_export::render(format, &cues))
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    log::info!("[Sessions] Transcript of {} exported to: {:?} ({} cues)", session_id, file_path, cues.len());
+    Ok(file_path.display().to_string())
+}
+
+/// Transcript of a session in recording order, each utterance with its start and end
+/// from the moment the recording started. Without `session_id`, the recording in progress
+/// (the utterances transcribed so far in VAD mode, `complete` false).
+#[tauri::command]
+async fn get_session_transcript(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+    session_id: Option<String>,
+) -> Result<transcript_export::SessionTranscript, String> {
+    let recordings_dir = recording_manager::recordings_dir(&app);
+    let (session_id, utterances, complete) = match session_id {
+        Some(session_id) => {
+            let record = recording_session::load_session(&recordings_dir, &session_id)
+                .map_err(|e| format!("Failed to load session: {}", e))?;
+            (session_id, record.utterances, true)
+        }
+        None => {
+            let (live_id, utterances) = state.lock().unwrap().as_ref()
+                .and_then(|manager| manager.live_transcript())
+                .ok_or_else(|| "No recording in progress".to_string())?;
+            (live_id, utterances, false)
+        }
+    };
+    let session_dir = recording_session::session_dir(&recordings_dir, &session_id).map_err(|e| e.to_string())?;
+
+    let offsets = transcript_export::recording_offsets(&session_dir, &session_id, &utterances);
+    Ok(transcript_export::session_transcript(&session_id, &utterances, &offsets, complete))
+}
+
+/// Re-process the stored utterances of a session with the current settings (or the
+/// overrides in `options`): new Whisper model, language, enhancement, and optionally a
+/// new domain model. The result is stored next to the session, the original is kept.
+#[tauri::command]
+async fn replay_session(
+    session_id: String,
+    options: Option<recording_session::ReplayOptions>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<recording_session::SessionReplay, String> {
+    let options = options.unwrap_or_default();
+    log::info!("[Replay] Replaying session {} with {:?}", session_id, options);
+
+    let recordings_dir = recording_manager::recordings_dir(&app);
+    let record = recording_session::load_session(&recordings_dir, &session_id)
+        .map_err(|e| format!("Failed to load session: {}", e))?;
+    let session_dir = recording_session::session_dir(&recordings_dir, &session_id).map_err(|e| e.to_string())?;
+
+    let (stt, enhancement) = {
+        let manager_guard = state.lock().unwrap();
+        let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+        let model_path = options.model_path.as_ref().map(std::path::PathBuf::from);
+        if let Some(path) = &model_path {
+            if !path.exists() {
+                return Err(format!("Whisper model not found: {}", path.display()));
+            }
+        }
+        let enhancement = options.enhance.unwrap_or(record.enhanced).then(|| manager.enhancement_config());
+        (manager.replay_transcriber(model_path), enhancement)
+    };
+    if let Some(language) = &options.language {
+        stt.set_language(&speech_to_text::normalize_language(language).map_err(|e| e.to_string())?);
+    }
+
+    // Whisper is blocking, keep it off the async runtime
+    let utterances = record.utterances.clone();
+    let raw = options.raw;
+    let enhanced = enhancement.is_some();
+    let (stt, utterances) = tauri::async_runtime::spawn_blocking(move || {
+        let mut replayed = Vec::new();
+        stt.transcribe_batch(
+            &utterances,
+            |utterance| {
+                let mut path = session_dir.join(&utterance.file_name);
+                if raw {
+                    let raw_path = audio_session::raw_track_path(&path);
+                    if raw_path.exists() {
+                        path = raw_path;
+                    } else {
+                        log::warn!("[Replay] No raw track for utterance {}, using the processed one", utterance.id);
+                    }
+                }
+                recording_manager::transcribe_utterance(&stt, enhancement.as_ref(), &path, None, |_| {})
+                    .map_err(|e| log::warn!("[Replay] Utterance {} failed: {}", utterance.id, e))
+                    .ok()
+            },
+            |utterance, result| replayed.push(utterance.transcribed(result)),
+        );
+        (stt, replayed)
+    })
+    .await
+    .map_err(|e| format!("Replay failed: {}", e))?;
+
+    let mut replay = recording_session::new_replay(&record, &stt.language(), &stt.model_name(), enhanced, utterances);
+
+    if options.generate_model && !replay.transcript.is_empty() {
+        let result = orchestrate(replay.transcript.clone(), None, options.llm.clone(), None, app.clone()).await?;
+        replay.domain_model = Some(serde_json::to_value(result).map_err(|e| e.to_string())?);
+    }
+
+    recording_session::save_replay(&recordings_dir, &replay)
+        .map_err(|e| format!("Failed to save replay: {}", e))?;
+    log::info!("[Replay] Session {} replayed as {}", session_id, replay.id);
+    Ok(replay)
+}
+
+/// Word-level diff of two transcripts of the same audio (e.g. small vs large Whisper
+/// model, or a session and its replay), `a` being the reference
+#[tauri::command]
+async fn compare_transcripts(a: String, b: String) -> Result<transcript_diff::TranscriptComparison, String> {
+    let comparison = transcript_diff::compare(&a, &b);
+    log::info!(
+        "[Compare] {} vs {} words, similarity {:.2}, WER {:.2}",
+        comparison.a_word_count, comparison.b_word_count, comparison.similarity, comparison.word_error_rate
+    );
+    Ok(comparison)
+}
+
+#[tauri::command]
+async fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+    
+    let host = cpal::default_host();
+    let default_device = host.default_input_device();
+    let default_name = default_device.as_ref().and_then(|d| d.name().ok());
+    
+    let devices: Vec<AudioDevice> = host
+        .input_devices()
+        .map_err(|e| format!("Failed to enumerate audio devices: {}", e))?
+        .filter_map(|device| {
+            device.name().ok().map(|name| AudioDevice {
+                is_default: Some(name.clone()) == default_name,
+                name,
+            })
+        })
+        .collect();
+    
+    Ok(devices)
+}
+
+/// Play a WAV file as if it were the microphone (demos, end-to-end tests without hardware)
+///
+/// `speed`: 1.0 (default) for real-time pacing, > 1.0 to accelerate, 0 for as fast as possible.
+/// Pass no path to go back to the real device.
+#[tauri::command]
+async fn set_virtual_microphone(
+    path: Option<String>,
+    speed: Option<f32>,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<String, String> {
+    use crate::audio_session::{Pacing, VirtualInput};
+
+    let virtual_input = match path {
+        Some(path) => {
+            let path = std::path::PathBuf::from(path);
+            if !path.exists() {
+                return Err(format!("Audio file not found: {}", path.display()));
+            }
+            let pacing = match speed.unwrap_or(1.0) {
+                speed if !speed.is_finite() => return Err(format!("Invalid playback speed: {}", speed)),
+                speed if speed <= 0.0 => Pacing::Unpaced,
+                1.0 => Pacing::RealTime,
+                speed => Pacing::Accelerated(speed),
+            };
+            Some(VirtualInput { path, pacing })
+        }
+        None => None,
+    };
+    let message = match &virtual_input {
+        Some(input) => format!("Virtual microphone playing {}", input.path.display()),
+        None => "Virtual microphone disabled".to_string(),
+    };
+
+    let manager_guard = state.lock().unwrap();
+    let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+    manager
+        .set_virtual_input(virtual_input)
+        .map_err(|e| format!("Failed to set virtual microphone: {}", e))?;
+
+    Ok(message)
+}
+
+/// Round-trip a generated tone through the capture/WAV/Whisper input path using the
+/// configuration negotiated with the selected (or default) input device
+#[tauri::command]
+async fn test_audio_pipeline(device_name: Option<String>) -> Result<audio_diagnostics::PipelineReport, String> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    let host = cpal::default_host();
+    let device = match &device_name {
+        Some(name) => host
+            .input_devices()
+            .map_err(|e| format!("Failed to enumerate audio devices: {}", e))?
+            .find(|d| d.name().map(|n| n == *name).unwrap_or(false)),
+        None => host.default_input_device(),
+    };
+
+    // Without a device (CI, headless machine) simulate the common 48kHz mono input
+    let (device_label, sample_rate, channels) = match device {
+        Some(device) => {
+            let default_config = device
+                .default_input_config()
+                .map_err(|e| format!("Failed to get default input config: {}", e))?;
+            let supported: Vec<_> = device
+                .supported_input_configs()
+                .map(|configs| configs.collect())
+                .unwrap_or_default();
+            let config = audio_session::negotiate_input_config(&default_config, &supported)
+                .ok_or("Audio device offers no supported input sample format")?;
+            (device.name().ok(), config.sample_rate().0, config.channels())
+        }
+        None => match device_name {
+            Some(name) => return Err(format!("Audio device '{}' not found", name)),
+            None => (None, 48000, 1),
+        },
+    };
+
+    log::info!(
+        "[Audio] Testing pipeline on {} / {:?}: {} Hz, {} channel(s)",
+        host.id().name(),
+        device_label,
+        sample_rate,
+        channels
+    );
+    let output_dir = std::env::temp_dir().join("domain-model-audio-test");
+    audio_diagnostics::round_trip(&output_dir, host.id().name(), device_label, sample_rate, channels)
+        .map_err(|e| format!("Audio pipeline test failed: {}", e))
+}
+
+/// Downsampled min/max peaks of a recorded WAV, for drawing its waveform
+///
+/// `start_ms`/`end_ms` restrict it to a transcript segment; `points` defaults to 1000 pairs.
+#[tauri::command]
+async fn get_waveform(
+    audio_path: String,
+    points: Option<usize>,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+) -> Result<waveform::Waveform, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        waveform::from_wav(
+            std::path::Path::new(&audio_path),
+            points.unwrap_or(waveform::DEFAULT_POINTS),
+            start_ms,
+            end_ms,
+        )
+    })
+    .await
+    .map_err(|e| format!("Waveform extraction failed: {}", e))?
+    .map_err(|e| format!("Waveform extraction failed: {}", e))
+}
+
+/// Record a few seconds and report noise floor, SNR, clipping and reverberation with
+/// setup recommendations, before the real interview starts
+#[tauri::command]
+async fn analyze_environment(
+    device_name: Option<String>,
+    duration_ms: Option<u32>,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<audio_diagnostics::EnvironmentReport, String> {
+    let device_name = {
+        let manager_guard = state.lock().unwrap();
+        let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+        if !matches!(manager.get_state(), recording_manager::RecordingState::Idle) {
+            return Err("Cannot analyze the environment while recording".to_string());
+        }
+        device_name.or_else(|| manager.get_selected_device())
+    };
+    let duration_ms = duration_ms.unwrap_or(5000).clamp(1000, 30000);
+
+    log::info!("[Audio] Analyzing environment on {:?} for {}ms", device_name, duration_ms);
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        let (samples, sample_rate, device) = audio_diagnostics::capture(device_name.as_deref(), duration_ms)?;
+        Ok::<_, anyhow::Error>(audio_diagnostics::analyze_environment(&samples, sample_rate, device))
+    })
+    .await
+    .map_err(|e| format!("Environment analysis failed: {}", e))?
+    .map_err(|e| format!("Environment analysis failed: {}", e))?;
+
+    log::info!(
+        "[Audio] Noise floor {:.1} dBFS, SNR {:.1} dB, {} recommendation(s)",
+        report.noise_floor_dbfs, report.snr_db, report.recommendations.len()
+    );
+    Ok(report)
+}
+
+/// System and process memory, Whisper models loaded (with reuse counts) and, for each
+/// model next to the current one, the memory it needs and whether it fits
+#[tauri::command]
+async fn get_memory_diagnostics(
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<memory_diagnostics::MemoryReport, String> {
+    let models_dir = state
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|manager| manager.model_path().parent().map(|dir| dir.to_path_buf()));
+    let report = memory_diagnostics::report(models_dir.as_deref());
+    log::info!(
+        "[Memory] Available {:?} bytes, {} model(s) loaded, {} on disk",
+        report.available_memory_bytes, report.loaded_models.len(), report.models.len()
+    );
+    Ok(report)
+}
+
+/// Space used by recordings, Whisper models and artifacts, and how much of it the
+/// next cleanup of stale temporary files and orphaned sessions would free
+#[tauri::command]
+async fn get_storage_usage(app: tauri::AppHandle) -> Result<storage_gc::StorageUsage, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let roots = storage_gc::StorageRoots::new(&recording_manager::recordings_dir(&app), &app_data_dir);
+    tauri::async_runtime::spawn_blocking(move || storage_gc::usage(&roots, &storage_gc::GcRules::default()))
+        .await
+        .map_err(|e| format!("Storage usage task failed: {}", e))
+}
+
+/// Provider policy of the workspace (LLM_POLICY_FILE or `llm-policy.json`) and whether
+/// the current LLM configuration complies with it
+#[tauri::command]
+async fn get_llm_policy() -> Result<provider_policy::PolicyReport, String> {
+    let (path, policy) = provider_policy::load().map_err(|e| format!("{:#}", e))?;
+    let violation = llm_router::LlmProvider::from_env().err().map(|e| format!("{:#}", e));
+    Ok(provider_policy::PolicyReport {
+        path: path.map(|path| path.display().to_string()),
+        policy,
+        violation,
+    })
+}
+
+/// Whisper models published on Hugging Face, with the ones already downloaded to the app
+/// data directory. Offline, only the installed models are listed.
+#[tauri::command]
+async fn list_whisper_models(app: tauri::AppHandle) -> Result<Vec<model_manager::WhisperModelInfo>, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let catalog = match provider_policy::load().and_then(|(_, policy)| policy.check_network("the model catalog")) {
+        Ok(()) => model_manager::fetch_catalog().await,
+        Err(e) => Err(e),
+    }
+    .unwrap_or_else(|e| {
+        log::warn!("[Models] Catalog unavailable, listing installed models only: {}", e);
+        Vec::new()
+    });
+    Ok(model_manager::merge_installed(catalog, &model_manager::models_dir(&app_data_dir)))
+}
+
+/// Download a Whisper model (e.g. "ggml-small.bin") into the app data directory, with
+/// "model-download-progress" events, and verify its checksum. Returns the installed model.
+#[tauri::command]
+async fn download_whisper_model(name: String, app: tauri::AppHandle) -> Result<model_manager::WhisperModelInfo, String> {
+    use tauri::Emitter;
+
+    model_manager::validate_model_name(&name).map_err(|e| e.to_string())?;
+    provider_policy::load()
+        .and_then(|(_, policy)| policy.check_network("downloading models"))
+        .map_err(|e| format!("Refused by the provider policy: {}", e))?;
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let catalog = model_manager::fetch_catalog().await.map_err(|e| format!("Failed to list models: {}", e))?;
+    let model = catalog
+        .into_iter()
+        .find(|model| model.name == name)
+        .ok_or_else(|| format!("Unknown Whisper model: {}", name))?;
+
+    let models_dir = model_manager::models_dir(&app_data_dir);
+    if let disk_space::Preflight::Insufficient(status) = disk_space::download_preflight(&models_dir, model.size_bytes.unwrap_or(0)) {
+        let _ = app.emit("disk-space-error", &status);
+        return Err(status.message());
+    }
+
+    log::info!("[Models] Downloading {} ({:?} bytes)", name, model.size_bytes);
+    let path = model_manager::download_model(&model, &models_dir, |progress| {
+        let _ = app.emit("model-download-progress", &progress);
+    })
+    .await
+    .map_err(|e| format!("Failed to download {}: {}", name, e))?;
+    Ok(model_manager::WhisperModelInfo { installed: true, path: Some(path.display().to_string()), ..model })
+}
+
+/// Persist the Whisper model used by recordings and `transcribe_audio`: an installed
+/// model name ("ggml-base.en.bin") or a model file path. Loaded on the next transcription.
+/// Returns the updated settings.
+#[tauri::command]
+async fn set_whisper_model(
+    model: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let model_path = model_manager::resolve_model(&model_manager::models_dir(&app_data_dir), &model).map_err(|e| e.to_string())?;
+
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    if model_manager::is_english_only(&model_path) && app_settings.transcription_language != "en" {
+        log::warn!("[Settings] {:?} only transcribes English, the language is '{}'", model_path, app_settings.transcription_language);
+    }
+    app_settings.whisper_model = Some(model_path.display().to_string());
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_whisper_model(model_path.clone());
+    }
+    log::info!("[Settings] Whisper model set to: {:?}", model_path);
+    Ok(app_settings)
+}
+
+/// Persist the Whisper backend ("auto", "cpu", "metal", "cuda" or "vulkan"); a GPU
+/// backend must be compiled into this build. Applied on the next transcription.
+/// Returns the updated settings.
+#[tauri::command]
+async fn set_whisper_backend(
+    backend: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    let backend: speech_to_text::WhisperBackend = backend.parse().map_err(|e: anyhow::Error| e.to_string())?;
+    let compiled = speech_to_text::compiled_gpu_backends();
+    if !matches!(backend, speech_to_text::WhisperBackend::Auto | speech_to_text::WhisperBackend::Cpu) && !compiled.contains(&backend) {
+        return Err(format!("{:?} is not available in this build (compiled GPU backends: {:?})", backend, compiled));
+    }
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.whisper_backend = backend;
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if let Some(manager) = state.lock().unwrap().as_ref() {
+        manager.set_whisper_backend(backend);
+    }
+    log::info!("[Settings] Whisper backend set to: {:?}", backend);
+    Ok(app_settings)
+}
+
+/// Backend the transcriptions run on, the GPU backends of this build and the
+/// quantization of the current model
+#[tauri::command]
+async fn get_whisper_backend(
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<speech_to_text::BackendReport, String> {
+    let manager_guard = state.lock().unwrap();
+    let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+    Ok(manager.whisper_backend())
+}
+
+/// Select the input device of the next recordings and load its saved audio profile
+/// (defaults for a device that was never tuned)
+#[tauri::command]
+async fn set_audio_device(
+    device_name: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<String, String> {
+    let app_settings = load_app_settings(&app)?;
+    let manager_guard = state.lock().unwrap();
+    let manager = manager_guard.as_ref().ok_or("Recording manager not initialized")?;
+    
+    manager.set_audio_device(device_name.clone())
+        .map_err(|e| format!("Failed to set audio device: {}", e))?;
+    manager.set_audio_profile(app_settings.audio_profile(Some(&device_name)));
+    
+    if app_settings.audio_profiles.contains_key(&device_name) {
+        Ok(format!("Audio device set to: {} (saved profile loaded)", device_name))
+    } else {
+        Ok(format!("Audio device set to: {}", device_name))
+    }
+}
+
+/// Audio profile of a device (the selected one by default): gain, AGC and enhancement
+#[tauri::command]
+async fn get_audio_profile(
+    device_name: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<audio_session::AudioProfile, String> {
+    let device_name = device_name.or_else(|| state.lock().unwrap().as_ref().and_then(|m| m.get_selected_device()));
+    Ok(load_app_settings(&app)?.audio_profile(device_name.as_deref()))
+}
+
+/// Persist the audio profile of a device (the selected one by default, or the system
+/// default device when none is selected) and apply it if that device is in use.
+/// Returns the updated settings.
+#[tauri::command]
+async fn save_audio_profile(
+    profile: audio_session::AudioProfile,
+    device_name: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<Option<recording_manager::RecordingManager>>>>,
+) -> Result<settings::AppSettings, String> {
+    profile.validate().map_err(|e| e.to_string())?;
+
+    let selected = state.lock().unwrap().as_ref().and_then(|m| m.get_selected_device());
+    let key = device_name
+        .or_else(|| selected.clone())
+        .unwrap_or_else(|| settings::DEFAULT_DEVICE_PROFILE.to_string());
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut app_settings = settings::load(&app_data_dir).map_err(|e| format!("Failed to load settings: {}", e))?;
+    app_settings.audio_profiles.insert(key.clone(), profile.clone());
+    settings::save(&app_data_dir, &app_settings).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if key == selected.as_deref().unwrap_or(settings::DEFAULT_DEVICE_PROFILE) {
+        if let Some(manager) = state.lock().unwrap().as_ref() {
+            manager.set_audio_profile(profile);
+        }
+    }
+    log::info!("[Settings] Audio profile saved for device: {}", key);
+    Ok(app_settings)
+}
+
+#[tauri::command]
+async fn save_interview_state(
+    project_name: String,
+    state_json: String,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    use std::fs;
+
+    log::info!("[Interview] Saving interview state for project: {}", project_name);
+
+    // Get app data directory
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    
+    // Create directory if it doesn't exist
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    // Create filename from project name (sanitized)
+    let sanitized_name = sanitize_project_name(&project_name);
+    
+    let file_path = app_data_dir.join(format!("{}.md", sanitized_name));
+
+    // Parse the JSON state to create a nice markdown format
+    let state: serde_json::Value = serde_json::from_str(&state_json)
+        .map_err(|e| format!("Failed to parse state JSON: {}", e))?;
+
+    let mut markdown = format!("# Interview: {}\n\n", project_name);
+    markdown.push_str(&format!("*Dernière mise à jour: {}*\n\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")));
+    markdown.push_str("---\n\n");
+
+    // Add answers grouped by section
+    if let Some(answers) = state["answers"].as_array() {
+        let mut current_section_id: i64 = -1;
+
+        for answer in answers {
+            let section_id = answer["sectionId"].as_i64().unwrap_or(-1);
+            let question = answer["question"].as_str().unwrap_or("");
+            let answer_text = answer["answer"].as_str().unwrap_or("");
+
+            // Get section title from the sections array
+            if section_id != current_section_id {
+                current_section_id = section_id;
+                // Find section title
+                if let Some(sections) = state["sections"].as_array() {
+                    if let Some(section) = sections.iter().find(|s| s["id"].as_i64() == Some(section_id)) {
+                        let section_title = section["title"].as_str().unwrap_or("");
+                        markdown.push_str(&format!("## {}\n\n", section_title));
+                    }
+                }
+            }
+
+            markdown.push_str(&format!("**Q:** {}\n\n", question));
+            markdown.push_str(&format!("**R:** {}\n\n", answer_text));
+            markdown.push_str("---\n\n");
+        }
+    }
+
+    // Write markdown file
+    safe_write::write(&file_path, markdown)
+        .map_err(|e| format!("Failed to write markdown file: {}", e))?;
+
+    // Also save raw JSON for loading
+    let json_path = app_data_dir.join(format!("{}.json", sanitized_name));
+    safe_write::write(&json_path, &state_json)
+        .map_err(|e| format!("Failed to write JSON file: {}", e))?;
+
+    log::info!("[Interview] State saved to: {:?} (markdown) and {:?} (json)", file_path, json_path);
+    Ok(format!("État sauvegardé dans {}", file_path.display()))
+}
+
+#[tauri::command]
+async fn load_interview_state(
+    project_name: String,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    use std::fs;
+
+    log::info!("[Interview] Loading interview state for project: {}", project_name);
+
+    // Get app data directory
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    // Create filename from project name (sanitized)
+    let sanitized_name = sanitize_project_name(&project_name);
+    
+    let file_path = app_data_dir.join(format!("{}.json", sanitized_name));
+
+    // Check if file exists
+    if !file_path.exists() {
+        return Err(format!("Aucune sauvegarde trouvée pour le projet '{}'", project_name));
+    }
+
+    // Read the JSON file
+    let json_content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    log::info!("[Interview] State loaded from: {:?}", file_path);
+    Ok(json_content)
+}
+
+#[tauri::command]
+async fn list_saved_projects(
+    app: tauri::AppHandle,
+) -> Result<Vec<String>, String> {
+    use std::fs;
+
+    log::info!("[Interview] Listing saved projects");
+
+    // Get app data directory
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    // Create directory if it doesn't exist
+    if !app_data_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    // Read directory and collect .json files
+    let entries = fs::read_dir(&app_data_dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    let projects: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()?.to_str()? == "json" {
+                path.file_stem()?.to_str().map(String::from)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    log::info!("[Interview] Found {} saved projects", projects.len());
+    Ok(projects)
+}
+
+/// Generate the canvas content of a section; `context` carries the sections generated
+/// before it so the canvas does not contradict itself
+#[tauri::command]
+async fn process_interview_section(
+    section: interview::InterviewSection,
+    context: Option<interview::InterviewContext>,
+) -> Result<interview::SectionCanvasResult, String> {
+    use crate::interview::InterviewProcessor;
+
+    log::info!("[Interview] Processing section: {}", section.section_title);
+    
+    let processor = InterviewProcessor::new()
+        .map_err(|e| {
+            log::error!("[Interview] Failed to initialize processor: {}", e);
+            format!("Failed to initialize interview processor: {}", e)
+        })?;
+    
+    processor.process_section(section, context.as_ref())
+        .await
+        .map_err(|e| {
+            log::error!("[Interview] Failed to process section: {}", e);
+            format!("Failed to process section: {}", e)
+        })
+}
+
+/// Rate an interview answer (completeness and specificity, 0 to 3 each) so the user can
+/// be asked to elaborate before the section is processed
+#[tauri::command]
+async fn score_answer(question: String, answer: String) -> Result<answer_quality::AnswerScore, String> {
+    use crate::interview::InterviewProcessor;
+
+    let processor = InterviewProcessor::new()
+        .map_err(|e| format!("Failed to initialize interview processor: {}", e))?;
+    processor.score_answer(&question, &answer)
+        .await
+        .map_err(|e| {
+            log::error!("[Interview] Failed to score answer: {}", e);
+            format!("Failed to score answer: {}", e)
+        })
+}
+
+#[tauri::command]
+async fn generate_full_canvas(
+    sections: Vec<interview::SectionCanvasResult>,
+    risk_register: Option<risk_register::RiskRegister>,
+) -> Result<interview::FullCanvasResult, String> {
+    use crate::interview::InterviewProcessor;
+
+    log::info!("[Interview] Generating full canvas from {} sections", sections.len());
+    
+    let processor = InterviewProcessor::new()
+        .map_err(|e| {
+            log::error!("[Interview] Failed to initialize processor: {}", e);
+            format!("Failed to initialize interview processor: {}", e)
+        })?;
+    
+    processor.generate_full_canvas(sections, risk_register.as_ref())
+        .await
+        .map_err(|e| {
+            log::error!("[Interview] Failed to generate canvas: {}", e);
+            format!("Failed to generate canvas: {}", e)
+        })
+}
+
+fn register_store(app: &tauri::AppHandle, project_name: &str) -> Result<risk_register::RegisterStore, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(risk_register::RegisterStore::open(&app_data_dir, project_name))
+}
+
+/// Extract assumptions and risks from the interview answers into the project's register
+///
+/// New entries are merged into the stored register; entries already present (and the
+/// impact/likelihood the user may have adjusted) are kept.
+#[tauri::command]
+async fn extract_risk_register(
+    app: tauri::AppHandle,
+    project_name: String,
+    sections: Vec<interview::InterviewSection>,
+) -> Result<risk_register::RiskRegister, String> {
+    use crate::interview::InterviewProcessor;
+
+    log::info!("[Risks] Extracting risk register for project: {}", project_name);
+
+    let processor = InterviewProcessor::new()
+        .map_err(|e| format!("Failed to initialize interview processor: {}", e))?;
+    let extracted = processor.extract_risks(&sections)
+        .await
+        .map_err(|e| {
+            log::error!("[Risks] Extraction failed: {}", e);
+            format!("Failed to extract risks: {}", e)
+        })?;
+
+    let store = register_store(&app, &project_name)?;
+    let mut register = store.load().map_err(|e| format!("Failed to load risk register: {}", e))?;
+    let added = register.merge(extracted);
+    store.save(&register).map_err(|e| format!("Failed to save risk register: {}", e))?;
+
+    log::info!("[Risks] {} new entries, {} in register", added, register.entries.len());
+    Ok(register)
+}
+
+#[tauri::command]
+async fn load_risk_register(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<risk_register::RiskRegister, String> {
+    register_store(&app, &project_name)?
+        .load()
+        .map_err(|e| format!("Failed to load risk register: {}", e))
+}
+
+/// Store the register as edited by the user (impact, likelihood, mitigation...)
+#[tauri::command]
+async fn save_risk_register(
+    app: tauri::AppHandle,
+    project_name: String,
+    register: risk_register::RiskRegister,
+) -> Result<(), String> {
+    log::info!("[Risks] Saving {} register entries for project: {}", register.entries.len(), project_name);
+    register_store(&app, &project_name)?
+        .save(&register)
+        .map_err(|e| format!("Failed to save risk register: {}", e))
+}
+
+fn kpi_store(app: &tauri::AppHandle, project_name: &str) -> Result<kpi_catalog::KpiStore, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(kpi_catalog::KpiStore::open(&app_data_dir, project_name))
+}
+
+/// Turn the canvas "Tests de domaine & KPI" section into the project's KPI catalog
+///
+/// Each KPI gets a formula over the model's entities and attributes; references
+/// missing from the model are reported in `issues`.
+#[tauri::command]
+async fn extract_kpi_catalog(
+    app: tauri::AppHandle,
+    project_name: String,
+    kpi_section: String,
+    model: Value,
+) -> Result<kpi_catalog::KpiCatalog, String> {
+    use crate::interview::InterviewProcessor;
+
+    log::info!("[KPI] Extracting KPI catalog for project: {}", project_name);
+
+    let processor = InterviewProcessor::new()
+        .map_err(|e| format!("Failed to initialize interview processor: {}", e))?;
+    let kpis = processor.extract_kpis(&kpi_section, &model)
+        .await
+        .map_err(|e| {
+            log::error!("[KPI] Extraction failed: {}", e);
+            format!("Failed to extract KPIs: {}", e)
+        })?;
+
+    let catalog = kpi_catalog::KpiCatalog::new(kpis, &model);
+    kpi_store(&app, &project_name)?
+        .save(&catalog)
+        .map_err(|e| format!("Failed to save KPI catalog: {}", e))?;
+
+    log::info!("[KPI] {} KPIs, {} reference issue(s)", catalog.kpis.len(), catalog.issues.len());
+    Ok(catalog)
+}
+
+#[tauri::command]
+async fn load_kpi_catalog(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<kpi_catalog::KpiCatalog, String> {
+    kpi_store(&app, &project_name)?
+        .load()
+        .map_err(|e| format!("Failed to load KPI catalog: {}", e))
+}
+
+/// Store KPIs edited by the user, re-validated against the model
+#[tauri::command]
+async fn save_kpi_catalog(
+    app: tauri::AppHandle,
+    project_name: String,
+    kpis: Vec<kpi_catalog::KpiRecord>,
+    model: Value,
+) -> Result<kpi_catalog::KpiCatalog, String> {
+    log::info!("[KPI] Saving {} KPIs for project: {}", kpis.len(), project_name);
+    let catalog = kpi_catalog::KpiCatalog::new(kpis, &model);
+    kpi_store(&app, &project_name)?
+        .save(&catalog)
+        .map_err(|e| format!("Failed to save KPI catalog: {}", e))?;
+    Ok(catalog)
+}
+
+/// Export the project's KPI catalog as `<project>_kpis.md` or `<project>_kpis.csv`
+#[tauri::command]
+async fn export_kpi_catalog(
+    app: tauri::AppHandle,
+    project_name: String,
+    format: String,
+) -> Result<String, String> {
+    let catalog = kpi_store(&app, &project_name)?
+        .load()
+        .map_err(|e| format!("Failed to load KPI catalog: {}", e))?;
+    let (content, extension) = match format.as_str() {
+        "markdown" | "md" => match current_model_semver(&app, &project_name) {
+            Some(semver) => (model_semver::tag_markdown(&catalog.to_markdown(), &semver), "md"),
+            None => (catalog.to_markdown(), "md"),
+        },
+        "csv" => (catalog.to_csv(), "csv"),
+        other => return Err(format!("Unsupported KPI catalog format '{}'", other)),
+    };
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let sanitized_name = sanitize_project_name(&project_name);
+    let file_path = app_data_dir.join(format!("{}_kpis.{}", sanitized_name, extension));
+    safe_write::write(&file_path, content)
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    log::info!("[KPI] Catalog exported to: {:?}", file_path);
+    Ok(format!("Catalogue KPI exporté dans {:?}", file_path))
+}
+
+/// Export the canvas as a slide deck to present the workshop outcome: `<project>_slides.md`
+/// ("marp") or `<project>_slides.html` ("reveal"), one slide per section, the mermaid
+/// blocks of the canvas and the domain model diagram on their own slides
+#[tauri::command]
+async fn export_slides(
+    app: tauri::AppHandle,
+    project_name: String,
+    markdown: String,
+    mermaid: Option<String>,
+    format: String,
+) -> Result<String, String> {
+    let format: slides::SlideFormat = format.parse().map_err(|e: anyhow::Error| e.to_string())?;
+    let deck = slides::canvas_slides(&markdown, mermaid.as_deref());
+    if deck.is_empty() {
+        return Err("The canvas is empty".to_string());
+    }
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let sanitized_name = sanitize_project_name(&project_name);
+    let file_path = app_data_dir.join(format!("{}_slides.{}", sanitized_name, format.extension()));
+    safe_write::write(&file_path, slides::render(format, &project_name, &deck))
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    log::info!("[Interview] Slides exported to: {:?} ({} slides)", file_path, deck.len());
+    Ok(format!("Présentation exportée dans {:?}", file_path))
+}
+
+/// Export the ubiquitous language as `<project>_glossary.csv` or `<project>_glossary.tbx`
+/// (TermBase eXchange, for translation tools): the terms of the canvas table, completed
+/// with the synonyms of the project glossary
+#[tauri::command]
+async fn export_glossary(
+    app: tauri::AppHandle,
+    project_name: String,
+    format: String,
+    markdown: Option<String>,
+    glossary: Option<Vec<glossary_export::GlossaryEntry>>,
+) -> Result<String, String> {
+    let format: glossary_export::GlossaryFormat = format.parse().map_err(|e: anyhow::Error| e.to_string())?;
+    let entries = glossary_export::merge(
+        glossary_export::parse_table(&project_canvas(&app, &project_name, markdown)?),
+        glossary.unwrap_or_default(),
+    );
+    if entries.is_empty() {
+        return Err("The glossary is empty".to_string());
+    }
+    let content = match format {
+        glossary_export::GlossaryFormat::Csv => glossary_export::to_csv(&entries),
+        glossary_export::GlossaryFormat::Tbx => {
+            let language = glossary_export::glossary_language(&load_app_settings(&app)?.transcription_language, &entries);
+            glossary_export::to_tbx(&entries, &format!("Glossaire — {}", project_name), &language)
+        }
+    };
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let sanitized_name = sanitize_project_name(&project_name);
+    let file_path = app_data_dir.join(format!("{}_glossary.{}", sanitized_name, format.extension()));
+    safe_write::write(&file_path, content)
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    log::info!("[Interview] Glossary exported to: {:?} ({} terms)", file_path, entries.len());
+    Ok(format!("Glossaire exporté dans {:?}", file_path))
+}
+
+/// Plain-language narration of a model, read back to the domain expert to validate it
+/// ("Each Customer places zero or more Orders..."); with `speak`, also read aloud by the
+/// speech synthesizer of the system
+#[tauri::command]
+async fn narrate_model(
+    app: tauri::AppHandle,
+    model: Value,
+    language: Option<String>,
+    speak: Option<bool>,
+) -> Result<narration::ModelNarration, String> {
+    let setting = load_app_settings(&app)?.transcription_language;
+    let language = narration::narration_language(language.as_deref(), &setting, &model);
+    let sentences = narration::narrate(&model, &language);
+    if sentences.is_empty() {
+        return Err("The model is empty".to_string());
+    }
+    let text = sentences.join("\n");
+
+    let spoken = speak.unwrap_or(false);
+    if spoken {
+        narration::speak(&text, &language).map_err(|e| format!("Failed to read the narration aloud: {}", e))?;
+    }
+    log::info!("[Interview] Model narrated in {} ({} sentences, spoken: {})", language, sentences.len(), spoken);
+    Ok(narration::ModelNarration { language, sentences, text, spoken })
+}
+
+#[tauri::command]
+async fn save_canvas_markdown(
+    app: tauri::AppHandle,
+    project_name: String,
+    markdown: String,
+) -> Result<String, String> {
+    use std::fs;
+
+    log::info!("[Interview] Saving canvas markdown for project: {}", project_name);
+
+    // Get app data directory
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    // Create directory if it doesn't exist
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    // Create filename from project name (sanitized)
+    let sanitized_name = sanitize_project_name(&project_name);
+    
+    let file_path = app_data_dir.join(format!("{}_canvas.md", sanitized_name));
+    let markdown = match current_model_semver(&app, &project_name) {
+        Some(semver) => model_semver::tag_markdown(&markdown, &semver),
+        None => markdown,
+    };
+
+    // Write the markdown file
+    safe_write::write(&file_path, &markdown)
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    if let Err(e) = artifact_store(&app, &project_name)?.put("canvas.md", "markdown", markdown.as_bytes()) {
+        log::warn!("[Interview] Canvas not recorded in the artifact store: {}", e);
+    }
+
+    log::info!("[Interview] Canvas saved to: {:?}", file_path);
+    Ok(format!("Canvas sauvegardé dans {:?}", file_path))
+}
+
+/// Record that the facilitator moved to another interview section (`section_id: None`
+/// when the interview is left), to measure the time spent per section
+#[tauri::command]
+async fn record_section_change(
+    app: tauri::AppHandle,
+    project_name: String,
+    section_id: Option<u32>,
+    section_title: Option<String>,
+) -> Result<(), String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let event = interview_analytics::SectionEvent {
+        section_id,
+        section_title,
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+    };
+    interview_analytics::AnalyticsStore::open(&app_data_dir, &project_name)
+        .record(event)
+        .map_err(|e| format!("Failed to record section change: {}", e))
+}
+
+/// Time spent per interview section, for one project or across all of them
+#[tauri::command]
+async fn get_interview_analytics(
+    app: tauri::AppHandle,
+    project_name: Option<String>,
+) -> Result<interview_analytics::InterviewAnalytics, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let streams = match project_name {
+        Some(project_name) => {
+            let events = interview_analytics::AnalyticsStore::open(&app_data_dir, &project_name)
+                .load()
+                .map_err(|e| format!("Failed to load interview analytics: {}", e))?;
+            vec![(project_name, events)]
+        }
+        None => interview_analytics::load_all(&app_data_dir)
+            .map_err(|e| format!("Failed to load interview analytics: {}", e))?,
+    };
+    Ok(interview_analytics::summarize(&streams))
+}
+
+#[tauri::command]
+fn list_model_templates() -> Vec<templates::ModelTemplate> {
+    templates::list_templates()
+}
+
+#[tauri::command]
+fn load_model_template(name: String) -> Result<Value, String> {
+    log::info!("[Templates] Loading model template: {}", name);
+    templates::load_template(&name).map_err(|e| e.to_string())
+}
+
+/// Open the few-shot example store in the app data directory
+fn few_shot_store(app: &tauri::AppHandle) -> Result<few_shot::FewShotStore, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    few_shot::FewShotStore::load(&app_data_dir)
+        .map_err(|e| format!("Failed to load few-shot examples: {}", e))
+}
+
+#[tauri::command]
+async fn list_few_shot_examples(app: tauri::AppHandle) -> Result<Vec<few_shot::FewShotExample>, String> {
+    Ok(few_shot_store(&app)?.all())
+}
+
+#[tauri::command]
+async fn add_few_shot_example(
+    app: tauri::AppHandle,
+    title: String,
+    transcript: String,
+    model: Value,
+) -> Result<few_shot::FewShotExample, String> {
+    log::info!("[FewShot] Adding example: {}", title);
+    few_shot_store(&app)?
+        .add(&title, &transcript, model)
+        .map_err(|e| format!("Failed to add few-shot example: {}", e))
+}
+
+#[tauri::command]
+async fn remove_few_shot_example(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    log::info!("[FewShot] Removing example: {}", id);
+    few_shot_store(&app)?
+        .remove(&id)
+        .map_err(|e| format!("Failed to remove few-shot example: {}", e))
+}
+
+#[tauri::command]
+async fn check_ubiquitous_language(
+    model: Value,
+    ubiquitous_language: Option<String>,
+    glossary: Option<Value>,
+) -> Result<Value, String> {
+    use crate::mcp_client::McpClient;
+
+    log::info!("[Lint] Checking ubiquitous language");
+
+    let mut arguments = serde_json::json!({ "model": model });
+    if let Some(table) = ubiquitous_language {
+        arguments["ubiquitous_language"] = Value::String(table);
+    }
+    if let Some(glossary) = glossary {
+        arguments["glossary"] = glossary;
+    }
+
+    McpClient::new(mcp_server_path())
+        .call_tool("check_ubiquitous_language", arguments)
+        .await
+        .map_err(|e| {
+            log::error!("[Lint] Ubiquitous language check failed: {}", e);
+            format!("Failed to check ubiquitous language: {}", e)
+        })
+}
+
+/// Default normalization system prompt, the usual starting point for a prompt variant
+#[tauri::command]
+fn get_default_system_prompt() -> String {
+    llm_integration::LlmIntegration::default_system_prompt().to_string()
+}
+
+/// Run the same transcript through two system prompts and compare the outputs
+///
+/// Both variants go through the same LLM provider and sampling options, concurrently, and are
+/// validated by the MCP server before being diffed.
+#[tauri::command]
+async fn compare_prompts(
+    transcript: String,
+    prompt_a: String,
+    prompt_b: String,
+    options: Option<llm_router::LlmRequestOptions>,
+) -> Result<PromptComparison, String> {
+    use crate::llm_integration::LlmIntegration;
+    use crate::mcp_client::McpClient;
+    use std::time::Instant;
+
+    log::info!("[ComparePrompts] Comparing two prompt variants ({} chars transcript)", transcript.len());
+
+    // Same options (and seed) for both variants so only the prompt differs
+    let llm_integration = LlmIntegration::new()
+        .map_err(|e| format!("Failed to initialize LLM integration: {}", e))?
+        .with_options(options.unwrap_or_default());
+    let mcp_client = McpClient::new(mcp_server_path());
+
+    let run_variant = |prompt: String| {
+        let llm_integration = &llm_integration;
+        let mcp_client = &mcp_client;
+        let transcript = &transcript;
+        async move {
+            let generation = llm_integration.generation_record();
+            let start = Instant::now();
+            let generated = llm_integration.generate_with_prompt(&prompt, transcript).await;
+            let duration_ms = start.elapsed().as_millis() as u64;
+
+            match generated {
+                Ok(model) => {
+                    let (valid, errors) = mcp_client
+                        .validation_report(model.clone())
+                        .await
+                        .unwrap_or_else(|e| (false, vec![format!("Validation failed: {}", e)]));
+                    PromptVariantResult { model, valid, errors, duration_ms, generation }
+                }
+                Err(e) => PromptVariantResult {
+                    model: Value::Null,
+                    valid: false,
+                    errors: vec![e.to_string()],
+                    duration_ms,
+                    generation,
+                },
+            }
+        }
+    };
+
+    let (a, b) = tokio::join!(run_variant(prompt_a), run_variant(prompt_b));
+    log::info!(
+        "[ComparePrompts] A: valid={} in {}ms, B: valid={} in {}ms",
+        a.valid, a.duration_ms, b.valid, b.duration_ms
+    );
+
+    let diff = if a.model.is_null() || b.model.is_null() {
+        Value::Null
+    } else {
+        mcp_client
+            .diff_models(a.model.clone(), b.model.clone())
+            .await
+            .map_err(|e| format!("Failed to diff models: {}", e))?
+    };
+
+    Ok(PromptComparison { a, b, diff })
+}
+
+/// Refine a single entity from an instruction ("split address into a value object")
+///
+/// The LLM only receives the entity and its related context and returns a
+/// minimal change set; the result is validated and returned as a JSON Patch.
+/// `previous_instructions` carries the earlier turns of the refinement chat.
+#[tauri::command]
+async fn refine_entity(
+    model: Value,
+    entity_id: String,
+    instruction: String,
+    previous_instructions: Option<Vec<String>>,
+) -> Result<RefineEntityResult, String> {
+    use crate::llm_integration::LlmIntegration;
+    use crate::mcp_client::McpClient;
+
+    log::info!("[Refine] Refining entity '{}': {}", entity_id, instruction);
+
+    let llm_integration = LlmIntegration::new()
+        .map_err(|e| format!("Failed to initialize LLM integration: {}", e))?;
+    let (patched, patch, changes) = llm_integration
+        .refine_entity(&model, &entity_id, &instruction, &previous_instructions.unwrap_or_default())
+        .await
+        .map_err(|e| {
+            log::error!("[Refine] Refinement failed: {}", e);
+            format!("Failed to refine entity: {}", e)
+        })?;
+
+    let (valid, errors) = McpClient::new(mcp_server_path())
+        .validation_report(patched.clone())
+        .await
+        .map_err(|e| format!("Failed to validate refined model: {}", e))?;
+    if !valid {
+        log::warn!("[Refine] Patch rejected, model would be invalid: {:?}", errors);
+        return Err(format!("Refinement rejected, the model would be invalid: {}", errors.join("; ")));
+    }
+
+    log::info!("[Refine] {} change(s), {} patch operation(s)", changes.len(), patch.len());
+    Ok(RefineEntityResult { patch, changes })
+}
+
+/// Concrete fixes for a model that failed validation
+///
+/// Each fix is an independent patch, so the user applies the ones they accept with
+/// `apply_model_patch` instead of regenerating the whole model. Without `errors`, every
+/// problem the MCP server can fix is reported.
+#[tauri::command]
+async fn suggest_fixes(model: Value, errors: Option<Vec<String>>) -> Result<FixSuggestions, String> {
+    use crate::mcp_client::McpClient;
+
+    log::info!("[Fixes] Suggesting fixes for {} error(s)", errors.as_ref().map_or(0, |e| e.len()));
+
+    let result = McpClient::new(mcp_server_path())
+        .suggest_fixes(model, errors)
+        .await
+        .map_err(|e| {
+            log::error!("[Fixes] Fix suggestion failed: {}", e);
+            format!("Failed to suggest fixes: {}", e)
+        })?;
+    let suggestions: FixSuggestions = serde_json::from_value(result)
+        .map_err(|e| format!("Invalid fix suggestions: {}", e))?;
+
+    log::info!("[Fixes] {} fix(es), {} unfixed error(s)", suggestions.fixes.len(), suggestions.unfixed.len());
+    Ok(suggestions)
+}
+
+/// Open the versioned model store of a project
+fn model_store(app: &tauri::AppHandle, project_name: &str) -> Result<model_store::ModelStore, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(model_store::ModelStore::open(&app_data_dir, project_name))
+}
+
+#[tauri::command]
+async fn save_model(
+    app: tauri::AppHandle,
+    project_name: String,
+    model: Value,
+    source: Option<String>,
+) -> Result<model_store::ModelVersion, String> {
+    log::info!("[Model] Saving model for project: {}", project_name);
+    model_store(&app, &project_name)?
+        .save(&model, source.as_deref().unwrap_or("save"))
+        .map_err(|e| format!("Failed to save model: {}", e))
+}
+
+#[tauri::command]
+async fn load_model(app: tauri::AppHandle, project_name: String) -> Result<Option<Value>, String> {
+    model_store(&app, &project_name)?
+        .load()
+        .map_err(|e| format!("Failed to load model: {}", e))
+}
+
+#[tauri::command]
+async fn get_model_history(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<Vec<model_store::ModelVersion>, String> {
+    model_store(&app, &project_name)?
+        .history()
+        .map_err(|e| format!("Failed to load model history: {}", e))
+}
+
+/// Semantic version of the project's current model, used to tag exported files
+fn current_model_semver(app: &tauri::AppHandle, project_name: &str) -> Option<String> {
+    model_store(app, project_name).ok()?.history().ok()?.iter().rev().find_map(|v| v.semver.clone())
+}
+
+/// Latest version of the project's model: semantic version, suggested bump and
+/// the breaking changes that justified it
+#[tauri::command]
+async fn get_model_version(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<Option<model_store::ModelVersion>, String> {
+    let history = model_store(&app, &project_name)?
+        .history()
+        .map_err(|e| format!("Failed to load model history: {}", e))?;
+    Ok(history.last().cloned())
+}
+
+#[tauri::command]
+async fn load_model_version(app: tauri::AppHandle, project_name: String, version: u32) -> Result<Value, String> {
+    model_store(&app, &project_name)?
+        .load_version(version)
+        .map_err(|e| format!("Failed to load model version: {}", e))
+}
+
+/// Stakeholder email summing up the project (key decisions, model highlights, open
+/// questions, next steps), drafted from its canvas, model, interview answers and risk
+/// register in the project's language. Returned for copy/paste, with a `mailto:` link.
+#[tauri::command]
+async fn draft_summary_email(app: tauri::AppHandle, project_name: String) -> Result<summary_email::SummaryEmail, String> {
+    use crate::llm_integration::LlmIntegration;
+
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let sanitized_name = sanitize_project_name(&project_name);
+
+    let sources = summary_email::EmailSources {
+        canvas: project_canvas(&app, &project_name, None).ok(),
+        model: model_store(&app, &project_name)?
+            .load()
+            .map_err(|e| format!("Failed to load model: {}", e))?,
+        // Markdown written by save_interview_state
+        interview: std::fs::read_to_string(app_data_dir.join(format!("{}.md", sanitized_name))).ok(),
+        risks: register_store(&app, &project_name)?
+            .load()
+            .map_err(|e| format!("Failed to load risk register: {}", e))?,
+    };
+    if sources.is_empty() {
+        return Err(format!("Aucun canvas, modèle ni interview pour le projet '{}'", project_name));
+    }
+
+    let language = summary_email::email_language(&load_app_settings(&app)?.transcription_language, &sources);
+    log::info!("[Email] Drafting summary email for project {} ({})", project_name, language);
+    let llm_integration = LlmIntegration::new()
+        .map_err(|e| format!("Failed to initialize LLM integration: {}", e))?;
+    llm_integration
+        .draft_summary_email(&project_name, &language, &sources)
+        .await
+        .map_err(|e| {
+            log::error!("[Email] Failed to draft summary email: {}", e);
+            format!("Failed to draft summary email: {}", e)
+        })
+}
+
+/// Short quiz (questions with expected answers) checking that the team absorbed the
+/// domain after the workshop, from the model and the canvas given or last saved
+#[tauri::command]
+async fn generate_quiz(
+    app: tauri::AppHandle,
+    project_name: String,
+    model: Option<Value>,
+    markdown: Option<String>,
+    count: Option<usize>,
+) -> Result<quiz::Quiz, String> {
+    use crate::llm_integration::LlmIntegration;
+
+    let model = match model {
+        Some(model) => Some(model),
+        None => model_store(&app, &project_name)?
+            .load()
+            .map_err(|e| format!("Failed to load model: {}", e))?,
+    };
+    let canvas = project_canvas(&app, &project_name, markdown).ok();
+    if model.is_none() && canvas.is_none() {
+        return Err(format!("Aucun modèle ni canvas pour le projet '{}'", project_name));
+    }
+
+    let count = count.unwrap_or(quiz::DEFAULT_QUESTION_COUNT).clamp(1, quiz::MAX_QUESTION_COUNT);
+    let language = quiz::quiz_language(&load_app_settings(&app)?.transcription_language, canvas.as_deref());
+    log::info!("[Quiz] Generating {} questions for project {} ({})", count, project_name, language);
+    let llm_integration = LlmIntegration::new()
+        .map_err(|e| format!("Failed to initialize LLM integration: {}", e))?;
+    llm_integration
+        .generate_quiz(&project_name, &language, count, model.as_ref(), canvas.as_deref())
+        .await
+        .map_err(|e| {
+            log::error!("[Quiz] Failed to generate quiz: {}", e);
+            format!("Failed to generate quiz: {}", e)
+        })
+}
+
+/// Export a quiz (as generated, or edited afterwards) as `<project>_quiz.md`, the answers
+/// folded under each question
+#[tauri::command]
+async fn export_quiz(app: tauri::AppHandle, project_name: String, quiz: quiz::Quiz) -> Result<String, String> {
+    if quiz.questions.is_empty() {
+        return Err("The quiz has no question".to_string());
+    }
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let sanitized_name = sanitize_project_name(&project_name);
+    let file_path = app_data_dir.join(format!("{}_quiz.md", sanitized_name));
+    safe_write::write(&file_path, quiz.to_markdown())
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    log::info!("[Quiz] Quiz exported to: {:?} ({} questions)", file_path, quiz.questions.len());
+    Ok(format!("Quiz exporté dans {:?}", file_path))
+}
+
+/// Canvas given by the frontend, or the one last saved for the project
+fn project_canvas(app: &tauri::AppHandle, project_name: &str, markdown: Option<String>) -> Result<String, String> {
+    if let Some(markdown) = markdown.filter(|m| !m.trim().is_empty()) {
+        return Ok(markdown);
+    }
+    artifact_store(app, project_name)?
+        .get("canvas.md")
+        .map_err(|e| format!("Failed to read canvas: {}", e))?
+        .and_then(|content| String::from_utf8(content).ok())
+        .ok_or_else(|| format!("Aucun canvas sauvegardé pour le projet '{}'", project_name))
+}
+
+/// Dry run of `push_tickets`: the ticket drafts of the canvas (Top 5 use cases with their
+/// Given-When-Then scenarios as acceptance criteria, then the roadmap steps)
+#[tauri::command]
+async fn preview_tickets(app: tauri::AppHandle, project_name: String, markdown: Option<String>) -> Result<Vec<tickets::TicketDraft>, String> {
+    let drafts = tickets::drafts_from_canvas(&project_canvas(&app, &project_name, markdown)?);
+    log::info!("[Tickets] {} drafts for project {}", drafts.len(), project_name);
+    Ok(drafts)
+}
+
+/// Create the tickets in the tracker of the settings: the drafts given (as edited after
+/// `preview_tickets`), or those of the canvas
+#[tauri::command]
+async fn push_tickets(
+    app: tauri::AppHandle,
+    project_name: String,
+    drafts: Option<Vec<tickets::TicketDraft>>,
+    markdown: Option<String>,
+) -> Result<Vec<tickets::PushedTicket>, String> {
+    let tracker = tickets::TicketTracker::from_settings(&load_app_settings(&app)?.ticket_tracker).map_err(|e| e.to_string())?;
+    let drafts = match drafts {
+        Some(drafts) => drafts,
+        None => tickets::drafts_from_canvas(&project_canvas(&app, &project_name, markdown)?),
+    };
+    if drafts.is_empty() {
+        return Err("No ticket to push".to_string());
+    }
+    log::info!("[Tickets] Pushing {} tickets for project {}", drafts.len(), project_name);
+    tracker.push(&drafts).await.map_err(|e| format!("Failed to push tickets: {}", e))
+}
+
+/// Human-readable changelog between two stored versions of the project's model
+///
+/// The structural diff gives one line per change and the LLM writes them up (the
+/// plain list is kept if it is unavailable). The section is appended to the
+/// project's exported canvas, or replaces the one of an earlier run for the same versions.
+#[tauri::command]
+async fn generate_changelog(
+    app: tauri::AppHandle,
+    project_name: String,
+    from: u32,
+    to: u32,
+) -> Result<changelog::ModelChangelog, String> {
+    use crate::llm_integration::LlmIntegration;
+    use crate::mcp_client::McpClient;
 
-    log::info!("[Interview] Saving interview state for project: {}", project_name);
+    log::info!("[Changelog] Project {}: v{} → v{}", project_name, from, to);
+
+    let store = model_store(&app, &project_name)?;
+    let before = store
+        .load_version(from)
+        .map_err(|e| format!("Failed to load model version: {}", e))?;
+    let after = store
+        .load_version(to)
+        .map_err(|e| format!("Failed to load model version: {}", e))?;
+    let diff = McpClient::new(mcp_server_path())
+        .diff_models(before.clone(), after.clone())
+        .await
+        .map_err(|e| format!("Failed to diff models: {}", e))?;
+    let changes = changelog::describe_diff(&diff);
+
+    let summary = if changes.is_empty() {
+        None
+    } else {
+        let summarized = match LlmIntegration::new() {
+            Ok(llm_integration) => llm_integration.summarize_changelog(&diff, &before, &after, &changes).await,
+            Err(e) => Err(e),
+        };
+        summarized
+            .map_err(|e| log::warn!("[Changelog] No LLM summary, keeping the change list: {}", e))
+            .ok()
+    };
+    let markdown = changelog::to_markdown(from, to, &changes, summary.as_deref());
 
-    // Get app data directory
     let app_data_dir = app.path()
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    // Create directory if it doesn't exist
-    fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    let sanitized_name = sanitize_project_name(&project_name);
+    let canvas_path = app_data_dir.join(format!("{}_canvas.md", sanitized_name));
+    let canvas = match std::fs::read_to_string(&canvas_path) {
+        Ok(canvas) => canvas,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read canvas: {}", e)),
+    };
+    let canvas = changelog::insert_section(&canvas, from, to, &markdown);
+    let canvas = match current_model_semver(&app, &project_name) {
+        Some(semver) => model_semver::tag_markdown(&canvas, &semver),
+        None => canvas,
+    };
+    safe_write::write(&canvas_path, &canvas)
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    if let Err(e) = artifact_store(&app, &project_name)?.put("canvas.md", "markdown", canvas.as_bytes()) {
+        log::warn!("[Changelog] Canvas not recorded in the artifact store: {}", e);
+    }
 
-    // Create filename from project name (sanitized)
-    let sanitized_name = project_name
-        .chars()
-        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
-        .collect::<String>();
-    
-    let file_path = app_data_dir.join(format!("{}.md", sanitized_name));
+    log::info!("[Changelog] {} change(s) appended to {:?}", changes.len(), canvas_path);
+    Ok(changelog::ModelChangelog { from, to, changes, summary, markdown })
+}
 
-    // Parse the JSON state to create a nice markdown format
-    let state: serde_json::Value = serde_json::from_str(&state_json)
-        .map_err(|e| format!("Failed to parse state JSON: {}", e))?;
+/// Apply an RFC 6902 JSON Patch to the project's current model
+///
+/// The patch is applied atomically, the result validated, then stored as a
+/// new version of the model history.
+#[tauri::command]
+async fn apply_model_patch(
+    app: tauri::AppHandle,
+    project_name: String,
+    patch: Vec<json_patch::PatchOperation>,
+    source: Option<String>,
+) -> Result<ApplyPatchResult, String> {
+    use crate::mcp_client::McpClient;
 
-    let mut markdown = format!("# Interview: {}\n\n", project_name);
-    markdown.push_str(&format!("*Dernière mise à jour: {}*\n\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")));
-    markdown.push_str("---\n\n");
+    log::info!("[Model] Applying {} patch operation(s) to project: {}", patch.len(), project_name);
 
-    // Add answers grouped by section
-    if let Some(answers) = state["answers"].as_array() {
-        let mut current_section_id: i64 = -1;
+    let store = model_store(&app, &project_name)?;
+    let current = store
+        .load()
+        .map_err(|e| format!("Failed to load model: {}", e))?
+        .ok_or_else(|| format!("No model saved for project '{}'", project_name))?;
+    let model = json_patch::apply(&current, &patch).map_err(|e| format!("Failed to apply patch: {:#}", e))?;
 
-        for answer in answers {
-            let section_id = answer["sectionId"].as_i64().unwrap_or(-1);
-            let question = answer["question"].as_str().unwrap_or("");
-            let answer_text = answer["answer"].as_str().unwrap_or("");
+    let (valid, errors) = McpClient::new(mcp_server_path())
+        .validation_report(model.clone())
+        .await
+        .map_err(|e| format!("Failed to validate patched model: {}", e))?;
+    if !valid {
+        log::warn!("[Model] Patch rejected, model would be invalid: {:?}", errors);
+        return Err(format!("Patch rejected, the model would be invalid: {}", errors.join("; ")));
+    }
 
-            // Get section title from the sections array
-            if section_id != current_section_id {
-                current_section_id = section_id;
-                // Find section title
-                if let Some(sections) = state["sections"].as_array() {
-                    if let Some(section) = sections.iter().find(|s| s["id"].as_i64() == Some(section_id)) {
-                        let section_title = section["title"].as_str().unwrap_or("");
-                        markdown.push_str(&format!("## {}\n\n", section_title));
-                    }
-                }
-            }
+    let version = store
+        .save(&model, source.as_deref().unwrap_or("patch"))
+        .map_err(|e| format!("Failed to save model: {}", e))?;
+    log::info!("[Model] Project {} now at version {}", project_name, version.version);
+    Ok(ApplyPatchResult { model, version })
+}
 
-            markdown.push_str(&format!("**Q:** {}\n\n", question));
-            markdown.push_str(&format!("**R:** {}\n\n", answer_text));
-            markdown.push_str("---\n\n");
-        }
-    }
+fn artifact_store(app: &tauri::AppHandle, project_name: &str) -> Result<artifact_store::ArtifactStore, String> {
+    let app_data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(artifact_store::ArtifactStore::open(&app_data_dir, project_name))
+}
 
-    // Write markdown file
-    fs::write(&file_path, markdown)
-        .map_err(|e| format!("Failed to write markdown file: {}", e))?;
+/// Generated artifact handed over by the frontend (markdown, mermaid...)
+#[derive(Debug, Deserialize)]
+struct ArtifactInput {
+    name: String,
+    kind: String,
+    content: String,
+    /// Stamped on the content before it is compared or stored (see `orchestrate`)
+    #[serde(default)]
+    provenance: Option<provenance::Provenance>,
+}
 
-    // Also save raw JSON for loading
-    let json_path = app_data_dir.join(format!("{}.json", sanitized_name));
-    fs::write(&json_path, &state_json)
-        .map_err(|e| format!("Failed to write JSON file: {}", e))?;
+impl ArtifactInput {
+    fn stamped_content(&self) -> Result<String, String> {
+        match &self.provenance {
+            Some(provenance) => provenance::stamp(&self.kind, &self.content, provenance)
+                .map_err(|e| format!("Failed to stamp artifact {}: {}", self.name, e)),
+            None => Ok(self.content.clone()),
+        }
+    }
+}
 
-    log::info!("[Interview] State saved to: {:?} (markdown) and {:?} (json)", file_path, json_path);
-    Ok(format!("État sauvegardé dans {}", file_path.display()))
+/// Names of the artifacts whose content differs from the stored one, to skip
+/// re-publishing when nothing changed
+#[tauri::command]
+async fn check_artifacts_changed(
+    app: tauri::AppHandle,
+    project_name: String,
+    artifacts: Vec<ArtifactInput>,
+) -> Result<Vec<String>, String> {
+    let store = artifact_store(&app, &project_name)?;
+    let mut changed = Vec::new();
+    for artifact in artifacts {
+        if store
+            .has_changed(&artifact.name, artifact.stamped_content()?.as_bytes())
+            .map_err(|e| format!("Failed to read artifact manifest: {}", e))?
+        {
+            changed.push(artifact.name);
+        }
+    }
+    Ok(changed)
 }
 
+/// Store generated artifacts under their content hash; returns the names that changed
 #[tauri::command]
-async fn load_interview_state(
+async fn publish_artifacts(
+    app: tauri::AppHandle,
     project_name: String,
+    artifacts: Vec<ArtifactInput>,
+) -> Result<Vec<String>, String> {
+    let store = artifact_store(&app, &project_name)?;
+    let mut changed = Vec::new();
+    for artifact in artifacts {
+        if store
+            .put(&artifact.name, &artifact.kind, artifact.stamped_content()?.as_bytes())
+            .map_err(|e| format!("Failed to store artifact {}: {}", artifact.name, e))?
+        {
+            changed.push(artifact.name);
+        }
+    }
+    log::info!("[Artifacts] {} artifact(s) changed for project: {}", changed.len(), project_name);
+    Ok(changed)
+}
+
+#[tauri::command]
+async fn get_artifact_manifest(
     app: tauri::AppHandle,
-) -> Result<String, String> {
-    use std::fs;
+    project_name: String,
+) -> Result<artifact_store::ArtifactManifest, String> {
+    artifact_store(&app, &project_name)?
+        .manifest()
+        .map_err(|e| format!("Failed to read artifact manifest: {}", e))
+}
 
-    log::info!("[Interview] Loading interview state for project: {}", project_name);
+/// Content of an artifact, refused if it no longer matches its hash
+#[tauri::command]
+async fn load_artifact(app: tauri::AppHandle, project_name: String, name: String) -> Result<Option<String>, String> {
+    let content = artifact_store(&app, &project_name)?
+        .get(&name)
+        .map_err(|e| format!("Failed to load artifact: {}", e))?;
+    content
+        .map(|bytes| String::from_utf8(bytes).map_err(|_| format!("Artifact {} is not text", name)))
+        .transpose()
+}
 
-    // Get app data directory
+#[tauri::command]
+async fn verify_artifacts(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<Vec<artifact_store::IntegrityIssue>, String> {
+    let issues = artifact_store(&app, &project_name)?
+        .verify()
+        .map_err(|e| format!("Failed to verify artifacts: {}", e))?;
+    if !issues.is_empty() {
+        log::warn!("[Artifacts] {} integrity issue(s) for project {}: {:?}", issues.len(), project_name, issues);
+    }
+    Ok(issues)
+}
+
+/// Check an artifact against its provenance: its content still matches its hash, and
+/// the transcript and prompt it was generated from are still stored unchanged
+#[tauri::command]
+async fn verify_artifact(
+    app: tauri::AppHandle,
+    project_name: String,
+    name: String,
+) -> Result<provenance::ProvenanceCheck, String> {
     let app_data_dir = app.path()
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    // Create filename from project name (sanitized)
-    let sanitized_name = project_name
-        .chars()
-        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
-        .collect::<String>();
-    
-    let file_path = app_data_dir.join(format!("{}.json", sanitized_name));
-
-    // Check if file exists
-    if !file_path.exists() {
-        return Err(format!("Aucune sauvegarde trouvée pour le projet '{}'", project_name));
+    let check = match artifact_store(&app, &project_name)?.get(&name) {
+        Ok(Some(content)) => provenance::verify(&name, &String::from_utf8_lossy(&content), |hash| {
+            artifact_store::load_object(&app_data_dir, hash).is_some()
+        }),
+        Ok(None) => return Err(format!("Artifact {} not found", name)),
+        Err(e) => provenance::ProvenanceCheck {
+            name: name.clone(),
+            provenance: None,
+            valid: false,
+            issues: vec![e.to_string()],
+        },
+    };
+    if !check.valid {
+        log::warn!("[Artifacts] {} of project {} failed verification: {:?}", name, project_name, check.issues);
     }
-
-    // Read the JSON file
-    let json_content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
-
-    log::info!("[Interview] State loaded from: {:?}", file_path);
-    Ok(json_content)
+    Ok(check)
 }
 
+/// Rebuild a domain model from existing markdown documentation
+///
+/// When `project_name` is given, the imported model is stored as a new version of
+/// the project's model.
 #[tauri::command]
-async fn list_saved_projects(
+async fn import_markdown(
     app: tauri::AppHandle,
-) -> Result<Vec<String>, String> {
-    use std::fs;
+    markdown: String,
+    use_llm: Option<bool>,
+    project_name: Option<String>,
+) -> Result<Value, String> {
+    use crate::mcp_client::McpClient;
 
-    log::info!("[Interview] Listing saved projects");
+    log::info!("[Import] Importing markdown documentation ({} chars)", markdown.len());
+    // Documentation generated here carries its provenance as front matter
+    let markdown = provenance::extract(&markdown).map(|(_, body)| body).unwrap_or(markdown);
 
-    // Get app data directory
-    let app_data_dir = app.path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let result = McpClient::new(mcp_server_path())
+        .import_markdown(&markdown, use_llm.unwrap_or(true))
+        .await
+        .map_err(|e| {
+            log::error!("[Import] Markdown import failed: {}", e);
+            format!("Failed to import markdown: {}", e)
+        })?;
 
-    // Create directory if it doesn't exist
-    if !app_data_dir.exists() {
-        return Ok(Vec::new());
+    if let Some(project_name) = project_name {
+        let version = model_store(&app, &project_name)?
+            .save(&result["model"], "import")
+            .map_err(|e| format!("Failed to save model: {}", e))?;
+        log::info!("[Import] Imported model saved as version {} of {}", version.version, project_name);
     }
+    Ok(result)
+}
 
-    // Read directory and collect .json files
-    let entries = fs::read_dir(&app_data_dir)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
+/// Start a new project from a transcript pasted from another tool
+/// (Otter export, Teams .vtt download, plain text)
+#[tauri::command]
+async fn paste_transcript(
+    app: tauri::AppHandle,
+    text: String,
+    project_name: Option<String>,
+) -> Result<transcript_intake::TranscriptImport, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let import = transcript_intake::import_transcript(&app_data_dir, &text, project_name)
+        .map_err(|e| format!("Failed to import transcript: {}", e))?;
+    log::info!("[Intake] Transcript pasted into project {} ({} chars)", import.project_name, import.transcript.len());
+    Ok(import)
+}
 
-    let projects: Vec<String> = entries
-        .filter_map(|entry| entry.ok())
-        .filter_map(|entry| {
-            let path = entry.path();
-            if path.extension()?.to_str()? == "json" {
-                path.file_stem()?.to_str().map(String::from)
-            } else {
-                None
-            }
-        })
-        .collect();
+/// Last import link received, kept until the frontend takes it to ask the user
+/// (a link that launches the app arrives before the UI listens for events)
+#[derive(Default)]
+struct PendingTranscriptImport(Mutex<Option<transcript_intake::ImportRequest>>);
 
-    log::info!("[Interview] Found {} saved projects", projects.len());
-    Ok(projects)
+#[tauri::command]
+fn take_pending_transcript_import(
+    pending: tauri::State<'_, PendingTranscriptImport>,
+) -> Result<Option<transcript_intake::ImportRequest>, String> {
+    let mut pending = pending.0.lock().map_err(|e| format!("Failed to lock pending import: {}", e))?;
+    Ok(pending.take())
 }
 
+/// Run an import link the user confirmed: load the transcript and store it in a new project
 #[tauri::command]
-async fn process_interview_section(
-    section: interview::InterviewSection,
-) -> Result<interview::SectionCanvasResult, String> {
-    use crate::interview::InterviewProcessor;
-
-    log::info!("[Interview] Processing section: {}", section.section_title);
-    
-    let processor = InterviewProcessor::new()
-        .map_err(|e| {
-            log::error!("[Interview] Failed to initialize processor: {}", e);
-            format!("Failed to initialize interview processor: {}", e)
-        })?;
-    
-    processor.process_section(section)
+async fn confirm_transcript_import(
+    app: tauri::AppHandle,
+    request: transcript_intake::ImportRequest,
+) -> Result<transcript_intake::TranscriptImport, String> {
+    let raw = transcript_intake::load_source(&request.source)
         .await
-        .map_err(|e| {
-            log::error!("[Interview] Failed to process section: {}", e);
-            format!("Failed to process section: {}", e)
-        })
+        .map_err(|e| format!("Failed to load transcript: {:#}", e))?;
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let import = transcript_intake::import_transcript(&app_data_dir, &raw, request.project_name)
+        .map_err(|e| format!("Failed to import transcript: {}", e))?;
+    log::info!("[Intake] Transcript imported from link into project {}", import.project_name);
+    Ok(import)
 }
 
-#[tauri::command]
-async fn generate_full_canvas(
-    sections: Vec<interview::SectionCanvasResult>,
-) -> Result<interview::FullCanvasResult, String> {
-    use crate::interview::InterviewProcessor;
+/// Handle a `whispeer://import?src=...` link: nothing is read yet, the request waits for
+/// the user's confirmation and the frontend is notified through the
+/// "transcript-import-requested" event
+fn handle_import_link(app: &tauri::AppHandle, link: &str) {
+    use tauri::Emitter;
+
+    match transcript_intake::parse_deep_link(link) {
+        Ok(request) => {
+            log::info!("[Intake] Import link received, waiting for confirmation");
+            if let Ok(mut pending) = app.state::<PendingTranscriptImport>().0.lock() {
+                *pending = Some(request.clone());
+            }
+            let _ = app.emit("transcript-import-requested", &request);
+        }
+        Err(e) => {
+            log::error!("[Intake] Rejected import link {}: {:#}", link, e);
+            let _ = app.emit("transcript-import-error", format!("{:#}", e));
+        }
+    }
+}
 
-    log::info!("[Interview] Generating full canvas from {} sections", sections.len());
-    
-    let processor = InterviewProcessor::new()
-        .map_err(|e| {
-            log::error!("[Interview] Failed to initialize processor: {}", e);
-            format!("Failed to initialize interview processor: {}", e)
-        })?;
-    
-    processor.generate_full_canvas(sections)
-        .await
-        .map_err(|e| {
-            log::error!("[Interview] Failed to generate canvas: {}", e);
-            format!("Failed to generate canvas: {}", e)
+/// Interview answers of a saved state as traceability sources, labelled "<section> — Q<n>"
+fn interview_sources(state: &Value) -> Vec<Value> {
+    let sections = state["sections"].as_array().map(|v| v.as_slice()).unwrap_or(&[]);
+    state["answers"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|answer| {
+            let section_id = answer["sectionId"].as_i64();
+            let title = sections
+                .iter()
+                .find(|s| s["id"].as_i64() == section_id)
+                .and_then(|s| s["title"].as_str())
+                .unwrap_or("Entretien");
+            serde_json::json!({
+                "source": format!("{} — Q{}", title, answer["questionIndex"].as_u64().unwrap_or(0) + 1),
+                "question": answer["question"].as_str().unwrap_or(""),
+                "answer": answer["answer"].as_str().unwrap_or("")
+            })
         })
+        .collect()
 }
 
+/// Traceability matrix of the project's invariants for governance reviews
+///
+/// Each invariant is mapped to the saved interview answers, the Gherkin scenarios of
+/// `features_dir` and the code of `code_dir` that reference it. Uses the project's
+/// current model unless `model` is given.
 #[tauri::command]
-async fn save_canvas_markdown(
+async fn generate_traceability_matrix(
     app: tauri::AppHandle,
     project_name: String,
-    markdown: String,
-) -> Result<String, String> {
+    model: Option<Value>,
+    features_dir: Option<String>,
+    code_dir: Option<String>,
+) -> Result<Value, String> {
+    use crate::mcp_client::McpClient;
     use std::fs;
 
-    log::info!("[Interview] Saving canvas markdown for project: {}", project_name);
+    log::info!("[Traceability] Building traceability matrix for project: {}", project_name);
+
+    let model = match model {
+        Some(model) => model,
+        None => model_store(&app, &project_name)?
+            .load()
+            .map_err(|e| format!("Failed to load model: {}", e))?
+            .ok_or_else(|| format!("No model saved for project '{}'", project_name))?,
+    };
 
-    // Get app data directory
     let app_data_dir = app.path()
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let sanitized_name = sanitize_project_name(&project_name);
+    let state_path = app_data_dir.join(format!("{}.json", sanitized_name));
+    let sources = if state_path.exists() {
+        let content = fs::read_to_string(&state_path)
+            .map_err(|e| format!("Failed to read interview state: {}", e))?;
+        let state: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse interview state: {}", e))?;
+        interview_sources(&state)
+    } else {
+        log::warn!("[Traceability] No saved interview for project {}, sources will be empty", project_name);
+        Vec::new()
+    };
 
-    // Create directory if it doesn't exist
-    fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
-
-    // Create filename from project name (sanitized)
-    let sanitized_name = project_name
-        .chars()
-        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
-        .collect::<String>();
-    
-    let file_path = app_data_dir.join(format!("{}_canvas.md", sanitized_name));
-
-    // Write the markdown file
-    fs::write(&file_path, markdown)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-
-    log::info!("[Interview] Canvas saved to: {:?}", file_path);
-    Ok(format!("Canvas sauvegardé dans {:?}", file_path))
+    McpClient::new(mcp_server_path())
+        .traceability_matrix(model, sources, features_dir.as_deref(), code_dir.as_deref())
+        .await
+        .map_err(|e| {
+            log::error!("[Traceability] Matrix generation failed: {}", e);
+            format!("Failed to build traceability matrix: {}", e)
+        })
 }
 
 #[cfg(test)]
@@ -449,11 +2983,13 @@ mod tests {
                 section_id: 1,
                 section_title: "Contexte & Vision".to_string(),
                 canvas_content: "* **Problème à résoudre :** Test".to_string(),
+                generation: None,
             },
             SectionCanvasResult {
                 section_id: 2,
                 section_title: "Acteurs & Use Cases".to_string(),
                 canvas_content: "* **Acteurs :** Utilisateur".to_string(),
+                generation: None,
             },
         ];
 
@@ -472,6 +3008,12 @@ mod tests {
                 "entities": [],
                 "relations": []
             }),
+            fixups: vec![],
+            warnings: vec![],
+            generation: None,
+            hooks: vec![],
+            provenance: None,
+            extraction: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -510,7 +3052,8 @@ mod tests {
     #[ignore] // Requires LLM and MCP setup
     async fn test_orchestrate_integration() {
         let transcript = "A user can create an order with multiple items";
-        let result = orchestrate(transcript.to_string()).await;
+        let app_data_dir = std::env::temp_dir().join(format!("orchestrate_test_{}", std::process::id()));
+        let result = run_orchestration(&app_data_dir, transcript.to_string(), None, None, None).await;
         
         // This test requires full environment setup
         // In a real test environment, we'd expect either success or specific error
@@ -529,6 +3072,7 @@ mod tests {
                 );
             }
         }
+        let _ = std::fs::remove_dir_all(&app_data_dir);
     }
 
     #[tokio::test]
@@ -547,7 +3091,7 @@ mod tests {
             ],
         };
 
-        let result = process_interview_section(section).await;
+        let result = process_interview_section(section, None).await;
         
         // This test requires LLM setup
         match result {
@@ -574,10 +3118,11 @@ mod tests {
                 section_id: 1,
                 section_title: "Contexte & Vision".to_string(),
                 canvas_content: "* **Problème à résoudre :** Gestion des commandes".to_string(),
+                generation: None,
             },
         ];
 
-        let result = generate_full_canvas(sections).await;
+        let result = generate_full_canvas(sections, None).await;
         
         // This test requires LLM setup
         match result {
@@ -595,19 +3140,62 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_interview_sources_labels() {
+        let state = serde_json::json!({
+            "sections": [{"id": 4, "title": "Agrégats"}],
+            "answers": [
+                {"sectionId": 4, "questionIndex": 1, "question": "Invariants ?", "answer": "Total ≥ 0"},
+                {"sectionId": 9, "questionIndex": 0, "question": "Autre ?", "answer": "Non"}
+            ]
+        });
+        let sources = interview_sources(&state);
+        assert_eq!(sources[0]["source"], "Agrégats — Q2");
+        assert_eq!(sources[0]["answer"], "Total ≥ 0");
+        assert_eq!(sources[1]["source"], "Entretien — Q1");
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_audio_capture::init())
         .setup(|app| {
+            // whispeer://import?src=... links from other tools
+            app.manage(PendingTranscriptImport::default());
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+
+                #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+                if let Err(e) = app.deep_link().register_all() {
+                    log::warn!("[Setup] Failed to register the {} URL scheme: {}", transcript_intake::URL_SCHEME, e);
+                }
+
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    for url in event.urls() {
+                        handle_import_link(&handle, url.as_str());
+                    }
+                });
+
+                // Link that launched the app
+                if let Ok(Some(urls)) = app.deep_link().get_current() {
+                    for url in urls {
+                        handle_import_link(app.handle(), url.as_str());
+                    }
+                }
+            }
+
             // Initialize RecordingManager
             let model_path = if let Ok(path_str) = std::env::var("WHISPER_MODEL_PATH") {
                 std::path::PathBuf::from(path_str)
             } else {
-                // Try to get from bundled resources first
-                if let Ok(resource_path) = app.path().resolve("ggml-small.bin", tauri::path::BaseDirectory::Resource) {
+                // Try to get from bundled resources first (quantized model on phones)
+                let bundled_model = if cfg!(mobile) { "ggml-small-q5_1.bin" } else { "ggml-small.bin" };
+                if let Ok(resource_path) = app.path().resolve(bundled_model, tauri::path::BaseDirectory::Resource) {
                     if resource_path.exists() {
                         log::info!("[Setup] Using bundled model from resources: {:?}", resource_path);
                         resource_path
@@ -642,21 +3230,94 @@ pub fn run() {
             
             log::info!("[Setup] Initializing RecordingManager with model path: {:?}", model_path);
             
+            // Nothing bundled nor in the dev tree: model downloaded from the app, if any
+            let model_path = match app.path().app_data_dir() {
+                Ok(dir) if !model_path.exists() => {
+                    let downloaded = model_manager::models_dir(&dir).join(model_manager::DEFAULT_MODEL);
+                    if downloaded.exists() { downloaded } else { model_path }
+                }
+                _ => model_path,
+            };
+
             // Verify model file exists
             if !model_path.exists() {
                 log::error!("[Setup] Whisper model file not found at: {:?}", model_path);
-                log::error!("[Setup] Please ensure the model file is present or set WHISPER_MODEL_PATH environment variable");
+                log::error!("[Setup] Please download a model from the app, place the model file or set WHISPER_MODEL_PATH environment variable");
             } else {
                 log::info!("[Setup] Whisper model file found");
             }
             
-            let manager = recording_manager::RecordingManager::new(
+            // One transcriber for the whole app: recordings and transcribe_audio share its model
+            let stt = Arc::new(speech_to_text::SpeechToText::with_settings(
                 model_path,
+                recording_manager::platform_whisper_settings(),
+            ));
+            app.manage(Arc::clone(&stt));
+            let manager = recording_manager::RecordingManager::new(
+                stt,
                 app.handle().clone(),
             );
+            let coach_state = Arc::new(Mutex::new(coach::Coach::default()));
+            #[cfg_attr(mobile, allow(unused_variables, unused_assignments))]
+            let mut recording_hotkey = settings::AppSettings::default().recording_hotkey;
+            match app.path().app_data_dir().map_err(anyhow::Error::from).and_then(|dir| settings::load(&dir)) {
+                Ok(app_settings) => {
+                    manager.set_language(&app_settings.transcription_language);
+                    manager.set_translate(app_settings.translate_to_english);
+                    match stt_provider::CloudStt::from_settings(&app_settings.cloud_stt) {
+                        Ok(cloud) => manager.set_cloud_stt(cloud),
+                        Err(e) => log::warn!("[Setup] Cloud transcription disabled: {}", e),
+                    }
+                    manager.set_power_profile(app_settings.power_profile);
+                    manager.set_vad_settings(app_settings.vad);
+                    manager.set_audio_profile(app_settings.audio_profile(None));
+                    manager.set_recording_mode(app_settings.recording_mode);
+                    manager.set_archive_raw(app_settings.archive_raw_audio);
+                    manager.set_system_capture(app_settings.system_capture.clone());
+                    manager.set_in_memory_audio(app_settings.in_memory_audio);
+                    manager.set_audio_retention(app_settings.audio_retention);
+                    manager.set_utterance_naming(app_settings.utterance_naming.clone());
+                    manager.set_whisper_backend(app_settings.whisper_backend);
+                    if let (Some(path), Err(_)) = (&app_settings.whisper_model, std::env::var("WHISPER_MODEL_PATH")) {
+                        manager.set_whisper_model(std::path::PathBuf::from(path));
+                    }
+                    coach_state.lock().unwrap().set_settings(app_settings.coach);
+                    recording_hotkey = app_settings.recording_hotkey;
+                }
+                Err(e) => log::warn!("[Setup] Failed to load settings, using defaults: {}", e),
+            }
             
             app.manage(Arc::new(Mutex::new(Some(manager))));
             log::info!("[Setup] RecordingManager initialized successfully");
+
+            // Facilitation coach: fed by the transcriptions, silent unless enabled
+            {
+                use tauri::Listener;
+
+                let handle = app.handle().clone();
+                let fed_coach = Arc::clone(&coach_state);
+                app.listen("transcription-result", move |event| feed_coach(&handle, &fed_coach, event.payload()));
+            }
+            app.manage(coach_state);
+
+            // Start/stop the recording while another app (Zoom, IDE) is focused
+            #[cfg(desktop)]
+            {
+                use tauri_plugin_global_shortcut::ShortcutState;
+
+                app.handle().plugin(
+                    tauri_plugin_global_shortcut::Builder::new()
+                        .with_handler(|app, _shortcut, event| {
+                            if event.state() == ShortcutState::Pressed {
+                                toggle_recording(app);
+                            }
+                        })
+                        .build(),
+                )?;
+                if let Err(e) = register_recording_hotkey(app.handle(), recording_hotkey.as_deref()) {
+                    log::warn!("[Setup] Recording hotkey unavailable: {:#}", e);
+                }
+            }
             
             Ok(())
         })
@@ -665,15 +3326,105 @@ pub fn run() {
             orchestrate,
             start_recording,
             stop_recording,
+            pause_recording,
+            resume_recording,
             transcribe_audio,
+            transcribe_folder,
             list_audio_devices,
             set_audio_device,
+            get_audio_profile,
+            save_audio_profile,
+            test_audio_pipeline,
+            analyze_environment,
+            get_waveform,
+            get_memory_diagnostics,
+            get_storage_usage,
+            list_whisper_models,
+            download_whisper_model,
+            set_whisper_model,
+            set_whisper_backend,
+            get_whisper_backend,
+            set_virtual_microphone,
             save_interview_state,
             load_interview_state,
             list_saved_projects,
             process_interview_section,
+            score_answer,
             generate_full_canvas,
-            save_canvas_markdown
+            save_canvas_markdown,
+            export_slides,
+            export_glossary,
+            narrate_model,
+            generate_quiz,
+            export_quiz,
+            draft_summary_email,
+            preview_tickets,
+            push_tickets,
+            record_section_change,
+            get_interview_analytics,
+            check_ubiquitous_language,
+            list_model_templates,
+            load_model_template,
+            list_few_shot_examples,
+            add_few_shot_example,
+            remove_few_shot_example,
+            get_default_system_prompt,
+            compare_prompts,
+            refine_entity,
+            suggest_fixes,
+            save_model,
+            load_model,
+            get_model_history,
+            get_model_version,
+            load_model_version,
+            generate_changelog,
+            apply_model_patch,
+            check_artifacts_changed,
+            publish_artifacts,
+            get_artifact_manifest,
+            load_artifact,
+            verify_artifacts,
+            verify_artifact,
+            generate_traceability_matrix,
+            extract_risk_register,
+            load_risk_register,
+            save_risk_register,
+            extract_kpi_catalog,
+            load_kpi_catalog,
+            save_kpi_catalog,
+            export_kpi_catalog,
+            import_markdown,
+            paste_transcript,
+            take_pending_transcript_import,
+            confirm_transcript_import,
+            get_app_settings,
+            set_transcription_language,
+            set_translate_to_english,
+            set_cloud_stt,
+            set_ticket_tracker,
+            get_llm_policy,
+            export_transcript,
+            get_session_transcript,
+            set_power_profile,
+            get_vad_settings,
+            set_vad_settings,
+            set_recording_mode,
+            set_archive_raw_audio,
+            set_system_capture,
+            set_in_memory_audio,
+            set_audio_retention,
+            purge_audio_cache,
+            set_utterance_naming,
+            set_coach_settings,
+            set_coach_section,
+            set_recording_hotkey,
+            set_audio_monitor,
+            cancel_transcription,
+            set_hooks,
+            test_hook,
+            list_recording_sessions,
+            replay_session,
+            compare_transcripts
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,25 +1,21 @@
 use anyhow::{Context, Result};
 use serde_json::Value;
 
-use crate::llm_router::LlmRouter;
+use crate::changelog;
+use crate::coach::{self, CoachRequest};
+use crate::entity_candidates::Candidates;
+use crate::few_shot::{self, FewShotExample};
+use crate::json_patch::{self, PatchOperation};
+use crate::llm_router::{GenerationRecord, LlmRequestOptions, LlmRouter};
+use crate::prompt_budget::{PromptBudget, PromptSection, Trim};
+use crate::quiz::{self, Quiz};
+use crate::recording_session;
+use crate::refine::{self, EntityPatch};
+use crate::summary_email::{self, EmailSources, SummaryEmail};
+use crate::transcript_normalize;
 
-/// Integration layer that uses LLM to generate DomainModel JSON
-/// The LLM is constrained to only output valid DomainModel schema
-pub struct LlmIntegration {
-    llm_router: LlmRouter,
-}
-
-impl LlmIntegration {
-    pub fn new() -> Result<Self> {
-        let llm_router = LlmRouter::new()?;
-        Ok(Self { llm_router })
-    }
-
-    /// Process a user request through the LLM and execute the resulting tool calls
-    /// Returns the final results from executing the tools
-    pub async fn process_request(&self, user_request: &str) -> Result<Value> {
-        // System prompt constrains LLM to only output valid DomainModel JSON
-        let system_prompt = r#"
+/// System prompt constraining the LLM to only output valid DomainModel JSON
+const SYSTEM_PROMPT: &str = r#"
 Tu es un normalizer de Domain Model. Rends UNIQUEMENT un JSON valide DomainModel conforme au schema. Interdis les champs non listés.
 
 Schema DomainModel (STRICT - aucun champ supplémentaire autorisé):
@@ -105,10 +101,105 @@ RÈGLES STRICTES:
 5. Réponds UNIQUEMENT avec ce JSON, pas de tool_calls
 "#;
 
+/// Integration layer that uses LLM to generate DomainModel JSON
+/// The LLM is constrained to only output valid DomainModel schema
+pub struct LlmIntegration {
+    llm_router: LlmRouter,
+    examples: Vec<FewShotExample>,
+    glossary: Option<String>,
+    budget: PromptBudget,
+    /// Spoken language of the transcripts, for number and date normalization
+    language: Option<String>,
+    /// Entity and attribute names pre-extracted from the transcript
+    candidates: Option<Candidates>,
+}
+
+impl LlmIntegration {
+    pub fn new() -> Result<Self> {
+        let llm_router = LlmRouter::new()?;
+        Ok(Self {
+            llm_router,
+            examples: Vec::new(),
+            glossary: None,
+            budget: PromptBudget::from_env(),
+            language: None,
+            candidates: None,
+        })
+    }
+
+    /// Project glossary (one term per line) given to the LLM as preferred vocabulary
+    pub fn with_glossary(mut self, glossary: Option<String>) -> Self {
+        self.glossary = glossary.filter(|g| !g.trim().is_empty());
+        self
+    }
+
+    /// Spoken language of the transcripts ("fr", "en"); None or "auto" guesses it from
+    /// the text when normalizing spoken numbers and dates
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Candidate names from the deterministic extraction pass, given to the LLM to
+    /// confirm or discard
+    pub fn with_candidates(mut self, candidates: Option<Candidates>) -> Self {
+        self.candidates = candidates.filter(|c| !c.is_empty());
+        self
+    }
+
+    /// Transcript with spoken numbers, ordinals and dates in canonical form
+    fn normalized(&self, transcript: &str) -> String {
+        transcript_normalize::normalize_transcript(transcript, self.language.as_deref())
+    }
+
+    /// Sampling options (temperature, top_p, seed) for the generations
+    pub fn with_options(mut self, options: LlmRequestOptions) -> Self {
+        self.llm_router = self.llm_router.with_options(options);
+        self
+    }
+
+    /// Provider and sampling parameters to record with the generated artifacts
+    pub fn generation_record(&self) -> GenerationRecord {
+        self.llm_router.generation_record()
+    }
+
+    /// Few-shot examples injected into the normalization prompt
+    pub fn with_examples(mut self, examples: Vec<FewShotExample>) -> Self {
+        self.examples = examples;
+        self
+    }
+
+    /// Process a user request through the LLM and execute the resulting tool calls
+    /// Returns the final results from executing the tools
+    pub async fn process_request(&self, user_request: &str) -> Result<Value> {
+        let (model, _) = self.process_request_with_base(user_request, None).await?;
+        Ok(model)
+    }
+
+    /// Same as `process_request`, refining `base_model` (e.g. a starter template)
+    /// instead of starting from scratch.
+    ///
+    /// Also returns the prompt budget warnings (context trimmed to fit the model window).
+    pub async fn process_request_with_base(
+        &self,
+        user_request: &str,
+        base_model: Option<&Value>,
+    ) -> Result<(Value, Vec<String>)> {
+        let user_request = self.normalized(user_request);
+        let user_prompt = match base_model {
+            Some(base) => Self::seeded_prompt(base, &user_request)?,
+            None => user_request,
+        };
+
+        let (system_prompt, user_prompt, warnings) = self.assemble_prompts(user_prompt);
+        for warning in &warnings {
+            log::warn!("[LLM Integration] {}", warning);
+        }
+
         // Get DomainModel JSON directly from LLM
         let domain_model = self
             .llm_router
-            .generate_domain_model(system_prompt, user_request)
+            .generate_domain_model(&system_prompt, &user_prompt)
             .await
             .context("Failed to generate DomainModel from LLM")?;
 
@@ -116,13 +207,239 @@ RÈGLES STRICTES:
         let model_json = serde_json::to_value(&domain_model)
             .context("Failed to serialize DomainModel")?;
 
-        Ok(model_json)
+        Ok((model_json, warnings))
+    }
+
+    /// Instructions for a bilingual transcript (language tags, see
+    /// `recording_session::transcript_languages`)
+    fn languages_note(transcript: &str) -> Option<String> {
+        let languages = recording_session::transcript_languages(transcript);
+        let (main, _) = languages.first()?;
+        let codes = languages.iter().map(|(code, _)| code.as_str()).collect::<Vec<_>>().join(", ");
+        Some(format!(
+            "\nLANGUES: la transcription mélange plusieurs langues ({}); une balise [xx] indique la langue des phrases qui suivent. \
+             Nomme entités, attributs et relations dans une seule langue ({}, la plus présente) en traduisant les termes des autres langues, \
+             et fusionne un même concept cité dans plusieurs langues. N'inclus pas les balises dans le modèle.\n",
+            codes, main
+        ))
+    }
+
+    /// Instructions for a transcript of a remote meeting (source labels, see
+    /// `recording_session::has_source_labels`)
+    fn sources_note(transcript: &str) -> Option<String> {
+        recording_session::has_source_labels(transcript).then(|| {
+            "\nINTERLOCUTEURS: chaque ligne commence par sa source. \"Micro:\" est la personne qui mène l'entretien, \
+             \"Système:\" les participants distants (experts métier). Les règles et le vocabulaire du domaine viennent surtout \
+             des participants distants; une proposition du Micro n'est retenue que si elle est confirmée. N'inclus pas les étiquettes dans le modèle.\n"
+                .to_string()
+        })
+    }
+
+    /// System prompt `process_request_with_base` sends for this request, recorded as the
+    /// provenance of the generated artifacts
+    pub fn system_prompt_for(&self, user_request: &str, base_model: Option<&Value>) -> Result<String> {
+        let user_request = self.normalized(user_request);
+        let user_prompt = match base_model {
+            Some(base) => Self::seeded_prompt(base, &user_request)?,
+            None => user_request,
+        };
+        Ok(self.assemble_prompts(user_prompt).0)
+    }
+
+    /// Fit system prompt, glossary, few-shot examples and transcript into the
+    /// token budget. The glossary and the candidates are trimmed first, then the least similar
+    /// examples; the system prompt and the transcript are always kept.
+    fn assemble_prompts(&self, user_prompt: String) -> (String, String, Vec<String>) {
+        let mut sections = vec![PromptSection::new("system prompt", SYSTEM_PROMPT.to_string(), 3, Trim::Never)];
+        if let Some(glossary) = &self.glossary {
+            sections.push(PromptSection::new(
+                "glossary",
+                format!("\nGLOSSAIRE (utilise ces termes):\n{}\n", glossary.trim()),
+                1,
+                Trim::Lines,
+            ));
+        }
+        if let Some(note) = self.candidates.as_ref().and_then(Candidates::prompt_note) {
+            sections.push(PromptSection::new("candidates", note, 1, Trim::Lines));
+        }
+        if let Some(note) = Self::languages_note(&user_prompt) {
+            sections.push(PromptSection::new("languages", note, 3, Trim::Never));
+        }
+        if let Some(note) = Self::sources_note(&user_prompt) {
+            sections.push(PromptSection::new("sources", note, 3, Trim::Never));
+        }
+        for (i, example) in self.examples.iter().enumerate() {
+            sections.push(PromptSection::new(
+                &format!("example:{}", example.id),
+                few_shot::format_example(i + 1, example),
+                2,
+                Trim::Drop,
+            ));
+        }
+        sections.push(PromptSection::new("transcript", user_prompt, 3, Trim::Never));
+
+        let fitted = self.budget.fit(sections);
+        let examples = fitted.text_of(&["example"]);
+        let system_prompt = format!(
+            "{}{}{}{}{}{}{}",
+            fitted.text_of(&["system prompt"]),
+            fitted.text_of(&["glossary"]),
+            fitted.text_of(&["candidates"]),
+            fitted.text_of(&["languages"]),
+            fitted.text_of(&["sources"]),
+            if examples.is_empty() { "" } else { few_shot::EXAMPLES_HEADER },
+            examples
+        );
+        (system_prompt, fitted.text_of(&["transcript"]), fitted.warnings)
+    }
+
+    /// Default normalization system prompt (without few-shot examples)
+    pub fn default_system_prompt() -> &'static str {
+        SYSTEM_PROMPT
+    }
+
+    /// Generate a DomainModel from `transcript` with a caller-provided system prompt
+    /// (no few-shot injection), used to compare prompt variants
+    pub async fn generate_with_prompt(&self, system_prompt: &str, transcript: &str) -> Result<Value> {
+        let domain_model = self
+            .llm_router
+            .generate_domain_model(system_prompt, &self.normalized(transcript))
+            .await
+            .context("Failed to generate DomainModel from LLM")?;
+
+        serde_json::to_value(&domain_model).context("Failed to serialize DomainModel")
+    }
+
+    /// Ask the LLM for a minimal patch refining one entity, and apply it.
+    ///
+    /// Only the entity and its surroundings are sent. Returns the patched
+    /// model, the equivalent RFC 6902 patch and the list of changes.
+    pub async fn refine_entity(
+        &self,
+        model: &Value,
+        entity_id: &str,
+        instruction: &str,
+        previous_instructions: &[String],
+    ) -> Result<(Value, Vec<PatchOperation>, Vec<String>)> {
+        let context = refine::entity_context(model, entity_id)?;
+        let user_prompt = refine::refine_prompt(&context, previous_instructions, instruction)?;
+
+        let response = self
+            .llm_router
+            .generate_json(refine::REFINE_SYSTEM_PROMPT, &user_prompt)
+            .await
+            .context("Failed to generate refinement patch from LLM")?;
+        let patch: EntityPatch = serde_json::from_value(response)
+            .context("LLM answer is not a valid refinement patch")?;
+
+        let (patched, changes) = refine::apply_patch(model, entity_id, &patch)?;
+        let operations = json_patch::diff(model, &patched);
+        Ok((patched, operations, changes))
+    }
+
+    /// Write up the changes between two model versions for the changelog
+    pub async fn summarize_changelog(&self, diff: &Value, before: &Value, after: &Value, changes: &[String]) -> Result<String> {
+        let user_prompt = changelog::changelog_prompt(diff, before, after, changes)?;
+        self.llm_router
+            .generate_text(changelog::CHANGELOG_SYSTEM_PROMPT, &user_prompt)
+            .await
+            .context("Failed to generate changelog summary from LLM")
+    }
+
+    /// Stakeholder email summing up the project in `language`
+    pub async fn draft_summary_email(&self, project_name: &str, language: &str, sources: &EmailSources) -> Result<SummaryEmail> {
+        let (user_prompt, warnings) = summary_email::email_prompt(project_name, language, sources, &self.budget);
+        for warning in warnings {
+            log::warn!("[Email] {}", warning);
+        }
+        let answer = self
+            .llm_router
+            .generate_text(summary_email::EMAIL_SYSTEM_PROMPT, &user_prompt)
+            .await
+            .context("Failed to draft summary email from LLM")?;
+        Ok(summary_email::parse_email(&answer, project_name, language))
+    }
+
+    /// Quiz of `count` questions with their expected answers, from the model and the canvas
+    pub async fn generate_quiz(
+        &self,
+        project_name: &str,
+        language: &str,
+        count: usize,
+        model: Option<&Value>,
+        canvas: Option<&str>,
+    ) -> Result<Quiz> {
+        let (user_prompt, warnings) = quiz::quiz_prompt(project_name, language, count, model, canvas, &self.budget)?;
+        for warning in warnings {
+            log::warn!("[Quiz] {}", warning);
+        }
+        let answer = self
+            .llm_router
+            .generate_json(quiz::QUIZ_SYSTEM_PROMPT, &user_prompt)
+            .await
+            .context("Failed to generate quiz from LLM")?;
+        Ok(Quiz {
+            project: project_name.to_string(),
+            language: language.to_string(),
+            questions: quiz::parse_questions(answer, count).context("LLM answer is not a valid quiz")?,
+        })
+    }
+
+    /// Prompts the facilitator could use to cover what the section still misses
+    pub async fn coach_suggestions(&self, request: &CoachRequest) -> Result<Vec<String>> {
+        let answer = self
+            .llm_router
+            .generate_text(coach::COACH_SYSTEM_PROMPT, &coach::coach_prompt(request))
+            .await
+            .context("Failed to generate coach suggestions from LLM")?;
+        Ok(coach::parse_suggestions(&answer))
+    }
+
+    /// Build the user prompt asking to refine a base model with the transcript
+    fn seeded_prompt(base_model: &Value, user_request: &str) -> Result<String> {
+        let base = serde_json::to_string_pretty(base_model)
+            .context("Failed to serialize base model")?;
+        Ok(format!(
+            "Modèle de départ (conserve ce qui reste valable, complète et corrige selon la transcription, retire ce qu'elle contredit):\n{}\n\nTranscription:\n{}",
+            base, user_request
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::llm_router::LlmProvider;
+
+    #[test]
+    fn test_seeded_prompt_contains_base_and_transcript() {
+        let base = serde_json::json!({"entities": [{"id": "Order"}], "relations": [], "invariants": []});
+        let prompt = LlmIntegration::seeded_prompt(&base, "Les commandes ont une date de livraison").unwrap();
+        assert!(prompt.contains("\"id\": \"Order\""));
+        assert!(prompt.ends_with("Les commandes ont une date de livraison"));
+    }
+
+    #[test]
+    fn test_assemble_prompts_trims_glossary_over_budget() {
+        // No request is sent: the provider only has to exist, not to be reachable
+        let glossary = (0..2000).map(|i| format!("Terme{}: définition", i)).collect::<Vec<_>>().join("\n");
+        let integration = LlmIntegration {
+            llm_router: LlmRouter::with_provider(LlmProvider::Ollama { base_url: "http://localhost:11434".to_string() }),
+            examples: Vec::new(),
+            glossary: None,
+            budget: PromptBudget { context_tokens: 3000, reserved_output_tokens: 1000 },
+            language: None,
+            candidates: None,
+        }
+        .with_glossary(Some(glossary));
+
+        let (system_prompt, user_prompt, warnings) = integration.assemble_prompts("Les commandes".to_string());
+        assert!(system_prompt.starts_with(SYSTEM_PROMPT));
+        assert!(system_prompt.contains("GLOSSAIRE"));
+        assert_eq!(user_prompt, "Les commandes");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("glossary"));
+    }
 
     #[tokio::test]
     #[ignore] // Requires environment variables (LLM_PROVIDER, etc.)
@@ -3,31 +3,57 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::env;
 
+use crate::openai_schema;
+
+/// Chat completions endpoint of OpenAI, when LLM_ENDPOINT is not set
+const OPENAI_ENDPOINT: &str = "https://api.openai.com/v1/chat/completions";
+/// Ollama model when OLLAMA_MODEL is not set
+const OLLAMA_DEFAULT_MODEL: &str = "domain-model-mistral";
+/// OpenAI model when LLM_MODEL is not set (supports structured outputs)
+const OPENAI_DEFAULT_MODEL: &str = "gpt-4o-mini";
+
 /// LLM Provider configuration
 #[derive(Debug, Clone)]
 pub enum LlmProvider {
     Ollama { base_url: String },
     External { api_key: String, endpoint: String },
+    /// OpenAI chat completions: structured outputs for the DomainModel and native tool calls
+    OpenAi { api_key: String, endpoint: String, model: String },
 }
 
 impl LlmProvider {
     /// Detect provider from environment variables
     /// Automatically loads .env file if present
     /// Defaults to Ollama if not specified
+    ///
+    /// The configuration must be allowed by the workspace policy (see `provider_policy`).
     pub fn from_env() -> Result<Self> {
         // Load .env file if it exists (silently ignore if not found)
         // Note: dotenv only sets env vars that aren't already set
         let _ = dotenvy::dotenv();
         
         let provider = env::var("LLM_PROVIDER").unwrap_or_else(|_| "ollama".to_string());
-        
+        let configured = Self::from_name(&provider)?;
+        crate::provider_policy::enforce(&provider, &configured)?;
+        Ok(configured)
+    }
+
+    /// Provider named by LLM_PROVIDER, configured from the other variables
+    fn from_name(provider: &str) -> Result<Self> {
         match provider.to_lowercase().as_str() {
             "ollama" => {
                 let base_url = env::var("OLLAMA_BASE_URL")
                     .unwrap_or_else(|_| "http://localhost:11434".to_string());
                 Ok(Self::Ollama { base_url })
             }
-            "external" | "openai" | "anthropic" => {
+            "openai" => {
+                let api_key = env::var("LLM_API_KEY")
+                    .context("LLM_API_KEY environment variable not set for OpenAI provider. Set LLM_PROVIDER=ollama to use local Ollama instead.")?;
+                let endpoint = env::var("LLM_ENDPOINT").unwrap_or_else(|_| OPENAI_ENDPOINT.to_string());
+                let model = env::var("LLM_MODEL").unwrap_or_else(|_| OPENAI_DEFAULT_MODEL.to_string());
+                Ok(Self::OpenAi { api_key, endpoint, model })
+            }
+            "external" | "anthropic" => {
                 let api_key = env::var("LLM_API_KEY")
                     .context("LLM_API_KEY environment variable not set for external provider. Set LLM_PROVIDER=ollama to use local Ollama instead.")?;
                 let endpoint = env::var("LLM_ENDPOINT")
@@ -41,6 +67,88 @@ impl LlmProvider {
     }
 }
 
+/// Model of the Ollama requests (and of their provenance records)
+fn ollama_model() -> String {
+    env::var("OLLAMA_MODEL").unwrap_or_else(|_| OLLAMA_DEFAULT_MODEL.to_string())
+}
+
+/// Sampling parameters sent with every LLM request
+///
+/// Unset values fall back to LLM_TEMPERATURE / LLM_TOP_P / LLM_SEED, then to
+/// the provider defaults (temperature 0.7 for external providers). A fixed
+/// seed with temperature 0 gives reproducible generations on providers that
+/// honor it (Ollama, OpenAI-compatible endpoints).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LlmRequestOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl LlmRequestOptions {
+    /// Options from LLM_TEMPERATURE, LLM_TOP_P and LLM_SEED (invalid values are ignored)
+    pub fn from_env() -> Self {
+        Self {
+            temperature: env::var("LLM_TEMPERATURE").ok().and_then(|v| v.parse().ok()),
+            top_p: env::var("LLM_TOP_P").ok().and_then(|v| v.parse().ok()),
+            seed: env::var("LLM_SEED").ok().and_then(|v| v.parse().ok()),
+        }
+    }
+
+    /// Fill the unset values from `defaults`
+    pub fn or(self, defaults: &Self) -> Self {
+        Self {
+            temperature: self.temperature.or(defaults.temperature),
+            top_p: self.top_p.or(defaults.top_p),
+            seed: self.seed.or(defaults.seed),
+        }
+    }
+
+    /// Add the parameters to an OpenAI-style chat completion body
+    fn apply_external(&self, body: &mut Value) {
+        body["temperature"] = json!(self.temperature.unwrap_or(0.7));
+        if let Some(top_p) = self.top_p {
+            body["top_p"] = json!(top_p);
+        }
+        if let Some(seed) = self.seed {
+            body["seed"] = json!(seed);
+        }
+    }
+
+    /// Add the parameters to an Ollama /api/generate body (under "options")
+    fn apply_ollama(&self, body: &mut Value) {
+        let mut options = serde_json::Map::new();
+        if let Some(temperature) = self.temperature {
+            options.insert("temperature".to_string(), json!(temperature));
+        }
+        if let Some(top_p) = self.top_p {
+            options.insert("top_p".to_string(), json!(top_p));
+        }
+        if let Some(seed) = self.seed {
+            options.insert("seed".to_string(), json!(seed));
+        }
+        if !options.is_empty() {
+            body["options"] = Value::Object(options);
+        }
+    }
+}
+
+/// How an artifact was generated, recorded so it can be reproduced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationRecord {
+    /// "ollama", "openai" or "external"
+    pub provider: String,
+    /// Model name when known (OLLAMA_MODEL / LLM_MODEL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(flatten)]
+    pub options: LlmRequestOptions,
+    pub generated_at: String,
+}
+
 /// Tool call structure that LLM should emit
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ToolCall {
@@ -73,14 +181,46 @@ struct OllamaResponse {
 pub struct LlmRouter {
     provider: LlmProvider,
     client: reqwest::Client,
+    options: LlmRequestOptions,
 }
 
 impl LlmRouter {
-    /// Create a new LLM router with provider from environment
+    /// Create a new LLM router with provider and sampling options from environment
     pub fn new() -> Result<Self> {
-        let provider = LlmProvider::from_env()?;
-        let client = reqwest::Client::new();
-        Ok(Self { provider, client })
+        Ok(Self::with_provider(LlmProvider::from_env()?))
+    }
+
+    /// Router for an already configured provider, sampling options from environment
+    pub fn with_provider(provider: LlmProvider) -> Self {
+        Self { provider, client: reqwest::Client::new(), options: LlmRequestOptions::from_env() }
+    }
+
+    /// Override the sampling options (unset values keep the environment defaults)
+    pub fn with_options(mut self, options: LlmRequestOptions) -> Self {
+        self.options = options.or(&self.options);
+        self
+    }
+
+    pub fn options(&self) -> &LlmRequestOptions {
+        &self.options
+    }
+
+    /// Provider, model and sampling options of the next generation
+    pub fn generation_record(&self) -> GenerationRecord {
+        let (provider, model) = match &self.provider {
+            LlmProvider::Ollama { .. } => (
+                "ollama",
+                Some(ollama_model()),
+            ),
+            LlmProvider::External { .. } => ("external", env::var("LLM_MODEL").ok()),
+            LlmProvider::OpenAi { model, .. } => ("openai", Some(model.clone())),
+        };
+        GenerationRecord {
+            provider: provider.to_string(),
+            model,
+            options: self.options.clone(),
+            generated_at: chrono::Utc::now().to_rfc3339(),
+        }
     }
 
     /// Send a prompt to the LLM and get structured tool calls back
@@ -99,6 +239,10 @@ impl LlmRouter {
                 self.generate_with_external(endpoint, api_key, system_prompt, user_prompt)
                     .await
             }
+            LlmProvider::OpenAi { api_key, endpoint, model } => {
+                self.generate_with_openai(endpoint, api_key, model, system_prompt, user_prompt)
+                    .await
+            }
         }
     }
 
@@ -117,6 +261,36 @@ impl LlmRouter {
                 self.generate_domain_model_external(endpoint, api_key, system_prompt, user_prompt)
                     .await
             }
+            LlmProvider::OpenAi { api_key, endpoint, model } => {
+                self.generate_domain_model_openai(endpoint, api_key, model, system_prompt, user_prompt)
+                    .await
+            }
+        }
+    }
+
+    /// Generate an arbitrary JSON object (JSON mode), for prompts whose answer
+    /// is not a full DomainModel (patches, reports...)
+    pub async fn generate_json(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<Value> {
+        match &self.provider {
+            LlmProvider::Ollama { base_url } => {
+                self.generate_json_ollama(base_url, system_prompt, user_prompt)
+                    .await
+            }
+            LlmProvider::External { api_key, endpoint } => {
+                self.generate_json_external(endpoint, api_key, system_prompt, user_prompt)
+                    .await
+            }
+            LlmProvider::OpenAi { api_key, endpoint, model } => {
+                let message = self
+                    .openai_chat(endpoint, api_key, model, system_prompt, user_prompt, json!({"response_format": {"type": "json_object"}}))
+                    .await?;
+                let content = message["content"].as_str().context("Failed to extract content from OpenAI response")?;
+                serde_json::from_str(content).context("Failed to parse JSON from OpenAI response")
+            }
         }
     }
 
@@ -135,6 +309,13 @@ impl LlmRouter {
                 self.generate_text_external(endpoint, api_key, system_prompt, user_prompt)
                     .await
             }
+            LlmProvider::OpenAi { api_key, endpoint, model } => {
+                let message = self.openai_chat(endpoint, api_key, model, system_prompt, user_prompt, json!({})).await?;
+                message["content"]
+                    .as_str()
+                    .map(str::to_string)
+                    .context("Failed to extract content from OpenAI response")
+            }
         }
     }
 
@@ -147,14 +328,15 @@ impl LlmRouter {
     ) -> Result<LlmResponse> {
         let url = format!("{}/api/generate", base_url);
         
-        let model = env::var("OLLAMA_MODEL").unwrap_or_else(|_| "llama2".to_string());
+        let model = ollama_model();
         
-        let request_body = json!({
+        let mut request_body = json!({
             "model": model,
             "prompt": format!("{}\n\nUser: {}", system_prompt, user_prompt),
             "stream": false,
             "format": "json"
         });
+        self.options.apply_ollama(&mut request_body);
 
         let response = self
             .client
@@ -189,14 +371,14 @@ impl LlmRouter {
         user_prompt: &str,
     ) -> Result<LlmResponse> {
         // This is a generic implementation - adjust based on your actual external provider
-        let request_body = json!({
+        let mut request_body = json!({
             "messages": [
                 {"role": "system", "content": system_prompt},
                 {"role": "user", "content": user_prompt}
             ],
-            "temperature": 0.7,
             "response_format": {"type": "json_object"}
         });
+        self.options.apply_external(&mut request_body);
 
         let response = self
             .client
@@ -235,6 +417,103 @@ impl LlmRouter {
         Ok(llm_response)
     }
 
+    /// Send a chat completion to OpenAI, `extra` merged into the body (response format,
+    /// tools), and return the assistant message. A refusal is an error.
+    async fn openai_chat(
+        &self,
+        endpoint: &str,
+        api_key: &str,
+        model: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+        extra: Value,
+    ) -> Result<Value> {
+        let mut request_body = json!({
+            "model": model,
+            "messages": [
+                {"role": "system", "content": system_prompt},
+                {"role": "user", "content": user_prompt}
+            ]
+        });
+        if let Value::Object(extra) = extra {
+            for (key, value) in extra {
+                request_body[key] = value;
+            }
+        }
+        self.options.apply_external(&mut request_body);
+
+        let response = self
+            .client
+            .post(endpoint)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await
+            .context("Failed to send request to OpenAI")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("OpenAI API error {}: {}", status, error_text);
+        }
+
+        let mut response_json: Value = response
+            .json()
+            .await
+            .context("Failed to parse OpenAI response")?;
+
+        let message = response_json
+            .pointer_mut("/choices/0/message")
+            .map(Value::take)
+            .context("Failed to extract message from OpenAI response")?;
+        if let Some(refusal) = message["refusal"].as_str() {
+            anyhow::bail!("OpenAI refused the request: {}", refusal);
+        }
+        Ok(message)
+    }
+
+    /// Generate tool calls with OpenAI native tool calling (the MCP tools as functions)
+    async fn generate_with_openai(
+        &self,
+        endpoint: &str,
+        api_key: &str,
+        model: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<LlmResponse> {
+        let message = self
+            .openai_chat(endpoint, api_key, model, system_prompt, user_prompt, json!({"tools": openai_schema::tools()}))
+            .await?;
+        let tool_calls = openai_schema::tool_calls(&message)?;
+        log::info!("[LLM Router] OpenAI returned {} tool calls", tool_calls.len());
+        Ok(LlmResponse { tool_calls })
+    }
+
+    /// Generate DomainModel using OpenAI structured outputs: the answer follows
+    /// domain_model.schema.json by construction
+    async fn generate_domain_model_openai(
+        &self,
+        endpoint: &str,
+        api_key: &str,
+        model: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<DomainModelResponse> {
+        let response_format = openai_schema::domain_model_response_format()?;
+        let message = self
+            .openai_chat(endpoint, api_key, model, system_prompt, user_prompt, json!({"response_format": response_format}))
+            .await?;
+        let content = message["content"]
+            .as_str()
+            .context("Failed to extract content from OpenAI response")?;
+
+        let mut domain_model: Value = serde_json::from_str(content)
+            .context("Failed to parse DomainModel from OpenAI response")?;
+        openai_schema::strip_nulls(&mut domain_model);
+        serde_json::from_value(domain_model).context("Failed to parse DomainModel from OpenAI response")
+    }
+
     /// Generate DomainModel using Ollama
     async fn generate_domain_model_ollama(
         &self,
@@ -243,14 +522,15 @@ impl LlmRouter {
         user_prompt: &str,
     ) -> Result<DomainModelResponse> {
         let url = format!("{}/api/generate", base_url);
-        let model = env::var("OLLAMA_MODEL").unwrap_or_else(|_| "domain-model-mistral".to_string());
+        let model = ollama_model();
         
-        let request_body = json!({
+        let mut request_body = json!({
             "model": model,
             "prompt": format!("{}\n\nUser: {}", system_prompt, user_prompt),
             "stream": false,
             "format": "json"
         });
+        self.options.apply_ollama(&mut request_body);
 
         let response = self
             .client
@@ -288,14 +568,14 @@ impl LlmRouter {
         system_prompt: &str,
         user_prompt: &str,
     ) -> Result<DomainModelResponse> {
-        let request_body = json!({
+        let mut request_body = json!({
             "messages": [
                 {"role": "system", "content": system_prompt},
                 {"role": "user", "content": user_prompt}
             ],
-            "temperature": 0.7,
             "response_format": {"type": "json_object"}
         });
+        self.options.apply_external(&mut request_body);
 
         let response = self
             .client
@@ -332,6 +612,94 @@ impl LlmRouter {
         Ok(domain_model)
     }
 
+    /// Generate a JSON object using Ollama
+    async fn generate_json_ollama(
+        &self,
+        base_url: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<Value> {
+        let url = format!("{}/api/generate", base_url);
+        let model = ollama_model();
+
+        let mut request_body = json!({
+            "model": model,
+            "prompt": format!("{}\n\nUser: {}", system_prompt, user_prompt),
+            "stream": false,
+            "format": "json"
+        });
+        self.options.apply_ollama(&mut request_body);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&request_body)
+            .send()
+            .await
+            .context("Failed to send request to Ollama")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Ollama API error: {}", response.status());
+        }
+
+        let ollama_response: OllamaResponse = response
+            .json()
+            .await
+            .context("Failed to parse Ollama response")?;
+
+        serde_json::from_str(&ollama_response.response)
+            .context("Failed to parse JSON from Ollama response")
+    }
+
+    /// Generate a JSON object using external provider
+    async fn generate_json_external(
+        &self,
+        endpoint: &str,
+        api_key: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<Value> {
+        let mut request_body = json!({
+            "messages": [
+                {"role": "system", "content": system_prompt},
+                {"role": "user", "content": user_prompt}
+            ],
+            "response_format": {"type": "json_object"}
+        });
+        self.options.apply_external(&mut request_body);
+
+        let response = self
+            .client
+            .post(endpoint)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await
+            .context("Failed to send request to external provider")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("External API error {}: {}", status, error_text);
+        }
+
+        let response_json: Value = response
+            .json()
+            .await
+            .context("Failed to parse external provider response")?;
+
+        let content = response_json
+            .get("choices")
+            .and_then(|c| c.get(0))
+            .and_then(|c| c.get("message"))
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_str())
+            .context("Failed to extract content from external provider response")?;
+
+        serde_json::from_str(content).context("Failed to parse JSON from external provider response")
+    }
+
     /// Generate text using Ollama
     async fn generate_text_ollama(
         &self,
@@ -340,7 +708,7 @@ impl LlmRouter {
         user_prompt: &str,
     ) -> Result<String> {
         let url = format!("{}/api/generate", base_url);
-        let model = env::var("OLLAMA_MODEL").unwrap_or_else(|_| "domain-model-mistral".to_string());
+        let model = ollama_model();
         
         log::info!("[LLM Router] Generating text with Ollama");
         log::info!("[LLM Router] URL: {}", url);
@@ -348,11 +716,12 @@ impl LlmRouter {
         log::info!("[LLM Router] System prompt length: {} chars", system_prompt.len());
         log::info!("[LLM Router] User prompt length: {} chars", user_prompt.len());
         
-        let request_body = json!({
+        let mut request_body = json!({
             "model": model,
             "prompt": format!("{}\n\nUser: {}", system_prompt, user_prompt),
             "stream": false
         });
+        self.options.apply_ollama(&mut request_body);
 
         log::info!("[LLM Router] Sending POST request to Ollama...");
         
@@ -393,13 +762,13 @@ impl LlmRouter {
         system_prompt: &str,
         user_prompt: &str,
     ) -> Result<String> {
-        let request_body = json!({
+        let mut request_body = json!({
             "messages": [
                 {"role": "system", "content": system_prompt},
                 {"role": "user", "content": user_prompt}
-            ],
-            "temperature": 0.7
+            ]
         });
+        self.options.apply_external(&mut request_body);
 
         let response = self
             .client
@@ -503,6 +872,39 @@ mod tests {
         let _ = result;
     }
 
+    #[test]
+    fn test_request_options_apply() {
+        let options = LlmRequestOptions { temperature: Some(0.0), top_p: None, seed: Some(42) };
+
+        let mut external = json!({"messages": []});
+        options.apply_external(&mut external);
+        assert_eq!(external["temperature"], json!(0.0));
+        assert_eq!(external["seed"], json!(42));
+        assert!(external.get("top_p").is_none());
+
+        let mut ollama = json!({"model": "m"});
+        options.apply_ollama(&mut ollama);
+        assert_eq!(ollama["options"], json!({"temperature": 0.0, "seed": 42}));
+
+        // No options: external keeps its historical default, Ollama its own defaults
+        let mut external = json!({});
+        LlmRequestOptions::default().apply_external(&mut external);
+        assert_eq!(external["temperature"], json!(0.7));
+        let mut external = json!({});
+        LlmRequestOptions { temperature: Some(0.3), top_p: Some(0.9), seed: None }.apply_external(&mut external);
+        assert_eq!(external.to_string(), r#"{"temperature":0.3,"top_p":0.9}"#);
+        let mut ollama = json!({});
+        LlmRequestOptions::default().apply_ollama(&mut ollama);
+        assert!(ollama.get("options").is_none());
+    }
+
+    #[test]
+    fn test_request_options_or() {
+        let defaults = LlmRequestOptions { temperature: Some(0.2), top_p: Some(0.9), seed: None };
+        let merged = LlmRequestOptions { temperature: None, top_p: None, seed: Some(7) }.or(&defaults);
+        assert_eq!(merged, LlmRequestOptions { temperature: Some(0.2), top_p: Some(0.9), seed: Some(7) });
+    }
+
     #[test]
     fn test_tool_call_serialization() {
         let tool_call = ToolCall {
@@ -572,6 +974,43 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_generate_domain_model_openai() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let content = json!({
+            "entities": [{"id": "Order", "name": "Commande", "description": null, "aggregateRoot": true, "primaryKey": null, "uniqueConstraints": null,
+                "attributes": [{"name": "id", "type": "uuid", "description": null, "required": true, "unique": null, "validation": null}]}],
+            "relations": [],
+            "invariants": []
+        });
+        let completion = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", "Bearer sk-test")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "model": "gpt-4o-mini",
+                "response_format": {"type": "json_schema", "json_schema": {"name": "domain_model", "strict": true}}
+            })))
+            .with_body(json!({"choices": [{"message": {"role": "assistant", "content": content.to_string(), "refusal": null}}]}).to_string())
+            .create_async()
+            .await;
+
+        let router = LlmRouter {
+            provider: LlmProvider::OpenAi {
+                api_key: "sk-test".to_string(),
+                endpoint: format!("{}/v1/chat/completions", server.url()),
+                model: "gpt-4o-mini".to_string(),
+            },
+            client: reqwest::Client::new(),
+            options: LlmRequestOptions::default(),
+        };
+        let model = router.generate_domain_model("Système", "Un client passe des commandes").await?;
+        completion.assert_async().await;
+        // Nulls of the unset optional fields are dropped
+        assert_eq!(model.entities[0], json!({"id": "Order", "name": "Commande", "aggregateRoot": true,
+            "attributes": [{"name": "id", "type": "uuid", "required": true}]}));
+        Ok(())
+    }
+
     #[test]
     fn test_llm_router_new_creates_client() {
         env::set_var("LLM_PROVIDER", "ollama");
@@ -2,6 +2,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use clap::Parser;
+use domain_model_note_taking_lib::safe_write;
 use std::path::PathBuf;
 
 mod whisper;
@@ -14,6 +15,11 @@ struct Args {
     #[arg(long)]
     stt_input: Option<PathBuf>,
 
+    /// Transcribe every audio file of this folder: one markdown per file and
+    /// transcriptions.md in --output-dir (default: <folder>/transcriptions)
+    #[arg(long, conflicts_with = "stt_input")]
+    stt_input_dir: Option<PathBuf>,
+
     /// Path to the Whisper model file
     #[arg(long)]
     model: Option<PathBuf>,
@@ -22,6 +28,10 @@ struct Args {
     #[arg(long)]
     emit_md: Option<PathBuf>,
 
+    /// Output subtitles of the transcription with --stt-input (.srt or .vtt)
+    #[arg(long)]
+    emit_subtitles: Option<PathBuf>,
+
     /// Enable audio streaming mode with VAD
     #[arg(long)]
     stream: bool,
@@ -34,14 +44,25 @@ struct Args {
     #[arg(long, default_value = "1000")]
     max_chunk_ms: u32,
 
-    /// Output directory for audio chunks
+    /// Output directory for audio chunks, or for the transcripts of --stt-input-dir
     #[arg(long)]
     output_dir: Option<PathBuf>,
+
+    /// Spoken language of the audio (ISO 639-1 code: fr, en, de...), or "auto" to detect it
+    #[arg(long, default_value = domain_model_note_taking_lib::speech_to_text::DEFAULT_LANGUAGE)]
+    language: String,
 }
 
 fn main() {
     env_logger::init();
-    let args = Args::parse();
+    let mut args = Args::parse();
+    args.language = match domain_model_note_taking_lib::speech_to_text::normalize_language(&args.language) {
+        Ok(language) => language,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Handle streaming mode
     if args.stream {
@@ -52,6 +73,14 @@ fn main() {
         return;
     }
 
+    if let Some(dir) = &args.stt_input_dir {
+        if let Err(e) = run_batch_mode(&args, dir) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // If CLI arguments are provided, run in CLI mode
     if let Some(audio_path) = &args.stt_input {
         // Use default model if not provided
@@ -59,7 +88,11 @@ fn main() {
             std::path::Path::new("models/ggml-base.bin")
         });
 
-        match whisper::transcribe_audio(model_path, audio_path) {
+        let transcription = match &args.emit_subtitles {
+            Some(subtitles_path) => write_subtitles(model_path, audio_path, &args.language, subtitles_path),
+            None => whisper::transcribe_audio(model_path, audio_path, &args.language),
+        };
+        match transcription {
             Ok(text) => {
                 // If --emit-md is provided, write to file
                 if let Some(output_path) = &args.emit_md {
@@ -89,56 +122,99 @@ fn main() {
     }
 }
 
-fn write_markdown_transcript(path: &PathBuf, text: &str) -> std::io::Result<()> {
+fn write_markdown_transcript(path: &PathBuf, text: &str) -> anyhow::Result<()> {
     use std::fs;
-    use std::io::Write;
 
     // Create parent directories if they don't exist
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    let mut file = fs::File::create(path)?;
-    writeln!(file, "# Transcription\n")?;
-    writeln!(file, "{}", text)?;
-    
+    safe_write::write(path, format!("# Transcription\n\n{}\n", text))
+}
+
+/// Transcribe with timed segments and write them as subtitles (format from the
+/// extension). Returns the text.
+fn write_subtitles(model_path: &std::path::Path, audio_path: &std::path::Path, language: &str, path: &std::path::Path) -> anyhow::Result<String> {
+    use domain_model_note_taking_lib::speech_to_text::SpeechToText;
+    use domain_model_note_taking_lib::transcript_export::{self, TranscriptFormat};
+
+    let format = TranscriptFormat::from_path(path)?;
+    let stt = SpeechToText::new(model_path.to_path_buf());
+    stt.set_language(language);
+    let result = stt.transcribe_file_with_segments(audio_path, |_| {})?;
+    let audio_ms = result.segments.last().map(|segment| segment.end_ms.max(0) as u64).unwrap_or(0);
+    let cues = transcript_export::cues(&result.segments, &result.text, audio_ms, 0);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    safe_write::write(path, transcript_export::render(format, &cues))?;
+    println!("Subtitles written to: {:?}", path);
+    Ok(result.text)
+}
+
+/// Transcribe a folder with one shared model, printing each file as it is processed
+fn run_batch_mode(args: &Args, dir: &std::path::Path) -> anyhow::Result<()> {
+    use domain_model_note_taking_lib::batch_transcription::{self, BatchFileStatus};
+    use domain_model_note_taking_lib::speech_to_text::{SpeechToText, TranscribeOptions};
+
+    let model_path = args.model.clone().unwrap_or_else(|| PathBuf::from("models/ggml-base.bin"));
+    let output_dir = args.output_dir.clone().unwrap_or_else(|| dir.join(batch_transcription::DEFAULT_OUTPUT_DIR));
+    let stt = SpeechToText::new(model_path);
+    stt.set_language(&args.language);
+
+    let result = batch_transcription::transcribe_folder(
+        dir,
+        &output_dir,
+        |path| stt.transcribe_file_with_options(path, &TranscribeOptions::default(), |_| {}).map(|result| result.text),
+        |progress| match progress.status {
+            BatchFileStatus::Started => println!("[{}/{}] Transcribing {}...", progress.index, progress.total, progress.file_name),
+            BatchFileStatus::Done => println!("[{}/{}] {} done", progress.index, progress.total, progress.file_name),
+            BatchFileStatus::Failed => eprintln!(
+                "[{}/{}] {} failed: {}",
+                progress.index,
+                progress.total,
+                progress.file_name,
+                progress.error.unwrap_or_default()
+            ),
+        },
+    )?;
+
+    let failed = result.files.iter().filter(|file| file.error.is_some()).count();
+    println!(
+        "{} of {} files transcribed, aggregated transcript written to: {:?}",
+        result.files.len() - failed,
+        result.files.len(),
+        result.aggregate_path
+    );
     Ok(())
 }
 
-fn initialize_markdown_file(path: &PathBuf) -> std::io::Result<()> {
+fn initialize_markdown_file(path: &PathBuf) -> anyhow::Result<()> {
     use std::fs;
-    use std::io::Write;
 
     // Create parent directories if they don't exist
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    let mut file = fs::File::create(path)?;
-    writeln!(file, "# Live Transcription\n")?;
-    writeln!(file, "*Recording started...*\n")?;
-    
-    Ok(())
+    safe_write::write(path, "# Live Transcription\n\n*Recording started...*\n\n")
 }
 
-fn append_to_markdown(path: &PathBuf, utterance_id: usize, text: &str) -> std::io::Result<()> {
-    use std::fs::OpenOptions;
-    use std::io::Write;
-
-    let mut file = OpenOptions::new()
-        .append(true)
-        .open(path)?;
-    
-    writeln!(file, "## Segment {}\n", utterance_id)?;
-    writeln!(file, "{}\n", text)?;
-    
-    Ok(())
+/// Rewrites the whole file rather than appending, so a crash mid-write never leaves a
+/// half-written segment (live transcripts stay small)
+fn append_to_markdown(path: &PathBuf, utterance_id: usize, text: &str) -> anyhow::Result<()> {
+    let mut content = std::fs::read_to_string(path)?;
+    content.push_str(&format!("## Segment {}\n\n{}\n\n", utterance_id, text));
+    safe_write::write(path, content)
 }
 
 fn transcription_worker(
     session: domain_model_note_taking_lib::audio_session::AudioSession,
     model_path: PathBuf,
     md_path: PathBuf,
+    language: String,
 ) {
     use std::collections::HashSet;
     use std::time::Duration;
@@ -159,7 +235,7 @@ fn transcription_worker(
             
             println!("Transcribing segment {}...", utterance.id);
             
-            match whisper::transcribe_audio(&model_path, &utterance.file_path) {
+            match whisper::transcribe_audio(&model_path, &utterance.file_path, &language) {
                 Ok(text) => {
                     if let Err(e) = append_to_markdown(&md_path, utterance.id, &text) {
                         eprintln!("Error appending to markdown: {}", e);
@@ -177,12 +253,13 @@ fn transcription_worker(
 }
 
 fn run_streaming_mode(args: &Args) -> anyhow::Result<()> {
-    use domain_model_note_taking_lib::audio_session::{AudioSession, AudioSessionConfig};
+    use domain_model_note_taking_lib::audio_session::{AudioSession, AudioSessionConfig, AudioSource};
     use webrtc_vad::VadMode;
 
     println!("=== Audio Streaming Mode ===");
     println!("VAD Threshold: {}", args.vad_threshold);
     println!("Max Chunk Duration: {}ms", args.max_chunk_ms);
+    println!("Language: {}", args.language);
     println!();
 
     // Map threshold to VadMode
@@ -220,7 +297,7 @@ fn run_streaming_mode(args: &Args) -> anyhow::Result<()> {
         // Initialize the markdown file
         if let Err(e) = initialize_markdown_file(md_path) {
             eprintln!("Error initializing markdown file: {}", e);
-            return Err(e.into());
+            return Err(e);
         }
     }
     println!();
@@ -228,6 +305,7 @@ fn run_streaming_mode(args: &Args) -> anyhow::Result<()> {
     let config = AudioSessionConfig {
         silence_duration_ms: args.max_chunk_ms,
         min_utterance_duration_ms: 300,
+        max_utterance_ms: 30_000,
         output_dir,
         vad_mode,
         device_name: None,
@@ -235,6 +313,15 @@ fn run_streaming_mode(args: &Args) -> anyhow::Result<()> {
         enable_agc: true,
         agc_target_level: 0.3,
         push_to_talk: false, // CLI mode uses VAD-based segmentation
+        virtual_input: None,
+        silence_alert_ms: 0,
+        power_profile: Default::default(),
+        archive_raw: false,
+        naming: Default::default(),
+        keep_in_memory: false,
+        source: AudioSource::Mic,
+        system_device: None,
+        echo_cancellation: false,
     };
 
     let session = AudioSession::new(config)?;
@@ -243,9 +330,10 @@ fn run_streaming_mode(args: &Args) -> anyhow::Result<()> {
     if let Some(md_path) = emit_md {
         let model_path_owned = model_path.to_path_buf();
         let session_clone = session.clone();
+        let language = args.language.clone();
         
         std::thread::spawn(move || {
-            transcription_worker(session_clone, model_path_owned, md_path);
+            transcription_worker(session_clone, model_path_owned, md_path, language);
         });
     }
     
@@ -82,6 +82,132 @@ impl McpClient {
         Ok(response)
     }
 
+    /// Call any MCP tool and return its raw result
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<Value> {
+        let mut child = self.spawn_server().await?;
+
+        let mut stdin = child.stdin.take().context("Failed to open stdin")?;
+        let stdout = child.stdout.take().context("Failed to open stdout")?;
+        let mut stdout_reader = BufReader::new(stdout);
+
+        // Initialize
+        let _init_response = Self::call_method(
+            &mut stdin,
+            &mut stdout_reader,
+            "initialize",
+            json!({}),
+            1,
+        )
+        .await?;
+
+        let tool_response = Self::call_method(
+            &mut stdin,
+            &mut stdout_reader,
+            "tools/call",
+            json!({
+                "name": name,
+                "arguments": arguments
+            }),
+            2,
+        )
+        .await?;
+
+        let result = tool_response
+            .result
+            .with_context(|| format!("Failed to get result from {}", name))?;
+
+        drop(stdin);
+        drop(stdout_reader);
+        let _ = child.wait().await;
+
+        Ok(result)
+    }
+
+    /// Call the normalize_relations tool, returning the normalized model and the applied changes
+    pub async fn normalize_relations(&self, model: Value) -> Result<(Value, Vec<String>)> {
+        let result = self.call_tool("normalize_relations", json!({ "model": model })).await?;
+
+        let model = result
+            .get("model")
+            .cloned()
+            .context("Failed to extract 'model' field from response")?;
+        let changes = result
+            .get("changes")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        Ok((model, changes))
+    }
+
+    /// Call the enforce_naming tool with the default naming policy, returning the renamed model and the fixups
+    pub async fn enforce_naming(&self, model: Value) -> Result<(Value, Vec<String>)> {
+        let result = self.call_tool("enforce_naming", json!({ "model": model })).await?;
+
+        let model = result
+            .get("model")
+            .cloned()
+            .context("Failed to extract 'model' field from response")?;
+        let fixups = result
+            .get("fixups")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        Ok((model, fixups))
+    }
+
+    /// Call the validate_model tool, returning validity and the error messages
+    pub async fn validation_report(&self, model: Value) -> Result<(bool, Vec<String>)> {
+        let result = self.call_tool("validate_model", json!({ "model": model })).await?;
+
+        let ok = result
+            .get("ok")
+            .and_then(|v| v.as_bool())
+            .context("Failed to extract 'ok' field from response")?;
+        let errors = result
+            .get("errors")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        Ok((ok, errors))
+    }
+
+    /// Call the diff_models tool (added/removed/modified elements from `before` to `after`)
+    pub async fn diff_models(&self, before: Value, after: Value) -> Result<Value> {
+        self.call_tool("diff_models", json!({ "before": before, "after": after })).await
+    }
+
+    /// Call the suggest_fixes tool (patches for the given validation errors, or for every detected problem)
+    pub async fn suggest_fixes(&self, model: Value, errors: Option<Vec<String>>) -> Result<Value> {
+        let mut arguments = json!({ "model": model });
+        if let Some(errors) = errors {
+            arguments["errors"] = json!(errors);
+        }
+        self.call_tool("suggest_fixes", arguments).await
+    }
+
+    /// Call the import_markdown tool (model, warnings, prose sections handed to the LLM)
+    pub async fn import_markdown(&self, markdown: &str, use_llm: bool) -> Result<Value> {
+        self.call_tool("import_markdown", json!({ "markdown": markdown, "use_llm": use_llm })).await
+    }
+
+    /// Call the traceability_matrix tool (rows, summary, markdown and CSV)
+    pub async fn traceability_matrix(
+        &self,
+        model: Value,
+        sources: Vec<Value>,
+        features_dir: Option<&str>,
+        code_dir: Option<&str>,
+    ) -> Result<Value> {
+        let mut arguments = json!({ "model": model, "sources": sources });
+        if let Some(dir) = features_dir {
+            arguments["features_dir"] = json!(dir);
+        }
+        if let Some(dir) = code_dir {
+            arguments["code_dir"] = json!(dir);
+        }
+        self.call_tool("traceability_matrix", arguments).await
+    }
+
     /// Call the emit_mermaid tool with a domain model
     pub async fn emit_mermaid(
         &self,
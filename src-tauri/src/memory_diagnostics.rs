@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::speech_to_text::{loaded_models, LoadedModel};
+
+/// Memory whisper.cpp needs on top of the model file (compute buffers and KV cache),
+/// per model family, from the whisper.cpp README figures
+const MODEL_OVERHEAD_MB: &[(&str, u64)] = &[
+    ("tiny", 200),
+    ("base", 250),
+    ("small", 390),
+    ("medium", 600),
+    ("large", 1000),
+];
+
+/// Whisper model available on disk, with the memory it would take once loaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelMemoryEstimate {
+    pub name: String,
+    pub path: String,
+    pub file_size_bytes: u64,
+    pub estimated_bytes: u64,
+    /// Whether it fits in the memory currently available (None = unknown)
+    pub fits: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryReport {
+    pub total_memory_bytes: Option<u64>,
+    pub available_memory_bytes: Option<u64>,
+    /// Resident memory of this process
+    pub process_rss_bytes: Option<u64>,
+    pub loaded_models: Vec<LoadedModel>,
+    pub models: Vec<ModelMemoryEstimate>,
+}
+
+/// Estimated memory of a loaded model: file size plus the overhead of its family
+/// (quantized files keep the overhead of their family, only the weights shrink)
+pub fn estimate_model_memory(file_name: &str, file_size_bytes: u64) -> u64 {
+    file_size_bytes + state_overhead_bytes(file_name, file_size_bytes)
+}
+
+/// Compute buffers and KV cache of one transcription in progress: what each extra
+/// concurrent transcription costs, the weights being shared
+pub fn state_overhead_bytes(file_name: &str, file_size_bytes: u64) -> u64 {
+    let name = file_name.to_lowercase();
+    let overhead_mb = MODEL_OVERHEAD_MB
+        .iter()
+        .find(|(family, _)| name.contains(family))
+        .map(|(_, mb)| *mb)
+        .unwrap_or(file_size_bytes / (1024 * 1024) / 3 + 200);
+    overhead_mb * 1024 * 1024
+}
+
+/// Memory the app can use: the available memory, or half of the total when the
+/// system does not report it
+pub fn memory_budget() -> Option<u64> {
+    let (total, available) = system_memory();
+    available.or(total.map(|t| t / 2))
+}
+
+/// `MemTotal` and `MemAvailable` from /proc/meminfo, in bytes
+fn parse_meminfo(content: &str) -> (Option<u64>, Option<u64>) {
+    let field = |name: &str| {
+        content
+            .lines()
+            .find(|line| line.starts_with(name))
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    };
+    (field("MemTotal:"), field("MemAvailable:"))
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Total and available system memory
+fn system_memory() -> (Option<u64>, Option<u64>) {
+    if cfg!(target_os = "linux") {
+        std::fs::read_to_string("/proc/meminfo").map(|c| parse_meminfo(&c)).unwrap_or((None, None))
+    } else if cfg!(target_os = "macos") {
+        let total = command_output("sysctl", &["-n", "hw.memsize"]).and_then(|s| s.parse().ok());
+        (total, None)
+    } else {
+        (None, None)
+    }
+}
+
+fn process_rss_bytes() -> Option<u64> {
+    if cfg!(target_os = "linux") {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let kb: u64 = status.lines().find(|l| l.starts_with("VmRSS:"))?.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kb * 1024)
+    } else if cfg!(target_os = "macos") {
+        let kb: u64 = command_output("ps", &["-o", "rss=", "-p", &std::process::id().to_string()])?.parse().ok()?;
+        Some(kb * 1024)
+    } else {
+        None
+    }
+}
+
+/// Memory usage and, for each `.bin` model in `models_dir`, whether it fits.
+/// Without an "available" figure, half of the total memory is assumed usable.
+pub fn report(models_dir: Option<&Path>) -> MemoryReport {
+    let (total, available) = system_memory();
+    let budget = available.or(total.map(|t| t / 2));
+
+    let mut models = Vec::new();
+    if let Some(entries) = models_dir.and_then(|dir| std::fs::read_dir(dir).ok()) {
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().and_then(|e| e.to_str()) != Some("bin") {
+                continue;
+            }
+            let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let file_size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            let estimated_bytes = estimate_model_memory(&name, file_size_bytes);
+            models.push(ModelMemoryEstimate {
+                name,
+                path: path.display().to_string(),
+                file_size_bytes,
+                estimated_bytes,
+                fits: budget.map(|b| estimated_bytes <= b),
+            });
+        }
+    }
+    models.sort_by_key(|m| m.estimated_bytes);
+
+    MemoryReport {
+        total_memory_bytes: total,
+        available_memory_bytes: available,
+        process_rss_bytes: process_rss_bytes(),
+        loaded_models: loaded_models(),
+        models,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimates_and_meminfo() {
+        const MB: u64 = 1024 * 1024;
+        assert_eq!(estimate_model_memory("ggml-small.bin", 466 * MB), 856 * MB);
+        // Quantized medium: smaller weights, same overhead
+        assert_eq!(estimate_model_memory("ggml-medium-q5_0.bin", 514 * MB), 1114 * MB);
+        assert_eq!(estimate_model_memory("custom.bin", 300 * MB), 600 * MB);
+
+        let meminfo = "MemTotal:        8053200 kB\nMemFree:          512000 kB\nMemAvailable:    2048000 kB\n";
+        assert_eq!(parse_meminfo(meminfo), (Some(8053200 * 1024), Some(2048000 * 1024)));
+        assert_eq!(parse_meminfo(""), (None, None));
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::safe_write::AtomicFile;
+
+/// Hugging Face repository of the ggml conversions published by whisper.cpp
+pub const HF_REPO: &str = "ggerganov/whisper.cpp";
+const HF_BASE_URL: &str = "https://huggingface.co";
+/// Model used when nothing else is configured
+pub const DEFAULT_MODEL: &str = "ggml-small.bin";
+/// Progress is reported at most every this many bytes
+const PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+
+/// Whisper model, available on Hugging Face and/or installed locally
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WhisperModelInfo {
+    /// File name, e.g. "ggml-small.bin"
+    pub name: String,
+    pub size_bytes: Option<u64>,
+    /// SHA-256 published by Hugging Face (None for local-only files)
+    pub sha256: Option<String>,
+    pub installed: bool,
+    pub path: Option<String>,
+}
+
+/// Payload of a "model-download-progress" event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub name: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+}
+
+/// Entry of the Hugging Face tree API (`/api/models/<repo>/tree/main`)
+#[derive(Debug, Deserialize)]
+struct TreeEntry {
+    path: String,
+    #[serde(default)]
+    size: u64,
+    lfs: Option<LfsInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LfsInfo {
+    /// SHA-256 of the file content
+    oid: String,
+    size: u64,
+}
+
+/// `<app data>/models/whisper`, where downloaded models are stored
+pub fn models_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("models").join("whisper")
+}
+
+/// Only plain `ggml-*.bin` file names are accepted (no paths)
+pub fn validate_model_name(name: &str) -> Result<()> {
+    let valid = name.starts_with("ggml-")
+        && name.ends_with(".bin")
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.contains("..");
+    if !valid {
+        anyhow::bail!("Invalid Whisper model name '{}', expected a ggml-*.bin file name", name);
+    }
+    Ok(())
+}
+
+/// File of a model picked by the user: a model name installed in `dir`
+/// ("ggml-base.en.bin") or the path of a model file placed elsewhere
+pub fn resolve_model(dir: &Path, model: &str) -> Result<PathBuf> {
+    if validate_model_name(model).is_ok() {
+        let installed = dir.join(model);
+        if installed.exists() {
+            return Ok(installed);
+        }
+    }
+    let path = PathBuf::from(model);
+    if path.is_file() {
+        return Ok(path);
+    }
+    anyhow::bail!("Whisper model '{}' is not installed, download it first", model)
+}
+
+/// Whether the model only transcribes English (".en" variants)
+pub fn is_english_only(model_path: &Path) -> bool {
+    model_path.file_name().map(|name| name.to_string_lossy().contains(".en")).unwrap_or(false)
+}
+
+/// Models of the repository tree: `ggml-*.bin` files with their size and checksum
+fn parse_tree(json: &str) -> Result<Vec<WhisperModelInfo>> {
+    let entries: Vec<TreeEntry> = serde_json::from_str(json).context("Invalid Hugging Face tree listing")?;
+    let mut models: Vec<WhisperModelInfo> = entries
+        .into_iter()
+        .filter(|entry| validate_model_name(&entry.path).is_ok())
+        .map(|entry| WhisperModelInfo {
+            size_bytes: Some(entry.lfs.as_ref().map(|lfs| lfs.size).unwrap_or(entry.size)),
+            sha256: entry.lfs.map(|lfs| lfs.oid),
+            name: entry.path,
+            installed: false,
+            path: None,
+        })
+        .collect();
+    models.sort_by_key(|m| m.size_bytes);
+    Ok(models)
+}
+
+/// Models published on Hugging Face
+pub async fn fetch_catalog() -> Result<Vec<WhisperModelInfo>> {
+    fetch_catalog_from(HF_BASE_URL).await
+}
+
+async fn fetch_catalog_from(base_url: &str) -> Result<Vec<WhisperModelInfo>> {
+    let url = format!("{}/api/models/{}/tree/main", base_url, HF_REPO);
+    let response = reqwest::get(&url).await.with_context(|| format!("Failed to list models from {}", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!("Listing models from {} failed: {}", url, response.status());
+    }
+    parse_tree(&response.text().await.context("Failed to read model listing")?)
+}
+
+/// `.bin` files of `dir`
+pub fn installed_models(dir: &Path) -> Vec<WhisperModelInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("bin"))
+        .map(|path| WhisperModelInfo {
+            name: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            size_bytes: std::fs::metadata(&path).map(|m| m.len()).ok(),
+            sha256: None,
+            installed: true,
+            path: Some(path.display().to_string()),
+        })
+        .collect()
+}
+
+/// Catalog entries marked as installed when present in `dir`, followed by the local
+/// files the catalog does not know (custom or bundled models)
+pub fn merge_installed(catalog: Vec<WhisperModelInfo>, dir: &Path) -> Vec<WhisperModelInfo> {
+    let mut local = installed_models(dir);
+    let mut models: Vec<WhisperModelInfo> = catalog
+        .into_iter()
+        .map(|model| match local.iter().position(|l| l.name == model.name) {
+            Some(index) => {
+                let installed = local.remove(index);
+                WhisperModelInfo { installed: true, path: installed.path, ..model }
+            }
+            None => model,
+        })
+        .collect();
+    models.extend(local);
+    models
+}
+
+/// Download `model` into `dir`, reporting progress, and check it against the catalog
+/// checksum. The file only appears under its name once verified.
+pub async fn download_model<F>(model: &WhisperModelInfo, dir: &Path, on_progress: F) -> Result<PathBuf>
+where
+    F: FnMut(DownloadProgress),
+{
+    let url = format!("{}/{}/resolve/main/{}", HF_BASE_URL, HF_REPO, model.name);
+    download_from(&url, model, dir, on_progress).await
+}
+
+async fn download_from<F>(url: &str, model: &WhisperModelInfo, dir: &Path, mut on_progress: F) -> Result<PathBuf>
+where
+    F: FnMut(DownloadProgress),
+{
+    validate_model_name(&model.name)?;
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let path = dir.join(&model.name);
+
+    let mut response = reqwest::get(url).await.with_context(|| format!("Failed to download {}", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!("Download of {} failed: {}", url, response.status());
+    }
+    let total_bytes = response.content_length().or(model.size_bytes);
+
+    // Dropped without commit (error, checksum mismatch), the partial file is removed
+    let mut file = AtomicFile::create(&path)?;
+    let mut hasher = Sha256::new();
+    let mut downloaded_bytes = 0u64;
+    let mut reported = 0u64;
+    while let Some(chunk) = response.chunk().await.context("Download interrupted")? {
+        file.write_all(&chunk).context("Failed to write model file")?;
+        hasher.update(&chunk);
+        downloaded_bytes += chunk.len() as u64;
+        if downloaded_bytes - reported >= PROGRESS_STEP_BYTES {
+            reported = downloaded_bytes;
+            on_progress(DownloadProgress { name: model.name.clone(), downloaded_bytes, total_bytes });
+        }
+    }
+    on_progress(DownloadProgress { name: model.name.clone(), downloaded_bytes, total_bytes });
+
+    let checksum = format!("{:x}", hasher.finalize());
+    match &model.sha256 {
+        Some(expected) if !expected.eq_ignore_ascii_case(&checksum) => {
+            anyhow::bail!("Checksum mismatch for {}: expected {}, got {}", model.name, expected, checksum)
+        }
+        Some(_) => {}
+        None => log::warn!("[Models] No published checksum for {}, stored unverified", model.name),
+    }
+    file.commit()?;
+    log::info!("[Models] Downloaded {} ({} bytes) to {:?}", model.name, downloaded_bytes, path);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_parsing_and_merge() {
+        let tree = r#"[
+            {"type": "file", "path": "README.md", "size": 1200},
+            {"type": "file", "path": "ggml-small.bin", "size": 134, "lfs": {"oid": "abc", "size": 487601967}},
+            {"type": "file", "path": "ggml-tiny.bin", "size": 134, "lfs": {"oid": "def", "size": 77691713}},
+            {"type": "directory", "path": "coreml", "size": 0}
+        ]"#;
+        let catalog = parse_tree(tree).unwrap();
+        assert_eq!(catalog.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), vec!["ggml-tiny.bin", "ggml-small.bin"]);
+        assert_eq!(catalog[1].size_bytes, Some(487601967));
+
+        let dir = std::env::temp_dir().join(format!("model_manager_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ggml-small.bin"), b"weights").unwrap();
+        std::fs::write(dir.join("ggml-custom.bin"), b"weights").unwrap();
+        let models = merge_installed(catalog, &dir);
+        assert!(models.iter().find(|m| m.name == "ggml-small.bin").unwrap().installed);
+        assert!(!models.iter().find(|m| m.name == "ggml-tiny.bin").unwrap().installed);
+        assert!(models.iter().any(|m| m.name == "ggml-custom.bin" && m.installed));
+        assert_eq!(resolve_model(&dir, "ggml-small.bin").unwrap(), dir.join("ggml-small.bin"));
+        assert!(resolve_model(&dir, "ggml-tiny.bin").is_err(), "not downloaded");
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(is_english_only(Path::new("/models/ggml-base.en.bin")));
+        assert!(validate_model_name("../ggml-small.bin").is_err());
+        assert!(validate_model_name("ggml-large-v3-q5_0.bin").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_download_checks_checksum() {
+        let mut server = mockito::Server::new_async().await;
+        let body = b"not really a model";
+        let mock = server.mock("GET", "/ggml-tiny.bin").with_body(body).expect(2).create_async().await;
+        let url = format!("{}/ggml-tiny.bin", server.url());
+        let dir = std::env::temp_dir().join(format!("model_download_{}", std::process::id()));
+
+        let mut model = WhisperModelInfo {
+            name: "ggml-tiny.bin".to_string(),
+            size_bytes: Some(body.len() as u64),
+            sha256: Some("0".repeat(64)),
+            installed: false,
+            path: None,
+        };
+        assert!(download_from(&url, &model, &dir, |_| {}).await.is_err());
+        assert!(std::fs::read_dir(&dir).unwrap().next().is_none(), "nothing left behind");
+
+        model.sha256 = Some(format!("{:x}", Sha256::digest(body)));
+        let mut last = None;
+        let path = download_from(&url, &model, &dir, |progress| last = Some(progress)).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert_eq!(last.unwrap().downloaded_bytes, body.len() as u64);
+        mock.assert_async().await;
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
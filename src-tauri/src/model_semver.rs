@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Semantic version of the first stored model
+pub const INITIAL_VERSION: &str = "1.0.0";
+
+/// Semver bump suggested by a model change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    None,
+    /// Wording only (names, descriptions, error messages)
+    Patch,
+    /// Compatible: new entities, optional attributes, relations, loosened constraints
+    Minor,
+    /// Breaking: something existing data or code relies on was removed or tightened
+    Major,
+}
+
+/// Classification of the changes between two models
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Classification {
+    pub bump: Bump,
+    pub breaking: Vec<String>,
+    /// Additions and loosened constraints
+    pub compatible: Vec<String>,
+}
+
+fn items<'a>(model: &'a Value, key: &str) -> &'a [Value] {
+    model.get(key).and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[])
+}
+
+fn find<'a>(list: &'a [Value], key: &str, id: &str) -> Option<&'a Value> {
+    list.iter().find(|item| item[key] == id)
+}
+
+fn flag(item: &Value, key: &str) -> bool {
+    item[key].as_bool().unwrap_or(false)
+}
+
+/// (min, max) occurrences of a cardinality, `None` max for unbounded
+fn bounds(cardinality: &str) -> Option<(u32, Option<u32>)> {
+    match cardinality {
+        "0..1" => Some((0, Some(1))),
+        "1" => Some((1, Some(1))),
+        "0..n" | "*" => Some((0, None)),
+        "1..n" => Some((1, None)),
+        _ => None,
+    }
+}
+
+/// Some(true) if `after` accepts fewer cases than `before`, Some(false) if more,
+/// None if unchanged or unknown
+fn tightened(before: &str, after: &str) -> Option<bool> {
+    let ((min_before, max_before), (min_after, max_after)) = (bounds(before)?, bounds(after)?);
+    let max_tighter = match (max_before, max_after) {
+        (None, Some(_)) => true,
+        (Some(b), Some(a)) => a < b,
+        _ => false,
+    };
+    let max_looser = match (max_before, max_after) {
+        (Some(_), None) => true,
+        (Some(b), Some(a)) => a > b,
+        _ => false,
+    };
+    if min_after > min_before || max_tighter {
+        Some(true)
+    } else if min_after < min_before || max_looser {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Classify the change from `before` to `after` and suggest a semver bump
+pub fn classify(before: &Value, after: &Value) -> Classification {
+    let mut breaking = Vec::new();
+    let mut compatible = Vec::new();
+    let mut patch = false;
+
+    let (entities_before, entities_after) = (items(before, "entities"), items(after, "entities"));
+    for old in entities_before {
+        let id = old["id"].as_str().unwrap_or_default();
+        let Some(new) = find(entities_after, "id", id) else {
+            breaking.push(format!("Entity {} removed", id));
+            continue;
+        };
+        let (attrs_before, attrs_after) = (items(old, "attributes"), items(new, "attributes"));
+        for attr in attrs_before {
+            let name = attr["name"].as_str().unwrap_or_default();
+            let Some(new_attr) = find(attrs_after, "name", name) else {
+                breaking.push(format!("Attribute {}.{} removed", id, name));
+                continue;
+            };
+            if attr["type"] != new_attr["type"] {
+                breaking.push(format!("Attribute {}.{} changed type", id, name));
+            }
+            if !flag(attr, "required") && flag(new_attr, "required") {
+                breaking.push(format!("Attribute {}.{} is now required", id, name));
+            }
+            if !flag(attr, "unique") && flag(new_attr, "unique") {
+                breaking.push(format!("Attribute {}.{} is now unique", id, name));
+            }
+            patch |= attr != new_attr;
+        }
+        for attr in attrs_after {
+            let name = attr["name"].as_str().unwrap_or_default();
+            if find(attrs_before, "name", name).is_none() {
+                if flag(attr, "required") {
+                    breaking.push(format!("Required attribute {}.{} added", id, name));
+                } else {
+                    compatible.push(format!("Attribute {}.{} added", id, name));
+                }
+            }
+        }
+        let strip = |entity: &Value| {
+            let mut entity = entity.clone();
+            if let Some(map) = entity.as_object_mut() {
+                map.remove("attributes");
+            }
+            entity
+        };
+        patch |= strip(old) != strip(new);
+    }
+    for new in entities_after {
+        let id = new["id"].as_str().unwrap_or_default();
+        if find(entities_before, "id", id).is_none() {
+            compatible.push(format!("Entity {} added", id));
+        }
+    }
+
+    let (relations_before, relations_after) = (items(before, "relations"), items(after, "relations"));
+    for old in relations_before {
+        let id = old["id"].as_str().unwrap_or_default();
+        let Some(new) = find(relations_after, "id", id) else {
+            breaking.push(format!("Relation {} removed", id));
+            continue;
+        };
+        for side in ["from", "to"] {
+            let (before_card, after_card) = (old["cardinality"][side].as_str(), new["cardinality"][side].as_str());
+            match tightened(before_card.unwrap_or_default(), after_card.unwrap_or_default()) {
+                Some(true) => breaking.push(format!(
+                    "Relation {} cardinality ({}) tightened from {} to {}",
+                    id, side, before_card.unwrap_or_default(), after_card.unwrap_or_default()
+                )),
+                Some(false) => compatible.push(format!("Relation {} cardinality ({}) loosened", id, side)),
+                None => {}
+            }
+        }
+        if old["from"]["entityId"] != new["from"]["entityId"] || old["to"]["entityId"] != new["to"]["entityId"] {
+            breaking.push(format!("Relation {} now links other entities", id));
+        }
+        patch |= old != new;
+    }
+    for new in relations_after {
+        let id = new["id"].as_str().unwrap_or_default();
+        if find(relations_before, "id", id).is_none() {
+            compatible.push(format!("Relation {} added", id));
+        }
+    }
+
+    // A new or rewritten rule can reject existing data; a dropped one only loosens the model
+    let (invariants_before, invariants_after) = (items(before, "invariants"), items(after, "invariants"));
+    for old in invariants_before {
+        let id = old["id"].as_str().unwrap_or_default();
+        match find(invariants_after, "id", id) {
+            None => compatible.push(format!("Invariant {} removed", id)),
+            Some(new) if old["expression"] != new["expression"] => {
+                breaking.push(format!("Invariant {} expression changed", id))
+            }
+            Some(new) => patch |= old != new,
+        }
+    }
+    for new in invariants_after {
+        let id = new["id"].as_str().unwrap_or_default();
+        if find(invariants_before, "id", id).is_none() {
+            breaking.push(format!("Invariant {} added", id));
+        }
+    }
+
+    let bump = if !breaking.is_empty() {
+        Bump::Major
+    } else if !compatible.is_empty() {
+        Bump::Minor
+    } else if patch {
+        Bump::Patch
+    } else {
+        Bump::None
+    };
+    Classification { bump, breaking, compatible }
+}
+
+/// Apply `bump` to a "major.minor.patch" version (an unparsable one restarts at INITIAL_VERSION)
+pub fn bump_version(version: &str, bump: Bump) -> String {
+    let parts: Vec<u64> = version.split('.').filter_map(|part| part.parse().ok()).collect();
+    let [major, minor, patch] = parts[..] else {
+        return INITIAL_VERSION.to_string();
+    };
+    match bump {
+        Bump::None => format!("{}.{}.{}", major, minor, patch),
+        Bump::Patch => format!("{}.{}.{}", major, minor, patch + 1),
+        Bump::Minor => format!("{}.{}.0", major, minor + 1),
+        Bump::Major => format!("{}.0.0", major + 1),
+    }
+}
+
+/// First line of tagged markdown exports
+const TAG_PREFIX: &str = "<!-- domain-model-version: ";
+
+/// Tag a markdown export with the model version, replacing a previous tag
+pub fn tag_markdown(markdown: &str, version: &str) -> String {
+    let body = match markdown.strip_prefix(TAG_PREFIX) {
+        Some(rest) => rest.split_once('\n').map_or("", |(_, body)| body),
+        None => markdown,
+    };
+    format!("{}{} -->\n{}", TAG_PREFIX, version, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn model(attributes: Value, cardinality_to: &str, invariants: Value) -> Value {
+        json!({
+            "entities": [
+                {"id": "Customer", "name": "Customer", "attributes": [{"name": "email", "type": "email"}]},
+                {"id": "Order", "name": "Order", "attributes": attributes}
+            ],
+            "relations": [{
+                "id": "customer_places_order", "name": "places",
+                "from": {"entityId": "Customer"}, "to": {"entityId": "Order"},
+                "cardinality": {"from": "1", "to": cardinality_to}
+            }],
+            "invariants": invariants
+        })
+    }
+
+    #[test]
+    fn test_classification() {
+        let base = model(json!([{"name": "total", "type": "number"}]), "0..n", json!([]));
+        assert_eq!(classify(&base, &base).bump, Bump::None);
+
+        let mut renamed = base.clone();
+        renamed["entities"][1]["description"] = json!("Commande client");
+        assert_eq!(classify(&base, &renamed).bump, Bump::Patch);
+
+        let additive = model(json!([{"name": "total", "type": "number"}, {"name": "note", "type": "text"}]), "0..n", json!([]));
+        let result = classify(&base, &additive);
+        assert_eq!(result.bump, Bump::Minor);
+        assert_eq!(result.compatible, vec!["Attribute Order.note added"]);
+
+        let tightened = model(json!([{"name": "total", "type": "number"}]), "1..n", json!([]));
+        let result = classify(&base, &tightened);
+        assert_eq!(result.bump, Bump::Major);
+        assert_eq!(result.breaking, vec!["Relation customer_places_order cardinality (to) tightened from 0..n to 1..n"]);
+        assert_eq!(classify(&tightened, &base).bump, Bump::Minor);
+
+        let removed = model(json!([]), "0..n", json!([{"id": "positive_total", "expression": "total > 0"}]));
+        let result = classify(&base, &removed);
+        assert_eq!(result.breaking, vec!["Attribute Order.total removed", "Invariant positive_total added"]);
+    }
+
+    #[test]
+    fn test_bump_and_tag() {
+        assert_eq!(bump_version("1.2.3", Bump::Patch), "1.2.4");
+        assert_eq!(bump_version("1.2.3", Bump::Minor), "1.3.0");
+        assert_eq!(bump_version("1.2.3", Bump::Major), "2.0.0");
+        assert_eq!(bump_version("garbage", Bump::Minor), INITIAL_VERSION);
+
+        let tagged = tag_markdown("# Canvas\n", "1.3.0");
+        assert_eq!(tagged, "<!-- domain-model-version: 1.3.0 -->\n# Canvas\n");
+        assert_eq!(tag_markdown(&tagged, "2.0.0"), "<!-- domain-model-version: 2.0.0 -->\n# Canvas\n");
+    }
+}
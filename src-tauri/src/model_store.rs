@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::artifact_store::{content_hash, ArtifactStore};
+use crate::json_patch::{self, PatchOperation};
+use crate::model_semver::{self, Bump};
+use crate::safe_write;
+
+/// Artifact name of the current model
+const MODEL_ARTIFACT: &str = "model.json";
+
+/// One entry of a project's model history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVersion {
+    pub version: u32,
+    pub timestamp: String,
+    /// What produced the version ("save", "orchestrate", "refine", "patch"...)
+    pub source: String,
+    /// Patch from the previous version (absent for the first one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<Vec<PatchOperation>>,
+    /// Semantic version ("1.2.0"), absent in histories written before it existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semver: Option<String>,
+    /// Bump from the previous version (absent for the first one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bump: Option<Bump>,
+    /// Changes that made the bump major
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub breaking_changes: Vec<String>,
+}
+
+/// Versioned domain model of a project, stored under `<app data>/models/<project>/`:
+/// `current.json`, one `v<N>.json` snapshot per version and `history.json`.
+/// The current model is also recorded as the `model.json` artifact of the project.
+pub struct ModelStore {
+    dir: PathBuf,
+    artifacts: ArtifactStore,
+}
+
+impl ModelStore {
+    pub fn open(app_data_dir: &Path, project_name: &str) -> Self {
+        let sanitized_name = crate::sanitize_project_name(project_name);
+        Self {
+            dir: app_data_dir.join("models").join(sanitized_name),
+            artifacts: ArtifactStore::open(app_data_dir, project_name),
+        }
+    }
+
+    /// Current model, if the project has one, checked against the hash recorded
+    /// in the artifact manifest
+    pub fn load(&self) -> Result<Option<Value>> {
+        let path = self.dir.join("current.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        if let Some(entry) = self.artifacts.manifest()?.artifacts.get(MODEL_ARTIFACT) {
+            if entry.hash != content_hash(content.as_bytes()) {
+                anyhow::bail!("Stored model {:?} does not match its recorded hash", path);
+            }
+        }
+        Ok(Some(serde_json::from_str(&content).context("Invalid stored model")?))
+    }
+
+    pub fn history(&self) -> Result<Vec<ModelVersion>> {
+        let path = self.dir.join("history.json");
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        serde_json::from_str(&content).context("Invalid model history")
+    }
+
+    /// Snapshot of a past version
+    pub fn load_version(&self, version: u32) -> Result<Value> {
+        let path = self.dir.join(format!("v{}.json", version));
+        let content = fs::read_to_string(&path).with_context(|| format!("Unknown model version {}", version))?;
+        serde_json::from_str(&content).context("Invalid model snapshot")
+    }
+
+    /// Store `model` as a new version, recording the patch from the current one.
+    /// Saving an unchanged model is a no-op returning the current version.
+    pub fn save(&self, model: &Value, source: &str) -> Result<ModelVersion> {
+        let mut history = self.history()?;
+        let (patch, classification) = match self.load()? {
+            Some(current) => {
+                let patch = json_patch::diff(&current, model);
+                if patch.is_empty() {
+                    if let Some(last) = history.last() {
+                        return Ok(last.clone());
+                    }
+                }
+                (Some(patch), Some(model_semver::classify(&current, model)))
+            }
+            None => (None, None),
+        };
+
+        let previous_semver = history.iter().rev().find_map(|v| v.semver.clone());
+        let semver = match (&previous_semver, &classification) {
+            (Some(previous), Some(classification)) => model_semver::bump_version(previous, classification.bump),
+            // Unversioned history: restart from the initial version
+            _ => model_semver::INITIAL_VERSION.to_string(),
+        };
+        let entry = ModelVersion {
+            version: history.last().map_or(1, |v| v.version + 1),
+            timestamp: chrono::Local::now().to_rfc3339(),
+            source: source.to_string(),
+            patch,
+            semver: Some(semver),
+            bump: classification.as_ref().map(|c| c.bump),
+            breaking_changes: classification.map(|c| c.breaking).unwrap_or_default(),
+        };
+
+        fs::create_dir_all(&self.dir).with_context(|| format!("Failed to create {:?}", self.dir))?;
+        let content = serde_json::to_string_pretty(model)?;
+        safe_write::write(self.dir.join(format!("v{}.json", entry.version)), &content)?;
+        safe_write::write(self.dir.join("current.json"), &content)?;
+        self.artifacts.put(MODEL_ARTIFACT, "model", content.as_bytes())?;
+        history.push(entry.clone());
+        safe_write::write(self.dir.join("history.json"), serde_json::to_string_pretty(&history)?)?;
+
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_save_records_patches() {
+        let app_data = std::env::temp_dir().join(format!("model_store_test_{}", std::process::id()));
+        let store = ModelStore::open(&app_data, "Mon projet");
+        assert!(store.load().unwrap().is_none());
+
+        let v1 = json!({"entities": [{"id": "Order", "name": "Order", "attributes": []}], "relations": [], "invariants": []});
+        let mut v2 = v1.clone();
+        v2["entities"][0]["name"] = json!("Commande");
+
+        let first = store.save(&v1, "orchestrate").unwrap();
+        assert_eq!((first.version, first.semver.as_deref()), (1, Some(model_semver::INITIAL_VERSION)));
+        let second = store.save(&v2, "patch").unwrap();
+        assert_eq!(second.version, 2);
+        // The replace, guarded by a test of the entity id
+        assert_eq!(second.patch.unwrap().len(), 2);
+        assert_eq!(second.semver.as_deref(), Some("1.0.1"));
+        assert_eq!(second.bump, Some(Bump::Patch));
+        // No change, no new version
+        assert_eq!(store.save(&v2, "save").unwrap().version, 2);
+
+        assert_eq!(store.load().unwrap().unwrap(), v2);
+        assert_eq!(store.load_version(1).unwrap(), v1);
+        assert_eq!(store.history().unwrap().len(), 2);
+        assert!(app_data.join("models").join("Mon_projet").exists());
+
+        // A current.json edited behind the store's back is refused
+        fs::write(app_data.join("models").join("Mon_projet").join("current.json"), "{}").unwrap();
+        assert!(store.load().is_err());
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
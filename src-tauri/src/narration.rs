@@ -0,0 +1,285 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::{Command, Stdio};
+
+use crate::transcript_normalize;
+
+/// Language of the narration when neither the request, the settings nor the model tell it
+const DEFAULT_LANGUAGE: &str = "fr";
+
+/// Plain-language reading of a model, to validate it aloud with the domain expert
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelNarration {
+    /// "fr" or "en"
+    pub language: String,
+    /// One sentence per entity, relation and invariant, in model order
+    pub sentences: Vec<String>,
+    pub text: String,
+    /// Sent to the speech synthesizer of the system
+    pub spoken: bool,
+}
+
+/// Words of a narration language
+struct Words {
+    intro_one: &'static str,
+    intro_many: &'static str,
+    and: &'static str,
+    has: &'static str,
+    required: &'static str,
+    unique: &'static str,
+    aggregate_root: &'static str,
+    each: &'static str,
+    linked_to: &'static str,
+    rule: &'static str,
+    warning: &'static str,
+    /// Colon and semicolon, with the French non-breaking space before them
+    colon: &'static str,
+    semicolon: &'static str,
+    quotes: (&'static str, &'static str),
+}
+
+const FR: Words = Words {
+    intro_one: "Le modèle compte une entité",
+    intro_many: "Le modèle compte {} entités",
+    and: "et",
+    has: "a",
+    required: "obligatoire",
+    unique: "unique",
+    aggregate_root: "est une racine d'agrégat",
+    each: "Chaque",
+    linked_to: "se rattache à",
+    rule: "Règle",
+    warning: "avertissement",
+    colon: "\u{a0}:",
+    semicolon: "\u{a0};",
+    quotes: ("«\u{a0}", "\u{a0}»"),
+};
+
+const EN: Words = Words {
+    intro_one: "The model has one entity",
+    intro_many: "The model has {} entities",
+    and: "and",
+    has: "has",
+    required: "required",
+    unique: "unique",
+    aggregate_root: "is an aggregate root",
+    each: "Each",
+    linked_to: "is linked to",
+    rule: "Rule",
+    warning: "warning",
+    colon: ":",
+    semicolon: ";",
+    quotes: ("“", "”"),
+};
+
+/// Language of the narration: the one asked, or the transcription language of the
+/// settings, or the one of the model descriptions when the settings say "auto". Only
+/// French and English are narrated.
+pub fn narration_language(requested: Option<&str>, setting: &str, model: &Value) -> String {
+    let language = match requested.filter(|l| *l != "auto").or(Some(setting).filter(|l| *l != "auto")) {
+        Some(language) => language.to_string(),
+        None => {
+            let text = ["entities", "relations", "invariants"]
+                .iter()
+                .flat_map(|list| model[*list].as_array().into_iter().flatten())
+                .filter_map(|item| item["description"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            transcript_normalize::guess_language(&text).unwrap_or(DEFAULT_LANGUAGE).to_string()
+        }
+    };
+    if language == "en" { language } else { DEFAULT_LANGUAGE.to_string() }
+}
+
+/// "a, b and c"
+fn list(items: &[String], and: &str) -> String {
+    match items {
+        [] => String::new(),
+        [one] => one.clone(),
+        [rest @ .., last] => format!("{} {} {}", rest.join(", "), and, last),
+    }
+}
+
+/// Plural of an entity name, good enough to be read aloud
+fn plural(name: &str) -> String {
+    if name.ends_with(['s', 'x', 'z']) { name.to_string() } else { format!("{}s", name) }
+}
+
+/// "zero or more Orders" for the cardinality of the `name` end of a relation
+fn quantity(cardinality: &str, name: &str, language: &str) -> String {
+    let (amount, many) = match (language, cardinality) {
+        ("en", "0..1") => ("at most one", false),
+        ("en", "1") => ("exactly one", false),
+        ("en", "1..n") => ("one or more", true),
+        ("en", _) => ("zero or more", true),
+        // Figures rather than "un"/"une": the gender of the entity is unknown
+        (_, "0..1") => ("au plus 1", false),
+        (_, "1") => ("exactement 1", false),
+        (_, "1..n") => ("au moins 1", true),
+        _ => ("0 ou plusieurs", true),
+    };
+    format!("{} {}", amount, if many { plural(name) } else { name.to_string() })
+}
+
+/// Narrate the entities with their attributes, the relations in both directions and the
+/// invariants of a DomainModel
+pub fn narrate(model: &Value, language: &str) -> Vec<String> {
+    let words = if language == "en" { &EN } else { &FR };
+    let items = |key: &str| model[key].as_array().cloned().unwrap_or_default();
+    let label = |item: &Value| item["name"].as_str().or_else(|| item["id"].as_str()).unwrap_or_default().to_string();
+    let entities = items("entities");
+    let entity_name = |id: &str| {
+        entities
+            .iter()
+            .find(|e| e["id"].as_str() == Some(id))
+            .map(label)
+            .unwrap_or_else(|| id.to_string())
+    };
+
+    let mut sentences = Vec::new();
+    if !entities.is_empty() {
+        let names: Vec<String> = entities.iter().map(label).collect();
+        let intro = if names.len() == 1 { words.intro_one.to_string() } else { words.intro_many.replace("{}", &names.len().to_string()) };
+        sentences.push(format!("{}{} {}.", intro, words.colon, list(&names, words.and)));
+    }
+    for entity in &entities {
+        let attributes: Vec<String> = entity["attributes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|attribute| {
+                let name = attribute["name"].as_str()?;
+                let flags: Vec<&str> = [("required", words.required), ("unique", words.unique)]
+                    .into_iter()
+                    .filter(|(flag, _)| attribute[*flag].as_bool() == Some(true))
+                    .map(|(_, word)| word)
+                    .collect();
+                Some(if flags.is_empty() { name.to_string() } else { format!("{} ({})", name, flags.join(", ")) })
+            })
+            .collect();
+        let mut parts = Vec::new();
+        if !attributes.is_empty() {
+            parts.push(format!("{} {} {} {}.", words.each, label(entity), words.has, list(&attributes, words.and)));
+        }
+        if entity["aggregateRoot"].as_bool() == Some(true) {
+            parts.push(format!("{} {}.", label(entity), words.aggregate_root));
+        }
+        if !parts.is_empty() {
+            sentences.push(parts.join(" "));
+        }
+    }
+
+    for relation in items("relations") {
+        let (Some(from), Some(to)) = (relation["from"]["entityId"].as_str(), relation["to"]["entityId"].as_str()) else { continue };
+        let (from, to) = (entity_name(from), entity_name(to));
+        let verb = relation["name"].as_str().filter(|n| !n.trim().is_empty()).unwrap_or(words.linked_to);
+        let cardinality = |end: &str| relation["cardinality"][end].as_str().unwrap_or("*").to_string();
+        sentences.push(format!(
+            "{} {} {} {}{} {} {} {} {}.",
+            words.each,
+            from,
+            verb,
+            quantity(&cardinality("to"), &to, language),
+            words.semicolon,
+            words.each.to_lowercase(),
+            to,
+            words.linked_to,
+            quantity(&cardinality("from"), &from, language)
+        ));
+    }
+
+    for invariant in items("invariants") {
+        let detail = invariant["description"].as_str().or_else(|| invariant["expression"].as_str()).unwrap_or_default();
+        let severity = if invariant["severity"].as_str() == Some("warning") { format!(" ({})", words.warning) } else { String::new() };
+        let (open, close) = words.quotes;
+        sentences.push(format!(
+            "{} {}{}{}{}{} {}.",
+            words.rule,
+            open,
+            label(&invariant),
+            close,
+            severity,
+            words.colon,
+            detail.trim().trim_end_matches('.')
+        ));
+    }
+    sentences
+}
+
+/// Read `text` aloud with the speech synthesizer of the system (`say` on macOS, SAPI on
+/// Windows, espeak-ng or speech-dispatcher on Linux), without waiting for the end
+pub fn speak(text: &str, language: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("say");
+        command.arg(text);
+        command
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+        ]);
+        command
+    } else {
+        let mut command = Command::new("espeak-ng");
+        command.args(["-v", language]).arg(text);
+        command
+    };
+    if cfg!(target_os = "windows") {
+        command.stdin(Stdio::piped());
+    }
+    let spawned = command.stdout(Stdio::null()).stderr(Stdio::null()).spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(_) if cfg!(target_os = "linux") => Command::new("spd-say")
+            .args(["-l", language])
+            .arg(text)
+            .spawn()
+            .context("No speech synthesizer found (install espeak-ng or speech-dispatcher)")?,
+        Err(e) => return Err(e).context("Failed to start the speech synthesizer"),
+    };
+    // SAPI reads the text from stdin, the other synthesizers get it as an argument
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        stdin.write_all(text.as_bytes()).context("Failed to send the text to the speech synthesizer")?;
+    }
+    // Reap the process once it is done speaking
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_narrate_model() {
+        let model = json!({
+            "entities": [
+                {"id": "Customer", "name": "Customer", "attributes": [{"name": "email", "type": "email", "required": true, "unique": true}, {"name": "name", "type": "string"}]},
+                {"id": "Order", "name": "Order", "aggregateRoot": true, "attributes": [{"name": "total", "type": "number", "required": true}]}
+            ],
+            "relations": [{"id": "places", "name": "places", "from": {"entityId": "Customer"}, "to": {"entityId": "Order"}, "cardinality": {"from": "1", "to": "0..n"}}],
+            "invariants": [{"id": "positive_total", "name": "Positive total", "type": "domain_constraint", "expression": "Order.total > 0", "description": "each Order must have a positive total"}]
+        });
+        assert_eq!(narration_language(None, "auto", &model), "en");
+        assert_eq!(narration_language(Some("de"), "en", &model), "fr");
+
+        assert_eq!(
+            narrate(&model, "en"),
+            vec![
+                "The model has 2 entities: Customer and Order.",
+                "Each Customer has email (required, unique) and name.",
+                "Each Order has total (required). Order is an aggregate root.",
+                "Each Customer places zero or more Orders; each Order is linked to exactly one Customer.",
+                "Rule “Positive total”: each Order must have a positive total.",
+            ]
+        );
+        let french = narrate(&model, "fr");
+        assert_eq!(french[0], "Le modèle compte 2 entités\u{a0}: Customer et Order.");
+        assert_eq!(french[3], "Chaque Customer places 0 ou plusieurs Orders\u{a0}; chaque Order se rattache à exactement 1 Customer.");
+    }
+}
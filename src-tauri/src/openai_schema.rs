@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Map, Value};
+
+use crate::llm_router::ToolCall;
+
+/// DomainModel schema shared with the MCP server
+const DOMAIN_MODEL_SCHEMA: &str = include_str!("../../mcp/domain_model.schema.json");
+
+/// Keywords OpenAI structured outputs refuse in strict mode; the MCP validation still
+/// checks them on the generated model
+const UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "$schema", "$id", "title", "default", "pattern", "format", "minLength", "maxLength",
+    "minimum", "maximum", "minItems", "maxItems", "uniqueItems",
+];
+
+/// Strict-mode version of a JSON schema: `$ref`s inlined, unsupported keywords removed,
+/// `additionalProperties: false` on every object and every property required, the
+/// optional ones nullable. Untyped values and free-form maps cannot be expressed and are
+/// left out.
+pub fn strict_schema(schema: &Value) -> Value {
+    strict_node(schema, &schema["definitions"]).unwrap_or_else(|| json!({"type": "object"}))
+}
+
+fn strict_node(node: &Value, definitions: &Value) -> Option<Value> {
+    if let Some(reference) = node["$ref"].as_str() {
+        let name = reference.strip_prefix("#/definitions/")?;
+        let mut resolved = strict_node(&definitions[name], definitions)?;
+        // The description of the reference wins over the one of the definition
+        if let Some(description) = node.get("description") {
+            resolved["description"] = description.clone();
+        }
+        return Some(resolved);
+    }
+    let mut out: Map<String, Value> = node
+        .as_object()?
+        .iter()
+        .filter(|(key, _)| !UNSUPPORTED_KEYWORDS.contains(&key.as_str()))
+        .filter(|(key, _)| !["definitions", "properties", "required", "items", "additionalProperties"].contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    match node["type"].as_str() {
+        Some("object") => {
+            let required: Vec<&str> = node["required"].as_array().into_iter().flatten().filter_map(Value::as_str).collect();
+            let mut properties = Map::new();
+            for (name, property) in node["properties"].as_object()? {
+                let Some(mut property) = strict_node(property, definitions) else { continue };
+                if !required.contains(&name.as_str()) {
+                    property = nullable(property);
+                }
+                properties.insert(name.clone(), property);
+            }
+            if properties.is_empty() {
+                return None;
+            }
+            out.insert("required".to_string(), json!(properties.keys().collect::<Vec<_>>()));
+            out.insert("properties".to_string(), Value::Object(properties));
+            out.insert("additionalProperties".to_string(), json!(false));
+        }
+        Some("array") => {
+            out.insert("items".to_string(), strict_node(&node["items"], definitions)?);
+        }
+        Some(_) => {}
+        None => return None,
+    }
+    Some(Value::Object(out))
+}
+
+/// Optional property: null allowed, stripped from the answer by `strip_nulls`
+fn nullable(mut property: Value) -> Value {
+    match (property["type"].as_str(), property.get("enum").is_some()) {
+        (Some(kind), false) => {
+            property["type"] = json!([kind, "null"]);
+            property
+        }
+        _ => {
+            let description = property.as_object_mut().and_then(|p| p.remove("description"));
+            let mut any_of = json!({"anyOf": [property, {"type": "null"}]});
+            if let Some(description) = description {
+                any_of["description"] = description;
+            }
+            any_of
+        }
+    }
+}
+
+/// `response_format` constraining the answer to a DomainModel. Layout hints are left to
+/// the editor and not generated.
+pub fn domain_model_response_format() -> Result<Value> {
+    let mut schema: Value = serde_json::from_str(DOMAIN_MODEL_SCHEMA).context("Invalid domain_model.schema.json")?;
+    if let Some(properties) = schema["properties"].as_object_mut() {
+        properties.remove("layout");
+    }
+    Ok(json!({
+        "type": "json_schema",
+        "json_schema": {
+            "name": "domain_model",
+            "strict": true,
+            "schema": strict_schema(&schema)
+        }
+    }))
+}
+
+/// Remove the null fields the strict schema forces the model to emit for missing
+/// optional values
+pub fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            object.retain(|_, v| !v.is_null());
+            object.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+/// Function tools offered to the model, the MCP tools the LLM may call
+pub fn tools() -> Value {
+    let function = |name: &str, description: &str, properties: Value, required: &[&str]| {
+        json!({
+            "type": "function",
+            "function": {
+                "name": name,
+                "description": description,
+                "parameters": {"type": "object", "properties": properties, "required": required}
+            }
+        })
+    };
+    let model = json!({"type": "object", "description": "DomainModel (entities, relations, invariants)"});
+    json!([
+        function(
+            "normalize_terms",
+            "Extract a domain model from a natural language transcript",
+            json!({"input_lang": {"type": "string"}, "transcript": {"type": "string"}}),
+            &["input_lang", "transcript"]
+        ),
+        function(
+            "emit_markdown",
+            "Generate the Markdown documentation of a domain model",
+            json!({"model": model, "audience": {"type": "string", "enum": ["technical", "business"]}}),
+            &["model"]
+        ),
+        function(
+            "emit_mermaid",
+            "Generate the Mermaid diagram of a domain model",
+            json!({"model": model, "style": {"type": "string", "enum": ["er", "class"]}}),
+            &["model"]
+        ),
+        function(
+            "validate_model",
+            "Validate a domain model for consistency",
+            json!({"model": model, "schema_path": {"type": "string"}}),
+            &["model"]
+        ),
+    ])
+}
+
+/// Tool calls of a chat completion message (`arguments` is a JSON string)
+pub fn tool_calls(message: &Value) -> Result<Vec<ToolCall>> {
+    message["tool_calls"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|call| {
+            let name = call["function"]["name"].as_str().context("Tool call without a name")?;
+            let arguments = call["function"]["arguments"].as_str().unwrap_or("{}");
+            Ok(ToolCall {
+                name: name.to_string(),
+                arguments: serde_json::from_str(arguments).with_context(|| format!("Invalid arguments for tool {}", name))?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_model_strict_schema() {
+        let format = domain_model_response_format().unwrap();
+        assert_eq!(format["json_schema"]["strict"], json!(true));
+        let schema = &format["json_schema"]["schema"];
+        assert_eq!(schema["required"], json!(["entities", "invariants", "relations"]));
+        assert_eq!(schema["additionalProperties"], json!(false));
+
+        let entity = &schema["properties"]["entities"]["items"];
+        assert_eq!(entity["additionalProperties"], json!(false));
+        assert!(entity["required"].as_array().unwrap().contains(&json!("aggregateRoot")));
+        assert_eq!(entity["properties"]["description"]["type"], json!(["string", "null"]));
+        assert!(entity["properties"]["id"].get("pattern").is_none());
+        let attribute = &entity["properties"]["attributes"]["items"];
+        assert!(attribute["properties"].get("defaultValue").is_none());
+        assert_eq!(attribute["properties"]["type"]["enum"].as_array().unwrap().len(), 11);
+        assert_eq!(attribute["properties"]["validation"]["type"], json!(["object", "null"]));
+        // An optional enum becomes nullable through anyOf
+        let invariant = &schema["properties"]["invariants"]["items"];
+        assert_eq!(invariant["properties"]["severity"]["anyOf"][1], json!({"type": "null"}));
+        let relation = &schema["properties"]["relations"]["items"];
+        assert_eq!(relation["properties"]["cardinality"]["properties"]["from"]["enum"], json!(["0..1", "1", "0..n", "1..n", "*"]));
+        assert!(!format.to_string().contains("$ref"));
+
+        let mut answer = json!({"entities": [{"id": "Order", "description": null, "attributes": [{"name": "id", "validation": null}]}]});
+        strip_nulls(&mut answer);
+        assert_eq!(answer, json!({"entities": [{"id": "Order", "attributes": [{"name": "id"}]}]}));
+    }
+
+    #[test]
+    fn test_native_tool_calls() {
+        let message = json!({
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": {"name": "normalize_terms", "arguments": "{\"input_lang\":\"fr\",\"transcript\":\"Un client passe des commandes\"}"}
+            }]
+        });
+        let calls = tool_calls(&message).unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "normalize_terms");
+        assert_eq!(calls[0].arguments["input_lang"], "fr");
+        assert!(tool_calls(&json!({"content": "Bonjour"})).unwrap().is_empty());
+        assert_eq!(tools().as_array().unwrap().len(), 4);
+    }
+}
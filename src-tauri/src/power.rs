@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// How hard the recorder works during long sessions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerProfile {
+    /// Transcribe as soon as a recording stops
+    #[default]
+    Balanced,
+    /// All-day workshops on battery: larger audio buffers, VAD on one frame out of three,
+    /// utterances written to disk in batches, and transcription deferred until the
+    /// machine is back on AC power
+    LowPower,
+}
+
+impl std::str::FromStr for PowerProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_lowercase().replace('-', "_").as_str() {
+            "balanced" => Ok(Self::Balanced),
+            "low_power" | "low" => Ok(Self::LowPower),
+            other => anyhow::bail!("Unknown power profile '{}', expected 'balanced' or 'low_power'", other),
+        }
+    }
+}
+
+/// Linux: `/sys/class/power_supply/*` entries of type "Mains" tell whether the
+/// charger is plugged in; a discharging battery without any mains entry means battery
+fn linux_ac_power(power_supply_dir: &Path) -> Option<bool> {
+    let read = |path: &Path| std::fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+    let mut mains = None;
+    let mut discharging = false;
+    for entry in std::fs::read_dir(power_supply_dir).ok()?.flatten() {
+        let path = entry.path();
+        match read(&path.join("type")).as_deref() {
+            Some("Mains") => {
+                let online = read(&path.join("online")).as_deref() == Some("1");
+                mains = Some(mains.unwrap_or(false) || online);
+            }
+            Some("Battery") => discharging |= read(&path.join("status")).as_deref() == Some("Discharging"),
+            _ => {}
+        }
+    }
+    mains.or(discharging.then_some(false))
+}
+
+/// macOS: first line of `pmset -g batt` ("Now drawing from 'AC Power'")
+fn parse_pmset(output: &str) -> Option<bool> {
+    let first_line = output.lines().next()?;
+    if first_line.contains("'AC Power'") {
+        Some(true)
+    } else if first_line.contains("'Battery Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Windows: `Win32_Battery.BatteryStatus` (1 = discharging, 2 = on AC), empty without battery
+fn parse_battery_status(output: &str) -> Option<bool> {
+    match output.trim().lines().next()?.trim() {
+        "1" => Some(false),
+        "" => None,
+        _ => Some(true),
+    }
+}
+
+/// Whether the machine runs on AC power (None = unknown, e.g. desktop without battery)
+pub fn on_ac_power() -> Option<bool> {
+    if cfg!(target_os = "linux") {
+        linux_ac_power(Path::new("/sys/class/power_supply"))
+    } else if cfg!(target_os = "macos") {
+        let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        parse_pmset(&String::from_utf8_lossy(&output.stdout))
+    } else if cfg!(target_os = "windows") {
+        let output = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", "(Get-CimInstance Win32_Battery).BatteryStatus"])
+            .output()
+            .ok()?;
+        parse_battery_status(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linux_power_supply() {
+        let root = std::env::temp_dir().join(format!("power_supply_{}", std::process::id()));
+        let supply = |name: &str, files: &[(&str, &str)]| {
+            std::fs::create_dir_all(root.join(name)).unwrap();
+            for (file, content) in files {
+                std::fs::write(root.join(name).join(file), content).unwrap();
+            }
+        };
+        supply("BAT0", &[("type", "Battery\n"), ("status", "Discharging\n")]);
+        assert_eq!(linux_ac_power(&root), Some(false));
+        supply("AC", &[("type", "Mains\n"), ("online", "0\n")]);
+        assert_eq!(linux_ac_power(&root), Some(false));
+        supply("AC", &[("type", "Mains\n"), ("online", "1\n")]);
+        assert_eq!(linux_ac_power(&root), Some(true));
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(linux_ac_power(&root), None);
+    }
+
+    #[test]
+    fn test_parse_power_commands() {
+        assert_eq!(parse_pmset("Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1)\t85%; discharging"), Some(false));
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'\n"), Some(true));
+        assert_eq!(parse_battery_status("1\r\n"), Some(false));
+        assert_eq!(parse_battery_status("2\r\n"), Some(true));
+        assert_eq!(parse_battery_status(""), None);
+        assert_eq!("low-power".parse::<PowerProfile>().unwrap(), PowerProfile::LowPower);
+        assert!("turbo".parse::<PowerProfile>().is_err());
+    }
+}
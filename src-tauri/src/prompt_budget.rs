@@ -0,0 +1,228 @@
+use std::env;
+
+/// Default context window when LLM_CONTEXT_TOKENS is not set
+const DEFAULT_CONTEXT_TOKENS: usize = 8192;
+
+/// Default share of the window kept free for the model's answer
+const DEFAULT_RESERVED_OUTPUT_TOKENS: usize = 2048;
+
+/// Rough token count without a tokenizer: about 4 characters per token,
+/// and never fewer tokens than words (French text with accents and short words).
+pub fn estimate_tokens(text: &str) -> usize {
+    let chars = text.chars().count();
+    let words = text.split_whitespace().count();
+    chars.div_ceil(4).max(words)
+}
+
+/// How a prompt section can be reduced when over budget
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trim {
+    /// Always kept (system prompt, transcript)
+    Never,
+    /// Dropped entirely (a few-shot example)
+    Drop,
+    /// Cut line by line from the end (glossary)
+    Lines,
+}
+
+/// A piece of context contributing to the prompt
+#[derive(Debug, Clone)]
+pub struct PromptSection {
+    pub name: String,
+    pub text: String,
+    /// Lower priorities are trimmed first
+    pub priority: u8,
+    pub trim: Trim,
+}
+
+impl PromptSection {
+    pub fn new(name: &str, text: String, priority: u8, trim: Trim) -> Self {
+        Self { name: name.to_string(), text, priority, trim }
+    }
+}
+
+/// Token budget of the target model
+#[derive(Debug, Clone, Copy)]
+pub struct PromptBudget {
+    pub context_tokens: usize,
+    pub reserved_output_tokens: usize,
+}
+
+/// Outcome of fitting the sections into the budget
+#[derive(Debug, Clone)]
+pub struct BudgetedPrompt {
+    /// Sections kept, in their original order (possibly shortened)
+    pub sections: Vec<PromptSection>,
+    pub estimated_tokens: usize,
+    /// One message per trimmed section, for the pipeline report
+    pub warnings: Vec<String>,
+}
+
+impl BudgetedPrompt {
+    /// Concatenated text of the kept sections named in `names`, in order
+    pub fn text_of(&self, names: &[&str]) -> String {
+        self.sections
+            .iter()
+            .filter(|s| names.iter().any(|n| s.name == *n || s.name.starts_with(&format!("{}:", n))))
+            .map(|s| s.text.as_str())
+            .collect()
+    }
+}
+
+impl Default for PromptBudget {
+    fn default() -> Self {
+        Self {
+            context_tokens: DEFAULT_CONTEXT_TOKENS,
+            reserved_output_tokens: DEFAULT_RESERVED_OUTPUT_TOKENS,
+        }
+    }
+}
+
+impl PromptBudget {
+    /// Budget from LLM_CONTEXT_TOKENS and LLM_RESERVED_OUTPUT_TOKENS
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            context_tokens: env::var("LLM_CONTEXT_TOKENS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.context_tokens),
+            reserved_output_tokens: env::var("LLM_RESERVED_OUTPUT_TOKENS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.reserved_output_tokens),
+        }
+    }
+
+    /// Tokens available for the prompt itself
+    pub fn prompt_tokens(&self) -> usize {
+        self.context_tokens.saturating_sub(self.reserved_output_tokens)
+    }
+
+    /// Trim the lowest-priority sections until the prompt fits.
+    ///
+    /// Sections of equal priority are trimmed from the last one, so callers
+    /// list few-shot examples from most to least relevant.
+    pub fn fit(&self, sections: Vec<PromptSection>) -> BudgetedPrompt {
+        let limit = self.prompt_tokens();
+        let mut sections: Vec<Option<PromptSection>> = sections.into_iter().map(Some).collect();
+        let total = |sections: &[Option<PromptSection>]| -> usize {
+            sections.iter().flatten().map(|s| estimate_tokens(&s.text)).sum()
+        };
+        let mut warnings = Vec::new();
+
+        let mut order: Vec<usize> = (0..sections.len()).collect();
+        order.sort_by_key(|&i| {
+            let s = sections[i].as_ref().unwrap();
+            (s.priority, std::cmp::Reverse(i))
+        });
+
+        for i in order {
+            let over = total(&sections).saturating_sub(limit);
+            if over == 0 {
+                break;
+            }
+            let Some(section) = sections[i].as_mut() else { continue };
+            match section.trim {
+                Trim::Never => {}
+                Trim::Drop => {
+                    warnings.push(format!(
+                        "Prompt over budget: dropped {} (~{} tokens)",
+                        section.name,
+                        estimate_tokens(&section.text)
+                    ));
+                    sections[i] = None;
+                }
+                Trim::Lines => {
+                    let before = estimate_tokens(&section.text);
+                    let mut lines: Vec<&str> = section.text.lines().collect();
+                    let removed_from = lines.len();
+                    while !lines.is_empty() && before - estimate_tokens(&lines.join("\n")) < over {
+                        lines.pop();
+                    }
+                    let removed = removed_from - lines.len();
+                    warnings.push(format!(
+                        "Prompt over budget: trimmed {} line(s) of {} (~{} tokens)",
+                        removed,
+                        section.name,
+                        before - estimate_tokens(&lines.join("\n"))
+                    ));
+                    if lines.is_empty() {
+                        sections[i] = None;
+                    } else {
+                        section.text = lines.join("\n") + "\n";
+                    }
+                }
+            }
+        }
+
+        let estimated_tokens = total(&sections);
+        if estimated_tokens > limit {
+            warnings.push(format!(
+                "Prompt still over budget after trimming (~{} tokens for a {} token limit); the provider may truncate it",
+                estimated_tokens, limit
+            ));
+        }
+
+        BudgetedPrompt {
+            sections: sections.into_iter().flatten().collect(),
+            estimated_tokens,
+            warnings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(n: usize) -> String {
+        vec!["mot"; n].join(" ")
+    }
+
+    fn sections() -> Vec<PromptSection> {
+        vec![
+            PromptSection::new("system prompt", words(100), 3, Trim::Never),
+            PromptSection::new("glossary", (0..20).map(|_| words(10)).collect::<Vec<_>>().join("\n"), 1, Trim::Lines),
+            PromptSection::new("example:1", words(100), 2, Trim::Drop),
+            PromptSection::new("example:2", words(100), 2, Trim::Drop),
+            PromptSection::new("transcript", words(100), 3, Trim::Never),
+        ]
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("a b c"), 3);
+    }
+
+    #[test]
+    fn test_fit_within_budget_keeps_everything() {
+        let budget = PromptBudget { context_tokens: 10_000, reserved_output_tokens: 0 };
+        let fitted = budget.fit(sections());
+        assert_eq!(fitted.sections.len(), 5);
+        assert!(fitted.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_fit_trims_lowest_priority_first() {
+        // 600 tokens of content: the glossary (200) goes first, then the last example
+        let budget = PromptBudget { context_tokens: 150, reserved_output_tokens: 0 };
+        let fitted = budget.fit(sections());
+        let names: Vec<&str> = fitted.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["system prompt", "transcript"]);
+        assert_eq!(fitted.warnings.len(), 4);
+        assert!(fitted.warnings[0].contains("glossary"));
+        assert!(fitted.warnings[1].contains("example:2"));
+        assert!(fitted.warnings[3].contains("still over budget"));
+
+        // Partial glossary trim
+        let budget = PromptBudget { context_tokens: 450, reserved_output_tokens: 0 };
+        let fitted = budget.fit(sections());
+        assert_eq!(fitted.sections.len(), 5);
+        assert!(fitted.estimated_tokens <= 450);
+        assert!(fitted.warnings[0].starts_with("Prompt over budget: trimmed 15 line(s) of glossary"));
+        assert_eq!(fitted.text_of(&["example"]), words(100) + &words(100));
+    }
+}
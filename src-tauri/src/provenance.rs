@@ -0,0 +1,150 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::artifact_store::content_hash;
+use crate::llm_router::GenerationRecord;
+
+const MARKDOWN_PREFIX: &str = "---\nprovenance: ";
+const MERMAID_PREFIX: &str = "%% provenance: ";
+
+/// Where a generated artifact comes from. The transcript and prompt are stored in the
+/// artifact objects under these hashes, so that the artifact can be checked later.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub app_version: String,
+    /// "ollama" or "external"
+    pub provider: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// SHA-256 of the system prompt sent (instructions, glossary, few-shot examples)
+    pub prompt_hash: String,
+    /// SHA-256 of the transcript
+    pub transcript_hash: String,
+    pub generated_at: String,
+}
+
+impl Provenance {
+    pub fn new(generation: &GenerationRecord, prompt: &str, transcript: &str) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            provider: generation.provider.clone(),
+            model: generation.model.clone(),
+            prompt_hash: content_hash(prompt.as_bytes()),
+            transcript_hash: content_hash(transcript.as_bytes()),
+            generated_at: generation.generated_at.clone(),
+        }
+    }
+}
+
+/// Result of `verify_artifact`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProvenanceCheck {
+    pub name: String,
+    pub provenance: Option<Provenance>,
+    pub valid: bool,
+    pub issues: Vec<String>,
+}
+
+/// Embed `provenance` in `content`: YAML front matter for markdown, a `%%` comment for
+/// Mermaid, a `provenance` field for the model JSON. An existing stamp is replaced.
+pub fn stamp(kind: &str, content: &str, provenance: &Provenance) -> Result<String> {
+    let body = extract(content).map(|(_, body)| body).unwrap_or_else(|| content.to_string());
+    let json = serde_json::to_string(provenance)?;
+    match kind {
+        // A JSON object is also valid YAML
+        "markdown" => Ok(format!("{}{}\n---\n\n{}", MARKDOWN_PREFIX, json, body)),
+        "mermaid" => Ok(format!("{}{}\n{}", MERMAID_PREFIX, json, body)),
+        "model" => {
+            let mut model: Value = serde_json::from_str(&body).context("Model artifact is not JSON")?;
+            let object = model.as_object_mut().context("Model artifact is not a JSON object")?;
+            object.insert("provenance".to_string(), serde_json::to_value(provenance)?);
+            Ok(serde_json::to_string_pretty(&model)?)
+        }
+        _ => anyhow::bail!("Cannot stamp provenance on a {} artifact", kind),
+    }
+}
+
+/// Provenance embedded in `content` and the content without it
+pub fn extract(content: &str) -> Option<(Provenance, String)> {
+    if let Some(rest) = content.strip_prefix(MARKDOWN_PREFIX) {
+        let (json, body) = rest.split_once("\n---\n")?;
+        return Some((serde_json::from_str(json).ok()?, body.strip_prefix('\n').unwrap_or(body).to_string()));
+    }
+    if let Some(rest) = content.strip_prefix(MERMAID_PREFIX) {
+        let (json, body) = rest.split_once('\n').unwrap_or((rest, ""));
+        return Some((serde_json::from_str(json).ok()?, body.to_string()));
+    }
+    let mut model: Value = serde_json::from_str(content).ok()?;
+    let provenance = model.as_object_mut()?.remove("provenance")?;
+    Some((serde_json::from_value(provenance).ok()?, serde_json::to_string_pretty(&model).ok()?))
+}
+
+fn short(hash: &str) -> &str {
+    hash.get(..12).unwrap_or(hash)
+}
+
+/// Check the provenance of `content`: `input_stored` tells whether an input with the
+/// given hash is still in the artifact objects, unchanged
+pub fn verify(name: &str, content: &str, input_stored: impl Fn(&str) -> bool) -> ProvenanceCheck {
+    let mut issues = Vec::new();
+    let provenance = extract(content).map(|(provenance, _)| provenance);
+    match &provenance {
+        None => issues.push("no provenance metadata".to_string()),
+        Some(provenance) => {
+            if !input_stored(&provenance.transcript_hash) {
+                issues.push(format!("transcript {} not found or modified", short(&provenance.transcript_hash)));
+            }
+            if !input_stored(&provenance.prompt_hash) {
+                issues.push(format!("prompt {} not found or modified", short(&provenance.prompt_hash)));
+            }
+        }
+    }
+    ProvenanceCheck {
+        name: name.to_string(),
+        provenance,
+        valid: issues.is_empty(),
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provenance() -> Provenance {
+        let generation = GenerationRecord {
+            provider: "ollama".to_string(),
+            model: Some("mistral".to_string()),
+            options: Default::default(),
+            generated_at: "2025-01-01T10:00:00+01:00".to_string(),
+        };
+        Provenance::new(&generation, "Tu es un expert DDD", "Les commandes ont une date")
+    }
+
+    #[test]
+    fn test_stamp_extract_and_verify() {
+        let provenance = provenance();
+        let markdown = stamp("markdown", "# Domaine\n", &provenance).unwrap();
+        assert!(markdown.starts_with("---\nprovenance: {"));
+        assert_eq!(extract(&markdown), Some((provenance.clone(), "# Domaine\n".to_string())));
+        // Stamping again replaces the previous stamp
+        assert_eq!(stamp("markdown", &markdown, &provenance).unwrap(), markdown);
+
+        let mermaid = stamp("mermaid", "erDiagram\n  Order ||--o{ Line : contains", &provenance).unwrap();
+        assert_eq!(extract(&mermaid).unwrap().1, "erDiagram\n  Order ||--o{ Line : contains");
+
+        let model = stamp("model", r#"{"entities":[],"relations":[],"invariants":[]}"#, &provenance).unwrap();
+        let (found, body) = extract(&model).unwrap();
+        assert_eq!(found.transcript_hash, content_hash("Les commandes ont une date".as_bytes()));
+        assert!(!body.contains("provenance"));
+        assert!(stamp("csv", "a,b", &provenance).is_err());
+
+        let stored = [provenance.transcript_hash.clone()];
+        let check = verify("domain.md", &markdown, |hash| stored.contains(&hash.to_string()));
+        assert!(!check.valid);
+        assert_eq!(check.issues.len(), 1);
+        assert!(check.issues[0].starts_with("prompt"));
+        assert!(!verify("notes.md", "# Notes", |_| true).valid);
+    }
+}
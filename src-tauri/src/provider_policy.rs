@@ -0,0 +1,146 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::llm_router::LlmProvider;
+
+/// Looked up in the working directory, next to `.env`, when LLM_POLICY_FILE is unset
+pub const DEFAULT_POLICY_FILE: &str = "llm-policy.json";
+
+/// Restrictions an organization puts on the LLM providers of a workspace. Every field
+/// is optional: `{}` allows everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderPolicy {
+    /// LLM_PROVIDER values that may be used ("ollama", "external", "openai",
+    /// "anthropic"); empty = all
+    pub allowed_providers: Vec<String>,
+    /// Regex the endpoint of an external provider must match, e.g.
+    /// `^https://llm\.example\.com/`
+    pub allowed_endpoints: Option<String>,
+    /// Only an Ollama server on this machine, and no network access from the app
+    /// (no model downloads either)
+    pub offline_only: bool,
+}
+
+/// Policy in force and where it comes from, for the settings screen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyReport {
+    pub path: Option<String>,
+    pub policy: ProviderPolicy,
+    /// Why the current LLM configuration is refused, if it is
+    pub violation: Option<String>,
+}
+
+fn is_loopback(url: &str) -> bool {
+    let host = url.split("://").nth(1).unwrap_or(url).split(['/', '?']).next().unwrap_or("");
+    let host = host.rsplit_once('@').map(|(_, host)| host).unwrap_or(host);
+    let host = if host.starts_with('[') {
+        host.split(']').next().unwrap_or("").trim_start_matches('[')
+    } else {
+        host.split(':').next().unwrap_or("")
+    };
+    host.eq_ignore_ascii_case("localhost") || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+impl ProviderPolicy {
+    /// Check a provider configuration; `name` is the LLM_PROVIDER value it comes from
+    pub fn check(&self, name: &str, provider: &LlmProvider) -> Result<()> {
+        let name = name.to_lowercase();
+        if !self.allowed_providers.is_empty() && !self.allowed_providers.iter().any(|allowed| allowed.eq_ignore_ascii_case(&name)) {
+            anyhow::bail!("provider '{}' is not allowed (allowed: {})", name, self.allowed_providers.join(", "));
+        }
+        match provider {
+            LlmProvider::Ollama { base_url } => {
+                if self.offline_only && !is_loopback(base_url) {
+                    anyhow::bail!("offline only: Ollama must run on this machine, not at {}", base_url);
+                }
+            }
+            LlmProvider::External { endpoint, .. } | LlmProvider::OpenAi { endpoint, .. } => {
+                if self.offline_only {
+                    anyhow::bail!("offline only: external provider '{}' is not allowed", name);
+                }
+                if let Some(pattern) = &self.allowed_endpoints {
+                    let regex = Regex::new(pattern).with_context(|| format!("invalid allowed_endpoints regex '{}'", pattern))?;
+                    if !regex.is_match(endpoint) {
+                        anyhow::bail!("endpoint {} does not match allowed_endpoints '{}'", endpoint, pattern);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Refuse network access (model catalog, downloads) under `offline_only`
+    pub fn check_network(&self, what: &str) -> Result<()> {
+        if self.offline_only {
+            anyhow::bail!("offline only: {} is not allowed", what);
+        }
+        Ok(())
+    }
+}
+
+/// LLM_POLICY_FILE, else `llm-policy.json` in the working directory if present
+pub fn policy_path() -> Option<PathBuf> {
+    match std::env::var("LLM_POLICY_FILE") {
+        Ok(path) if !path.trim().is_empty() => Some(PathBuf::from(path)),
+        _ => Some(PathBuf::from(DEFAULT_POLICY_FILE)).filter(|path| path.exists()),
+    }
+}
+
+fn load_from(path: &Path) -> Result<ProviderPolicy> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read provider policy {:?}", path))?;
+    let policy: ProviderPolicy = serde_json::from_str(&content).with_context(|| format!("Invalid provider policy {:?}", path))?;
+    if let Some(pattern) = &policy.allowed_endpoints {
+        Regex::new(pattern).with_context(|| format!("Invalid allowed_endpoints regex in {:?}", path))?;
+    }
+    Ok(policy)
+}
+
+/// Policy of the workspace, allow-all without a policy file. A policy file that
+/// cannot be read is an error rather than no restriction.
+pub fn load() -> Result<(Option<PathBuf>, ProviderPolicy)> {
+    match policy_path() {
+        Some(path) => Ok((Some(path.clone()), load_from(&path)?)),
+        None => Ok((None, ProviderPolicy::default())),
+    }
+}
+
+/// Check a provider configuration against the workspace policy
+pub fn enforce(name: &str, provider: &LlmProvider) -> Result<()> {
+    let (path, policy) = load()?;
+    policy.check(name, provider).map_err(|e| {
+        let source = path.map(|path| path.display().to_string()).unwrap_or_default();
+        log::warn!("[Policy] LLM provider refused by {}: {}", source, e);
+        anyhow::anyhow!("LLM provider refused by policy {}: {}", source, e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_checks() {
+        let ollama = |base_url: &str| LlmProvider::Ollama { base_url: base_url.to_string() };
+        let external = |endpoint: &str| LlmProvider::External { api_key: "key".to_string(), endpoint: endpoint.to_string() };
+
+        let policy: ProviderPolicy = serde_json::from_str(r#"{"allowed_providers": ["ollama", "openai"], "allowed_endpoints": "^https://llm\\.example\\.com/"}"#).unwrap();
+        assert!(policy.check("ollama", &ollama("http://gpu-box:11434")).is_ok());
+        assert!(policy.check("openai", &external("https://llm.example.com/v1/chat/completions")).is_ok());
+        let refused = policy.check("openai", &external("https://api.openai.com/v1/chat/completions")).unwrap_err();
+        assert!(refused.to_string().contains("does not match allowed_endpoints"));
+        assert!(policy.check("anthropic", &external("https://llm.example.com/v1")).unwrap_err().to_string().contains("not allowed"));
+
+        let offline = ProviderPolicy { offline_only: true, ..Default::default() };
+        assert!(offline.check("ollama", &ollama("http://localhost:11434")).is_ok());
+        assert!(offline.check("ollama", &ollama("http://127.0.0.1:11434/")).is_ok());
+        assert!(offline.check("ollama", &ollama("http://[::1]:11434")).is_ok());
+        assert!(offline.check("ollama", &ollama("http://gpu-box:11434")).is_err());
+        assert!(offline.check("external", &external("https://llm.example.com/v1")).is_err());
+        assert!(offline.check_network("model download").is_err());
+        assert!(ProviderPolicy::default().check("external", &external("https://anything")).is_ok());
+    }
+}
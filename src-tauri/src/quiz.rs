@@ -0,0 +1,180 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::prompt_budget::{PromptBudget, PromptSection, Trim};
+use crate::transcript_normalize;
+
+/// System prompt for the quiz checking that a team absorbed the domain
+pub const QUIZ_SYSTEM_PROMPT: &str = r#"
+Tu prépares un quiz pour vérifier qu'une équipe de développement a bien assimilé un domaine métier après un atelier Domain-Driven Design. Tu reçois le Domain Model (entités, attributs, relations, invariants) et le canvas de l'atelier.
+
+Réponds UNIQUEMENT avec un JSON:
+{
+  "questions": [
+    {
+      "question": "string",
+      "answer": "string (réponse attendue, une ou deux phrases)",
+      "topic": "vocabulary|entity|relation|invariant|use_case",
+      "source": "string (élément du modèle ou section du canvas qui justifie la réponse)"
+    }
+  ]
+}
+
+RÈGLES:
+1. Exactement le nombre de questions demandé, sur des sujets variés: vocabulaire, entités, relations et cardinalités, invariants, use cases
+2. Chaque réponse se déduit du modèle ou du canvas fournis; n'invente rien
+3. Des questions de compréhension, pas de mémoire: "Que se passe-t-il si...", "Pourquoi...", "Qui peut..." plutôt que "Combien d'attributs..."
+4. Questions et réponses courtes, dans la langue demandée, avec le vocabulaire métier du projet
+"#;
+
+/// Questions asked when the caller does not say
+pub const DEFAULT_QUESTION_COUNT: usize = 8;
+/// More questions would not fit a short post-workshop check
+pub const MAX_QUESTION_COUNT: usize = 20;
+/// Language of the quiz when neither the settings nor the canvas tell it
+const DEFAULT_LANGUAGE: &str = "fr";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuizTopic {
+    Vocabulary,
+    Entity,
+    Relation,
+    Invariant,
+    UseCase,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuizQuestion {
+    pub question: String,
+    /// Expected answer
+    pub answer: String,
+    pub topic: QuizTopic,
+    /// Model element or canvas section the answer comes from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quiz {
+    pub project: String,
+    pub language: String,
+    pub questions: Vec<QuizQuestion>,
+}
+
+/// Language of the quiz: the transcription language of the settings, or the one of the
+/// canvas when the settings say "auto"
+pub fn quiz_language(setting: &str, canvas: Option<&str>) -> String {
+    if setting != "auto" {
+        return setting.to_string();
+    }
+    canvas.and_then(transcript_normalize::guess_language).unwrap_or(DEFAULT_LANGUAGE).to_string()
+}
+
+/// User prompt: the expected number of questions and language, the model, then the canvas
+/// (cut from its end when over the token budget)
+pub fn quiz_prompt(
+    project_name: &str,
+    language: &str,
+    count: usize,
+    model: Option<&Value>,
+    canvas: Option<&str>,
+    budget: &PromptBudget,
+) -> Result<(String, Vec<String>)> {
+    let mut sections = vec![
+        PromptSection::new("system prompt", QUIZ_SYSTEM_PROMPT.to_string(), 4, Trim::Never),
+        PromptSection::new(
+            "header",
+            format!("Projet: {}\nNombre de questions: {}\nLangue du quiz (code ISO 639-1): {}\n\n", project_name, count, language),
+            4,
+            Trim::Never,
+        ),
+    ];
+    if let Some(model) = model {
+        sections.push(PromptSection::new("model", format!("## Domain Model\n{}\n\n", serde_json::to_string_pretty(model)?), 3, Trim::Lines));
+    }
+    if let Some(canvas) = canvas {
+        sections.push(PromptSection::new("canvas", format!("## Canvas\n{}\n", canvas.trim()), 2, Trim::Lines));
+    }
+
+    let budgeted = budget.fit(sections);
+    Ok((budgeted.text_of(&["header", "model", "canvas"]), budgeted.warnings))
+}
+
+/// Questions of the LLM answer: incomplete and repeated questions dropped, at most `count`
+pub fn parse_questions(answer: Value, count: usize) -> Result<Vec<QuizQuestion>> {
+    #[derive(Deserialize)]
+    struct Answer {
+        questions: Vec<Value>,
+    }
+    let answer: Answer = serde_json::from_value(answer)?;
+    let mut questions: Vec<QuizQuestion> = Vec::new();
+    for question in answer.questions {
+        let Ok(question) = serde_json::from_value::<QuizQuestion>(question) else { continue };
+        let (text, expected) = (question.question.trim(), question.answer.trim());
+        if text.is_empty() || expected.is_empty() || questions.iter().any(|q| q.question.eq_ignore_ascii_case(text)) {
+            continue;
+        }
+        questions.push(QuizQuestion { question: text.to_string(), answer: expected.to_string(), ..question });
+    }
+    if questions.is_empty() {
+        anyhow::bail!("The LLM answer has no usable question");
+    }
+    questions.truncate(count);
+    Ok(questions)
+}
+
+impl Quiz {
+    /// Questions first, answers folded under each of them so the quiz can be taken as is
+    pub fn to_markdown(&self) -> String {
+        let (intro, answer_label, source_label) = match self.language.as_str() {
+            "en" => ("Answer each question, then unfold the expected answer.", "Answer", "Source:"),
+            _ => ("Répondez à chaque question, puis dépliez la réponse attendue.", "Réponse", "Source :"),
+        };
+        let mut out = format!("# Quiz — {}\n\n{}\n\n", self.project, intro);
+        for (index, question) in self.questions.iter().enumerate() {
+            out.push_str(&format!("## {}. {}\n\n", index + 1, question.question));
+            out.push_str(&format!("<details>\n<summary>{}</summary>\n\n{}\n", answer_label, question.answer));
+            if let Some(source) = &question.source {
+                out.push_str(&format!("\n*{} {}*\n", source_label, source));
+            }
+            out.push_str("\n</details>\n\n");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_quiz_parsing_and_markdown() {
+        let model = json!({"entities": [{"id": "Order", "name": "Commande", "attributes": [{"name": "total", "type": "number"}]}], "relations": [], "invariants": []});
+        let (prompt, warnings) = quiz_prompt("Boutique", "fr", 3, Some(&model), Some("## Contexte & Vision\n\nVente en ligne."), &PromptBudget::default()).unwrap();
+        assert!(warnings.is_empty());
+        assert!(prompt.starts_with("Projet: Boutique\nNombre de questions: 3\nLangue du quiz (code ISO 639-1): fr\n"));
+        assert!(prompt.contains("\"name\": \"Commande\"") && prompt.contains("Vente en ligne."));
+        assert_eq!(quiz_language("auto", Some("Le client passe une commande et la paie.")), "fr");
+
+        let answer = json!({"questions": [
+            {"question": "Que se passe-t-il si le total d'une Commande est négatif ?", "answer": "La Commande est refusée.", "topic": "invariant", "source": "Invariant Total positif"},
+            {"question": "que se passe-t-il si le total d'une commande est négatif ?", "answer": "Doublon", "topic": "invariant"},
+            {"question": "Qui passe une Commande ?", "answer": " ", "topic": "relation"},
+            {"question": "Qu'est-ce qu'une Commande ?", "answer": "Une demande d'achat validée.", "topic": "vocabulary"},
+            {"question": "Sujet inconnu ?", "answer": "Oui", "topic": "weather"}
+        ]});
+        let questions = parse_questions(answer, 5).unwrap();
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[1].topic, QuizTopic::Vocabulary);
+        assert!(parse_questions(json!({"questions": []}), 5).is_err());
+
+        let quiz = Quiz { project: "Boutique".to_string(), language: "fr".to_string(), questions };
+        let markdown = quiz.to_markdown();
+        assert!(markdown.starts_with("# Quiz — Boutique\n\n"));
+        assert!(markdown.contains("## 1. Que se passe-t-il si le total d'une Commande est négatif ?\n\n<details>\n<summary>Réponse</summary>\n\nLa Commande est refusée.\n\n*Source : Invariant Total positif*\n\n</details>"));
+        assert!(markdown.contains("## 2. Qu'est-ce qu'une Commande ?"));
+    }
+}
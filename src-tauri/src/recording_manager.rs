@@ -1,16 +1,30 @@
-use crate::audio_session::{AudioSession, AudioSessionConfig};
+use crate::audio_session::{self, AudioProfile, AudioSession, AudioSessionConfig, DeviceEvent, RecordingMode, SilenceEvent, SystemCapture, Utterance, UtteranceNaming, VadSettings, VirtualInput};
 use crate::audio_enhancement::{AudioEnhancer, AudioEnhancementConfig};
-use crate::speech_to_text::SpeechToText;
+use crate::disk_space::{self, Preflight};
+use crate::hooks::{self, HookPoint};
+use crate::power::{self, PowerProfile};
+use crate::recording_session::{self, SessionRecord, SessionUtterance};
+use crate::settings;
+use crate::speech_to_text::{self, BackendReport, Cancelled, PartialSegment, SpeechToText, TranscribeOptions, TranscriptionResult, WhisperBackend, WhisperSettings};
+use crate::storage_gc::{self, AudioRetention, GcReport, GcRules, StorageRoots};
+use crate::stt_provider::CloudStt;
+use crate::transcription_scheduler;
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_audio_capture::{AudioCaptureExt, StartCaptureRequest};
 
 #[derive(Debug, Clone)]
 pub enum RecordingState {
     Idle,
     Recording,
+    /// Recording paused: the session stays open and resumes into the same directory
+    Paused,
     Processing,
 }
 
@@ -20,49 +34,604 @@ pub struct RecordingManager {
     stt: Arc<SpeechToText>,
     app_handle: AppHandle,
     selected_device: Arc<Mutex<Option<String>>>,
-    enhancement_config: Arc<Mutex<AudioEnhancementConfig>>,
+    audio_profile: Arc<Mutex<AudioProfile>>,
+    system_capture: Arc<Mutex<SystemCapture>>,
+    virtual_input: Arc<Mutex<Option<VirtualInput>>>,
+    power_profile: Arc<Mutex<PowerProfile>>,
+    vad_settings: Arc<Mutex<VadSettings>>,
+    recording_mode: Arc<Mutex<RecordingMode>>,
+    archive_raw: Arc<Mutex<bool>>,
+    in_memory_audio: Arc<Mutex<bool>>,
+    retention: Arc<Mutex<AudioRetention>>,
+    naming: Arc<Mutex<UtteranceNaming>>,
+    whisper_backend: Arc<Mutex<WhisperBackend>>,
+    monitor: Arc<Mutex<bool>>,
+    deferred: Arc<Mutex<Vec<DeferredSession>>>,
+    last_activity: Arc<Mutex<Instant>>,
+    live_transcript: LiveTranscript,
+}
+
+/// Id of the recording in progress and its utterances transcribed so far (VAD mode),
+/// until the session is stored
+type LiveTranscript = Arc<Mutex<Option<(String, Vec<SessionUtterance>)>>>;
+
+/// How often deferred sessions are checked for AC power / idleness
+const DEFERRED_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Without AC power, deferred sessions are transcribed after this long without recording
+const LOW_POWER_IDLE_DELAY: Duration = Duration::from_secs(15 * 60);
+
+/// How often stale temporary files and orphaned sessions are cleaned up (also at startup)
+const STORAGE_GC_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// How often a VAD-mode recording is checked for newly closed utterances
+const LIVE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Session recorded in low power mode on battery, waiting to be transcribed
+struct DeferredSession {
+    recordings_root: PathBuf,
+    session_id: String,
+    utterances: Vec<Utterance>,
+    enhancement: AudioEnhancementConfig,
+}
+
+/// Where recordings are written. Mobile apps can only write inside their sandbox.
+pub(crate) fn recordings_dir(app_handle: &AppHandle) -> PathBuf {
+    if cfg!(mobile) {
+        if let Ok(dir) = app_handle.path().app_local_data_dir() {
+            return dir.join("recordings");
+        }
+    }
+    // Use home directory for easier access to audio files during debugging
+    std::env::var("HOME")
+        .map(|h| std::path::PathBuf::from(h).join("domain-model-audio"))
+        .unwrap_or_else(|_| std::env::temp_dir().join("domain-model-audio"))
+}
+
+/// Whisper settings for this platform. Phones get the quantized model (see setup)
+/// and lighter inference settings.
+pub(crate) fn platform_whisper_settings() -> WhisperSettings {
+    if cfg!(mobile) {
+        WhisperSettings::low_memory()
+    } else {
+        WhisperSettings::default()
+    }
+}
+
+/// Samples of an utterance at 16kHz, after audio enhancement when `enhancement` is given
+/// (falls back to the original file if enhancement is unavailable). `sample_rate` is
+/// the rate the WAV was written at, read from its header when unknown.
+pub(crate) fn utterance_samples(
+    enhancement: Option<&AudioEnhancementConfig>,
+    path: &Path,
+    sample_rate: Option<u32>,
+) -> Result<Vec<f32>> {
+    let Some(enhancement) = enhancement else {
+        return speech_to_text::read_wav_16k(path);
+    };
+
+    // Appliquer l'amélioration audio avant transcription
+    let enhanced_path = path.with_extension("enhanced.wav");
+    
+    // Créer l'enhancer au sample rate réel du fichier
+    let sample_rate = match sample_rate.map(Ok).unwrap_or_else(|| hound::WavReader::open(path).map(|r| r.spec().sample_rate)) {
+        Ok(sample_rate) => sample_rate,
+        Err(e) => {
+            log::warn!("Unreadable WAV header, skipping audio enhancement: {}", e);
+            return speech_to_text::read_wav_16k(path);
+        }
+    };
+    
+    match AudioEnhancer::new(sample_rate, enhancement.clone()) {
+        Ok(enhancer) => match enhancer.process_file(path, &enhanced_path) {
+            Ok(_) => {
+                log::info!("Audio enhancement applied successfully");
+                // Lire le fichier amélioré puis supprimer le fichier temporaire
+                let samples = speech_to_text::read_wav_16k(&enhanced_path);
+                let _ = std::fs::remove_file(&enhanced_path);
+                samples
+            }
+            Err(e) => {
+                log::warn!("Audio enhancement failed, using original file: {}", e);
+                speech_to_text::read_wav_16k(path)
+            }
+        },
+        Err(e) => {
+            log::warn!("Failed to create audio enhancer: {}", e);
+            speech_to_text::read_wav_16k(path)
+        }
+    }
+}
+
+/// Samples of a recorded utterance at 16kHz: from memory when the session kept it there
+/// (`keep_in_memory`, enhanced through ffmpeg pipes), from its WAV file otherwise (see
+/// `utterance_samples`)
+pub(crate) fn recorded_samples(enhancement: Option<&AudioEnhancementConfig>, utterance: &Utterance) -> Result<Vec<f32>> {
+    let Some(samples) = &utterance.samples else {
+        return utterance_samples(enhancement, &utterance.file_path, Some(utterance.sample_rate));
+    };
+    if let Some(enhancement) = enhancement {
+        match AudioEnhancer::new(utterance.sample_rate, enhancement.clone())
+            .and_then(|enhancer| enhancer.process_samples(samples, utterance.sample_rate))
+        {
+            Ok(enhanced) => {
+                log::info!("Audio enhancement applied in memory");
+                return Ok(enhanced);
+            }
+            Err(e) => log::warn!("Audio enhancement failed, using original samples: {}", e),
+        }
+    }
+    Ok(speech_to_text::samples_16k(samples, utterance.sample_rate))
+}
+
+/// Transcribe an utterance, after audio enhancement when `enhancement` is given
+/// (see `utterance_samples`)
+pub(crate) fn transcribe_utterance<F>(
+    stt: &SpeechToText,
+    enhancement: Option<&AudioEnhancementConfig>,
+    path: &Path,
+    sample_rate: Option<u32>,
+    on_segment: F,
+) -> Result<TranscriptionResult>
+where
+    F: FnMut(PartialSegment) + 'static,
+{
+    log::info!("Transcribing audio file: {}", path.display());
+    let samples = utterance_samples(enhancement, path, sample_rate)?;
+    stt.transcribe_samples_with_options(&samples, &TranscribeOptions::default(), on_segment)
+}
+
+/// Transcribe the samples of an utterance (`path` identifies it in the events) and emit
+/// the result (or the error) to the frontend, with a "transcription-partial" event for
+/// each segment while Whisper runs
+fn emit_transcription(
+    stt: &SpeechToText,
+    app_handle: &AppHandle,
+    path: &Path,
+    samples: Result<Vec<f32>>,
+) -> Option<TranscriptionResult> {
+    let partial_handle = app_handle.clone();
+    let on_segment = move |segment: PartialSegment| {
+        log::debug!("Partial segment {}: '{}'", segment.index, segment.text);
+        let _ = partial_handle.emit("transcription-partial", &segment);
+    };
+    let result = samples.and_then(|samples| stt.transcribe_samples_with_options(&samples, &TranscribeOptions::default(), on_segment));
+    emit_result(app_handle, path, result)
+}
+
+/// Emit a transcription outcome: "transcription-result", "transcription-cancelled" (with
+/// the path) or "transcription-error"
+fn emit_result(app_handle: &AppHandle, path: &Path, result: Result<TranscriptionResult>) -> Option<TranscriptionResult> {
+    match result {
+        Ok(result) => {
+            log::info!("Transcription successful: {}", result.text);
+            let _ = app_handle.emit("transcription-result", &result);
+            Some(result)
+        }
+        Err(e) if e.is::<Cancelled>() => {
+            log::info!("Transcription cancelled: {:?}", path);
+            let _ = app_handle.emit("transcription-cancelled", path.display().to_string());
+            None
+        }
+        Err(e) => {
+            log::error!("Transcription failed: {}", e);
+            let _ = app_handle.emit("transcription-error", format!("{}", e));
+            None
+        }
+    }
+}
+
+/// Store the transcripts of a finished session so it can be replayed later
+/// ("recording-session-saved" carries its id)
+fn record_session(
+    stt: &SpeechToText,
+    app_handle: &AppHandle,
+    recordings_root: &Path,
+    session_id: &str,
+    enhanced: bool,
+    deferred: bool,
+    utterances: Vec<SessionUtterance>,
+) {
+    if let Ok(session_dir) = recording_session::session_dir(recordings_root, session_id) {
+        for utterance in utterances.iter().filter(|u| !u.text.is_empty()) {
+            if let Err(e) = audio_session::set_sidecar_transcript(&session_dir.join(&utterance.file_name), &utterance.text) {
+                log::warn!("Failed to update sidecar of utterance {}: {}", utterance.id, e);
+            }
+        }
+    }
+    let record = SessionRecord {
+        id: session_id.to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        language: stt.language(),
+        model: stt.model_name(),
+        enhanced,
+        utterances,
+        deferred,
+    };
+    match recording_session::save_session(recordings_root, &record) {
+        Ok(path) => {
+            log::info!("Session {} saved to {:?}", session_id, path);
+            let _ = app_handle.emit("recording-session-saved", session_id);
+            run_transcription_hooks(app_handle, &record, &path);
+        }
+        Err(e) => log::warn!("Failed to save session {}: {}", session_id, e),
+    }
+}
+
+/// Run the post_transcription hooks on a saved session (not on sessions stored
+/// without text, whose transcription is deferred); each run is emitted as "hook-result"
+fn run_transcription_hooks(app_handle: &AppHandle, record: &SessionRecord, session_file: &Path) {
+    let transcript = record.transcript();
+    if transcript.trim().is_empty() {
+        return;
+    }
+    let configured = match app_handle.path().app_data_dir().map_err(anyhow::Error::from).and_then(|dir| settings::load(&dir)) {
+        Ok(app_settings) => app_settings.hooks,
+        Err(e) => {
+            log::warn!("Hooks unavailable: {}", e);
+            return;
+        }
+    };
+    let session_dir = session_file.parent().unwrap_or(Path::new("."));
+    let payload = serde_json::json!({
+        "session_id": record.id,
+        "transcript": transcript,
+        "language": record.language,
+        "artifacts": {
+            "session": session_file.display().to_string(),
+            "utterances": record.utterances.iter().map(|u| session_dir.join(&u.file_name).display().to_string()).collect::<Vec<_>>(),
+        },
+    });
+    for run in hooks::run_hooks(&configured, HookPoint::PostTranscription, &payload) {
+        let _ = app_handle.emit("hook-result", &run);
+    }
+}
+
+/// Transcribe the utterances of a session and store the record
+fn transcribe_session(
+    stt: &SpeechToText,
+    app_handle: &AppHandle,
+    enhancement: &AudioEnhancementConfig,
+    recordings_root: &Path,
+    session_id: &str,
+    utterances: &[Utterance],
+) {
+    log::info!("Found {} utterances to transcribe ({} at a time)", utterances.len(), stt.concurrency());
+    let cancellation = stt.cancellation_token();
+    let mut transcripts = Vec::new();
+    // Results are emitted in utterance order, while the next ones are transcribed.
+    // Cancelled: the remaining utterances are stored without text (replayable)
+    let done = |utterance: &Utterance, result: Option<Result<TranscriptionResult>>| {
+        let result = result.and_then(|result| emit_result(app_handle, &utterance.file_path, result));
+        transcripts.push(session_utterance(utterance, result));
+    };
+    if stt.gpu_active() {
+        // One GPU state, short utterances packed into shared runs
+        transcription_scheduler::transcribe_scheduled(
+            stt,
+            utterances,
+            &cancellation,
+            |utterance| utterance.duration_ms,
+            |utterance| recorded_samples(Some(enhancement), utterance),
+            done,
+        );
+    } else {
+        stt.transcribe_batch(
+            utterances,
+            |utterance| {
+                if cancellation.is_cancelled() {
+                    return None;
+                }
+                log::info!("Transcribing utterance {}: {:?}", utterance.id, utterance.file_path);
+                let samples = recorded_samples(Some(enhancement), utterance);
+                Some(samples.and_then(|samples| stt.transcribe_samples_with_options(&samples, &TranscribeOptions::default(), |_| {})))
+            },
+            done,
+        );
+    }
+    record_session(stt, app_handle, recordings_root, session_id, true, false, transcripts);
+}
+
+/// VAD mode: transcribe each utterance as soon as the VAD closes it, while the
+/// recording goes on. Once `stop` is set, the utterances written on stop are
+/// transcribed too and the results are returned by utterance id (None when failed
+/// or cancelled).
+fn transcribe_live(
+    session: AudioSession,
+    stt: Arc<SpeechToText>,
+    app_handle: AppHandle,
+    enhancement: AudioEnhancementConfig,
+    stop: Arc<AtomicBool>,
+    live_transcript: LiveTranscript,
+) -> thread::JoinHandle<HashMap<usize, Option<TranscriptionResult>>> {
+    thread::spawn(move || {
+        let cancellation = stt.cancellation_token();
+        let mut texts = HashMap::new();
+        loop {
+            let stopping = stop.load(Ordering::Relaxed);
+            for utterance in session.get_utterances() {
+                if texts.contains_key(&utterance.id) {
+                    continue;
+                }
+                if cancellation.is_cancelled() {
+                    texts.insert(utterance.id, None);
+                    continue;
+                }
+                log::info!("Transcribing utterance {} live: {:?}", utterance.id, utterance.file_path);
+                let samples = recorded_samples(Some(&enhancement), &utterance);
+                let result = emit_transcription(&stt, &app_handle, &utterance.file_path, samples);
+                if let Some((_, transcribed)) = live_transcript.lock().unwrap().as_mut() {
+                    transcribed.push(session_utterance(&utterance, result.clone()));
+                }
+                texts.insert(utterance.id, result);
+            }
+            if stopping {
+                return texts;
+            }
+            thread::sleep(LIVE_POLL_INTERVAL);
+        }
+    })
+}
+
+fn session_utterance(utterance: &Utterance, result: Option<TranscriptionResult>) -> SessionUtterance {
+    SessionUtterance {
+        id: utterance.id,
+        file_name: utterance.file_path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+        duration_ms: utterance.duration_ms,
+        text: String::new(),
+        language: None,
+        segments: Vec::new(),
+        source: Some(utterance.source),
+    }
+    .transcribed(result)
+}
+
+/// Utterance of a stored session, read back from its WAV file
+fn stored_utterance(session_dir: &Path, utterance: &SessionUtterance) -> Utterance {
+    let sample_rate = audio_session::TARGET_SAMPLE_RATE;
+    Utterance {
+        id: utterance.id,
+        file_path: session_dir.join(&utterance.file_name),
+        duration_ms: utterance.duration_ms,
+        sample_count: (utterance.duration_ms as u64 * sample_rate as u64 / 1000) as usize,
+        sample_rate,
+        samples: None,
+        source: utterance.source.unwrap_or_default(),
+    }
+}
+
+/// Apply the audio retention policy to the recordings, sparing the sessions whose
+/// transcription is deferred
+fn apply_retention(app_handle: &AppHandle, retention: &AudioRetention, deferred: &Mutex<Vec<DeferredSession>>) -> GcReport {
+    let keep: Vec<String> = deferred.lock().unwrap().iter().map(|session| session.session_id.clone()).collect();
+    storage_gc::enforce_retention(&recordings_dir(app_handle), retention, &keep)
+}
+
+/// Transcribe the deferred sessions if the recorder is idle and the machine is on AC
+/// power (or idle for LOW_POWER_IDLE_DELAY, or back to the balanced profile)
+fn process_deferred(
+    state: &Mutex<RecordingState>,
+    stt: &SpeechToText,
+    app_handle: &AppHandle,
+    deferred: &Mutex<Vec<DeferredSession>>,
+    profile: PowerProfile,
+    idle_for: Duration,
+) {
+    if deferred.lock().unwrap().is_empty() {
+        return;
+    }
+    let ready = profile == PowerProfile::Balanced
+        || power::on_ac_power() != Some(false)
+        || idle_for >= LOW_POWER_IDLE_DELAY;
+    if !ready {
+        return;
+    }
+    {
+        let mut state = state.lock().unwrap();
+        if !matches!(*state, RecordingState::Idle) {
+            return;
+        }
+        *state = RecordingState::Processing;
+    }
+    let _ = app_handle.emit("recording-state-changed", "processing");
+
+    let sessions = std::mem::take(&mut *deferred.lock().unwrap());
+    for session in sessions {
+        log::info!("Transcribing deferred session {}", session.session_id);
+        transcribe_session(stt, app_handle, &session.enhancement, &session.recordings_root, &session.session_id, &session.utterances);
+    }
+
+    *state.lock().unwrap() = RecordingState::Idle;
+    let _ = app_handle.emit("recording-state-changed", "idle");
 }
 
 impl RecordingManager {
-    pub fn new(model_path: PathBuf, app_handle: AppHandle) -> Self {
-        Self {
+    /// `stt` is the app-wide transcriber (also used by `transcribe_audio`), so the model
+    /// is loaded once
+    pub fn new(stt: Arc<SpeechToText>, app_handle: AppHandle) -> Self {
+        let manager = Self {
             state: Arc::new(Mutex::new(RecordingState::Idle)),
             session: Arc::new(Mutex::new(None)),
-            stt: Arc::new(SpeechToText::new(model_path)),
+            stt,
             app_handle,
             selected_device: Arc::new(Mutex::new(None)),
-            enhancement_config: Arc::new(Mutex::new(AudioEnhancementConfig::default())),
+            audio_profile: Arc::new(Mutex::new(AudioProfile::default())),
+            system_capture: Arc::new(Mutex::new(SystemCapture::default())),
+            virtual_input: Arc::new(Mutex::new(None)),
+            power_profile: Arc::new(Mutex::new(PowerProfile::default())),
+            vad_settings: Arc::new(Mutex::new(VadSettings::default())),
+            recording_mode: Arc::new(Mutex::new(RecordingMode::default())),
+            archive_raw: Arc::new(Mutex::new(false)),
+            in_memory_audio: Arc::new(Mutex::new(false)),
+            retention: Arc::new(Mutex::new(AudioRetention::default())),
+            naming: Arc::new(Mutex::new(UtteranceNaming::default())),
+            whisper_backend: Arc::new(Mutex::new(WhisperBackend::default())),
+            monitor: Arc::new(Mutex::new(false)),
+            deferred: Arc::new(Mutex::new(Vec::new())),
+            last_activity: Arc::new(Mutex::new(Instant::now())),
+            live_transcript: Arc::new(Mutex::new(None)),
+        };
+        manager.requeue_deferred();
+        manager.watch_deferred();
+        manager.watch_storage();
+        manager
+    }
+
+    /// Queue again the sessions whose deferred transcription had not run when the app
+    /// last quit; until then retention spares their audio
+    fn requeue_deferred(&self) {
+        let recordings_root = recordings_dir(&self.app_handle);
+        let sessions = match recording_session::list_sessions(&recordings_root) {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                log::warn!("Deferred sessions unavailable: {}", e);
+                return;
+            }
+        };
+        let enhancement = self.enhancement_config();
+        let mut deferred = self.deferred.lock().unwrap();
+        for record in sessions.into_iter().filter(|record| record.deferred) {
+            let Ok(session_dir) = recording_session::session_dir(&recordings_root, &record.id) else { continue };
+            log::info!("Deferred transcription of session {} queued again", record.id);
+            deferred.push(DeferredSession {
+                recordings_root: recordings_root.clone(),
+                utterances: record.utterances.iter().map(|u| stored_utterance(&session_dir, u)).collect(),
+                session_id: record.id,
+                enhancement: enhancement.clone(),
+            });
         }
     }
 
+    /// Background cleanup of temporary files left by failed transcriptions and of audio
+    /// over the retention limits, skipped while a recording or its processing is running
+    fn watch_storage(&self) {
+        let state = Arc::clone(&self.state);
+        let app_handle = self.app_handle.clone();
+        let retention = Arc::clone(&self.retention);
+        let deferred = Arc::clone(&self.deferred);
+        thread::spawn(move || loop {
+            let idle = matches!(*state.lock().unwrap(), RecordingState::Idle);
+            if let (true, Ok(app_data_dir)) = (idle, app_handle.path().app_data_dir()) {
+                let roots = StorageRoots::new(&recordings_dir(&app_handle), &app_data_dir);
+                storage_gc::run(&roots, &GcRules::default());
+                let retention = *retention.lock().unwrap();
+                apply_retention(&app_handle, &retention, &deferred);
+            }
+            thread::sleep(STORAGE_GC_INTERVAL);
+        });
+    }
+
+    /// Background check for sessions deferred in low power mode
+    fn watch_deferred(&self) {
+        let state = Arc::clone(&self.state);
+        let stt = Arc::clone(&self.stt);
+        let app_handle = self.app_handle.clone();
+        let deferred = Arc::clone(&self.deferred);
+        let power_profile = Arc::clone(&self.power_profile);
+        let last_activity = Arc::clone(&self.last_activity);
+        thread::spawn(move || loop {
+            thread::sleep(DEFERRED_CHECK_INTERVAL);
+            let profile = *power_profile.lock().unwrap();
+            let idle_for = last_activity.lock().unwrap().elapsed();
+            process_deferred(&state, &stt, &app_handle, &deferred, profile, idle_for);
+        });
+    }
+
     pub fn start_recording(&self) -> Result<String> {
         let mut state = self.state.lock().unwrap();
         
-        if matches!(*state, RecordingState::Recording) {
+        if matches!(*state, RecordingState::Recording | RecordingState::Paused) {
             anyhow::bail!("Recording already in progress");
         }
 
-        // Use home directory for easier access to audio files during debugging
-        let output_dir = std::env::var("HOME")
-            .map(|h| std::path::PathBuf::from(h).join("domain-model-audio"))
-            .unwrap_or_else(|_| std::env::temp_dir().join("domain-model-audio"));
+        // One directory per session, kept for replays
+        let recordings_root = recordings_dir(&self.app_handle);
+        let session_id = recording_session::new_session_id();
+        let output_dir = recordings_root.join(&session_id);
+
+        // Refuse upfront rather than failing mid-session on a full disk
+        let archive_raw = *self.archive_raw.lock().unwrap();
+        let bytes_per_minute = disk_space::BYTES_PER_RECORDING_MINUTE * if archive_raw { 2 } else { 1 };
+        match disk_space::recording_preflight(&recordings_root, bytes_per_minute) {
+            Preflight::Ok => {}
+            Preflight::Low(status) => {
+                log::warn!("Low disk space: about {:?} minutes of recording left", status.recording_minutes_left);
+                let _ = self.app_handle.emit("disk-space-warning", &status);
+            }
+            Preflight::Insufficient(status) => {
+                let _ = self.app_handle.emit("disk-space-error", &status);
+                anyhow::bail!(status.message());
+            }
+        }
+
+        let virtual_input = self.virtual_input.lock().unwrap().clone();
+        if cfg!(mobile) && virtual_input.is_none() {
+            return self.start_native_capture(&output_dir, &mut state);
+        }
+
         let device_name = self.selected_device.lock().unwrap().clone();
+        let power_profile = *self.power_profile.lock().unwrap();
+        let vad_settings = *self.vad_settings.lock().unwrap();
+        let recording_mode = *self.recording_mode.lock().unwrap();
         
         let config = AudioSessionConfig {
             output_dir: output_dir.clone(),
             device_name,
+            virtual_input,
+            power_profile,
+            push_to_talk: recording_mode == RecordingMode::PushToTalk,
+            archive_raw,
+            naming: self.naming.lock().unwrap().clone(),
+            keep_in_memory: *self.in_memory_audio.lock().unwrap(),
             ..Default::default()
-        };
+        }
+        .with_vad(vad_settings)
+        .with_profile(&self.audio_profile.lock().unwrap())
+        .with_system_capture(&self.system_capture.lock().unwrap());
 
         let session = AudioSession::new(config)
             .context("Failed to create audio session")?;
 
+        // VU meter: "audio-level" every 100ms while capturing
+        let level_handle = self.app_handle.clone();
+        session.set_level_listener(move |level| {
+            let _ = level_handle.emit("audio-level", level);
+        });
+
+        session.set_monitor(*self.monitor.lock().unwrap());
+
+        // Unplugged device: "device-lost", then "device-reconnected" once capture resumes
+        // on the default device
+        let device_handle = self.app_handle.clone();
+        session.set_device_listener(move |event| {
+            let name = match event {
+                DeviceEvent::Lost { .. } => "device-lost",
+                DeviceEvent::Reconnected { .. } => "device-reconnected",
+            };
+            let _ = device_handle.emit(name, &event);
+        });
+
+        // Dead air (muted mic, wrong device): "silence-alert", then "silence-ended"
+        let silence_handle = self.app_handle.clone();
+        session.set_silence_listener(move |event| {
+            let name = match event {
+                SilenceEvent::Alert { .. } => "silence-alert",
+                SilenceEvent::Ended { .. } => "silence-ended",
+            };
+            let _ = silence_handle.emit(name, &event);
+        });
+
         let session_clone = session.clone();
         let state_clone = Arc::clone(&self.state);
         let session_arc = Arc::clone(&self.session);
         let stt_clone = Arc::clone(&self.stt);
         let app_handle = self.app_handle.clone();
-        let enhancement_config = self.enhancement_config.lock().unwrap().clone();
+        let enhancement_config = self.enhancement_config();
+        let deferred = Arc::clone(&self.deferred);
+        let retention = Arc::clone(&self.retention);
+        let last_activity = Arc::clone(&self.last_activity);
+        *last_activity.lock().unwrap() = Instant::now();
+        let live_transcript = Arc::clone(&self.live_transcript);
+        *live_transcript.lock().unwrap() = Some((session_id.clone(), Vec::new()));
 
         // Store session
         *self.session.lock().unwrap() = Some(session.clone());
@@ -71,6 +640,20 @@ impl RecordingManager {
         // Emit recording started event
         let _ = self.app_handle.emit("recording-state-changed", "recording");
 
+        // VAD mode: per-utterance transcription during the recording, unless it is
+        // deferred anyway (low power profile)
+        let live_stop = Arc::new(AtomicBool::new(false));
+        let live = (recording_mode == RecordingMode::Vad && power_profile == PowerProfile::Balanced).then(|| {
+            transcribe_live(
+                session.clone(),
+                Arc::clone(&self.stt),
+                self.app_handle.clone(),
+                enhancement_config.clone(),
+                Arc::clone(&live_stop),
+                Arc::clone(&self.live_transcript),
+            )
+        });
+
         // Start recording in a background thread
         thread::spawn(move || {
             log::info!("Starting audio recording thread");
@@ -80,6 +663,47 @@ impl RecordingManager {
                 let _ = app_handle.emit("recording-error", format!("{}", e));
             }
             
+            *last_activity.lock().unwrap() = Instant::now();
+            let utterances = session_arc.lock().unwrap().as_ref().map(|s| s.get_utterances()).unwrap_or_default();
+
+            // Utterances were transcribed as they ended: only the last ones are left
+            if let Some(live) = live {
+                *state_clone.lock().unwrap() = RecordingState::Processing;
+                let _ = app_handle.emit("recording-state-changed", "processing");
+                live_stop.store(true, Ordering::Relaxed);
+                let mut texts = live.join().unwrap_or_default();
+                let transcripts = utterances
+                    .iter()
+                    .map(|u| session_utterance(u, texts.remove(&u.id).flatten()))
+                    .collect();
+                record_session(&stt_clone, &app_handle, &recordings_root, &session_id, true, false, transcripts);
+                *live_transcript.lock().unwrap() = None;
+                apply_retention(&app_handle, &retention.lock().unwrap(), &deferred);
+                *state_clone.lock().unwrap() = RecordingState::Idle;
+                let _ = app_handle.emit("recording-state-changed", "idle");
+                return;
+            }
+
+            // Low power on battery: keep the files, transcribe later (see process_deferred).
+            // The session is stored right away without text, marked deferred so that it
+            // is queued again if the app quits first, and can also be replayed.
+            if power_profile == PowerProfile::LowPower && power::on_ac_power() == Some(false) {
+                log::info!("On battery: deferring transcription of {} utterances", utterances.len());
+                let pending = utterances.iter().map(|u| session_utterance(u, None)).collect();
+                record_session(&stt_clone, &app_handle, &recordings_root, &session_id, true, true, pending);
+                let _ = app_handle.emit("transcription-deferred", &session_id);
+                deferred.lock().unwrap().push(DeferredSession {
+                    recordings_root,
+                    session_id,
+                    utterances,
+                    enhancement: enhancement_config,
+                });
+                *live_transcript.lock().unwrap() = None;
+                *state_clone.lock().unwrap() = RecordingState::Idle;
+                let _ = app_handle.emit("recording-state-changed", "idle");
+                return;
+            }
+
             // When recording stops, process utterances
             log::info!("Recording stopped, processing utterances");
             let mut state_guard = state_clone.lock().unwrap();
@@ -88,74 +712,9 @@ impl RecordingManager {
 
             let _ = app_handle.emit("recording-state-changed", "processing");
 
-            // Get all utterances
-            let session_guard = session_arc.lock().unwrap();
-            if let Some(sess) = session_guard.as_ref() {
-                let utterances = sess.get_utterances();
-                log::info!("Found {} utterances to transcribe", utterances.len());
-                
-                // Transcribe all utterances
-                for utterance in utterances {
-                    log::info!("Transcribing utterance {}: {:?}", utterance.id, utterance.file_path);
-                    
-                    // Appliquer l'amélioration audio avant transcription
-                    let enhanced_path = utterance.file_path.with_extension("enhanced.wav");
-                    
-                    // Créer l'enhancer avec le sample rate du fichier (detecté depuis le nom de fichier ou par défaut 48kHz)
-                    let sample_rate = 48000; // TODO: détecter depuis le fichier WAV
-                    
-                    match AudioEnhancer::new(sample_rate, enhancement_config.clone()) {
-                        Ok(mut enhancer) => {
-                            match enhancer.process_file(&utterance.file_path, &enhanced_path) {
-                                Ok(_) => {
-                                    log::info!("Audio enhancement applied successfully");
-                                    // Transcrire le fichier amélioré
-                                    match stt_clone.transcribe_file(&enhanced_path) {
-                                        Ok(result) => {
-                                            log::info!("Transcription successful: {}", result.text);
-                                            let _ = app_handle.emit("transcription-result", &result);
-                                        }
-                                        Err(e) => {
-                                            log::error!("Transcription failed: {}", e);
-                                            let _ = app_handle.emit("transcription-error", format!("{}", e));
-                                        }
-                                    }
-                                    // Supprimer le fichier temporaire amélioré
-                                    let _ = std::fs::remove_file(&enhanced_path);
-                                }
-                                Err(e) => {
-                                    log::warn!("Audio enhancement failed, using original file: {}", e);
-                                    // Fallback: transcrire le fichier original
-                                    match stt_clone.transcribe_file(&utterance.file_path) {
-                                        Ok(result) => {
-                                            log::info!("Transcription successful: {}", result.text);
-                                            let _ = app_handle.emit("transcription-result", &result);
-                                        }
-                                        Err(e) => {
-                                            log::error!("Transcription failed: {}", e);
-                                            let _ = app_handle.emit("transcription-error", format!("{}", e));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            log::warn!("Failed to create audio enhancer: {}", e);
-                            // Fallback: transcrire le fichier original
-                            match stt_clone.transcribe_file(&utterance.file_path) {
-                                Ok(result) => {
-                                    log::info!("Transcription successful: {}", result.text);
-                                    let _ = app_handle.emit("transcription-result", &result);
-                                }
-                                Err(e) => {
-                                    log::error!("Transcription failed: {}", e);
-                                    let _ = app_handle.emit("transcription-error", format!("{}", e));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            transcribe_session(&stt_clone, &app_handle, &enhancement_config, &recordings_root, &session_id, &utterances);
+            *live_transcript.lock().unwrap() = None;
+            apply_retention(&app_handle, &retention.lock().unwrap(), &deferred);
 
             let mut state_guard = state_clone.lock().unwrap();
             *state_guard = RecordingState::Idle;
@@ -168,12 +727,16 @@ impl RecordingManager {
     }
 
     pub fn stop_recording(&self) -> Result<String> {
-        let state = self.state.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
         
-        if !matches!(*state, RecordingState::Recording) {
+        if !matches!(*state, RecordingState::Recording | RecordingState::Paused) {
             anyhow::bail!("No recording in progress");
         }
 
+        if cfg!(mobile) {
+            return self.stop_native_capture(&mut state);
+        }
+
         // Signal the audio session to stop
         if let Some(session) = self.session.lock().unwrap().as_ref() {
             session.stop();
@@ -182,6 +745,108 @@ impl RecordingManager {
         Ok("Recording stopped. Processing utterances...".to_string())
     }
 
+    /// Pause the current recording; resuming continues the same session, so the
+    /// utterances before and after the pause end up in one session and one transcript
+    pub fn pause_recording(&self) -> Result<String> {
+        let mut state = self.state.lock().unwrap();
+        if !matches!(*state, RecordingState::Recording) {
+            anyhow::bail!("No recording in progress");
+        }
+        if cfg!(mobile) {
+            anyhow::bail!("Pausing is not supported by the native recorder");
+        }
+
+        if let Some(session) = self.session.lock().unwrap().as_ref() {
+            session.pause();
+        }
+        *state = RecordingState::Paused;
+        let _ = self.app_handle.emit("recording-state-changed", "paused");
+        Ok("Recording paused".to_string())
+    }
+
+    pub fn resume_recording(&self) -> Result<String> {
+        let mut state = self.state.lock().unwrap();
+        if !matches!(*state, RecordingState::Paused) {
+            anyhow::bail!("Recording is not paused");
+        }
+
+        if let Some(session) = self.session.lock().unwrap().as_ref() {
+            session.resume();
+        }
+        *self.last_activity.lock().unwrap() = Instant::now();
+        *state = RecordingState::Recording;
+        let _ = self.app_handle.emit("recording-state-changed", "recording");
+        Ok("Recording resumed".to_string())
+    }
+
+    /// Mobile: the native recorder (AVAudioSession / AudioRecord) writes one 16kHz WAV
+    /// per take in the app sandbox; it is transcribed when the take stops
+    fn start_native_capture(&self, output_dir: &Path, state: &mut RecordingState) -> Result<String> {
+        std::fs::create_dir_all(output_dir).context("Failed to create recordings directory")?;
+        let output_path = output_dir.join(format!("take_{}.wav", chrono::Local::now().format("%Y%m%d_%H%M%S")));
+
+        self.app_handle
+            .audio_capture()
+            .start(StartCaptureRequest {
+                output_path: output_path.display().to_string(),
+                sample_rate: audio_session::TARGET_SAMPLE_RATE,
+            })
+            .context("Failed to start native audio capture")?;
+
+        *state = RecordingState::Recording;
+        let _ = self.app_handle.emit("recording-state-changed", "recording");
+        Ok(format!("Recording started. Audio will be saved to: {:?}", output_path))
+    }
+
+    fn stop_native_capture(&self, state: &mut RecordingState) -> Result<String> {
+        let capture = self
+            .app_handle
+            .audio_capture()
+            .stop()
+            .context("Failed to stop native audio capture")?;
+
+        *state = RecordingState::Processing;
+        let _ = self.app_handle.emit("recording-state-changed", "processing");
+
+        let state_clone = Arc::clone(&self.state);
+        let stt_clone = Arc::clone(&self.stt);
+        let app_handle = self.app_handle.clone();
+        thread::spawn(move || {
+            log::info!("Transcribing native take {} ({}ms)", capture.path, capture.duration_ms);
+            // No ffmpeg enhancement on phones: the take is already 16kHz mono
+            let take_path = Path::new(&capture.path);
+            let samples = utterance_samples(None, take_path, Some(audio_session::TARGET_SAMPLE_RATE));
+            let result = emit_transcription(&stt_clone, &app_handle, take_path, samples);
+
+            // The take was written in its session directory (see start_native_capture)
+            let session_dir = take_path.parent().unwrap_or(Path::new("."));
+            if let (Some(session_id), Some(recordings_root)) = (session_dir.file_name(), session_dir.parent()) {
+                let take = SessionUtterance {
+                    id: 1,
+                    file_name: take_path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                    duration_ms: capture.duration_ms.min(u32::MAX as u64) as u32,
+                    text: String::new(),
+                    language: None,
+                    segments: Vec::new(),
+                    source: None,
+                }
+                .transcribed(result);
+                record_session(&stt_clone, &app_handle, recordings_root, &session_id.to_string_lossy(), false, false, vec![take]);
+            }
+
+            *state_clone.lock().unwrap() = RecordingState::Idle;
+            let _ = app_handle.emit("recording-state-changed", "idle");
+        });
+
+        Ok("Recording stopped. Processing take...".to_string())
+    }
+
+    /// Session id of the recording in progress and its utterances transcribed so far (only
+    /// VAD mode transcribes during the recording); None once the session is stored
+    pub fn live_transcript(&self) -> Option<(String, Vec<SessionUtterance>)> {
+        self.live_transcript.lock().unwrap().clone()
+    }
+
     pub fn get_state(&self) -> RecordingState {
         self.state.lock().unwrap().clone()
     }
@@ -199,6 +864,185 @@ impl RecordingManager {
         Ok(())
     }
 
+    /// Gain, AGC and enhancement of the next recordings (a recording in progress keeps
+    /// its levels), usually the saved profile of the selected device
+    pub fn set_audio_profile(&self, profile: AudioProfile) {
+        log::info!("Audio profile set to {:?}", profile);
+        *self.audio_profile.lock().unwrap() = profile;
+    }
+
+    pub fn audio_profile(&self) -> AudioProfile {
+        self.audio_profile.lock().unwrap().clone()
+    }
+
+    /// Record the system audio (loopback device) next to the microphone in the next
+    /// recordings, with utterances tagged by source (None device = microphone only)
+    pub fn set_system_capture(&self, capture: SystemCapture) {
+        log::info!("System audio capture set to {:?}", capture);
+        *self.system_capture.lock().unwrap() = capture;
+    }
+
+    /// Replace the microphone by a WAV file (None = back to the real device)
+    pub fn set_virtual_input(&self, virtual_input: Option<VirtualInput>) -> Result<()> {
+        let state = self.state.lock().unwrap();
+
+        if !matches!(*state, RecordingState::Idle) {
+            anyhow::bail!("Cannot change audio source while recording");
+        }
+
+        match &virtual_input {
+            Some(input) => log::info!("Virtual microphone set to {:?} ({:?})", input.path, input.pacing),
+            None => log::info!("Virtual microphone disabled"),
+        }
+        *self.virtual_input.lock().unwrap() = virtual_input;
+
+        Ok(())
+    }
+
+    /// Spoken language of the next transcriptions (takes effect immediately,
+    /// including for utterances still queued in a recording)
+    pub fn set_language(&self, language: &str) {
+        self.stt.set_language(language);
+    }
+
+    pub fn set_translate(&self, translate: bool) {
+        self.stt.set_translate(translate);
+    }
+
+    /// Remote provider used without local model or on low Whisper confidence
+    pub fn set_cloud_stt(&self, cloud: Option<CloudStt>) {
+        self.stt.set_cloud_fallback(cloud.map(Arc::new));
+    }
+
+    /// Whisper transcriber with the current settings, on another model if given
+    /// (used to re-process stored sessions without touching the live one)
+    pub fn replay_transcriber(&self, model_path: Option<PathBuf>) -> SpeechToText {
+        let model_path = model_path.unwrap_or_else(|| self.stt.model_path());
+        let settings = WhisperSettings { use_gpu: self.stt.uses_gpu(), ..platform_whisper_settings() };
+        let stt = SpeechToText::with_settings(model_path, settings);
+        stt.set_language(&self.stt.language());
+        stt.set_translate(self.stt.translate());
+        stt.set_cloud_fallback(self.stt.cloud_fallback());
+        stt
+    }
+
+    /// Silence detection of the next recordings (a recording in progress keeps its settings)
+    pub fn set_vad_settings(&self, vad: VadSettings) {
+        *self.vad_settings.lock().unwrap() = vad;
+        log::info!("VAD settings set to {:?}", vad);
+    }
+
+    /// Play back what is being captured (after gain and AGC) on the default output, for
+    /// the current recording and the next ones
+    pub fn set_monitor(&self, enabled: bool) {
+        *self.monitor.lock().unwrap() = enabled;
+        if let Some(session) = self.session.lock().unwrap().as_ref() {
+            session.set_monitor(enabled);
+        }
+        log::info!("Monitoring {}", if enabled { "enabled" } else { "disabled" });
+    }
+
+    /// Segmentation of the next recordings (a recording in progress keeps its mode)
+    pub fn set_recording_mode(&self, mode: RecordingMode) {
+        *self.recording_mode.lock().unwrap() = mode;
+        log::info!("Recording mode set to {:?}", mode);
+    }
+
+    /// Also store the raw capture of the next recordings (`utterance_0001.raw.wav` next
+    /// to each utterance), so they can be replayed from the untouched signal
+    pub fn set_archive_raw(&self, enabled: bool) {
+        *self.archive_raw.lock().unwrap() = enabled;
+        log::info!("Raw audio archiving {}", if enabled { "enabled" } else { "disabled" });
+    }
+
+    /// Keep the utterances of the next recordings in memory and transcribe them from
+    /// there: no WAV is left in the recordings directory, but the sessions cannot be replayed
+    pub fn set_in_memory_audio(&self, enabled: bool) {
+        *self.in_memory_audio.lock().unwrap() = enabled;
+        log::info!("In-memory audio {}", if enabled { "enabled" } else { "disabled" });
+    }
+
+    /// Limits on the audio kept by finished sessions, enforced now (in the background),
+    /// after each session and with the periodic storage cleanup
+    pub fn set_audio_retention(&self, retention: AudioRetention) {
+        log::info!("Audio retention set to {:?}", retention);
+        *self.retention.lock().unwrap() = retention;
+        let app_handle = self.app_handle.clone();
+        let deferred = Arc::clone(&self.deferred);
+        thread::spawn(move || apply_retention(&app_handle, &retention, &deferred));
+    }
+
+    /// Remove the audio of every finished session. Transcripts stay; the sessions being
+    /// recorded or waiting for a deferred transcription keep theirs.
+    pub fn purge_audio_cache(&self) -> GcReport {
+        let keep: Vec<String> = self.deferred.lock().unwrap().iter().map(|session| session.session_id.clone()).collect();
+        storage_gc::purge_audio(&recordings_dir(&self.app_handle), &keep)
+    }
+
+    /// File names and sidecars of the next recordings
+    pub fn set_utterance_naming(&self, naming: UtteranceNaming) {
+        log::info!("Utterance naming set to {:?}", naming);
+        *self.naming.lock().unwrap() = naming;
+    }
+
+    /// Power profile of the next recordings. Going back to balanced transcribes the
+    /// deferred sessions right away.
+    pub fn set_power_profile(&self, profile: PowerProfile) {
+        *self.power_profile.lock().unwrap() = profile;
+        log::info!("Power profile set to {:?}", profile);
+
+        let state = Arc::clone(&self.state);
+        let stt = Arc::clone(&self.stt);
+        let app_handle = self.app_handle.clone();
+        let deferred = Arc::clone(&self.deferred);
+        let idle_for = self.last_activity.lock().unwrap().elapsed();
+        thread::spawn(move || process_deferred(&state, &stt, &app_handle, &deferred, profile, idle_for));
+    }
+
+    /// Abort the transcriptions in progress: the current utterance stops at Whisper's next
+    /// abort check and the remaining ones of the session are stored without text
+    pub fn cancel_transcription(&self) {
+        self.stt.cancel();
+    }
+
+    /// Whisper model of the next transcriptions, loaded on first use
+    pub fn set_whisper_model(&self, model_path: PathBuf) {
+        self.stt.set_model_path(model_path);
+    }
+
+    /// Backend of the next transcriptions; a GPU backend missing from this build falls
+    /// back to the CPU
+    pub fn set_whisper_backend(&self, backend: WhisperBackend) {
+        *self.whisper_backend.lock().unwrap() = backend;
+        let active = backend.resolve(&speech_to_text::compiled_gpu_backends());
+        self.stt.set_use_gpu(active != WhisperBackend::Cpu);
+    }
+
+    pub fn whisper_backend(&self) -> BackendReport {
+        let preference = *self.whisper_backend.lock().unwrap();
+        let compiled = speech_to_text::compiled_gpu_backends();
+        let model_path = self.stt.model_path();
+        BackendReport {
+            preference,
+            active: if self.stt.uses_gpu() { preference.resolve(&compiled) } else { WhisperBackend::Cpu },
+            compiled,
+            model: self.stt.model_name(),
+            quantization: speech_to_text::model_quantization(&model_path),
+            loaded: self.stt.is_loaded(),
+            concurrency: self.stt.concurrency(),
+            scheduler: crate::transcription_scheduler::metrics(),
+        }
+    }
+
+    /// Whisper model used for live transcriptions
+    pub fn model_path(&self) -> PathBuf {
+        self.stt.model_path()
+    }
+
+    pub fn enhancement_config(&self) -> AudioEnhancementConfig {
+        self.audio_profile.lock().unwrap().enhancement.clone()
+    }
+
     pub fn get_selected_device(&self) -> Option<String> {
         self.selected_device.lock().unwrap().clone()
     }
@@ -0,0 +1,340 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::audio_session::AudioSource;
+use crate::llm_router::LlmRequestOptions;
+use crate::safe_write;
+use crate::speech_to_text::{Segment, TranscriptionResult};
+
+const SESSION_FILE: &str = "session.json";
+/// Session ids are the local time the recording started
+const SESSION_ID_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+/// Transcript of one stored utterance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionUtterance {
+    pub id: usize,
+    /// WAV file, relative to the session directory
+    pub file_name: String,
+    pub duration_ms: u32,
+    pub text: String,
+    /// Spoken language of the utterance (detected when the session language is "auto")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Timed segments, relative to the start of the utterance (for subtitles)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<Segment>,
+    /// Captured stream (microphone or system loopback); sessions recorded before
+    /// dual-source capture have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<AudioSource>,
+}
+
+impl SessionUtterance {
+    /// Same utterance with the text, language and segments of a transcription (none
+    /// when it failed or was cancelled)
+    pub fn transcribed(&self, result: Option<TranscriptionResult>) -> Self {
+        let (text, language, segments) = result.map(|r| (r.text, r.language, r.segments)).unwrap_or_default();
+        Self { text, language, segments, ..self.clone() }
+    }
+}
+
+/// A recording session: its utterances are kept under `<recordings>/<id>/` next to
+/// `session.json`, so they can be transcribed again later
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub id: String,
+    pub created_at: String,
+    pub language: String,
+    /// Whisper model file name
+    pub model: String,
+    /// Whether utterances went through audio enhancement before transcription
+    pub enhanced: bool,
+    pub utterances: Vec<SessionUtterance>,
+    /// Transcription postponed (low power profile on battery): the utterances have no
+    /// text yet and the session is queued again when the app starts
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deferred: bool,
+}
+
+impl SessionRecord {
+    /// Full transcript, one line per utterance, with source labels and language tags
+    /// when the utterances come from several streams or languages (see `transcript_of`)
+    pub fn transcript(&self) -> String {
+        transcript_of(&self.utterances)
+    }
+}
+
+/// What to change when replaying a session (unset = current settings)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayOptions {
+    pub language: Option<String>,
+    /// Whisper model to use instead of the current one
+    pub model_path: Option<String>,
+    pub enhance: Option<bool>,
+    /// Also regenerate the domain model from the new transcript
+    pub generate_model: bool,
+    pub llm: Option<LlmRequestOptions>,
+    /// Transcribe the raw tracks (`*.raw.wav`, before gain and AGC) when the session kept them
+    pub raw: bool,
+}
+
+/// Result of re-processing a session, stored under `<session>/replays/<id>.json`
+/// without touching the original record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionReplay {
+    pub id: String,
+    pub session_id: String,
+    pub created_at: String,
+    pub language: String,
+    pub model: String,
+    pub enhanced: bool,
+    pub utterances: Vec<SessionUtterance>,
+    pub transcript: String,
+    /// Transcript of the original recording, for comparison
+    pub original_transcript: String,
+    /// Orchestration result when `generate_model` was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain_model: Option<Value>,
+}
+
+/// One line per utterance. When they are in several languages, a `[xx]` tag opens
+/// each line whose language differs from the previous one, e.g.
+/// `[fr] Le client passe commande.\n[en] The order has lines.`
+/// When both the microphone and the system audio were captured, every line starts
+/// with its source label (`Micro: ...`, `Système: [en] ...`) so the speakers stay apparent.
+pub(crate) fn transcript_of(utterances: &[SessionUtterance]) -> String {
+    let lines: Vec<(Option<AudioSource>, Option<&str>, &str)> = utterances
+        .iter()
+        .map(|u| (u.source, u.language.as_deref(), u.text.trim()))
+        .filter(|(_, _, text)| !text.is_empty())
+        .collect();
+    let mut languages: Vec<&str> = lines.iter().filter_map(|(_, language, _)| *language).collect();
+    languages.sort_unstable();
+    languages.dedup();
+    let dual_source = lines.iter().any(|(source, _, _)| *source == Some(AudioSource::System))
+        && lines.iter().any(|(source, _, _)| *source != Some(AudioSource::System));
+
+    let mut previous = None;
+    lines
+        .into_iter()
+        .map(|(source, language, text)| {
+            let line = match language {
+                Some(language) if languages.len() > 1 && previous != Some(language) => {
+                    previous = Some(language);
+                    format!("[{}] {}", language, text)
+                }
+                _ => text.to_string(),
+            };
+            if dual_source {
+                format!("{}: {}", source.unwrap_or_default().label(), line)
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether the transcript has source labels (see `transcript_of`)
+pub fn has_source_labels(transcript: &str) -> bool {
+    transcript.lines().any(|line| line.split_whitespace().next().and_then(AudioSource::from_label) == Some(AudioSource::System))
+}
+
+/// `[xx]` language tag of a transcript (see `transcript_of`)
+pub(crate) fn language_tag(word: &str) -> Option<&str> {
+    let code = word.strip_prefix('[')?.strip_suffix(']')?;
+    ((2..=3).contains(&code.len()) && code.chars().all(|c| c.is_ascii_lowercase())).then_some(code)
+}
+
+/// Languages of a tagged transcript with their word count, most used first (empty
+/// for a transcript in a single language)
+pub fn transcript_languages(transcript: &str) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    let mut current: Option<usize> = None;
+    for word in transcript.split_whitespace() {
+        if AudioSource::from_label(word).is_some() {
+            continue;
+        }
+        if let Some(code) = language_tag(word) {
+            current = Some(counts.iter().position(|(language, _)| language == code).unwrap_or_else(|| {
+                counts.push((code.to_string(), 0));
+                counts.len() - 1
+            }));
+        } else if let Some(index) = current {
+            counts[index].1 += 1;
+        }
+    }
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    if counts.len() < 2 {
+        counts.clear();
+    }
+    counts
+}
+
+/// Session id from the current time (also the name of its directory)
+pub fn new_session_id() -> String {
+    chrono::Local::now().format(SESSION_ID_FORMAT).to_string()
+}
+
+/// Time the recording of a session started, from its id (None for ids not made by
+/// `new_session_id`)
+pub fn session_started_at(session_id: &str) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    use chrono::TimeZone;
+    let naive = chrono::NaiveDateTime::parse_from_str(session_id, SESSION_ID_FORMAT).ok()?;
+    chrono::Local.from_local_datetime(&naive).earliest().map(|time| time.fixed_offset())
+}
+
+/// Directory of a session (ids are generated by `new_session_id`, anything
+/// that could escape the recordings directory is refused)
+pub fn session_dir(recordings_dir: &Path, session_id: &str) -> Result<PathBuf> {
+    if session_id.is_empty() || !session_id.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        anyhow::bail!("Invalid session id '{}'", session_id);
+    }
+    Ok(recordings_dir.join(session_id))
+}
+
+pub fn save_session(recordings_dir: &Path, record: &SessionRecord) -> Result<PathBuf> {
+    let dir = session_dir(recordings_dir, &record.id)?;
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let path = dir.join(SESSION_FILE);
+    let content = serde_json::to_string_pretty(record).context("Failed to serialize session")?;
+    safe_write::write(&path, content)?;
+    Ok(path)
+}
+
+pub fn load_session(recordings_dir: &Path, session_id: &str) -> Result<SessionRecord> {
+    let path = session_dir(recordings_dir, session_id)?.join(SESSION_FILE);
+    let content = fs::read_to_string(&path).with_context(|| format!("Unknown session '{}'", session_id))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid session file {:?}", path))
+}
+
+/// Stored sessions, most recent first
+pub fn list_sessions(recordings_dir: &Path) -> Result<Vec<SessionRecord>> {
+    if !recordings_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(recordings_dir).with_context(|| format!("Failed to read {:?}", recordings_dir))? {
+        let path = entry?.path().join(SESSION_FILE);
+        if !path.exists() {
+            continue;
+        }
+        match fs::read_to_string(&path).map_err(anyhow::Error::from).and_then(|c| Ok(serde_json::from_str(&c)?)) {
+            Ok(record) => sessions.push(record),
+            Err(e) => log::warn!("[Session] Skipping {:?}: {}", path, e),
+        }
+    }
+    sessions.sort_by(|a: &SessionRecord, b| b.created_at.cmp(&a.created_at));
+    Ok(sessions)
+}
+
+/// Build the replay of `record` from its re-transcribed utterances
+pub fn new_replay(record: &SessionRecord, language: &str, model: &str, enhanced: bool, utterances: Vec<SessionUtterance>) -> SessionReplay {
+    let now = chrono::Local::now();
+    SessionReplay {
+        id: now.format("%Y%m%d_%H%M%S_%3f").to_string(),
+        session_id: record.id.clone(),
+        created_at: now.to_rfc3339(),
+        language: language.to_string(),
+        model: model.to_string(),
+        enhanced,
+        transcript: transcript_of(&utterances),
+        utterances,
+        original_transcript: record.transcript(),
+        domain_model: None,
+    }
+}
+
+pub fn save_replay(recordings_dir: &Path, replay: &SessionReplay) -> Result<PathBuf> {
+    let dir = session_dir(recordings_dir, &replay.session_id)?.join("replays");
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let path = dir.join(format!("{}.json", replay.id));
+    let content = serde_json::to_string_pretty(replay).context("Failed to serialize replay")?;
+    safe_write::write(&path, content)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterance(id: usize, text: &str) -> SessionUtterance {
+        SessionUtterance { id, file_name: format!("utterance_{:04}.wav", id), duration_ms: 1200, text: text.to_string(), language: None, segments: Vec::new(), source: None }
+    }
+
+    #[test]
+    fn test_bilingual_transcript_is_tagged() {
+        let spoken = |id, language: &str, text| SessionUtterance { language: Some(language.to_string()), ..utterance(id, text) };
+        let mixed = [
+            spoken(1, "fr", "Le client passe une commande."),
+            spoken(2, "fr", "Elle a des lignes."),
+            spoken(3, "en", "Each order line has a SKU."),
+            spoken(4, "fr", "Et une quantité."),
+        ];
+        let transcript = transcript_of(&mixed);
+        assert_eq!(
+            transcript,
+            "[fr] Le client passe une commande.\nElle a des lignes.\n[en] Each order line has a SKU.\n[fr] Et une quantité."
+        );
+        assert_eq!(transcript_languages(&transcript), vec![("fr".to_string(), 12), ("en".to_string(), 6)]);
+
+        // A single language: no tags
+        let french = transcript_of(&mixed[..2]);
+        assert_eq!(french, "Le client passe une commande.\nElle a des lignes.");
+        assert!(transcript_languages(&french).is_empty());
+
+        // Microphone and system audio: every line is labelled, tags follow the label
+        let captured = |source, utterance: SessionUtterance| SessionUtterance { source: Some(source), ..utterance };
+        let meeting = [captured(AudioSource::Mic, mixed[1].clone()), captured(AudioSource::System, mixed[2].clone())];
+        let transcript = transcript_of(&meeting);
+        assert_eq!(transcript, "Micro: [fr] Elle a des lignes.\nSystème: [en] Each order line has a SKU.");
+        assert!(has_source_labels(&transcript) && !has_source_labels(&french));
+        assert_eq!(transcript_languages(&transcript), vec![("en".to_string(), 6), ("fr".to_string(), 4)]);
+    }
+
+    #[test]
+    fn test_session_round_trip_and_replay_keeps_original() {
+        let recordings = std::env::temp_dir().join(format!("sessions_test_{}", std::process::id()));
+        let record = SessionRecord {
+            id: "20250101_100000".to_string(),
+            created_at: "2025-01-01T10:00:00+01:00".to_string(),
+            language: "fr".to_string(),
+            model: "ggml-small.bin".to_string(),
+            enhanced: true,
+            utterances: vec![utterance(1, " Un client passe commande. "), utterance(2, ""), utterance(3, "Elle a des lignes.")],
+            deferred: false,
+        };
+        let path = save_session(&recordings, &record).unwrap();
+        assert_eq!(load_session(&recordings, &record.id).unwrap(), record);
+        assert!(!fs::read_to_string(&path).unwrap().contains("deferred"));
+        assert_eq!(record.transcript(), "Un client passe commande.\nElle a des lignes.");
+
+        let replay = new_replay(&record, "fr", "ggml-large-v3.bin", false, vec![utterance(1, "Un client passe une commande.")]);
+        let path = save_replay(&recordings, &replay).unwrap();
+        assert!(path.starts_with(recordings.join(&record.id).join("replays")));
+        assert_eq!(replay.original_transcript, record.transcript());
+        assert_eq!(load_session(&recordings, &record.id).unwrap(), record);
+
+        // A deferred session stays marked as such across restarts
+        let pending = SessionRecord { id: "20250101_110000".to_string(), deferred: true, ..record.clone() };
+        save_session(&recordings, &pending).unwrap();
+        assert!(load_session(&recordings, &pending.id).unwrap().deferred);
+
+        let sessions = list_sessions(&recordings).unwrap();
+        assert_eq!(sessions.len(), 2);
+        let _ = fs::remove_dir_all(&recordings);
+    }
+
+    #[test]
+    fn test_session_id_cannot_escape_recordings_dir() {
+        let recordings = Path::new("/tmp/recordings");
+        assert!(session_dir(recordings, "../etc").is_err());
+        assert!(session_dir(recordings, "").is_err());
+        assert_eq!(session_dir(recordings, "20250101_100000").unwrap(), recordings.join("20250101_100000"));
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+/// System prompt for entity refinement: the LLM only sees one entity and its
+/// surroundings, and answers with a minimal patch
+pub const REFINE_SYSTEM_PROMPT: &str = r#"
+Tu affines UNE entité d'un Domain Model selon une instruction. Tu reçois l'entité cible, ses relations, les entités voisines (résumées) et les invariants qui la concernent.
+
+Réponds UNIQUEMENT avec un patch JSON minimal:
+{
+  "upsert": {
+    "entities": [ /* entités complètes ajoutées ou modifiées (même format que le Domain Model) */ ],
+    "relations": [ /* relations complètes ajoutées ou modifiées */ ],
+    "invariants": [ /* invariants complets ajoutés ou modifiés */ ]
+  },
+  "remove": {
+    "entities": [ /* ids */ ],
+    "relations": [ /* ids */ ],
+    "invariants": [ /* ids */ ]
+  }
+}
+
+RÈGLES:
+1. N'inclus QUE ce qui change; omets les listes vides
+2. Une entité ou relation modifiée est renvoyée en entier, avec le même id
+3. Ne modifie ni ne supprime les entités voisines; tu peux en créer de nouvelles (ex: value object)
+4. Ne supprime que l'entité cible, ses relations et ses invariants
+5. Respecte le schema: ids ^[a-zA-Z][a-zA-Z0-9_-]*$, types d'attributs string|number|integer|boolean|date|datetime|email|url|uuid|json|text, cardinalités 0..1|1|0..n|1..n|*
+"#;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatchElements {
+    #[serde(default)]
+    pub entities: Vec<Value>,
+    #[serde(default)]
+    pub relations: Vec<Value>,
+    #[serde(default)]
+    pub invariants: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatchRemovals {
+    #[serde(default)]
+    pub entities: Vec<String>,
+    #[serde(default)]
+    pub relations: Vec<String>,
+    #[serde(default)]
+    pub invariants: Vec<String>,
+}
+
+/// Minimal change set returned by the LLM for a refinement.
+///
+/// Elements are addressed by id, which LLMs handle far better than array
+/// indices; the applied result is turned into an RFC 6902 patch afterwards.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntityPatch {
+    #[serde(default)]
+    pub upsert: PatchElements,
+    #[serde(default)]
+    pub remove: PatchRemovals,
+}
+
+fn id_of(value: &Value) -> &str {
+    value.get("id").and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn list<'a>(model: &'a Value, key: &str) -> &'a [Value] {
+    model.get(key).and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[])
+}
+
+fn touches(relation: &Value, entity_id: &str) -> bool {
+    relation.pointer("/from/entityId").and_then(|v| v.as_str()) == Some(entity_id)
+        || relation.pointer("/to/entityId").and_then(|v| v.as_str()) == Some(entity_id)
+}
+
+/// Invariants scoped to the entity or whose expression mentions it
+fn concerns(invariant: &Value, entity_id: &str) -> bool {
+    let scoped = invariant
+        .pointer("/scope/entities")
+        .and_then(|v| v.as_array())
+        .is_some_and(|e| e.iter().any(|e| e.as_str() == Some(entity_id)));
+    let expression = invariant.get("expression").and_then(|v| v.as_str()).unwrap_or("");
+    scoped
+        || expression
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|word| word == entity_id)
+}
+
+/// The part of the model the LLM sees: the entity, its relations, the
+/// neighbouring entities (id, name and attribute names only) and its invariants
+pub fn entity_context(model: &Value, entity_id: &str) -> Result<Value> {
+    let entity = list(model, "entities")
+        .iter()
+        .find(|e| id_of(e) == entity_id)
+        .with_context(|| format!("Entity '{}' not found in the model", entity_id))?;
+
+    let relations: Vec<&Value> = list(model, "relations").iter().filter(|r| touches(r, entity_id)).collect();
+    let neighbour_ids: HashSet<&str> = relations
+        .iter()
+        .flat_map(|r| {
+            [
+                r.pointer("/from/entityId").and_then(|v| v.as_str()),
+                r.pointer("/to/entityId").and_then(|v| v.as_str()),
+            ]
+        })
+        .flatten()
+        .filter(|id| *id != entity_id)
+        .collect();
+    let neighbours: Vec<Value> = list(model, "entities")
+        .iter()
+        .filter(|e| neighbour_ids.contains(id_of(e)))
+        .map(|e| {
+            json!({
+                "id": e["id"],
+                "name": e["name"],
+                "attributes": list(e, "attributes").iter().map(|a| a["name"].clone()).collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let invariants: Vec<&Value> = list(model, "invariants").iter().filter(|i| concerns(i, entity_id)).collect();
+
+    Ok(json!({
+        "entity": entity,
+        "relations": relations,
+        "neighbours": neighbours,
+        "invariants": invariants
+    }))
+}
+
+/// User prompt: context, previous instructions of the conversation and the new instruction
+pub fn refine_prompt(context: &Value, previous_instructions: &[String], instruction: &str) -> Result<String> {
+    let mut prompt = format!(
+        "Contexte:\n{}\n\n",
+        serde_json::to_string_pretty(context).context("Failed to serialize entity context")?
+    );
+    if !previous_instructions.is_empty() {
+        prompt.push_str("Instructions déjà appliquées (le contexte en tient compte):\n");
+        for previous in previous_instructions {
+            prompt.push_str(&format!("- {}\n", previous));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str(&format!("Instruction: {}", instruction));
+    Ok(prompt)
+}
+
+/// Apply a refinement patch, refusing changes outside the refinement scope
+/// (other existing entities, relations and invariants not in the context).
+///
+/// Returns the patched model and a human-readable list of changes.
+pub fn apply_patch(model: &Value, entity_id: &str, patch: &EntityPatch) -> Result<(Value, Vec<String>)> {
+    let in_scope_relations: HashSet<&str> = list(model, "relations")
+        .iter()
+        .filter(|r| touches(r, entity_id))
+        .map(id_of)
+        .collect();
+    let in_scope_invariants: HashSet<&str> = list(model, "invariants")
+        .iter()
+        .filter(|i| concerns(i, entity_id))
+        .map(id_of)
+        .collect();
+    let exists = |key: &str, id: &str| list(model, key).iter().any(|v| id_of(v) == id);
+
+    for entity in &patch.upsert.entities {
+        let id = id_of(entity);
+        if id.is_empty() {
+            anyhow::bail!("Patch contains an entity without id");
+        }
+        if id != entity_id && exists("entities", id) {
+            anyhow::bail!("Patch modifies entity '{}' outside the refinement scope", id);
+        }
+    }
+    for id in &patch.remove.entities {
+        if id != entity_id {
+            anyhow::bail!("Patch removes entity '{}' outside the refinement scope", id);
+        }
+    }
+    for (key, in_scope, upserts, removals) in [
+        ("relations", &in_scope_relations, &patch.upsert.relations, &patch.remove.relations),
+        ("invariants", &in_scope_invariants, &patch.upsert.invariants, &patch.remove.invariants),
+    ] {
+        let upserted = upserts.iter().map(|v| id_of(v).to_string());
+        for id in upserted.chain(removals.iter().cloned()) {
+            if id.is_empty() {
+                anyhow::bail!("Patch contains {} without id", key);
+            }
+            if exists(key, &id) && !in_scope.contains(id.as_str()) {
+                anyhow::bail!("Patch changes {} '{}' outside the refinement scope", key.trim_end_matches('s'), id);
+            }
+        }
+    }
+
+    let mut patched = model.clone();
+    let mut changes = Vec::new();
+    for (key, label, upserts, removals) in [
+        ("entities", "Entity", &patch.upsert.entities, &patch.remove.entities),
+        ("relations", "Relation", &patch.upsert.relations, &patch.remove.relations),
+        ("invariants", "Invariant", &patch.upsert.invariants, &patch.remove.invariants),
+    ] {
+        if patched.get(key).is_none_or(|v| !v.is_array()) {
+            patched[key] = json!([]);
+        }
+        let items = patched[key].as_array_mut().expect("checked above");
+        for id in removals {
+            let before = items.len();
+            items.retain(|v| id_of(v) != id);
+            if items.len() < before {
+                changes.push(format!("{} '{}' removed", label, id));
+            }
+        }
+        for value in upserts {
+            let id = id_of(value).to_string();
+            match items.iter_mut().find(|v| id_of(v) == id) {
+                Some(existing) if existing != value => {
+                    *existing = value.clone();
+                    changes.push(format!("{} '{}' updated", label, id));
+                }
+                Some(_) => {}
+                None => {
+                    items.push(value.clone());
+                    changes.push(format!("{} '{}' added", label, id));
+                }
+            }
+        }
+    }
+
+    Ok((patched, changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> Value {
+        json!({
+            "entities": [
+                {"id": "Customer", "name": "Customer", "attributes": [
+                    {"name": "id", "type": "uuid", "unique": true},
+                    {"name": "street", "type": "string"},
+                    {"name": "city", "type": "string"}
+                ]},
+                {"id": "Order", "name": "Order", "attributes": [{"name": "id", "type": "uuid", "unique": true}]},
+                {"id": "Product", "name": "Product", "attributes": [{"name": "sku", "type": "string", "unique": true}]}
+            ],
+            "relations": [
+                {"id": "customer-places-order", "name": "places", "from": {"entityId": "Customer"}, "to": {"entityId": "Order"}, "cardinality": {"from": "1", "to": "0..n"}},
+                {"id": "order-contains-product", "name": "contains", "from": {"entityId": "Order"}, "to": {"entityId": "Product"}, "cardinality": {"from": "0..n", "to": "1..n"}}
+            ],
+            "invariants": [
+                {"id": "customer_has_city", "name": "City required", "type": "domain_constraint", "expression": "Customer.city IS NOT NULL"},
+                {"id": "sku_unique", "name": "SKU unique", "type": "uniqueness", "expression": "UNIQUE(Product.sku)"}
+            ]
+        })
+    }
+
+    #[test]
+    fn test_entity_context() {
+        let context = entity_context(&model(), "Customer").unwrap();
+        assert_eq!(context["entity"]["id"], "Customer");
+        assert_eq!(context["relations"].as_array().unwrap().len(), 1);
+        assert_eq!(context["neighbours"], json!([{"id": "Order", "name": "Order", "attributes": ["id"]}]));
+        assert_eq!(context["invariants"][0]["id"], "customer_has_city");
+        assert!(entity_context(&model(), "Invoice").is_err());
+    }
+
+    #[test]
+    fn test_apply_patch_split_value_object() {
+        let patch: EntityPatch = serde_json::from_value(json!({
+            "upsert": {
+                "entities": [
+                    {"id": "Customer", "name": "Customer", "attributes": [{"name": "id", "type": "uuid", "unique": true}]},
+                    {"id": "Address", "name": "Address", "attributes": [{"name": "street", "type": "string"}, {"name": "city", "type": "string"}]}
+                ],
+                "relations": [
+                    {"id": "customer-has-address", "name": "has", "from": {"entityId": "Customer"}, "to": {"entityId": "Address"}, "cardinality": {"from": "1", "to": "1"}}
+                ]
+            },
+            "remove": {"invariants": ["customer_has_city"]}
+        }))
+        .unwrap();
+
+        let (patched, changes) = apply_patch(&model(), "Customer", &patch).unwrap();
+        assert_eq!(patched["entities"].as_array().unwrap().len(), 4);
+        assert_eq!(patched["relations"].as_array().unwrap().len(), 3);
+        assert_eq!(patched["invariants"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            changes,
+            vec![
+                "Entity 'Customer' updated",
+                "Entity 'Address' added",
+                "Relation 'customer-has-address' added",
+                "Invariant 'customer_has_city' removed"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_patch_rejects_out_of_scope_changes() {
+        let touch_neighbour: EntityPatch = serde_json::from_value(json!({
+            "upsert": {"entities": [{"id": "Order", "name": "Order", "attributes": []}]}
+        }))
+        .unwrap();
+        assert!(apply_patch(&model(), "Customer", &touch_neighbour).is_err());
+
+        let remove_other_relation: EntityPatch = serde_json::from_value(json!({
+            "remove": {"relations": ["order-contains-product"]}
+        }))
+        .unwrap();
+        assert!(apply_patch(&model(), "Customer", &remove_other_relation).is_err());
+    }
+}
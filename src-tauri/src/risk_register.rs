@@ -0,0 +1,281 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::interview::InterviewSection;
+use crate::safe_write;
+
+/// System prompt for extracting assumptions and risks from interview answers
+pub const EXTRACTION_SYSTEM_PROMPT: &str = r#"
+Tu analyses les réponses d'une interview Domain-Driven Design pour en extraire les HYPOTHÈSES (ce qui est supposé vrai sans être vérifié, ex: "on suppose que le paiement est synchrone") et les RISQUES (ce qui pourrait mal tourner: dépendance externe, volumétrie, règle floue, contrainte réglementaire...).
+
+Réponds UNIQUEMENT avec un JSON:
+{
+  "entries": [
+    {
+      "kind": "assumption" | "risk",
+      "statement": "formulation courte et autonome",
+      "impact": "low" | "medium" | "high",
+      "likelihood": "low" | "medium" | "high",
+      "source": "titre de la section — question d'origine",
+      "mitigation": "action proposée (optionnel)"
+    }
+  ]
+}
+
+RÈGLES:
+1. N'invente rien: chaque entrée doit s'appuyer sur une réponse
+2. Pour une hypothèse, "likelihood" est la probabilité qu'elle soit FAUSSE
+3. Une entrée par idée, sans doublon
+4. Si rien n'est mentionné, renvoie {"entries": []}
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Assumption,
+    Risk,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Low,
+    Medium,
+    High,
+}
+
+impl Level {
+    fn weight(self) -> u8 {
+        match self {
+            Level::Low => 1,
+            Level::Medium => 2,
+            Level::High => 3,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Level::Low => "Faible",
+            Level::Medium => "Moyen",
+            Level::High => "Élevé",
+        }
+    }
+}
+
+fn default_level() -> Level {
+    Level::Medium
+}
+
+/// One assumption or risk of the register
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterEntry {
+    /// "H<n>" for assumptions, "R<n>" for risks; assigned when merged
+    #[serde(default)]
+    pub id: String,
+    pub kind: EntryKind,
+    pub statement: String,
+    #[serde(default = "default_level")]
+    pub impact: Level,
+    #[serde(default = "default_level")]
+    pub likelihood: Level,
+    /// Interview section/question the entry comes from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mitigation: Option<String>,
+}
+
+impl RegisterEntry {
+    /// Impact × likelihood, from 1 to 9
+    pub fn score(&self) -> u8 {
+        self.impact.weight() * self.likelihood.weight()
+    }
+}
+
+/// Assumptions and risks of a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskRegister {
+    pub entries: Vec<RegisterEntry>,
+}
+
+/// Lowercase alphanumeric words, to spot the same statement worded slightly differently
+fn statement_key(statement: &str) -> String {
+    statement
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl RiskRegister {
+    /// Add newly extracted entries, skipping statements already in the register.
+    /// Existing entries keep their id and any impact/likelihood edited by the user.
+    /// Returns the number of entries added.
+    pub fn merge(&mut self, extracted: Vec<RegisterEntry>) -> usize {
+        let mut added = 0;
+        for mut entry in extracted {
+            let key = statement_key(&entry.statement);
+            if key.is_empty() || self.entries.iter().any(|e| statement_key(&e.statement) == key) {
+                continue;
+            }
+            let prefix = match entry.kind {
+                EntryKind::Assumption => "H",
+                EntryKind::Risk => "R",
+            };
+            let next = self
+                .entries
+                .iter()
+                .filter_map(|e| e.id.strip_prefix(prefix).and_then(|n| n.parse::<u32>().ok()))
+                .max()
+                .unwrap_or(0)
+                + 1;
+            entry.id = format!("{}{}", prefix, next);
+            self.entries.push(entry);
+            added += 1;
+        }
+        added
+    }
+
+    /// Canvas section: assumptions then risks, highest score first
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("## Registre des hypothèses & risques\n\n");
+        if self.entries.is_empty() {
+            markdown.push_str("*Aucune hypothèse ni aucun risque relevé.*\n\n");
+            return markdown;
+        }
+
+        for (kind, title) in [(EntryKind::Assumption, "Hypothèses"), (EntryKind::Risk, "Risques")] {
+            let mut entries: Vec<&RegisterEntry> = self.entries.iter().filter(|e| e.kind == kind).collect();
+            if entries.is_empty() {
+                continue;
+            }
+            entries.sort_by_key(|e| std::cmp::Reverse(e.score()));
+            markdown.push_str(&format!("### {}\n\n", title));
+            markdown.push_str("| Id | Énoncé | Impact | Probabilité | Score | Source | Mitigation |\n");
+            markdown.push_str("| -- | ------ | ------ | ----------- | ----- | ------ | ---------- |\n");
+            for entry in entries {
+                markdown.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} | {} |\n",
+                    entry.id,
+                    entry.statement.replace('|', "\\|"),
+                    entry.impact.label(),
+                    entry.likelihood.label(),
+                    entry.score(),
+                    entry.source.as_deref().unwrap_or("—").replace('|', "\\|"),
+                    entry.mitigation.as_deref().unwrap_or("—").replace('|', "\\|"),
+                ));
+            }
+            markdown.push('\n');
+        }
+        markdown
+    }
+}
+
+/// User prompt listing every answer with its section
+pub fn extraction_prompt(sections: &[InterviewSection]) -> String {
+    let mut prompt = String::new();
+    for section in sections {
+        prompt.push_str(&format!("Section: {}\n\n", section.section_title));
+        for answer in &section.answers {
+            prompt.push_str(&format!("Q: {}\nR: {}\n\n", answer.question, answer.answer));
+        }
+    }
+    prompt
+}
+
+/// Register of a project, stored as `<app data>/risks/<project>.json`
+/// (not next to the interview states, which are listed as projects)
+pub struct RegisterStore {
+    path: PathBuf,
+}
+
+impl RegisterStore {
+    pub fn open(app_data_dir: &Path, project_name: &str) -> Self {
+        let sanitized_name = crate::sanitize_project_name(project_name);
+        Self { path: app_data_dir.join("risks").join(format!("{}.json", sanitized_name)) }
+    }
+
+    /// Stored register, empty if the project has none yet
+    pub fn load(&self) -> Result<RiskRegister> {
+        if !self.path.exists() {
+            return Ok(RiskRegister::default());
+        }
+        let content = fs::read_to_string(&self.path).with_context(|| format!("Failed to read {:?}", self.path))?;
+        serde_json::from_str(&content).context("Invalid risk register")
+    }
+
+    pub fn save(&self, register: &RiskRegister) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+        safe_write::write(&self.path, serde_json::to_string_pretty(register)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: EntryKind, statement: &str, impact: Level, likelihood: Level) -> RegisterEntry {
+        RegisterEntry {
+            id: String::new(),
+            kind,
+            statement: statement.to_string(),
+            impact,
+            likelihood,
+            source: None,
+            mitigation: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_assigns_ids_and_skips_duplicates() {
+        let mut register = RiskRegister::default();
+        let added = register.merge(vec![
+            entry(EntryKind::Assumption, "Le paiement est synchrone", Level::High, Level::Medium),
+            entry(EntryKind::Risk, "Le PSP peut être indisponible", Level::High, Level::Low),
+            entry(EntryKind::Risk, "Volumétrie inconnue en période de soldes", Level::Medium, Level::High),
+        ]);
+        assert_eq!(added, 3);
+        let ids: Vec<&str> = register.entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["H1", "R1", "R2"]);
+
+        // Same statement, different punctuation and case
+        register.entries[0].impact = Level::Low;
+        let added = register.merge(vec![
+            entry(EntryKind::Assumption, "le paiement est synchrone.", Level::High, Level::High),
+            entry(EntryKind::Assumption, "Les stocks sont mis à jour en temps réel", Level::Medium, Level::Medium),
+        ]);
+        assert_eq!(added, 1);
+        assert_eq!(register.entries[0].impact, Level::Low);
+        assert_eq!(register.entries[3].id, "H2");
+    }
+
+    #[test]
+    fn test_markdown_sorted_by_score() {
+        let mut register = RiskRegister::default();
+        register.merge(vec![
+            entry(EntryKind::Risk, "PSP indisponible", Level::High, Level::Low),
+            entry(EntryKind::Risk, "Soldes | pics de charge", Level::High, Level::High),
+        ]);
+        let markdown = register.to_markdown();
+        assert!(markdown.starts_with("## Registre des hypothèses & risques"));
+        assert!(!markdown.contains("### Hypothèses"));
+        let high = markdown.find("| R2 | Soldes \\| pics de charge | Élevé | Élevé | 9 |").unwrap();
+        let low = markdown.find("| R1 | PSP indisponible | Élevé | Faible | 3 |").unwrap();
+        assert!(high < low);
+    }
+
+    #[test]
+    fn test_parse_llm_entries_with_defaults() {
+        let entries: Vec<RegisterEntry> = serde_json::from_str(
+            r#"[{"kind": "assumption", "statement": "Un client a une seule adresse"}]"#,
+        )
+        .unwrap();
+        assert_eq!(entries[0].impact, Level::Medium);
+        assert_eq!(entries[0].score(), 4);
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// File written next to its destination and moved into place only once complete and
+/// flushed to disk: after a crash the destination holds either the old or the new
+/// content, never a truncated one. Dropped without `commit`, the temporary file is removed.
+pub struct AtomicFile {
+    file: Option<File>,
+    temp_path: PathBuf,
+    path: PathBuf,
+}
+
+/// Hidden temporary name in the same directory (a rename across file systems is not atomic)
+fn temp_path_for(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let unique = COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}-{}.tmp", name, std::process::id(), unique))
+}
+
+impl AtomicFile {
+    pub fn create(path: &Path) -> Result<Self> {
+        let temp_path = temp_path_for(path);
+        let file = File::create(&temp_path).with_context(|| format!("Failed to create {:?}", temp_path))?;
+        Ok(Self { file: Some(file), temp_path, path: path.to_path_buf() })
+    }
+
+    /// Flush to disk and replace the destination
+    pub fn commit(mut self) -> Result<()> {
+        let file = self.file.take().expect("file is only taken by commit");
+        file.sync_all().with_context(|| format!("Failed to sync {:?}", self.temp_path))?;
+        drop(file);
+        fs::rename(&self.temp_path, &self.path)
+            .with_context(|| format!("Failed to move {:?} to {:?}", self.temp_path, self.path))?;
+        sync_parent_dir(&self.path);
+        Ok(())
+    }
+
+    fn file(&mut self) -> &mut File {
+        self.file.as_mut().expect("file is only taken by commit")
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file().flush()
+    }
+}
+
+impl Seek for AtomicFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file().seek(pos)
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Persist the rename itself (the directory entry); not possible on Windows, where
+/// `MoveFileEx` is already durable enough for our purposes
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+/// Atomic replacement for `fs::write`
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
+    let mut file = AtomicFile::create(path.as_ref())?;
+    file.write_all(contents.as_ref())
+        .with_context(|| format!("Failed to write {:?}", path.as_ref()))?;
+    file.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_replaces_and_abandoned_file_leaves_no_trace() {
+        let dir = std::env::temp_dir().join(format!("safe_write_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json");
+
+        write(&path, "{\"v\":1}").unwrap();
+        write(&path, "{\"v\":2}").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"v\":2}");
+
+        // Interrupted before commit: destination untouched, temporary file removed
+        {
+            let mut file = AtomicFile::create(&path).unwrap();
+            file.write_all(b"{\"v\":").unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"v\":2}");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
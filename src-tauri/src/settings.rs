@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::audio_session::{AudioProfile, RecordingMode, SystemCapture, UtteranceNaming, VadSettings};
+use crate::coach::CoachSettings;
+use crate::hooks::Hook;
+use crate::power::PowerProfile;
+use crate::safe_write;
+use crate::speech_to_text::{WhisperBackend, DEFAULT_LANGUAGE};
+use crate::storage_gc::AudioRetention;
+use crate::stt_provider::CloudSttSettings;
+use crate::tickets::TicketTrackerSettings;
+
+/// Global shortcut toggling the recording, in the format of tauri-plugin-global-shortcut
+pub const DEFAULT_RECORDING_HOTKEY: &str = "CommandOrControl+Shift+R";
+
+/// Key of the audio profile used while no input device is selected (system default)
+pub const DEFAULT_DEVICE_PROFILE: &str = "default";
+
+/// User preferences kept across launches. Missing fields take their default so
+/// files written by older versions still load.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Spoken language passed to Whisper (ISO 639-1 code)
+    pub transcription_language: String,
+    /// Transcribe non-English interviews directly into English
+    pub translate_to_english: bool,
+    /// Battery trade-offs for long recordings
+    pub power_profile: PowerProfile,
+    /// User scripts run after transcription, model generation and emission
+    pub hooks: Vec<Hook>,
+    /// Utterance segmentation of the next recordings
+    pub vad: VadSettings,
+    /// Push-to-talk, or VAD segmentation with per-utterance transcription
+    pub recording_mode: RecordingMode,
+    /// Live facilitation suggestions during the interview
+    pub coach: CoachSettings,
+    /// Starts/stops the recording while another app is focused (desktop only, None to disable)
+    pub recording_hotkey: Option<String>,
+    /// Keep the raw capture (before gain and AGC) next to each processed utterance
+    pub archive_raw_audio: bool,
+    /// Keep the utterances in memory instead of writing WAV files (sessions not replayable)
+    pub in_memory_audio: bool,
+    /// Age and size limits on the audio of finished sessions (no limit by default)
+    pub audio_retention: AudioRetention,
+    /// File names of the utterances and their JSON sidecars
+    pub utterance_naming: UtteranceNaming,
+    /// Whisper model file chosen by the user (None = bundled model; WHISPER_MODEL_PATH wins)
+    pub whisper_model: Option<String>,
+    /// CPU, or a GPU backend compiled into this build
+    pub whisper_backend: WhisperBackend,
+    /// Remote transcription without local model or on low confidence
+    pub cloud_stt: CloudSttSettings,
+    /// Gain, AGC and enhancement per input device name, loaded when the device is selected
+    pub audio_profiles: BTreeMap<String, AudioProfile>,
+    /// System audio loopback recorded next to the microphone, for remote meetings
+    pub system_capture: SystemCapture,
+    /// Jira or Linear project the use-case tickets are pushed to
+    pub ticket_tracker: TicketTrackerSettings,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            transcription_language: DEFAULT_LANGUAGE.to_string(),
+            translate_to_english: false,
+            power_profile: PowerProfile::default(),
+            hooks: Vec::new(),
+            vad: VadSettings::default(),
+            recording_mode: RecordingMode::default(),
+            coach: CoachSettings::default(),
+            recording_hotkey: Some(DEFAULT_RECORDING_HOTKEY.to_string()),
+            archive_raw_audio: false,
+            in_memory_audio: false,
+            audio_retention: AudioRetention::default(),
+            utterance_naming: UtteranceNaming::default(),
+            whisper_model: None,
+            whisper_backend: WhisperBackend::default(),
+            cloud_stt: CloudSttSettings::default(),
+            audio_profiles: BTreeMap::new(),
+            system_capture: SystemCapture::default(),
+            ticket_tracker: TicketTrackerSettings::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Saved profile of an input device (None = system default), defaults if it was never tuned
+    pub fn audio_profile(&self, device_name: Option<&str>) -> AudioProfile {
+        self.audio_profiles
+            .get(device_name.unwrap_or(DEFAULT_DEVICE_PROFILE))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// `<app data>/settings/settings.json` (kept out of the app data root, where every
+/// JSON file is listed as a saved project)
+fn settings_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("settings").join("settings.json")
+}
+
+/// Load the settings, defaults if none were saved yet
+pub fn load(app_data_dir: &Path) -> Result<AppSettings> {
+    let path = settings_path(app_data_dir);
+    if !path.exists() {
+        return Ok(AppSettings::default());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    serde_json::from_str(&content).context("Invalid settings file")
+}
+
+pub fn save(app_data_dir: &Path, settings: &AppSettings) -> Result<()> {
+    let path = settings_path(app_data_dir);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+    let content = serde_json::to_string_pretty(settings).context("Failed to serialize settings")?;
+    safe_write::write(&path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let app_data = std::env::temp_dir().join(format!("settings_test_{}", std::process::id()));
+        assert_eq!(load(&app_data).unwrap(), AppSettings::default());
+
+        let settings = AppSettings {
+            transcription_language: "en".to_string(),
+            power_profile: PowerProfile::LowPower,
+            recording_mode: RecordingMode::Vad,
+            archive_raw_audio: true,
+            ..Default::default()
+        };
+        save(&app_data, &settings).unwrap();
+        assert_eq!(load(&app_data).unwrap(), settings);
+
+        // Older files without the field fall back to the default
+        fs::write(settings_path(&app_data), "{}").unwrap();
+        assert_eq!(load(&app_data).unwrap().transcription_language, DEFAULT_LANGUAGE);
+
+        // Profiles are keyed by device name, untuned devices get the defaults
+        let speakerphone = AudioProfile { gain: 1.0, enable_agc: false, ..Default::default() };
+        let mut settings = AppSettings::default();
+        settings.audio_profiles.insert("Jabra Speak 510".to_string(), speakerphone.clone());
+        save(&app_data, &settings).unwrap();
+        let loaded = load(&app_data).unwrap();
+        assert_eq!(loaded.audio_profile(Some("Jabra Speak 510")), speakerphone);
+        assert_eq!(loaded.audio_profile(Some("MacBook Pro Microphone")), AudioProfile::default());
+
+        // Partial VAD settings keep the defaults of the missing fields
+        fs::write(settings_path(&app_data), r#"{"vad": {"vad_mode": "very_aggressive"}}"#).unwrap();
+        let vad = load(&app_data).unwrap().vad;
+        assert_eq!(vad.vad_mode, crate::audio_session::VadSensitivity::VeryAggressive);
+        assert_eq!(vad.silence_duration_ms, VadSettings::default().silence_duration_ms);
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
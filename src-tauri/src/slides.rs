@@ -0,0 +1,205 @@
+use anyhow::Result;
+
+/// Mermaid, loaded from a CDN by the exported decks to draw the diagram slides
+const MERMAID_MODULE: &str = "https://cdn.jsdelivr.net/npm/mermaid@10/dist/mermaid.esm.min.mjs";
+const REVEAL_DIST: &str = "https://cdn.jsdelivr.net/npm/reveal.js@5/dist";
+const REVEAL_MARKDOWN_PLUGIN: &str = "https://cdn.jsdelivr.net/npm/reveal.js@5/plugin/markdown/markdown.js";
+/// Title of the slide showing the domain model diagram
+const MODEL_SLIDE_TITLE: &str = "Modèle du domaine";
+
+/// Slide deck formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlideFormat {
+    /// Marp markdown (diagrams need the `--html` option of marp-cli)
+    Marp,
+    /// Standalone reveal.js HTML page
+    Reveal,
+}
+
+impl std::str::FromStr for SlideFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "marp" => Ok(Self::Marp),
+            "reveal" | "revealjs" | "reveal.js" => Ok(Self::Reveal),
+            other => anyhow::bail!("Unknown slide format '{}', expected marp or reveal", other),
+        }
+    }
+}
+
+impl SlideFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Marp => "md",
+            Self::Reveal => "html",
+        }
+    }
+}
+
+/// One slide: a heading and its markdown content
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slide {
+    pub title: String,
+    pub body: String,
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Diagram drawn by mermaid once the deck is opened
+fn diagram(code: &str) -> String {
+    format!("<pre class=\"mermaid\">\n{}\n</pre>", html_escape(code.trim()))
+}
+
+/// Body of a section without its ```mermaid blocks, and those blocks
+fn split_diagrams(content: &str) -> (String, Vec<String>) {
+    let mut body = Vec::new();
+    let mut diagrams = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    for line in content.lines() {
+        match current.as_mut() {
+            Some(code) if line.trim_start().starts_with("```") => {
+                diagrams.push(code.join("\n"));
+                current = None;
+            }
+            Some(code) => code.push(line),
+            None if line.trim_start().starts_with("```mermaid") => current = Some(Vec::new()),
+            None => body.push(line),
+        }
+    }
+    // Unclosed block: kept as it was
+    if let Some(code) = current {
+        body.push("```mermaid");
+        body.extend(code);
+    }
+    (body.join("\n").trim().to_string(), diagrams)
+}
+
+/// Slide of a section, then one slide per mermaid block it contains
+fn push_section(slides: &mut Vec<Slide>, title: Option<String>, content: &str) {
+    let (body, diagrams) = split_diagrams(content);
+    let title = title.unwrap_or_default();
+    if !body.is_empty() || (diagrams.is_empty() && !title.is_empty()) {
+        slides.push(Slide { title: title.clone(), body });
+    }
+    let count = diagrams.len();
+    slides.extend(diagrams.into_iter().enumerate().map(|(index, code)| Slide {
+        title: if count > 1 { format!("{} — diagramme {}", title, index + 1) } else { format!("{} — diagramme", title) },
+        body: diagram(&code),
+    }));
+}
+
+/// One slide per `## ` section of the canvas (the `# ` title and its intro open the
+/// deck), each mermaid block of a section on its own slide after it, and the domain
+/// model diagram last
+pub fn canvas_slides(canvas: &str, model_diagram: Option<&str>) -> Vec<Slide> {
+    let mut slides = Vec::new();
+    let mut title: Option<String> = None;
+    let mut content = String::new();
+    for line in canvas.lines() {
+        let heading = line.strip_prefix("## ").or_else(|| line.strip_prefix("# "));
+        if let Some(heading) = heading {
+            push_section(&mut slides, title.take(), &content);
+            content.clear();
+            title = Some(heading.trim().to_string());
+        } else if line.trim() != "---" {
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+    push_section(&mut slides, title, &content);
+
+    if let Some(code) = model_diagram.map(str::trim).filter(|code| !code.is_empty()) {
+        slides.push(Slide { title: MODEL_SLIDE_TITLE.to_string(), body: diagram(code) });
+    }
+    slides
+}
+
+fn slide_markdown(slide: &Slide, level: &str) -> String {
+    match (slide.title.is_empty(), slide.body.is_empty()) {
+        (true, _) => slide.body.clone(),
+        (false, true) => format!("{} {}", level, slide.title),
+        (false, false) => format!("{} {}\n\n{}", level, slide.title, slide.body),
+    }
+}
+
+/// The deck: Marp markdown, or a reveal.js page loading reveal and mermaid from a CDN
+pub fn render(format: SlideFormat, title: &str, slides: &[Slide]) -> String {
+    let has_diagrams = slides.iter().any(|slide| slide.body.contains("<pre class=\"mermaid\">"));
+    // The first slide is the title slide
+    let level = |index: usize| if index == 0 { "#" } else { "##" };
+    match format {
+        SlideFormat::Marp => {
+            let mut out = format!("---\nmarp: true\npaginate: true\ntitle: \"{}\"\n---\n\n", title.replace('"', "'"));
+            let pages: Vec<String> = slides.iter().enumerate().map(|(i, slide)| slide_markdown(slide, level(i))).collect();
+            out.push_str(&pages.join("\n\n---\n\n"));
+            out.push('\n');
+            if has_diagrams {
+                out.push_str(&format!(
+                    "\n<script type=\"module\">import mermaid from '{}'; mermaid.initialize({{ startOnLoad: true }});</script>\n",
+                    MERMAID_MODULE
+                ));
+            }
+            out
+        }
+        SlideFormat::Reveal => {
+            let mut out = format!(
+                "<!doctype html>\n<html lang=\"fr\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+                 <link rel=\"stylesheet\" href=\"{dist}/reveal.css\">\n<link rel=\"stylesheet\" href=\"{dist}/theme/white.css\">\n\
+                 </head>\n<body>\n<div class=\"reveal\">\n<div class=\"slides\">\n",
+                html_escape(title),
+                dist = REVEAL_DIST
+            );
+            // The textarea content is read as text: escaped once more so that the
+            // markdown plugin gets it back unchanged
+            for (index, slide) in slides.iter().enumerate() {
+                out.push_str(&format!(
+                    "<section data-markdown><textarea data-template>\n{}\n</textarea></section>\n",
+                    html_escape(&slide_markdown(slide, level(index)))
+                ));
+            }
+            out.push_str(&format!(
+                "</div>\n</div>\n<script src=\"{}/reveal.js\"></script>\n<script src=\"{}\"></script>\n\
+                 <script type=\"module\">\nimport mermaid from '{}';\nmermaid.initialize({{ startOnLoad: false }});\n\
+                 Reveal.initialize({{ hash: true, plugins: [RevealMarkdown] }}).then(() => mermaid.run());\n</script>\n</body>\n</html>\n",
+                REVEAL_DIST, REVEAL_MARKDOWN_PLUGIN, MERMAID_MODULE
+            ));
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canvas_slides() {
+        let canvas = "# Canvas — Rich Domain Model (DDD)\n\n> Objectif : cadrer un domaine.\n\n---\n\n\
+            ## Contexte & Vision\n\n* **Problème à résoudre :** commandes perdues\n\n\
+            ## Domain Events & Règles\n\n| Événement | Quand |\n| --- | --- |\n| CommandeValidée | validation |\n\n\
+            ```mermaid\nsequenceDiagram\nClient->>Commande: valider\n```\n";
+        let slides = canvas_slides(canvas, Some("classDiagram\nCommande <|-- CommandeExpress"));
+        let titles: Vec<&str> = slides.iter().map(|slide| slide.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Canvas — Rich Domain Model (DDD)", "Contexte & Vision", "Domain Events & Règles", "Domain Events & Règles — diagramme", MODEL_SLIDE_TITLE]
+        );
+        assert!(!slides[2].body.contains("mermaid"));
+        assert_eq!(slides[3].body, "<pre class=\"mermaid\">\nsequenceDiagram\nClient-&gt;&gt;Commande: valider\n</pre>");
+
+        let marp = render(SlideFormat::Marp, "Boutique", &slides);
+        assert!(marp.starts_with("---\nmarp: true\n"));
+        assert_eq!(marp.matches("\n---\n").count(), 1 + slides.len() - 1);
+        assert!(marp.contains("# Canvas — Rich Domain Model (DDD)\n\n> Objectif"));
+        assert!(marp.contains("import mermaid"));
+
+        // Escaped twice in the textarea, once after the markdown plugin reads it
+        let reveal = render(SlideFormat::Reveal, "Boutique", &slides);
+        assert_eq!(reveal.matches("<section data-markdown>").count(), slides.len());
+        assert!(reveal.contains("Commande &amp;lt;|-- CommandeExpress"));
+        assert_eq!("revealjs".parse::<SlideFormat>().unwrap().extension(), "html");
+    }
+}
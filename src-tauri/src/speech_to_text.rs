@@ -1,61 +1,713 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, Weak};
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
+use crate::stt_provider::CloudStt;
+
+/// Spoken language used when none was chosen
+pub const DEFAULT_LANGUAGE: &str = "fr";
+
+/// Let Whisper detect the spoken language of each transcription
+pub const AUTO_LANGUAGE: &str = "auto";
+
+/// Validate a Whisper language code ("fr", "EN " -> "en", or "auto")
+pub fn normalize_language(code: &str) -> Result<String> {
+    let code = code.trim().to_lowercase();
+    if code == AUTO_LANGUAGE {
+        return Ok(code);
+    }
+    if !(2..=3).contains(&code.len()) || !code.chars().all(|c| c.is_ascii_lowercase()) {
+        anyhow::bail!("Invalid language code '{}', expected an ISO 639-1 code such as 'fr' or 'en'", code);
+    }
+    Ok(code)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionResult {
     pub text: String,
     pub language: Option<String>,
     pub duration_ms: u64,
+    /// Timed segments, to align the text with the recording's playback position
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    /// Mean token probability (0-1), when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// Remote provider that produced the transcription (None = local Whisper)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
 }
 
-pub struct SpeechToText {
-    context: Arc<Mutex<Option<WhisperContext>>>,
+/// Segment of a transcription, times relative to the start of the audio file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+    /// Spoken language (chosen, or detected for this transcription with "auto")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Filled when word timestamps are enabled (`WhisperSettings::word_timestamps`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<WordTimestamp>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordTimestamp {
+    pub word: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Mean token probability of the word
+    pub probability: f32,
+}
+
+/// Token as decoded by Whisper: text, start and end in centiseconds, probability
+struct TimedToken {
+    text: String,
+    t0: i64,
+    t1: i64,
+    p: f32,
+}
+
+/// `[_BEG_]`, `<|fr|>`... carry no text
+fn is_special(token: &str) -> bool {
+    token.starts_with("[_") || token.starts_with("<|")
+}
+
+/// Merge Whisper tokens into words: a token starting with a space opens a new word,
+/// special tokens (`[_BEG_]`, `<|fr|>`...) are dropped
+fn group_words(tokens: &[TimedToken]) -> Vec<WordTimestamp> {
+    let mut words: Vec<(WordTimestamp, usize)> = Vec::new();
+    for token in tokens {
+        if is_special(&token.text) || token.text.trim().is_empty() {
+            continue;
+        }
+        let continues_word = !token.text.starts_with(' ') && !words.is_empty();
+        if continues_word {
+            let (word, count) = words.last_mut().unwrap();
+            word.word.push_str(&token.text);
+            word.end_ms = token.t1 * 10;
+            word.probability += token.p;
+            *count += 1;
+        } else {
+            words.push((
+                WordTimestamp {
+                    word: token.text.trim_start().to_string(),
+                    start_ms: token.t0 * 10,
+                    end_ms: token.t1 * 10,
+                    probability: token.p,
+                },
+                1,
+            ));
+        }
+    }
+    words
+        .into_iter()
+        .map(|(mut word, count)| {
+            word.probability /= count as f32;
+            word
+        })
+        .collect()
+}
+
+/// Segment decoded while Whisper is still running on the rest of the audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartialSegment {
+    pub index: i32,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+}
+
+/// Whisper runtime settings
+#[derive(Debug, Clone)]
+pub struct WhisperSettings {
+    /// Inference threads (None = whisper.cpp default)
+    pub n_threads: Option<i32>,
+    pub use_gpu: bool,
+    /// Flash attention, smaller KV cache
+    pub flash_attn: bool,
+    /// Don't feed previous segments back as prompt
+    pub no_context: bool,
+    /// Per-word start/end times in the segments (slightly slower)
+    pub word_timestamps: bool,
+}
+
+impl Default for WhisperSettings {
+    fn default() -> Self {
+        Self {
+            n_threads: None,
+            use_gpu: true,
+            flash_attn: false,
+            no_context: false,
+            word_timestamps: false,
+        }
+    }
+}
+
+impl WhisperSettings {
+    /// Settings for phones: two threads (thermal throttling), flash attention and
+    /// no carried-over context to keep the working set small
+    pub fn low_memory() -> Self {
+        Self {
+            n_threads: Some(2),
+            use_gpu: true,
+            flash_attn: true,
+            no_context: true,
+            word_timestamps: false,
+        }
+    }
+}
+
+/// Per-call overrides of a transcription, for callers sharing one transcriber
+/// (unset = the transcriber's own language and settings)
+#[derive(Debug, Clone, Default)]
+pub struct TranscribeOptions {
+    pub language: Option<String>,
+    pub word_timestamps: Option<bool>,
+    /// Output English whatever the spoken language
+    pub translate: Option<bool>,
+}
+
+/// Compute backend of whisper.cpp. GPU backends must be compiled in (Metal on macOS,
+/// `cuda`/`vulkan` features elsewhere); `Auto` takes the first one available.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WhisperBackend {
+    #[default]
+    Auto,
+    Cpu,
+    Metal,
+    Cuda,
+    Vulkan,
+}
+
+impl std::str::FromStr for WhisperBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "cpu" => Ok(Self::Cpu),
+            "metal" => Ok(Self::Metal),
+            "cuda" => Ok(Self::Cuda),
+            "vulkan" => Ok(Self::Vulkan),
+            other => anyhow::bail!("Unknown Whisper backend '{}', expected auto, cpu, metal, cuda or vulkan", other),
+        }
+    }
+}
+
+impl WhisperBackend {
+    /// Backend used for this preference given the compiled GPU backends: a GPU backend
+    /// that is not compiled in falls back to the CPU
+    pub fn resolve(self, compiled: &[WhisperBackend]) -> WhisperBackend {
+        match self {
+            Self::Cpu => Self::Cpu,
+            Self::Auto => compiled.first().copied().unwrap_or(Self::Cpu),
+            gpu if compiled.contains(&gpu) => gpu,
+            _ => Self::Cpu,
+        }
+    }
+}
+
+/// GPU backends compiled into this build
+pub fn compiled_gpu_backends() -> Vec<WhisperBackend> {
+    let mut backends = Vec::new();
+    if cfg!(any(feature = "metal", target_os = "macos")) {
+        backends.push(WhisperBackend::Metal);
+    }
+    if cfg!(feature = "cuda") {
+        backends.push(WhisperBackend::Cuda);
+    }
+    if cfg!(feature = "vulkan") {
+        backends.push(WhisperBackend::Vulkan);
+    }
+    backends
+}
+
+/// Quantization of a ggml model from its file name ("q5_0", "q8_0"), None for f16 models.
+/// whisper.cpp loads them like any other model, with less memory and faster CPU inference.
+pub fn model_quantization(model_path: &Path) -> Option<String> {
+    let name = model_path.file_stem()?.to_string_lossy().to_lowercase();
+    name.rsplit('-').next().filter(|part| part.starts_with('q') && part[1..].starts_with(|c: char| c.is_ascii_digit())).map(str::to_string)
+}
+
+/// Backend in use, reported by `get_whisper_backend`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackendReport {
+    pub preference: WhisperBackend,
+    pub active: WhisperBackend,
+    pub compiled: Vec<WhisperBackend>,
+    pub model: String,
+    pub quantization: Option<String>,
+    /// Whether the model is already in memory (it loads on the first transcription)
+    pub loaded: bool,
+    /// Transcriptions run at once (1 on the GPU)
+    pub concurrency: usize,
+    /// Batches of short utterances sent to the GPU
+    pub scheduler: crate::transcription_scheduler::SchedulerMetrics,
+}
+
+/// A loaded model is shared by every transcriber using the same file and GPU options
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ContextKey {
     model_path: PathBuf,
+    use_gpu: bool,
+    flash_attn: bool,
+}
+
+struct SharedContext {
+    context: Weak<WhisperContext>,
+    load_ms: u64,
+    reuse_count: usize,
+}
+
+/// Loaded contexts, held weakly: the model is freed once no transcriber uses it
+fn shared_contexts() -> &'static Mutex<HashMap<ContextKey, SharedContext>> {
+    static CONTEXTS: OnceLock<Mutex<HashMap<ContextKey, SharedContext>>> = OnceLock::new();
+    CONTEXTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// A Whisper model currently in memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadedModel {
+    pub model_path: String,
+    pub file_size_bytes: u64,
+    pub use_gpu: bool,
+    pub load_ms: u64,
+    /// Times the loaded context was reused instead of reading the file again
+    pub reuse_count: usize,
+    /// Transcribers currently holding the context
+    pub users: usize,
+}
+
+/// Models currently loaded (see `SpeechToText::ensure_model_loaded`)
+pub fn loaded_models() -> Vec<LoadedModel> {
+    let mut contexts = shared_contexts().lock().unwrap();
+    contexts.retain(|_, shared| shared.context.strong_count() > 0);
+    contexts
+        .iter()
+        .map(|(key, shared)| LoadedModel {
+            model_path: key.model_path.display().to_string(),
+            file_size_bytes: std::fs::metadata(&key.model_path).map(|m| m.len()).unwrap_or(0),
+            use_gpu: key.use_gpu,
+            load_ms: shared.load_ms,
+            reuse_count: shared.reuse_count,
+            users: shared.context.strong_count(),
+        })
+        .collect()
+}
+
+/// Upper bound of concurrent transcriptions on one transcriber, whatever the machine
+const MAX_CONCURRENT_TRANSCRIPTIONS: usize = 4;
+/// Threads whisper.cpp uses per transcription when `n_threads` is not set
+const WHISPER_DEFAULT_THREADS: usize = 4;
+
+/// Concurrent transcriptions a machine sustains: one per group of `threads_per_state`
+/// cores, as many as the spare memory holds states of `state_bytes`, at least one
+pub fn pool_size(cores: usize, threads_per_state: usize, spare_memory: Option<u64>, state_bytes: u64) -> usize {
+    let by_cpu = cores / threads_per_state.max(1);
+    let by_memory = spare_memory.map(|bytes| (bytes / state_bytes.max(1)) as usize).unwrap_or(by_cpu);
+    by_cpu.min(by_memory).clamp(1, MAX_CONCURRENT_TRANSCRIPTIONS)
+}
+
+/// Pool size for `model_path` on this machine (memory beyond the weights, which are
+/// loaded once and shared). On the GPU, one state at a time: concurrent states would
+/// compete for VRAM, the scheduler batches short utterances instead.
+fn pool_size_for(model_path: &Path, settings: &WhisperSettings) -> usize {
+    if settings.use_gpu && !compiled_gpu_backends().is_empty() {
+        return 1;
+    }
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let threads = settings.n_threads.map(|n| n.max(1) as usize).unwrap_or(WHISPER_DEFAULT_THREADS.min(cores));
+    let file_name = model_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let file_size = std::fs::metadata(model_path).map(|m| m.len()).unwrap_or(0);
+    let spare_memory = crate::memory_diagnostics::memory_budget().map(|budget| budget.saturating_sub(file_size));
+    pool_size(cores, threads, spare_memory, crate::memory_diagnostics::state_overhead_bytes(&file_name, file_size))
+}
+
+/// Bounds the transcriptions running at once on a shared context: each has its own
+/// whisper state (cheap next to the weights) but takes CPU threads and buffers
+pub struct TranscriptionPool {
+    /// (running, limit)
+    slots: Mutex<(usize, usize)>,
+    freed: Condvar,
+}
+
+/// Slot of a running transcription, released on drop
+pub struct PoolPermit<'a>(&'a TranscriptionPool);
+
+impl TranscriptionPool {
+    pub fn new(limit: usize) -> Self {
+        Self { slots: Mutex::new((0, limit.max(1))), freed: Condvar::new() }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.slots.lock().unwrap().1
+    }
+
+    pub fn set_limit(&self, limit: usize) {
+        self.slots.lock().unwrap().1 = limit.max(1);
+        self.freed.notify_all();
+    }
+
+    /// Wait for a free slot
+    pub fn acquire(&self) -> PoolPermit<'_> {
+        let mut slots = self.slots.lock().unwrap();
+        while slots.0 >= slots.1 {
+            slots = self.freed.wait(slots).unwrap();
+        }
+        slots.0 += 1;
+        PoolPermit(self)
+    }
+}
+
+impl Drop for PoolPermit<'_> {
+    fn drop(&mut self) {
+        self.0.slots.lock().unwrap().0 -= 1;
+        self.0.freed.notify_one();
+    }
+}
+
+/// Cancellation shared by the transcriptions started before `SpeechToText::cancel`
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Error of a transcription stopped by `SpeechToText::cancel` (check with `Error::is`)
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transcription cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+pub struct SpeechToText {
+    context: Arc<Mutex<Option<Arc<WhisperContext>>>>,
+    /// Changeable between transcriptions, see `set_model_path`
+    model_path: Mutex<PathBuf>,
+    /// `use_gpu` changes with `set_use_gpu`
+    settings: Mutex<WhisperSettings>,
+    /// Spoken language, changeable between transcriptions
+    language: Mutex<String>,
+    /// Translate to English instead of transcribing in the spoken language
+    translate: AtomicBool,
+    /// Token of the transcriptions in progress, replaced on cancel
+    cancellation: Mutex<CancellationToken>,
+    /// Transcriptions allowed to run at once, sized for the model
+    pool: TranscriptionPool,
+    /// Remote transcription without local model or on low confidence
+    cloud: Mutex<Option<Arc<CloudStt>>>,
 }
 
 impl SpeechToText {
     /// Create a new SpeechToText instance
     pub fn new(model_path: PathBuf) -> Self {
+        Self::with_settings(model_path, WhisperSettings::default())
+    }
+
+    /// Create a SpeechToText instance with specific Whisper settings
+    pub fn with_settings(model_path: PathBuf, settings: WhisperSettings) -> Self {
+        let pool = TranscriptionPool::new(pool_size_for(&model_path, &settings));
+        log::info!("Up to {} concurrent transcriptions", pool.limit());
         Self {
             context: Arc::new(Mutex::new(None)),
-            model_path,
+            pool,
+            model_path: Mutex::new(model_path),
+            settings: Mutex::new(settings),
+            language: Mutex::new(DEFAULT_LANGUAGE.to_string()),
+            translate: AtomicBool::new(false),
+            cancellation: Mutex::new(CancellationToken::default()),
+            cloud: Mutex::new(None),
         }
     }
 
-    /// Initialize the Whisper model (lazy loading)
-    fn ensure_model_loaded(&self) -> Result<()> {
+    /// Token of the transcriptions started from now on until the next `cancel`; a
+    /// caller transcribing several files checks it between files
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.lock().unwrap().clone()
+    }
+
+    /// Abort the transcriptions in progress (Whisper stops at its next abort check);
+    /// later transcriptions are not affected
+    pub fn cancel(&self) {
+        let token = std::mem::take(&mut *self.cancellation.lock().unwrap());
+        token.cancel();
+        log::info!("Transcription cancellation requested");
+    }
+
+    /// Language of the next transcriptions (see `normalize_language`)
+    pub fn set_language(&self, language: &str) {
+        log::info!("Transcription language set to: {}", language);
+        *self.language.lock().unwrap() = language.to_string();
+    }
+
+    pub fn language(&self) -> String {
+        self.language.lock().unwrap().clone()
+    }
+
+    /// Have Whisper translate the next transcriptions to English (the spoken language
+    /// is still the one set or detected)
+    pub fn set_translate(&self, translate: bool) {
+        log::info!("Translation to English {}", if translate { "enabled" } else { "disabled" });
+        self.translate.store(translate, Ordering::SeqCst);
+    }
+
+    pub fn translate(&self) -> bool {
+        self.translate.load(Ordering::SeqCst)
+    }
+
+    /// Remote provider to fall back to, None for local transcriptions only
+    pub fn set_cloud_fallback(&self, cloud: Option<Arc<CloudStt>>) {
+        if let Some(cloud) = &cloud {
+            log::info!("Cloud transcription fallback: {}", cloud.name());
+        }
+        *self.cloud.lock().unwrap() = cloud;
+    }
+
+    pub fn cloud_fallback(&self) -> Option<Arc<CloudStt>> {
+        self.cloud.lock().unwrap().clone()
+    }
+
+    pub fn model_path(&self) -> PathBuf {
+        self.model_path.lock().unwrap().clone()
+    }
+
+    /// Switch to another model file. The current context is released and the new model
+    /// is loaded on the next transcription; transcriptions in progress finish with the old one.
+    pub fn set_model_path(&self, model_path: PathBuf) {
         let mut context = self.context.lock().unwrap();
-        
-        if context.is_none() {
-            log::info!("Loading Whisper model from {:?}", self.model_path);
-            let ctx = WhisperContext::new_with_params(
-                &self.model_path.to_string_lossy(),
-                WhisperContextParameters::default(),
-            )
-            .context("Failed to load Whisper model")?;
-            *context = Some(ctx);
-            log::info!("Whisper model loaded successfully");
+        let mut current = self.model_path.lock().unwrap();
+        if *current != model_path {
+            log::info!("Whisper model set to {:?}", model_path);
+            self.pool.set_limit(pool_size_for(&model_path, &self.settings.lock().unwrap()));
+            *current = model_path;
+            *context = None;
         }
-        
-        Ok(())
     }
 
-    /// Transcribe audio from a WAV file
+    /// Run the next transcriptions on the GPU or the CPU; like `set_model_path`, the
+    /// model is reloaded on next use
+    pub fn set_use_gpu(&self, use_gpu: bool) {
+        let mut context = self.context.lock().unwrap();
+        let mut settings = self.settings.lock().unwrap();
+        if settings.use_gpu != use_gpu {
+            log::info!("Whisper GPU {}", if use_gpu { "enabled" } else { "disabled" });
+            settings.use_gpu = use_gpu;
+            self.pool.set_limit(pool_size_for(&self.model_path(), &settings));
+            *context = None;
+        }
+    }
+
+    pub fn uses_gpu(&self) -> bool {
+        self.settings.lock().unwrap().use_gpu
+    }
+
+    /// Whether inference actually runs on a GPU (GPU wanted and a GPU backend compiled in)
+    pub fn gpu_active(&self) -> bool {
+        self.uses_gpu() && !compiled_gpu_backends().is_empty()
+    }
+
+    /// Whether a model is in memory for this transcriber
+    pub fn is_loaded(&self) -> bool {
+        self.context.lock().unwrap().is_some()
+    }
+
+    /// Transcriptions this transcriber runs at once
+    pub fn concurrency(&self) -> usize {
+        self.pool.limit()
+    }
+
+    /// Run `work` on each item with up to `concurrency()` items in flight, and hand the
+    /// results to `done` in the order of `items` as soon as the ones before are done
+    /// (events and transcripts stay in order while later items are still running)
+    pub fn transcribe_batch<T, R, W, D>(&self, items: &[T], work: W, mut done: D)
+    where
+        T: Sync,
+        R: Send,
+        W: Fn(&T) -> R + Sync,
+        D: FnMut(&T, R),
+    {
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::scope(|scope| {
+            for _ in 0..self.concurrency().min(items.len()) {
+                let sender = sender.clone();
+                let (next, work) = (&next, &work);
+                scope.spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(index) else { break };
+                    if sender.send((index, work(item))).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+
+            let mut finished = HashMap::new();
+            let mut delivered = 0;
+            for (index, result) in receiver {
+                finished.insert(index, result);
+                while let Some(result) = finished.remove(&delivered) {
+                    done(&items[delivered], result);
+                    delivered += 1;
+                }
+            }
+        });
+    }
+
+    /// Model file name, recorded with transcripts
+    pub fn model_name(&self) -> String {
+        self.model_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Initialize the Whisper model (lazy loading). A model already loaded by another
+    /// transcriber with the same options is reused; each transcription gets its own state.
+    /// whisper.cpp has no memory-mapped loading, so sharing is what avoids a second copy
+    /// of the weights.
+    fn ensure_model_loaded(&self) -> Result<Arc<WhisperContext>> {
+        let mut context = self.context.lock().unwrap();
+        if let Some(ctx) = context.as_ref() {
+            return Ok(Arc::clone(ctx));
+        }
+
+        let model_path = self.model_path();
+        let settings = self.settings.lock().unwrap().clone();
+        let key = ContextKey {
+            model_path: model_path.clone(),
+            use_gpu: settings.use_gpu,
+            flash_attn: settings.flash_attn,
+        };
+        let mut contexts = shared_contexts().lock().unwrap();
+        if let Some(shared) = contexts.get_mut(&key) {
+            if let Some(ctx) = shared.context.upgrade() {
+                shared.reuse_count += 1;
+                log::info!("Reusing loaded Whisper model {:?}", model_path);
+                *context = Some(Arc::clone(&ctx));
+                return Ok(ctx);
+            }
+        }
+
+        log::info!("Loading Whisper model from {:?} ({:?})", model_path, settings);
+        let started = std::time::Instant::now();
+        let mut ctx_params = WhisperContextParameters::default();
+        ctx_params
+            .use_gpu(settings.use_gpu)
+            .flash_attn(settings.flash_attn);
+        let ctx = Arc::new(
+            WhisperContext::new_with_params(&model_path.to_string_lossy(), ctx_params)
+                .context("Failed to load Whisper model")?,
+        );
+        let load_ms = started.elapsed().as_millis() as u64;
+        log::info!("Whisper model loaded successfully ({}ms)", load_ms);
+        contexts.insert(key, SharedContext { context: Arc::downgrade(&ctx), load_ms, reuse_count: 0 });
+        *context = Some(Arc::clone(&ctx));
+        Ok(ctx)
+    }
+
+    /// Transcribe audio from a file (WAV, MP3, M4A, FLAC...)
     pub fn transcribe_file(&self, audio_path: &PathBuf) -> Result<TranscriptionResult> {
-        self.ensure_model_loaded()?;
-        
+        self.transcribe_file_with_segments(audio_path, |_| {})
+    }
+
+    /// Transcribe audio from a file, calling `on_segment` for each segment as soon as
+    /// Whisper decodes it (long recordings show text while inference runs)
+    pub fn transcribe_file_with_segments<F>(&self, audio_path: &Path, on_segment: F) -> Result<TranscriptionResult>
+    where
+        F: FnMut(PartialSegment) + 'static,
+    {
+        self.transcribe_file_with_options(audio_path, &TranscribeOptions::default(), on_segment)
+    }
+
+    /// Like `transcribe_file_with_segments`, with a language or word timestamps for this
+    /// call only: the model stays loaded and shared, the transcriber's settings unchanged
+    pub fn transcribe_file_with_options<F>(&self, audio_path: &Path, options: &TranscribeOptions, on_segment: F) -> Result<TranscriptionResult>
+    where
+        F: FnMut(PartialSegment) + 'static,
+    {
         log::info!("Transcribing audio file: {}", audio_path.display());
-        let start = std::time::Instant::now();
-        
-        // Read and convert audio
-        let audio_data = self.read_wav_file(audio_path)?;
+        // Read and convert audio (WAV, or a compressed recording decoded to 16kHz mono)
+        let audio_data = crate::audio_decode::read_audio_16k(audio_path)?;
         log::info!("Audio loaded: {} samples", audio_data.len());
+        self.transcribe_samples_with_options(&audio_data, options, on_segment)
+    }
+
+    /// Transcribe 16kHz mono samples (see `read_wav_16k`). With a cloud fallback, the
+    /// samples are sent to it when no local model is installed, or when Whisper's
+    /// confidence is under the fallback's threshold (the local result is kept if the
+    /// request fails).
+    pub fn transcribe_samples_with_options<F>(&self, audio_data: &[f32], options: &TranscribeOptions, mut on_segment: F) -> Result<TranscriptionResult>
+    where
+        F: FnMut(PartialSegment) + 'static,
+    {
+        let Some(cloud) = self.cloud_fallback() else {
+            return self.transcribe_local(audio_data, options, on_segment);
+        };
+        let language = options.language.clone().unwrap_or_else(|| self.language());
+        let translate = options.translate.unwrap_or_else(|| self.translate());
+
+        if !self.model_path().exists() {
+            log::info!("No Whisper model at {:?}, transcribing with {}", self.model_path(), cloud.name());
+            let result = cloud.transcribe(audio_data, &language, translate)?;
+            for (index, segment) in result.segments.iter().enumerate() {
+                on_segment(PartialSegment {
+                    index: index as i32,
+                    start_ms: segment.start_ms,
+                    end_ms: segment.end_ms,
+                    text: segment.text.clone(),
+                });
+            }
+            return Ok(result);
+        }
+
+        let local = self.transcribe_local(audio_data, options, on_segment)?;
+        if !cloud.needs_fallback(&local) {
+            return Ok(local);
+        }
+        log::warn!("Low Whisper confidence ({:.2}), transcribing again with {}", local.confidence.unwrap_or_default(), cloud.name());
+        match cloud.transcribe(audio_data, &language, translate) {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                log::warn!("Cloud transcription failed, keeping the local one: {}", e);
+                Ok(local)
+            }
+        }
+    }
+
+    fn transcribe_local<F>(&self, audio_data: &[f32], options: &TranscribeOptions, mut on_segment: F) -> Result<TranscriptionResult>
+    where
+        F: FnMut(PartialSegment) + 'static,
+    {
+        let cancellation = self.cancellation_token();
+        let ctx = self.ensure_model_loaded()?;
+        let start = std::time::Instant::now();
         
-        let context = self.context.lock().unwrap();
-        let ctx = context.as_ref().unwrap();
+        let language = options.language.clone().unwrap_or_else(|| self.language());
+        let translate = options.translate.unwrap_or_else(|| self.translate());
+        let mut settings = self.settings.lock().unwrap().clone();
+        if let Some(word_timestamps) = options.word_timestamps {
+            settings.word_timestamps = word_timestamps;
+        }
         
         // Create transcription parameters
         let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
@@ -63,31 +715,87 @@ impl SpeechToText {
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
-        params.set_language(Some("fr")); // Français
-        params.set_translate(false);
+        // None = whisper.cpp detects the language on the first 30s of the file
+        let auto_detect = language == AUTO_LANGUAGE;
+        params.set_language(if auto_detect { None } else { Some(&language) });
+        // English-only models ignore it
+        params.set_translate(translate && language != "en");
+        params.set_no_context(settings.no_context);
+        params.set_token_timestamps(settings.word_timestamps);
+        if let Some(n_threads) = settings.n_threads {
+            params.set_n_threads(n_threads);
+        }
+        // Whisper timestamps are in centiseconds
+        params.set_segment_callback_safe(move |data: whisper_rs::SegmentCallbackData| {
+            on_segment(PartialSegment {
+                index: data.segment,
+                start_ms: data.start_timestamp * 10,
+                end_ms: data.end_timestamp * 10,
+                text: data.text.trim().to_string(),
+            });
+        });
+        let abort = cancellation.clone();
+        params.set_abort_callback_safe(move || abort.is_cancelled());
         
-        // Create a new state for this transcription
+        // Create a new state for this transcription, once a slot of the pool is free
+        let _permit = self.pool.acquire();
         let mut state = ctx.create_state().context("Failed to create Whisper state")?;
         
         log::info!("Running Whisper inference...");
         // Run the transcription
-        state
-            .full(params, &audio_data)
-            .context("Failed to run Whisper transcription")?;
+        let result = state.full(params, audio_data);
+        if cancellation.is_cancelled() {
+            log::info!("Transcription cancelled");
+            return Err(Cancelled.into());
+        }
+        result.context("Failed to run Whisper transcription")?;
         
         // Extract the transcription text
         let num_segments = state.full_n_segments();
         log::info!("Transcription complete: {} segments", num_segments);
         
+        let detected_language = if auto_detect {
+            // A negative id means no language was detected
+            let detected = Some(state.full_lang_id_from_state())
+                .filter(|id| *id >= 0)
+                .and_then(whisper_rs::get_lang_str)
+                .map(str::to_string);
+            log::info!("Detected language: {:?}", detected);
+            detected
+        } else {
+            Some(language.clone())
+        };
+        
         let mut full_text = String::new();
+        let mut segments = Vec::new();
+        let mut probabilities = Vec::new();
         for i in 0..num_segments {
-            if let Some(segment) = state.get_segment(i as i32) {
+            if let Some(segment) = state.get_segment(i) {
                 // Use safe API to extract text
                 match segment.to_str() {
                     Ok(text) => {
                         log::debug!("Segment {}: '{}'", i, text);
                         full_text.push_str(text);
                         full_text.push(' ');
+                        
+                        let tokens: Vec<TimedToken> = (0..segment.n_tokens())
+                            .filter_map(|j| segment.get_token(j))
+                            .filter_map(|token| {
+                                let data = token.token_data();
+                                let text = token.to_str_lossy().ok()?.into_owned();
+                                Some(TimedToken { text, t0: data.t0, t1: data.t1, p: data.p })
+                            })
+                            .collect();
+                        probabilities.extend(tokens.iter().filter(|token| !is_special(&token.text)).map(|token| token.p));
+                        let words = if settings.word_timestamps { group_words(&tokens) } else { Vec::new() };
+                        // Whisper timestamps are in centiseconds
+                        segments.push(Segment {
+                            start_ms: segment.start_timestamp() * 10,
+                            end_ms: segment.end_timestamp() * 10,
+                            text: text.trim().to_string(),
+                            language: detected_language.clone(),
+                            words,
+                        });
                     }
                     Err(e) => {
                         log::error!("Failed to extract text from segment {}: {:?}", i, e);
@@ -101,58 +809,78 @@ impl SpeechToText {
         
         Ok(TranscriptionResult {
             text: full_text.trim().to_string(),
-            language: Some("fr".to_string()),
+            language: detected_language,
             duration_ms,
+            segments,
+            confidence: (!probabilities.is_empty()).then(|| probabilities.iter().sum::<f32>() / probabilities.len() as f32),
+            provider: None,
         })
     }
+}
 
-    /// Read a WAV file and convert it to f32 samples at 16kHz mono
-    fn read_wav_file(&self, path: &PathBuf) -> Result<Vec<f32>> {
-        use std::fs::File;
-        use std::io::Read;
-        
-        let mut file = File::open(path).context("Failed to open audio file")?;
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer).context("Failed to read audio file")?;
-        
-        // Parse WAV header
-        if buffer.len() < 44 {
-            anyhow::bail!("Invalid WAV file: too short");
-        }
-        
-        // Lire le sample rate depuis le header WAV (offset 24, 4 bytes, little-endian)
-        let sample_rate = u32::from_le_bytes([
-            buffer[24], buffer[25], buffer[26], buffer[27]
-        ]);
-        
-        log::info!("WAV file sample rate: {} Hz", sample_rate);
-        
-        // Skip WAV header (44 bytes)
-        let audio_data = &buffer[44..];
-        
-        // Convert i16 samples to f32 normalized to [-1.0, 1.0]
-        let mut samples: Vec<f32> = audio_data
-            .chunks_exact(2)
-            .map(|chunk| {
-                let sample = i16::from_le_bytes([chunk[0], chunk[1]]);
-                sample as f32 / 32768.0
-            })
-            .collect();
-        
-        // Whisper attend du 16kHz, downsampler si nécessaire
-        if sample_rate != 16000 {
-            log::info!("Resampling from {} Hz to 16000 Hz", sample_rate);
-            samples = resample_audio(&samples, sample_rate, 16000);
-            log::info!("Resampled to {} samples", samples.len());
+/// Read a WAV file and convert it to f32 samples at 16kHz mono.
+/// The header is inspected: 8/16/24/32-bit integer and 32-bit float PCM are accepted,
+/// channels are averaged to mono and other sample rates are resampled.
+pub fn read_wav_16k(path: &Path) -> Result<Vec<f32>> {
+    let (mut samples, sample_rate) = read_wav_mono(path)?;
+
+    // Whisper attend du 16kHz, downsampler si nécessaire
+    if sample_rate != 16000 {
+        log::info!("Resampling from {} Hz to 16000 Hz", sample_rate);
+        samples = resample_audio(&samples, sample_rate, 16000);
+        log::info!("Resampled to {} samples", samples.len());
+    }
+
+    Ok(samples)
+}
+
+/// Read a WAV file as f32 mono samples in [-1.0, 1.0], at its own sample rate
+pub fn read_wav_mono(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let mut reader = hound::WavReader::open(path)
+        .with_context(|| format!("Failed to open WAV file {:?}", path))?;
+    let spec = reader.spec();
+    log::info!(
+        "WAV file: {} Hz, {} channel(s), {}-bit {:?}",
+        spec.sample_rate, spec.channels, spec.bits_per_sample, spec.sample_format
+    );
+    if spec.channels == 0 || spec.sample_rate == 0 {
+        anyhow::bail!("Invalid WAV file {:?}: {} channels at {} Hz", path, spec.channels, spec.sample_rate);
+    }
+
+    // Normalize to [-1.0, 1.0]
+    let interleaved: Vec<f32> = match (spec.sample_format, spec.bits_per_sample) {
+        (hound::SampleFormat::Float, 32) => reader.samples::<f32>().collect::<Result<_, _>>(),
+        (hound::SampleFormat::Int, bits @ 8..=32) => {
+            let scale = (1i64 << (bits - 1)) as f32;
+            reader.samples::<i32>().map(|s| s.map(|s| s as f32 / scale)).collect::<Result<_, _>>()
         }
-        
-        Ok(samples)
+        (format, bits) => anyhow::bail!("Unsupported WAV format in {:?}: {}-bit {:?}", path, bits, format),
     }
+    .with_context(|| format!("Failed to read samples from {:?}", path))?;
+
+    let samples: Vec<f32> = if spec.channels == 1 {
+        interleaved
+    } else {
+        log::info!("Downmixing {} channels to mono", spec.channels);
+        interleaved
+            .chunks_exact(spec.channels as usize)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect()
+    };
+
+    Ok((samples, spec.sample_rate))
+}
+
+/// Convert 16-bit mono samples kept in memory to f32 samples at 16kHz, as
+/// `read_wav_16k` does for a WAV file
+pub fn samples_16k(samples: &[i16], sample_rate: u32) -> Vec<f32> {
+    let samples: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+    resample_audio(&samples, sample_rate, 16000)
 }
 
 /// Resample audio from one sample rate to another using linear interpolation
-fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
-    if from_rate == to_rate {
+pub(crate) fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
         return samples.to_vec();
     }
     
@@ -181,11 +909,128 @@ fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_read_wav_converts_format() {
+        let dir = std::env::temp_dir().join(format!("read_wav_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // 0.5s of 24-bit stereo at 32kHz, left at +0.5 and right at -0.25
+        let stereo = dir.join("stereo_24.wav");
+        let spec = hound::WavSpec { channels: 2, sample_rate: 32000, bits_per_sample: 24, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&stereo, spec).unwrap();
+        for _ in 0..16000 {
+            writer.write_sample(1i32 << 22).unwrap();
+            writer.write_sample(-(1i32 << 21)).unwrap();
+        }
+        writer.finalize().unwrap();
+        let samples = read_wav_16k(&stereo).unwrap();
+        assert_eq!(samples.len(), 8000);
+        assert!(samples.iter().all(|s| (s - 0.125).abs() < 1e-4));
+
+        // 32-bit float mono at 16kHz is read as is
+        let float = dir.join("float.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate: 16000, bits_per_sample: 32, sample_format: hound::SampleFormat::Float };
+        let mut writer = hound::WavWriter::create(&float, spec).unwrap();
+        for s in [0.0f32, 0.5, -1.0] {
+            writer.write_sample(s).unwrap();
+        }
+        writer.finalize().unwrap();
+        assert_eq!(read_wav_16k(&float).unwrap(), vec![0.0, 0.5, -1.0]);
+
+        // Not a WAV file: clear error instead of garbage samples
+        let text = dir.join("notes.wav");
+        std::fs::write(&text, "not audio").unwrap();
+        assert!(read_wav_16k(&text).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     #[ignore] // Requires a Whisper model file
     fn test_speech_to_text_creation() {
         let model_path = PathBuf::from("models/ggml-base.en.bin");
         let stt = SpeechToText::new(model_path);
         assert!(stt.context.lock().unwrap().is_none());
+        stt.set_model_path(PathBuf::from("models/ggml-small.bin"));
+        assert_eq!(stt.model_name(), "ggml-small.bin");
+    }
+
+    #[test]
+    fn test_cancel_only_affects_running_transcriptions() {
+        let stt = SpeechToText::new(PathBuf::from("ggml-test.bin"));
+        let running = stt.cancellation_token();
+        stt.cancel();
+        assert!(running.is_cancelled());
+        assert!(!stt.cancellation_token().is_cancelled());
+        assert!(anyhow::Error::from(Cancelled).is::<Cancelled>());
+    }
+
+    #[test]
+    fn test_pool_size_and_ordered_batch() {
+        const MB: u64 = 1024 * 1024;
+        assert_eq!(pool_size(16, 4, Some(8000 * MB), 390 * MB), 4);
+        // Memory bound: two small states fit
+        assert_eq!(pool_size(16, 4, Some(800 * MB), 390 * MB), 2);
+        assert_eq!(pool_size(2, 4, None, 390 * MB), 1);
+
+        let stt = SpeechToText::new(PathBuf::from("/nonexistent/ggml-small.bin"));
+        stt.pool.set_limit(3);
+        let items: Vec<u64> = vec![30, 5, 20, 1, 10];
+        let running = std::sync::atomic::AtomicUsize::new(0);
+        let peak = std::sync::atomic::AtomicUsize::new(0);
+        let mut delivered = Vec::new();
+        stt.transcribe_batch(
+            &items,
+            |ms| {
+                let _permit = stt.pool.acquire();
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(std::time::Duration::from_millis(*ms));
+                running.fetch_sub(1, Ordering::SeqCst);
+                ms * 2
+            },
+            |ms, doubled| delivered.push((*ms, doubled)),
+        );
+        assert_eq!(delivered, vec![(30, 60), (5, 10), (20, 40), (1, 2), (10, 20)]);
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn test_backend_resolution_and_quantization() {
+        let compiled = [WhisperBackend::Cuda];
+        assert_eq!(WhisperBackend::Auto.resolve(&compiled), WhisperBackend::Cuda);
+        assert_eq!(WhisperBackend::Auto.resolve(&[]), WhisperBackend::Cpu);
+        assert_eq!(WhisperBackend::Metal.resolve(&compiled), WhisperBackend::Cpu);
+        assert_eq!(WhisperBackend::Cpu.resolve(&compiled), WhisperBackend::Cpu);
+        assert_eq!(model_quantization(Path::new("models/ggml-medium-q5_0.bin")).as_deref(), Some("q5_0"));
+        assert_eq!(model_quantization(Path::new("models/ggml-large-v3-q8_0.bin")).as_deref(), Some("q8_0"));
+        assert_eq!(model_quantization(Path::new("models/ggml-small.bin")), None);
+    }
+
+    #[test]
+    fn test_group_words() {
+        let token = |text: &str, t0: i64, t1: i64, p: f32| TimedToken { text: text.to_string(), t0, t1, p };
+        let tokens = vec![
+            token("[_BEG_]", 0, 0, 1.0),
+            token(" Un", 0, 20, 0.9),
+            token(" cli", 20, 35, 0.8),
+            token("ent", 35, 50, 0.6),
+            token(" passe", 50, 80, 0.95),
+            token(".", 80, 82, 0.99),
+            token("<|endoftext|>", 82, 82, 1.0),
+        ];
+        let words = group_words(&tokens);
+        assert_eq!(words.iter().map(|w| w.word.as_str()).collect::<Vec<_>>(), vec!["Un", "client", "passe."]);
+        assert_eq!((words[1].start_ms, words[1].end_ms), (200, 500));
+        assert!((words[1].probability - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language(" EN ").unwrap(), "en");
+        assert_eq!(normalize_language("haw").unwrap(), "haw");
+        assert_eq!(normalize_language("Auto").unwrap(), AUTO_LANGUAGE);
+        assert!(normalize_language("").is_err());
+        assert!(normalize_language("french").is_err());
+        assert!(normalize_language("f1").is_err());
     }
 }
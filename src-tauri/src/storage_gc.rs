@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::model_manager;
+
+/// Marks a session directory as complete (see recording_session)
+const SESSION_FILE: &str = "session.json";
+
+/// What the garbage collector removes
+#[derive(Debug, Clone, PartialEq)]
+pub struct GcRules {
+    /// `*.enhanced.wav` copies and `.*.tmp` files of interrupted writes, once this old
+    pub temp_max_age: Duration,
+    /// Session directories without `session.json` (recording or transcription that
+    /// never finished), once their newest file is this old
+    pub orphan_max_age: Duration,
+}
+
+impl Default for GcRules {
+    fn default() -> Self {
+        Self {
+            temp_max_age: Duration::from_secs(60 * 60),
+            orphan_max_age: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+/// Limits on the audio kept by finished recording sessions. Over them, the WAV files of
+/// the oldest sessions are removed; `session.json` (the transcripts) and replays stay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioRetention {
+    /// Sessions older than this lose their audio (None = no age limit)
+    pub max_age_days: Option<u32>,
+    /// Audio of all sessions above this is removed, oldest sessions first (None = no size limit)
+    pub max_mb: Option<u64>,
+}
+
+/// Directories holding app data on disk
+#[derive(Debug, Clone)]
+pub struct StorageRoots {
+    pub recordings: PathBuf,
+    pub models: PathBuf,
+    pub artifacts: PathBuf,
+}
+
+impl StorageRoots {
+    pub fn new(recordings_dir: &Path, app_data_dir: &Path) -> Self {
+        Self {
+            recordings: recordings_dir.to_path_buf(),
+            models: model_manager::models_dir(app_data_dir),
+            artifacts: app_data_dir.join("artifacts"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GcReport {
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+/// Space used on disk, by kind
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageUsage {
+    /// Recording sessions (utterances, raw tracks, sidecars, replays)
+    pub audio_bytes: u64,
+    pub models_bytes: u64,
+    pub artifacts_bytes: u64,
+    /// Part of the above the next garbage collection would free
+    pub reclaimable_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Files under `dir`, recursively
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            walk(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Size of a file, or of a directory and everything under it
+pub fn size_of(path: &Path) -> u64 {
+    if path.is_file() {
+        return fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    }
+    let mut files = Vec::new();
+    walk(path, &mut files);
+    files.iter().filter_map(|file| fs::metadata(file).ok()).map(|m| m.len()).sum()
+}
+
+fn age(path: &Path, now: SystemTime) -> Duration {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .unwrap_or_default()
+}
+
+/// Enhanced copy or leftover of an atomic write. Temporary files of this process may
+/// still be written to (a long model download) and are left alone.
+fn is_temp_file(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let own_write = format!(".{}-", std::process::id());
+    name.ends_with(".enhanced.wav") || (name.starts_with('.') && name.ends_with(".tmp") && !name.contains(&own_write))
+}
+
+/// What the rules would remove at `now`
+pub fn collect(roots: &StorageRoots, rules: &GcRules, now: SystemTime) -> Vec<PathBuf> {
+    let mut stale = Vec::new();
+    let mut files = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(&roots.recordings) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !path.is_dir() {
+                files.push(path);
+                continue;
+            }
+            let mut session_files = Vec::new();
+            walk(&path, &mut session_files);
+            let newest = session_files.iter().map(|file| age(file, now)).min().unwrap_or_else(|| age(&path, now));
+            if !path.join(SESSION_FILE).exists() && newest >= rules.orphan_max_age {
+                stale.push(path);
+            } else {
+                files.extend(session_files);
+            }
+        }
+    }
+    walk(&roots.models, &mut files);
+    walk(&roots.artifacts, &mut files);
+
+    stale.extend(files.into_iter().filter(|file| is_temp_file(file) && age(file, now) >= rules.temp_max_age));
+    stale
+}
+
+/// Remove what the rules select
+pub fn run(roots: &StorageRoots, rules: &GcRules) -> GcReport {
+    let report = remove(collect(roots, rules, SystemTime::now()));
+    if !report.removed.is_empty() {
+        log::info!("[Storage] Removed {} stale files ({} bytes)", report.removed.len(), report.freed_bytes);
+    }
+    report
+}
+
+fn is_audio_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+}
+
+/// Audio files the retention policy removes at `now`, whole sessions at a time, oldest
+/// first. Sessions without `session.json` (recording or transcription in progress) and
+/// the sessions of `keep` (transcription deferred) are left alone.
+pub fn retention_collect(recordings: &Path, retention: &AudioRetention, keep: &[String], now: SystemTime) -> Vec<PathBuf> {
+    let mut sessions = Vec::new();
+    let mut total_bytes = 0;
+    if let Ok(entries) = fs::read_dir(recordings) {
+        for path in entries.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()) {
+            let mut audio = Vec::new();
+            walk(&path, &mut audio);
+            audio.retain(|file| is_audio_file(file));
+            let bytes: u64 = audio.iter().map(|file| size_of(file)).sum();
+            total_bytes += bytes;
+            let id = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            if path.join(SESSION_FILE).exists() && !keep.contains(&id) && !audio.is_empty() {
+                sessions.push((age(&path.join(SESSION_FILE), now), bytes, audio));
+            }
+        }
+    }
+    sessions.sort_by_key(|(session_age, _, _)| std::cmp::Reverse(*session_age));
+
+    let max_age = retention.max_age_days.map(|days| Duration::from_secs(days as u64 * 24 * 60 * 60));
+    let max_bytes = retention.max_mb.map(|mb| mb * 1024 * 1024);
+    let mut stale = Vec::new();
+    for (session_age, bytes, audio) in sessions {
+        let too_old = max_age.is_some_and(|max_age| session_age >= max_age);
+        let too_big = max_bytes.is_some_and(|max_bytes| total_bytes > max_bytes);
+        if too_old || too_big {
+            total_bytes -= bytes;
+            stale.extend(audio);
+        }
+    }
+    stale
+}
+
+/// Apply the retention policy
+pub fn enforce_retention(recordings: &Path, retention: &AudioRetention, keep: &[String]) -> GcReport {
+    let report = remove(retention_collect(recordings, retention, keep, SystemTime::now()));
+    if !report.removed.is_empty() {
+        log::info!("[Storage] Retention removed {} audio files ({} bytes)", report.removed.len(), report.freed_bytes);
+    }
+    report
+}
+
+/// Remove the audio of every finished session (except those of `keep`)
+pub fn purge_audio(recordings: &Path, keep: &[String]) -> GcReport {
+    enforce_retention(recordings, &AudioRetention { max_age_days: None, max_mb: Some(0) }, keep)
+}
+
+fn remove(paths: Vec<PathBuf>) -> GcReport {
+    let mut report = GcReport::default();
+    for path in paths {
+        let size = size_of(&path);
+        let removed = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+        match removed {
+            Ok(()) => {
+                report.freed_bytes += size;
+                report.removed.push(path.display().to_string());
+            }
+            Err(e) => log::warn!("[Storage] Failed to remove {:?}: {}", path, e),
+        }
+    }
+    report
+}
+
+pub fn usage(roots: &StorageRoots, rules: &GcRules) -> StorageUsage {
+    let audio_bytes = size_of(&roots.recordings);
+    let models_bytes = size_of(&roots.models);
+    let artifacts_bytes = size_of(&roots.artifacts);
+    StorageUsage {
+        audio_bytes,
+        models_bytes,
+        artifacts_bytes,
+        reclaimable_bytes: collect(roots, rules, SystemTime::now()).iter().map(|path| size_of(path)).sum(),
+        total_bytes: audio_bytes + models_bytes + artifacts_bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collects_temp_files_and_orphaned_sessions() {
+        let root = std::env::temp_dir().join(format!("storage_gc_{}", std::process::id()));
+        let roots = StorageRoots::new(&root.join("recordings"), &root.join("app_data"));
+        let complete = roots.recordings.join("20250101_100000");
+        let orphan = roots.recordings.join("20250101_110000");
+        fs::create_dir_all(&complete).unwrap();
+        fs::create_dir_all(&orphan).unwrap();
+        fs::create_dir_all(&roots.models).unwrap();
+        fs::write(complete.join(SESSION_FILE), "{}").unwrap();
+        fs::write(complete.join("utterance_0001.wav"), vec![0u8; 100]).unwrap();
+        fs::write(complete.join("utterance_0001.enhanced.wav"), vec![0u8; 40]).unwrap();
+        fs::write(orphan.join("utterance_0001.wav"), vec![0u8; 100]).unwrap();
+        fs::write(roots.models.join(".ggml-small.bin.0-0.tmp"), vec![0u8; 10]).unwrap();
+        fs::write(roots.models.join("ggml-small.bin"), vec![0u8; 1000]).unwrap();
+
+        let rules = GcRules::default();
+        assert!(collect(&roots, &rules, SystemTime::now()).is_empty(), "nothing is old enough yet");
+
+        let in_two_hours = SystemTime::now() + Duration::from_secs(2 * 60 * 60);
+        let stale = collect(&roots, &rules, in_two_hours);
+        assert_eq!(stale.len(), 2);
+        assert!(stale.contains(&complete.join("utterance_0001.enhanced.wav")));
+        assert!(stale.contains(&roots.models.join(".ggml-small.bin.0-0.tmp")));
+
+        let in_two_days = SystemTime::now() + Duration::from_secs(2 * 24 * 60 * 60);
+        assert!(collect(&roots, &rules, in_two_days).contains(&orphan));
+
+        let usage = usage(&roots, &rules);
+        assert_eq!(usage.audio_bytes, 242);
+        assert_eq!(usage.models_bytes, 1010);
+        assert_eq!(usage.total_bytes, 1252);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_retention_removes_audio_of_oldest_sessions() {
+        let recordings = std::env::temp_dir().join(format!("retention_{}", std::process::id()));
+        let mb = vec![0u8; 1024 * 1024];
+        for (session, finished) in [("20250101_100000", true), ("20250102_100000", true), ("20250103_100000", true), ("20250104_100000", false)] {
+            let dir = recordings.join(session);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("utterance_0001.wav"), &mb).unwrap();
+            if finished {
+                fs::write(dir.join(SESSION_FILE), "{}").unwrap();
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        }
+        let wav = |session: &str| recordings.join(session).join("utterance_0001.wav");
+        let now = SystemTime::now();
+
+        // 4 MB for 2 MB allowed: the two oldest finished sessions lose their audio
+        let by_size = AudioRetention { max_age_days: None, max_mb: Some(2) };
+        assert_eq!(retention_collect(&recordings, &by_size, &[], now), vec![wav("20250101_100000"), wav("20250102_100000")]);
+        // A deferred session is kept: the next one goes instead
+        let keep = vec!["20250101_100000".to_string()];
+        assert_eq!(retention_collect(&recordings, &by_size, &keep, now), vec![wav("20250102_100000"), wav("20250103_100000")]);
+
+        let by_age = AudioRetention { max_age_days: Some(7), max_mb: None };
+        assert!(retention_collect(&recordings, &by_age, &[], now).is_empty());
+        let in_eight_days = now + Duration::from_secs(8 * 24 * 60 * 60);
+        assert_eq!(retention_collect(&recordings, &by_age, &[], in_eight_days).len(), 3);
+
+        // The session still recording keeps its audio, the transcripts stay
+        let report = purge_audio(&recordings, &[]);
+        assert_eq!(report.freed_bytes, 3 * 1024 * 1024);
+        assert!(wav("20250104_100000").exists());
+        assert!(recordings.join("20250101_100000").join(SESSION_FILE).exists());
+        fs::remove_dir_all(&recordings).ok();
+    }
+}
@@ -0,0 +1,315 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::env;
+use std::time::Duration;
+
+use crate::provider_policy;
+use crate::speech_to_text::{Segment, TranscriptionResult, WordTimestamp, AUTO_LANGUAGE};
+
+/// Remote speech-to-text services
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloudProvider {
+    /// OpenAI Whisper API (`/v1/audio/transcriptions`), or a compatible server
+    OpenAi,
+    Deepgram,
+}
+
+impl CloudProvider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Deepgram => "deepgram",
+        }
+    }
+
+    fn default_endpoint(&self) -> &'static str {
+        match self {
+            Self::OpenAi => "https://api.openai.com/v1",
+            Self::Deepgram => "https://api.deepgram.com/v1/listen",
+        }
+    }
+
+    fn default_model(&self) -> &'static str {
+        match self {
+            Self::OpenAi => "whisper-1",
+            Self::Deepgram => "nova-2",
+        }
+    }
+}
+
+/// Remote transcription used when no local Whisper model is installed, or when the
+/// local transcription is too unsure. The API key comes from STT_API_KEY, like
+/// LLM_API_KEY for the LLM, so that it is never written to the settings file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CloudSttSettings {
+    /// None = local Whisper only
+    pub provider: Option<CloudProvider>,
+    /// Remote model ("whisper-1", "nova-2"...), the provider's default if unset
+    pub model: Option<String>,
+    /// Mean token probability of a local transcription under which it is redone remotely
+    pub min_confidence: f32,
+}
+
+impl Default for CloudSttSettings {
+    fn default() -> Self {
+        Self { provider: None, model: None, min_confidence: 0.35 }
+    }
+}
+
+/// Configured remote provider
+#[derive(Debug, Clone)]
+pub struct CloudStt {
+    provider: CloudProvider,
+    api_key: String,
+    endpoint: String,
+    model: String,
+    min_confidence: f32,
+}
+
+impl CloudStt {
+    /// None when no remote provider is selected; an error when one is but STT_API_KEY
+    /// is missing. STT_ENDPOINT overrides the provider's URL (proxy, compatible server).
+    pub fn from_settings(settings: &CloudSttSettings) -> Result<Option<Self>> {
+        let Some(provider) = settings.provider else {
+            return Ok(None);
+        };
+        let api_key = env::var("STT_API_KEY").context("STT_API_KEY must be set to use cloud transcription")?;
+        let endpoint = env::var("STT_ENDPOINT")
+            .ok()
+            .filter(|endpoint| !endpoint.trim().is_empty())
+            .unwrap_or_else(|| provider.default_endpoint().to_string());
+        Ok(Some(Self {
+            provider,
+            api_key,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            model: settings.model.clone().unwrap_or_else(|| provider.default_model().to_string()),
+            min_confidence: settings.min_confidence,
+        }))
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.provider.name()
+    }
+
+    /// Whether a local transcription is unsure enough to be sent to the provider
+    pub fn needs_fallback(&self, local: &TranscriptionResult) -> bool {
+        local.confidence.is_some_and(|confidence| confidence < self.min_confidence)
+    }
+
+    /// Transcribe 16kHz mono samples. Blocking: called from the transcription threads.
+    pub fn transcribe(&self, samples: &[f32], language: &str, translate: bool) -> Result<TranscriptionResult> {
+        let (_, policy) = provider_policy::load()?;
+        policy.check_network("cloud transcription")?;
+
+        let started = std::time::Instant::now();
+        let wav = wav_bytes(samples)?;
+        log::info!("[CloudSTT] Sending {} KB of audio to {}", wav.len() / 1024, self.name());
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .context("Failed to create HTTP client")?;
+        let mut result = match self.provider {
+            CloudProvider::OpenAi => {
+                // Translations take no language: the source language is detected
+                let translate = translate && language != "en";
+                let route = if translate { "translations" } else { "transcriptions" };
+                let mut fields = vec![("model", self.model.as_str()), ("response_format", "verbose_json")];
+                if !translate && language != AUTO_LANGUAGE {
+                    fields.push(("language", language));
+                }
+                let boundary = format!("----stt-{}", chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default());
+                let response = client
+                    .post(format!("{}/audio/{}", self.endpoint, route))
+                    .bearer_auth(&self.api_key)
+                    .header("Content-Type", format!("multipart/form-data; boundary={}", boundary))
+                    .body(multipart_body(&boundary, &fields, &wav))
+                    .send()
+                    .context("Failed to reach the transcription API")?;
+                let body = json_response(response)?;
+                parse_openai(&body, (!translate && language != AUTO_LANGUAGE).then_some(language))?
+            }
+            CloudProvider::Deepgram => {
+                if translate {
+                    anyhow::bail!("Deepgram does not translate transcriptions");
+                }
+                let mut query = vec![("model", self.model.as_str()), ("smart_format", "true")];
+                if language == AUTO_LANGUAGE {
+                    query.push(("detect_language", "true"));
+                } else {
+                    query.push(("language", language));
+                }
+                let response = client
+                    .post(&self.endpoint)
+                    .header("Authorization", format!("Token {}", self.api_key))
+                    .header("Content-Type", "audio/wav")
+                    .query(&query)
+                    .body(wav)
+                    .send()
+                    .context("Failed to reach the transcription API")?;
+                parse_deepgram(&json_response(response)?, (language != AUTO_LANGUAGE).then_some(language))?
+            }
+        };
+        result.duration_ms = started.elapsed().as_millis() as u64;
+        result.provider = Some(self.name().to_string());
+        log::info!("[CloudSTT] {} transcribed {} chars in {}ms", self.name(), result.text.len(), result.duration_ms);
+        Ok(result)
+    }
+}
+
+fn json_response(response: reqwest::blocking::Response) -> Result<Value> {
+    let status = response.status();
+    if !status.is_success() {
+        let error = response.text().unwrap_or_default();
+        anyhow::bail!("Transcription API error ({}): {}", status, error);
+    }
+    response.json().context("Invalid transcription API response")
+}
+
+/// Samples as a 16-bit PCM WAV file
+fn wav_bytes(samples: &[f32]) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec { channels: 1, sample_rate: 16000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut cursor, spec).context("Failed to encode audio")?;
+    for sample in samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize().context("Failed to encode audio")?;
+    Ok(cursor.into_inner())
+}
+
+fn multipart_body(boundary: &str, fields: &[(&str, &str)], wav: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (name, value) in fields {
+        body.extend_from_slice(format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", boundary, name, value).as_bytes());
+    }
+    body.extend_from_slice(
+        format!("--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"audio.wav\"\r\nContent-Type: audio/wav\r\n\r\n", boundary).as_bytes(),
+    );
+    body.extend_from_slice(wav);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+/// ISO 639-1 code of the language names returned by the OpenAI API ("french")
+fn language_code(name: &str) -> Option<String> {
+    let code = match name.to_lowercase().as_str() {
+        "english" => "en",
+        "french" => "fr",
+        "german" => "de",
+        "spanish" => "es",
+        "italian" => "it",
+        "portuguese" => "pt",
+        "dutch" => "nl",
+        code if code.len() == 2 => return Some(code.to_string()),
+        _ => return None,
+    };
+    Some(code.to_string())
+}
+
+fn seconds_to_ms(value: &Value) -> i64 {
+    (value.as_f64().unwrap_or_default() * 1000.0).round() as i64
+}
+
+/// `verbose_json` response: segments with their mean log probability
+fn parse_openai(body: &Value, language: Option<&str>) -> Result<TranscriptionResult> {
+    let text = body["text"].as_str().context("Transcription API response without text")?.trim().to_string();
+    let language = language.map(str::to_string).or_else(|| body["language"].as_str().and_then(language_code));
+    let raw_segments = body["segments"].as_array().cloned().unwrap_or_default();
+    let segments: Vec<Segment> = raw_segments
+        .iter()
+        .map(|segment| Segment {
+            start_ms: seconds_to_ms(&segment["start"]),
+            end_ms: seconds_to_ms(&segment["end"]),
+            text: segment["text"].as_str().unwrap_or_default().trim().to_string(),
+            language: language.clone(),
+            words: Vec::new(),
+        })
+        .collect();
+    let probabilities: Vec<f64> = raw_segments.iter().filter_map(|segment| segment["avg_logprob"].as_f64()).map(f64::exp).collect();
+    let confidence = (!probabilities.is_empty()).then(|| (probabilities.iter().sum::<f64>() / probabilities.len() as f64) as f32);
+    Ok(TranscriptionResult { text, language, duration_ms: 0, segments, confidence, provider: None })
+}
+
+/// First alternative of the first channel, as one segment with its words
+fn parse_deepgram(body: &Value, language: Option<&str>) -> Result<TranscriptionResult> {
+    let channel = &body["results"]["channels"][0];
+    let alternative = &channel["alternatives"][0];
+    let text = alternative["transcript"].as_str().context("Transcription API response without transcript")?.trim().to_string();
+    let language = language.map(str::to_string).or_else(|| channel["detected_language"].as_str().map(str::to_string));
+    let words: Vec<WordTimestamp> = alternative["words"]
+        .as_array()
+        .map(|words| {
+            words
+                .iter()
+                .map(|word| WordTimestamp {
+                    word: word["punctuated_word"].as_str().or(word["word"].as_str()).unwrap_or_default().to_string(),
+                    start_ms: seconds_to_ms(&word["start"]),
+                    end_ms: seconds_to_ms(&word["end"]),
+                    probability: word["confidence"].as_f64().unwrap_or_default() as f32,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let segments = match (words.first(), words.last()) {
+        (Some(first), Some(last)) => vec![Segment {
+            start_ms: first.start_ms,
+            end_ms: last.end_ms,
+            text: text.clone(),
+            language: language.clone(),
+            words,
+        }],
+        _ => Vec::new(),
+    };
+    Ok(TranscriptionResult {
+        text,
+        language,
+        duration_ms: 0,
+        segments,
+        confidence: alternative["confidence"].as_f64().map(|confidence| confidence as f32),
+        provider: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openai_fallback_request() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(mockito::Matcher::Regex("name=\"language\"\r\n\r\nfr\r\n".to_string()))
+            .with_body(r#"{"language": "french", "text": " Le client passe commande.", "segments": [{"start": 0.0, "end": 1.52, "text": " Le client passe commande.", "avg_logprob": -0.1}]}"#)
+            .create();
+        let cloud = CloudStt {
+            provider: CloudProvider::OpenAi,
+            api_key: "test-key".to_string(),
+            endpoint: format!("{}/v1", server.url()),
+            model: "whisper-1".to_string(),
+            min_confidence: 0.35,
+        };
+        let result = cloud.transcribe(&vec![0.0; 16000], "fr", false).unwrap();
+        mock.assert();
+        assert_eq!(result.text, "Le client passe commande.");
+        assert_eq!(result.provider.as_deref(), Some("openai"));
+        assert_eq!((result.segments[0].end_ms, result.segments[0].language.as_deref()), (1520, Some("fr")));
+        assert!(!cloud.needs_fallback(&result));
+        assert!(cloud.needs_fallback(&TranscriptionResult { confidence: Some(0.2), ..result.clone() }));
+        // Without a probability (older transcripts) the local result is kept
+        assert!(!cloud.needs_fallback(&TranscriptionResult { confidence: None, ..result }));
+
+        let deepgram = serde_json::json!({"results": {"channels": [{"detected_language": "en", "alternatives": [{
+            "transcript": "Orders have lines.", "confidence": 0.97,
+            "words": [{"word": "orders", "punctuated_word": "Orders", "start": 0.2, "end": 0.6, "confidence": 0.99},
+                      {"word": "lines", "punctuated_word": "lines.", "start": 0.9, "end": 1.3, "confidence": 0.95}]
+        }]}]}});
+        let result = parse_deepgram(&deepgram, None).unwrap();
+        assert_eq!((result.language.as_deref(), result.confidence), (Some("en"), Some(0.97)));
+        assert_eq!((result.segments[0].start_ms, result.segments[0].end_ms, result.segments[0].words.len()), (200, 1300, 2));
+    }
+}
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::prompt_budget::{PromptBudget, PromptSection, Trim};
+use crate::risk_register::{EntryKind, RiskRegister};
+use crate::transcript_normalize;
+
+/// System prompt for the stakeholder email summing up a project
+pub const EMAIL_SYSTEM_PROMPT: &str = r#"
+Tu rédiges l'email de compte rendu d'un atelier Domain-Driven Design, pour des parties prenantes qui n'y ont pas assisté (sponsor, équipes voisines). Tu reçois le canvas du domaine, le modèle (entités, relations, invariants), les réponses de l'interview et le registre des hypothèses et risques.
+
+Format, en texte brut (seules les puces "- " sont permises):
+Objet: <objet court>
+
+<salutation>
+
+<une phrase de contexte>
+
+Décisions clés:
+- ...
+
+Points saillants du modèle:
+- ...
+
+Questions ouvertes:
+- ...
+
+Prochaines étapes:
+- ...
+
+<formule de politesse>
+
+RÈGLES:
+1. Concis: 250 mots au plus, 5 puces au plus par rubrique
+2. N'invente rien: une rubrique sans matière contient une seule puce disant qu'il n'y a rien pour l'instant
+3. Les questions ouvertes viennent des hypothèses à vérifier, des risques et des points laissés en suspens dans les réponses
+4. Vocabulaire métier: pas de jargon DDD sans l'expliquer
+5. Rédige tout l'email, rubriques comprises, dans la langue demandée; seul le mot "Objet:" de la première ligne reste tel quel
+"#;
+
+/// Language of the email when neither the settings nor the content tell it
+const DEFAULT_LANGUAGE: &str = "fr";
+
+/// Project content the email is drafted from (each part may be missing)
+#[derive(Debug, Clone, Default)]
+pub struct EmailSources {
+    pub canvas: Option<String>,
+    pub model: Option<Value>,
+    /// Interview answers, as saved with the interview state
+    pub interview: Option<String>,
+    pub risks: RiskRegister,
+}
+
+impl EmailSources {
+    pub fn is_empty(&self) -> bool {
+        self.canvas.is_none() && self.model.is_none() && self.interview.is_none() && self.risks.entries.is_empty()
+    }
+}
+
+/// Draft ready to copy or to open in the mail client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryEmail {
+    pub subject: String,
+    pub body: String,
+    pub language: String,
+    /// `mailto:` link with the subject and body filled in (no recipient)
+    pub mailto: String,
+}
+
+/// Language of the project: the transcription language of the settings, or the one of
+/// its content when the settings say "auto"
+pub fn email_language(setting: &str, sources: &EmailSources) -> String {
+    if setting != "auto" {
+        return setting.to_string();
+    }
+    let text = [sources.canvas.as_deref(), sources.interview.as_deref()].into_iter().flatten().collect::<Vec<_>>().join("\n");
+    transcript_normalize::guess_language(&text).unwrap_or(DEFAULT_LANGUAGE).to_string()
+}
+
+fn names(list: &Value, name: impl Fn(&Value) -> Option<String>) -> Vec<String> {
+    list.as_array().map(|items| items.iter().filter_map(name).collect()).unwrap_or_default()
+}
+
+/// Entities, relations and invariants on a few lines: the JSON model is too verbose for
+/// the prompt and an email does not need attributes
+pub fn model_digest(model: &Value) -> String {
+    let label = |item: &Value| item["name"].as_str().or_else(|| item["id"].as_str()).map(str::to_string);
+    let entities = names(&model["entities"], label);
+    let relations = names(&model["relations"], |relation| {
+        Some(format!(
+            "{} → {} ({})",
+            relation["from"]["entityId"].as_str()?,
+            relation["to"]["entityId"].as_str()?,
+            relation["name"].as_str().or_else(|| relation["id"].as_str()).unwrap_or_default()
+        ))
+    });
+    let invariants = names(&model["invariants"], |invariant| {
+        let name = label(invariant)?;
+        Some(match invariant["description"].as_str().or_else(|| invariant["expression"].as_str()) {
+            Some(detail) => format!("{}: {}", name, detail),
+            None => name,
+        })
+    });
+
+    let mut digest = format!("Entités: {}\n", entities.join(", "));
+    for (title, items) in [("Relations", relations), ("Invariants", invariants)] {
+        if !items.is_empty() {
+            digest.push_str(&format!("{}:\n{}\n", title, items.iter().map(|i| format!("- {}", i)).collect::<Vec<_>>().join("\n")));
+        }
+    }
+    digest
+}
+
+/// User prompt: the language, then each available source; the interview answers and the
+/// canvas are cut from their end when over the token budget
+pub fn email_prompt(project_name: &str, language: &str, sources: &EmailSources, budget: &PromptBudget) -> (String, Vec<String>) {
+    let mut sections = vec![
+        PromptSection::new("system prompt", EMAIL_SYSTEM_PROMPT.to_string(), 4, Trim::Never),
+        PromptSection::new("header", format!("Projet: {}\nLangue de l'email (code ISO 639-1): {}\n\n", project_name, language), 4, Trim::Never),
+    ];
+    if let Some(model) = &sources.model {
+        sections.push(PromptSection::new("model", format!("## Modèle\n{}\n", model_digest(model)), 3, Trim::Lines));
+    }
+    if !sources.risks.entries.is_empty() {
+        let mut entries: Vec<_> = sources.risks.entries.iter().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.score()));
+        let lines: Vec<String> = entries
+            .iter()
+            .map(|e| {
+                let kind = match e.kind {
+                    EntryKind::Assumption => "hypothèse",
+                    EntryKind::Risk => "risque",
+                };
+                format!("- {} ({}, score {}/9)", e.statement, kind, e.score())
+            })
+            .collect();
+        sections.push(PromptSection::new("risks", format!("## Hypothèses & risques\n{}\n\n", lines.join("\n")), 3, Trim::Lines));
+    }
+    if let Some(canvas) = &sources.canvas {
+        sections.push(PromptSection::new("canvas", format!("## Canvas\n{}\n\n", canvas.trim()), 2, Trim::Lines));
+    }
+    if let Some(interview) = &sources.interview {
+        sections.push(PromptSection::new("interview", format!("## Réponses de l'interview\n{}\n", interview.trim()), 1, Trim::Lines));
+    }
+
+    let budgeted = budget.fit(sections);
+    (budgeted.text_of(&["header", "model", "risks", "canvas", "interview"]), budgeted.warnings)
+}
+
+/// RFC 6068 percent-encoding of a `mailto:` field (line breaks as CRLF)
+fn mailto_encode(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\n', "\r\n")
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Email from the LLM answer: the "Objet:" line is the subject (the project name when
+/// missing), the rest the body
+pub fn parse_email(answer: &str, project_name: &str, language: &str) -> SummaryEmail {
+    let answer = answer.trim().trim_start_matches("```text").trim_start_matches("```").trim_end_matches("```").trim();
+    let (first, rest) = answer.split_once('\n').unwrap_or((answer, ""));
+    let subject_line = ["Objet:", "Objet :", "Subject:"].iter().find_map(|prefix| first.trim().strip_prefix(prefix));
+    let (subject, body) = match subject_line {
+        Some(subject) => (subject.trim().to_string(), rest.trim().to_string()),
+        None => (project_name.to_string(), answer.to_string()),
+    };
+    let mailto = format!("mailto:?subject={}&body={}", mailto_encode(&subject), mailto_encode(&body));
+    SummaryEmail { subject, body, language: language.to_string(), mailto }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_email_prompt_and_parsing() {
+        let sources = EmailSources {
+            canvas: Some("## Contexte & Vision\n\nLes commandes sont validées par le superviseur avant la livraison.".to_string()),
+            model: Some(json!({
+                "entities": [{"id": "Order", "name": "Commande"}, {"id": "Customer", "name": "Client"}],
+                "relations": [{"id": "places", "name": "passe", "from": {"entityId": "Customer"}, "to": {"entityId": "Order"}}],
+                "invariants": [{"id": "positive_total", "name": "Total positif", "expression": "total >= 0"}]
+            })),
+            ..Default::default()
+        };
+        assert_eq!(email_language("auto", &sources), "fr");
+        assert_eq!(email_language("en", &sources), "en");
+
+        let (prompt, warnings) = email_prompt("Boutique", "fr", &sources, &PromptBudget::default());
+        assert!(warnings.is_empty());
+        assert!(prompt.starts_with("Projet: Boutique\nLangue de l'email (code ISO 639-1): fr\n"));
+        assert!(prompt.contains("Entités: Commande, Client\nRelations:\n- Customer → Order (passe)\nInvariants:\n- Total positif: total >= 0"));
+        assert!(!prompt.contains("Réponses de l'interview"));
+
+        let email = parse_email("Objet: Atelier Boutique — décisions\n\nBonjour,\n\nDécisions clés:\n- Validation & livraison", "Boutique", "fr");
+        assert_eq!(email.subject, "Atelier Boutique — décisions");
+        assert!(email.body.starts_with("Bonjour,"));
+        assert!(email.mailto.starts_with("mailto:?subject=Atelier%20Boutique%20%E2%80%94%20d%C3%A9cisions&body=Bonjour%2C%0D%0A%0D%0A"));
+        assert!(email.mailto.ends_with("Validation%20%26%20livraison"));
+        assert_eq!(parse_email("Bonjour,", "Boutique", "fr").subject, "Boutique");
+    }
+}
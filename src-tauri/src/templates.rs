@@ -0,0 +1,82 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Starter domain model shipped with the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelTemplate {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+}
+
+/// (name, title, description, model JSON)
+const TEMPLATES: &[(&str, &str, &str, &str)] = &[
+    (
+        "ecommerce",
+        "E-commerce",
+        "Clients, catalogue produits, commandes, lignes de commande et paiements",
+        include_str!("../templates/ecommerce.json"),
+    ),
+    (
+        "booking",
+        "Réservation",
+        "Ressources réservables, réservations sur une période et annulations",
+        include_str!("../templates/booking.json"),
+    ),
+    (
+        "subscription_billing",
+        "Facturation d'abonnements",
+        "Comptes, offres, abonnements récurrents et factures",
+        include_str!("../templates/subscription_billing.json"),
+    ),
+];
+
+/// List the available starter templates
+pub fn list_templates() -> Vec<ModelTemplate> {
+    TEMPLATES
+        .iter()
+        .map(|(name, title, description, _)| ModelTemplate {
+            name: name.to_string(),
+            title: title.to_string(),
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+/// Load a starter template as a DomainModel JSON value
+pub fn load_template(name: &str) -> Result<Value> {
+    let (_, _, _, json) = TEMPLATES
+        .iter()
+        .find(|(template, ..)| *template == name)
+        .with_context(|| format!("Unknown model template '{}'", name))?;
+
+    serde_json::from_str(json).with_context(|| format!("Invalid model template '{}'", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_templates_are_consistent() {
+        for template in list_templates() {
+            let model = load_template(&template.name).unwrap();
+            let entities = model["entities"].as_array().unwrap();
+            assert!(!entities.is_empty(), "{} has no entities", template.name);
+
+            let ids: HashSet<&str> = entities.iter().map(|e| e["id"].as_str().unwrap()).collect();
+            for relation in model["relations"].as_array().unwrap() {
+                assert!(ids.contains(relation["from"]["entityId"].as_str().unwrap()));
+                assert!(ids.contains(relation["to"]["entityId"].as_str().unwrap()));
+            }
+            assert!(model["invariants"].is_array());
+        }
+    }
+
+    #[test]
+    fn test_unknown_template() {
+        assert!(load_template("crm").is_err());
+    }
+}
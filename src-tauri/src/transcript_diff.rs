@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+
+/// Kind of change between two transcripts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Equal,
+    Insert,
+    Delete,
+    Replace,
+}
+
+/// Run of words that is identical in both transcripts or differs between them.
+/// Words keep their original spelling; indices are word positions in each transcript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffChunk {
+    pub kind: ChangeKind,
+    pub a_start: usize,
+    pub b_start: usize,
+    pub a_words: Vec<String>,
+    pub b_words: Vec<String>,
+}
+
+/// Word-level alignment of two transcripts of the same audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptComparison {
+    pub chunks: Vec<DiffChunk>,
+    pub a_word_count: usize,
+    pub b_word_count: usize,
+    /// Share of words common to both transcripts (0..1)
+    pub similarity: f32,
+    /// Substitutions + deletions + insertions over the length of `a`, taking
+    /// `a` as the reference
+    pub word_error_rate: f32,
+}
+
+/// Comparison key: case and surrounding punctuation are not meaningful
+/// differences between two Whisper runs
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+/// Align `a` and `b` word by word (patience diff, robust on long transcripts)
+pub fn compare(a: &str, b: &str) -> TranscriptComparison {
+    let a_words: Vec<&str> = a.split_whitespace().collect();
+    let b_words: Vec<&str> = b.split_whitespace().collect();
+    let a_keys: Vec<String> = a_words.iter().map(|w| normalize_word(w)).collect();
+    let b_keys: Vec<String> = b_words.iter().map(|w| normalize_word(w)).collect();
+
+    let mut chunks = Vec::new();
+    let mut equal_words = 0;
+    let mut errors = 0;
+    for op in capture_diff_slices(Algorithm::Patience, &a_keys, &b_keys) {
+        let (tag, a_range, b_range) = op.as_tag_tuple();
+        let kind = match tag {
+            DiffTag::Equal => {
+                equal_words += a_range.len();
+                ChangeKind::Equal
+            }
+            DiffTag::Insert => ChangeKind::Insert,
+            DiffTag::Delete => ChangeKind::Delete,
+            DiffTag::Replace => ChangeKind::Replace,
+        };
+        if kind != ChangeKind::Equal {
+            // A replacement of n words by m words is min(n, m) substitutions plus the rest
+            errors += a_range.len().max(b_range.len());
+        }
+        chunks.push(DiffChunk {
+            kind,
+            a_start: a_range.start,
+            b_start: b_range.start,
+            a_words: a_words[a_range].iter().map(|w| w.to_string()).collect(),
+            b_words: b_words[b_range].iter().map(|w| w.to_string()).collect(),
+        });
+    }
+
+    let total = a_words.len() + b_words.len();
+    TranscriptComparison {
+        chunks,
+        a_word_count: a_words.len(),
+        b_word_count: b_words.len(),
+        similarity: if total == 0 { 1.0 } else { 2.0 * equal_words as f32 / total as f32 },
+        word_error_rate: if a_words.is_empty() {
+            if b_words.is_empty() { 0.0 } else { 1.0 }
+        } else {
+            errors as f32 / a_words.len() as f32
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_small_vs_large_model() {
+        let small = "Le client passe une commande qui contient des lignes.";
+        let large = "Le client passe une commande, elle contient plusieurs lignes.";
+        let comparison = compare(small, large);
+
+        let kinds: Vec<ChangeKind> = comparison.chunks.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![ChangeKind::Equal, ChangeKind::Replace, ChangeKind::Equal, ChangeKind::Replace, ChangeKind::Equal]
+        );
+        // "commande" vs "commande," is not a difference, original spelling is kept
+        assert_eq!(comparison.chunks[0].b_words.last().unwrap(), "commande,");
+        assert_eq!(comparison.chunks[1].a_words, vec!["qui"]);
+        assert_eq!(comparison.chunks[1].b_words, vec!["elle"]);
+        assert_eq!(comparison.chunks[3].b_start, 7);
+        assert!((comparison.word_error_rate - 2.0 / 9.0).abs() < 1e-6);
+        assert!((comparison.similarity - 14.0 / 18.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_compare_insertions_and_empty() {
+        let comparison = compare("Bonjour", "Bonjour à tous");
+        assert_eq!(comparison.chunks[1].kind, ChangeKind::Insert);
+        assert_eq!(comparison.chunks[1].b_words, vec!["à", "tous"]);
+        assert!((comparison.word_error_rate - 2.0).abs() < 1e-6);
+
+        let identical = compare("Un livre", "un livre.");
+        assert_eq!(identical.similarity, 1.0);
+        assert_eq!(identical.word_error_rate, 0.0);
+        assert_eq!(compare("", "").similarity, 1.0);
+    }
+}
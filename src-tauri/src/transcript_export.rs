@@ -0,0 +1,298 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::audio_session::{self, AudioSource, UtteranceSidecar};
+use crate::recording_session::{self, SessionUtterance};
+use crate::speech_to_text::Segment;
+
+/// Timed transcript file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    Srt,
+    /// WebVTT
+    Vtt,
+    /// One timestamped paragraph per cue
+    Markdown,
+}
+
+impl std::str::FromStr for TranscriptFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "srt" => Ok(Self::Srt),
+            "vtt" | "webvtt" => Ok(Self::Vtt),
+            "markdown" | "md" => Ok(Self::Markdown),
+            other => anyhow::bail!("Unknown transcript format '{}', expected srt, vtt or markdown", other),
+        }
+    }
+}
+
+impl TranscriptFormat {
+    /// Format of a file from its extension
+    pub fn from_path(path: &Path) -> Result<Self> {
+        path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().parse()
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+/// Text shown from `start_ms` to `end_ms` of the recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// Cues of one transcription starting at `offset_ms`: one per segment, or the whole
+/// text over `duration_ms` when there are no segments
+pub fn cues(segments: &[Segment], text: &str, duration_ms: u64, offset_ms: u64) -> Vec<Cue> {
+    if segments.is_empty() {
+        return (!text.trim().is_empty())
+            .then(|| Cue { start_ms: offset_ms, end_ms: offset_ms + duration_ms, text: text.trim().to_string() })
+            .into_iter()
+            .collect();
+    }
+    segments
+        .iter()
+        .filter(|segment| !segment.text.trim().is_empty())
+        .map(|segment| {
+            let start_ms = offset_ms + segment.start_ms.max(0) as u64;
+            Cue {
+                start_ms,
+                end_ms: (offset_ms + segment.end_ms.max(0) as u64).max(start_ms),
+                text: segment.text.trim().to_string(),
+            }
+        })
+        .collect()
+}
+
+type Timestamp = chrono::DateTime<chrono::FixedOffset>;
+
+/// Start time of each utterance, read from its sidecar (None without sidecar)
+fn sidecar_start_times(session_dir: &Path, utterances: &[SessionUtterance]) -> Vec<Option<Timestamp>> {
+    utterances
+        .iter()
+        .map(|utterance| {
+            let content = std::fs::read_to_string(audio_session::sidecar_path(&session_dir.join(&utterance.file_name))).ok()?;
+            let sidecar: UtteranceSidecar = serde_json::from_str(&content).ok()?;
+            chrono::DateTime::parse_from_rfc3339(&sidecar.started_at).ok()
+        })
+        .collect()
+}
+
+fn offsets_from(origin: Timestamp, started_at: Vec<Option<Timestamp>>) -> Vec<Option<u64>> {
+    started_at
+        .into_iter()
+        .map(|time| time.map(|time| (time - origin).num_milliseconds().max(0) as u64))
+        .collect()
+}
+
+/// Start of each utterance from the beginning of the session, read from the sidecars
+/// (None without sidecar)
+pub fn sidecar_offsets(session_dir: &Path, utterances: &[SessionUtterance]) -> Vec<Option<u64>> {
+    let started_at = sidecar_start_times(session_dir, utterances);
+    match started_at.iter().flatten().min().copied() {
+        Some(first) => offsets_from(first, started_at),
+        None => vec![None; utterances.len()],
+    }
+}
+
+/// Start of each utterance from the moment the recording started (the time in the session
+/// id), or from the first utterance when the id is not a start time
+pub fn recording_offsets(session_dir: &Path, session_id: &str, utterances: &[SessionUtterance]) -> Vec<Option<u64>> {
+    match recording_session::session_started_at(session_id) {
+        Some(origin) => offsets_from(origin, sidecar_start_times(session_dir, utterances)),
+        None => sidecar_offsets(session_dir, utterances),
+    }
+}
+
+/// One transcribed utterance placed on the recording timeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub utterance_id: usize,
+    /// From the start of the recording
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<AudioSource>,
+}
+
+/// Transcript of a whole session in recording order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionTranscript {
+    pub session_id: String,
+    pub entries: Vec<TimelineEntry>,
+    /// Entries joined as the session transcript (source labels and language tags included)
+    pub text: String,
+    /// False while the recording or its transcription is still running
+    pub complete: bool,
+}
+
+/// Transcribed utterances sorted by their start on the recording timeline. Utterances
+/// without offset are placed right after the previous one; those without text are left out.
+pub fn session_transcript(session_id: &str, utterances: &[SessionUtterance], offsets_ms: &[Option<u64>], complete: bool) -> SessionTranscript {
+    let mut cursor = 0;
+    let mut placed: Vec<(u64, &SessionUtterance)> = Vec::new();
+    for (index, utterance) in utterances.iter().enumerate() {
+        let start = offsets_ms.get(index).copied().flatten().unwrap_or(cursor);
+        cursor = start + utterance.duration_ms as u64;
+        if !utterance.text.trim().is_empty() {
+            placed.push((start, utterance));
+        }
+    }
+    // Stable: utterances of two sources starting together keep their id order
+    placed.sort_by_key(|(start, _)| *start);
+
+    let ordered: Vec<SessionUtterance> = placed.iter().map(|(_, utterance)| (*utterance).clone()).collect();
+    SessionTranscript {
+        session_id: session_id.to_string(),
+        entries: placed
+            .into_iter()
+            .map(|(start, utterance)| TimelineEntry {
+                utterance_id: utterance.id,
+                start_ms: start,
+                end_ms: start + utterance.duration_ms as u64,
+                text: utterance.text.trim().to_string(),
+                language: utterance.language.clone(),
+                source: utterance.source,
+            })
+            .collect(),
+        text: recording_session::transcript_of(&ordered),
+        complete,
+    }
+}
+
+/// Cues of a session: utterances are placed at their offset, or right after the
+/// previous one when it is unknown
+pub fn session_cues(utterances: &[SessionUtterance], offsets_ms: &[Option<u64>]) -> Vec<Cue> {
+    let mut cursor = 0;
+    let mut all = Vec::new();
+    for (index, utterance) in utterances.iter().enumerate() {
+        let start = offsets_ms.get(index).copied().flatten().unwrap_or(cursor);
+        all.extend(cues(&utterance.segments, &utterance.text, utterance.duration_ms as u64, start));
+        cursor = start + utterance.duration_ms as u64;
+    }
+    all
+}
+
+fn timestamp(ms: u64, separator: char) -> String {
+    format!("{:02}:{:02}:{:02}{}{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, separator, ms % 1000)
+}
+
+/// A blank line ends a cue and "-->" is reserved in WebVTT
+fn cue_text(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace("-->", "->")
+}
+
+pub fn render(format: TranscriptFormat, cues: &[Cue]) -> String {
+    let mut out = String::new();
+    match format {
+        TranscriptFormat::Srt => {
+            for (index, cue) in cues.iter().enumerate() {
+                out.push_str(&format!(
+                    "{}\n{} --> {}\n{}\n\n",
+                    index + 1,
+                    timestamp(cue.start_ms, ','),
+                    timestamp(cue.end_ms, ','),
+                    cue_text(&cue.text)
+                ));
+            }
+        }
+        TranscriptFormat::Vtt => {
+            out.push_str("WEBVTT\n\n");
+            for cue in cues {
+                out.push_str(&format!("{} --> {}\n{}\n\n", timestamp(cue.start_ms, '.'), timestamp(cue.end_ms, '.'), cue_text(&cue.text)));
+            }
+        }
+        TranscriptFormat::Markdown => {
+            out.push_str("# Transcription\n\n");
+            for cue in cues {
+                out.push_str(&format!("**[{}]** {}\n\n", &timestamp(cue.start_ms, '.')[..8], cue.text.trim()));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_subtitles() {
+        let segment = |start_ms, end_ms, text: &str| Segment { start_ms, end_ms, text: text.to_string(), language: None, words: Vec::new() };
+        let utterance = |id, duration_ms, text: &str, segments| SessionUtterance {
+            id,
+            file_name: format!("utterance_{:04}.wav", id),
+            duration_ms,
+            text: text.to_string(),
+            language: None,
+            segments,
+            source: None,
+        };
+        let utterances = [
+            utterance(1, 4000, "Le client passe commande. Elle a des lignes.", vec![segment(0, 2000, "Le client passe commande."), segment(2000, 3800, "Elle a des lignes.")]),
+            utterance(2, 1500, "Une quantité --> un prix.", Vec::new()),
+            utterance(3, 1000, "", Vec::new()),
+        ];
+        // The second utterance started 65 s into the session
+        let cues = session_cues(&utterances, &[Some(0), Some(65_000), None]);
+        assert_eq!(cues.len(), 3);
+        assert_eq!((cues[2].start_ms, cues[2].end_ms), (65_000, 66_500));
+
+        let srt = render(TranscriptFormat::Srt, &cues);
+        assert!(srt.starts_with("1\n00:00:00,000 --> 00:00:02,000\nLe client passe commande.\n\n2\n"));
+        assert!(srt.contains("3\n00:01:05,000 --> 00:01:06,500\nUne quantité -> un prix.\n"));
+        let vtt = render(TranscriptFormat::Vtt, &cues);
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:02.000\n"));
+        assert!(render(TranscriptFormat::Markdown, &cues).contains("**[00:01:05]** Une quantité --> un prix."));
+
+        assert_eq!(TranscriptFormat::from_path(Path::new("notes.vtt")).unwrap(), TranscriptFormat::Vtt);
+        assert!("docx".parse::<TranscriptFormat>().is_err());
+    }
+
+    #[test]
+    fn test_session_transcript_follows_recording_timeline() {
+        let utterance = |id, duration_ms, text: &str, source| SessionUtterance {
+            id,
+            file_name: format!("utterance_{:04}.wav", id),
+            duration_ms,
+            text: text.to_string(),
+            language: None,
+            segments: Vec::new(),
+            source: Some(source),
+        };
+        // Closed in this order by the VAD, but the system audio utterance started first
+        let utterances = [
+            utterance(1, 3000, "Comment une commande est-elle validée ?", AudioSource::Mic),
+            utterance(2, 500, " ", AudioSource::Mic),
+            utterance(3, 4000, "Par le superviseur.", AudioSource::System),
+            utterance(4, 1000, "Merci.", AudioSource::Mic),
+        ];
+        let transcript = session_transcript("20250101_100000", &utterances, &[Some(12_000), None, Some(8_000), Some(15_500)], true);
+
+        let timeline: Vec<(usize, u64, u64)> = transcript.entries.iter().map(|e| (e.utterance_id, e.start_ms, e.end_ms)).collect();
+        assert_eq!(timeline, vec![(3, 8_000, 12_000), (1, 12_000, 15_000), (4, 15_500, 16_500)]);
+        assert_eq!(transcript.text, "Système: Par le superviseur.\nMicro: Comment une commande est-elle validée ?\nMicro: Merci.");
+
+        let origin = recording_session::session_started_at("20250101_100000").unwrap();
+        assert_eq!(offsets_from(origin, vec![Some(origin + chrono::Duration::milliseconds(1500)), None]), vec![Some(1500), None]);
+    }
+}
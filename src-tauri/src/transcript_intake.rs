@@ -0,0 +1,265 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::safe_write;
+
+/// Custom URL scheme handled by the app (`whispeer://import?src=...`)
+pub const URL_SCHEME: &str = "whispeer";
+
+/// Transcripts above this size are refused (a meeting transcript is a few hundred KB)
+const MAX_TRANSCRIPT_BYTES: usize = 5 * 1024 * 1024;
+
+/// Whole download of a transcript URL, so that a stalled server cannot hang the import
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where the text of an import link comes from. Local files are not reachable from a
+/// link: any web page can open one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ImportSource {
+    Text(String),
+    Url(String),
+}
+
+/// A `whispeer://import` request, run only once the user confirmed it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportRequest {
+    pub source: ImportSource,
+    pub project_name: Option<String>,
+}
+
+/// A transcript imported into a new project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptImport {
+    pub project_name: String,
+    pub transcript: String,
+    /// Stored copy of the cleaned transcript
+    pub path: String,
+}
+
+/// Parse `whispeer://import?src=<http(s) URL>&project=<name>` (or `text=<inline
+/// transcript>` instead of `src`)
+pub fn parse_deep_link(link: &str) -> Result<ImportRequest> {
+    let url = reqwest::Url::parse(link).with_context(|| format!("Invalid link '{}'", link))?;
+    if url.scheme() != URL_SCHEME {
+        anyhow::bail!("Unsupported scheme '{}'", url.scheme());
+    }
+    // whispeer://import parses "import" as the host, whispeer:import as the path
+    let action = url.host_str().unwrap_or_else(|| url.path().trim_matches('/'));
+    if action != "import" {
+        anyhow::bail!("Unsupported action '{}'", action);
+    }
+
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+            .filter(|value| !value.trim().is_empty())
+    };
+
+    let source = match (param("src"), param("text")) {
+        (Some(src), _) if src.starts_with("http://") || src.starts_with("https://") => ImportSource::Url(src),
+        (Some(src), _) => anyhow::bail!("Import links only accept http(s) URLs, not '{}': paste the transcript instead", src),
+        (None, Some(text)) => ImportSource::Text(text),
+        (None, None) => anyhow::bail!("Import link needs a 'src' or 'text' parameter"),
+    };
+
+    Ok(ImportRequest { source, project_name: param("project") })
+}
+
+/// Read the text of an import
+pub async fn load_source(source: &ImportSource) -> Result<String> {
+    let text = match source {
+        ImportSource::Text(text) => text.clone(),
+        ImportSource::Url(url) => {
+            let client = reqwest::Client::builder()
+                .timeout(DOWNLOAD_TIMEOUT)
+                .build()
+                .context("Failed to create HTTP client")?;
+            let mut response = client.get(url).send().await.with_context(|| format!("Failed to download {}", url))?;
+            if !response.status().is_success() {
+                anyhow::bail!("Download of {} failed: {}", url, response.status());
+            }
+            if let Some(length) = response.content_length().filter(|length| *length > MAX_TRANSCRIPT_BYTES as u64) {
+                anyhow::bail!("Transcript too large ({} bytes, max {})", length, MAX_TRANSCRIPT_BYTES);
+            }
+            // Read chunk by chunk: the announced length may be missing or wrong
+            let mut body = Vec::new();
+            while let Some(chunk) = response.chunk().await.context("Failed to read downloaded transcript")? {
+                if body.len() + chunk.len() > MAX_TRANSCRIPT_BYTES {
+                    anyhow::bail!("Transcript too large (more than {} bytes)", MAX_TRANSCRIPT_BYTES);
+                }
+                body.extend_from_slice(&chunk);
+            }
+            String::from_utf8(body).context("Downloaded transcript is not UTF-8 text")?
+        }
+    };
+    if text.len() > MAX_TRANSCRIPT_BYTES {
+        anyhow::bail!("Transcript too large ({} bytes, max {})", text.len(), MAX_TRANSCRIPT_BYTES);
+    }
+    Ok(text)
+}
+
+fn is_timestamp(line: &str) -> bool {
+    !line.is_empty() && line.chars().all(|c| c.is_ascii_digit() || c == ':' || c == '.' || c == ',')
+        && line.contains(':')
+}
+
+/// "Speaker 1  0:03" / "Jane Doe 01:02:03" headers of Otter exports
+fn otter_speaker(line: &str) -> Option<&str> {
+    let (speaker, time) = line.trim_end().rsplit_once(char::is_whitespace)?;
+    let speaker = speaker.trim();
+    (is_timestamp(time) && !speaker.is_empty() && speaker.len() <= 60).then_some(speaker)
+}
+
+/// Clean a pasted or downloaded transcript into "Speaker: text" lines.
+///
+/// Handles WebVTT (Teams download: cue ids, timings, `<v Name>` voice tags),
+/// Otter text exports ("Name  0:03" headers) and plain text. Consecutive
+/// lines of the same speaker are merged.
+pub fn clean_transcript(raw: &str) -> String {
+    let raw = raw.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let is_vtt = raw.trim_start().starts_with("WEBVTT");
+    let mut turns: Vec<(Option<String>, String)> = Vec::new();
+    let mut speaker: Option<String> = None;
+    let mut in_note = false;
+
+    let mut push = |speaker: Option<String>, text: &str| {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            return;
+        }
+        match turns.last_mut() {
+            Some((last, content)) if *last == speaker => {
+                content.push(' ');
+                content.push_str(&text);
+            }
+            _ => turns.push((speaker, text)),
+        }
+    };
+
+    for line in raw.lines() {
+        let line = line.trim();
+        if is_vtt {
+            if line.is_empty() {
+                in_note = false;
+                continue;
+            }
+            if line.starts_with("WEBVTT") || line.starts_with("NOTE") || in_note {
+                in_note = in_note || line.starts_with("NOTE");
+                continue;
+            }
+            // Cue timing line, or a cue identifier right before one
+            if line.contains("-->") || line.chars().all(|c| c.is_ascii_digit()) || (line.len() == 36 && line.matches('-').count() == 4) {
+                continue;
+            }
+            // <v Jane Doe>Hello</v>
+            if let Some(rest) = line.strip_prefix("<v ") {
+                if let Some((name, text)) = rest.split_once('>') {
+                    let text = text.replace("</v>", "");
+                    push(Some(name.trim().to_string()), &text);
+                    continue;
+                }
+            }
+            push(speaker.clone(), line);
+            continue;
+        }
+
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = otter_speaker(line) {
+            speaker = Some(name.to_string());
+            continue;
+        }
+        push(speaker.clone(), line);
+    }
+
+    turns
+        .into_iter()
+        .map(|(speaker, text)| match speaker {
+            Some(speaker) => format!("{}: {}", speaker, text),
+            None => text,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Project name for an import that did not name one
+pub fn default_project_name() -> String {
+    format!("Import {}", chrono::Local::now().format("%Y-%m-%d %H-%M"))
+}
+
+/// Store the transcript as `<app data>/transcripts/<project>.txt`
+pub fn store_transcript(app_data_dir: &Path, project_name: &str, transcript: &str) -> Result<PathBuf> {
+    let sanitized_name = crate::sanitize_project_name(project_name);
+    let dir = app_data_dir.join("transcripts");
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let path = dir.join(format!("{}.txt", sanitized_name));
+    safe_write::write(&path, transcript)?;
+    Ok(path)
+}
+
+/// Clean `raw` and store it as the transcript of a new project
+pub fn import_transcript(app_data_dir: &Path, raw: &str, project_name: Option<String>) -> Result<TranscriptImport> {
+    let transcript = clean_transcript(raw);
+    if transcript.is_empty() {
+        anyhow::bail!("The transcript is empty");
+    }
+    let project_name = project_name.unwrap_or_else(default_project_name);
+    let path = store_transcript(app_data_dir, &project_name, &transcript)?;
+    Ok(TranscriptImport { project_name, transcript, path: path.display().to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deep_link() {
+        let request = parse_deep_link("whispeer://import?src=https%3A%2F%2Fexample.com%2Fmeeting.vtt&project=Atelier%20commandes").unwrap();
+        assert_eq!(request.source, ImportSource::Url("https://example.com/meeting.vtt".to_string()));
+        assert_eq!(request.project_name.as_deref(), Some("Atelier commandes"));
+
+        let request = parse_deep_link("whispeer://import?text=Le%20client%20passe%20commande").unwrap();
+        assert_eq!(request.source, ImportSource::Text("Le client passe commande".to_string()));
+        assert_eq!(request.project_name, None);
+
+        // A web page must not get a local file read through a link
+        assert!(parse_deep_link("whispeer://import?src=file%3A%2F%2F%2Fetc%2Fpasswd").is_err());
+        assert!(parse_deep_link("whispeer://import?src=%2Fhome%2Fme%2F.ssh%2Fid_rsa").is_err());
+
+        assert!(parse_deep_link("whispeer://import").is_err());
+        assert!(parse_deep_link("whispeer://delete?src=/tmp/x").is_err());
+        assert!(parse_deep_link("https://import?src=/tmp/x").is_err());
+    }
+
+    #[test]
+    fn test_clean_teams_vtt() {
+        let vtt = "WEBVTT\n\nNOTE exported from Teams\n\n1\n00:00:01.000 --> 00:00:04.000\n<v Alice Martin>Un client passe une commande.</v>\n\n2\n00:00:04.500 --> 00:00:06.000\n<v Alice Martin>Elle contient des lignes.</v>\n\n3\n00:00:06.000 --> 00:00:08.000\n<v Bob>Et le paiement ?</v>\n";
+        assert_eq!(
+            clean_transcript(vtt),
+            "Alice Martin: Un client passe une commande. Elle contient des lignes.\nBob: Et le paiement ?"
+        );
+    }
+
+    #[test]
+    fn test_clean_otter_and_plain_text() {
+        let otter = "Speaker 1  0:03\nOn gère des abonnements.\n\nSpeaker 2  0:10\nMensuels\nou annuels ?\n";
+        assert_eq!(clean_transcript(otter), "Speaker 1: On gère des abonnements.\nSpeaker 2: Mensuels ou annuels ?");
+        assert_eq!(clean_transcript("  Un livre   a un auteur.\r\n"), "Un livre a un auteur.");
+    }
+
+    #[test]
+    fn test_import_transcript_stores_text() {
+        let app_data = std::env::temp_dir().join(format!("intake_test_{}", std::process::id()));
+        let import = import_transcript(&app_data, "Bob  0:01\nBonjour", Some("Mon atelier".to_string())).unwrap();
+        assert_eq!(import.transcript, "Bob: Bonjour");
+        assert_eq!(fs::read_to_string(app_data.join("transcripts/Mon_atelier.txt")).unwrap(), "Bob: Bonjour");
+        assert!(import_transcript(&app_data, "  \n", None).is_err());
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
@@ -0,0 +1,552 @@
+//! Spoken numbers, ordinals and dates rewritten into canonical forms ("vingt-trois" ->
+//! "23", "le trois mars deux mille vingt-cinq" -> "le 2025-03-03", "the fourth line" ->
+//! "the 4th line") before a transcript reaches the LLM. French and English; text in
+//! other languages is left as is.
+
+use crate::recording_session::language_tag;
+use crate::speech_to_text::AUTO_LANGUAGE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    Fr,
+    En,
+}
+
+impl Lang {
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "fr" => Some(Self::Fr),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// 0-9
+    Unit,
+    /// 10-19
+    Teen,
+    /// 20, 30... 90
+    Ten,
+    Hundred,
+    /// thousand, million, billion
+    Scale,
+}
+
+fn cardinal(lang: Lang, word: &str) -> Option<(u64, Kind)> {
+    use Kind::*;
+    let entry = match lang {
+        Lang::Fr => match word {
+            "zéro" => (0, Unit),
+            "un" | "une" => (1, Unit),
+            "deux" => (2, Unit),
+            "trois" => (3, Unit),
+            "quatre" => (4, Unit),
+            "cinq" => (5, Unit),
+            "six" => (6, Unit),
+            "sept" => (7, Unit),
+            "huit" => (8, Unit),
+            "neuf" => (9, Unit),
+            "dix" => (10, Teen),
+            "onze" => (11, Teen),
+            "douze" => (12, Teen),
+            "treize" => (13, Teen),
+            "quatorze" => (14, Teen),
+            "quinze" => (15, Teen),
+            "seize" => (16, Teen),
+            "vingt" | "vingts" => (20, Ten),
+            "trente" => (30, Ten),
+            "quarante" => (40, Ten),
+            "cinquante" => (50, Ten),
+            "soixante" => (60, Ten),
+            "cent" | "cents" => (100, Hundred),
+            "mille" => (1_000, Scale),
+            "million" | "millions" => (1_000_000, Scale),
+            "milliard" | "milliards" => (1_000_000_000, Scale),
+            _ => return None,
+        },
+        Lang::En => match word {
+            "zero" => (0, Unit),
+            "one" => (1, Unit),
+            "two" => (2, Unit),
+            "three" => (3, Unit),
+            "four" => (4, Unit),
+            "five" => (5, Unit),
+            "six" => (6, Unit),
+            "seven" => (7, Unit),
+            "eight" => (8, Unit),
+            "nine" => (9, Unit),
+            "ten" => (10, Teen),
+            "eleven" => (11, Teen),
+            "twelve" => (12, Teen),
+            "thirteen" => (13, Teen),
+            "fourteen" => (14, Teen),
+            "fifteen" => (15, Teen),
+            "sixteen" => (16, Teen),
+            "seventeen" => (17, Teen),
+            "eighteen" => (18, Teen),
+            "nineteen" => (19, Teen),
+            "twenty" => (20, Ten),
+            "thirty" => (30, Ten),
+            "forty" => (40, Ten),
+            "fifty" => (50, Ten),
+            "sixty" => (60, Ten),
+            "seventy" => (70, Ten),
+            "eighty" => (80, Ten),
+            "ninety" => (90, Ten),
+            "hundred" => (100, Hundred),
+            "thousand" => (1_000, Scale),
+            "million" => (1_000_000, Scale),
+            "billion" => (1_000_000_000, Scale),
+            _ => return None,
+        },
+    };
+    Some(entry)
+}
+
+/// Ordinal word as its cardinal value and French suffix ("er", "re", "e"; empty in
+/// English, where the suffix depends on the value)
+fn ordinal(lang: Lang, word: &str) -> Option<(u64, Kind, &'static str)> {
+    match lang {
+        Lang::Fr => {
+            match word {
+                "premier" => return Some((1, Kind::Unit, "er")),
+                "première" => return Some((1, Kind::Unit, "re")),
+                _ => {}
+            }
+            let stem = word.strip_suffix("ième")?;
+            let (value, kind) = match stem {
+                "cinqu" => cardinal(lang, "cinq"),
+                "neuv" => cardinal(lang, "neuf"),
+                _ => cardinal(lang, stem).or_else(|| cardinal(lang, &format!("{}e", stem))),
+            }?;
+            Some((value, kind, "e"))
+        }
+        Lang::En => {
+            let (value, kind) = match word {
+                "first" => (1, Kind::Unit),
+                "second" => (2, Kind::Unit),
+                "third" => (3, Kind::Unit),
+                "fifth" => (5, Kind::Unit),
+                "eighth" => (8, Kind::Unit),
+                "ninth" => (9, Kind::Unit),
+                "twelfth" => (12, Kind::Teen),
+                _ => {
+                    let stem = word.strip_suffix("th")?;
+                    match stem.strip_suffix("ie") {
+                        // twentieth -> twenty
+                        Some(tens) => cardinal(lang, &format!("{}y", tens)),
+                        None => cardinal(lang, stem),
+                    }?
+                }
+            };
+            Some((value, kind, ""))
+        }
+    }
+}
+
+fn english_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// Words left alone unless they are part of a date or a percentage ("une commande",
+/// "un bâtiment neuf", "the one", "a second")
+fn is_weak(lang: Lang, word: &str) -> bool {
+    match lang {
+        Lang::Fr => matches!(word, "un" | "une" | "neuf" | "premier" | "première"),
+        Lang::En => matches!(word, "one" | "first" | "second" | "third"),
+    }
+}
+
+fn is_connector(lang: Lang, word: &str) -> bool {
+    match lang {
+        Lang::Fr => word == "et",
+        Lang::En => word == "and",
+    }
+}
+
+const FR_MONTHS: [&str; 12] = ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"];
+const EN_MONTHS: [&str; 12] = ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"];
+
+/// Month number (1-12)
+fn month(lang: Lang, word: &str) -> Option<usize> {
+    let index = match lang {
+        Lang::Fr => FR_MONTHS.iter().position(|month| *month == word).or(match word {
+            "fevrier" => Some(1),
+            "aout" => Some(7),
+            "decembre" => Some(11),
+            _ => None,
+        }),
+        Lang::En => EN_MONTHS.iter().position(|month| month.eq_ignore_ascii_case(word)),
+    };
+    index.map(|index| index + 1)
+}
+
+/// Value of the number words read so far
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    total: u64,
+    current: u64,
+    last: Option<(Kind, u64)>,
+    /// "et"/"and" read, waiting for the next word
+    connector: bool,
+}
+
+impl Accumulator {
+    /// Add a number word, false if it cannot follow the previous ones ("twenty
+    /// twenty-five" is two numbers)
+    fn push(&mut self, lang: Lang, value: u64, kind: Kind) -> bool {
+        use Kind::*;
+        let last = self.last;
+        if self.connector {
+            self.connector = false;
+            // "vingt et un", "soixante et onze", "two hundred and five"
+            let valid = match lang {
+                Lang::Fr => matches!(last, Some((Ten, _))) && (value == 1 || value == 11),
+                Lang::En => matches!(last, Some((Hundred | Scale, _))) && matches!(kind, Unit | Teen | Ten),
+            };
+            if !valid {
+                return false;
+            }
+        }
+        // quatre-vingt(s)
+        let eighty = lang == Lang::Fr && kind == Ten && value == 20 && last == Some((Unit, 4));
+        let valid = match (kind, last) {
+            (_, None) => true,
+            (_, Some((Scale, _))) if kind != Scale => true,
+            (Unit | Teen | Ten, Some((Hundred, _))) => true,
+            (Unit, Some((Ten, _))) => value > 0,
+            // dix-sept, soixante-dix-huit
+            (Unit, Some((Teen, 10))) => lang == Lang::Fr && value >= 7,
+            // soixante-douze, quatre-vingt-dix
+            (Teen, Some((Ten, ten))) => lang == Lang::Fr && (ten == 60 || ten == 80),
+            (Ten, _) => eighty,
+            (Hundred, Some((Unit, unit))) => self.current < 10 && (unit >= 2 || lang == Lang::En),
+            (Scale, Some((previous, _))) => previous != Scale,
+            _ => false,
+        };
+        if !valid {
+            return false;
+        }
+        match kind {
+            Hundred => self.current = self.current.max(1) * 100,
+            Scale => {
+                self.total += self.current.max(1) * value;
+                self.current = 0;
+            }
+            _ if eighty => self.current += 76,
+            _ => self.current += value,
+        }
+        self.last = Some((kind, if eighty { 80 } else { value }));
+        true
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+}
+
+/// Word of the transcript with the punctuation around it
+struct Token<'a> {
+    raw: &'a str,
+    lead: &'a str,
+    core: &'a str,
+    trail: &'a str,
+    /// Lowercase core
+    word: String,
+}
+
+fn token(raw: &str) -> Token<'_> {
+    let start = raw.find(char::is_alphanumeric).unwrap_or(raw.len());
+    let end = raw
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_alphanumeric())
+        .map(|(index, c)| index + c.len_utf8())
+        .unwrap_or(start)
+        .max(start);
+    Token {
+        raw,
+        lead: &raw[..start],
+        core: &raw[start..end],
+        trail: &raw[end..],
+        word: raw[start..end].to_lowercase(),
+    }
+}
+
+/// Number read from tokens `start..end`
+struct NumberPhrase {
+    value: u64,
+    /// Suffix of an ordinal ("e", "er", "th"...)
+    ordinal: Option<&'static str>,
+    end: usize,
+    /// Already in digits ("3", "2025", "1er")
+    digits: bool,
+    weak: bool,
+}
+
+impl NumberPhrase {
+    fn render(&self) -> String {
+        match self.ordinal {
+            Some("") => format!("{}{}", self.value, english_suffix(self.value)),
+            Some(suffix) => format!("{}{}", self.value, suffix),
+            None => self.value.to_string(),
+        }
+    }
+}
+
+/// "3", "2025", "3rd", "1er"
+fn digits(lang: Lang, word: &str) -> Option<(u64, Option<&'static str>)> {
+    let count = word.chars().take_while(char::is_ascii_digit).count();
+    if count == 0 || count > 12 {
+        return None;
+    }
+    let value = word[..count].parse().ok()?;
+    let suffix = match (lang, &word[count..]) {
+        (_, "") => None,
+        (Lang::Fr, "er") => Some("er"),
+        (Lang::Fr, "re") => Some("re"),
+        (Lang::Fr, "e" | "ème") => Some("e"),
+        (Lang::En, "st" | "nd" | "rd" | "th") => Some(""),
+        _ => return None,
+    };
+    Some((value, suffix))
+}
+
+/// Longest number starting at token `start`; it ends at punctuation or after an ordinal
+fn parse_number(lang: Lang, tokens: &[Token], start: usize) -> Option<NumberPhrase> {
+    let first = tokens.get(start)?;
+    if let Some((value, ordinal)) = digits(lang, &first.word) {
+        return Some(NumberPhrase { value, ordinal, end: start + 1, digits: true, weak: false });
+    }
+    let mut accumulator = Accumulator::default();
+    let mut phrase = None;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        if index > start && !token.lead.is_empty() {
+            break;
+        }
+        let mut next = accumulator.clone();
+        let mut suffix = None;
+        let mut valid = !token.word.is_empty();
+        for part in token.word.split('-') {
+            valid = suffix.is_none()
+                && if is_connector(lang, part) {
+                    let dangling = next.last.is_none() || next.connector;
+                    next.connector = true;
+                    !dangling
+                } else if let Some((value, kind, ordinal_suffix)) = ordinal(lang, part) {
+                    suffix = Some(ordinal_suffix);
+                    next.push(lang, value, kind)
+                } else if let Some((value, kind)) = cardinal(lang, part) {
+                    next.push(lang, value, kind)
+                } else {
+                    false
+                };
+            if !valid {
+                break;
+            }
+        }
+        if !valid {
+            break;
+        }
+        accumulator = next;
+        if !accumulator.connector {
+            phrase = Some(NumberPhrase {
+                value: accumulator.value(),
+                ordinal: suffix,
+                end: index + 1,
+                digits: false,
+                weak: index == start && is_weak(lang, &token.word),
+            });
+        }
+        if suffix.is_some() || !token.trail.is_empty() {
+            break;
+        }
+    }
+    phrase
+}
+
+/// Year starting at token `start`: 1000-2999, or "nineteen ninety" in English
+fn parse_year(lang: Lang, tokens: &[Token], start: usize) -> Option<(u64, usize)> {
+    let first = parse_number(lang, tokens, start).filter(|number| number.ordinal.is_none())?;
+    if (1000..=2999).contains(&first.value) {
+        return Some((first.value, first.end));
+    }
+    if lang == Lang::En && !first.digits && (10..=29).contains(&first.value) && tokens[first.end - 1].trail.is_empty() {
+        let second = parse_number(lang, tokens, first.end).filter(|number| number.ordinal.is_none() && !number.digits && number.value < 100)?;
+        return Some((first.value * 100 + second.value, second.end));
+    }
+    None
+}
+
+fn render_date(lang: Lang, day: &NumberPhrase, month: usize, year: Option<u64>) -> String {
+    match (year, lang) {
+        (Some(year), _) => format!("{:04}-{:02}-{:02}", year, month, day.value),
+        (None, Lang::Fr) => format!("{}{} {}", day.value, if day.value == 1 { "er" } else { "" }, FR_MONTHS[month - 1]),
+        (None, Lang::En) => format!("{} {}", EN_MONTHS[month - 1], day.value),
+    }
+}
+
+/// Date whose day is `day`: "trois mars [2025]", "third of March [2025]"; returns the
+/// rendered date and the token after it
+fn date_from_day(lang: Lang, tokens: &[Token], day: &NumberPhrase) -> Option<(String, usize)> {
+    if !(1..=31).contains(&day.value) || !tokens[day.end - 1].trail.is_empty() {
+        return None;
+    }
+    let mut index = day.end;
+    if lang == Lang::En && tokens.get(index).is_some_and(|token| token.word == "of") {
+        index += 1;
+    }
+    let month_token = tokens.get(index).filter(|token| token.lead.is_empty())?;
+    let month = month(lang, &month_token.word)?;
+    let year = if month_token.trail.is_empty() { parse_year(lang, tokens, index + 1) } else { None };
+    let end = year.map(|(_, end)| end).unwrap_or(index + 1);
+    Some((render_date(lang, day, month, year.map(|(year, _)| year)), end))
+}
+
+/// English date starting with the month: "March third, 2025"
+fn date_from_month(lang: Lang, tokens: &[Token], start: usize) -> Option<(String, usize)> {
+    // "we may two..." is not a date
+    if lang != Lang::En || !tokens[start].trail.is_empty() || tokens[start].core == "may" {
+        return None;
+    }
+    let month = month(lang, &tokens[start].word)?;
+    let day = parse_number(lang, tokens, start + 1).filter(|day| (1..=31).contains(&day.value))?;
+    if !tokens[start + 1].lead.is_empty() {
+        return None;
+    }
+    let day_trail = tokens[day.end - 1].trail;
+    let year = if day_trail.is_empty() || day_trail == "," { parse_year(lang, tokens, day.end) } else { None };
+    let end = year.map(|(_, end)| end).unwrap_or(day.end);
+    Some((render_date(lang, &day, month, year.map(|(year, _)| year)), end))
+}
+
+/// "vingt pour cent", "twenty percent"
+fn percent_end(lang: Lang, tokens: &[Token], number: &NumberPhrase) -> Option<usize> {
+    if !tokens[number.end - 1].trail.is_empty() || number.ordinal.is_some() {
+        return None;
+    }
+    let word = |offset: usize| tokens.get(number.end + offset).map(|token| token.word.as_str());
+    match lang {
+        Lang::Fr => (word(0) == Some("pour") && word(1) == Some("cent")).then_some(number.end + 2),
+        Lang::En if word(0) == Some("percent") => Some(number.end + 1),
+        Lang::En => (word(0) == Some("per") && word(1) == Some("cent")).then_some(number.end + 2),
+    }
+}
+
+fn normalize_tokens(lang: Lang, tokens: &[Token]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        let lead = tokens[index].lead;
+        if let Some((date, end)) = date_from_month(lang, tokens, index) {
+            out.push(format!("{}{}{}", lead, date, tokens[end - 1].trail));
+            index = end;
+            continue;
+        }
+        if let Some(number) = parse_number(lang, tokens, index) {
+            if let Some((date, end)) = date_from_day(lang, tokens, &number) {
+                // "the third of March" -> "March 3"
+                if lang == Lang::En && out.last().is_some_and(|word| word.eq_ignore_ascii_case("the")) {
+                    out.pop();
+                }
+                out.push(format!("{}{}{}", lead, date, tokens[end - 1].trail));
+                index = end;
+                continue;
+            }
+            if let Some(end) = percent_end(lang, tokens, &number) {
+                let separator = if lang == Lang::Fr { " " } else { "" };
+                out.push(format!("{}{}{}%{}", lead, number.render(), separator, tokens[end - 1].trail));
+                index = end;
+                continue;
+            }
+            if !number.digits && !number.weak {
+                out.push(format!("{}{}{}", lead, number.render(), tokens[number.end - 1].trail));
+                index = number.end;
+                continue;
+            }
+        }
+        out.push(tokens[index].raw.to_string());
+        index += 1;
+    }
+    out
+}
+
+/// Language of untagged text from its most common function words, None if unclear
+pub fn guess_language(text: &str) -> Option<&'static str> {
+    const FR: &[&str] = &["le", "la", "les", "de", "des", "du", "et", "est", "il", "elle", "que", "qui", "pour", "dans", "avec", "une"];
+    const EN: &[&str] = &["the", "and", "is", "are", "of", "to", "in", "that", "for", "with", "it", "has", "have", "this"];
+    let (mut fr, mut en) = (0, 0);
+    for word in text.split_whitespace().map(|word| token(word).word) {
+        fr += FR.contains(&word.as_str()) as usize;
+        en += EN.contains(&word.as_str()) as usize;
+    }
+    match fr.cmp(&en) {
+        std::cmp::Ordering::Greater => Some("fr"),
+        std::cmp::Ordering::Less => Some("en"),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Normalize a transcript in `language` ("auto" or None: guessed from the text).
+/// `[xx]` language tags switch the language of the following words; lines without
+/// spoken numbers are kept as they are.
+pub fn normalize_transcript(transcript: &str, language: Option<&str>) -> String {
+    let language = language.filter(|language| *language != AUTO_LANGUAGE).or_else(|| guess_language(transcript));
+    let mut current = language.and_then(Lang::from_code);
+    let mut lines = Vec::new();
+    for line in transcript.lines() {
+        let tokens: Vec<Token> = line.split_whitespace().map(token).collect();
+        let mut out = Vec::with_capacity(tokens.len());
+        let mut run_start = 0;
+        for index in 0..=tokens.len() {
+            let tag = tokens.get(index).and_then(|token| language_tag(token.raw));
+            if index < tokens.len() && tag.is_none() {
+                continue;
+            }
+            let run = &tokens[run_start..index];
+            match current {
+                Some(lang) => out.extend(normalize_tokens(lang, run)),
+                None => out.extend(run.iter().map(|token| token.raw.to_string())),
+            }
+            if let Some(tag) = tag {
+                out.push(tokens[index].raw.to_string());
+                current = Lang::from_code(tag);
+            }
+            run_start = index + 1;
+        }
+        let changed = out.len() != tokens.len() || out.iter().zip(&tokens).any(|(word, token)| word != token.raw);
+        lines.push(if changed { out.join(" ") } else { line.to_string() });
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_numbers_and_dates() {
+        let transcript = "Le client a vingt-trois commandes depuis le trois mars deux mille vingt-cinq, soit quatre-vingt-dix-sept pour cent des ventes.\n\
+                          Une commande a un premier article et soixante et onze lignes au plus ; la troisième est facultative.\n\
+                          [en] The third of March twenty twenty-five, two hundred and five orders were placed; the fourth line has one item.\n\
+                          Invoices are due on March first.";
+        let normalized = normalize_transcript(transcript, Some("fr"));
+        let lines: Vec<&str> = normalized.lines().collect();
+        assert_eq!(lines[0], "Le client a 23 commandes depuis le 2025-03-03, soit 97 % des ventes.");
+        assert_eq!(lines[1], "Une commande a un premier article et 71 lignes au plus ; la 3e est facultative.");
+        assert_eq!(lines[2], "[en] 2025-03-03, 205 orders were placed; the 4th line has one item.");
+        assert_eq!(lines[3], "Invoices are due on March 1.");
+
+        assert_eq!(normalize_transcript("Il y a deux cent mille clients, livrés le premier juin", None), "Il y a 200000 clients, livrés le 1er juin");
+        assert_eq!(normalize_transcript("Das Lager hat zwei Tore", Some("de")), "Das Lager hat zwei Tore");
+    }
+}
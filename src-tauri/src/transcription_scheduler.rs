@@ -0,0 +1,251 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::Mutex;
+
+use crate::audio_session::TARGET_SAMPLE_RATE;
+use crate::speech_to_text::{Cancelled, CancellationToken, Segment, SpeechToText, TranscribeOptions, TranscriptionResult, AUTO_LANGUAGE};
+
+/// Utterances shorter than this are merged with their neighbours on the GPU
+const SHORT_UTTERANCE_MS: u32 = 8_000;
+/// Audio per batch, under the 30 s window Whisper decodes in one pass
+const MAX_BATCH_MS: u32 = 25_000;
+/// Silence between merged utterances, so that Whisper closes a segment at each boundary
+const BATCH_GAP_MS: u32 = 600;
+
+/// Counters of the GPU scheduler since startup, reported by `get_whisper_backend`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerMetrics {
+    /// Whisper runs
+    pub batches: u64,
+    pub utterances: u64,
+    /// Utterances that shared a run with others
+    pub merged_utterances: u64,
+    /// Audio transcribed
+    pub audio_ms: u64,
+    /// Inference time
+    pub busy_ms: u64,
+}
+
+static METRICS: Mutex<SchedulerMetrics> = Mutex::new(SchedulerMetrics {
+    batches: 0,
+    utterances: 0,
+    merged_utterances: 0,
+    audio_ms: 0,
+    busy_ms: 0,
+});
+
+pub fn metrics() -> SchedulerMetrics {
+    METRICS.lock().unwrap().clone()
+}
+
+fn record(utterances: usize, audio_ms: u64, busy_ms: u64) {
+    let mut metrics = METRICS.lock().unwrap();
+    metrics.batches += 1;
+    metrics.utterances += utterances as u64;
+    if utterances > 1 {
+        metrics.merged_utterances += utterances as u64;
+    }
+    metrics.audio_ms += audio_ms;
+    metrics.busy_ms += busy_ms;
+}
+
+/// Consecutive utterances sent to Whisper together: short ones are packed up to
+/// MAX_BATCH_MS (gaps included), long ones go alone. Order is kept.
+pub fn plan_batches(durations_ms: &[u32]) -> Vec<Range<usize>> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut total_ms = 0;
+    for (index, &duration) in durations_ms.iter().enumerate() {
+        let fits = total_ms + BATCH_GAP_MS + duration <= MAX_BATCH_MS;
+        if index > start && (duration >= SHORT_UTTERANCE_MS || !fits) {
+            batches.push(start..index);
+            start = index;
+            total_ms = 0;
+        }
+        if index > start {
+            total_ms += BATCH_GAP_MS;
+        }
+        total_ms += duration;
+        if duration >= SHORT_UTTERANCE_MS {
+            batches.push(index..index + 1);
+            start = index + 1;
+            total_ms = 0;
+        }
+    }
+    if start < durations_ms.len() {
+        batches.push(start..durations_ms.len());
+    }
+    batches
+}
+
+/// Clips joined with BATCH_GAP_MS of silence, and where each one starts and ends (ms)
+fn merge(clips: &[Vec<f32>]) -> (Vec<f32>, Vec<Range<i64>>) {
+    let gap = vec![0.0; (TARGET_SAMPLE_RATE * BATCH_GAP_MS / 1000) as usize];
+    let to_ms = |samples: usize| (samples as u64 * 1000 / TARGET_SAMPLE_RATE as u64) as i64;
+    let mut audio = Vec::new();
+    let mut spans = Vec::new();
+    for (index, clip) in clips.iter().enumerate() {
+        if index > 0 {
+            audio.extend_from_slice(&gap);
+        }
+        let start = to_ms(audio.len());
+        audio.extend_from_slice(clip);
+        spans.push(start..to_ms(audio.len()));
+    }
+    (audio, spans)
+}
+
+/// Give each segment of a merged run back to the clip holding its midpoint, with times
+/// relative to that clip
+fn split(result: TranscriptionResult, spans: &[Range<i64>]) -> Vec<TranscriptionResult> {
+    let mut results: Vec<TranscriptionResult> = spans
+        .iter()
+        .map(|_| TranscriptionResult {
+            text: String::new(),
+            language: result.language.clone(),
+            duration_ms: result.duration_ms,
+            segments: Vec::new(),
+            confidence: result.confidence,
+            provider: result.provider.clone(),
+        })
+        .collect();
+    for segment in result.segments {
+        let midpoint = (segment.start_ms + segment.end_ms) / 2;
+        // Midpoint in a gap: the closest clip before it
+        let index = spans.iter().rposition(|span| span.start <= midpoint).unwrap_or(0);
+        let offset = spans[index].start;
+        let shifted = Segment {
+            start_ms: (segment.start_ms - offset).max(0),
+            end_ms: (segment.end_ms - offset).max(0),
+            words: segment
+                .words
+                .into_iter()
+                .map(|mut word| {
+                    word.start_ms = (word.start_ms - offset).max(0);
+                    word.end_ms = (word.end_ms - offset).max(0);
+                    word
+                })
+                .collect(),
+            text: segment.text,
+            language: segment.language,
+        };
+        results[index].segments.push(shifted);
+    }
+    for result in &mut results {
+        result.text = result.segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ");
+    }
+    results
+}
+
+fn copy_error(e: &anyhow::Error) -> anyhow::Error {
+    if e.is::<Cancelled>() {
+        Cancelled.into()
+    } else {
+        anyhow::anyhow!("{:#}", e)
+    }
+}
+
+/// GPU transcription queue: `items` are transcribed batch by batch (see `plan_batches`),
+/// one Whisper run per batch on the single GPU state, and `done` gets each result in
+/// order. Once `cancellation` fires, the remaining items get None.
+pub fn transcribe_scheduled<T, L, D>(
+    stt: &SpeechToText,
+    items: &[T],
+    cancellation: &CancellationToken,
+    duration_ms: impl Fn(&T) -> u32,
+    load: L,
+    mut done: D,
+) where
+    L: Fn(&T) -> Result<Vec<f32>>,
+    D: FnMut(&T, Option<Result<TranscriptionResult>>),
+{
+    let durations: Vec<u32> = items.iter().map(&duration_ms).collect();
+    // Whisper detects one language per run: with "auto", each utterance keeps its own
+    let batches = if stt.language() == AUTO_LANGUAGE {
+        (0..items.len()).map(|index| index..index + 1).collect()
+    } else {
+        plan_batches(&durations)
+    };
+    log::info!("[Scheduler] {} utterances in {} GPU batches", items.len(), batches.len());
+
+    for batch in batches {
+        if cancellation.is_cancelled() {
+            for item in &items[batch] {
+                done(item, None);
+            }
+            continue;
+        }
+
+        // Unreadable clips fail alone, the others still share the run
+        let mut loaded = Vec::new();
+        let mut clips = Vec::new();
+        for index in batch {
+            match load(&items[index]) {
+                Ok(clip) => {
+                    loaded.push(index);
+                    clips.push(clip);
+                }
+                Err(e) => done(&items[index], Some(Err(e))),
+            }
+        }
+        if clips.is_empty() {
+            continue;
+        }
+
+        let (audio, spans) = merge(&clips);
+        let started = std::time::Instant::now();
+        let result = stt.transcribe_samples_with_options(&audio, &TranscribeOptions::default(), |_| {});
+        let audio_ms = spans.last().map(|span| span.end as u64).unwrap_or(0);
+        record(clips.len(), audio_ms, started.elapsed().as_millis() as u64);
+
+        match result {
+            Ok(result) => {
+                for (index, result) in loaded.into_iter().zip(split(result, &spans)) {
+                    done(&items[index], Some(Ok(result)));
+                }
+            }
+            Err(e) => {
+                for index in loaded {
+                    done(&items[index], Some(Err(copy_error(&e))));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_batches() {
+        // Short utterances packed under 25 s, the long one alone
+        assert_eq!(plan_batches(&[3000, 4000, 12000, 2000, 2000]), vec![0..2, 2..3, 3..5]);
+        assert_eq!(plan_batches(&[7000, 7000, 7000, 7000]), vec![0..3, 3..4]);
+        assert_eq!(plan_batches(&[9000]), vec![0..1]);
+        assert!(plan_batches(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_merge_and_split() {
+        let clips = vec![vec![0.1; 16000], vec![0.1; 8000]];
+        let (audio, spans) = merge(&clips);
+        assert_eq!(audio.len(), 16000 + 9600 + 8000);
+        assert_eq!(spans, vec![0..1000, 1600..2100]);
+
+        let segment = |start_ms, end_ms, text: &str| Segment { start_ms, end_ms, text: text.to_string(), language: None, words: Vec::new() };
+        let merged = TranscriptionResult {
+            text: "Bonjour à tous. Merci.".to_string(),
+            language: Some("fr".to_string()),
+            duration_ms: 120,
+            segments: vec![segment(0, 900, "Bonjour à tous."), segment(1600, 2050, "Merci.")],
+            confidence: None,
+            provider: None,
+        };
+        let results = split(merged, &spans);
+        assert_eq!(results[0].text, "Bonjour à tous.");
+        assert_eq!(results[1].text, "Merci.");
+        assert_eq!((results[1].segments[0].start_ms, results[1].segments[0].end_ms), (0, 450));
+    }
+}
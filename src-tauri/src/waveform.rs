@@ -0,0 +1,84 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::speech_to_text::read_wav_mono;
+
+/// Nombre de paires min/max par défaut, assez pour une forme d'onde en pleine largeur
+pub const DEFAULT_POINTS: usize = 1000;
+
+/// Borne haute du nombre de paires demandé par l'interface
+const MAX_POINTS: usize = 20_000;
+
+/// Forme d'onde réduite d'un enregistrement (ou d'un extrait) pour l'affichage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Waveform {
+    pub sample_rate: u32,
+    /// Début et fin de l'extrait couvert, en ms depuis le début du fichier
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Paires [min, max] dans [-1.0, 1.0], une par tranche de durée égale
+    pub peaks: Vec<[f32; 2]>,
+}
+
+/// Paires min/max de `samples` découpés en `points` tranches (moins si l'extrait est plus court)
+pub fn peaks(samples: &[f32], points: usize) -> Vec<[f32; 2]> {
+    if samples.is_empty() || points == 0 {
+        return Vec::new();
+    }
+    let points = points.min(samples.len());
+    (0..points)
+        .map(|i| {
+            // Bornes réparties sur toute la longueur, sans perdre les derniers samples
+            let bucket = &samples[i * samples.len() / points..(i + 1) * samples.len() / points];
+            bucket.iter().fold([f32::MAX, f32::MIN], |[min, max], &s| [min.min(s), max.max(s)])
+        })
+        .collect()
+}
+
+/// Forme d'onde d'un WAV enregistré, éventuellement limitée à un segment de transcription
+pub fn from_wav(path: &Path, points: usize, start_ms: Option<u64>, end_ms: Option<u64>) -> Result<Waveform> {
+    let (samples, sample_rate) = read_wav_mono(path)?;
+    let duration_ms = samples.len() as u64 * 1000 / sample_rate as u64;
+    let end_ms = end_ms.unwrap_or(duration_ms).min(duration_ms);
+    let start_ms = start_ms.unwrap_or(0).min(end_ms);
+    let index = |ms: u64| (ms * sample_rate as u64 / 1000) as usize;
+    let excerpt = &samples[index(start_ms)..index(end_ms).min(samples.len())];
+
+    Ok(Waveform {
+        sample_rate,
+        start_ms,
+        end_ms,
+        peaks: peaks(excerpt, points.clamp(1, MAX_POINTS)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peaks_of_segment() {
+        let dir = std::env::temp_dir().join(format!("waveform_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("utterance.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate: 16000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        // 1s de silence puis 1s de signal carré à mi-amplitude
+        for i in 0..32000 {
+            let sample = if i < 16000 { 0 } else if i % 2 == 0 { 16384 } else { -16384 };
+            writer.write_sample(sample as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let whole = from_wav(&path, 4, None, None).unwrap();
+        assert_eq!((whole.start_ms, whole.end_ms), (0, 2000));
+        assert_eq!(whole.peaks, vec![[0.0, 0.0], [0.0, 0.0], [-0.5, 0.5], [-0.5, 0.5]]);
+
+        let segment = from_wav(&path, 1000, Some(1500), Some(5000)).unwrap();
+        assert_eq!((segment.start_ms, segment.end_ms), (1500, 2000));
+        assert_eq!(segment.peaks.len(), 1000);
+        assert!(segment.peaks.iter().all(|p| *p == [-0.5, 0.5]));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
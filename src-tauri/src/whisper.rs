@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 use std::path::Path;
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
-pub fn transcribe_audio(model_path: &Path, audio_path: &Path) -> Result<String> {
+pub fn transcribe_audio(model_path: &Path, audio_path: &Path, language: &str) -> Result<String> {
     // Load the Whisper model
     let ctx = WhisperContext::new_with_params(
         model_path.to_str().context("Invalid model path")?,
@@ -10,8 +10,9 @@ pub fn transcribe_audio(model_path: &Path, audio_path: &Path) -> Result<String>
     )
     .context("Failed to load Whisper model")?;
 
-    // Read the audio file
-    let audio_data = load_audio(audio_path).context("Failed to load audio file")?;
+    // Read the audio file (WAV, MP3, M4A, FLAC..., converted to 16kHz mono f32 as Whisper expects)
+    let audio_data = domain_model_note_taking_lib::audio_decode::read_audio_16k(audio_path)
+        .context("Failed to load audio file")?;
 
     // Configure transcription parameters
     let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
@@ -20,7 +21,8 @@ pub fn transcribe_audio(model_path: &Path, audio_path: &Path) -> Result<String>
     params.set_print_progress(false);
     params.set_print_realtime(false);
     params.set_print_timestamps(false);
-    params.set_language(Some("fr")); // French by default, can be made configurable
+    // "auto" lets Whisper detect the language
+    params.set_language(if language == "auto" { None } else { Some(language) });
 
     // Create a state for transcription
     let mut state = ctx.create_state().context("Failed to create whisper state")?;
@@ -45,38 +47,3 @@ pub fn transcribe_audio(model_path: &Path, audio_path: &Path) -> Result<String>
 
     Ok(full_text.trim().to_string())
 }
-
-/// Load audio file and convert to the format expected by Whisper
-/// Whisper expects 16kHz mono f32 samples
-fn load_audio(path: &Path) -> Result<Vec<f32>> {
-    // For now, we'll use a simple approach assuming the input is already in the right format
-    // In production, you'd want to use a library like `symphonia` or `hound` to decode various formats
-    
-    // This is a placeholder - you'll need to implement proper audio loading
-    // based on your audio format (WAV, MP3, etc.)
-    
-    // For WAV files specifically:
-    use std::fs::File;
-    use std::io::Read;
-    
-    let mut file = File::open(path).context("Failed to open audio file")?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer).context("Failed to read audio file")?;
-    
-    // Simple WAV parsing (assuming 16-bit PCM, 16kHz, mono)
-    // Skip the WAV header (44 bytes typically)
-    if buffer.len() < 44 {
-        anyhow::bail!("Audio file too small to be a valid WAV");
-    }
-    
-    let audio_data = &buffer[44..];
-    let mut samples = Vec::with_capacity(audio_data.len() / 2);
-    
-    // Convert 16-bit PCM to f32
-    for chunk in audio_data.chunks_exact(2) {
-        let sample = i16::from_le_bytes([chunk[0], chunk[1]]);
-        samples.push(sample as f32 / 32768.0);
-    }
-    
-    Ok(samples)
-}
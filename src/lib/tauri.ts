@@ -4,12 +4,227 @@ export interface OrchestrateResult {
   markdown: string;
   mermaid: string;
   model: DomainModel;
+  /** Automatic corrections applied to the generated model */
+  fixups: string[];
+  /** Pipeline warnings (e.g. prompt context trimmed to fit the token budget) */
+  warnings: string[];
+  /** Provider and sampling parameters used, to reproduce the model */
+  generation?: GenerationRecord;
+  /** User hooks run after model generation and emission (failures also in warnings) */
+  hooks: HookRun[];
+  /** Stamped on markdown and mermaid; pass it with model.json to publishArtifacts */
+  provenance?: Provenance;
+  /** Candidate names pre-extracted from the transcript, compared with the model */
+  extraction?: CandidateReport;
+}
+
+/** Name proposed by the deterministic extraction pass */
+export interface Candidate {
+  name: string;
+  mentions: number;
+  /** Entity an attribute was said to belong to */
+  entity?: string;
+}
+
+export interface CandidateReport {
+  candidates: { entities: Candidate[]; attributes: Candidate[] };
+  /** Candidate entities found in the model */
+  confirmed: string[];
+  /** Candidate entities mentioned several times but missing from the model */
+  missed: string[];
+  /** Model entities and attributes ("Entity.attribute") matching no candidate */
+  unexpected: string[];
+}
+
+/** Where a generated artifact comes from */
+export interface Provenance {
+  app_version: string;
+  provider: string;
+  model?: string;
+  /** SHA-256 of the system prompt (instructions, glossary, few-shot examples) */
+  prompt_hash: string;
+  /** SHA-256 of the transcript */
+  transcript_hash: string;
+  generated_at: string;
+}
+
+export interface ProvenanceCheck {
+  name: string;
+  provenance: Provenance | null;
+  valid: boolean;
+  issues: string[];
+}
+
+/** Sampling parameters (unset values use LLM_TEMPERATURE / LLM_TOP_P / LLM_SEED) */
+export interface LlmRequestOptions {
+  temperature?: number;
+  top_p?: number;
+  seed?: number;
+}
+
+export interface GenerationRecord extends LlmRequestOptions {
+  provider: string;
+  model?: string;
+  generated_at: string;
+}
+
+export interface PromptVariantResult {
+  /** Generated model (null when generation failed) */
+  model: DomainModel | null;
+  valid: boolean;
+  errors: string[];
+  duration_ms: number;
+  generation: GenerationRecord;
+}
+
+export interface ModelElementDiff {
+  added: string[];
+  removed: string[];
+  modified: Record<string, unknown>[];
+}
+
+export interface ModelDiff {
+  identical: boolean;
+  entities: ModelElementDiff;
+  relations: ModelElementDiff;
+  invariants: ModelElementDiff;
+}
+
+export interface PromptComparison {
+  a: PromptVariantResult;
+  b: PromptVariantResult;
+  /** Diff from model A to model B (null when either generation failed) */
+  diff: ModelDiff | null;
+}
+
+/** RFC 6902 JSON Patch operation */
+export type PatchOperation =
+  | { op: "add"; path: string; value: unknown }
+  | { op: "remove"; path: string }
+  | { op: "replace"; path: string; value: unknown }
+  | { op: "move"; from: string; path: string }
+  | { op: "copy"; from: string; path: string }
+  | { op: "test"; path: string; value: unknown };
+
+export interface RefineEntityResult {
+  /** Patch to apply with applyModelPatch */
+  patch: PatchOperation[];
+  changes: string[];
+}
+
+export interface FixSuggestion {
+  id: string;
+  /** Validation error the fix solves */
+  error: string;
+  description: string;
+  /** Patch to apply with applyModelPatch */
+  patch: PatchOperation[];
+}
+
+export interface FixSuggestions {
+  fixes: FixSuggestion[];
+  /** Errors no fix could be proposed for */
+  unfixed: string[];
+}
+
+export interface MarkdownImportResult {
+  model: DomainModel;
+  warnings: string[];
+  /** Headings of the sections that were not tables and went to the LLM */
+  prose_sections: string[];
+  /** Ids of the elements the LLM added from prose */
+  llm_added: string[];
+}
+
+export interface TranscriptImport {
+  project_name: string;
+  /** Cleaned transcript, one "Speaker: text" line per turn */
+  transcript: string;
+  /** Stored copy of the transcript */
+  path: string;
+}
+
+/** Transcript a whispeer://import link asks to load: inline text or an http(s) URL */
+export interface ImportRequest {
+  source: { kind: "text"; value: string } | { kind: "url"; value: string };
+  project_name: string | null;
+}
+
+export interface TraceabilityRow {
+  invariant: string;
+  name: string;
+  /** Interview answers the invariant comes from ("<section> — Q<n>") */
+  sources: string[];
+  /** Gherkin scenarios ("<file>:<line> <title>") */
+  tests: string[];
+  /** Code lines naming the invariant ("<file>:<line>") */
+  code: string[];
+  status: "traced" | "partial" | "untraced";
+}
+
+export interface TraceabilityMatrix {
+  rows: TraceabilityRow[];
+  summary: {
+    invariants: number;
+    with_sources: number;
+    with_tests: number;
+    with_code: number;
+    traced: number;
+    code_audited: boolean;
+  };
+  markdown: string;
+  csv: string;
+}
+
+export interface ModelVersion {
+  version: number;
+  timestamp: string;
+  /** What produced the version ("save", "orchestrate", "refine", "patch"...) */
+  source: string;
+  /** Patch from the previous version (absent for the first one) */
+  patch?: PatchOperation[];
+  /** Semantic version, e.g. "1.2.0" (absent in histories written before it existed) */
+  semver?: string;
+  /** Bump from the previous version: breaking changes are "major", additions and loosened constraints "minor" */
+  bump?: "none" | "patch" | "minor" | "major";
+  /** Changes that made the bump major */
+  breaking_changes?: string[];
+}
+
+export interface ModelChangelog {
+  from: number;
+  to: number;
+  /** One line per structural change, derived from the diff */
+  changes: string[];
+  /** LLM write-up of the changes, absent if the LLM was unavailable */
+  summary?: string;
+  /** Section appended to the exported canvas */
+  markdown: string;
+}
+
+export interface ApplyPatchResult {
+  model: DomainModel;
+  version: ModelVersion;
 }
 
 export interface DomainModel {
   entities: Entity[];
   relations: Relation[];
   invariants: Invariant[];
+  layout?: LayoutHints;
+}
+
+export interface LayoutHints {
+  groups?: LayoutGroup[];
+  direction?: "TB" | "TD" | "BT" | "LR" | "RL";
+  ranks?: Record<string, number>;
+  hiddenRelations?: string[];
+}
+
+export interface LayoutGroup {
+  id: string;
+  label?: string;
+  entities: string[];
 }
 
 export interface Entity {
@@ -18,6 +233,7 @@ export interface Entity {
   description?: string;
   attributes: Attribute[];
   primaryKey?: string[];
+  aggregateRoot?: boolean;
   uniqueConstraints?: UniqueConstraint[];
 }
 
@@ -88,6 +304,386 @@ export interface TranscriptionResult {
   text: string;
   language: string | null;
   duration_ms: number;
+  segments: TranscriptSegment[];
+  /** Mean token probability (0-1), when known */
+  confidence?: number;
+  /** "openai" or "deepgram" when transcribed remotely, absent for local Whisper */
+  provider?: string;
+}
+
+/** Timed segment of a transcription (ms from the start of the audio file) */
+export interface TranscriptSegment {
+  start_ms: number;
+  end_ms: number;
+  text: string;
+  /** Spoken language (detected per utterance with "auto") */
+  language?: string;
+  /** Only with word timestamps enabled */
+  words?: WordTimestamp[];
+}
+
+export interface WordTimestamp {
+  word: string;
+  start_ms: number;
+  end_ms: number;
+  probability: number;
+}
+
+export interface SessionUtterance {
+  id: number;
+  file_name: string;
+  duration_ms: number;
+  text: string;
+  /** Spoken language of the utterance */
+  language?: string;
+  /** Timed segments, used for subtitle export */
+  segments?: TranscriptSegment[];
+  /** Captured stream; absent in sessions recorded before dual-source capture */
+  source?: AudioSource;
+}
+
+/** Microphone, or system audio (loopback) recorded next to it during remote meetings */
+export type AudioSource = "mic" | "system";
+
+/** Recording session stored with its utterances for later replays */
+export interface SessionRecord {
+  id: string;
+  created_at: string;
+  language: string;
+  model: string;
+  enhanced: boolean;
+  utterances: SessionUtterance[];
+  // Transcription postponed until the machine is plugged in or idle
+  deferred?: boolean;
+}
+
+export interface ReplayOptions {
+  language?: string;
+  model_path?: string;
+  enhance?: boolean;
+  generate_model?: boolean;
+  llm?: LlmRequestOptions;
+  /** Transcribe the raw tracks (before gain and AGC) when the session kept them */
+  raw?: boolean;
+}
+
+export interface SessionReplay {
+  id: string;
+  session_id: string;
+  created_at: string;
+  language: string;
+  model: string;
+  enhanced: boolean;
+  utterances: SessionUtterance[];
+  transcript: string;
+  original_transcript: string;
+  domain_model?: OrchestrateResult;
+}
+
+/** Run of words equal in both transcripts or changed between them */
+export interface DiffChunk {
+  kind: "equal" | "insert" | "delete" | "replace";
+  a_start: number;
+  b_start: number;
+  a_words: string[];
+  b_words: string[];
+}
+
+export interface TranscriptComparison {
+  chunks: DiffChunk[];
+  a_word_count: number;
+  b_word_count: number;
+  similarity: number;
+  word_error_rate: number;
+}
+
+/** Acoustic check of the room and microphone */
+/** Downsampled waveform of a recording or of one of its segments */
+export interface Waveform {
+  sample_rate: number;
+  start_ms: number;
+  end_ms: number;
+  /** [min, max] pairs in [-1, 1], one per equal slice of the excerpt */
+  peaks: [number, number][];
+}
+
+export interface EnvironmentReport {
+  device: string | null;
+  sample_rate: number;
+  duration_ms: number;
+  noise_floor_dbfs: number;
+  speech_level_dbfs: number;
+  snr_db: number;
+  peak_dbfs: number;
+  clipping_ratio: number;
+  reverberation_ms: number | null;
+  speech_detected: boolean;
+  recommendations: string[];
+}
+
+/** Whisper model held in memory, shared by all transcriptions using it */
+export interface LoadedModel {
+  model_path: string;
+  file_size_bytes: number;
+  use_gpu: boolean;
+  load_ms: number;
+  reuse_count: number;
+  users: number;
+}
+
+/** Model file found next to the current one, with its estimated memory once loaded */
+export interface ModelMemoryEstimate {
+  name: string;
+  path: string;
+  file_size_bytes: number;
+  estimated_bytes: number;
+  fits: boolean | null;
+}
+
+export interface MemoryReport {
+  total_memory_bytes: number | null;
+  available_memory_bytes: number | null;
+  process_rss_bytes: number | null;
+  loaded_models: LoadedModel[];
+  models: ModelMemoryEstimate[];
+}
+
+/** Battery trade-offs for long recordings */
+export type PowerProfile = "balanced" | "low_power";
+
+/** VAD sensitivity, from the most permissive to the strictest */
+export type VadMode = "quality" | "low_bitrate" | "aggressive" | "very_aggressive";
+
+/** Utterance segmentation of the recordings */
+export interface VadSettings {
+  /** Silence ending an utterance, 100 to 10000 (default 1000) */
+  silence_duration_ms: number;
+  /** Shorter utterances are dropped, at most 10000 (default 300) */
+  min_utterance_duration_ms: number;
+  /** Default "aggressive" */
+  vad_mode: VadMode;
+  /** Seconds without voice before "silence-alert", 0 to disable, at most 3600 (default 120) */
+  silence_alert_secs: number;
+  /** Continuous speech is cut after this many seconds so live transcription keeps up, 0 for no limit, at most 600 (default 30) */
+  max_utterance_secs: number;
+}
+
+/** "push_to_talk": one take transcribed on stop; "vad": automatic segmentation, each utterance transcribed as it ends */
+export type RecordingMode = "push_to_talk" | "vad";
+
+/** Facilitation coach preferences (disabled by default) */
+export interface CoachSettings {
+  enabled: boolean;
+  /** Minimum time between two analyses (default 90) */
+  interval_secs: number;
+}
+
+/** Section being facilitated, checked by the coach against the recent transcript */
+export interface CoachSection {
+  section_id: number;
+  section_title: string;
+  /** Questions or topics the section should cover */
+  goals: string[];
+}
+
+/** Payload of the "coach-suggestion" event */
+export interface CoachSuggestion {
+  section_id: number;
+  message: string;
+}
+
+export type HookPoint = "post_transcription" | "post_model" | "post_emit";
+
+/**
+ * User script run at a pipeline point. It receives the payload as JSON on stdin
+ * (with artifact paths in `artifacts`) and the hook point in DOMAIN_MODEL_HOOK.
+ */
+export interface Hook {
+  point: HookPoint;
+  /** Program looked up in PATH, e.g. "python3" */
+  command: string;
+  args?: string[];
+  /** Defaults to 30 */
+  timeout_secs?: number;
+  /** Defaults to true */
+  enabled?: boolean;
+}
+
+/** Outcome of a hook (also emitted as "hook-result" after a transcription) */
+export interface HookRun {
+  point: HookPoint;
+  command: string;
+  success: boolean;
+  exit_code: number | null;
+  timed_out: boolean;
+  duration_ms: number;
+  stdout: string;
+  stderr: string;
+}
+
+/** User preferences persisted in the app data directory */
+export interface AppSettings {
+  transcription_language: string;
+  /** Whisper outputs English whatever the spoken language */
+  translate_to_english: boolean;
+  power_profile: PowerProfile;
+  hooks: Hook[];
+  vad: VadSettings;
+  recording_mode: RecordingMode;
+  coach: CoachSettings;
+  /** Global shortcut toggling the recording (desktop), null when disabled */
+  recording_hotkey: string | null;
+  /** Keep the raw capture next to each processed utterance */
+  archive_raw_audio: boolean;
+  /** Keep utterances in memory instead of WAV files (sessions not replayable) */
+  in_memory_audio: boolean;
+  /** Limits on the audio kept by finished sessions */
+  audio_retention: AudioRetention;
+  utterance_naming: UtteranceNaming;
+  /** Whisper model file chosen with setWhisperModel (null = bundled model) */
+  whisper_model: string | null;
+  whisper_backend: WhisperBackend;
+  /** Remote transcription without local model or on low confidence */
+  cloud_stt: CloudSttSettings;
+  /** Gain, AGC and enhancement per input device name ("default" = system default device) */
+  audio_profiles: Record<string, AudioProfile>;
+  /** System audio recorded next to the microphone */
+  system_capture: SystemCapture;
+  /** Jira or Linear project the use-case tickets are pushed to */
+  ticket_tracker: TicketTrackerSettings;
+}
+
+/** Ticket tracker; the API token comes from TRACKER_API_TOKEN (and TRACKER_EMAIL for Jira) */
+export interface TicketTrackerSettings {
+  /** null = preview only */
+  provider: "jira" | "linear" | null;
+  /** Jira site, e.g. "https://acme.atlassian.net"; Linear's API when null */
+  base_url: string | null;
+  /** Jira project key, or Linear team id */
+  project: string | null;
+  /** Jira issue type, "Story" by default */
+  issue_type: string;
+  /** Jira labels added to every ticket */
+  labels: string[];
+}
+
+/** Loopback device recorded next to the microphone, tagging utterances by source */
+export interface SystemCapture {
+  /** Input device replaying the system output (BlackHole, "Monitor of ...", "Stereo Mix"); null = microphone only */
+  device: string | null;
+  /** Remove the speakers' echo from the microphone */
+  echo_cancellation: boolean;
+}
+
+/** Levels and enhancement tuned for one microphone */
+export interface AudioProfile {
+  /** Gain multiplier (0.1 to 10, 1.0 = unchanged) */
+  gain: number;
+  enable_agc: boolean;
+  /** AGC target level (0.05 to 1) */
+  agc_target_level: number;
+  /** ffmpeg enhancement applied before transcription */
+  enhancement: {
+    /** Noise reduction strength (0 to 1) */
+    noise_reduction: number;
+    enable_highpass: boolean;
+    normalize: boolean;
+  };
+}
+
+/** Remote speech-to-text fallback (API key from the STT_API_KEY environment variable) */
+export interface CloudSttSettings {
+  /** null = local Whisper only */
+  provider: "openai" | "deepgram" | null;
+  /** Remote model, e.g. "whisper-1" or "nova-2" (provider default if null) */
+  model: string | null;
+  /** Mean token probability (0-1) under which a local transcription is redone remotely */
+  min_confidence: number;
+}
+
+/** Compute backend of Whisper; GPU backends must be compiled in */
+export type WhisperBackend = "auto" | "cpu" | "metal" | "cuda" | "vulkan";
+
+/** Backend the transcriptions run on */
+export interface BackendReport {
+  preference: WhisperBackend;
+  active: WhisperBackend;
+  /** GPU backends of this build */
+  compiled: WhisperBackend[];
+  model: string;
+  /** e.g. "q5_0" for quantized models, null for f16 */
+  quantization: string | null;
+  /** Whether the model is already in memory */
+  loaded: boolean;
+  /** Whisper states run in parallel (1 on GPU) */
+  concurrency: number;
+  scheduler: SchedulerMetrics;
+}
+
+/** GPU scheduler counters since startup */
+export interface SchedulerMetrics {
+  /** Whisper runs */
+  batches: number;
+  utterances: number;
+  /** Utterances that shared a run with others */
+  merged_utterances: number;
+  audio_ms: number;
+  busy_ms: number;
+}
+
+/** File names of the utterances and their JSON sidecars */
+export interface UtteranceNaming {
+  /** Name without extension: {index} (required), {session}, {timestamp}, {speaker} */
+  template: string;
+  speaker: string | null;
+  /** Write `<name>.json` (duration, device, gain, VAD mode, transcript) next to each WAV */
+  sidecars: boolean;
+}
+
+/** Input level emitted with "audio-level" every 100ms while capturing (before gain and AGC) */
+export interface AudioLevel {
+  rms_dbfs: number;
+  peak_dbfs: number;
+  /** A sample reached ±0.99 during the period */
+  clipping: boolean;
+}
+
+/** Emitted as "disk-space-warning" (recording started, little room left) or "disk-space-error" (recording or download refused) */
+export interface DiskSpaceStatus {
+  path: string;
+  available_bytes: number;
+  required_bytes: number;
+  /** Recording time the free space allows (recordings only) */
+  recording_minutes_left: number | null;
+}
+
+/** Space used on disk, by kind */
+export interface StorageUsage {
+  /** Recording sessions (utterances, raw tracks, sidecars, replays) */
+  audio_bytes: number;
+  models_bytes: number;
+  artifacts_bytes: number;
+  /** Part of the above the next cleanup would free (stale temporary files, orphaned sessions) */
+  reclaimable_bytes: number;
+  total_bytes: number;
+}
+
+/** Emitted as "device-lost" when the input stream dies mid-recording, then "device-reconnected" once capture resumes on the default device */
+export type DeviceEvent =
+  | { kind: "lost"; device: string; reason: string }
+  | { kind: "reconnected"; device: string };
+
+/** Emitted as "silence-alert" when no voice was detected for `silence_alert_secs` while recording, then "silence-ended" when voice comes back */
+export type SilenceEvent =
+  | { kind: "alert"; silent_ms: number }
+  | { kind: "ended"; silent_ms: number };
+
+/** Segment emitted with "transcription-partial" while Whisper is still running */
+export interface PartialSegment {
+  index: number;
+  start_ms: number;
+  end_ms: number;
+  text: string;
 }
 
 export interface AudioDevice {
@@ -95,6 +691,22 @@ export interface AudioDevice {
   is_default: boolean;
 }
 
+export interface PipelineReport {
+  host: string;
+  /** Device whose configuration was negotiated (null = simulated 48kHz input) */
+  device: string | null;
+  sample_rate: number;
+  channels: number;
+  tone_hz: number;
+  detected_hz: number;
+  expected_duration_ms: number;
+  measured_duration_ms: number;
+  /** Voice detection of the recordings ("energy" when webrtc-vad is unusable) */
+  vad_engine: "web_rtc" | "energy";
+  passed: boolean;
+  issues: string[];
+}
+
 // Interview types
 export interface InterviewUserAnswer {
   section_id: number;
@@ -113,126 +725,1359 @@ export interface SectionCanvasResult {
   section_id: number;
   section_title: string;
   canvas_content: string;
+  generation?: GenerationRecord;
+}
+
+/** Sections already generated, so the next one stays consistent with them */
+export interface InterviewContext {
+  /** In interview order; the oldest are cut first when over the token budget */
+  previous_sections: { section_title: string; content: string }[];
+}
+
+export type RiskLevel = "low" | "medium" | "high";
+
+export interface RegisterEntry {
+  /** "H<n>" for assumptions, "R<n>" for risks */
+  id: string;
+  kind: "assumption" | "risk";
+  statement: string;
+  impact: RiskLevel;
+  likelihood: RiskLevel;
+  /** Interview section/question the entry comes from */
+  source?: string;
+  mitigation?: string;
+}
+
+export interface RiskRegister {
+  entries: RegisterEntry[];
+}
+
+export interface KpiRecord {
+  name: string;
+  description?: string;
+  /** Formula over the model, attributes written Entity.attribute */
+  formula: string;
+  /** Entity the data comes from */
+  entity: string;
+  attributes: string[];
+  target?: string;
+}
+
+export interface KpiCatalog {
+  kpis: KpiRecord[];
+  /** References to entities/attributes missing from the model */
+  issues: { kpi: string; message: string }[];
 }
 
 export interface FullCanvasResult {
   markdown: string;
+  /** Inconsistencies between sections, to show next to the sections they point at */
+  findings: ConsistencyFinding[];
+}
+
+/** Contradiction between sections of the canvas */
+export interface ConsistencyFinding {
+  kind: "missing_actor" | "naming_mismatch" | "contradiction";
+  /** Sections to revisit */
+  section_ids: number[];
+  message: string;
+  /** Built-in rule, or LLM review of the whole canvas */
+  origin: "rule" | "llm";
+}
+
+export interface ModelTemplate {
+  name: string;
+  title: string;
+  description: string;
+}
+
+export interface FewShotExample {
+  id: string;
+  title: string;
+  transcript: string;
+  model: DomainModel;
+  builtin: boolean;
+}
+
+export interface GlossaryTerm {
+  term: string;
+  definition?: string;
+  synonyms?: string[];
+}
+
+export interface UbiquitousLanguageSuggestion {
+  canonical: string;
+  synonyms: string[];
+  locations: string[];
+}
+
+export interface UbiquitousLanguageReport {
+  ok: boolean;
+  warnings: string[];
+  suggestions: UbiquitousLanguageSuggestion[];
 }
 
 /**
  * Orchestrate the entire flow: transcript -> domain model -> markdown + mermaid
  * @param transcript - The input transcript to process
+ * @param baseModel - Optional model to refine (e.g. a starter template)
+ * @param options - Optional sampling parameters (temperature, top_p, seed)
+ * @param glossary - Optional project glossary (one term per line) to steer the vocabulary
  * @returns The orchestrated result with markdown, mermaid, and domain model
  */
-export async function orchestrate(transcript: string): Promise<OrchestrateResult> {
-  return invoke<OrchestrateResult>("orchestrate", { transcript });
+export async function orchestrate(
+  transcript: string,
+  baseModel?: DomainModel,
+  options?: LlmRequestOptions,
+  glossary?: string
+): Promise<OrchestrateResult> {
+  return invoke<OrchestrateResult>("orchestrate", { transcript, baseModel, options, glossary });
 }
 
 /**
- * Start recording audio with voice activity detection (backend)
- * @returns Status message indicating where files will be saved
+ * Get the default normalization system prompt
+ * @returns The system prompt used by orchestrate (without few-shot examples)
  */
-export async function startRecording(): Promise<string> {
-  return invoke<string>("start_recording");
+export async function getDefaultSystemPrompt(): Promise<string> {
+  return invoke<string>("get_default_system_prompt");
 }
 
 /**
- * Stop recording audio
- * @returns Status message
+ * Run a transcript through two system prompts and compare the results side by side
+ * @param transcript - The transcript to model
+ * @param promptA - First system prompt variant
+ * @param promptB - Second system prompt variant
+ * @param options - Sampling parameters shared by both variants (set a seed to compare prompts only)
+ * @returns Both generated models with their validation, and the diff from A to B
  */
-export async function stopRecording(): Promise<string> {
-  return invoke<string>("stop_recording");
+export async function comparePrompts(
+  transcript: string,
+  promptA: string,
+  promptB: string,
+  options?: LlmRequestOptions
+): Promise<PromptComparison> {
+  return invoke<PromptComparison>("compare_prompts", { transcript, promptA, promptB, options });
 }
 
 /**
- * Transcribe an audio file using Whisper (NOT YET IMPLEMENTED)
- * @param audioPath - Path to the audio file (WAV format)
- * @returns Error message - use Web Speech API instead
+ * Refine one entity from an instruction; only the entity and its context are sent to the LLM
+ * @param model - Current domain model
+ * @param entityId - Entity to refine
+ * @param instruction - What to change (e.g. "split address into a value object")
+ * @param previousInstructions - Earlier instructions of the refinement conversation
+ * @returns A validated JSON Patch (apply it with applyModelPatch) and the list of changes
  */
-export async function transcribeAudio(audioPath: string): Promise<string> {
-  return invoke<string>("transcribe_audio", { audioPath });
+export async function refineEntity(
+  model: DomainModel,
+  entityId: string,
+  instruction: string,
+  previousInstructions?: string[]
+): Promise<RefineEntityResult> {
+  return invoke<RefineEntityResult>("refine_entity", {
+    model,
+    entityId,
+    instruction,
+    previousInstructions,
+  });
 }
 
 /**
- * List available audio input devices
- * @returns Array of audio devices with their names and default status
+ * Propose fixes for a model that failed validation (missing primary key, duplicate attribute, mistyped entityId)
+ * @param model - Domain model that failed validation
+ * @param errors - Validation errors to fix; every detected problem when omitted
+ * @returns Independent fixes (apply the accepted ones with applyModelPatch) and the errors left unfixed
  */
-export async function listAudioDevices(): Promise<AudioDevice[]> {
-  return invoke<AudioDevice[]>("list_audio_devices");
+export async function suggestFixes(model: DomainModel, errors?: string[]): Promise<FixSuggestions> {
+  return invoke<FixSuggestions>("suggest_fixes", { model, errors });
 }
 
 /**
- * Set the audio input device to use for recording
- * @param deviceName - Name of the audio device to use
- * @returns Success message
+ * Save a project's model as a new version of its history
+ * @param projectName - Project name
+ * @param model - Domain model to store
+ * @param source - What produced the model (defaults to "save")
+ * @returns The new version (unchanged models do not create one)
  */
-export async function setAudioDevice(deviceName: string): Promise<string> {
-  return invoke<string>("set_audio_device", { deviceName });
+export async function saveModel(
+  projectName: string,
+  model: DomainModel,
+  source?: string
+): Promise<ModelVersion> {
+  return invoke<ModelVersion>("save_model", { projectName, model, source });
 }
 
 /**
- * Process interview section answers through LLM to generate canvas content
- * @param section - Interview section with user answers
- * @returns Canvas content for this section
+ * Load a project's current model
+ * @param projectName - Project name
+ * @returns The model, or null if none was saved
  */
-export async function processInterviewSection(
-  section: InterviewSection
-): Promise<SectionCanvasResult> {
-  return invoke<SectionCanvasResult>("process_interview_section", { section });
+export async function loadModel(projectName: string): Promise<DomainModel | null> {
+  return invoke<DomainModel | null>("load_model", { projectName });
 }
 
 /**
- * Generate the complete canvas markdown from all processed sections
- * @param sections - Array of processed section results
- * @returns Complete canvas markdown document
+ * List the versions of a project's model, with the patch of each change
+ * @param projectName - Project name
  */
-export async function generateFullCanvas(
-  sections: SectionCanvasResult[]
-): Promise<FullCanvasResult> {
-  return invoke<FullCanvasResult>("generate_full_canvas", { sections });
+export async function getModelHistory(projectName: string): Promise<ModelVersion[]> {
+  return invoke<ModelVersion[]>("get_model_history", { projectName });
 }
 
 /**
- * Save interview state to a markdown file named after the project
- * @param projectName - Name of the project
- * @param stateJson - JSON string of the interview state
- * @returns Success message with file path
+ * Get the latest version of a project's model, with its semantic version
+ * @param projectName - Project name
+ * @returns null if no model was saved yet
  */
-export async function saveInterviewState(
-  projectName: string,
-  stateJson: string
-): Promise<string> {
-  return invoke<string>("save_interview_state", { projectName, stateJson });
+export async function getModelVersion(projectName: string): Promise<ModelVersion | null> {
+  return invoke<ModelVersion | null>("get_model_version", { projectName });
 }
 
 /**
- * Load interview state from a JSON file
- * @param projectName - Name of the project
- * @returns JSON string of the saved state
+ * Load a past version of a project's model
+ * @param projectName - Project name
+ * @param version - Version number from the history
  */
-export async function loadInterviewState(
-  projectName: string
-): Promise<string> {
-  return invoke<string>("load_interview_state", { projectName });
+export async function loadModelVersion(projectName: string, version: number): Promise<DomainModel> {
+  return invoke<DomainModel>("load_model_version", { projectName, version });
 }
 
 /**
- * List all saved project names
- * @returns Array of project names
+ * Generate a human-readable changelog between two versions of a project's model,
+ * and append it to the project's exported canvas
+ * @param projectName - Project name
+ * @param from - Older version number
+ * @param to - Newer version number
  */
-export async function listSavedProjects(): Promise<string[]> {
-  return invoke<string[]>("list_saved_projects");
+export async function generateChangelog(projectName: string, from: number, to: number): Promise<ModelChangelog> {
+  return invoke<ModelChangelog>("generate_changelog", { projectName, from, to });
+}
+
+/** Ticket draft of a use case or a roadmap step, editable before pushTickets */
+export interface TicketDraft {
+  kind: "use_case" | "action_item";
+  title: string;
+  description: string;
+  /** Given-When-Then scenarios of the canvas matching the use case */
+  acceptance_criteria: string[];
+}
+
+export interface PushedTicket {
+  title: string;
+  /** e.g. "SHOP-12", null when the ticket failed */
+  key: string | null;
+  url: string | null;
+  error: string | null;
 }
 
 /**
- * Save canvas markdown to a file
- * @param projectName - Name of the project
- * @param markdown - Canvas markdown content
- * @returns Success message with file path
+ * Dry run of pushTickets: ticket drafts of the Top 5 use cases (Given-When-Then tests as
+ * acceptance criteria) and of the roadmap steps
+ * @param projectName - Project name
+ * @param markdown - Canvas markdown, defaults to the one last saved for the project
  */
-export async function saveCanvasMarkdown(
+export async function previewTickets(projectName: string, markdown?: string): Promise<TicketDraft[]> {
+  return invoke<TicketDraft[]>("preview_tickets", { projectName, markdown });
+}
+
+/**
+ * Create the tickets in the tracker chosen with setTicketTracker. A failed ticket is
+ * reported in its result and does not stop the others.
+ * @param projectName - Project name
+ * @param drafts - Drafts from previewTickets (possibly edited), defaults to those of the canvas
+ * @param markdown - Canvas markdown when drafts are not given
+ */
+export async function pushTickets(projectName: string, drafts?: TicketDraft[], markdown?: string): Promise<PushedTicket[]> {
+  return invoke<PushedTicket[]>("push_tickets", { projectName, drafts, markdown });
+}
+
+/** Stakeholder email drafted by draftSummaryEmail */
+export interface SummaryEmail {
+  subject: string;
+  /** Plain text: key decisions, model highlights, open questions, next steps */
+  body: string;
+  /** ISO 639-1 code the email is written in */
+  language: string;
+  /** mailto: link with the subject and body filled in, no recipient */
+  mailto: string;
+}
+
+/**
+ * Draft a concise email summing up the project for stakeholders, from its canvas, model,
+ * interview answers and risk register, in the transcription language (guessed when "auto")
+ * @param projectName - Project name
+ */
+export async function draftSummaryEmail(projectName: string): Promise<SummaryEmail> {
+  return invoke<SummaryEmail>("draft_summary_email", { projectName });
+}
+
+/**
+ * Apply an RFC 6902 JSON Patch to a project's current model
+ * @param projectName - Project name
+ * @param patch - Operations, applied atomically
+ * @param source - What produced the patch (defaults to "patch")
+ * @returns The validated model and its new version
+ */
+export async function applyModelPatch(
   projectName: string,
-  markdown: string
-): Promise<string> {
-  return invoke<string>("save_canvas_markdown", { projectName, markdown });
+  patch: PatchOperation[],
+  source?: string
+): Promise<ApplyPatchResult> {
+  return invoke<ApplyPatchResult>("apply_model_patch", { projectName, patch, source });
+}
+
+/** Generated artifact to store (markdown, mermaid...) */
+export interface ArtifactInput {
+  name: string;
+  kind: string;
+  content: string;
+  /** Stamped on the content before storing ("markdown", "mermaid" and "model" kinds) */
+  provenance?: Provenance;
+}
+
+export interface ArtifactEntry {
+  /** SHA-256 of the content, hex encoded */
+  hash: string;
+  size: number;
+  kind: string;
+  updated_at: string;
+}
+
+export interface ArtifactManifest {
+  artifacts: Record<string, ArtifactEntry>;
+}
+
+export interface IntegrityIssue {
+  name: string;
+  message: string;
+}
+
+/**
+ * Names of the artifacts that differ from the stored ones, to skip re-publishing unchanged output
+ * @param projectName - Project name
+ * @param artifacts - Artifacts about to be published
+ */
+export async function checkArtifactsChanged(projectName: string, artifacts: ArtifactInput[]): Promise<string[]> {
+  return invoke<string[]>("check_artifacts_changed", { projectName, artifacts });
+}
+
+/**
+ * Store artifacts under their content hash (identical contents are written once)
+ * @param projectName - Project name
+ * @param artifacts - Artifacts to store
+ * @returns Names of the artifacts that changed
+ */
+export async function publishArtifacts(projectName: string, artifacts: ArtifactInput[]): Promise<string[]> {
+  return invoke<string[]>("publish_artifacts", { projectName, artifacts });
+}
+
+/**
+ * Artifact names of a project with their hash, size and kind
+ * @param projectName - Project name
+ */
+export async function getArtifactManifest(projectName: string): Promise<ArtifactManifest> {
+  return invoke<ArtifactManifest>("get_artifact_manifest", { projectName });
+}
+
+/**
+ * Load an artifact, rejected if its content no longer matches its hash
+ * @param projectName - Project name
+ * @param name - Artifact name (e.g. "canvas.md", "model.json")
+ * @returns The content, or null if the artifact does not exist
+ */
+export async function loadArtifact(projectName: string, name: string): Promise<string | null> {
+  return invoke<string | null>("load_artifact", { projectName, name });
+}
+
+/**
+ * Check every artifact of a project against its stored content
+ * @param projectName - Project name
+ * @returns Corrupted or missing artifacts (empty when all is well)
+ */
+export async function verifyArtifacts(projectName: string): Promise<IntegrityIssue[]> {
+  return invoke<IntegrityIssue[]>("verify_artifacts", { projectName });
+}
+
+/**
+ * Check an artifact against its provenance: content hash, transcript and prompt still stored unchanged
+ * @param projectName - Project name
+ * @param name - Artifact name (e.g. "domain.md")
+ */
+export async function verifyArtifact(projectName: string, name: string): Promise<ProvenanceCheck> {
+  return invoke<ProvenanceCheck>("verify_artifact", { projectName, name });
+}
+
+/**
+ * Rebuild a domain model from existing markdown documentation (emit_markdown output or similar)
+ * @param markdown - Document to import
+ * @param options - useLlm (default true) for prose sections; projectName to store the result as a model version
+ * @returns The imported model, parsing warnings and what came from the LLM
+ */
+export async function importMarkdown(
+  markdown: string,
+  options: { useLlm?: boolean; projectName?: string } = {}
+): Promise<MarkdownImportResult> {
+  return invoke<MarkdownImportResult>("import_markdown", { markdown, ...options });
+}
+
+/**
+ * Start a new project from a transcript copied from another tool (Otter, Teams .vtt, plain text)
+ * @param text - Transcript to import; read from the clipboard when omitted
+ * @param projectName - Name of the new project (defaults to "Import <date>")
+ * @returns The project name and the cleaned transcript
+ */
+export async function pasteTranscript(text?: string, projectName?: string): Promise<TranscriptImport> {
+  const content = text ?? (await navigator.clipboard.readText());
+  return invoke<TranscriptImport>("paste_transcript", { text: content, projectName });
+}
+
+/**
+ * Take the whispeer://import link waiting for the user's confirmation, if any
+ * (the link that launched the app arrives before the "transcript-import-requested" event is listened to)
+ * @returns The pending request, or null
+ */
+export async function takePendingTranscriptImport(): Promise<ImportRequest | null> {
+  return invoke<ImportRequest | null>("take_pending_transcript_import");
+}
+
+/**
+ * Load the transcript of an import link the user confirmed into a new project
+ * @param request - Request taken with takePendingTranscriptImport
+ * @returns The project name and the cleaned transcript
+ */
+export async function confirmTranscriptImport(request: ImportRequest): Promise<TranscriptImport> {
+  return invoke<TranscriptImport>("confirm_transcript_import", { request });
+}
+
+/**
+ * Build the traceability matrix of a project's invariants for governance reviews
+ * @param projectName - Project whose saved interview provides the sources
+ * @param options - Model (defaults to the project's current one), Gherkin and code directories
+ * @returns Rows, coverage summary and the matrix as markdown and CSV
+ */
+export async function generateTraceabilityMatrix(
+  projectName: string,
+  options: { model?: DomainModel; featuresDir?: string; codeDir?: string } = {}
+): Promise<TraceabilityMatrix> {
+  return invoke<TraceabilityMatrix>("generate_traceability_matrix", { projectName, ...options });
+}
+
+/**
+ * List the starter domain model templates shipped with the app
+ * @returns Template names, titles and descriptions
+ */
+export async function listModelTemplates(): Promise<ModelTemplate[]> {
+  return invoke<ModelTemplate[]>("list_model_templates");
+}
+
+/**
+ * Load a starter domain model, to pass as baseModel to orchestrate
+ * @param name - Template name (e.g. "ecommerce", "booking", "subscription_billing")
+ * @returns The template domain model
+ */
+export async function loadModelTemplate(name: string): Promise<DomainModel> {
+  return invoke<DomainModel>("load_model_template", { name });
+}
+
+/**
+ * List the few-shot examples (built-in and user) used to guide the LLM
+ * @returns All few-shot examples
+ */
+export async function listFewShotExamples(): Promise<FewShotExample[]> {
+  return invoke<FewShotExample[]>("list_few_shot_examples");
+}
+
+/**
+ * Save a transcript and its expected model as a few-shot example
+ * @param title - Short label for the example
+ * @param transcript - Source transcript
+ * @param model - Expected domain model for this transcript
+ * @returns The stored example
+ */
+export async function addFewShotExample(
+  title: string,
+  transcript: string,
+  model: DomainModel
+): Promise<FewShotExample> {
+  return invoke<FewShotExample>("add_few_shot_example", { title, transcript, model });
+}
+
+/**
+ * Remove a user few-shot example (built-in examples cannot be removed)
+ * @param id - Example id
+ */
+export async function removeFewShotExample(id: string): Promise<void> {
+  return invoke<void>("remove_few_shot_example", { id });
+}
+
+/**
+ * Start recording audio with voice activity detection (backend)
+ * @returns Status message indicating where files will be saved
+ */
+export async function startRecording(): Promise<string> {
+  return invoke<string>("start_recording");
+}
+
+/**
+ * Stop recording audio
+ * @returns Status message
+ */
+export async function stopRecording(): Promise<string> {
+  return invoke<string>("stop_recording");
+}
+
+/**
+ * Abort the transcriptions in progress (e.g. a long recording processed after stop).
+ * The current utterance emits "transcription-cancelled"; the remaining ones are saved without text.
+ */
+export async function cancelTranscription(): Promise<void> {
+  return invoke<void>("cancel_transcription");
+}
+
+/**
+ * Hear what is being captured, after gain and AGC, on the default output (use headphones).
+ * Applies to the current recording and the next ones; off at each launch.
+ * @param enabled - Whether to play back the capture
+ */
+export async function setAudioMonitor(enabled: boolean): Promise<void> {
+  return invoke<void>("set_audio_monitor", { enabled });
+}
+
+/**
+ * Pause the current recording; resuming continues the same session
+ * @returns Status message
+ */
+export async function pauseRecording(): Promise<string> {
+  return invoke<string>("pause_recording");
+}
+
+/**
+ * Resume a paused recording
+ * @returns Status message
+ */
+export async function resumeRecording(): Promise<string> {
+  return invoke<string>("resume_recording");
+}
+
+/**
+ * Transcribe an audio file using Whisper, with the model already loaded for the recordings
+ * (cancelled by cancelTranscription like them)
+ * @param audioPath - Path to the audio file: WAV, MP3, M4A/AAC, FLAC or OGG (other codecs need ffmpeg)
+ * @param language - Spoken language (ISO 639-1 code or "auto"), defaults to the one in the settings
+ * @param wordTimestamps - Also return per-word start/end times in the segments
+ * @param translate - Translate to English, defaults to the setting (see setTranslateToEnglish)
+ * @returns Transcribed text with timed segments
+ */
+export async function transcribeAudio(
+  audioPath: string,
+  language?: string,
+  wordTimestamps?: boolean,
+  translate?: boolean
+): Promise<TranscriptionResult> {
+  return invoke<TranscriptionResult>("transcribe_audio", { audioPath, language, wordTimestamps, translate });
+}
+
+/** Emitted as "batch-transcription-progress" before and after each file of transcribeFolder */
+export interface BatchProgress {
+  /** 1-based position of the file in the batch */
+  index: number;
+  total: number;
+  file_name: string;
+  status: "started" | "done" | "failed";
+  error?: string;
+}
+
+export interface BatchFileResult {
+  file_name: string;
+  /** Markdown transcript of the file, null when it failed */
+  markdown_path: string | null;
+  text: string;
+  error: string | null;
+  duration_ms: number;
+}
+
+export interface BatchResult {
+  files: BatchFileResult[];
+  /** transcriptions.md, every file in name order */
+  aggregate_path: string;
+  /** Stopped by cancelTranscription: the remaining files were not transcribed */
+  cancelled: boolean;
+}
+
+/**
+ * Transcribe every audio file of a folder (sub-folders excluded), one markdown per file plus
+ * an aggregated transcriptions.md. A failed file is reported and the batch goes on.
+ * @param dir - Folder of the recordings
+ * @param outputDir - Where the markdown files go, defaults to <dir>/transcriptions
+ * @param language - Spoken language (ISO 639-1 code or "auto"), defaults to the one in the settings
+ */
+export async function transcribeFolder(dir: string, outputDir?: string, language?: string): Promise<BatchResult> {
+  return invoke<BatchResult>("transcribe_folder", { dir, outputDir, language });
+}
+
+/**
+ * List stored recording sessions, most recent first
+ */
+export async function listRecordingSessions(): Promise<SessionRecord[]> {
+  return invoke<SessionRecord[]>("list_recording_sessions");
+}
+
+/**
+ * Re-process the stored utterances of a session with the current settings (or overrides).
+ * The original transcript is kept; the replay is stored next to it.
+ * @param sessionId - Id sent with "recording-session-saved"
+ * @param options - Language, Whisper model, enhancement and whether to regenerate the domain model
+ */
+export async function replaySession(sessionId: string, options?: ReplayOptions): Promise<SessionReplay> {
+  return invoke<SessionReplay>("replay_session", { sessionId, options });
+}
+
+/** Transcribed utterance placed on the recording timeline */
+export interface TimelineEntry {
+  utterance_id: number;
+  /** From the start of the recording */
+  start_ms: number;
+  end_ms: number;
+  text: string;
+  language?: string;
+  source?: AudioSource;
+}
+
+/** Transcript of a whole session in recording order */
+export interface SessionTranscript {
+  session_id: string;
+  entries: TimelineEntry[];
+  /** Entries joined as one transcript, with source labels and language tags */
+  text: string;
+  /** False while the recording or its transcription is still running */
+  complete: boolean;
+}
+
+/**
+ * Get the transcript of a session in recording order, each utterance timed from the
+ * start of the recording (rather than assembling "transcription-result" events)
+ * @param sessionId - Stored session; omitted for the recording in progress
+ * @returns Ordered entries and the joined transcript
+ */
+export async function getSessionTranscript(sessionId?: string): Promise<SessionTranscript> {
+  return invoke<SessionTranscript>("get_session_transcript", { sessionId });
+}
+
+/**
+ * Write the transcript of a stored session as timed subtitles (or timestamped markdown)
+ * @param sessionId - Id sent with "recording-session-saved"
+ * @param format - "srt", "vtt" (WebVTT) or "markdown"
+ * @returns Path of the written file, in the session directory
+ */
+export async function exportTranscript(sessionId: string, format: "srt" | "vtt" | "markdown"): Promise<string> {
+  return invoke<string>("export_transcript", { sessionId, format });
+}
+
+/**
+ * Align two transcripts of the same audio word by word (e.g. small vs large Whisper model)
+ * @param a - Reference transcript
+ * @param b - Transcript to compare
+ * @returns Equal/insert/delete/replace chunks, similarity and word error rate of b against a
+ */
+export async function compareTranscripts(a: string, b: string): Promise<TranscriptComparison> {
+  return invoke<TranscriptComparison>("compare_transcripts", { a, b });
+}
+
+/**
+ * Get the persisted user settings
+ */
+export async function getAppSettings(): Promise<AppSettings> {
+  return invoke<AppSettings>("get_app_settings");
+}
+
+/**
+ * Set and persist the spoken language used by Whisper for recordings and file transcriptions
+ * @param language - ISO 639-1 code ("fr", "en", "de"...), or "auto" to detect it for each utterance
+ * @returns Updated settings
+ */
+export async function setTranscriptionLanguage(language: string): Promise<AppSettings> {
+  return invoke<AppSettings>("set_transcription_language", { language });
+}
+
+/**
+ * Have Whisper translate recordings and file transcriptions directly into English (persisted)
+ * @param enabled - true to output English whatever the spoken language
+ * @returns Updated settings
+ */
+export async function setTranslateToEnglish(enabled: boolean): Promise<AppSettings> {
+  return invoke<AppSettings>("set_translate_to_english", { enabled });
+}
+
+/**
+ * Choose the remote provider used when no Whisper model is installed or when Whisper is unsure (persisted)
+ * @param cloudStt - Provider (null to disable), model and confidence threshold
+ * @returns Updated settings; fails when a provider is chosen without STT_API_KEY
+ */
+export async function setCloudStt(cloudStt: CloudSttSettings): Promise<AppSettings> {
+  return invoke<AppSettings>("set_cloud_stt", { cloudStt });
+}
+
+/**
+ * Set the power profile of the next recordings (persisted in the settings).
+ * "low_power" uses larger audio buffers, fewer VAD decisions and batched disk writes,
+ * and on battery defers transcription ("transcription-deferred" event) until the
+ * machine is on AC power or the recorder has been idle for 15 minutes.
+ * @param profile - "balanced" or "low_power"
+ * @returns The updated settings
+ */
+export async function setPowerProfile(profile: PowerProfile): Promise<AppSettings> {
+  return invoke<AppSettings>("set_power_profile", { profile });
+}
+
+/**
+ * Get the silence detection settings of the recordings
+ */
+export async function getVadSettings(): Promise<VadSettings> {
+  return invoke<VadSettings>("get_vad_settings");
+}
+
+/**
+ * Set the silence detection settings (persisted); they apply from the next recording
+ * @param vad - Silence and minimal utterance durations, VAD mode
+ * @returns The updated settings
+ */
+export async function setVadSettings(vad: VadSettings): Promise<AppSettings> {
+  return invoke<AppSettings>("set_vad_settings", { vad });
+}
+
+/**
+ * Set the global shortcut starting/stopping the recording while another app is focused
+ * (persisted, desktop only). Fails if the shortcut is invalid or taken by another app.
+ * @param hotkey - e.g. "CommandOrControl+Shift+R", or null to disable it
+ * @returns The updated settings
+ */
+export async function setRecordingHotkey(hotkey: string | null): Promise<AppSettings> {
+  return invoke<AppSettings>("set_recording_hotkey", { hotkey });
+}
+
+/**
+ * Keep the raw capture (before gain and AGC) of the next recordings next to each
+ * processed utterance, to replay questionable transcriptions with `raw: true`
+ * @param enabled - Whether to archive the raw tracks
+ * @returns The updated settings
+ */
+export async function setArchiveRawAudio(enabled: boolean): Promise<AppSettings> {
+  return invoke<AppSettings>("set_archive_raw_audio", { enabled });
+}
+
+/**
+ * Record the system audio of the next recordings next to the microphone, so remote
+ * participants are told apart in the transcript ("Micro:" / "Système:" lines)
+ * @param capture - Loopback device (null = microphone only) and echo cancellation
+ * @returns The updated settings
+ */
+export async function setSystemCapture(capture: SystemCapture): Promise<AppSettings> {
+  return invoke<AppSettings>("set_system_capture", { capture });
+}
+
+/**
+ * Choose the Jira or Linear project tickets are pushed to (persisted)
+ * @param tracker - Provider (null for preview only), project and Jira options
+ * @returns Updated settings; fails when a provider is chosen without a project or TRACKER_API_TOKEN
+ */
+export async function setTicketTracker(tracker: TicketTrackerSettings): Promise<AppSettings> {
+  return invoke<AppSettings>("set_ticket_tracker", { tracker });
+}
+
+/**
+ * Keep the utterances of the next recordings in memory and transcribe them from there,
+ * so that no audio is left on disk (the sessions can then not be replayed)
+ * @param enabled - Whether to skip the WAV files
+ * @returns The updated settings
+ */
+export async function setInMemoryAudio(enabled: boolean): Promise<AppSettings> {
+  return invoke<AppSettings>("set_in_memory_audio", { enabled });
+}
+
+/**
+ * Set how the utterances of the next recordings are named, e.g.
+ * "{session}_{speaker}_{index}", and whether each one gets a JSON sidecar
+ * @param naming - Template, speaker and sidecar switch
+ * @returns The updated settings
+ */
+export async function setUtteranceNaming(naming: UtteranceNaming): Promise<AppSettings> {
+  return invoke<AppSettings>("set_utterance_naming", { naming });
+}
+
+/**
+ * Set the recording mode of the next recordings (persisted in the settings).
+ * In "vad" mode, utterances are cut on silences and each one is transcribed as soon
+ * as it ends ("transcription-result" during the recording), which suits long meetings.
+ * @param mode - "push_to_talk" or "vad"
+ * @returns The updated settings
+ */
+export async function setRecordingMode(mode: RecordingMode): Promise<AppSettings> {
+  return invoke<AppSettings>("set_recording_mode", { mode });
+}
+
+/**
+ * Enable or disable the facilitation coach (persisted). When enabled, the recent
+ * transcript is compared with the goals of the current section at most every
+ * `interval_secs`, and prompts are emitted as "coach-suggestion" events.
+ * @param coachSettings - Whether the coach runs, and its minimum interval
+ * @returns The updated settings
+ */
+export async function setCoachSettings(coachSettings: CoachSettings): Promise<AppSettings> {
+  return invoke<AppSettings>("set_coach_settings", { coachSettings });
+}
+
+/**
+ * Tell the coach which section the interview is on
+ * @param section - Current section and its goals, or null when leaving the interview
+ */
+export async function setCoachSection(section: CoachSection | null): Promise<void> {
+  return invoke<void>("set_coach_section", { section });
+}
+
+/**
+ * Replace the user hooks run after transcription, model generation and emission
+ * @param hooks - Hooks, run in order for each point
+ * @returns The updated settings
+ */
+export async function setHooks(hooks: Hook[]): Promise<AppSettings> {
+  return invoke<AppSettings>("set_hooks", { hooks });
+}
+
+/**
+ * Run a hook once with a sample payload ({ "test": true }) to check the script
+ * @param hook - Hook to try
+ */
+export async function testHook(hook: Hook): Promise<HookRun> {
+  return invoke<HookRun>("test_hook", { hook });
+}
+
+/**
+ * List available audio input devices
+ * @returns Array of audio devices with their names and default status
+ */
+export async function listAudioDevices(): Promise<AudioDevice[]> {
+  return invoke<AudioDevice[]>("list_audio_devices");
+}
+
+/**
+ * Set the audio input device to use for recording; its saved audio profile is loaded
+ * @param deviceName - Name of the audio device to use
+ * @returns Success message
+ */
+export async function setAudioDevice(deviceName: string): Promise<string> {
+  return invoke<string>("set_audio_device", { deviceName });
+}
+
+/**
+ * Get the audio profile of a device (defaults if it was never tuned)
+ * @param deviceName - Device name (defaults to the selected one)
+ */
+export async function getAudioProfile(deviceName?: string): Promise<AudioProfile> {
+  return invoke<AudioProfile>("get_audio_profile", { deviceName });
+}
+
+/**
+ * Save the audio profile of a device, reloaded whenever it is selected
+ * @param profile - Gain, AGC and enhancement settings
+ * @param deviceName - Device name (defaults to the selected one)
+ * @returns The updated settings
+ */
+export async function saveAudioProfile(profile: AudioProfile, deviceName?: string): Promise<AppSettings> {
+  return invoke<AppSettings>("save_audio_profile", { profile, deviceName });
+}
+
+/**
+ * Record a few seconds and check the room and microphone before an interview
+ * @param deviceName - Device to analyze (defaults to the selected one)
+ * @param durationMs - Recording length, 5000 by default (1000 to 30000)
+ * @returns Noise floor, SNR, clipping, reverberation and what to fix
+ */
+export async function analyzeEnvironment(deviceName?: string, durationMs?: number): Promise<EnvironmentReport> {
+  return invoke<EnvironmentReport>("analyze_environment", { deviceName, durationMs });
+}
+
+/**
+ * Min/max peaks of a recorded WAV, to draw its waveform next to a transcript segment
+ * @param audioPath - Recorded WAV file (utterance or session audio)
+ * @param points - Number of [min, max] pairs, 1000 by default
+ * @param startMs - Segment start (defaults to the beginning of the file)
+ * @param endMs - Segment end (defaults to the end of the file)
+ * @returns The peaks and the range they cover
+ */
+export async function getWaveform(
+  audioPath: string,
+  points?: number,
+  startMs?: number,
+  endMs?: number
+): Promise<Waveform> {
+  return invoke<Waveform>("get_waveform", { audioPath, points, startMs, endMs });
+}
+
+/**
+ * Memory usage and which Whisper models fit on this machine
+ * @returns System/process memory, loaded models and an estimate per model file
+ */
+export async function getMemoryDiagnostics(): Promise<MemoryReport> {
+  return invoke<MemoryReport>("get_memory_diagnostics");
+}
+
+/**
+ * Disk space used by the app
+ * @returns Bytes used by recordings, models and artifacts, and how much a cleanup would free
+ */
+export async function getStorageUsage(): Promise<StorageUsage> {
+  return invoke<StorageUsage>("get_storage_usage");
+}
+
+/** Over these limits, finished sessions lose their WAV files (oldest first); transcripts stay */
+export interface AudioRetention {
+  /** Sessions older than this lose their audio (null = no age limit) */
+  max_age_days: number | null;
+  /** Total audio size allowed, in MB (null = no size limit) */
+  max_mb: number | null;
+}
+
+/** Files removed by a cleanup */
+export interface GcReport {
+  removed: string[];
+  freed_bytes: number;
+}
+
+/**
+ * Set the retention policy of the recorded audio, enforced right away, after each
+ * session and with the periodic cleanup
+ * @param retention - Age and size limits
+ * @returns The updated settings
+ */
+export async function setAudioRetention(retention: AudioRetention): Promise<AppSettings> {
+  return invoke<AppSettings>("set_audio_retention", { retention });
+}
+
+/**
+ * Remove the audio of every finished session (the transcripts are kept, the sessions
+ * can no longer be replayed)
+ * @returns The removed files and the space freed
+ */
+export async function purgeAudioCache(): Promise<GcReport> {
+  return invoke<GcReport>("purge_audio_cache");
+}
+
+/** Whisper model published on Hugging Face and/or installed locally */
+export interface WhisperModelInfo {
+  /** File name, e.g. "ggml-small.bin" */
+  name: string;
+  size_bytes: number | null;
+  sha256: string | null;
+  installed: boolean;
+  path: string | null;
+}
+
+/** Emitted as "model-download-progress" while a model downloads */
+export interface DownloadProgress {
+  name: string;
+  downloaded_bytes: number;
+  total_bytes: number | null;
+}
+
+/** Restrictions on the LLM providers of the workspace (LLM_POLICY_FILE or llm-policy.json) */
+export interface ProviderPolicy {
+  /** LLM_PROVIDER values allowed, all when empty */
+  allowed_providers: string[];
+  /** Regex external endpoints must match */
+  allowed_endpoints: string | null;
+  /** Local Ollama only, no model downloads */
+  offline_only: boolean;
+}
+
+export interface PolicyReport {
+  /** Policy file in force, null when there is none */
+  path: string | null;
+  policy: ProviderPolicy;
+  /** Why the current LLM configuration is refused, null when it complies */
+  violation: string | null;
+}
+
+/**
+ * Provider policy of the workspace and whether the current LLM configuration complies
+ */
+export async function getLlmPolicy(): Promise<PolicyReport> {
+  return invoke<PolicyReport>("get_llm_policy");
+}
+
+/**
+ * Whisper models available for download, with the ones already installed
+ * @returns Catalog entries (installed ones have a path), then local-only files
+ */
+export async function listWhisperModels(): Promise<WhisperModelInfo[]> {
+  return invoke<WhisperModelInfo[]>("list_whisper_models");
+}
+
+/**
+ * Download a Whisper model into the app data directory and verify its checksum.
+ * Listen to "model-download-progress" for a progress bar.
+ * @param name - Model file name, e.g. "ggml-small.bin"
+ * @returns The installed model
+ */
+export async function downloadWhisperModel(name: string): Promise<WhisperModelInfo> {
+  return invoke<WhisperModelInfo>("download_whisper_model", { name });
+}
+
+/**
+ * Choose the Whisper model of the recordings and transcribeAudio (persisted). Loaded on
+ * the next transcription; ".en" models only transcribe English.
+ * @param model - Installed model name, e.g. "ggml-base.en.bin", or a model file path
+ * @returns The updated settings
+ */
+export async function setWhisperModel(model: string): Promise<AppSettings> {
+  return invoke<AppSettings>("set_whisper_model", { model });
+}
+
+/**
+ * Choose CPU or GPU inference for Whisper (persisted, applied on the next transcription)
+ * @param backend - "auto" (first GPU backend of the build, else CPU), "cpu", "metal", "cuda" or "vulkan"
+ * @returns The updated settings
+ */
+export async function setWhisperBackend(backend: WhisperBackend): Promise<AppSettings> {
+  return invoke<AppSettings>("set_whisper_backend", { backend });
+}
+
+/**
+ * Which backend Whisper runs on and the quantization of the current model
+ * @returns Preference, active backend, compiled GPU backends and model details
+ */
+export async function getWhisperBackend(): Promise<BackendReport> {
+  return invoke<BackendReport>("get_whisper_backend");
+}
+
+/**
+ * Play a WAV file as if it were the microphone, for demos and tests without hardware
+ * @param path - WAV file to play; omit to go back to the real device
+ * @param speed - 1 (default) for real time, > 1 to accelerate, 0 for as fast as possible
+ * @returns Success message
+ */
+export async function setVirtualMicrophone(path?: string, speed?: number): Promise<string> {
+  return invoke<string>("set_virtual_microphone", { path, speed });
+}
+
+/**
+ * Round-trip a generated 440 Hz tone through the recording pipeline with the device's negotiated format
+ * @param deviceName - Device to test (defaults to the system default input)
+ * @returns Negotiated format, detected pitch/duration and any issue found
+ */
+export async function testAudioPipeline(deviceName?: string): Promise<PipelineReport> {
+  return invoke<PipelineReport>("test_audio_pipeline", { deviceName });
+}
+
+/**
+ * Process interview section answers through LLM to generate canvas content
+ * @param section - Interview section with user answers
+ * @param context - Sections generated before this one (e.g. built from their results)
+ * @returns Canvas content for this section
+ */
+export async function processInterviewSection(
+  section: InterviewSection,
+  context?: InterviewContext
+): Promise<SectionCanvasResult> {
+  return invoke<SectionCanvasResult>("process_interview_section", { section, context });
+}
+
+/** Rubric rating of an interview answer */
+export interface AnswerScore {
+  /** 0 (off-topic) to 3 (every aspect of the question covered) */
+  completeness: number;
+  /** 0 (generic) to 3 (figures, edge cases, exceptions) */
+  specificity: number;
+  /** "too_short" answers are rated without an LLM call */
+  verdict: "ok" | "elaborate" | "too_short";
+  /** Aspects of the question the answer leaves out */
+  missing: string[];
+  /** Question to ask to complete the answer (only when elaborating is advised) */
+  follow_up: string | null;
+}
+
+/**
+ * Rate an answer before its section is processed, to nudge the user to elaborate
+ * @param question - Interview question
+ * @param answer - User's answer
+ * @returns Completeness and specificity scores with a verdict and a follow-up question
+ */
+export async function scoreAnswer(question: string, answer: string): Promise<AnswerScore> {
+  return invoke<AnswerScore>("score_answer", { question, answer });
+}
+
+/**
+ * Generate the complete canvas markdown from all processed sections
+ * @param sections - Array of processed section results
+ * @param riskRegister - Register appended as the "Registre des hypothèses & risques" section
+ * @returns Complete canvas markdown document and the inconsistencies between sections
+ */
+export async function generateFullCanvas(
+  sections: SectionCanvasResult[],
+  riskRegister?: RiskRegister
+): Promise<FullCanvasResult> {
+  return invoke<FullCanvasResult>("generate_full_canvas", { sections, riskRegister });
+}
+
+/**
+ * Extract assumptions and risks from the interview answers into the project's register
+ * @param projectName - Project owning the register
+ * @param sections - Interview sections with their answers
+ * @returns The register, new entries merged into the stored ones
+ */
+export async function extractRiskRegister(
+  projectName: string,
+  sections: InterviewSection[]
+): Promise<RiskRegister> {
+  return invoke<RiskRegister>("extract_risk_register", { projectName, sections });
+}
+
+/**
+ * Load a project's risk register (empty if none was extracted yet)
+ */
+export async function loadRiskRegister(projectName: string): Promise<RiskRegister> {
+  return invoke<RiskRegister>("load_risk_register", { projectName });
+}
+
+/**
+ * Save a risk register edited by the user
+ */
+export async function saveRiskRegister(projectName: string, register: RiskRegister): Promise<void> {
+  return invoke<void>("save_risk_register", { projectName, register });
+}
+
+/**
+ * Turn the canvas "Tests de domaine & KPI" section into the project's KPI catalog
+ * @param projectName - Project owning the catalog
+ * @param kpiSection - Markdown of the KPI section
+ * @param model - Domain model the KPI data must come from
+ * @returns KPI records and the references missing from the model
+ */
+export async function extractKpiCatalog(
+  projectName: string,
+  kpiSection: string,
+  model: DomainModel
+): Promise<KpiCatalog> {
+  return invoke<KpiCatalog>("extract_kpi_catalog", { projectName, kpiSection, model });
+}
+
+/**
+ * Load a project's KPI catalog (empty if none was extracted yet)
+ */
+export async function loadKpiCatalog(projectName: string): Promise<KpiCatalog> {
+  return invoke<KpiCatalog>("load_kpi_catalog", { projectName });
+}
+
+/**
+ * Save KPIs edited by the user; they are re-validated against the model
+ */
+export async function saveKpiCatalog(
+  projectName: string,
+  kpis: KpiRecord[],
+  model: DomainModel
+): Promise<KpiCatalog> {
+  return invoke<KpiCatalog>("save_kpi_catalog", { projectName, kpis, model });
+}
+
+/**
+ * Export the project's KPI catalog next to the canvas
+ * @param format - "markdown" or "csv"
+ * @returns Success message with the file path
+ */
+export async function exportKpiCatalog(projectName: string, format: "markdown" | "csv"): Promise<string> {
+  return invoke<string>("export_kpi_catalog", { projectName, format });
+}
+
+/**
+ * Export the canvas as a slide deck to present the workshop outcome
+ * @param markdown - Canvas markdown, one slide per section
+ * @param format - "marp" (markdown) or "reveal" (standalone HTML page)
+ * @param mermaid - Domain model diagram, shown on the last slide
+ * @returns Success message with the file path
+ */
+export async function exportSlides(
+  projectName: string,
+  markdown: string,
+  format: "marp" | "reveal",
+  mermaid?: string
+): Promise<string> {
+  return invoke<string>("export_slides", { projectName, markdown, mermaid: mermaid ?? null, format });
+}
+
+/**
+ * Export the ubiquitous language for documentation and translation teams
+ * @param format - "csv" or "tbx" (TermBase eXchange, read by translation tools)
+ * @param markdown - Canvas markdown, the last saved canvas when omitted
+ * @param glossary - Project glossary, its synonyms are added to the canvas terms
+ * @returns Success message with the file path
+ */
+export async function exportGlossary(
+  projectName: string,
+  format: "csv" | "tbx",
+  markdown?: string,
+  glossary?: GlossaryTerm[]
+): Promise<string> {
+  return invoke<string>("export_glossary", { projectName, format, markdown: markdown ?? null, glossary: glossary ?? null });
+}
+
+export interface ModelNarration {
+  /** "fr" or "en" */
+  language: string;
+  /** One sentence per entity, relation and invariant */
+  sentences: string[];
+  text: string;
+  /** Read aloud by the speech synthesizer of the system */
+  spoken: boolean;
+}
+
+/**
+ * Narrate the model in plain language to validate it with the domain expert
+ * @param model - DomainModel to narrate
+ * @param language - "fr" or "en", the transcription language when omitted
+ * @param speak - Also read the narration aloud (say, SAPI or espeak-ng)
+ */
+export async function narrateModel(model: DomainModel, language?: string, speak?: boolean): Promise<ModelNarration> {
+  return invoke<ModelNarration>("narrate_model", { model, language: language ?? null, speak: speak ?? null });
+}
+
+export type QuizTopic = "vocabulary" | "entity" | "relation" | "invariant" | "use_case";
+
+export interface QuizQuestion {
+  question: string;
+  /** Expected answer */
+  answer: string;
+  topic: QuizTopic;
+  /** Model element or canvas section the answer comes from */
+  source?: string;
+}
+
+export interface Quiz {
+  project: string;
+  language: string;
+  questions: QuizQuestion[];
+}
+
+/**
+ * Generate a short quiz checking that the team absorbed the domain after the workshop
+ * @param model - DomainModel, the last saved model when omitted
+ * @param markdown - Canvas markdown, the last saved canvas when omitted
+ * @param count - Number of questions (8 by default, 20 at most)
+ */
+export async function generateQuiz(
+  projectName: string,
+  model?: DomainModel,
+  markdown?: string,
+  count?: number
+): Promise<Quiz> {
+  return invoke<Quiz>("generate_quiz", { projectName, model: model ?? null, markdown: markdown ?? null, count: count ?? null });
+}
+
+/**
+ * Export a quiz as markdown, the answers folded under each question
+ * @returns Success message with the file path
+ */
+export async function exportQuiz(projectName: string, quiz: Quiz): Promise<string> {
+  return invoke<string>("export_quiz", { projectName, quiz });
+}
+
+/**
+ * Save interview state to a markdown file named after the project
+ * @param projectName - Name of the project
+ * @param stateJson - JSON string of the interview state
+ * @returns Success message with file path
+ */
+export async function saveInterviewState(
+  projectName: string,
+  stateJson: string
+): Promise<string> {
+  return invoke<string>("save_interview_state", { projectName, stateJson });
+}
+
+/**
+ * Load interview state from a JSON file
+ * @param projectName - Name of the project
+ * @returns JSON string of the saved state
+ */
+export async function loadInterviewState(
+  projectName: string
+): Promise<string> {
+  return invoke<string>("load_interview_state", { projectName });
+}
+
+/**
+ * List all saved project names
+ * @returns Array of project names
+ */
+export async function listSavedProjects(): Promise<string[]> {
+  return invoke<string[]>("list_saved_projects");
+}
+
+/** Time spent in one interview section */
+export interface SectionTime {
+  section_id: number;
+  section_title: string;
+  total_ms: number;
+  /** Times the section was entered */
+  visits: number;
+  /** Share of the total interview time (0 to 1) */
+  share: number;
+}
+
+/** Time per interview section, most time-consuming first */
+export interface InterviewAnalytics {
+  projects: string[];
+  total_ms: number;
+  sections: SectionTime[];
+}
+
+/**
+ * Record that the interview moved to another section (null when it is left or hidden)
+ * @param projectName - Name of the project
+ * @param sectionId - Section entered, or null to stop counting
+ * @param sectionTitle - Title of the section
+ */
+export async function recordSectionChange(
+  projectName: string,
+  sectionId: number | null,
+  sectionTitle: string | null
+): Promise<void> {
+  return invoke<void>("record_section_change", { projectName, sectionId, sectionTitle });
+}
+
+/**
+ * Get the time spent per interview section, a gap between two pings counting for at most 30 minutes
+ * @param projectName - One project, or omitted to aggregate all of them
+ */
+export async function getInterviewAnalytics(projectName?: string): Promise<InterviewAnalytics> {
+  return invoke<InterviewAnalytics>("get_interview_analytics", { projectName });
+}
+
+/**
+ * Save canvas markdown to a file
+ * @param projectName - Name of the project
+ * @param markdown - Canvas markdown content
+ * @returns Success message with file path
+ */
+export async function saveCanvasMarkdown(
+  projectName: string,
+  markdown: string
+): Promise<string> {
+  return invoke<string>("save_canvas_markdown", { projectName, markdown });
+}
+
+/**
+ * Lint model names against the glossary and the "Langage Ubiquiste" table
+ * @param model - Domain model to check
+ * @param ubiquitousLanguage - Markdown of the "Langage Ubiquiste" canvas section
+ * @param glossary - Optional project glossary terms
+ * @returns Lint warnings with canonical term suggestions
+ */
+export async function checkUbiquitousLanguage(
+  model: DomainModel,
+  ubiquitousLanguage?: string,
+  glossary?: GlossaryTerm[]
+): Promise<UbiquitousLanguageReport> {
+  return invoke<UbiquitousLanguageReport>("check_ubiquitous_language", {
+    model,
+    ubiquitousLanguage,
+    glossary,
+  });
 }